@@ -2,9 +2,9 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use dotenv::dotenv;
 use rust_decimal::Decimal;
-use secp256k1::Secp256k1;
 use serde::Serialize;
 use terra_rust_api::core_types::Coin;
+use terra_rust_api::shared_secp256k1;
 use terra_rust_api::terra_u64_format;
 use terra_rust_api::{GasOptions, Message, MsgExecuteContract, Terra};
 use terra_rust_cli::cli_helpers::Cli;
@@ -58,7 +58,7 @@ async fn run() -> Result<()> {
     let cli = Cli::<Swap>::parse();
     let gas_opts: GasOptions = cli.gas_opts().await?;
     let terra = Terra::lcd_client(&cli.lcd, &cli.chain_id, &gas_opts, None);
-    let secp = Secp256k1::new();
+    let secp = shared_secp256k1();
     let wallet = Wallet::create(&cli.wallet);
 
     let seed: Option<&str> = if cli.seed.is_empty() {
@@ -74,9 +74,9 @@ async fn run() -> Result<()> {
                 vec![]
             };
 
-            let from_key = wallet.get_private_key(&secp, &swap.sender, seed)?;
+            let from_key = wallet.get_private_key(shared_secp256k1(), &swap.sender, seed)?;
 
-            let from_public_key = from_key.public_key(&secp);
+            let from_public_key = from_key.public_key(secp);
 
             let store_message = MsgSwap::create(
                 &from_public_key.account()?,
@@ -93,7 +93,7 @@ async fn run() -> Result<()> {
             log::info!("Message:\n{}", json);
             let resp = terra
                 .submit_transaction_sync(
-                    &secp,
+                    secp,
                     &from_key,
                     messages,
                     Some(format!(
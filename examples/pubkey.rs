@@ -1,17 +1,17 @@
 use dotenv::dotenv;
-use secp256k1::Secp256k1;
+use terra_rust_api::shared_secp256k1;
 
 use terra_rust_cli::cli_helpers;
 
 async fn run() -> anyhow::Result<()> {
     let cli = cli_helpers::gen_cli("pubkey", "pubkey");
     let matches = cli.get_matches();
-    let secp = Secp256k1::new();
-    let private_key = cli_helpers::get_private_key(&secp, &matches)?;
+    let secp = shared_secp256k1();
+    let private_key = cli_helpers::get_private_key(secp, &matches)?;
 
     let msg = "mary had a little lamb. I ate, coz lamb is delicious";
-    // let signature = from_key.sign(&secp, &cli.message)?;
-    let signature = private_key.sign(&secp, &msg)?;
+    // let signature = from_key.sign(secp, &cli.message)?;
+    let signature = private_key.sign(secp, &msg)?;
 
     println!("PubKeySig={}", signature.pub_key.value);
 
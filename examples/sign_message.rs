@@ -1,9 +1,9 @@
 use clap::Parser;
 use dotenv::dotenv;
 use rust_decimal::Decimal;
-use secp256k1::Secp256k1;
 use serde::Serialize;
 use terra_rust_api::core_types::Coin;
+use terra_rust_api::shared_secp256k1;
 use terra_rust_api::terra_u64_format;
 use terra_rust_api::{Message, MsgExecuteContract};
 use terra_rust_wallet::Wallet;
@@ -44,7 +44,7 @@ struct Cli {
 async fn run() -> anyhow::Result<()> {
     let cli: Cli = Cli::parse();
 
-    let secp = Secp256k1::new();
+    let secp = shared_secp256k1();
     let wallet = Wallet::create(&cli.wallet);
 
     let seed: Option<&str> = if cli.seed.is_empty() {
@@ -54,9 +54,9 @@ async fn run() -> anyhow::Result<()> {
     };
     //let msg = r#"random/{"token_uri":"https://www.merriam-webster.com/dictionary/token4","image":null,"image_data":null,"external_url":null,"description":null,"name":null,"attributes":[{"display_type":null,"trait_type":"gender","value":"female"},{"display_type":null,"trait_type":"name","value":"James T. Kirk"}],"background_color":null,"animation_url":null,"youtube_url":null}"#;
     let msg = cli.message;
-    let from_key = wallet.get_private_key(&secp, &cli.from, seed)?;
-    // let signature = from_key.sign(&secp, &cli.message)?;
-    let signature = from_key.sign(&secp, &msg)?;
+    let from_key = wallet.get_private_key(shared_secp256k1(), &cli.from, seed)?;
+    // let signature = from_key.sign(secp, &cli.message)?;
+    let signature = from_key.sign(secp, &msg)?;
 
     println!("Message={}", &msg);
     println!("Signature={}", signature.signature);
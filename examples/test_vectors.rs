@@ -0,0 +1,33 @@
+use terra_rust_api::shared_secp256k1;
+use terra_rust_api::test_vectors::{standard_vectors, TestVector};
+
+/// prints the standard test vectors as JSON, or with a file argument, checks the vectors in it
+fn run() -> anyhow::Result<()> {
+    let secp = shared_secp256k1();
+    match std::env::args().nth(1) {
+        Some(file) => {
+            let vectors: Vec<TestVector> = serde_json::from_str(&std::fs::read_to_string(&file)?)?;
+            for vector in &vectors {
+                vector.verify(secp)?;
+            }
+            println!("{}: {} vectors ok", file, vectors.len());
+        }
+        None => println!(
+            "{}",
+            serde_json::to_string_pretty(&standard_vectors(secp)?)?
+        ),
+    }
+    Ok(())
+}
+
+fn main() {
+    env_logger::init();
+
+    if let Err(ref err) = run() {
+        log::error!("{}", err);
+        err.chain()
+            .skip(1)
+            .for_each(|cause| log::error!("because: {}", cause));
+        std::process::exit(1);
+    }
+}
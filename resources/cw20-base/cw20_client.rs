@@ -0,0 +1,204 @@
+// generated by `cargo terra gen-client` from resources/cw20-base. do not edit
+#![allow(dead_code, clippy::too_many_arguments)]
+use secp256k1::{Context, Secp256k1, Signing};
+use serde::{Deserialize, Serialize};
+use terra_rust_api::client::tx_types::TXResultSync;
+use terra_rust_api::core_types::Coin;
+use terra_rust_api::errors::TerraRustAPIError;
+use terra_rust_api::{LCDResult, Message, MsgExecuteContract, PrivateKey, Terra};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BalanceResponse {
+    pub balance: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TokenInfoResponse {
+    pub decimals: u8,
+    pub name: String,
+    pub symbol: String,
+    pub total_supply: String,
+}
+
+pub struct Cw20Client<'a> {
+    terra: &'a Terra,
+    pub contract: String,
+}
+
+impl<'a> Cw20Client<'a> {
+    pub fn create(terra: &'a Terra, contract: &str) -> Cw20Client<'a> {
+        Cw20Client {
+            terra,
+            contract: contract.into(),
+        }
+    }
+
+    /// Transfer is a base message to move tokens to another account without triggering actions
+    pub fn transfer_msg(&self, sender: &str, amount: String, recipient: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"transfer": {"amount": amount, "recipient": recipient}}), coins)
+    }
+    /// Transfer is a base message to move tokens to another account without triggering actions
+    pub async fn transfer<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, recipient: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.transfer_msg(&sender, amount, recipient, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Burn is a base message to destroy tokens forever
+    pub fn burn_msg(&self, sender: &str, amount: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"burn": {"amount": amount}}), coins)
+    }
+    /// Burn is a base message to destroy tokens forever
+    pub async fn burn<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.burn_msg(&sender, amount, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Send is a base message to transfer tokens to a contract and trigger an action on the receiving contract.
+    pub fn send_msg(&self, sender: &str, amount: String, contract: String, msg: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"send": {"amount": amount, "contract": contract, "msg": msg}}), coins)
+    }
+    /// Send is a base message to transfer tokens to a contract and trigger an action on the receiving contract.
+    pub async fn send<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, contract: String, msg: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.send_msg(&sender, amount, contract, msg, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Only with "approval" extension. Allows spender to access an additional amount tokens from the owner's (env.sender) account. If expires is Some(), overwrites current allowance expiration with this one.
+    pub fn increase_allowance_msg(&self, sender: &str, amount: String, expires: Option<serde_json::Value>, spender: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"increase_allowance": {"amount": amount, "expires": expires, "spender": spender}}), coins)
+    }
+    /// Only with "approval" extension. Allows spender to access an additional amount tokens from the owner's (env.sender) account. If expires is Some(), overwrites current allowance expiration with this one.
+    pub async fn increase_allowance<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, expires: Option<serde_json::Value>, spender: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.increase_allowance_msg(&sender, amount, expires, spender, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Only with "approval" extension. Lowers the spender's access of tokens from the owner's (env.sender) account by amount. If expires is Some(), overwrites current allowance expiration with this one.
+    pub fn decrease_allowance_msg(&self, sender: &str, amount: String, expires: Option<serde_json::Value>, spender: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"decrease_allowance": {"amount": amount, "expires": expires, "spender": spender}}), coins)
+    }
+    /// Only with "approval" extension. Lowers the spender's access of tokens from the owner's (env.sender) account by amount. If expires is Some(), overwrites current allowance expiration with this one.
+    pub async fn decrease_allowance<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, expires: Option<serde_json::Value>, spender: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.decrease_allowance_msg(&sender, amount, expires, spender, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Only with "approval" extension. Transfers amount tokens from owner -> recipient if `env.sender` has sufficient pre-approval.
+    pub fn transfer_from_msg(&self, sender: &str, amount: String, owner: String, recipient: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"transfer_from": {"amount": amount, "owner": owner, "recipient": recipient}}), coins)
+    }
+    /// Only with "approval" extension. Transfers amount tokens from owner -> recipient if `env.sender` has sufficient pre-approval.
+    pub async fn transfer_from<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, owner: String, recipient: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.transfer_from_msg(&sender, amount, owner, recipient, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Only with "approval" extension. Sends amount tokens from owner -> contract if `env.sender` has sufficient pre-approval.
+    pub fn send_from_msg(&self, sender: &str, amount: String, contract: String, msg: String, owner: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"send_from": {"amount": amount, "contract": contract, "msg": msg, "owner": owner}}), coins)
+    }
+    /// Only with "approval" extension. Sends amount tokens from owner -> contract if `env.sender` has sufficient pre-approval.
+    pub async fn send_from<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, contract: String, msg: String, owner: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.send_from_msg(&sender, amount, contract, msg, owner, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Only with "approval" extension. Destroys tokens forever
+    pub fn burn_from_msg(&self, sender: &str, amount: String, owner: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"burn_from": {"amount": amount, "owner": owner}}), coins)
+    }
+    /// Only with "approval" extension. Destroys tokens forever
+    pub async fn burn_from<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, owner: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.burn_from_msg(&sender, amount, owner, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Only with the "mintable" extension. If authorized, creates amount new tokens and adds to the recipient balance.
+    pub fn mint_msg(&self, sender: &str, amount: String, recipient: String, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"mint": {"amount": amount, "recipient": recipient}}), coins)
+    }
+    /// Only with the "mintable" extension. If authorized, creates amount new tokens and adds to the recipient balance.
+    pub async fn mint<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, amount: String, recipient: String, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.mint_msg(&sender, amount, recipient, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Only with the "marketing" extension. If authorized, updates marketing metadata. Setting None/null for any of these will leave it unchanged. Setting Some("") will clear this field on the contract storage
+    pub fn update_marketing_msg(&self, sender: &str, description: Option<String>, marketing: Option<String>, project: Option<String>, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"update_marketing": {"description": description, "marketing": marketing, "project": project}}), coins)
+    }
+    /// Only with the "marketing" extension. If authorized, updates marketing metadata. Setting None/null for any of these will leave it unchanged. Setting Some("") will clear this field on the contract storage
+    pub async fn update_marketing<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, description: Option<String>, marketing: Option<String>, project: Option<String>, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.update_marketing_msg(&sender, description, marketing, project, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// If set as the "marketing" role on the contract, upload a new URL, SVG, or PNG for the token
+    pub fn upload_logo_msg(&self, sender: &str, upload_logo: serde_json::Value, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(sender, &self.contract, &serde_json::json!({"upload_logo": upload_logo}), coins)
+    }
+    /// If set as the "marketing" role on the contract, upload a new URL, SVG, or PNG for the token
+    pub async fn upload_logo<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey, upload_logo: serde_json::Value, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let execute = self.upload_logo_msg(&sender, upload_logo, coins)?;
+        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await
+    }
+
+    /// Returns the current balance of the given address, 0 if unset. Return type: BalanceResponse.
+    pub async fn query_balance(&self, address: String, height: Option<u64>) -> Result<LCDResult<BalanceResponse>, TerraRustAPIError> {
+        let query = serde_json::json!({"balance": {"address": address}}).to_string();
+        self.terra.wasm().query(&self.contract, &query, height).await
+    }
+
+    /// Returns metadata on the contract - name, decimals, supply, etc. Return type: TokenInfoResponse.
+    pub async fn query_token_info(&self, height: Option<u64>) -> Result<LCDResult<TokenInfoResponse>, TerraRustAPIError> {
+        let query = serde_json::json!({"token_info": {}}).to_string();
+        self.terra.wasm().query(&self.contract, &query, height).await
+    }
+
+    /// Only with "mintable" extension. Returns who can mint and the hard cap on maximum tokens after minting. Return type: MinterResponse.
+    pub async fn query_minter(&self, height: Option<u64>) -> Result<LCDResult<serde_json::Value>, TerraRustAPIError> {
+        let query = serde_json::json!({"minter": {}}).to_string();
+        self.terra.wasm().query(&self.contract, &query, height).await
+    }
+
+    /// Only with "allowance" extension. Returns how much spender can use from owner account, 0 if unset. Return type: AllowanceResponse.
+    pub async fn query_allowance(&self, owner: String, spender: String, height: Option<u64>) -> Result<LCDResult<serde_json::Value>, TerraRustAPIError> {
+        let query = serde_json::json!({"allowance": {"owner": owner, "spender": spender}}).to_string();
+        self.terra.wasm().query(&self.contract, &query, height).await
+    }
+
+    /// Only with "enumerable" extension (and "allowances") Returns all allowances this owner has approved. Supports pagination. Return type: AllAllowancesResponse.
+    pub async fn query_all_allowances(&self, limit: Option<u32>, owner: String, start_after: Option<String>, height: Option<u64>) -> Result<LCDResult<serde_json::Value>, TerraRustAPIError> {
+        let query = serde_json::json!({"all_allowances": {"limit": limit, "owner": owner, "start_after": start_after}}).to_string();
+        self.terra.wasm().query(&self.contract, &query, height).await
+    }
+
+    /// Only with "enumerable" extension Returns all accounts that have balances. Supports pagination. Return type: AllAccountsResponse.
+    pub async fn query_all_accounts(&self, limit: Option<u32>, start_after: Option<String>, height: Option<u64>) -> Result<LCDResult<serde_json::Value>, TerraRustAPIError> {
+        let query = serde_json::json!({"all_accounts": {"limit": limit, "start_after": start_after}}).to_string();
+        self.terra.wasm().query(&self.contract, &query, height).await
+    }
+
+    /// Only with "marketing" extension Returns more metadata on the contract to display in the client: - description, logo, project url, etc. Return type: MarketingInfoResponse
+    pub async fn query_marketing_info(&self, height: Option<u64>) -> Result<LCDResult<serde_json::Value>, TerraRustAPIError> {
+        let query = serde_json::json!({"marketing_info": {}}).to_string();
+        self.terra.wasm().query(&self.contract, &query, height).await
+    }
+
+    /// Only with "marketing" extension Downloads the mbeded logo data (if stored on chain). Errors if no logo data ftored for this contract. Return type: DownloadLogoResponse.
+    pub async fn query_download_logo(&self, height: Option<u64>) -> Result<LCDResult<serde_json::Value>, TerraRustAPIError> {
+        let query = serde_json::json!({"download_logo": {}}).to_string();
+        self.terra.wasm().query(&self.contract, &query, height).await
+    }
+}
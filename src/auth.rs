@@ -1,9 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
-use terra_rust_api::Terra;
+use terra_rust_api::{shared_secp256k1, Terra};
 
-use secp256k1::Secp256k1;
 use terra_rust_wallet::Wallet;
 #[derive(Parser)]
 /// Auth operations
@@ -18,6 +17,20 @@ pub enum AuthEnum {
         #[clap(name = "address", help = "the address to query")]
         address: String,
     },
+    /// the account as it was at a block, as JSON
+    #[clap(name = "account-at")]
+    AccountAt {
+        #[clap(name = "address", help = "the terra address")]
+        address: String,
+        #[clap(name = "height", help = "block height")]
+        height: u64,
+    },
+    /// the account's first transaction, as JSON
+    #[clap(name = "first-seen")]
+    FirstSeen {
+        #[clap(name = "address", help = "the terra address")]
+        address: String,
+    },
     /// list delegations of account
     #[clap(name = "delegations")]
     Delegations {
@@ -25,6 +38,14 @@ pub enum AuthEnum {
         // the address to get more info on.
         address: String,
     },
+    /// delegations of account at each of the given heights, as JSON
+    #[clap(name = "delegation-history")]
+    DelegationHistory {
+        #[clap(name = "address", help = "the terra address")]
+        address: String,
+        #[clap(name = "heights", required = true, help = "block heights")]
+        heights: Vec<u64>,
+    },
     /// list unbonding delegations of account
     #[clap(name = "unbonding")]
     Unbonding {
@@ -32,6 +53,12 @@ pub enum AuthEnum {
         // the address to get more info on.
         address: String,
     },
+    /// when the account's unbondings unlock, and how much is claimable now, as JSON
+    #[clap(name = "unbonding-schedule")]
+    UnbondingSchedule {
+        #[clap(name = "address", help = "the terra address")]
+        address: String,
+    },
     /// list validators of account
     #[clap(name = "validators")]
     Validators {
@@ -45,8 +72,7 @@ impl AuthCommand {
         match self.command {
             AuthEnum::Account { address } => {
                 let account_id = if !address.starts_with("terra1") {
-                    let secp = Secp256k1::new();
-                    wallet.get_account(&secp, &address, seed)?
+                    wallet.get_account(shared_secp256k1(), &address, seed)?
                 } else {
                     address
                 };
@@ -54,10 +80,29 @@ impl AuthCommand {
 
                 println!("{}", serde_json::to_string_pretty(&sw)?);
             }
+            AuthEnum::AccountAt { address, height } => {
+                let account_id = if !address.starts_with("terra1") {
+                    wallet.get_account(shared_secp256k1(), &address, seed)?
+                } else {
+                    address
+                };
+                let account = terra.auth().account_at(&account_id, height).await?;
+                println!("{}", serde_json::to_string_pretty(&account)?);
+            }
+            AuthEnum::FirstSeen { address } => {
+                let account_id = if !address.starts_with("terra1") {
+                    wallet.get_account(shared_secp256k1(), &address, seed)?
+                } else {
+                    address
+                };
+                match terra.auth().first_seen(&account_id).await? {
+                    Some(seen) => println!("{}", serde_json::to_string_pretty(&seen)?),
+                    None => println!("{} has no transactions", account_id),
+                }
+            }
             AuthEnum::Delegations { address } => {
                 let account_id = if !address.starts_with("terra1") {
-                    let secp = Secp256k1::new();
-                    wallet.get_account(&secp, &address, seed)?
+                    wallet.get_account(shared_secp256k1(), &address, seed)?
                 } else {
                     address
                 };
@@ -67,10 +112,21 @@ impl AuthCommand {
                     .await?;
                 println!("{:#?}", v.result);
             }
+            AuthEnum::DelegationHistory { address, heights } => {
+                let account_id = if !address.starts_with("terra1") {
+                    wallet.get_account(shared_secp256k1(), &address, seed)?
+                } else {
+                    address
+                };
+                let history = terra
+                    .auth()
+                    .delegation_history(&account_id, &heights)
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&history)?);
+            }
             AuthEnum::Unbonding { address } => {
                 let account_id = if !address.starts_with("terra1") {
-                    let secp = Secp256k1::new();
-                    wallet.get_account(&secp, &address, seed)?
+                    wallet.get_account(shared_secp256k1(), &address, seed)?
                 } else {
                     address
                 };
@@ -80,10 +136,18 @@ impl AuthCommand {
                     .await?;
                 println!("{:#?}", v.result);
             }
+            AuthEnum::UnbondingSchedule { address } => {
+                let account_id = if !address.starts_with("terra1") {
+                    wallet.get_account(shared_secp256k1(), &address, seed)?
+                } else {
+                    address
+                };
+                let schedule = terra.staking().unbonding_schedule(&account_id).await?;
+                println!("{}", serde_json::to_string_pretty(&schedule)?);
+            }
             AuthEnum::Validators { address } => {
                 let account_id = if !address.starts_with("terra1") {
-                    let secp = Secp256k1::new();
-                    wallet.get_account(&secp, &address, seed)?
+                    wallet.get_account(shared_secp256k1(), &address, seed)?
                 } else {
                     address
                 };
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use secp256k1::Secp256k1;
 use terra_rust_api::messages::{Message, MsgSend};
+use terra_rust_api::shared_secp256k1;
 use terra_rust_api::Terra;
 
 use crate::{NAME, VERSION};
@@ -20,7 +20,7 @@ pub enum BankEnum {
     Send {
         /// from account (specify the key name in the wallet
         from: String,
-        /// the to account in 'terra' format
+        /// the to account in 'terra' format, or @name of a key or watch-only entry in the wallet
         to: String,
         /// the amount
         amount: Decimal,
@@ -29,6 +29,9 @@ pub enum BankEnum {
     },
     Balance {
         account: String,
+        /// show amounts in display units, like UST 12.34, instead of the LCD's JSON
+        #[clap(long)]
+        human: bool,
     },
 }
 impl BankCommand {
@@ -40,17 +43,18 @@ impl BankCommand {
                 amount,
                 denom,
             } => {
-                let secp = Secp256k1::new();
-                let from_key = wallet.get_private_key(&secp, &from, seed)?;
-                let from_public_key = from_key.public_key(&secp);
+                let secp = shared_secp256k1();
+                let from_key = wallet.get_private_key(secp, &from, seed)?;
+                let from_public_key = from_key.public_key(secp);
                 let coin: Coin = Coin::create(&denom, amount);
                 let from_account = from_public_key.account()?;
-                let send = MsgSend::create(from_account, to.clone(), vec![coin])?;
+                let to = wallet.resolve_recipient(secp, &to, seed)?;
+                let send = MsgSend::create_single(from_account, to, coin)?;
 
                 let messages: Vec<Message> = vec![send];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &from_key,
                         messages,
                         Some(format!(
@@ -64,15 +68,21 @@ impl BankCommand {
                 println!("{}", resp.txhash);
                 log::info!("{}", resp.raw_log);
             }
-            BankEnum::Balance { account } => {
+            BankEnum::Balance { account, human } => {
                 let account_id = if !account.starts_with("terra1") {
-                    let secp = Secp256k1::new();
-                    wallet.get_account(&secp, &account, seed)?
+                    wallet.get_account(shared_secp256k1(), &account, seed)?
                 } else {
                     account.clone()
                 };
                 let sw = terra.bank().balances(&account_id, None).await?;
-                println!("{}", serde_json::to_string_pretty(&sw)?);
+                if human {
+                    let formatter = terra.bank().denom_registry().await.formatter();
+                    for coin in &sw.result {
+                        println!("{}", formatter.format(coin));
+                    }
+                } else {
+                    println!("{}", serde_json::to_string_pretty(&sw)?);
+                }
             }
         };
         Ok(())
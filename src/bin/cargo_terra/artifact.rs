@@ -0,0 +1,146 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// the subset of `cargo metadata` we need to find the wasm artifact
+#[derive(Deserialize, Debug)]
+struct CargoMetadata {
+    packages: Vec<CargoPackage>,
+    workspace_members: Vec<String>,
+    workspace_root: PathBuf,
+    target_directory: PathBuf,
+}
+#[derive(Deserialize, Debug)]
+struct CargoPackage {
+    id: String,
+    name: String,
+    manifest_path: PathBuf,
+    targets: Vec<CargoTarget>,
+}
+#[derive(Deserialize, Debug)]
+struct CargoTarget {
+    name: String,
+    kind: Vec<String>,
+}
+impl CargoPackage {
+    /// the name rustc uses for the wasm file. the cdylib target if there is one
+    fn artifact_name(&self) -> String {
+        let name = self
+            .targets
+            .iter()
+            .find(|t| t.kind.iter().any(|k| k == "cdylib"))
+            .map(|t| t.name.clone())
+            .unwrap_or_else(|| self.name.clone());
+        name.replace('-', "_")
+    }
+}
+
+/// where the current crate lives, and what its wasm file will be called
+pub struct CrateInfo {
+    pub name: String,
+    pub artifact_name: String,
+    pub workspace_root: PathBuf,
+    candidates: Vec<PathBuf>,
+}
+
+/// look up the crate we are sitting in via `cargo metadata`
+pub fn current_crate() -> Result<CrateInfo> {
+    let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .args(["metadata", "--format-version", "1", "--no-deps"])
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "cargo metadata failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let metadata: CargoMetadata = serde_json::from_slice(&output.stdout)?;
+    let cwd = std::env::current_dir()?;
+    let package = current_package(&metadata, &cwd)?;
+    let artifact_name = package.artifact_name();
+    let candidates = candidate_paths(&metadata, package, &artifact_name);
+
+    Ok(CrateInfo {
+        name: package.name.clone(),
+        artifact_name,
+        workspace_root: metadata.workspace_root.clone(),
+        candidates,
+    })
+}
+
+/// find the wasm file of the current crate.
+/// the optimizer output in `artifacts/` is preferred over the raw `wasm32-unknown-unknown` build
+pub fn discover_wasm() -> Result<String> {
+    let crate_info = current_crate()?;
+    for candidate in &crate_info.candidates {
+        if candidate.is_file() {
+            log::info!("using wasm artifact {}", candidate.display());
+            return Ok(candidate.display().to_string());
+        }
+    }
+    anyhow::bail!(
+        "unable to find a wasm artifact for {}. looked in {}. try `cargo wasm` or the rust-optimizer first",
+        crate_info.name,
+        crate_info
+            .candidates
+            .iter()
+            .map(|c| c.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// the workspace member we are sitting in, or the only member if we are at the workspace root
+fn current_package<'a>(metadata: &'a CargoMetadata, cwd: &Path) -> Result<&'a CargoPackage> {
+    let members = metadata
+        .packages
+        .iter()
+        .filter(|p| metadata.workspace_members.contains(&p.id))
+        .collect::<Vec<_>>();
+    let in_dir = members
+        .iter()
+        .filter(|p| {
+            p.manifest_path
+                .parent()
+                .map(|dir| cwd.starts_with(dir))
+                .unwrap_or(false)
+        })
+        // deepest package wins, in case the workspace root is also a package
+        .max_by_key(|p| p.manifest_path.components().count());
+    match in_dir {
+        Some(package) => Ok(package),
+        None if members.len() == 1 => Ok(members[0]),
+        None => anyhow::bail!(
+            "unable to determine the contract. run from inside the contract's directory or specify the wasm file"
+        ),
+    }
+}
+
+fn candidate_paths(
+    metadata: &CargoMetadata,
+    package: &CargoPackage,
+    artifact_name: &str,
+) -> Vec<PathBuf> {
+    let mut candidates = vec![];
+    let mut artifact_dirs = vec![metadata.workspace_root.join("artifacts")];
+    if let Some(package_dir) = package.manifest_path.parent() {
+        let package_artifacts = package_dir.join("artifacts");
+        if !artifact_dirs.contains(&package_artifacts) {
+            artifact_dirs.push(package_artifacts);
+        }
+    }
+    for dir in artifact_dirs {
+        candidates.push(dir.join(format!("{}.wasm", artifact_name)));
+        // rust-optimizer-arm64 suffixes the architecture
+        candidates.push(dir.join(format!("{}-aarch64.wasm", artifact_name)));
+    }
+    candidates.push(
+        metadata
+            .target_directory
+            .join("wasm32-unknown-unknown")
+            .join("release")
+            .join(format!("{}.wasm", artifact_name)),
+    );
+    candidates
+}
@@ -0,0 +1,540 @@
+use anyhow::Result;
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use serde_json::Value;
+use std::fmt::Write;
+use std::path::Path;
+
+/// rust keywords that show up as field names in contracts
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "const", "crate", "enum", "fn", "impl", "in", "let", "loop",
+    "match", "mod", "move", "ref", "self", "static", "struct", "super", "trait", "type", "use",
+    "where",
+];
+
+/// names the generated methods use themselves, which fields are renamed around
+const RESERVED: &[&str] = &[
+    "sender", "secp", "from", "coins", "memo", "height", "query", "execute", "self", "super",
+    "crate",
+];
+
+/// a field of a message, or of a response
+struct Field {
+    name: String,
+    rust_type: String,
+}
+/// what a variant carries
+enum Body {
+    /// sent as a plain string
+    Unit,
+    /// an object of named fields
+    Fields(Vec<Field>),
+    /// a newtype variant whose value isn't an object of fields, such as cw20's `UploadLogo(Logo)`
+    Value(Field),
+}
+/// one variant of ExecuteMsg / QueryMsg
+struct Variant {
+    name: String,
+    doc: Option<String>,
+    body: Body,
+}
+
+/// a rust identifier for a field of a struct
+fn ident(name: &str) -> String {
+    let name = name.to_snake_case();
+    if let "self" | "super" | "crate" = name.as_str() {
+        // self, super & crate can't be raw identifiers
+        format!("{}_", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+/// the parameter names of a variant's fields: clear of the names the methods use, and of each other
+fn param_names(variant: &Variant) -> Vec<String> {
+    let fields = match &variant.body {
+        Body::Unit => return vec![],
+        Body::Fields(fields) => fields.iter().collect::<Vec<_>>(),
+        Body::Value(field) => vec![field],
+    };
+    let mut names: Vec<String> = vec![];
+    for field in fields {
+        let mut name = field.name.to_snake_case();
+        while RESERVED.contains(&name.as_str())
+            || names.iter().any(|n| n.trim_start_matches("r#") == name)
+        {
+            name.push('_');
+        }
+        if KEYWORDS.contains(&name.as_str()) {
+            name = format!("r#{}", name);
+        }
+        names.push(name);
+    }
+    names
+}
+
+fn read_schema(dir: &Path, names: &[&str]) -> Result<Option<Value>> {
+    for name in names {
+        let file = dir.join(format!("{}.json", name));
+        if file.is_file() {
+            return Ok(Some(serde_json::from_str(&std::fs::read_to_string(file)?)?));
+        }
+    }
+    Ok(None)
+}
+
+/// follow `$ref`s, and single-element `allOf`s, into the schema's definitions
+fn resolve<'a>(schema: &'a Value, definitions: &'a Value) -> &'a Value {
+    let mut schema = schema;
+    // a few hops at most, so a definition referring to itself can't loop
+    for _ in 0..8 {
+        if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+            match reference
+                .strip_prefix("#/definitions/")
+                .and_then(|name| definitions.get(name))
+            {
+                Some(definition) => schema = definition,
+                None => break,
+            }
+        } else if let Some([inner]) = schema
+            .get("allOf")
+            .and_then(|a| a.as_array())
+            .map(|a| a.as_slice())
+        {
+            schema = inner;
+        } else {
+            break;
+        }
+    }
+    schema
+}
+
+/// map a JSON schema type onto the rust type we use in the client.
+/// cosmwasm's number wrappers are strings on the wire; anything more complex stays a json Value
+fn rust_type(schema: &Value, definitions: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        let name = reference.trim_start_matches("#/definitions/");
+        if let "Uint64" | "Uint128" | "Uint256" | "Decimal" | "Decimal256" | "Addr" | "Binary" =
+            name
+        {
+            return "String".into();
+        }
+        let resolved = resolve(schema, definitions);
+        return match resolved.get("type") {
+            // an alias of a plain type
+            Some(Value::String(t)) if t != "object" && resolved.get("$ref").is_none() => {
+                rust_type(resolved, definitions)
+            }
+            _ => "serde_json::Value".into(),
+        };
+    }
+    if schema.get("allOf").is_some() {
+        let resolved = resolve(schema, definitions);
+        if resolved.get("allOf").is_none() {
+            return rust_type(resolved, definitions);
+        }
+    }
+    // Option<T> is written as anyOf [T, null]
+    if let Some(any_of) = schema.get("anyOf").and_then(|a| a.as_array()) {
+        let not_null = any_of
+            .iter()
+            .filter(|s| s.get("type").and_then(|t| t.as_str()) != Some("null"))
+            .collect::<Vec<_>>();
+        return match not_null.as_slice() {
+            [inner] if not_null.len() < any_of.len() => {
+                format!("Option<{}>", rust_type(inner, definitions))
+            }
+            _ => "serde_json::Value".into(),
+        };
+    }
+    let format = schema.get("format").and_then(|f| f.as_str());
+    match schema.get("type") {
+        Some(Value::String(t)) => base_type(t, format, schema, definitions),
+        // ["string", "null"] is the other way of saying Option<T>
+        Some(Value::Array(types)) => {
+            let types = types.iter().filter_map(|t| t.as_str()).collect::<Vec<_>>();
+            match types.as_slice() {
+                [t, "null"] | ["null", t] => {
+                    format!("Option<{}>", base_type(t, format, schema, definitions))
+                }
+                _ => "serde_json::Value".into(),
+            }
+        }
+        _ => "serde_json::Value".into(),
+    }
+}
+
+fn base_type(t: &str, format: Option<&str>, schema: &Value, definitions: &Value) -> String {
+    match (t, format) {
+        ("string", _) => "String".into(),
+        ("boolean", _) => "bool".into(),
+        ("integer", Some("uint8")) => "u8".into(),
+        ("integer", Some("uint16")) => "u16".into(),
+        ("integer", Some("uint32")) => "u32".into(),
+        ("integer", Some("uint64")) => "u64".into(),
+        ("integer", Some("int32")) => "i32".into(),
+        ("integer", _) => "i64".into(),
+        ("number", _) => "f64".into(),
+        ("array", _) => match schema.get("items") {
+            Some(items) if items.is_object() => {
+                format!("Vec<{}>", rust_type(items, definitions))
+            }
+            _ => "Vec<serde_json::Value>".into(),
+        },
+        _ => "serde_json::Value".into(),
+    }
+}
+
+fn fields(schema: &Value, definitions: &Value) -> Vec<Field> {
+    let required = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|s| s.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, property)| {
+                    let rust_type = rust_type(property, definitions);
+                    let rust_type =
+                        if required.contains(&name.as_str()) || rust_type.starts_with("Option<") {
+                            rust_type
+                        } else {
+                            format!("Option<{}>", rust_type)
+                        };
+                    Field {
+                        name: name.clone(),
+                        rust_type,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// what the variant called `name` carries, given the schema of its value
+fn body(name: &str, schema: &Value, definitions: &Value) -> Body {
+    let resolved = resolve(schema, definitions);
+    let is_object = resolved.get("type").and_then(|t| t.as_str()) == Some("object");
+    if is_object || resolved.get("properties").is_some() {
+        Body::Fields(fields(resolved, definitions))
+    } else {
+        Body::Value(Field {
+            name: name.into(),
+            rust_type: rust_type(schema, definitions),
+        })
+    }
+}
+
+/// the variants of a cosmwasm ExecuteMsg/QueryMsg enum schema
+fn variants(schema: &Value) -> Vec<Variant> {
+    let mut variants = vec![];
+    let empty = vec![];
+    let definitions = schema.get("definitions").unwrap_or(&Value::Null);
+    let one_of = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(|o| o.as_array())
+        .unwrap_or(&empty);
+    for option in one_of {
+        let doc = option
+            .get("description")
+            .and_then(|d| d.as_str())
+            .map(String::from);
+        if let Some(names) = option.get("enum").and_then(|e| e.as_array()) {
+            for name in names.iter().filter_map(|n| n.as_str()) {
+                variants.push(Variant {
+                    name: name.into(),
+                    doc: doc.clone(),
+                    body: Body::Unit,
+                });
+            }
+        } else if let Some(properties) = option.get("properties").and_then(|p| p.as_object()) {
+            for (name, inner) in properties {
+                variants.push(Variant {
+                    name: name.clone(),
+                    doc: doc.clone(),
+                    body: body(name, inner, definitions),
+                });
+            }
+        }
+    }
+    variants
+}
+
+fn write_doc(out: &mut String, doc: &Option<String>) -> Result<()> {
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            writeln!(out, "    /// {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+/// the json! literal for a message
+fn message_json(variant: &Variant) -> String {
+    let names = param_names(variant);
+    match &variant.body {
+        Body::Unit => format!("serde_json::json!(\"{}\")", variant.name),
+        Body::Fields(fields) => {
+            let fields = fields
+                .iter()
+                .zip(&names)
+                .map(|(f, param)| format!("\"{}\": {}", f.name, param))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "serde_json::json!({{\"{}\": {{{}}}}})",
+                variant.name, fields
+            )
+        }
+        Body::Value(_) => format!("serde_json::json!({{\"{}\": {}}})", variant.name, names[0]),
+    }
+}
+
+fn params(variant: &Variant) -> String {
+    let types = match &variant.body {
+        Body::Unit => vec![],
+        Body::Fields(fields) => fields.iter().map(|f| f.rust_type.as_str()).collect(),
+        Body::Value(field) => vec![field.rust_type.as_str()],
+    };
+    param_names(variant)
+        .iter()
+        .zip(types)
+        .map(|(name, rust_type)| format!(", {}: {}", name, rust_type))
+        .collect()
+}
+
+fn args(variant: &Variant) -> String {
+    param_names(variant)
+        .iter()
+        .map(|name| format!("{}, ", name))
+        .collect()
+}
+
+/// generate a typed client for the contract whose schema files are in `dir`
+pub fn generate(dir: &Path, name: &str) -> Result<String> {
+    let execute = read_schema(dir, &["execute_msg", "handle_msg"])?;
+    let query = read_schema(dir, &["query_msg"])?;
+    if execute.is_none() && query.is_none() {
+        anyhow::bail!(
+            "no execute_msg.json or query_msg.json in {}. run `cargo schema` first",
+            dir.display()
+        );
+    }
+    let client = format!("{}Client", name.to_upper_camel_case());
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// generated by `cargo terra gen-client` from {}. do not edit",
+        dir.display()
+    )?;
+    writeln!(out, "#![allow(dead_code, clippy::too_many_arguments)]")?;
+    writeln!(out, "use secp256k1::{{Context, Secp256k1, Signing}};")?;
+    writeln!(out, "use serde::{{Deserialize, Serialize}};")?;
+    writeln!(out, "use terra_rust_api::client::tx_types::TXResultSync;")?;
+    writeln!(out, "use terra_rust_api::core_types::Coin;")?;
+    writeln!(out, "use terra_rust_api::errors::TerraRustAPIError;")?;
+    writeln!(
+        out,
+        "use terra_rust_api::{{LCDResult, Message, MsgExecuteContract, PrivateKey, Terra}};"
+    )?;
+    writeln!(out)?;
+
+    // response structures, for the queries that have them
+    let query_variants = query.as_ref().map(variants).unwrap_or_default();
+    let mut structs: Vec<String> = vec![];
+    let mut response_types = vec![];
+    for variant in &query_variants {
+        let response_name = format!("{}_response", variant.name);
+        let response_type = match read_schema(dir, &[&response_name])? {
+            Some(schema) => {
+                let struct_name = schema
+                    .get("title")
+                    .and_then(|t| t.as_str())
+                    .map(|t| t.to_upper_camel_case())
+                    .unwrap_or_else(|| response_name.to_upper_camel_case());
+                if !structs.contains(&struct_name) {
+                    writeln!(out, "#[derive(Deserialize, Serialize, Debug, Clone)]")?;
+                    writeln!(out, "pub struct {} {{", struct_name)?;
+                    let definitions = schema.get("definitions").unwrap_or(&Value::Null);
+                    for field in fields(&schema, definitions) {
+                        if ident(&field.name) != field.name {
+                            writeln!(out, "    #[serde(rename = \"{}\")]", field.name)?;
+                        }
+                        writeln!(out, "    pub {}: {},", ident(&field.name), field.rust_type)?;
+                    }
+                    writeln!(out, "}}")?;
+                    writeln!(out)?;
+                    structs.push(struct_name.clone());
+                }
+                struct_name
+            }
+            None => "serde_json::Value".into(),
+        };
+        response_types.push(response_type);
+    }
+
+    writeln!(out, "pub struct {}<'a> {{", client)?;
+    writeln!(out, "    terra: &'a Terra,")?;
+    writeln!(out, "    pub contract: String,")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "impl<'a> {}<'a> {{", client)?;
+    writeln!(
+        out,
+        "    pub fn create(terra: &'a Terra, contract: &str) -> {}<'a> {{",
+        client
+    )?;
+    writeln!(out, "        {} {{", client)?;
+    writeln!(out, "            terra,")?;
+    writeln!(out, "            contract: contract.into(),")?;
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+
+    for variant in execute.as_ref().map(variants).unwrap_or_default() {
+        let method = ident(&variant.name);
+        let plain = method.trim_start_matches("r#");
+        writeln!(out)?;
+        write_doc(&mut out, &variant.doc)?;
+        writeln!(
+            out,
+            "    pub fn {}_msg(&self, sender: &str{}, coins: &[Coin]) -> Result<Message, TerraRustAPIError> {{",
+            plain,
+            params(&variant)
+        )?;
+        writeln!(
+            out,
+            "        MsgExecuteContract::create_from_value(sender, &self.contract, &{}, coins)",
+            message_json(&variant)
+        )?;
+        writeln!(out, "    }}")?;
+        write_doc(&mut out, &variant.doc)?;
+        writeln!(
+            out,
+            "    pub async fn {}<C: Signing + Context>(&self, secp: &Secp256k1<C>, from: &PrivateKey{}, coins: &[Coin], memo: Option<String>) -> Result<TXResultSync, TerraRustAPIError> {{",
+            method,
+            params(&variant)
+        )?;
+        writeln!(
+            out,
+            "        let sender = from.public_key(secp).account()?;"
+        )?;
+        writeln!(
+            out,
+            "        let execute = self.{}_msg(&sender, {}coins)?;",
+            plain,
+            args(&variant)
+        )?;
+        writeln!(
+            out,
+            "        self.terra.submit_transaction_sync(secp, from, vec![execute], memo).await"
+        )?;
+        writeln!(out, "    }}")?;
+    }
+
+    for (variant, response_type) in query_variants.iter().zip(response_types) {
+        writeln!(out)?;
+        write_doc(&mut out, &variant.doc)?;
+        writeln!(
+            out,
+            "    pub async fn {}(&self{}, height: Option<u64>) -> Result<LCDResult<{}>, TerraRustAPIError> {{",
+            ident(&format!("query_{}", variant.name)),
+            params(variant),
+            response_type
+        )?;
+        writeln!(
+            out,
+            "        let query = {}.to_string();",
+            message_json(variant)
+        )?;
+        writeln!(
+            out,
+            "        self.terra.wasm().query(&self.contract, &query, height).await"
+        )?;
+        writeln!(out, "    }}")?;
+    }
+    writeln!(out, "}}")?;
+    Ok(out)
+}
+
+/// the client generated from the cw20-base schemas, compiled so the golden file stays valid rust
+#[cfg(test)]
+#[rustfmt::skip]
+#[path = "../../../resources/cw20-base/cw20_client.rs"]
+mod cw20_client;
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_cw20_golden() -> Result<()> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/cw20-base");
+        let golden = std::fs::read_to_string(dir.join("cw20_client.rs"))?;
+        // the golden file records the directory it was generated from
+        let code = generate(Path::new("resources/cw20-base"), "cw20")?;
+        assert_eq!(code, golden);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_ref_variants() -> Result<()> {
+        let schema = serde_json::json!({
+            "oneOf": [
+                {"type": "object", "required": ["receive"],
+                 "properties": {"receive": {"$ref": "#/definitions/Cw20ReceiveMsg"}}},
+                {"type": "object", "required": ["upload_logo"],
+                 "properties": {"upload_logo": {"$ref": "#/definitions/Logo"}}},
+                {"type": "object", "required": ["set_limit"],
+                 "properties": {"set_limit": {"$ref": "#/definitions/Uint128"}}}
+            ],
+            "definitions": {
+                "Cw20ReceiveMsg": {
+                    "type": "object",
+                    "required": ["amount", "msg", "sender"],
+                    "properties": {
+                        "amount": {"$ref": "#/definitions/Uint128"},
+                        "msg": {"$ref": "#/definitions/Binary"},
+                        "sender": {"type": "string"},
+                        "Sender": {"type": "string"}
+                    }
+                },
+                "Logo": {"oneOf": [{"type": "object", "properties": {"url": {"type": "string"}}}]},
+                "Uint128": {"type": "string"},
+                "Binary": {"type": "string"}
+            }
+        });
+        let variants = variants(&schema);
+        assert_eq!(variants.len(), 3);
+
+        let receive = &variants[0];
+        assert!(matches!(&receive.body, Body::Fields(fields) if fields.len() == 4));
+        assert_eq!(
+            param_names(receive),
+            vec!["sender_", "amount", "msg", "sender__"]
+        );
+        assert_eq!(
+            params(receive),
+            ", sender_: Option<String>, amount: String, msg: String, sender__: String"
+        );
+        assert_eq!(
+            message_json(receive),
+            "serde_json::json!({\"receive\": {\"Sender\": sender_, \"amount\": amount, \"msg\": msg, \"sender\": sender__}})"
+        );
+
+        let logo = &variants[1];
+        assert!(matches!(&logo.body, Body::Value(field) if field.rust_type == "serde_json::Value"));
+        assert_eq!(
+            message_json(logo),
+            "serde_json::json!({\"upload_logo\": upload_logo})"
+        );
+
+        assert_eq!(params(&variants[2]), ", set_limit: String");
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use clap::{ArgMatches, Subcommand};
+use terra_rust_api::shared_secp256k1;
+use terra_rust_cli::cli_helpers;
+
+/// cw20 token operations. amounts are in the token's smallest unit
+#[derive(Subcommand)]
+pub enum Cw20Command {
+    /// token balance of an address (defaults to the signing key's)
+    Balance {
+        token: String,
+        address: Option<String>,
+    },
+    /// transfer tokens to an address
+    Transfer {
+        token: String,
+        recipient: String,
+        amount: u128,
+    },
+    /// name, symbol, decimals & total supply of a token
+    Info { token: String },
+}
+
+fn check_address(address: &str) -> Result<()> {
+    if !address.starts_with("terra1") {
+        anyhow::bail!("invalid address {}", address);
+    }
+    Ok(())
+}
+
+pub async fn run(matches: &ArgMatches, cw20: &ArgMatches, memo: Option<String>) -> Result<()> {
+    match cw20.subcommand() {
+        Some(("balance", balance)) => {
+            let token = cli_helpers::get_arg_value(balance, "token")?;
+            check_address(token)?;
+            let address = match balance.value_of("address") {
+                Some(address) => address.to_string(),
+                None => {
+                    let secp = shared_secp256k1();
+                    cli_helpers::get_private_key(secp, matches)?
+                        .public_key(secp)
+                        .account()?
+                }
+            };
+            let terra = cli_helpers::lcd_no_tx_from_args(matches)?;
+            let resp = terra.cw20().balance(token, &address, None).await?;
+            println!("{}", resp.result.balance);
+        }
+        Some(("transfer", transfer)) => {
+            let token = cli_helpers::get_arg_value(transfer, "token")?;
+            let recipient = cli_helpers::get_arg_value(transfer, "recipient")?;
+            check_address(token)?;
+            check_address(recipient)?;
+            let amount = cli_helpers::get_arg_value(transfer, "amount")?.parse::<u128>()?;
+            let terra = cli_helpers::lcd_from_args(matches).await?;
+            let secp = shared_secp256k1();
+            let private = cli_helpers::get_private_key(secp, matches)?;
+            let resp = terra
+                .cw20()
+                .transfer(secp, &private, token, recipient, amount, memo)
+                .await?;
+            println!("{}", resp.txhash);
+        }
+        Some(("info", info)) => {
+            let token = cli_helpers::get_arg_value(info, "token")?;
+            check_address(token)?;
+            let terra = cli_helpers::lcd_no_tx_from_args(matches)?;
+            let resp = terra.cw20().token_info(token, None).await?;
+            println!("{}", serde_json::to_string_pretty(&resp.result)?);
+        }
+        _ => println!("try cw20 --help"),
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use serde::Deserialize;
+use terra_rust_api::core_types::Coin;
+use terra_rust_api::{Message, MsgExecuteContract};
+use terra_rust_cli::cli_helpers;
+
+/// one entry of a --msgs-file
+#[derive(Deserialize, Debug)]
+struct ExecEntry {
+    contract: String,
+    msg: serde_json::Value,
+    coins: Option<String>,
+}
+
+/// a single execute message, before it is turned into a [Message]
+pub struct ExecSpec {
+    pub contract: String,
+    pub json: serde_json::Value,
+    pub coins: Vec<Coin>,
+}
+impl ExecSpec {
+    pub fn create(
+        contract: &str,
+        json: serde_json::Value,
+        coins: Option<&str>,
+    ) -> Result<ExecSpec> {
+        if !contract.starts_with("terra1") {
+            anyhow::bail!("invalid contract address {}", contract);
+        }
+        let coins = match coins {
+            Some(coin_str) if !coin_str.is_empty() => Coin::parse_coins(coin_str)?,
+            _ => vec![],
+        };
+        Ok(ExecSpec {
+            contract: contract.into(),
+            json,
+            coins,
+        })
+    }
+    /// parse `contract:json[:coins]`. the json part can be anything get_json_block accepts
+    pub fn parse(msg: &str) -> Result<ExecSpec> {
+        let (contract, rest) = msg
+            .split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("expected contract:json[:coins] got {}", msg))?;
+        // json is full of ':', so only treat the last part as coins if it actually parses as coins
+        let (json_str, coins) = match rest.rsplit_once(':') {
+            Some((json_str, coins))
+                if !coins.ends_with('}') && Coin::parse_coins(coins).is_ok() =>
+            {
+                (json_str, Some(coins))
+            }
+            _ => (rest, None),
+        };
+        ExecSpec::create(contract, cli_helpers::get_json_block(json_str)?, coins)
+    }
+    /// read a JSON array of `{"contract": .., "msg": {..}, "coins": ".."}` entries
+    pub fn from_file(file: &str) -> Result<Vec<ExecSpec>> {
+        let entries: Vec<ExecEntry> = serde_json::from_value(cli_helpers::get_json_block(file)?)?;
+        entries
+            .into_iter()
+            .map(|entry| ExecSpec::create(&entry.contract, entry.msg, entry.coins.as_deref()))
+            .collect()
+    }
+    pub fn message(&self, sender: &str) -> Result<Message> {
+        Ok(MsgExecuteContract::create_from_value(
+            sender,
+            &self.contract,
+            &self.json,
+            &self.coins,
+        )?)
+    }
+}
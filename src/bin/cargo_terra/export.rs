@@ -0,0 +1,118 @@
+use anyhow::Result;
+use clap::{ArgMatches, Subcommand};
+use std::fs::File;
+use std::io::BufWriter;
+use terra_rust_api::client::tx_types::V1TXResponse;
+use terra_rust_api::export::{EventRow, ExportFormat, ExportRow, Exporter};
+use terra_rust_api::Terra;
+use terra_rust_cli::cli_helpers;
+
+/// txs fetched per request
+const PAGE_SIZE: u64 = 100;
+
+/// query results as .csv or .parquet files, picked by the --out extension
+#[derive(Subcommand)]
+pub enum ExportCommand {
+    /// transactions sent by the account, oldest first
+    Txs {
+        #[clap(long)]
+        account: String,
+        #[clap(long)]
+        out: String,
+        /// a row per event attribute instead of per transaction
+        #[clap(long)]
+        events: bool,
+    },
+    /// the account's balances
+    Balances {
+        #[clap(long)]
+        account: String,
+        #[clap(long)]
+        out: String,
+    },
+    /// the account's delegations
+    Delegations {
+        #[clap(long)]
+        account: String,
+        #[clap(long)]
+        out: String,
+    },
+}
+
+fn exporter<R: ExportRow>(out: &str) -> Result<Exporter<BufWriter<File>, R>> {
+    Ok(Exporter::create(
+        BufWriter::new(File::create(out)?),
+        ExportFormat::from_path(out),
+    )?)
+}
+
+/// page through the account's transactions, turning each into rows
+async fn export_txs<R: ExportRow>(
+    terra: &Terra,
+    account: &str,
+    out: &str,
+    rows_of: fn(&V1TXResponse) -> Vec<R>,
+) -> Result<usize> {
+    let mut rows = exporter(out)?;
+    let mut offset = 0;
+    loop {
+        let page = terra
+            .tx()
+            .get_txs_for_account(account, Some(offset), Some(PAGE_SIZE))
+            .await?;
+        rows.write(
+            &page
+                .tx_responses
+                .iter()
+                .flat_map(rows_of)
+                .collect::<Vec<_>>(),
+        )?;
+        offset += page.tx_responses.len() as u64;
+        if page.tx_responses.is_empty() || offset >= page.pagination.total {
+            break;
+        }
+    }
+    Ok(rows.finish()?)
+}
+
+pub async fn run(matches: &ArgMatches, export: &ArgMatches) -> Result<()> {
+    let terra = cli_helpers::lcd_from_args(matches).await?;
+    let (out, rows) = match export.subcommand() {
+        Some(("txs", txs)) => {
+            let account = cli_helpers::get_arg_value(txs, "account")?;
+            let out = cli_helpers::get_arg_value(txs, "out")?;
+            let rows = if txs.is_present("events") {
+                export_txs(&terra, account, out, EventRow::from_tx).await?
+            } else {
+                export_txs(&terra, account, out, |tx| vec![tx.clone()]).await?
+            };
+            (out, rows)
+        }
+        Some(("balances", balances)) => {
+            let account = cli_helpers::get_arg_value(balances, "account")?;
+            let out = cli_helpers::get_arg_value(balances, "out")?;
+            let mut rows = exporter(out)?;
+            rows.write(&terra.bank().balances(account, None).await?.result)?;
+            (out, rows.finish()?)
+        }
+        Some(("delegations", delegations)) => {
+            let account = cli_helpers::get_arg_value(delegations, "account")?;
+            let out = cli_helpers::get_arg_value(delegations, "out")?;
+            let mut rows = exporter(out)?;
+            rows.write(
+                &terra
+                    .auth()
+                    .validator_delegations(account, None)
+                    .await?
+                    .result,
+            )?;
+            (out, rows.finish()?)
+        }
+        _ => {
+            println!("try export --help");
+            return Ok(());
+        }
+    };
+    println!("{}: {} rows", out, rows);
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use clap::{ArgMatches, Subcommand};
+use serde::Deserialize;
+use terra_rust_api::core_types::Coin;
+use terra_rust_api::messages::gov::{
+    MsgDeposit, MsgSubmitProposal, MsgVote, ParameterChangeProposal, ProposalContent, VoteOption,
+};
+use terra_rust_api::shared_secp256k1;
+use terra_rust_api::upgrade_types::UpgradePlan;
+use terra_rust_cli::cli_helpers;
+
+/// governance proposals, votes & deposits
+#[derive(Subcommand)]
+pub enum GovCommand {
+    /// submit a proposal. kind is text, parameter-change (or param-change), community-pool-spend or software-upgrade
+    Submit {
+        kind: String,
+        /// JSON, a file of it, or '-' for stdin. see `gov template`
+        params: String,
+    },
+    /// print an example params file for a kind of proposal
+    Template { kind: String },
+    /// vote yes, no, abstain or no_with_veto
+    Vote { proposal_id: u64, option: String },
+    /// add coins to a proposal's deposit
+    Deposit { proposal_id: u64, amount: String },
+}
+
+/// the params common to every kind of proposal
+#[derive(Deserialize)]
+struct ProposalParams {
+    title: String,
+    description: String,
+    /// coins, like 512000000uluna
+    #[serde(default)]
+    deposit: String,
+    /// parameter-change
+    #[serde(default)]
+    changes: Vec<ParamChangeParams>,
+    /// community-pool-spend
+    recipient: Option<String>,
+    amount: Option<String>,
+    /// software-upgrade
+    name: Option<String>,
+    height: Option<u64>,
+    #[serde(default)]
+    info: String,
+}
+#[derive(Deserialize)]
+struct ParamChangeParams {
+    subspace: String,
+    key: String,
+    /// any JSON. strings are taken as the JSON text of the value
+    value: serde_json::Value,
+    /// submit a subspace or key that isn't known to be on the chain
+    #[serde(default)]
+    unchecked: bool,
+}
+
+fn template(kind: &str) -> Result<serde_json::Value> {
+    let common = serde_json::json!({
+        "title": "Title of the proposal",
+        "description": "What it does, and why",
+        "deposit": "512000000uluna"
+    });
+    let extra = match kind {
+        "text" => serde_json::json!({}),
+        "parameter-change" | "param-change" => serde_json::json!({
+            "changes": [{ "subspace": "oracle", "key": "VotePeriod", "value": "\"5\"" }]
+        }),
+        "community-pool-spend" => serde_json::json!({
+            "recipient": "terra1...",
+            "amount": "1000000uluna"
+        }),
+        "software-upgrade" => serde_json::json!({
+            "name": "v0.5.16",
+            "height": 7100000,
+            "info": "https://github.com/terra-money/core/releases"
+        }),
+        _ => anyhow::bail!(
+            "unknown proposal kind {}. try text, parameter-change, community-pool-spend or software-upgrade",
+            kind
+        ),
+    };
+    let mut json = common;
+    if let (Some(json), Some(extra)) = (json.as_object_mut(), extra.as_object()) {
+        json.extend(extra.clone());
+    }
+    Ok(json)
+}
+
+fn content(kind: &str, params: ProposalParams) -> Result<ProposalContent> {
+    let missing = |field: &str| anyhow::anyhow!("{} proposals need a {}", kind, field);
+    match kind {
+        "text" => ProposalContent::text(params.title, params.description),
+        "parameter-change" | "param-change" => params
+            .changes
+            .into_iter()
+            .try_fold(
+                ParameterChangeProposal::create(params.title, params.description),
+                |proposal, c| {
+                    let value = match c.value {
+                        serde_json::Value::String(s) => s,
+                        v => v.to_string(),
+                    };
+                    if c.unchecked {
+                        proposal.change_unchecked(&c.subspace, &c.key, &value)
+                    } else {
+                        proposal.change(&c.subspace, &c.key, &value)
+                    }
+                },
+            )?
+            .content(),
+        "community-pool-spend" => {
+            let recipient = params.recipient.ok_or_else(|| missing("recipient"))?;
+            let amount = Coin::parse_coins(&params.amount.ok_or_else(|| missing("amount"))?)?;
+            ProposalContent::community_pool_spend(
+                params.title,
+                params.description,
+                recipient,
+                &amount,
+            )
+        }
+        "software-upgrade" => {
+            let plan = UpgradePlan {
+                name: params.name.ok_or_else(|| missing("name"))?,
+                height: params.height.ok_or_else(|| missing("height"))?,
+                info: params.info,
+            };
+            ProposalContent::software_upgrade(params.title, params.description, plan)
+        }
+        _ => anyhow::bail!("unknown proposal kind {}. see `gov template`", kind),
+    }
+}
+
+pub async fn run(matches: &ArgMatches, gov: &ArgMatches, memo: Option<String>) -> Result<()> {
+    if let Some(("template", template_args)) = gov.subcommand() {
+        let kind = cli_helpers::get_arg_value(template_args, "kind")?;
+        println!("{}", serde_json::to_string_pretty(&template(kind)?)?);
+        return Ok(());
+    }
+    let secp = shared_secp256k1();
+    let private = cli_helpers::get_private_key(secp, matches)?;
+    let from = private.public_key(secp).account()?;
+    let message = match gov.subcommand() {
+        Some(("submit", submit)) => {
+            let kind = cli_helpers::get_arg_value(submit, "kind")?;
+            let params =
+                cli_helpers::get_json_block(cli_helpers::get_arg_value(submit, "params")?)?;
+            let params: ProposalParams = serde_json::from_value(params)?;
+            let deposit = if params.deposit.is_empty() {
+                vec![]
+            } else {
+                Coin::parse_coins(&params.deposit)?
+            };
+            MsgSubmitProposal::create(from, content(kind, params)?, &deposit)?
+        }
+        Some(("vote", vote)) => {
+            let proposal_id = cli_helpers::get_arg_value(vote, "proposal-id")?.parse::<u64>()?;
+            let option = cli_helpers::get_arg_value(vote, "option")?.parse::<VoteOption>()?;
+            MsgVote::create(from, proposal_id, option)?
+        }
+        Some(("deposit", deposit)) => {
+            let proposal_id = cli_helpers::get_arg_value(deposit, "proposal-id")?.parse::<u64>()?;
+            let amount = Coin::parse_coins(cli_helpers::get_arg_value(deposit, "amount")?)?;
+            MsgDeposit::create(from, proposal_id, &amount)?
+        }
+        _ => {
+            println!("try gov --help");
+            return Ok(());
+        }
+    };
+    let terra = cli_helpers::lcd_from_args(matches).await?;
+    let resp = terra
+        .submit_transaction_sync(secp, &private, vec![message], memo)
+        .await?;
+    println!("{}", resp.txhash);
+    Ok(())
+}
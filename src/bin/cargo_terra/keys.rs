@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::{ArgMatches, Subcommand};
+use std::io::BufRead;
+use terra_rust_api::errors::TerraRustAPIError;
+use terra_rust_api::PrivateKey;
+
+/// seed phrase checks. nothing here touches the wallet or the network
+#[derive(Subcommand)]
+pub enum KeysCommand {
+    /// say what is wrong with a seed phrase: unknown words, the word count, or the checksum.
+    /// reads the words from stdin, so they stay out of the shell's history
+    Validate,
+}
+
+pub fn run(keys: &ArgMatches) -> Result<()> {
+    match keys.subcommand() {
+        Some(("validate", _)) => {
+            let words = std::io::stdin()
+                .lock()
+                .lines()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("no recovery words given"))??;
+            match PrivateKey::validate_words(&words) {
+                Ok(()) => println!("ok"),
+                Err(TerraRustAPIError::MnemonicUnknownWord(position)) => {
+                    let word = words
+                        .split_whitespace()
+                        .nth(position - 1)
+                        .unwrap_or_default();
+                    anyhow::bail!(
+                        "word {} `{}` is not in the word list. did you mean: {}",
+                        position,
+                        word,
+                        PrivateKey::word_suggestions(word).join(", ")
+                    )
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        _ => println!("try keys --help"),
+    }
+    Ok(())
+}
@@ -0,0 +1,828 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Subcommand};
+use dotenv::dotenv;
+use std::path::Path;
+use terra_rust_api::addressbook::EmbeddedSource;
+use terra_rust_api::client::wasm_types::{InstantiateResult, MigrateResult, StoreResult};
+use terra_rust_api::core_types::Coin;
+use terra_rust_api::messages::wasm::{AccessConfig, MsgInstantiateContract, MsgMigrateContract};
+use terra_rust_api::messages::MsgSend;
+use terra_rust_api::redact::RedactingLogger;
+use terra_rust_api::shared_secp256k1;
+use terra_rust_api::{AddressBook, Message, PublicKey, Terra};
+use terra_rust_cli::cli_helpers;
+
+mod artifact;
+mod codegen;
+mod cw20;
+mod exec;
+mod export;
+mod gov;
+mod keys;
+mod manifest;
+mod multisig;
+mod optimize;
+mod stake;
+mod sweep;
+mod tx;
+mod validator;
+
+/// VERSION number of package
+pub const VERSION: Option<&'static str> = option_env!("CARGO_PKG_VERSION");
+/// NAME of package
+pub const NAME: Option<&'static str> = option_env!("CARGO_PKG_NAME");
+
+#[derive(Subcommand)]
+#[allow(clippy::upper_case_acronyms)]
+enum TerraCommands {
+    Migrate {
+        /// contract address, or its name in the manifest
+        contract: String,
+        /// wasm file, code id, or '.' to use the current crate's artifact
+        wasm: String,
+        migrate: Option<String>,
+    },
+    /// execute one or more messages in a single transaction
+    Exec {
+        contract: Option<String>,
+        exec: Option<String>,
+        coins: Option<String>,
+        /// additional message as contract:json[:coins]. can be repeated
+        #[clap(long = "msg", multiple_occurrences = true)]
+        msg: Vec<String>,
+        /// JSON array of {"contract","msg","coins"} entries
+        #[clap(long = "msgs-file")]
+        msgs_file: Option<String>,
+    },
+    Store {
+        /// wasm file. defaults to the current crate's artifact
+        wasm: Option<String>,
+        /// run the wasm through wasm-opt (or the rust-optimizer docker image) before storing
+        #[clap(long)]
+        optimize: bool,
+        /// who may instantiate the code: everybody, nobody, or an address
+        #[clap(long)]
+        instantiate_permission: Option<String>,
+        /// check the wasm against the chain's max contract size before signing
+        #[clap(long)]
+        check_size: bool,
+    },
+    /// store every wasm file in a directory, without waiting for each to commit, writing the code ids to the manifest
+    StoreAll {
+        dir: String,
+    },
+    /// the JSON may use ##SENDER##, ##ADMIN##, ##CODE_ID##, ##env:VAR## and ##contract:name##
+    Instantiate {
+        /// wasm file, code id, or '.' to use the current crate's artifact
+        wasm: String,
+        json: String,
+        admin: Option<String>,
+        coins: Option<String>,
+        /// record the new contract in the manifest under this name
+        #[clap(long)]
+        name: Option<String>,
+    },
+    Query {
+        contract: String,
+        query: String,
+    },
+    /// write every key/value in a contract's store to JSON. keys are hex, values base64
+    StateDump {
+        contract: String,
+        /// file to write. defaults to stdout
+        #[clap(long)]
+        out: Option<String>,
+        /// dump the state as of this height
+        #[clap(long)]
+        height: Option<u64>,
+    },
+    /// cw20 token balance, transfer & info
+    Cw20 {
+        #[clap(subcommand)]
+        command: cw20::Cw20Command,
+    },
+    /// generate a typed rust client from a contract's schema directory
+    GenClient {
+        /// directory with the execute_msg.json/query_msg.json files from `cargo schema`
+        #[clap(default_value = "schema")]
+        schema: String,
+        /// contract name, used to name the client struct
+        #[clap(long, default_value = "contract")]
+        name: String,
+        /// file to write. defaults to stdout
+        #[clap(long)]
+        out: Option<String>,
+    },
+    /// governance proposals, votes & deposits
+    Gov {
+        #[clap(subcommand)]
+        command: gov::GovCommand,
+    },
+    /// collect and combine signatures for a legacy multisig account
+    Multisig {
+        #[clap(subcommand)]
+        command: multisig::MultisigCommand,
+    },
+    /// block until the chain reaches a height, or an upgrade has been applied. checks every --sleep seconds
+    Wait {
+        #[clap(long, required_unless_present = "upgrade")]
+        height: Option<u64>,
+        /// the upgrade plan's name. fails unless it is applied or the chain's current plan
+        #[clap(long)]
+        upgrade: Option<String>,
+        /// give up after this many seconds
+        #[clap(long)]
+        timeout: Option<u64>,
+    },
+    /// peers from the network's address book, for config.toml
+    Peers {
+        /// mainnet, testnet, embedded (the snapshot compiled in), or the URL or file:// of an addrbook.json, gzipped or not
+        #[clap(long, default_value = "mainnet")]
+        network: String,
+        /// only peers accepting connections on their p2p port
+        #[clap(long)]
+        alive_only: bool,
+        /// seconds to wait for each connection
+        #[clap(long, default_value = "3")]
+        timeout: u64,
+        /// lines, one peer per line, or persistent_peers, a line to paste into config.toml
+        #[clap(long, default_value = "lines")]
+        format: String,
+    },
+    /// delegate, undelegate, redelegate & withdraw rewards
+    Stake {
+        #[clap(subcommand)]
+        command: stake::StakeCommand,
+    },
+    /// swap every token worth more than a threshold into one
+    Sweep {
+        /// the token to swap into, like uusd
+        to_coin: String,
+        /// what a token has to be worth in to_coin to be swapped
+        threshold: String,
+        /// a threshold for one token, as denom=amount. can be repeated
+        #[clap(long, multiple_occurrences = true)]
+        min: Vec<String>,
+        /// only swap these tokens. can be repeated
+        #[clap(long, multiple_occurrences = true)]
+        allow: Vec<String>,
+        /// never swap these tokens. can be repeated
+        #[clap(long, multiple_occurrences = true)]
+        deny: Vec<String>,
+        /// swap at most this many tokens, the most valuable first
+        #[clap(long)]
+        max_messages: Option<usize>,
+        /// show each token's balance, value, spread and whether it would be swapped, without swapping
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// unjail, edit, withdraw commission & check on your validator
+    Validator {
+        #[clap(subcommand)]
+        command: validator::ValidatorCommand,
+    },
+    /// write an account's transactions, events, balances or delegations to a .csv or .parquet file
+    Export {
+        #[clap(subcommand)]
+        command: export::ExportCommand,
+    },
+    /// send coins. the recipient is an address, or @name from `alias`.
+    /// with --add-tax, a fixed --fees has the stability tax on the coins added to it
+    Send {
+        to: String,
+        /// coins, like 100uusd or 1000uluna,20uusd
+        amount: String,
+        /// instead of the default memo
+        #[clap(long)]
+        memo: Option<String>,
+    },
+    /// names for addresses, kept in the wallet with no key, to `send` to as @name
+    Alias {
+        #[clap(subcommand)]
+        command: AliasCommand,
+    },
+    /// seed phrase checks
+    Keys {
+        #[clap(subcommand)]
+        command: keys::KeysCommand,
+    },
+    /// sign and broadcast transactions separately
+    Tx {
+        #[clap(subcommand)]
+        command: tx::TxCommand,
+    },
+}
+#[derive(Subcommand)]
+enum AliasCommand {
+    /// name an address (account or contract)
+    Add {
+        name: String,
+        address: String,
+    },
+    Remove {
+        name: String,
+    },
+    List,
+}
+/// the command line, as `run` parses it
+fn cli() -> clap::Command<'static> {
+    let cli: clap::Command = cli_helpers::gen_cli("terra", "cargo-terra").args(&[
+        Arg::new("retries")
+            .long("retries")
+            .takes_value(true)
+            .value_name("retries")
+            .required(false)
+            .default_value("5")
+            .help("amount of times to retry fetching hash"),
+        Arg::new("sleep")
+            .long("sleep")
+            .takes_value(true)
+            .value_name("sleep")
+            .required(false)
+            .default_value("3")
+            .help("amount of seconds before retying to fetch hash"),
+        Arg::new("schema")
+            .long("schema")
+            .takes_value(true)
+            .value_name("schema")
+            .required(false)
+            .help("check contract messages against this schema file, or the directory `cargo schema` wrote, before sending"),
+        Arg::new("manifest")
+            .long("manifest")
+            .takes_value(true)
+            .value_name("manifest")
+            .required(false)
+            .default_value("manifest.json")
+            .global(true)
+            .help("the code ids & contract addresses deployed, filling ##contract:name## in messages"),
+    ]);
+    TerraCommands::augment_subcommands(cli)
+}
+async fn run(args: Vec<String>) -> Result<()> {
+    let memo = Some(format!(
+        "PFC-{}/{}",
+        NAME.unwrap_or("TERRARUST"),
+        VERSION.unwrap_or("DEV")
+    ));
+    let matches: ArgMatches = cli().get_matches_from(args);
+
+    let sleep = cli_helpers::get_arg_value(&matches, "sleep")?.parse::<u64>()?;
+    let retries = cli_helpers::get_arg_value(&matches, "retries")?.parse::<usize>()?;
+    let schema = matches.value_of("schema");
+    let manifest_file = Path::new(cli_helpers::get_arg_value(&matches, "manifest")?);
+
+    match matches.subcommand() {
+        Some(("migrate", migrate)) => {
+            let terra = cli_helpers::lcd_from_args(&matches).await?;
+            let manifest = manifest::Manifest::load_for(manifest_file, &terra.chain_id)?;
+            let contract = cli_helpers::get_arg_value(migrate, "contract")?;
+            let contract = match manifest.contracts.get(contract) {
+                Some(address) => address.as_str(),
+                None if contract.starts_with("terra1") => contract,
+                None => anyhow::bail!(
+                    "invalid contract address, and no {} in {}",
+                    contract,
+                    manifest_file.display()
+                ),
+            };
+            let secp = shared_secp256k1();
+            let private = cli_helpers::get_private_key(secp, &matches)?;
+            let wasm = cli_helpers::get_arg_value(migrate, "wasm")?;
+            let code_id = if let Ok(code_id) = wasm.parse::<u64>() {
+                code_id
+            } else {
+                let wasm = resolve_wasm(Some(wasm))?;
+                let hash = terra
+                    .wasm()
+                    .store(secp, &private, &wasm, memo.clone())
+                    .await?
+                    .txhash;
+                let tx = terra
+                    .tx()
+                    .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(sleep))
+                    .await?;
+                StoreResult::from_tx(&tx)?.code_id
+            };
+
+            let json = if let Some(migrate_json) = migrate.value_of("migrate") {
+                let json_block = cli_helpers::fetch_json_block(migrate_json)
+                    .await?
+                    .to_string();
+                let migrate_json = MsgMigrateContract::template(
+                    &private.public_key(secp).account()?,
+                    contract,
+                    code_id,
+                )
+                .contracts(&manifest.contracts)
+                .render(&json_block)?;
+                if let Some(schema) = schema {
+                    cli_helpers::check_json_schema(
+                        &serde_json::from_str(&migrate_json)?,
+                        schema,
+                        "migrate",
+                    )?;
+                }
+                Some(migrate_json)
+            } else {
+                None
+            };
+            let hash = terra
+                .wasm()
+                .migrate(secp, &private, contract, code_id, json, memo)
+                .await?
+                .txhash;
+            let tx = terra
+                .tx()
+                .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(sleep))
+                .await?;
+            let migrated = MigrateResult::from_tx(&tx)?;
+            println!(
+                "Contract: {} Migrated to {}",
+                migrated.contract_address, migrated.code_id
+            );
+        }
+        Some(("instantiate", instantiate)) => {
+            let terra = cli_helpers::lcd_from_args(&matches).await?;
+            // only a manifest the new contract is recorded in has to be for this chain
+            let mut manifest = if instantiate.is_present("name") {
+                manifest::Manifest::load_or_create(manifest_file, &terra.chain_id)?
+            } else {
+                manifest::Manifest::load_for(manifest_file, &terra.chain_id)?
+            };
+            let secp = shared_secp256k1();
+            let private = cli_helpers::get_private_key(secp, &matches)?;
+            let wasm = cli_helpers::get_arg_value(instantiate, "wasm")?;
+            let coins = if let Some(coin_str) = instantiate.value_of("coins") {
+                Coin::parse_coins(coin_str)?
+            } else {
+                vec![]
+            };
+            let code_id = if let Ok(code_id) = wasm.parse::<u64>() {
+                code_id
+            } else {
+                let wasm = resolve_wasm(Some(wasm))?;
+                let hash = terra
+                    .wasm()
+                    .store(secp, &private, &wasm, memo.clone())
+                    .await?
+                    .txhash;
+                let tx = terra
+                    .tx()
+                    .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(sleep))
+                    .await?;
+                StoreResult::from_tx(&tx)?.code_id
+            };
+            let admin: Option<String> = if let Some(admin) = instantiate.value_of("admin") {
+                if admin.starts_with("terra1") {
+                    Some(admin.to_string())
+                } else if admin == "same" {
+                    Some(private.public_key(secp).account()?)
+                } else if admin == "none" {
+                    todo!("Admin of none/empty not supported")
+                } else {
+                    let wallet = cli_helpers::wallet_from_args(&matches)?;
+                    let seed = matches.value_of("seed");
+                    let admin_key = wallet.get_public_key(shared_secp256k1(), admin, seed)?;
+                    let admin_account = admin_key.account()?;
+                    Some(admin_account)
+                }
+            } else {
+                todo!("Admin of none/empty not supported")
+            };
+
+            let init_json = cli_helpers::get_arg_value(instantiate, "json")?;
+            let json = cli_helpers::fetch_json_block(init_json).await?.to_string();
+            let init_json_parsed = MsgInstantiateContract::template(
+                &private.public_key(secp).account()?,
+                admin.clone(),
+                code_id,
+            )
+            .contracts(&manifest.contracts)
+            .render(&json)?;
+            if let Some(schema) = schema {
+                cli_helpers::check_json_schema(
+                    &serde_json::from_str(&init_json_parsed)?,
+                    schema,
+                    "instantiate",
+                )?;
+            }
+
+            let hash = terra
+                .wasm()
+                .instantiate(
+                    secp,
+                    &private,
+                    code_id,
+                    init_json_parsed,
+                    coins,
+                    admin,
+                    memo,
+                )
+                .await?
+                .txhash;
+            let tx = terra
+                .tx()
+                .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(sleep))
+                .await?;
+            let instantiated = InstantiateResult::from_tx(&tx)?;
+            println!(
+                "Contract: {} running  code {}",
+                instantiated.contract_address, instantiated.code_id
+            );
+            if let Some(name) = instantiate.value_of("name") {
+                manifest
+                    .contracts
+                    .insert(name.into(), instantiated.contract_address);
+                manifest.save(manifest_file)?;
+                println!("recorded as {} in {}", name, manifest_file.display());
+            }
+        }
+        Some(("store", store)) => {
+            let permission = match store.value_of("instantiate-permission") {
+                None => None,
+                Some("everybody") => Some(AccessConfig::everybody()),
+                Some("nobody") => Some(AccessConfig::nobody()),
+                Some(address) => {
+                    if PublicKey::from_account(address).is_err() {
+                        anyhow::bail!(
+                            "instantiate permission `{}` is not a terra account address. the only keywords accepted are everybody and nobody",
+                            address
+                        );
+                    }
+                    Some(AccessConfig::only_address(address))
+                }
+            };
+            let terra = cli_helpers::lcd_from_args(&matches).await?;
+            let secp = shared_secp256k1();
+            let private = cli_helpers::get_private_key(secp, &matches)?;
+            let wasm = if store.is_present("optimize") {
+                optimize::optimize(store.value_of("wasm").filter(|w| *w != "."))?
+            } else {
+                resolve_wasm(store.value_of("wasm"))?
+            };
+            if store.is_present("check-size") {
+                terra.wasm().check_code_size(&wasm).await?;
+            }
+
+            let hash = terra
+                .wasm()
+                .store_with_permission(secp, &private, &wasm, permission, memo.clone())
+                .await?
+                .txhash;
+            let tx = terra
+                .tx()
+                .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(sleep))
+                .await?;
+
+            println!(
+                "Contract: stored with code {}",
+                StoreResult::from_tx(&tx)?.code_id
+            );
+        }
+        Some(("wait", wait)) => {
+            let terra = cli_helpers::lcd_from_args(&matches).await?;
+            let terra = match wait.value_of("timeout") {
+                Some(timeout) => {
+                    terra.with_timeout(tokio::time::Duration::from_secs(timeout.parse::<u64>()?))
+                }
+                None => terra,
+            };
+            let interval = tokio::time::Duration::from_secs(sleep);
+            if let Some(height) = wait.value_of("height") {
+                let block = terra
+                    .tendermint()
+                    .wait_for_height(height.parse::<u64>()?, interval)
+                    .await?;
+                println!("Height: {}", block.block.header.height);
+            }
+            if let Some(upgrade) = wait.value_of("upgrade") {
+                if let Ok(Some(plan)) = terra.upgrade().current_plan().await {
+                    if plan.name == upgrade {
+                        println!(
+                            "Upgrade: {} is planned for height {}",
+                            plan.name, plan.height
+                        );
+                    }
+                }
+                let height = terra.upgrade().wait_applied(upgrade, interval).await?;
+                println!("Upgrade: {} applied at height {}", upgrade, height);
+            }
+        }
+        Some(("peers", peers)) => {
+            let book = match cli_helpers::get_arg_value(peers, "network")? {
+                "mainnet" => Terra::production_address_book().await?,
+                "testnet" => Terra::testnet_address_book().await?,
+                "embedded" => EmbeddedSource::address_book(),
+                url => Terra::address_book(url).await?,
+            };
+            let list = if peers.is_present("alive-only") {
+                let timeout = cli_helpers::get_arg_value(peers, "timeout")?.parse::<u64>()?;
+                book.alive_peers(tokio::time::Duration::from_secs(timeout), 32)
+                    .await
+            } else {
+                book.peers()
+            };
+            match cli_helpers::get_arg_value(peers, "format")? {
+                "persistent_peers" => println!(
+                    "persistent_peers = \"{}\"",
+                    AddressBook::persistent_peers(&list)
+                ),
+                "lines" => list.iter().for_each(|p| println!("{}", p.to_string())),
+                format => anyhow::bail!("unknown format {}. try lines or persistent_peers", format),
+            }
+        }
+        Some(("store-all", store_all)) => {
+            let terra = cli_helpers::lcd_from_args(&matches).await?;
+            let secp = shared_secp256k1();
+            let private = cli_helpers::get_private_key(secp, &matches)?;
+            let dir = cli_helpers::get_arg_value(store_all, "dir")?;
+            let files = manifest::wasm_files(Path::new(dir))?;
+            if files.is_empty() {
+                anyhow::bail!("no wasm files found in {}", dir);
+            }
+            let mut manifest = manifest::Manifest::load_or_create(manifest_file, &terra.chain_id)?;
+
+            // signed with consecutive sequence numbers & sent back-to-back, then tracked in order
+            let wasms = files
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect::<Vec<_>>();
+            let sent = terra
+                .wasm()
+                .store_many(secp, &private, &wasms, None, memo)
+                .await?;
+            let mut rejected = None;
+            for (file, resp) in files.iter().zip(sent) {
+                let file_name = file
+                    .file_name()
+                    .map(|f| f.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let txhash = match resp {
+                    Ok(resp) => resp.txhash,
+                    Err(e) => {
+                        rejected = Some(format!("{}: {}", file_name, e));
+                        break;
+                    }
+                };
+                let tx = terra
+                    .tx()
+                    .get_and_wait_v1(&txhash, retries, tokio::time::Duration::from_secs(sleep))
+                    .await?;
+                let code_id = StoreResult::from_tx(&tx)?.code_id;
+                println!("{}: stored with code {} ({})", file_name, code_id, txhash);
+                manifest
+                    .codes
+                    .insert(file_name, manifest::StoredCode { code_id, txhash });
+                // saved as we go, so a failure part way through doesn't lose what was stored
+                manifest.save(manifest_file)?;
+            }
+            if let Some(rejected) = rejected {
+                anyhow::bail!(
+                    "{}. the files after it weren't sent. {} has what was stored",
+                    rejected,
+                    manifest_file.display()
+                );
+            }
+            println!("manifest written to {}", manifest_file.display());
+        }
+        Some(("exec", exec)) => {
+            let mut specs = vec![];
+            if let Some(contract) = exec.value_of("contract") {
+                let exec_str = cli_helpers::get_arg_value(exec, "exec")?;
+                specs.push(exec::ExecSpec::create(
+                    contract,
+                    cli_helpers::fetch_json_block(exec_str).await?,
+                    exec.value_of("coins"),
+                )?);
+            }
+            if let Some(msgs) = exec.values_of("msg") {
+                for msg in msgs {
+                    specs.push(exec::ExecSpec::parse(msg)?);
+                }
+            }
+            if let Some(msgs_file) = exec.value_of("msgs-file") {
+                specs.extend(exec::ExecSpec::from_file(msgs_file)?);
+            }
+            if specs.is_empty() {
+                anyhow::bail!("nothing to execute. specify a contract, --msg or --msgs-file");
+            }
+            if let Some(schema) = schema {
+                for spec in &specs {
+                    cli_helpers::check_json_schema(&spec.json, schema, "execute")?;
+                }
+            }
+
+            let terra = cli_helpers::lcd_from_args(&matches).await?;
+            let secp = shared_secp256k1();
+            let private = cli_helpers::get_private_key(secp, &matches)?;
+            let sender = private.public_key(secp).account()?;
+            let messages = specs
+                .iter()
+                .map(|spec| spec.message(&sender))
+                .collect::<Result<Vec<Message>>>()?;
+
+            let resp = terra
+                .submit_transaction_sync(secp, &private, messages, memo)
+                .await?
+                .txhash;
+            println!("{}", resp);
+        }
+        Some(("query", query)) => {
+            let contract = cli_helpers::get_arg_value(query, "contract")?;
+
+            if !contract.starts_with("terra1") {
+                anyhow::bail!("invalid contract address");
+            }
+            let terra = cli_helpers::lcd_no_tx_from_args(&matches)?;
+            let query_str = cli_helpers::get_arg_value(query, "query")?;
+            let query_json = cli_helpers::get_json_block_checked(query_str, schema, "query")
+                .await?
+                .to_string();
+            let result = terra
+                .wasm()
+                .query::<serde_json::Value>(contract, &query_json, None)
+                .await?;
+
+            println!("{}", serde_json::to_string_pretty(&result)?)
+        }
+        Some(("state-dump", state_dump)) => {
+            let contract = cli_helpers::get_arg_value(state_dump, "contract")?;
+
+            if !contract.starts_with("terra1") {
+                anyhow::bail!("invalid contract address");
+            }
+            let height = match state_dump.value_of("height") {
+                Some(height) => Some(height.parse::<u64>()?),
+                None => None,
+            };
+            let terra = cli_helpers::lcd_no_tx_from_args(&matches)?;
+            let state = terra.wasm().all_contract_state(contract, height).await?;
+            let models = state
+                .iter()
+                .map(|(key, value)| {
+                    serde_json::json!({"key": hex::encode(key), "value": base64::encode(value)})
+                })
+                .collect::<Vec<_>>();
+            let dump = serde_json::to_string_pretty(&serde_json::json!({
+                "contract": contract,
+                "height": height,
+                "models": models,
+            }))?;
+            match state_dump.value_of("out") {
+                Some(out) => {
+                    std::fs::write(out, dump)?;
+                    println!("{} entries written to {}", state.len(), out);
+                }
+                None => println!("{}", dump),
+            }
+        }
+        Some(("cw20", cw20)) => cw20::run(&matches, cw20, memo).await?,
+        Some(("gov", gov)) => gov::run(&matches, gov, memo).await?,
+        Some(("stake", stake)) => stake::run(&matches, stake, memo).await?,
+        Some(("sweep", sweep)) => sweep::run(&matches, sweep, memo).await?,
+        Some(("validator", validator)) => validator::run(&matches, validator, memo).await?,
+        Some(("gen-client", gen_client)) => {
+            let schema = cli_helpers::get_arg_value(gen_client, "schema")?;
+            let name = cli_helpers::get_arg_value(gen_client, "name")?;
+            let code = codegen::generate(Path::new(schema), name)?;
+            match gen_client.value_of("out") {
+                Some(out) => {
+                    std::fs::write(out, code)?;
+                    // best effort. the generated code is valid either way
+                    let _ = std::process::Command::new("rustfmt")
+                        .args(["--edition", "2018", out])
+                        .status();
+                    println!("client written to {}", out);
+                }
+                None => println!("{}", code),
+            }
+        }
+        Some(("multisig", multisig)) => multisig::run(&matches, multisig).await?,
+        Some(("tx", tx)) => tx::run(&matches, tx).await?,
+        Some(("export", export)) => export::run(&matches, export).await?,
+        Some(("keys", keys)) => keys::run(keys)?,
+        Some(("send", send)) => {
+            let secp = shared_secp256k1();
+            let wallet = cli_helpers::wallet_from_args(&matches)?;
+            let to = wallet.resolve_recipient(
+                secp,
+                cli_helpers::get_arg_value(send, "to")?,
+                matches.value_of("seed"),
+            )?;
+            let amount = Coin::parse_coins(cli_helpers::get_arg_value(send, "amount")?)?;
+            let private = cli_helpers::get_private_key(secp, &matches)?;
+            let from = private.public_key(secp).account()?;
+            let terra = cli_helpers::lcd_from_args(&matches).await?;
+            let resp = terra
+                .submit_transaction_sync(
+                    secp,
+                    &private,
+                    vec![MsgSend::create(from, to, &amount)?],
+                    send.value_of("memo").map(String::from).or(memo),
+                )
+                .await?;
+            println!("{}", resp.txhash);
+        }
+        Some(("alias", alias)) => {
+            let wallet = cli_helpers::wallet_from_args(&matches)?;
+            match alias.subcommand() {
+                Some(("add", add)) => {
+                    wallet.store_address(
+                        cli_helpers::get_arg_value(add, "name")?,
+                        cli_helpers::get_arg_value(add, "address")?,
+                    )?;
+                }
+                Some(("remove", remove)) => {
+                    wallet.delete_address(cli_helpers::get_arg_value(remove, "name")?)?;
+                }
+                Some(("list", _)) => {
+                    for (name, address) in wallet.list_watch_only()? {
+                        println!("@{}\t{}", name, address);
+                    }
+                }
+                _ => println!("try alias --help"),
+            }
+        }
+        _ => {
+            println!("try --help")
+        }
+    }
+    Ok(())
+}
+
+/// use the wasm file given, or go looking for the current crate's artifact if none (or '.') was specified
+fn resolve_wasm(wasm: Option<&str>) -> Result<String> {
+    match wasm {
+        Some(".") | None => artifact::discover_wasm(),
+        Some(wasm) => Ok(wasm.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv().ok();
+    let logger = env_logger::Builder::from_default_env().build();
+    let level = logger.filter();
+    // seed phrases are passed on the command line. keep them out of the logs
+    RedactingLogger::init(Box::new(logger), level).expect("logger already set");
+    // in case we are invoked by cargo-terra
+    let mut args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 && args[1] == "terra" {
+        args.remove(1);
+    }
+
+    if let Err(ref err) = run(args).await {
+        log::error!("{}", err);
+        err.chain()
+            .skip(1)
+            .for_each(|cause| log::error!("because: {}", cause));
+
+        // The backtrace is not always generated. Try to run this example
+        // with `$env:RUST_BACKTRACE=1`.
+        //    if let Some(backtrace) = e.backtrace() {
+        //        log::debug!("backtrace: {:?}", backtrace);
+        //    }
+
+        ::std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_manifest_arg() {
+        let manifest = |args: &[&str]| {
+            cli()
+                .get_matches_from(args)
+                .value_of("manifest")
+                .map(String::from)
+        };
+        // given after the subcommand, as store-all used to take it
+        assert_eq!(
+            manifest(&[
+                "cargo-terra",
+                "store-all",
+                "artifacts",
+                "--manifest",
+                "x.json"
+            ]),
+            Some("x.json".into())
+        );
+        assert_eq!(
+            manifest(&[
+                "cargo-terra",
+                "--manifest",
+                "y.json",
+                "store-all",
+                "artifacts"
+            ]),
+            Some("y.json".into())
+        );
+        assert_eq!(
+            manifest(&["cargo-terra", "store-all", "artifacts"]),
+            Some("manifest.json".into())
+        );
+    }
+}
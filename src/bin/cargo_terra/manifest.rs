@@ -0,0 +1,81 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// a stored wasm file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoredCode {
+    pub code_id: u64,
+    pub txhash: String,
+}
+
+/// the result of a release. which wasm files went up as which code ids,
+/// and the contracts instantiated from them, for `##contract:name##` in later messages
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Manifest {
+    pub chain_id: String,
+    /// keyed by wasm file name
+    pub codes: BTreeMap<String, StoredCode>,
+    /// contract addresses, keyed by the name given to `instantiate --name`
+    #[serde(default)]
+    pub contracts: BTreeMap<String, String>,
+}
+impl Manifest {
+    pub fn create(chain_id: &str) -> Manifest {
+        Manifest {
+            chain_id: chain_id.into(),
+            codes: Default::default(),
+            contracts: Default::default(),
+        }
+    }
+    /// the manifest in `file`, or a new one if there isn't one yet.
+    /// a manifest from another chain is an error, as its code ids & addresses mean nothing here
+    pub fn load_or_create(file: &Path, chain_id: &str) -> Result<Manifest> {
+        if !file.exists() {
+            return Ok(Manifest::create(chain_id));
+        }
+        let manifest: Manifest = serde_json::from_str(&std::fs::read_to_string(file)?)?;
+        if manifest.chain_id != chain_id {
+            anyhow::bail!(
+                "{} is for {}, not {}",
+                file.display(),
+                manifest.chain_id,
+                chain_id
+            );
+        }
+        Ok(manifest)
+    }
+    /// the manifest in `file`, to read names from. one from another chain is passed over
+    /// with a warning, as only a manifest being written to has to match
+    pub fn load_for(file: &Path, chain_id: &str) -> Result<Manifest> {
+        if !file.exists() {
+            return Ok(Manifest::create(chain_id));
+        }
+        let manifest: Manifest = serde_json::from_str(&std::fs::read_to_string(file)?)?;
+        if manifest.chain_id != chain_id {
+            log::warn!(
+                "{} is for {}, not {}. not using it",
+                file.display(),
+                manifest.chain_id,
+                chain_id
+            );
+            return Ok(Manifest::create(chain_id));
+        }
+        Ok(manifest)
+    }
+    pub fn save(&self, file: &Path) -> Result<()> {
+        std::fs::write(file, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// all the .wasm files in a directory, sorted by name so releases are repeatable
+pub fn wasm_files(dir: &Path) -> Result<Vec<std::path::PathBuf>> {
+    let mut files = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().map(|e| e == "wasm").unwrap_or(false))
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
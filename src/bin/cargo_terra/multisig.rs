@@ -0,0 +1,100 @@
+use crate::tx;
+use anyhow::Result;
+use clap::{ArgMatches, Subcommand};
+use terra_rust_api::core_types::{PubKeySig, SignedTx, StdSignature};
+use terra_rust_api::shared_secp256k1;
+use terra_rust_api::LegacyAminoMultisig;
+use terra_rust_cli::cli_helpers;
+
+/// legacy amino multisig. each signer runs `sign`, then someone runs `combine` and `tx broadcast`
+#[derive(Subcommand)]
+pub enum MultisigCommand {
+    /// sign an unsigned transaction as one of the multisig's keys
+    Sign {
+        /// the unsigned transaction json
+        file: String,
+        /// where to write the signature. defaults to stdout
+        #[clap(long)]
+        out: Option<String>,
+    },
+    /// combine the signer's signatures into a transaction ready to broadcast
+    Combine {
+        /// the unsigned transaction json
+        file: String,
+        /// signature files from `multisig sign`
+        #[clap(required = true)]
+        signatures: Vec<String>,
+        /// number of signatures the multisig needs
+        #[clap(long)]
+        threshold: u64,
+        /// base64 public keys of the multisig, comma separated, in the order the multisig was created with
+        #[clap(long, use_value_delimiter = true, required = true)]
+        pubkeys: Vec<String>,
+        /// where to write the signed transaction. defaults to stdout
+        #[clap(long)]
+        out: Option<String>,
+    },
+}
+
+fn write_out(out: Option<&str>, js: &str) -> Result<()> {
+    match out {
+        Some(out) => {
+            std::fs::write(out, js)?;
+            println!("written to {}", out);
+        }
+        None => println!("{}", js),
+    }
+    Ok(())
+}
+
+pub async fn run(matches: &ArgMatches, multisig: &ArgMatches) -> Result<()> {
+    match multisig.subcommand() {
+        Some(("sign", sign)) => {
+            let secp = shared_secp256k1();
+            let private = cli_helpers::get_private_key(secp, matches)?;
+            let unsigned: SignedTx = tx::read_tx(cli_helpers::get_arg_value(sign, "file")?)?;
+            let signature = unsigned.sign_msg.sign(secp, &private)?;
+            write_out(
+                sign.value_of("out"),
+                &serde_json::to_string_pretty(&signature)?,
+            )?;
+        }
+        Some(("combine", combine)) => {
+            let unsigned: SignedTx = tx::read_tx(cli_helpers::get_arg_value(combine, "file")?)?;
+            let threshold = cli_helpers::get_arg_value(combine, "threshold")?.parse::<u64>()?;
+            let pubkeys = combine
+                .values_of("pubkeys")
+                .map(|keys| {
+                    keys.map(|key| PubKeySig {
+                        stype: "tendermint/PubKeySecp256k1".into(),
+                        value: key.into(),
+                    })
+                    .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let multisig = LegacyAminoMultisig::create(threshold, pubkeys)?;
+            let signatures = combine
+                .values_of("signatures")
+                .map(|files| {
+                    files
+                        .map(|file| -> Result<StdSignature> {
+                            Ok(serde_json::from_value(cli_helpers::get_json_block(file)?)?)
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+                .unwrap_or_else(|| Ok(vec![]))?;
+            log::info!("multisig account {}", multisig.account()?);
+
+            let signed = SignedTx {
+                sign_msg: unsigned.sign_msg,
+                signatures: vec![multisig.combine(&signatures)?],
+            };
+            write_out(
+                combine.value_of("out"),
+                &serde_json::to_string_pretty(&signed)?,
+            )?;
+        }
+        _ => println!("try multisig --help"),
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use crate::artifact;
+use anyhow::Result;
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// the docker image used when wasm-opt isn't installed locally
+const OPTIMIZER_IMAGE: &str = "cosmwasm/rust-optimizer:0.12.6";
+/// every wasm module starts with these bytes
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+/// optimize the wasm file (or the current crate) and return the path of the optimized artifact.
+/// wasm-opt is used if it is on the PATH, otherwise the cosmwasm rust-optimizer docker image is run
+pub fn optimize(wasm: Option<&str>) -> Result<String> {
+    let optimized = if has_wasm_opt() {
+        let input = match wasm {
+            Some(wasm) => PathBuf::from(wasm),
+            None => PathBuf::from(artifact::discover_wasm()?),
+        };
+        run_wasm_opt(&input)?
+    } else {
+        if wasm.is_some() {
+            log::warn!(
+                "wasm-opt not found. {} rebuilds the whole crate, ignoring the wasm file specified",
+                OPTIMIZER_IMAGE
+            );
+        }
+        let optimized = run_rust_optimizer()?;
+        verify_checksum(&optimized)?;
+        optimized
+    };
+    Ok(optimized.display().to_string())
+}
+
+fn has_wasm_opt() -> bool {
+    Command::new("wasm-opt")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// same flags as the rust-optimizer, output goes into ./artifacts
+fn run_wasm_opt(input: &Path) -> Result<PathBuf> {
+    let file_name = input
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("{} is not a file", input.display()))?;
+    let artifacts = PathBuf::from("artifacts");
+    std::fs::create_dir_all(&artifacts)?;
+    let output = artifacts.join(file_name);
+    let tmp_output = artifacts.join(format!("{}.tmp", file_name.to_string_lossy()));
+
+    log::info!("wasm-opt {} -> {}", input.display(), output.display());
+    let status = Command::new("wasm-opt")
+        .arg("-Os")
+        .arg(input)
+        .arg("-o")
+        .arg(&tmp_output)
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("wasm-opt failed on {}", input.display());
+    }
+    std::fs::rename(&tmp_output, &output)?;
+    log::info!(
+        "optimized {} bytes down to {} bytes",
+        std::fs::metadata(input)?.len(),
+        std::fs::metadata(&output)?.len()
+    );
+    write_checksum(&artifacts, &output)?;
+    Ok(output)
+}
+
+fn run_rust_optimizer() -> Result<PathBuf> {
+    let crate_info = artifact::current_crate()?;
+    let root = &crate_info.workspace_root;
+    let cache_name = format!(
+        "{}_cache",
+        root.file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| crate_info.name.clone())
+    );
+    log::info!("running {} on {}", OPTIMIZER_IMAGE, root.display());
+    let status = Command::new("docker")
+        .arg("run")
+        .arg("--rm")
+        .arg("-v")
+        .arg(format!("{}:/code", root.display()))
+        .arg("--mount")
+        .arg(format!(
+            "type=volume,source={},target=/code/target",
+            cache_name
+        ))
+        .arg("--mount")
+        .arg("type=volume,source=registry_cache,target=/usr/local/cargo/registry")
+        .arg(OPTIMIZER_IMAGE)
+        .status()
+        .map_err(|e| anyhow::anyhow!("unable to run docker ({}). install wasm-opt or docker", e))?;
+    if !status.success() {
+        anyhow::bail!("{} failed", OPTIMIZER_IMAGE);
+    }
+    let output = root
+        .join("artifacts")
+        .join(format!("{}.wasm", crate_info.artifact_name));
+    if !output.is_file() {
+        anyhow::bail!("{} did not produce {}", OPTIMIZER_IMAGE, output.display());
+    }
+    Ok(output)
+}
+
+fn sha256_file(file: &Path) -> Result<String> {
+    let contents = std::fs::read(file)?;
+    if !contents.starts_with(WASM_MAGIC) {
+        anyhow::bail!("{} is not a wasm file", file.display());
+    }
+    let mut sha = Sha256::new();
+    sha.input(&contents);
+    Ok(sha.result_str())
+}
+
+/// record the hash the same way the rust-optimizer does in checksums.txt
+fn write_checksum(artifacts: &Path, file: &Path) -> Result<()> {
+    let checksum_file = artifacts.join("checksums.txt");
+    let file_name = file
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let existing = std::fs::read_to_string(&checksum_file).unwrap_or_default();
+    let mut lines = existing
+        .lines()
+        .filter(|line| !line.ends_with(&format!("  {}", file_name)))
+        .map(String::from)
+        .collect::<Vec<_>>();
+    lines.push(format!("{}  {}", sha256_file(file)?, file_name));
+    std::fs::write(checksum_file, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
+/// make sure the file on disk is what the rust-optimizer image says it produced in checksums.txt
+fn verify_checksum(file: &Path) -> Result<()> {
+    let hash = sha256_file(file)?;
+    let file_name = file
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let checksum_file = file
+        .parent()
+        .map(|p| p.join("checksums.txt"))
+        .unwrap_or_else(|| PathBuf::from("checksums.txt"));
+    let checksums = std::fs::read_to_string(&checksum_file)
+        .map_err(|e| anyhow::anyhow!("unable to read {}: {}", checksum_file.display(), e))?;
+    let expected = checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some(hash), Some(name)) if name == file_name => Some(hash.to_string()),
+            _ => None,
+        }
+    });
+    match expected {
+        Some(expected) if expected == hash => {
+            log::info!("{} checksum {}", file.display(), hash);
+            Ok(())
+        }
+        Some(expected) => anyhow::bail!(
+            "checksum mismatch on {}. expected {} got {}",
+            file.display(),
+            expected,
+            hash
+        ),
+        None => anyhow::bail!("{} is not listed in {}", file_name, checksum_file.display()),
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use clap::{ArgMatches, Subcommand};
+use terra_rust_api::core_types::Coin;
+use terra_rust_api::messages::distribution::MsgWithdrawDelegationReward;
+use terra_rust_api::messages::staking::{MsgBeginRedelegate, MsgDelegate, MsgUndelegate};
+use terra_rust_api::shared_secp256k1;
+use terra_rust_api::Terra;
+use terra_rust_cli::cli_helpers;
+
+/// delegate, undelegate, redelegate & withdraw rewards. validators are operator addresses or monikers
+#[derive(Subcommand)]
+pub enum StakeCommand {
+    /// delegate an amount, like 1000000uluna, to a validator
+    Delegate { validator: String, amount: String },
+    /// start unbonding an amount from a validator
+    Undelegate { validator: String, amount: String },
+    /// move an amount from one validator to another, without unbonding
+    Redelegate {
+        from: String,
+        to: String,
+        amount: String,
+    },
+    /// withdraw the rewards from a validator
+    Rewards {
+        #[clap(required_unless_present = "all-rewards")]
+        validator: Option<String>,
+        /// from every validator delegated to, in one transaction
+        #[clap(long)]
+        all_rewards: bool,
+    },
+}
+
+/// the operator address of a validator given by address or moniker
+async fn validator_address(terra: &Terra, validator: &str) -> Result<String> {
+    if validator.starts_with("terravaloper1") {
+        return Ok(validator.to_string());
+    }
+    match terra.staking().validator_by_moniker(validator).await? {
+        Some(v) => Ok(v.operator_address),
+        None => anyhow::bail!("no validator with moniker {}", validator),
+    }
+}
+
+fn coin(amount: &str) -> Result<Coin> {
+    Coin::parse(amount)?.ok_or_else(|| anyhow::anyhow!("can't parse {} into a coin", amount))
+}
+
+pub async fn run(matches: &ArgMatches, stake: &ArgMatches, memo: Option<String>) -> Result<()> {
+    let terra = cli_helpers::lcd_from_args(matches).await?;
+    let secp = shared_secp256k1();
+    let private = cli_helpers::get_private_key(secp, matches)?;
+    let delegator = private.public_key(secp).account()?;
+    let messages = match stake.subcommand() {
+        Some(("delegate", delegate)) => {
+            let validator =
+                validator_address(&terra, cli_helpers::get_arg_value(delegate, "validator")?)
+                    .await?;
+            let amount = coin(cli_helpers::get_arg_value(delegate, "amount")?)?;
+            vec![MsgDelegate::create(delegator, validator, amount)?]
+        }
+        Some(("undelegate", undelegate)) => {
+            let validator =
+                validator_address(&terra, cli_helpers::get_arg_value(undelegate, "validator")?)
+                    .await?;
+            let amount = coin(cli_helpers::get_arg_value(undelegate, "amount")?)?;
+            vec![MsgUndelegate::create(delegator, validator, amount)?]
+        }
+        Some(("redelegate", redelegate)) => {
+            let from =
+                validator_address(&terra, cli_helpers::get_arg_value(redelegate, "from")?).await?;
+            let to =
+                validator_address(&terra, cli_helpers::get_arg_value(redelegate, "to")?).await?;
+            let amount = coin(cli_helpers::get_arg_value(redelegate, "amount")?)?;
+            vec![MsgBeginRedelegate::create(delegator, to, from, amount)?]
+        }
+        Some(("rewards", rewards)) => {
+            let validators = if rewards.is_present("all-rewards") {
+                terra
+                    .auth()
+                    .validator_delegations(&delegator, None)
+                    .await?
+                    .result
+                    .into_iter()
+                    .map(|d| d.delegation.validator_address)
+                    .collect::<Vec<_>>()
+            } else {
+                vec![
+                    validator_address(&terra, cli_helpers::get_arg_value(rewards, "validator")?)
+                        .await?,
+                ]
+            };
+            if validators.is_empty() {
+                anyhow::bail!("{} has no delegations", delegator);
+            }
+            validators
+                .into_iter()
+                .map(|validator| MsgWithdrawDelegationReward::create(&delegator, validator))
+                .collect::<anyhow::Result<Vec<_>>>()?
+        }
+        _ => {
+            println!("try stake --help");
+            return Ok(());
+        }
+    };
+    let resp = terra
+        .submit_transaction_sync(secp, &private, messages, memo)
+        .await?;
+    println!("{}", resp.txhash);
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use rust_decimal::Decimal;
+use terra_rust_api::market_types::{SweepOptions, SweepReportLine};
+use terra_rust_api::shared_secp256k1;
+use terra_rust_cli::cli_helpers;
+
+fn options(sweep: &ArgMatches) -> Result<SweepOptions> {
+    let values = |name: &str| {
+        sweep
+            .values_of(name)
+            .map(|v| v.map(String::from).collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+    let mut options = cli_helpers::sweep_thresholds(
+        SweepOptions::create(cli_helpers::get_arg_value(sweep, "threshold")?.parse::<Decimal>()?),
+        &values("min"),
+    )?;
+    options.allow = values("allow");
+    options.deny = values("deny");
+    if let Some(max_messages) = sweep.value_of("max-messages") {
+        options.max_messages = Some(max_messages.parse()?);
+    }
+    Ok(options)
+}
+
+fn print_report(report: &[SweepReportLine]) {
+    println!(
+        "{:<12} {:>20} {:>20} {:>8} {:>12} swap",
+        "denom", "balance", "value", "spread", "threshold"
+    );
+    for line in report {
+        let swap = match (line.swapped, line.passes) {
+            (true, _) => "yes",
+            (false, true) => "over max",
+            (false, false) => "no",
+        };
+        println!(
+            "{:<12} {:>20} {:>20} {:>8} {:>12} {}",
+            line.balance.denom,
+            line.balance.amount,
+            line.value.amount,
+            line.spread.round_dp(4),
+            line.threshold,
+            swap
+        );
+    }
+}
+
+pub async fn run(matches: &ArgMatches, sweep: &ArgMatches, memo: Option<String>) -> Result<()> {
+    let terra = cli_helpers::lcd_from_args(matches).await?;
+    let secp = shared_secp256k1();
+    let private = cli_helpers::get_private_key(secp, matches)?;
+    let from = private.public_key(secp).account()?;
+    let to_coin = cli_helpers::get_arg_value(sweep, "to-coin")?;
+    let options = options(sweep)?;
+    if sweep.is_present("dry-run") {
+        let report = terra
+            .market()
+            .sweep_report(&from, to_coin, &options, None)
+            .await?;
+        print_report(&report);
+        return Ok(());
+    }
+    let messages = terra
+        .market()
+        .generate_sweep_messages_with(&from, to_coin, &options, None)
+        .await?;
+    if messages.is_empty() {
+        println!("No coins match your threshold");
+        return Ok(());
+    }
+    let resp = terra
+        .submit_transaction_sync(secp, &private, messages, memo)
+        .await?;
+    println!("{}", resp.txhash);
+    Ok(())
+}
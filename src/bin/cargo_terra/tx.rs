@@ -0,0 +1,274 @@
+use anyhow::Result;
+use clap::{ArgMatches, Subcommand};
+use serde::de::DeserializeOwned;
+use terra_rust_api::client::tx_types::GasSummary;
+use terra_rust_api::core_types::{Coin, SignedTx, StdSignMsg};
+use terra_rust_api::shared_secp256k1;
+use terra_rust_cli::cli_helpers;
+
+/// offline signing. sign on one machine, broadcast from another. and looking at what happened
+#[derive(Subcommand)]
+pub enum TxCommand {
+    /// sign an unsigned StdSignMsg (or add another signature to an already signed one). no network needed
+    Sign {
+        /// the unsigned (or signed) transaction json
+        file: String,
+        /// where to write the signed transaction. defaults to stdout
+        #[clap(long)]
+        out: Option<String>,
+    },
+    /// broadcast a signed transaction
+    Broadcast {
+        /// the signed transaction json
+        file: String,
+    },
+    /// fetch a transaction, decoding its contract messages and listing its events
+    Show {
+        hash: String,
+        /// print the transaction as JSON, with the contract messages decoded
+        #[clap(long)]
+        json: bool,
+    },
+    /// what transactions cost: gas, fees and the stability tax, each and in total
+    Gas {
+        #[clap(required = true)]
+        hashes: Vec<String>,
+        /// print the reports and the total as JSON
+        #[clap(long)]
+        json: bool,
+    },
+}
+
+fn coins(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|c| format!("{}{}", c.amount, c.denom))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// fields holding base64 encoded JSON contract messages
+const PAYLOAD_FIELDS: [&str; 5] = ["execute_msg", "init_msg", "migrate_msg", "query_msg", "msg"];
+
+/// replace base64 contract messages with the JSON they encode
+fn decode_payloads(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if PAYLOAD_FIELDS.contains(&key.as_str()) {
+                    if let Some(decoded) = value
+                        .as_str()
+                        .and_then(|s| base64::decode(s).ok())
+                        .and_then(|b| serde_json::from_slice::<serde_json::Value>(&b).ok())
+                    {
+                        *value = decoded;
+                        continue;
+                    }
+                }
+                decode_payloads(value);
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(decode_payloads),
+        _ => {}
+    }
+}
+
+/// print rows as columns, padded to the widest cell
+fn print_table(header: [&str; 4], rows: &[[String; 4]]) {
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let line = |cells: [&str; 4]| {
+        let padded = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>();
+        println!("  {}", padded.join("  ").trim_end());
+    };
+    line(header);
+    for row in rows {
+        line([&row[0], &row[1], &row[2], &row[3]]);
+    }
+}
+
+/// a file is either a bare sign message, or one that has been through `tx sign` already
+pub fn read_tx<S: DeserializeOwned>(file: &str) -> Result<SignedTx<S>> {
+    let json = cli_helpers::get_json_block(file)?;
+    if json.get("sign_msg").is_some() {
+        Ok(serde_json::from_value(json)?)
+    } else {
+        let sign_msg: StdSignMsg = serde_json::from_value(json)?;
+        Ok(SignedTx {
+            sign_msg,
+            signatures: vec![],
+        })
+    }
+}
+
+pub async fn run(matches: &ArgMatches, tx: &ArgMatches) -> Result<()> {
+    match tx.subcommand() {
+        Some(("sign", sign)) => {
+            let secp = shared_secp256k1();
+            let private = cli_helpers::get_private_key(secp, matches)?;
+            let mut signed: SignedTx = read_tx(cli_helpers::get_arg_value(sign, "file")?)?;
+            let signature = signed.sign_msg.sign(secp, &private)?;
+            signed.signatures.push(signature);
+            let js = serde_json::to_string_pretty(&signed)?;
+            match sign.value_of("out") {
+                Some(out) => {
+                    std::fs::write(out, js)?;
+                    println!(
+                        "{} signature(s). written to {}",
+                        signed.signatures.len(),
+                        out
+                    );
+                }
+                None => println!("{}", js),
+            }
+        }
+        Some(("broadcast", broadcast)) => {
+            // multisig signatures look different, so pass them through as is
+            let signed: SignedTx<serde_json::Value> =
+                read_tx(cli_helpers::get_arg_value(broadcast, "file")?)?;
+            if signed.signatures.is_empty() {
+                anyhow::bail!("transaction is not signed. run `cargo terra tx sign` first");
+            }
+            let terra = cli_helpers::lcd_no_tx_from_args(matches)?;
+            if signed.sign_msg.chain_id != terra.chain_id {
+                anyhow::bail!(
+                    "transaction was signed for {} but connected to {}",
+                    signed.sign_msg.chain_id,
+                    terra.chain_id
+                );
+            }
+            let resp = terra
+                .tx()
+                .broadcast_sync(&signed.sign_msg, &signed.signatures)
+                .await?;
+            if !resp.is_success() {
+                anyhow::bail!("broadcast failed: {}", resp.raw_log);
+            }
+            println!("{}", resp.txhash);
+        }
+        Some(("gas", gas)) => {
+            let hashes = gas
+                .values_of("hashes")
+                .into_iter()
+                .flatten()
+                .map(String::from)
+                .collect::<Vec<_>>();
+            let terra = cli_helpers::lcd_no_tx_from_args(matches)?;
+            let reports = terra.tx().gas_reports(&hashes).await?;
+            let summary = GasSummary::from_reports(&reports);
+            if gas.is_present("json") {
+                let json = serde_json::json!({ "txs": reports, "total": summary });
+                println!("{}", serde_json::to_string_pretty(&json)?);
+                return Ok(());
+            }
+            let mut rows = reports
+                .iter()
+                .map(|report| {
+                    [
+                        report.txhash.clone(),
+                        format!("{}/{}", report.gas_used, report.gas_wanted),
+                        coins(&report.fee),
+                        coins(&report.tax),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            rows.push([
+                format!("total ({} failed)", summary.failed),
+                format!("{}/{}", summary.gas_used, summary.gas_wanted),
+                coins(&summary.fee),
+                coins(&summary.tax),
+            ]);
+            print_table(["tx", "gas used/wanted", "fee", "tax"], &rows);
+        }
+        Some(("show", show)) => {
+            let hash = cli_helpers::get_arg_value(show, "hash")?;
+            let terra = cli_helpers::lcd_no_tx_from_args(matches)?;
+            let tx = terra.tx().get_v1(hash).await?;
+            let mut messages = serde_json::Value::Array(tx.tx.body.messages.clone());
+            decode_payloads(&mut messages);
+            let response = &tx.tx_response;
+            if show.is_present("json") {
+                let mut json = serde_json::to_value(&tx)?;
+                json["tx"]["body"]["messages"] = messages;
+                decode_payloads(&mut json["tx_response"]["tx"]);
+                println!("{}", serde_json::to_string_pretty(&json)?);
+                return Ok(());
+            }
+            println!("Hash:   {}", response.txhash);
+            println!("Height: {} ({})", response.height, response.timestamp);
+            if response.code == 0 {
+                println!("Result: success");
+            } else {
+                println!(
+                    "Result: failed with code {} ({}): {}",
+                    response.code, response.codespace, response.raw_log
+                );
+            }
+            println!(
+                "Gas:    {} used of {} wanted",
+                response.gas_used, response.gas_wanted
+            );
+            let fee = &tx.tx.auth_info["fee"]["amount"];
+            if let Some(coins) = fee.as_array() {
+                let coins = coins
+                    .iter()
+                    .map(|c| {
+                        format!(
+                            "{}{}",
+                            c["amount"].as_str().unwrap_or_default(),
+                            c["denom"].as_str().unwrap_or_default()
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                println!("Fee:    {}", coins.join(","));
+            }
+            if !tx.tx.body.memo.is_empty() {
+                println!("Memo:   {}", tx.tx.body.memo);
+            }
+            println!("Messages:");
+            for (i, message) in messages.as_array().into_iter().flatten().enumerate() {
+                println!(
+                    "  #{} {}",
+                    i,
+                    message["@type"].as_str().unwrap_or("(unknown type)")
+                );
+                for line in serde_json::to_string_pretty(message)?.lines() {
+                    println!("    {}", line);
+                }
+            }
+            let rows = response
+                .logs
+                .iter()
+                .flatten()
+                .flat_map(|log| {
+                    let msg = log.msg_index.unwrap_or_default().to_string();
+                    log.events.iter().flat_map(move |event| {
+                        let msg = msg.clone();
+                        event.attributes.iter().map(move |attribute| {
+                            [
+                                msg.clone(),
+                                event.s_type.clone(),
+                                attribute.key.clone(),
+                                attribute.value.clone().unwrap_or_default(),
+                            ]
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !rows.is_empty() {
+                println!("Events:");
+                print_table(["msg", "event", "key", "value"], &rows);
+            }
+        }
+        _ => println!("try tx --help"),
+    }
+    Ok(())
+}
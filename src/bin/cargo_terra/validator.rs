@@ -0,0 +1,124 @@
+use anyhow::Result;
+use clap::{ArgMatches, Subcommand};
+use rust_decimal::Decimal;
+use terra_rust_api::messages::distribution::MsgWithdrawValidatorCommission;
+use terra_rust_api::messages::slashing::MsgUnjail;
+use terra_rust_api::messages::staking::{MsgEditValidator, ValidatorDescription};
+use terra_rust_api::shared_secp256k1;
+use terra_rust_api::Terra;
+use terra_rust_cli::cli_helpers;
+
+/// operate the validator whose operator key signs
+#[derive(Subcommand)]
+pub enum ValidatorCommand {
+    /// get the validator out of jail
+    Unjail,
+    /// change the description, commission rate or minimum self delegation. fields not given don't change
+    Edit {
+        #[clap(long)]
+        moniker: Option<String>,
+        /// the keybase.io PGP identity string
+        #[clap(long)]
+        identity: Option<String>,
+        #[clap(long)]
+        website: Option<String>,
+        /// usually an email
+        #[clap(long)]
+        security_contact: Option<String>,
+        #[clap(long)]
+        details: Option<String>,
+        /// commission rate. 1.00 = 100%
+        #[clap(long)]
+        rate: Option<Decimal>,
+        #[clap(long)]
+        min_self_delegation: Option<Decimal>,
+    },
+    /// withdraw the commission earned
+    WithdrawCommission,
+    /// staking, signing & oracle status
+    Status,
+}
+
+fn bond_status(status: u16) -> &'static str {
+    match status {
+        1 => "unbonded",
+        2 => "unbonding",
+        3 => "bonded",
+        _ => "unknown",
+    }
+}
+
+async fn status(terra: &Terra, operator: &str) -> Result<()> {
+    let validator = terra.staking().validator(operator).await?.result;
+    println!("{} {}", validator.description.moniker, operator);
+    println!(
+        "status:     {}{}",
+        bond_status(validator.status),
+        if validator.jailed.unwrap_or_default() {
+            ", jailed"
+        } else {
+            ""
+        }
+    );
+    println!("tokens:     {}", validator.tokens);
+    println!(
+        "commission: {} (max {})",
+        validator.commission.commission_rates.rate, validator.commission.commission_rates.max_rate
+    );
+    let slashing = terra.slashing();
+    let signing = slashing.signing_info(&validator.consensus_pubkey).await?;
+    let window = slashing.parameters().await?.signed_blocks_window;
+    println!(
+        "missed:     {} of the last {} blocks",
+        signing.missed_blocks_counter, window
+    );
+    if signing.tombstoned {
+        println!("tombstoned");
+    } else if validator.jailed.unwrap_or_default() {
+        println!("jailed until {}", signing.jailed_until);
+    }
+    let voters = terra.oracle().voters(operator);
+    println!("oracle misses: {}", voters.miss_count(None).await?.result);
+    println!("oracle feeder: {}", voters.feeder(None).await?.result);
+    Ok(())
+}
+
+pub async fn run(matches: &ArgMatches, validator: &ArgMatches, memo: Option<String>) -> Result<()> {
+    let terra = cli_helpers::lcd_from_args(matches).await?;
+    let secp = shared_secp256k1();
+    let private = cli_helpers::get_private_key(secp, matches)?;
+    let operator = private.public_key(secp).operator_address()?;
+    let message = match validator.subcommand() {
+        Some(("unjail", _)) => MsgUnjail::create(operator)?,
+        Some(("edit", edit)) => {
+            let field = |name: &str| edit.value_of(name).map(String::from);
+            let decimal = |name: &str| -> Result<Option<Decimal>> {
+                Ok(edit.value_of(name).map(str::parse::<Decimal>).transpose()?)
+            };
+            let description = ValidatorDescription::create_edit(
+                field("details"),
+                field("identity"),
+                field("moniker"),
+                field("security-contact"),
+                field("website"),
+            );
+            MsgEditValidator::create(
+                description,
+                operator,
+                decimal("rate")?,
+                decimal("min-self-delegation")?,
+            )?
+        }
+        Some(("withdraw-commission", _)) => MsgWithdrawValidatorCommission::create(operator)?,
+        Some(("status", _)) => return status(&terra, &operator).await,
+        _ => {
+            println!("try validator --help");
+            return Ok(());
+        }
+    };
+    let resp = terra
+        .submit_transaction_sync(secp, &private, vec![message], memo)
+        .await?;
+    println!("{}", resp.txhash);
+    Ok(())
+}
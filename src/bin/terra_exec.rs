@@ -1,8 +1,9 @@
 use anyhow::Result;
 use clap::{Arg, ArgMatches};
 use dotenv::dotenv;
-use secp256k1::Secp256k1;
 use terra_rust_api::core_types::Coin;
+use terra_rust_api::redact::RedactingLogger;
+use terra_rust_api::shared_secp256k1;
 use terra_rust_api::{Message, MsgExecuteContract};
 use terra_rust_cli::cli_helpers;
 //use tokio::runtime::Handle;
@@ -49,10 +50,10 @@ pub async fn run_it(cli: &ArgMatches) -> Result<()> {
 
     let json: serde_json::Value = cli_helpers::get_json_block(json_str)?;
 
-    let secp = Secp256k1::new();
+    let secp = shared_secp256k1();
 
-    let from_key = cli_helpers::get_private_key(&secp, cli)?;
-    let from_public_key = from_key.public_key(&secp);
+    let from_key = cli_helpers::get_private_key(secp, cli)?;
+    let from_public_key = from_key.public_key(secp);
 
     let coins = if let Some(coins) = coins_str {
         Coin::parse_coins(coins)?
@@ -70,7 +71,7 @@ pub async fn run_it(cli: &ArgMatches) -> Result<()> {
 
     let resp = terra
         .submit_transaction_sync(
-            &secp,
+            secp,
             &from_key,
             messages,
             Some(format!(
@@ -99,7 +100,10 @@ pub async fn run_it(cli: &ArgMatches) -> Result<()> {
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    env_logger::init();
+    let logger = env_logger::Builder::from_default_env().build();
+    let level = logger.filter();
+    // seed phrases are passed on the command line. keep them out of the logs
+    RedactingLogger::init(Box::new(logger), level).expect("logger already set");
 
     if let Err(ref err) = run().await {
         eprintln!("{}", err);
@@ -1,6 +1,7 @@
 use dotenv::dotenv;
 
 use clap::Arg;
+use terra_rust_api::redact::RedactingLogger;
 use terra_rust_cli::cli_helpers;
 
 /// VERSION number of package
@@ -38,7 +39,10 @@ async fn run() -> anyhow::Result<()> {
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    env_logger::init();
+    let logger = env_logger::Builder::from_default_env().build();
+    let level = logger.filter();
+    // seed phrases are passed on the command line. keep them out of the logs
+    RedactingLogger::init(Box::new(logger), level).expect("logger already set");
 
     if let Err(ref err) = run().await {
         log::error!("{}", err);
@@ -1,10 +1,11 @@
 use anyhow::Result;
-use secp256k1::Secp256k1;
 use std::path::Path;
+use terra_rust_api::shared_secp256k1;
 use terra_rust_api::Terra;
 
 use crate::{NAME, VERSION};
 use clap::{Parser, Subcommand};
+use terra_rust_api::client::wasm_types::{InstantiateResult, MigrateResult, StoreResult};
 use terra_rust_api::core_types::Coin;
 use terra_rust_api::messages::wasm::{MsgInstantiateContract, MsgMigrateContract};
 use terra_rust_wallet::Wallet;
@@ -90,7 +91,7 @@ pub enum CodeEnum {
 }
 impl CodeCommand {
     pub async fn parse(self, terra: &Terra, wallet: &Wallet<'_>, seed: Option<&str>) -> Result<()> {
-        let secp = Secp256k1::new();
+        let secp = shared_secp256k1();
         let memo = Some(format!(
             "PFC-{}/{}",
             NAME.unwrap_or("TERRARUST"),
@@ -102,21 +103,17 @@ impl CodeCommand {
                 wasm,
                 retries,
             } => {
-                let from_key = wallet.get_private_key(&secp, &sender, seed)?;
+                let from_key = wallet.get_private_key(shared_secp256k1(), &sender, seed)?;
                 let hash = terra
                     .wasm()
-                    .store(&secp, &from_key, &wasm, memo)
+                    .store(secp, &from_key, &wasm, memo)
                     .await?
                     .txhash;
-                let code_id = get_attribute_tx(
-                    terra,
-                    &hash,
-                    retries,
-                    tokio::time::Duration::from_secs(3),
-                    "store_code",
-                    "code_id",
-                )
-                .await?;
+                let tx = terra
+                    .tx()
+                    .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(3))
+                    .await?;
+                let code_id = StoreResult::from_tx(&tx)?.code_id;
 
                 println!("Code Id: {}", code_id);
             }
@@ -128,17 +125,17 @@ impl CodeCommand {
                 admin,
                 retries,
             } => {
-                let from_key = wallet.get_private_key(&secp, &sender, seed)?;
+                let from_key = wallet.get_private_key(shared_secp256k1(), &sender, seed)?;
                 let json = Path::new(&json_file);
 
                 let admin_key = if admin.starts_with("terra1") {
                     Some(admin)
                 } else if admin == "same" {
-                    Some(from_key.public_key(&secp).account()?)
+                    Some(from_key.public_key(secp).account()?)
                 } else if admin == "none" {
                     todo!("Admin of none is currently not supported")
                 } else {
-                    let admin_key = wallet.get_public_key(&secp, &admin, seed)?;
+                    let admin_key = wallet.get_public_key(shared_secp256k1(), &admin, seed)?;
                     let admin_account = admin_key.account()?;
                     Some(admin_account)
                 };
@@ -152,44 +149,34 @@ impl CodeCommand {
                 } else {
                     let hash = terra
                         .wasm()
-                        .store(&secp, &from_key, &wasm, memo.clone())
+                        .store(secp, &from_key, &wasm, memo.clone())
                         .await?
                         .txhash;
-                    let code_id = get_attribute_tx(
-                        terra,
-                        &hash,
-                        retries,
-                        tokio::time::Duration::from_secs(3),
-                        "store_code",
-                        "code_id",
-                    )
-                    .await?;
-
-                    code_id.parse::<u64>()?
+                    let tx = terra
+                        .tx()
+                        .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(3))
+                        .await?;
+                    StoreResult::from_tx(&tx)?.code_id
                 };
-                let contents = MsgInstantiateContract::replace_parameters(
-                    &from_key.public_key(&secp).account()?,
+                let contents = MsgInstantiateContract::fill_parameters(
+                    &from_key.public_key(secp).account()?,
                     admin_key.clone(),
                     code_id,
                     &std::fs::read_to_string(json)?,
-                );
+                )?;
 
                 let hash = terra
                     .wasm()
                     .instantiate(
-                        &secp, &from_key, code_id, contents, coin_vec, admin_key, memo,
+                        secp, &from_key, code_id, contents, coin_vec, admin_key, memo,
                     )
                     .await?
                     .txhash;
-                let contract = get_attribute_tx(
-                    terra,
-                    &hash,
-                    retries,
-                    tokio::time::Duration::from_secs(3),
-                    "instantiate_contract",
-                    "contract_address",
-                )
-                .await?;
+                let tx = terra
+                    .tx()
+                    .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(3))
+                    .await?;
+                let contract = InstantiateResult::from_tx(&tx)?.contract_address;
                 println!("Contract: {}", contract);
             }
             CodeEnum::Migrate {
@@ -199,43 +186,38 @@ impl CodeCommand {
                 json_file,
                 retries,
             } => {
-                let from_key = wallet.get_private_key(&secp, &sender, seed)?;
+                let from_key = wallet.get_private_key(shared_secp256k1(), &sender, seed)?;
 
                 let new_code_id = if let Ok(code_id) = wasm.parse::<u64>() {
                     code_id
                 } else {
                     let hash = terra
                         .wasm()
-                        .store(&secp, &from_key, &wasm, memo.clone())
+                        .store(secp, &from_key, &wasm, memo.clone())
                         .await?
                         .txhash;
-                    let code_id = get_attribute_tx(
-                        terra,
-                        &hash,
-                        retries,
-                        tokio::time::Duration::from_secs(3),
-                        "store_code",
-                        "code_id",
-                    )
-                    .await?;
-                    code_id.parse::<u64>()?
+                    let tx = terra
+                        .tx()
+                        .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(3))
+                        .await?;
+                    StoreResult::from_tx(&tx)?.code_id
                 };
                 let contents = if let Some(json_filename) = json_file {
                     let json = Path::new(&json_filename);
 
-                    Some(MsgMigrateContract::replace_parameters(
-                        &from_key.public_key(&secp).account()?,
+                    Some(MsgMigrateContract::fill_parameters(
+                        &from_key.public_key(secp).account()?,
                         &contract,
                         new_code_id,
                         &std::fs::read_to_string(json)?,
-                    ))
+                    )?)
                 } else {
                     None
                 };
 
                 let hash = terra
                     .wasm()
-                    .migrate(&secp, &from_key, &contract, new_code_id, contents, memo)
+                    .migrate(secp, &from_key, &contract, new_code_id, contents, memo)
                     .await?
                     .txhash;
 
@@ -244,49 +226,13 @@ impl CodeCommand {
                     .get_and_wait_v1(&hash, retries, tokio::time::Duration::from_secs(3))
                     .await?;
 
-                let codes = tx
-                    .tx_response
-                    .get_attribute_from_logs("migrate_contract", "contract_address");
-                let contract = if let Some(code) = codes.first() {
-                    code.1.clone()
-                } else {
-                    panic!(
-                        "{}/{} not present in TX log",
-                        "migrate_contract", "contract_address"
-                    );
-                };
-
-                let codes = tx
-                    .tx_response
-                    .get_attribute_from_logs("migrate_contract", "code_id");
-                let code_id = if let Some(code) = codes.first() {
-                    code.1.clone()
-                } else {
-                    panic!("{}/{} not present in TX log", "migrate_contract", "code_id");
-                };
-
-                println!("Contract: {} Migrated to {}", contract, code_id);
+                let migrated = MigrateResult::from_tx(&tx)?;
+                println!(
+                    "Contract: {} Migrated to {}",
+                    migrated.contract_address, migrated.code_id
+                );
             }
         }
         Ok(())
     }
 }
-
-async fn get_attribute_tx(
-    terra: &Terra,
-    hash: &str,
-    retries: usize,
-    sleep: tokio::time::Duration,
-    event_type: &str,
-    attribute_key: &str,
-) -> Result<String> {
-    let tx = terra.tx().get_and_wait_v1(hash, retries, sleep).await?;
-    let codes = tx
-        .tx_response
-        .get_attribute_from_logs(event_type, attribute_key);
-    if let Some(code) = codes.first() {
-        Ok(code.1.clone())
-    } else {
-        panic!("{}/{} not present in TX log", event_type, attribute_key)
-    }
-}
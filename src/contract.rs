@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use serde_json::Value;
+use terra_rust_api::core_types::Pagination;
 use terra_rust_api::Terra;
 
 #[derive(Subcommand)]
@@ -10,6 +11,25 @@ enum ContractEnum {
         #[clap(name = "codeId", help = "code id you want to obtain info about")]
         code_id: u64,
     },
+    #[clap(name = "code-list", about = "List the stored codes")]
+    CodeList {
+        #[clap(long, help = "how many to list", default_value = "100")]
+        limit: u64,
+        #[clap(long, help = "the next_key from the previous page")]
+        key: Option<String>,
+    },
+    #[clap(
+        name = "contracts",
+        about = "List the contracts instantiated from a code ID"
+    )]
+    Contracts {
+        #[clap(name = "codeId", help = "code id the contracts were instantiated from")]
+        code_id: u64,
+        #[clap(long, help = "how many to list", default_value = "100")]
+        limit: u64,
+        #[clap(long, help = "the next_key from the previous page")]
+        key: Option<String>,
+    },
     #[clap(name = "info", about = "Get code info based on the contract address")]
     Info {
         #[clap(
@@ -48,7 +68,31 @@ impl ContractCommand {
     pub async fn parse(self, terra: &Terra) -> Result<()> {
         match self.command {
             ContractEnum::Codes { code_id } => {
-                let code_result = terra.wasm().codes(code_id, None).await?;
+                let code_result = terra.wasm().code(code_id, None).await?;
+                println!("{}", serde_json::to_string_pretty(&code_result)?);
+                Ok(())
+            }
+            ContractEnum::CodeList { limit, key } => {
+                let pagination = Pagination {
+                    key,
+                    limit: Some(limit),
+                    ..Default::default()
+                };
+                let code_result = terra.wasm().code_list(&pagination).await?;
+                println!("{}", serde_json::to_string_pretty(&code_result)?);
+                Ok(())
+            }
+            ContractEnum::Contracts {
+                code_id,
+                limit,
+                key,
+            } => {
+                let pagination = Pagination {
+                    key,
+                    limit: Some(limit),
+                    ..Default::default()
+                };
+                let code_result = terra.wasm().contracts_by_code(code_id, &pagination).await?;
                 println!("{}", serde_json::to_string_pretty(&code_result)?);
                 Ok(())
             }
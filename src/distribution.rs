@@ -3,10 +3,10 @@ use clap::{Parser, Subcommand};
 use terra_rust_api::Terra;
 
 use crate::{NAME, VERSION};
-use secp256k1::Secp256k1;
 use terra_rust_api::messages::distribution::{
     MsgWithdrawDelegationReward, MsgWithdrawValidatorCommission,
 };
+use terra_rust_api::shared_secp256k1;
 
 use terra_rust_api::messages::Message;
 use terra_rust_wallet::Wallet;
@@ -49,15 +49,15 @@ pub struct DistributionCommand {
 }
 impl DistributionCommand {
     pub async fn parse(self, terra: &Terra, wallet: &Wallet<'_>, seed: Option<&str>) -> Result<()> {
-        let secp = Secp256k1::new();
+        let secp = shared_secp256k1();
         match self.command {
             DistributionEnum::Reward {
                 delegator,
                 validator,
             } => {
                 log::info!("Delegator {}", &delegator);
-                let delegator_key = wallet.get_private_key(&secp, &delegator, seed)?;
-                let delegator_account = delegator_key.public_key(&secp).account()?;
+                let delegator_key = wallet.get_private_key(shared_secp256k1(), &delegator, seed)?;
+                let delegator_account = delegator_key.public_key(secp).account()?;
 
                 match validator {
                     Some(v) => {
@@ -66,7 +66,7 @@ impl DistributionCommand {
                         let messages: Vec<Message> = vec![msg];
                         let resp = terra
                             .submit_transaction_sync(
-                                &secp,
+                                secp,
                                 &delegator_key,
                                 messages,
                                 Some(format!(
@@ -89,14 +89,14 @@ impl DistributionCommand {
                 validator,
             } => {
                 log::info!("Delegator {}", &delegator);
-                let delegator_key = wallet.get_private_key(&secp, &delegator, seed)?;
+                let delegator_key = wallet.get_private_key(shared_secp256k1(), &delegator, seed)?;
 
                 log::info!("Validator {}", &validator);
                 let msg = MsgWithdrawValidatorCommission::create(validator)?;
                 let messages: Vec<Message> = vec![msg];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &delegator_key,
                         messages,
                         Some(format!(
@@ -116,18 +116,18 @@ impl DistributionCommand {
                 //validator,
             } => {
                 log::info!("Delegator {}", &delegator);
-                let delegator_key = wallet.get_private_key(&secp, &delegator, seed)?;
-                let delegator_account = delegator_key.public_key(&secp).account()?;
-                let validator = delegator_key.public_key(&secp).operator_address()?;
+                let delegator_key = wallet.get_private_key(shared_secp256k1(), &delegator, seed)?;
+                let delegator_account = delegator_key.public_key(secp).account()?;
+                let validator = delegator_key.public_key(secp).operator_address()?;
 
                 log::info!("Validator {}", &validator);
-                let msg_commission = MsgWithdrawValidatorCommission::create(validator.clone())?;
+                let msg_commission = MsgWithdrawValidatorCommission::create(&validator)?;
                 let msg_rewards =
                     MsgWithdrawDelegationReward::create(delegator_account, validator)?;
                 let messages: Vec<Message> = vec![msg_commission, msg_rewards];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &delegator_key,
                         messages,
                         Some(format!(
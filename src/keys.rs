@@ -1,9 +1,10 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::io::{self, BufRead};
-use terra_rust_api::PrivateKey;
+use terra_rust_api::client::batch::DEFAULT_MAX_CONCURRENCY;
+use terra_rust_api::{PrivateKey, Terra};
 
-use secp256k1::Secp256k1;
+use terra_rust_api::shared_secp256k1;
 use terra_rust_wallet::Wallet;
 /// Key Operations
 #[derive(Parser)]
@@ -42,18 +43,41 @@ pub enum KeysEnum {
         #[clap(name = "name", help = "the key with this name.")]
         name: String,
     },
-    /// List keys in the wallet
+    /// List keys in the wallet, and the watch-only entries
     List,
+    /// Add an address with no key, to query or send to by name, like a cold wallet
+    Watch {
+        #[clap(name = "name", help = "a memorable name to use in this client")]
+        name: String,
+        #[clap(name = "address", help = "the terra address")]
+        address: String,
+    },
+    /// list the addresses of the recovery words (read from stdin) over a range of accounts & indexes,
+    /// to find funds sent to a different derivation path
+    Derive {
+        /// how many accounts to scan, from 0
+        #[clap(long, default_value = "1")]
+        accounts: u32,
+        /// how many indexes to scan in each account, from 0
+        #[clap(long, default_value = "10")]
+        indexes: u32,
+        /// the BIP44 coin type. 330 for terra, 118 for keys made by cosmos wallets
+        #[clap(long, default_value = "330")]
+        coin_type: u32,
+        /// show each address's balances. this queries the LCD once per address, a few at a time
+        #[clap(long)]
+        balances: bool,
+    },
 }
 
 impl KeysCommand {
-    pub fn parse(self, wallet: &Wallet, seed: Option<&str>) -> Result<()> {
+    pub async fn parse(self, terra: &Terra, wallet: &Wallet<'_>, seed: Option<&str>) -> Result<()> {
         match self.command {
             KeysEnum::Parse { .. } => {
                 todo!()
             }
             KeysEnum::Recover { name } => {
-                let secp = Secp256k1::new();
+                let secp = shared_secp256k1();
 
                 println!("Please input the set of the recovery words, followed by the passphrase (which is passed via --seed)");
                 if seed.is_some() {
@@ -76,20 +100,20 @@ impl KeysCommand {
                 let words = iterator.next().unwrap().unwrap();
 
                 let pk = match seed {
-                    Some(seed_str) => PrivateKey::from_words_seed(&secp, &words, seed_str)?,
-                    None => PrivateKey::from_words(&secp, &words, 0, 0)?,
+                    Some(seed_str) => PrivateKey::from_words_seed(secp, &words, seed_str)?,
+                    None => PrivateKey::from_words(secp, &words, 0, 0)?,
                 };
                 wallet.store_key(&name, &pk)?;
             }
 
             KeysEnum::New { name } => {
-                let secp = Secp256k1::new();
+                let secp = shared_secp256k1();
 
                 println!("This key will be stored in your computer's vault (win10)/secret service (linux)/keyring (os/x)");
 
                 let pk = match seed {
-                    None => PrivateKey::new(&secp)?,
-                    Some(seed_str) => PrivateKey::new_seed(&secp, seed_str)?,
+                    None => PrivateKey::new(secp)?,
+                    Some(seed_str) => PrivateKey::new_seed(secp, seed_str)?,
                 };
                 println!("Please write these down and save these in a secure location.");
                 println!("These words can be used to transfer all your coins out of your account");
@@ -103,7 +127,7 @@ impl KeysCommand {
 
                 wallet.store_key(&name, &pk)?;
 
-                let pub_key = wallet.get_public_key(&secp, &name, seed)?;
+                let pub_key = wallet.get_public_key(shared_secp256k1(), &name, seed)?;
 
                 println!("{}", pub_key.account()?)
             }
@@ -111,14 +135,64 @@ impl KeysCommand {
                 wallet.delete_key(&name)?;
             }
             KeysEnum::Get { name } => {
-                let secp = Secp256k1::new();
-                let pub_key = wallet.get_public_key(&secp, &name, seed)?;
+                let pub_key = wallet.get_public_key(shared_secp256k1(), &name, seed)?;
 
                 println!("{}", pub_key.account()?);
             }
             KeysEnum::List => {
                 let keys = wallet.list()?;
                 println!("{:#?}", keys);
+                let watch_only = wallet.list_watch_only()?;
+                if !watch_only.is_empty() {
+                    println!("watch-only: {:#?}", watch_only);
+                }
+            }
+            KeysEnum::Watch { name, address } => {
+                wallet.store_address(&name, &address)?;
+            }
+            KeysEnum::Derive {
+                accounts,
+                indexes,
+                coin_type,
+                balances,
+            } => {
+                let secp = shared_secp256k1();
+                println!("Recovery words:");
+                let words = io::stdin()
+                    .lock()
+                    .lines()
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("no recovery words given"))??;
+                let matrix = PrivateKey::derive_addresses_seed(
+                    secp,
+                    &words,
+                    seed.unwrap_or_default(),
+                    coin_type,
+                    0..accounts,
+                    0..indexes,
+                )?;
+                let derived = matrix.into_iter().flatten().collect::<Vec<_>>();
+                if balances {
+                    let mut batch = terra.batch(DEFAULT_MAX_CONCURRENCY);
+                    batch.extend(
+                        derived
+                            .iter()
+                            .map(|d| async move { terra.bank().balances(&d.address, None).await }),
+                    );
+                    for (d, coins) in derived.iter().zip(batch.run().await) {
+                        let coins = coins?
+                            .result
+                            .iter()
+                            .map(|c| c.to_string())
+                            .collect::<Vec<_>>()
+                            .join(",");
+                        println!("{}\t{}\t{}", d.path(), d.address, coins);
+                    }
+                } else {
+                    for d in derived {
+                        println!("{}\t{}", d.path(), d.address);
+                    }
+                }
             }
         }
         Ok(())
@@ -4,13 +4,15 @@ use terra_rust_api::{PublicKey, Terra};
 //use crate::errors::Result;
 //use crate::keys::get_private_key;
 
-use secp256k1::Secp256k1;
 use terra_rust_api::messages::Message;
+use terra_rust_api::shared_secp256k1;
 
 use crate::{NAME, VERSION};
 use rust_decimal::Decimal;
 use terra_rust_api::core_types::Coin;
+use terra_rust_api::market_types::SweepOptions;
 use terra_rust_api::messages::market::MsgSwap;
+use terra_rust_cli::cli_helpers;
 use terra_rust_wallet::Wallet;
 #[derive(Parser)]
 /// Market Operations
@@ -29,6 +31,18 @@ pub enum MarketEnum {
         #[clap(name = "ask", help = "what to swap the amount into")]
         ask: String,
     },
+    #[clap(
+        name = "simulate",
+        about = "see what a swap would return, the spread it pays, and what it does to the pool"
+    )]
+    Simulate {
+        #[clap(name = "denom", help = "token symbol. remember we are uXXX not XXX")]
+        denom: String,
+        #[clap(name = "amount", help = "the amount. remember we are uXXX not XXX")]
+        amount: Decimal,
+        #[clap(name = "ask", help = "what to swap the amount into")]
+        ask: String,
+    },
     #[clap(name = "swap", about = "swap/exchange token")]
     Swap {
         /// from. The nickname in the wallet used to sign the transaction, do the token exchange
@@ -60,6 +74,22 @@ pub enum MarketEnum {
             help = "the minimum amount to consider in 'to-coin' denomination. (ie. don't swap if there is only 10c worth)"
         )]
         threshold: Decimal,
+        #[clap(
+            long,
+            help = "a threshold for one token, as denom=amount, in place of 'threshold'"
+        )]
+        min: Vec<String>,
+        #[clap(long, help = "only swap these tokens")]
+        allow: Vec<String>,
+        #[clap(long, help = "never swap these tokens")]
+        deny: Vec<String>,
+        #[clap(long, help = "swap at most this many tokens, the most valuable first")]
+        max_messages: Option<usize>,
+        #[clap(
+            long,
+            help = "show each token's balance, value, spread and whether it would be swapped, without swapping"
+        )]
+        dry_run: bool,
     },
 }
 impl MarketCommand {
@@ -71,6 +101,17 @@ impl MarketCommand {
 
                 println!("{}", serde_json::to_string_pretty(&sw)?);
             }
+            MarketEnum::Simulate { denom, ask, amount } => {
+                let coin = Coin::create(&denom, amount);
+                let simulation = terra.market().simulate_swap(&coin, &ask, None).await?;
+                println!("{}", serde_json::to_string_pretty(&simulation)?);
+                if simulation.exceeds_min_spread() {
+                    eprintln!(
+                        "the pool is depleted: this swap pays a spread of {}, above the minimum of {}",
+                        simulation.spread, simulation.min_spread
+                    );
+                }
+            }
 
             MarketEnum::Swap {
                 from,
@@ -78,9 +119,9 @@ impl MarketCommand {
                 to,
                 ask_denom,
             } => {
-                let secp = Secp256k1::new();
-                let from_key = wallet.get_private_key(&secp, &from, seed)?;
-                let from_public_key = from_key.public_key(&secp);
+                let secp = shared_secp256k1();
+                let from_key = wallet.get_private_key(secp, &from, seed)?;
+                let from_public_key = from_key.public_key(secp);
                 let coin: Coin = Coin::parse(&offer_coin)
                     .expect("invalid offer coin. hint: 1000ukrw")
                     .unwrap();
@@ -97,7 +138,7 @@ impl MarketCommand {
                 let messages: Vec<Message> = vec![swap];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &from_key,
                         messages,
                         Some(format!(
@@ -115,14 +156,35 @@ impl MarketCommand {
                 from,
                 to_coin,
                 threshold,
+                min,
+                allow,
+                deny,
+                max_messages,
+                dry_run,
             } => {
-                let secp = Secp256k1::new();
-                let from_key = wallet.get_private_key(&secp, &from, seed)?;
-                let from_public_key = from_key.public_key(&secp);
+                let mut options =
+                    cli_helpers::sweep_thresholds(SweepOptions::create(threshold), &min)?;
+                options.allow = allow;
+                options.deny = deny;
+                options.max_messages = max_messages;
+                if dry_run {
+                    let from_account = wallet
+                        .get_public_key(shared_secp256k1(), &from, seed)?
+                        .account()?;
+                    let report = terra
+                        .market()
+                        .sweep_report(&from_account, &to_coin, &options, None)
+                        .await?;
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    return Ok(());
+                }
+                let secp = shared_secp256k1();
+                let from_key = wallet.get_private_key(secp, &from, seed)?;
+                let from_public_key = from_key.public_key(secp);
                 let from_account = from_public_key.account()?;
                 let messages = terra
                     .market()
-                    .generate_sweep_messages(from_account, to_coin, threshold, None)
+                    .generate_sweep_messages_with(&from_account, &to_coin, &options, None)
                     .await?;
 
                 if messages.is_empty() {
@@ -130,7 +192,7 @@ impl MarketCommand {
                 } else {
                     let resp = terra
                         .submit_transaction_sync(
-                            &secp,
+                            secp,
                             &from_key,
                             messages,
                             Some(format!(
@@ -4,10 +4,10 @@ use terra_rust_api::Terra;
 //use crate::errors::Result;
 //use crate::keys::get_private_key;
 use crate::{NAME, VERSION};
-use secp256k1::Secp256k1;
 use terra_rust_api::client::oracle::Voters;
 use terra_rust_api::messages::oracle::MsgDelegateFeedConsent;
 use terra_rust_api::messages::Message;
+use terra_rust_api::shared_secp256k1;
 use terra_rust_wallet::Wallet;
 
 #[derive(Subcommand)]
@@ -62,16 +62,16 @@ impl OracleCommand {
                 delegate,
             } => {
                 println!("Set Feeder {}", delegate);
-                let secp = Secp256k1::new();
-                let from_key = wallet.get_private_key(&secp, &validator, seed)?;
-                let from_public_key = from_key.public_key(&secp);
+                let secp = shared_secp256k1();
+                let from_key = wallet.get_private_key(secp, &validator, seed)?;
+                let from_public_key = from_key.public_key(secp);
                 let from_operator = from_public_key.operator_address()?;
                 let delegate_msg = MsgDelegateFeedConsent::create(from_operator, delegate)?;
 
                 let messages: Vec<Message> = vec![delegate_msg];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &from_key,
                         messages,
                         Some(format!(
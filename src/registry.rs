@@ -0,0 +1,51 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use terra_rust_api::registry::{ChainRegistryEntry, CHAIN_REGISTRY_URL};
+/// cosmos chain registry commands
+#[derive(Parser)]
+pub struct RegistryCommand {
+    #[clap(
+        name = "registry-url",
+        long = "registry-url",
+        env = "TERRARUST_CHAIN_REGISTRY",
+        default_value = CHAIN_REGISTRY_URL,
+        help = "the chain.json to read. file:// for a local copy"
+    )]
+    url: String,
+    #[clap(subcommand)]
+    command: RegistryEnum,
+}
+#[derive(Subcommand)]
+pub enum RegistryEnum {
+    #[clap(name = "entry", about = "the chain's registry entry")]
+    Entry,
+    #[clap(
+        name = "endpoints",
+        about = "the public LCDs & RPCs that are up and synced, best first"
+    )]
+    Endpoints {
+        #[clap(
+            name = "max-lag",
+            long = "max-lag",
+            default_value = "2",
+            help = "how many blocks behind the best an endpoint can be"
+        )]
+        max_lag: u64,
+    },
+    #[clap(name = "fees", about = "the fee denominations, and their gas prices")]
+    Fees,
+}
+impl RegistryCommand {
+    pub async fn parse(&self) -> Result<()> {
+        let entry = ChainRegistryEntry::fetch(&self.url).await?;
+        match &self.command {
+            RegistryEnum::Entry => println!("{}", serde_json::to_string(&entry)?),
+            RegistryEnum::Endpoints { max_lag } => {
+                let endpoints = entry.healthy_endpoints(*max_lag).await;
+                println!("{}", serde_json::to_string(&endpoints)?)
+            }
+            RegistryEnum::Fees => println!("{}", serde_json::to_string(&entry.fees)?),
+        }
+        Ok(())
+    }
+}
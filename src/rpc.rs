@@ -31,6 +31,18 @@ pub enum RPCSubCommand {
     Block { height: Option<u64> },
     #[clap(name = "block-results", about = "Get the block at a given height")]
     BlockResults { height: Option<u64> },
+    #[clap(
+        name = "validator-events",
+        about = "a validator's commission withdrawals and slashes between two heights"
+    )]
+    ValidatorEvents {
+        #[clap(name = "validator", help = "the validator's terravaloper address")]
+        validator: String,
+        #[clap(name = "from", help = "the first height")]
+        from: u64,
+        #[clap(name = "to", help = "the last height")]
+        to: u64,
+    },
 }
 impl RPCCommand {
     pub async fn parse(self, terra: &Terra) -> Result<()> {
@@ -65,6 +77,15 @@ impl RPCCommand {
                     None => rpc_endpoint.block_results().await?,
                 };
 
+                println!("{}", serde_json::to_string(&resp)?)
+            }
+            RPCSubCommand::ValidatorEvents {
+                validator,
+                from,
+                to,
+            } => {
+                let resp = rpc_endpoint.validator_events(&validator, from, to).await?;
+
                 println!("{}", serde_json::to_string(&resp)?)
             }
         }
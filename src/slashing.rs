@@ -2,8 +2,8 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use terra_rust_api::Terra;
 
-use secp256k1::Secp256k1;
 use terra_rust_api::messages::Message;
+use terra_rust_api::shared_secp256k1;
 
 use crate::{NAME, VERSION};
 use terra_rust_api::messages::slashing::MsgUnjail;
@@ -28,9 +28,9 @@ impl SlashingCommand {
     pub async fn parse(self, terra: &Terra, wallet: &Wallet<'_>, seed: Option<&str>) -> Result<()> {
         match self.command {
             SlashingEnum::UnJail { validator } => {
-                let secp = Secp256k1::new();
-                let from_key = wallet.get_private_key(&secp, &validator, seed)?;
-                let from_public_key = from_key.public_key(&secp);
+                let secp = shared_secp256k1();
+                let from_key = wallet.get_private_key(secp, &validator, seed)?;
+                let from_public_key = from_key.public_key(secp);
 
                 let from_account = from_public_key.operator_address()?;
                 let un_jail = MsgUnjail::create(from_account)?;
@@ -38,7 +38,7 @@ impl SlashingCommand {
                 let messages: Vec<Message> = vec![un_jail];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &from_key,
                         messages,
                         Some(format!(
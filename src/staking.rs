@@ -4,13 +4,13 @@ use terra_rust_api::Terra;
 
 use crate::{NAME, VERSION};
 use rust_decimal::Decimal;
-use secp256k1::Secp256k1;
 use terra_rust_api::core_types::Coin;
 use terra_rust_api::messages::staking::{
     MsgBeginRedelegate, MsgCreateValidator, MsgDelegate, MsgEditValidator, MsgUndelegate,
     ValidatorCommission, ValidatorDescription,
 };
 use terra_rust_api::messages::Message;
+use terra_rust_api::shared_secp256k1;
 use terra_rust_wallet::Wallet;
 
 #[derive(Subcommand)]
@@ -133,7 +133,7 @@ pub struct StakingCommand {
 }
 impl StakingCommand {
     pub async fn parse(self, terra: &Terra, wallet: &Wallet<'_>, seed: Option<&str>) -> Result<()> {
-        let secp = Secp256k1::new();
+        let secp = shared_secp256k1();
         match self.command {
             StakingEnum::CreateValidator {
                 delegator,
@@ -164,8 +164,8 @@ impl StakingCommand {
                 };
 
                 log::info!("Delegator {}", &delegator);
-                let delegator_key = wallet.get_private_key(&secp, &delegator, seed)?;
-                let delegator_account = delegator_key.public_key(&secp).account()?;
+                let delegator_key = wallet.get_private_key(shared_secp256k1(), &delegator, seed)?;
+                let delegator_account = delegator_key.public_key(secp).account()?;
                 log::info!("Validator {}", &validator);
                 log::info!("Pubkey {}", &pubkey);
                 // let validator_key = PublicKey::from_tendermint_key(&validator)?.operator_address()?;
@@ -182,7 +182,7 @@ impl StakingCommand {
                 let messages: Vec<Message> = vec![msg];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &delegator_key,
                         messages,
                         Some(format!(
@@ -216,14 +216,14 @@ impl StakingCommand {
                     website,
                 );
                 log::info!("Validator {}", &validator);
-                let validator_key = wallet.get_private_key(&secp, &validator, seed)?;
-                let validator_operator = validator_key.public_key(&secp).operator_address()?;
+                let validator_key = wallet.get_private_key(shared_secp256k1(), &validator, seed)?;
+                let validator_operator = validator_key.public_key(secp).operator_address()?;
                 let msg =
                     MsgEditValidator::create(desc, validator_operator, rate, min_self_delegation)?;
                 let messages: Vec<Message> = vec![msg];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &validator_key,
                         messages,
                         Some(format!(
@@ -245,8 +245,8 @@ impl StakingCommand {
                 amount,
             } => {
                 log::info!("Delegator {}", &delegator);
-                let delegator_key = wallet.get_private_key(&secp, &delegator, seed)?;
-                let delegator_account = delegator_key.public_key(&secp).account()?;
+                let delegator_key = wallet.get_private_key(shared_secp256k1(), &delegator, seed)?;
+                let delegator_account = delegator_key.public_key(secp).account()?;
                 let msg = MsgDelegate::create(
                     delegator_account,
                     validator,
@@ -255,7 +255,7 @@ impl StakingCommand {
                 let messages: Vec<Message> = vec![msg];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &delegator_key,
                         messages,
                         Some(format!(
@@ -277,8 +277,8 @@ impl StakingCommand {
                 amount,
             } => {
                 log::info!("Delegator {}", &delegator);
-                let delegator_key = wallet.get_private_key(&secp, &delegator, seed)?;
-                let delegator_account = delegator_key.public_key(&secp).account()?;
+                let delegator_key = wallet.get_private_key(shared_secp256k1(), &delegator, seed)?;
+                let delegator_account = delegator_key.public_key(secp).account()?;
                 let msg = MsgBeginRedelegate::create(
                     delegator_account,
                     destination,
@@ -288,7 +288,7 @@ impl StakingCommand {
                 let messages: Vec<Message> = vec![msg];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &delegator_key,
                         messages,
                         Some(format!(
@@ -309,8 +309,8 @@ impl StakingCommand {
                 amount,
             } => {
                 log::info!("Delegator {}", &delegator);
-                let delegator_key = wallet.get_private_key(&secp, &delegator, seed)?;
-                let delegator_account = delegator_key.public_key(&secp).account()?;
+                let delegator_key = wallet.get_private_key(shared_secp256k1(), &delegator, seed)?;
+                let delegator_account = delegator_key.public_key(secp).account()?;
                 let msg = MsgUndelegate::create(
                     delegator_account,
                     validator,
@@ -319,7 +319,7 @@ impl StakingCommand {
                 let messages: Vec<Message> = vec![msg];
                 let resp = terra
                     .submit_transaction_sync(
-                        &secp,
+                        secp,
                         &delegator_key,
                         messages,
                         Some(format!(
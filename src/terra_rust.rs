@@ -18,6 +18,7 @@ mod fcd;
 mod keys;
 mod market;
 mod oracle;
+mod registry;
 mod rpc;
 mod slashing;
 mod staking;
@@ -36,6 +37,7 @@ use crate::fcd::FCDCommand;
 use crate::keys::KeysCommand;
 use crate::market::MarketCommand;
 use crate::oracle::OracleCommand;
+use crate::registry::RegistryCommand;
 use crate::rpc::RPCCommand;
 use crate::slashing::SlashingCommand;
 use crate::staking::StakingCommand;
@@ -44,6 +46,7 @@ use crate::tx::TxCommand;
 use crate::validator::ValidatorCommand;
 use crate::wallet::WalletCommand;
 use crate::wasm::WasmCommand;
+use terra_rust_api::redact::RedactingLogger;
 use terra_rust_api::{GasOptions, Terra};
 use terra_rust_cli::cli_helpers;
 use terra_rust_wallet::Wallet;
@@ -73,6 +76,7 @@ enum Command {
     ValidatorSets(ValidatorSetsCommand),
     RPC(RPCCommand),
     FCD(FCDCommand),
+    Registry(RegistryCommand),
     WASM(WasmCommand),
     CODE(CodeCommand),
 }
@@ -94,7 +98,7 @@ async fn run() -> anyhow::Result<()> {
     };
     let wallet = Wallet::create(&cli.wallet);
     match cli.cmd {
-        Command::Keys(key_cmd) => key_cmd.parse(&wallet, seed),
+        Command::Keys(key_cmd) => key_cmd.parse(&t, &wallet, seed).await,
         Command::Bank(bank_cmd) => bank_cmd.parse(&t, &wallet, seed).await,
         Command::Oracle(cmd) => cmd.parse(&t, &wallet, seed).await,
         Command::Validator(cmd) => cmd.parse(&t, &wallet, seed).await,
@@ -110,6 +114,7 @@ async fn run() -> anyhow::Result<()> {
         Command::ValidatorSets(cmd) => cmd.parse(&t).await,
         Command::RPC(cmd) => cmd.parse(&t).await,
         Command::FCD(cmd) => cmd.parse(&t, &cli.fcd).await,
+        Command::Registry(cmd) => cmd.parse().await,
         Command::WASM(cmd) => cmd.parse(&t).await,
         Command::CODE(cmd) => cmd.parse(&t, &wallet, seed).await,
     }
@@ -117,7 +122,10 @@ async fn run() -> anyhow::Result<()> {
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    env_logger::init();
+    let logger = env_logger::Builder::from_default_env().build();
+    let level = logger.filter();
+    // seed phrases are passed on the command line. keep them out of the logs
+    RedactingLogger::init(Box::new(logger), level).expect("logger already set");
 
     if let Err(ref err) = run().await {
         log::error!("{}", err);
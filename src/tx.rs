@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use terra_rust_api::client::event_query::EventQuery;
 use terra_rust_api::errors::TerraRustAPIError;
 use terra_rust_api::Terra;
 
@@ -18,6 +19,38 @@ enum TxEnum {
         offset: Option<u64>,
         limit: Option<u64>,
     },
+    #[clap(name = "search", about = "look up TXs by their events")]
+    Search {
+        #[clap(long, help = "sent by this account")]
+        sender: Option<String>,
+        #[clap(long, help = "calling this contract")]
+        contract: Option<String>,
+        #[clap(long, help = "the contract's wasm action, like transfer")]
+        action: Option<String>,
+        #[clap(long, help = "in this block")]
+        height: Option<u64>,
+        #[clap(
+            long,
+            help = "any other event attribute, as event_type.attribute=value"
+        )]
+        event: Vec<String>,
+        #[clap(long)]
+        offset: Option<u64>,
+        #[clap(long)]
+        limit: Option<u64>,
+    },
+    #[clap(
+        name = "ibc",
+        about = "follow an IBC transfer until it is acknowledged or times out"
+    )]
+    Ibc {
+        #[clap(name = "hash", help = "the transfer's hash")]
+        hash: String,
+        #[clap(long, default_value = "60", help = "times to check on it")]
+        max_times: usize,
+        #[clap(long, default_value = "10", help = "seconds between checks")]
+        sleep: u64,
+    },
 }
 #[derive(Parser)]
 /// Transaction Commands
@@ -32,7 +65,7 @@ impl TxCommand {
                 let tx_r = terra.tx().get_v1(&hash).await;
                 match tx_r {
                     Ok(tx) => println!("{}", serde_json::to_string_pretty(&tx)?),
-                    Err(e) => match e {
+                    Err(e) => match e.without_context() {
                         TerraRustAPIError::TerraLCDResponse(s, x) => println!("{}/{}", s, x),
                         _ => println!("{:?}", e),
                     },
@@ -46,6 +79,53 @@ impl TxCommand {
                 let txs = terra.tx().get_txs_in_block(height, offset, limit).await?;
                 println!("{}", serde_json::to_string_pretty(&txs)?);
             }
+            TxEnum::Search {
+                sender,
+                contract,
+                action,
+                height,
+                event,
+                offset,
+                limit,
+            } => {
+                let mut query = EventQuery::new();
+                if let Some(sender) = sender {
+                    query = query.sender(&sender);
+                }
+                if let Some(contract) = contract {
+                    query = query.contract(&contract);
+                }
+                if let Some(action) = action {
+                    query = query.action(&action);
+                }
+                if let Some(height) = height {
+                    query = query.height(height);
+                }
+                for event in event {
+                    match event.split_once('=') {
+                        Some((key, value)) => query = query.eq(key, value),
+                        None => {
+                            anyhow::bail!("--event {} is not event_type.attribute=value", event)
+                        }
+                    }
+                }
+                if query.is_empty() {
+                    anyhow::bail!("search for something");
+                }
+                let txs = terra.tx().search(&query, offset, limit).await?;
+                println!("{}", serde_json::to_string_pretty(&txs)?);
+            }
+            TxEnum::Ibc {
+                hash,
+                max_times,
+                sleep,
+            } => {
+                let transfer = terra
+                    .ibc()
+                    .follow_ibc_transfer(&hash, max_times, tokio::time::Duration::from_secs(sleep))
+                    .await?;
+                println!("{}", serde_json::to_string_pretty(&transfer)?);
+            }
         }
         Ok(())
     }
@@ -1,10 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use terra_rust_api::Terra;
+use terra_rust_api::{shared_secp256k1, Terra};
 use terra_rust_wallet::Wallet;
 
-use secp256k1::Secp256k1;
-
 #[derive(Subcommand)]
 pub enum VoterCommand {
     Feeder,
@@ -43,6 +41,8 @@ pub enum ValidatorEnum {
         #[clap(name = "validator", help = "the validator's terravaloper address")]
         // the validator to get more info on. hint: use the terravaloper address. try terravaloper12g4nkvsjjnl0t7fvq3hdcw7y8dc9fq69nyeu9q
         validator: String,
+        #[clap(long, help = "only show how many delegators there are")]
+        count: bool,
     },
     #[clap(name = "unbonding")]
     Unbonding {
@@ -68,9 +68,8 @@ impl ValidatorCommand {
             }
             ValidatorEnum::Describe { validator } => {
                 let account_id = if !validator.starts_with("terravaloper1") {
-                    let secp = Secp256k1::new();
                     wallet
-                        .get_public_key(&secp, &validator, seed)?
+                        .get_public_key(shared_secp256k1(), &validator, seed)?
                         .operator_address()?
                 } else {
                     validator
@@ -82,23 +81,26 @@ impl ValidatorCommand {
                 let v = terra.staking().validator_by_moniker(&moniker).await?;
                 println!("{:#?}", v);
             }
-            ValidatorEnum::Delegations { validator } => {
+            ValidatorEnum::Delegations { validator, count } => {
                 let account_id = if !validator.starts_with("terravaloper1") {
-                    let secp = Secp256k1::new();
                     wallet
-                        .get_public_key(&secp, &validator, seed)?
+                        .get_public_key(shared_secp256k1(), &validator, seed)?
                         .operator_address()?
                 } else {
                     validator
                 };
-                let v = terra.staking().validator_delegations(&account_id).await?;
-                println!("{:#?}", v.result);
+                if count {
+                    let count = terra.staking().delegation_count(&account_id).await?;
+                    println!("{}", count);
+                } else {
+                    let v = terra.staking().validator_delegations(&account_id).await?;
+                    println!("{:#?}", v.result);
+                }
             }
             ValidatorEnum::Unbonding { validator } => {
                 let account_id = if !validator.starts_with("terravaloper1") {
-                    let secp = Secp256k1::new();
                     wallet
-                        .get_public_key(&secp, &validator, seed)?
+                        .get_public_key(shared_secp256k1(), &validator, seed)?
                         .operator_address()?
                 } else {
                     validator
@@ -0,0 +1,27 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use terra_rust_api::PublicKey;
+
+fn bech32(c: &mut Criterion) {
+    let raw =
+        hex::decode("02A1633CAFCC01EBFB6D78E39F687A1F0995C62FC95F51EAD10A02EE0BE551B5DC").unwrap();
+    let key = PublicKey::from_public_key(&raw);
+    c.bench_function("account, cached", |b| {
+        b.iter(|| black_box(&key).account().unwrap())
+    });
+    c.bench_function("account, encoded", |b| {
+        b.iter(|| {
+            PublicKey::create(
+                key.raw_pub_key().map(<[u8]>::to_vec),
+                black_box(key.raw_address().map(<[u8]>::to_vec)),
+            )
+            .account()
+            .unwrap()
+        })
+    });
+    c.bench_function("operator address, cached", |b| {
+        b.iter(|| black_box(&key).operator_address().unwrap())
+    });
+}
+
+criterion_group!(benches, bech32);
+criterion_main!(benches);
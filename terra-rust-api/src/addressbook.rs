@@ -1,6 +1,17 @@
+use crate::errors::{RequestContext, TerraRustAPIError};
 use crate::terra_datetime_format;
+use crate::Terra;
 use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+#[cfg(feature = "tokio")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Hash, PartialEq, Eq)]
 pub struct NodeIDIPPort {
@@ -8,6 +19,14 @@ pub struct NodeIDIPPort {
     pub ip: String,
     pub port: usize,
 }
+impl NodeIDIPPort {
+    /// can a TCP connection to the node's p2p port be made within `timeout`
+    #[cfg(feature = "tokio")]
+    pub async fn is_alive(&self, timeout: Duration) -> bool {
+        let connect = tokio::net::TcpStream::connect((self.ip.as_str(), self.port as u16));
+        matches!(tokio::time::timeout(timeout, connect).await, Ok(Ok(_)))
+    }
+}
 impl ToString for NodeIDIPPort {
     fn to_string(&self) -> String {
         format!("{}@{}:{}", self.id, self.ip, self.port)
@@ -33,3 +52,432 @@ pub struct AddressBook {
     pub key: String,
     pub addrs: Vec<NodeAddr>,
 }
+impl AddressBook {
+    /// every distinct peer, most recently reached first
+    pub fn peers(&self) -> Vec<NodeIDIPPort> {
+        let mut addrs = self.addrs.iter().collect::<Vec<_>>();
+        addrs.sort_by_key(|a| std::cmp::Reverse(a.last_success));
+        let mut seen = std::collections::HashSet::new();
+        addrs
+            .into_iter()
+            .filter(|a| seen.insert(&a.addr))
+            .map(|a| a.addr.clone())
+            .collect()
+    }
+    /// the [AddressBook::peers] accepting connections, checking `max_concurrency` at a time
+    #[cfg(feature = "tokio")]
+    pub async fn alive_peers(
+        &self,
+        timeout: Duration,
+        max_concurrency: usize,
+    ) -> Vec<NodeIDIPPort> {
+        use futures::StreamExt;
+        futures::stream::iter(self.peers())
+            .map(|peer| async move { (peer.is_alive(timeout).await, peer) })
+            .buffered(max_concurrency.max(1))
+            .filter_map(|(alive, peer)| async move { Some(peer).filter(|_| alive) })
+            .collect()
+            .await
+    }
+    /// the value for `persistent_peers` in tendermint's config.toml
+    pub fn persistent_peers(peers: &[NodeIDIPPort]) -> String {
+        peers
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+    /// a book as JSON, gzipped or not. a gzipped one can't be over [MAX_ADDRESS_BOOK_BYTES] decompressed
+    pub fn parse(bytes: &[u8]) -> Result<AddressBook, TerraRustAPIError> {
+        if bytes.starts_with(&GZIP_MAGIC) {
+            let mut json = vec![];
+            GzDecoder::new(bytes)
+                .take(MAX_ADDRESS_BOOK_BYTES + 1)
+                .read_to_end(&mut json)?;
+            if json.len() as u64 > MAX_ADDRESS_BOOK_BYTES {
+                return Err(TerraRustAPIError::AddressBookTooLarge(
+                    MAX_ADDRESS_BOOK_BYTES,
+                ));
+            }
+            Ok(serde_json::from_slice(&json)?)
+        } else {
+            Ok(serde_json::from_slice(bytes)?)
+        }
+    }
+    /// read the book from `source`
+    pub async fn load(source: &dyn AddressBookSource) -> Result<AddressBook, TerraRustAPIError> {
+        AddressBook::parse(&source.fetch().await?)
+    }
+    /// read the book from `source`, or the [EmbeddedSource] snapshot if it can't be read
+    #[cfg(feature = "embedded-addressbook")]
+    pub async fn load_or_embedded(source: &dyn AddressBookSource) -> AddressBook {
+        match AddressBook::load(source).await {
+            Ok(book) => book,
+            Err(e) => {
+                log::warn!(
+                    "address book {} unavailable, using the embedded snapshot: {}",
+                    source.location(),
+                    e
+                );
+                EmbeddedSource::address_book()
+            }
+        }
+    }
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// the most a gzipped book may decompress to. main-net's is a few hundred KB
+pub const MAX_ADDRESS_BOOK_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Somewhere an address book can be read from. see [AddressBook::load] & [source_for]
+pub trait AddressBookSource: Send + Sync {
+    /// the book's bytes, which may be gzipped
+    fn fetch(&self) -> BoxFuture<'_, Result<Vec<u8>, TerraRustAPIError>>;
+    /// where the book is, for messages
+    fn location(&self) -> String;
+}
+
+/// the source `location` names: `file://` a path, `embedded:` the [EmbeddedSource] snapshot,
+/// anything else a URL
+pub fn source_for(location: &str) -> Box<dyn AddressBookSource> {
+    if let Some(path) = location.strip_prefix("file://") {
+        Box::new(FileSource(PathBuf::from(path)))
+    } else if location.starts_with("embedded:") {
+        Box::new(EmbeddedSource)
+    } else {
+        Box::new(HttpSource::create(location))
+    }
+}
+
+/// a book in a local file
+#[derive(Clone, Debug)]
+pub struct FileSource(pub PathBuf);
+impl AddressBookSource for FileSource {
+    fn fetch(&self) -> BoxFuture<'_, Result<Vec<u8>, TerraRustAPIError>> {
+        Box::pin(async move { Ok(std::fs::read(&self.0)?) })
+    }
+    fn location(&self) -> String {
+        format!("file://{}", self.0.display())
+    }
+}
+
+/// a book served over http(s), fetched with the client's default headers and any added
+#[derive(Clone, Debug)]
+pub struct HttpSource {
+    pub url: String,
+    headers: HeaderMap,
+}
+impl HttpSource {
+    pub fn create<S: Into<String>>(url: S) -> HttpSource {
+        HttpSource {
+            url: url.into(),
+            headers: Terra::construct_headers(),
+        }
+    }
+    /// send this header too, like an API key the host wants
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<HttpSource, TerraRustAPIError> {
+        let invalid = || TerraRustAPIError::Config(format!("header {}", name), value.into());
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+        let value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+    async fn get(&self) -> Result<Vec<u8>, TerraRustAPIError> {
+        let resp = Terra::http_client()
+            .get(&self.url)
+            .headers(self.headers.clone())
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(TerraRustAPIError::TerraLCDResponse(
+                status,
+                resp.text().await?,
+            ));
+        }
+        Ok(resp.bytes().await?.to_vec())
+    }
+}
+impl AddressBookSource for HttpSource {
+    fn fetch(&self) -> BoxFuture<'_, Result<Vec<u8>, TerraRustAPIError>> {
+        Box::pin(async move {
+            self.get()
+                .await
+                .map_err(|e| e.with_context(RequestContext::create("GET", &self.url, None)))
+        })
+    }
+    fn location(&self) -> String {
+        self.url.clone()
+    }
+}
+
+/// the main-net book as of this release, compiled in. for when the network is out of reach.
+/// needs the embedded-addressbook feature, without which fetching it fails
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedSource;
+#[cfg(feature = "embedded-addressbook")]
+impl EmbeddedSource {
+    const SNAPSHOT: &'static [u8] = include_bytes!("../resources/addressbook.json");
+
+    pub fn address_book() -> AddressBook {
+        AddressBook::parse(EmbeddedSource::SNAPSHOT).expect("the embedded address book parses")
+    }
+}
+impl AddressBookSource for EmbeddedSource {
+    #[cfg(feature = "embedded-addressbook")]
+    fn fetch(&self) -> BoxFuture<'_, Result<Vec<u8>, TerraRustAPIError>> {
+        Box::pin(async { Ok(EmbeddedSource::SNAPSHOT.to_vec()) })
+    }
+    #[cfg(not(feature = "embedded-addressbook"))]
+    fn fetch(&self) -> BoxFuture<'_, Result<Vec<u8>, TerraRustAPIError>> {
+        Box::pin(async {
+            Err(TerraRustAPIError::Config(
+                "address book".into(),
+                "embedded: needs the embedded-addressbook feature".into(),
+            ))
+        })
+    }
+    fn location(&self) -> String {
+        "embedded:".into()
+    }
+}
+
+/// the peers that appeared and disappeared between two versions of a book
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct PeerChanges {
+    pub added: Vec<NodeIDIPPort>,
+    pub removed: Vec<NodeIDIPPort>,
+}
+impl PeerChanges {
+    pub fn between(old: &[NodeIDIPPort], new: &[NodeIDIPPort]) -> PeerChanges {
+        PeerChanges {
+            added: new.iter().filter(|p| !old.contains(p)).cloned().collect(),
+            removed: old.iter().filter(|p| !new.contains(p)).cloned().collect(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Keeps the peers of a book current, reading it again every `interval`,
+/// and tells subscribers which peers came and went. Clones share the peers and subscribers
+#[cfg(feature = "tokio")]
+#[derive(Clone)]
+pub struct AddressBookManager {
+    source: Arc<dyn AddressBookSource>,
+    peers: Arc<Mutex<Vec<NodeIDIPPort>>>,
+    changes: tokio::sync::broadcast::Sender<PeerChanges>,
+    pub interval: Duration,
+}
+#[cfg(feature = "tokio")]
+impl AddressBookManager {
+    pub fn create<S: AddressBookSource + 'static>(source: S, interval: Duration) -> Self {
+        AddressBookManager {
+            source: Arc::new(source),
+            peers: Default::default(),
+            changes: tokio::sync::broadcast::channel(16).0,
+            interval,
+        }
+    }
+    /// the peers as of the last refresh, most recently reached first. empty until then
+    pub fn peers(&self) -> Vec<NodeIDIPPort> {
+        self.peers.lock().unwrap().clone()
+    }
+    /// the changes of every refresh from now on that changes anything.
+    /// the first refresh adds every peer. a subscriber that falls 16 behind loses the oldest
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<PeerChanges> {
+        self.changes.subscribe()
+    }
+    /// read the book now, and tell subscribers what changed
+    pub async fn refresh(&self) -> Result<PeerChanges, TerraRustAPIError> {
+        let peers = AddressBook::load(self.source.as_ref()).await?.peers();
+        let changes = {
+            let mut current = self.peers.lock().unwrap();
+            let changes = PeerChanges::between(&current, &peers);
+            *current = peers;
+            changes
+        };
+        if !changes.is_empty() {
+            log::info!(
+                "address book {}: {} peers added, {} removed",
+                self.source.location(),
+                changes.added.len(),
+                changes.removed.len()
+            );
+            // no subscribers isn't an error
+            let _ = self.changes.send(changes.clone());
+        }
+        Ok(changes)
+    }
+    /// refresh every `interval` in the background. a failed refresh keeps the peers it had.
+    /// abort the handle to stop
+    pub fn spawn(&self) -> tokio::task::JoinHandle<()> {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = manager.refresh().await {
+                    log::warn!(
+                        "address book {} not refreshed: {}",
+                        manager.source.location(),
+                        e
+                    );
+                }
+                tokio::time::sleep(manager.interval).await;
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tst {
+    use super::*;
+
+    #[tokio::test]
+    pub async fn test_alive_peers() -> anyhow::Result<()> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port() as usize;
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let closed_port = closed.local_addr()?.port() as usize;
+        drop(closed);
+        let addr = |id: &str, port: usize, last_success: &str| -> anyhow::Result<NodeAddr> {
+            let addr = NodeIDIPPort {
+                id: id.into(),
+                ip: "127.0.0.1".into(),
+                port,
+            };
+            Ok(NodeAddr {
+                addr: addr.clone(),
+                src: addr,
+                buckets: vec![],
+                attempts: 0,
+                last_attempt: Utc::now(),
+                last_success: last_success.parse()?,
+                last_ban_time: Utc::now(),
+            })
+        };
+        let book = AddressBook {
+            key: "".into(),
+            addrs: vec![
+                addr("dead", closed_port, "2022-01-02T00:00:00Z")?,
+                addr("live", port, "2022-01-01T00:00:00Z")?,
+                addr("live", port, "2021-01-01T00:00:00Z")?,
+            ],
+        };
+        let peers = book.peers();
+        assert_eq!(peers.len(), 2);
+        assert_eq!(peers[0].id, "dead");
+        let alive = book.alive_peers(Duration::from_secs(2), 4).await;
+        assert_eq!(
+            AddressBook::persistent_peers(&alive),
+            format!("live@127.0.0.1:{}", port)
+        );
+        Ok(())
+    }
+
+    /// hands out the books it's given, one per fetch, then fails
+    struct Books(Mutex<Vec<Vec<u8>>>);
+    impl AddressBookSource for Books {
+        fn fetch(&self) -> BoxFuture<'_, Result<Vec<u8>, TerraRustAPIError>> {
+            let book = self.0.lock().unwrap().pop();
+            Box::pin(async move { book.ok_or_else(|| TerraRustAPIError::StdErr("gone".into())) })
+        }
+        fn location(&self) -> String {
+            "books".into()
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_manager() -> anyhow::Result<()> {
+        let peer = |id: &str| NodeIDIPPort {
+            id: id.into(),
+            ip: "10.0.0.1".into(),
+            port: 26656,
+        };
+        let book = |ids: &[&str]| -> anyhow::Result<Vec<u8>> {
+            let addrs = ids
+                .iter()
+                .map(|id| NodeAddr {
+                    addr: peer(id),
+                    src: peer(id),
+                    buckets: vec![],
+                    attempts: 0,
+                    last_attempt: Utc::now(),
+                    last_success: Utc::now(),
+                    last_ban_time: Utc::now(),
+                })
+                .collect();
+            let book = AddressBook {
+                key: "k".into(),
+                addrs,
+            };
+            Ok(serde_json::to_vec(&book)?)
+        };
+        let books = Books(Mutex::new(vec![
+            book(&["b", "c"])?,
+            book(&["a", "b"])?,
+            book(&["a", "b"])?,
+        ]));
+        let manager = AddressBookManager::create(books, Duration::from_secs(60));
+        let mut subscriber = manager.subscribe();
+        assert_eq!(manager.refresh().await?.added.len(), 2);
+        assert!(manager.refresh().await?.is_empty());
+        let changes = manager.refresh().await?;
+        assert_eq!(
+            changes,
+            PeerChanges {
+                added: vec![peer("c")],
+                removed: vec![peer("a")],
+            }
+        );
+        assert_eq!(subscriber.recv().await?.added.len(), 2);
+        assert_eq!(subscriber.recv().await?, changes);
+        assert!(subscriber.try_recv().is_err());
+        assert!(manager.refresh().await.is_err());
+        assert_eq!(manager.peers().len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_sources() -> anyhow::Result<()> {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+        let json = std::fs::read("resources/addressbook.json")?;
+        let book = AddressBook::parse(&json)?;
+        assert!(!book.addrs.is_empty());
+        let mut gz = GzEncoder::new(vec![], flate2::Compression::default());
+        gz.write_all(&json)?;
+        let path = std::env::temp_dir().join(format!("addrbook-{}.json.gz", std::process::id()));
+        std::fs::write(&path, gz.finish()?)?;
+        let location = format!("file://{}", path.display());
+        let gzipped = crate::Terra::address_book(&location).await;
+        std::fs::remove_file(&path)?;
+        let gzipped = gzipped?;
+        assert_eq!(gzipped.key, book.key);
+        assert_eq!(gzipped.addrs.len(), book.addrs.len());
+
+        assert!(AddressBook::load(&FileSource(path.clone())).await.is_err());
+        #[cfg(feature = "embedded-addressbook")]
+        {
+            let embedded = AddressBook::load(&*source_for("embedded:")).await?;
+            assert_eq!(embedded.key, book.key);
+            let fallback = AddressBook::load_or_embedded(&FileSource(path)).await;
+            assert_eq!(fallback.key, book.key);
+        }
+
+        // a gzip bomb stops at the limit
+        let mut gz = GzEncoder::new(vec![], flate2::Compression::best());
+        gz.write_all(&vec![b' '; MAX_ADDRESS_BOOK_BYTES as usize + 1])?;
+        assert!(matches!(
+            AddressBook::parse(&gz.finish()?),
+            Err(TerraRustAPIError::AddressBookTooLarge(_))
+        ));
+        assert!(HttpSource::create("http://localhost")
+            .with_header("x-api-key", "k")
+            .is_ok());
+        assert!(HttpSource::create("http://localhost")
+            .with_header("bad header", "k")
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+/*!
+ * Keep a record of everything a client signs.
+ *
+ * Give a [crate::Terra] client an [AuditSink] with [crate::Terra::with_audit_sink], and every transaction it signs
+ * is recorded as a [SigningRecord]: who signed what, on which chain, at which sequence, and the txhash it got.
+ * [CallbackAuditSink] hands the records to your own async function, [JsonLinesAuditSink] appends them to a file.
+ *
+ * The `submit_transaction_*` helpers record a transaction once it is broadcast. A sink that fails then is only logged,
+ * as failing a transaction that was sent would have it sent again. [crate::Terra::generate_transaction_to_broadcast]
+ * records before handing the signature out, and fails if the sink does.
+ */
+use crate::client::client_types::terra_datetime_format;
+use crate::core_types::StdSignMsg;
+use crate::errors::TerraRustAPIError;
+use chrono::{DateTime, Utc};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use futures::channel::oneshot;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// one signed transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SigningRecord {
+    #[serde(with = "terra_datetime_format")]
+    pub timestamp: DateTime<Utc>,
+    pub chain_id: String,
+    /// the account that signed
+    pub signer: String,
+    pub account_number: u64,
+    pub sequence: u64,
+    /// hex sha256 of the bytes signed
+    pub payload_digest: String,
+    /// None if it wasn't broadcast, or the broadcast failed
+    pub txhash: Option<String>,
+}
+impl SigningRecord {
+    /// the record of `signer` signing `std_sign_msg`
+    pub fn create(
+        std_sign_msg: &StdSignMsg,
+        signer: &str,
+        txhash: Option<String>,
+    ) -> Result<SigningRecord, TerraRustAPIError> {
+        Ok(SigningRecord {
+            timestamp: Utc::now(),
+            chain_id: std_sign_msg.chain_id.clone(),
+            signer: signer.into(),
+            account_number: std_sign_msg.account_number,
+            sequence: std_sign_msg.sequence,
+            payload_digest: SigningRecord::digest(std_sign_msg)?,
+            txhash,
+        })
+    }
+    /// hex sha256 of the JSON a [StdSignMsg] is signed as
+    pub fn digest(std_sign_msg: &StdSignMsg) -> Result<String, TerraRustAPIError> {
+        let mut sha = Sha256::new();
+        sha.input_str(&serde_json::to_string(std_sign_msg)?);
+        Ok(sha.result_str())
+    }
+}
+
+/// somewhere to keep [SigningRecord]s
+pub trait AuditSink: Send + Sync {
+    fn record<'a>(&'a self, record: &'a SigningRecord) -> BoxFuture<'a, anyhow::Result<()>>;
+}
+
+/// calls an async function with each record
+pub struct CallbackAuditSink<F>
+where
+    F: Fn(SigningRecord) -> BoxFuture<'static, anyhow::Result<()>> + Send + Sync,
+{
+    callback: F,
+}
+impl<F> CallbackAuditSink<F>
+where
+    F: Fn(SigningRecord) -> BoxFuture<'static, anyhow::Result<()>> + Send + Sync,
+{
+    pub fn create(callback: F) -> CallbackAuditSink<F> {
+        CallbackAuditSink { callback }
+    }
+}
+impl<F> AuditSink for CallbackAuditSink<F>
+where
+    F: Fn(SigningRecord) -> BoxFuture<'static, anyhow::Result<()>> + Send + Sync,
+{
+    fn record<'a>(&'a self, record: &'a SigningRecord) -> BoxFuture<'a, anyhow::Result<()>> {
+        (self.callback)(record.clone())
+    }
+}
+
+/// appends each record to a file as a line of JSON, and syncs it to disk.
+/// the writing happens on a thread of its own, so recording doesn't block the executor;
+/// dropping the sink waits for it to finish what it was sent
+pub struct JsonLinesAuditSink {
+    sink: Option<Sender<AuditLine>>,
+    thread: Option<JoinHandle<()>>,
+}
+/// a line to write, and where to say how it went
+type AuditLine = (Vec<u8>, oneshot::Sender<std::io::Result<()>>);
+impl JsonLinesAuditSink {
+    /// append to `path`, creating it if need be
+    pub fn to_file<P: AsRef<Path>>(path: P) -> Result<JsonLinesAuditSink, TerraRustAPIError> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let (sink, lines) = channel::<AuditLine>();
+        let thread = std::thread::spawn(move || {
+            for (line, done) in lines {
+                done.send(file.write_all(&line).and_then(|_| file.sync_data()))
+                    .ok();
+            }
+        });
+        Ok(JsonLinesAuditSink {
+            sink: Some(sink),
+            thread: Some(thread),
+        })
+    }
+}
+impl Drop for JsonLinesAuditSink {
+    fn drop(&mut self) {
+        // the writer stops once the channel is closed
+        self.sink.take();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+impl AuditSink for JsonLinesAuditSink {
+    fn record<'a>(&'a self, record: &'a SigningRecord) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let mut line = serde_json::to_vec(record)?;
+            line.push(b'\n');
+            let (done, written) = oneshot::channel();
+            self.sink
+                .as_ref()
+                .and_then(|sink| sink.send((line, done)).ok())
+                .ok_or_else(|| anyhow::anyhow!("the audit log writer has stopped"))?;
+            written
+                .await
+                .map_err(|_| anyhow::anyhow!("the audit log writer has stopped"))??;
+            Ok(())
+        })
+    }
+}
+
+/// keep the record, logging any failure
+pub(crate) async fn record_logged(sink: &Option<Arc<dyn AuditSink>>, record: SigningRecord) {
+    if let Some(sink) = sink {
+        if let Err(e) = sink.record(&record).await {
+            log::error!("audit record {:?} failed: {}", record, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::core_types::{Coin, StdFee};
+    use rust_decimal_macros::dec;
+
+    fn sign_msg() -> StdSignMsg {
+        StdSignMsg {
+            chain_id: "bombay-12".into(),
+            account_number: 43045,
+            sequence: 3,
+            fee: StdFee::create_single(Coin::create("uluna", dec!(50000)), 90000),
+            msgs: vec![],
+            memo: "audit".into(),
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_json_lines() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("terra-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = JsonLinesAuditSink::to_file(&path)?;
+        let record = SigningRecord::create(
+            &sign_msg(),
+            "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7",
+            Some("ABC".into()),
+        )?;
+        assert_eq!(record.payload_digest.len(), 64);
+        assert_eq!(record.payload_digest, SigningRecord::digest(&sign_msg())?);
+        sink.record(&record).await?;
+        sink.record(&SigningRecord {
+            txhash: None,
+            ..record.clone()
+        })
+        .await?;
+        let lines = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(&path)?;
+        let records = lines
+            .lines()
+            .map(serde_json::from_str::<SigningRecord>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].sequence, 3);
+        assert_eq!(records[0].txhash.as_deref(), Some("ABC"));
+        assert_eq!(records[1].txhash, None);
+
+        // records from many tasks at once each land on a line of their own
+        let sink = Arc::new(JsonLinesAuditSink::to_file(&path)?);
+        let tasks = (0..16)
+            .map(|sequence| {
+                let sink = sink.clone();
+                let record = SigningRecord {
+                    sequence,
+                    ..record.clone()
+                };
+                tokio::spawn(async move { sink.record(&record).await })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await??;
+        }
+        drop(sink);
+        let lines = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(&path)?;
+        let mut sequences = lines
+            .lines()
+            .map(|line| serde_json::from_str::<SigningRecord>(line).map(|r| r.sequence))
+            .collect::<Result<Vec<_>, _>>()?;
+        sequences.sort_unstable();
+        assert_eq!(sequences, (0..16).collect::<Vec<_>>());
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
 // use crate::errors::{ErrorKind, Result};
-use crate::client::tx_types::{TXResultAsync, TXResultSync, TxFeeResult};
-use crate::core_types::{Coin, StdFee, StdSignMsg, StdSignature};
-use reqwest::header::{HeaderMap, CONTENT_TYPE, USER_AGENT};
-use reqwest::{Client, RequestBuilder};
+#[cfg(feature = "tx")]
+use crate::client::tx_types::{TXResultAsync, TXResultSync, TxFeeResult, V1TXResult};
+use crate::core_types::Coin;
+#[cfg(feature = "tx")]
+use crate::core_types::{StdFee, StdSignMsg, StdSignature, StdTx};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, USER_AGENT,
+};
+use reqwest::{Client, RequestBuilder, StatusCode};
 use serde::{Deserialize, Serialize};
 
 pub mod auth;
@@ -10,21 +15,80 @@ pub mod auth;
 pub mod auth_types;
 /// APIs around bank module (get balances)
 pub mod bank;
+/// Structures used for the bank module's denom metadata
+pub mod bank_types;
+/// run many queries with bounded concurrency
+pub mod batch;
+/// configure a client from code or the environment
+pub mod builder;
+/// stopping requests part way
+pub mod cancel;
+/// clients for several chains at once
+pub mod chain_manager;
 /// JSON Serializer/Deserializer helpers
 pub mod client_types;
+/// showing coins in display units
+pub mod coin_format;
 /// Common Structures throughout the library
 pub mod core_types;
+/// cw20 token contract helpers
+#[cfg(feature = "wasm")]
+pub mod cw20;
+/// Structures used for cw20 token contracts
+#[cfg(feature = "wasm")]
+pub mod cw20_types;
+/// symbols & exponents of denoms
+pub mod denom_registry;
+/// raising the fee of transactions that aren't being committed
+#[cfg(feature = "tx")]
+pub mod escalation;
+/// conditions on transaction events, for searches
+pub mod event_query;
+#[cfg(feature = "fcd")]
 pub mod fcd;
+/// Structures used for FCD market prices
+#[cfg(feature = "fcd")]
+pub mod fcd_types;
+/// fee & gas arithmetic, rounded the way the chain checks it
+pub mod fees;
+/// conditional requests for rarely changing responses
+pub mod http_cache;
+/// following IBC transfers to their acknowledgement or timeout
+#[cfg(feature = "tx")]
+pub mod ibc;
+/// Structures used for IBC packets
+pub mod ibc_types;
 pub mod lcd_types;
+/// talk to a node on the same host over its unix domain socket
+#[cfg(all(unix, feature = "tokio"))]
+pub mod local;
 /// APIs around market operations (swap)
 pub mod market;
+/// Structures used for market swaps
+pub mod market_types;
+/// the memo transactions are signed with
+pub mod memo;
 /// APIs to perform oracle related things
 pub mod oracle;
 /// Structures used for Oracle APIs
 pub mod oracle_types;
+/// falling back when the LCD has pruned the height asked for
+pub mod pruned;
+/// signing each request, for gateways that authenticate them
+pub mod request_signing;
 /// tendermint RPC
+#[cfg(feature = "rpc")]
 pub mod rpc;
+#[cfg(feature = "rpc")]
 pub mod rpc_types;
+/// what clones of the client share: sequences, endpoint health & gas prices
+pub mod shared;
+/// validator signing info & jailing
+pub mod slashing;
+/// Structures used for slashing
+pub mod slashing_types;
+/// run several queries against the same block
+pub mod snapshot;
 /// staking routines
 pub mod staking;
 /// Structures used for Staking APIs
@@ -33,25 +97,80 @@ pub mod staking_types;
 pub mod tendermint;
 /// Structures used for Tendermint / Misc APIs
 pub mod tendermint_types;
+/// the stability tax
+pub mod treasury;
+/// Structures used for the stability tax
+pub mod treasury_types;
 /// operations around the transaction itself
+#[cfg(feature = "tx")]
 pub mod tx;
+/// queueing many transactions from one key
+#[cfg(feature = "tx")]
+pub mod tx_queue;
 /// Structures used for sending transactions to LCD
 pub mod tx_types;
+/// strict/lenient handling of fields we don't know about
+pub mod unknown_fields;
+/// software upgrade plans
+pub mod upgrade;
+/// Structures used for upgrade plans
+pub mod upgrade_types;
 /// wasm module/contract related apis
+#[cfg(feature = "wasm")]
 pub mod wasm;
+/// typed events of common contract standards
+pub mod wasm_events;
+#[cfg(feature = "wasm")]
 pub mod wasm_types;
+/// every request and response, written to a file, and played back from one
+pub mod wire_log;
 
+use crate::addressbook::source_for;
+#[cfg(feature = "tx")]
+use crate::audit::{record_logged, AuditSink, SigningRecord};
+#[cfg(feature = "tx")]
 use crate::auth_types::AuthAccount;
-use crate::errors::TerraRustAPIError;
-use crate::errors::TerraRustAPIError::{GasPriceError, TxResultError};
+#[cfg(feature = "rpc")]
+use crate::client::rpc_types::{RPCConsensusParams, RPCResult};
+use crate::client::tendermint_types::NodeInfoResult;
+#[cfg(feature = "fcd")]
+use crate::errors::TerraRustAPIError::GasPriceError;
+#[cfg(feature = "tx")]
+use crate::errors::TerraRustAPIError::TxResultError;
+use crate::errors::{RequestContext, TerraRustAPIError};
+#[cfg(feature = "tx")]
 use crate::messages::Message;
+#[cfg(feature = "tx")]
+use crate::notify::{notify, TxEvent, TxNotifier};
+use crate::runtime::Timer;
+use crate::AddressBook;
+#[cfg(feature = "tx")]
+use crate::LCDResult;
+#[cfg(feature = "tx")]
 use crate::PrivateKey;
-use crate::{AddressBook, LCDResult};
+#[cfg(feature = "tx")]
+use escalation::FeeEscalation;
+use http_cache::HttpCache;
+#[cfg(feature = "tx")]
+use memo::MemoContext;
+use memo::MemoPolicy;
+use pruned::{HeightSubstitution, PrunedHeights};
+use request_signing::RequestSigner;
+use unknown_fields::UnknownFields;
+use wire_log::{Replay, WireLog, WireLogEntry};
 
+use chrono::Utc;
+use rust_decimal::Decimal;
+#[cfg(feature = "tx")]
 use rust_decimal_macros::dec;
+#[cfg(feature = "tx")]
 use secp256k1::Secp256k1;
+#[cfg(feature = "tx")]
 use secp256k1::Signing;
-use std::fs::File;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Version # of package sent out on requests to help with debugging
 const VERSION: Option<&'static str> = option_env!("CARGO_PKG_VERSION");
@@ -64,46 +183,85 @@ const NETWORK_TEST_ADDRESS_BOOK: &str =
 
 /// When Submitting transactions you need to either submit gas or a fee to the validator
 /// This structure is used to determine what your preferences are by default
-/// Higher fees may be given preference by the validator to include the transaction in their block
+/// Higher fees may be given preference by the validator to include the transaction in their block.
+/// Build it with [GasOptions::builder], which turns away settings that don't go together
 #[derive(Clone, Debug)]
 pub struct GasOptions {
     /// If specified the TX will use the fee specified
-    pub fees: Option<Coin>,
+    pub(crate) fees: Option<Coin>,
     /// if true, the server will call the 'estimate_transaction' to get an estimate.
     /// This estimate is then multiplied by the gas_adjustment field
-    pub estimate_gas: bool,
+    pub(crate) estimate_gas: bool,
     /// your estimate of the gas to use.
-    pub gas: Option<u64>,
+    pub(crate) gas: Option<u64>,
     /// used to calculate the fee .. gas * gas_price
-    pub gas_price: Option<Coin>,
+    pub(crate) gas_price: Option<Coin>,
     /// used to adjust the estimate
-    pub gas_adjustment: Option<f64>,
+    pub(crate) gas_adjustment: Option<f64>,
+    /// transactions whose fee, in this coin's denom, is more than this are not sent
+    pub(crate) max_fee: Option<Coin>,
+    /// add the stability tax on the coins a transaction sends to the fixed fees
+    pub(crate) add_tax: bool,
 }
 impl GasOptions {
+    /// settings checked for consistency as they are built
+    /// ```
+    /// use terra_rust_api::GasOptions;
+    /// let gas_options = GasOptions::builder()
+    ///     .gas_price("0.15uusd")
+    ///     .adjustment(1.4)
+    ///     .max_fee("5000000uusd")
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(gas_options.estimate_gas());
+    /// assert!(GasOptions::builder().fees("5uusd").estimate_gas().build().is_err());
+    /// ```
+    pub fn builder() -> GasOptionsBuilder {
+        GasOptionsBuilder::default()
+    }
+    /// the fixed fee the transaction pays, if any
+    pub fn fees(&self) -> Option<&Coin> {
+        self.fees.as_ref()
+    }
+    /// whether the LCD is asked how much gas each transaction needs
+    pub fn estimate_gas(&self) -> bool {
+        self.estimate_gas
+    }
+    /// the fixed amount of gas, if any
+    pub fn gas(&self) -> Option<u64> {
+        self.gas
+    }
+    /// the price of a unit of gas, if the fee is worked out from one
+    pub fn gas_price(&self) -> Option<&Coin> {
+        self.gas_price.as_ref()
+    }
+    /// what the gas estimate is multiplied by
+    pub fn gas_adjustment(&self) -> Option<f64> {
+        self.gas_adjustment
+    }
+    /// the most a transaction's fee may be, in this coin's denom
+    pub fn max_fee(&self) -> Option<&Coin> {
+        self.max_fee.as_ref()
+    }
+    /// whether the stability tax on the coins sent is added to the fixed fees
+    pub fn add_tax(&self) -> bool {
+        self.add_tax
+    }
     /// for hard-coding of fees
     pub fn create_with_fees(fees: &str, gas: u64) -> Result<GasOptions, TerraRustAPIError> {
-        Ok(GasOptions {
-            fees: Coin::parse(fees)?,
-            estimate_gas: false,
-            gas: Some(gas),
-            gas_price: None,
-            gas_adjustment: None,
-        })
+        GasOptions::builder().fees(fees).gas(gas).build()
     }
     /// for when you want the validator to give you an estimate on the amounts
-
     pub fn create_with_gas_estimate(
         gas_price: &str,
         gas_adjustment: f64,
     ) -> Result<GasOptions, TerraRustAPIError> {
-        Ok(GasOptions {
-            fees: None,
-            estimate_gas: true,
-            gas: None,
-            gas_price: Coin::parse(gas_price)?,
-            gas_adjustment: Some(gas_adjustment),
-        })
+        GasOptions::builder()
+            .gas_price(gas_price)
+            .adjustment(gas_adjustment)
+            .build()
     }
+    #[cfg(feature = "fcd")]
     pub async fn create_with_fcd(
         client: &reqwest::Client,
         fcd_url: &str,
@@ -113,18 +271,365 @@ impl GasOptions {
         let prices = fcd::FCD::fetch_gas_prices(client, fcd_url).await?;
         if let Some(price) = prices.get(gas_denom) {
             let gas_coin = Coin::create(gas_denom, *price);
-            let gas_price = Some(gas_coin);
-            Ok(GasOptions {
-                fees: None,
-                estimate_gas: true,
-                gas: None,
-                gas_price,
-                gas_adjustment: Some(gas_adjustment),
-            })
+            GasOptions::builder()
+                .gas_price(gas_coin.to_string())
+                .adjustment(gas_adjustment)
+                .build()
         } else {
             Err(GasPriceError(gas_denom.into()))
         }
     }
+    /// the same options paying `price` for gas. fixed fees are worked out again for their gas.
+    /// fails if [GasOptions::max_fee] is in another denom, as the fee would go unchecked
+    pub fn at_gas_price(&self, price: Coin) -> Result<GasOptions, TerraRustAPIError> {
+        if let Some(max_fee) = &self.max_fee {
+            if max_fee.denom != price.denom {
+                return Err(TerraRustAPIError::InvalidGasOptions(format!(
+                    "the max fee is in {}, but fees are paid in {}",
+                    max_fee.denom, price.denom
+                )));
+            }
+        }
+        let mut options = self.clone();
+        match (&self.fees, self.gas) {
+            (Some(_), Some(gas)) => options.fees = Some(fees::fee_for_gas(&price, gas)),
+            _ => options.gas_price = Some(price),
+        }
+        Ok(options)
+    }
+    /// fails with [TerraRustAPIError::FeeOverMax] if the fee pays more than [GasOptions::max_fee]
+    pub fn check_fee(&self, fee: &[Coin]) -> Result<(), TerraRustAPIError> {
+        if let Some(max_fee) = &self.max_fee {
+            if let Some(coin) = fee.iter().find(|c| c.denom == max_fee.denom) {
+                if coin.amount > max_fee.amount {
+                    return Err(TerraRustAPIError::FeeOverMax(
+                        coin.to_string(),
+                        max_fee.to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builds [GasOptions], rejecting combinations that would only fail when the transaction is broadcast.
+/// Either the fees are fixed, with a fixed amount of gas, or they are worked out from a gas price
+/// and a fixed or estimated amount of gas. With only a gas price, the gas is estimated
+#[derive(Clone, Debug, Default)]
+pub struct GasOptionsBuilder {
+    fees: Option<String>,
+    estimate_gas: bool,
+    gas: Option<u64>,
+    gas_price: Option<String>,
+    gas_adjustment: Option<f64>,
+    max_fee: Option<String>,
+    add_tax: bool,
+}
+impl GasOptionsBuilder {
+    /// pay exactly this, eg. 30000uluna. needs [GasOptionsBuilder::gas]
+    pub fn fees<S: Into<String>>(mut self, fees: S) -> Self {
+        self.fees = Some(fees.into());
+        self
+    }
+    /// ask the LCD how much gas the transaction needs, each time
+    pub fn estimate_gas(mut self) -> Self {
+        self.estimate_gas = true;
+        self
+    }
+    /// use this much gas
+    pub fn gas(mut self, gas: u64) -> Self {
+        self.gas = Some(gas);
+        self
+    }
+    /// the price of a unit of gas, eg. 0.15uusd
+    pub fn gas_price<S: Into<String>>(mut self, gas_price: S) -> Self {
+        self.gas_price = Some(gas_price.into());
+        self
+    }
+    /// what the gas estimate is multiplied by
+    pub fn adjustment(mut self, gas_adjustment: f64) -> Self {
+        self.gas_adjustment = Some(gas_adjustment);
+        self
+    }
+    /// the most to pay in fees, eg. 5000000uusd
+    pub fn max_fee<S: Into<String>>(mut self, max_fee: S) -> Self {
+        self.max_fee = Some(max_fee.into());
+        self
+    }
+    /// add the stability tax on the coins sent to fixed fees. an estimate already includes it
+    pub fn add_tax(mut self) -> Self {
+        self.add_tax = true;
+        self
+    }
+    pub fn build(self) -> Result<GasOptions, TerraRustAPIError> {
+        let invalid = |reason: String| Err(TerraRustAPIError::InvalidGasOptions(reason));
+        let parse = |name: &str, coin: &Option<String>| match coin {
+            Some(text) => Coin::parse(text)?
+                .ok_or_else(|| {
+                    TerraRustAPIError::InvalidGasOptions(format!(
+                        "{} `{}` isn't a coin",
+                        name, text
+                    ))
+                })
+                .map(Some),
+            None => Ok(None),
+        };
+        let fees = parse("fees", &self.fees)?;
+        let gas_price = parse("gas price", &self.gas_price)?;
+        let max_fee = parse("max fee", &self.max_fee)?;
+        let estimate_gas = self.estimate_gas || (fees.is_none() && self.gas.is_none());
+
+        if fees.is_some() && estimate_gas {
+            return invalid("fixed fees can't be combined with estimating gas".into());
+        }
+        if estimate_gas && self.gas.is_some() {
+            return invalid("the gas can't be both fixed and estimated".into());
+        }
+        if fees.is_some() && self.gas.is_none() {
+            return invalid("fixed fees need a fixed amount of gas".into());
+        }
+        if fees.is_none() && gas_price.is_none() {
+            return invalid("set either fees or a gas price".into());
+        }
+        if let Some(gas_price) = &gas_price {
+            if gas_price.amount <= Decimal::ZERO {
+                return invalid(format!("the gas price {} isn't positive", gas_price));
+            }
+        }
+        if self.add_tax && estimate_gas {
+            return invalid("the tax is only added to fixed fees".into());
+        }
+        if let Some(gas_adjustment) = self.gas_adjustment {
+            if !estimate_gas {
+                return invalid("a gas adjustment only applies to estimated gas".into());
+            }
+            if !(gas_adjustment.is_finite() && gas_adjustment > 0.0) {
+                return invalid(format!(
+                    "the gas adjustment {} isn't positive",
+                    gas_adjustment
+                ));
+            }
+        }
+        // a fixed amount of gas at a price is a fixed fee
+        let fees = match (fees, &gas_price, self.gas) {
+            (None, Some(price), Some(gas)) => Some(fees::fee_for_gas(price, gas)),
+            (fees, _, _) => fees,
+        };
+        if let Some(max_fee) = &max_fee {
+            let paid_in = fees
+                .as_ref()
+                .or(gas_price.as_ref())
+                .map(|c| c.denom.as_str());
+            if paid_in != Some(max_fee.denom.as_str()) {
+                return invalid(format!(
+                    "the max fee is in {}, but fees are paid in {}",
+                    max_fee.denom,
+                    paid_in.unwrap_or_default()
+                ));
+            }
+        }
+        let gas_options = GasOptions {
+            fees,
+            estimate_gas,
+            gas: self.gas,
+            gas_price,
+            gas_adjustment: self.gas_adjustment,
+            max_fee,
+            add_tax: self.add_tax,
+        };
+        if let Some(fees) = &gas_options.fees {
+            gas_options.check_fee(std::slice::from_ref(fees))?;
+        }
+        Ok(gas_options)
+    }
+}
+
+/// which shape of routes the LCD serves. see [Terra::api_version]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiVersion {
+    /// the terra routes, like `/staking/validators`
+    Legacy,
+    /// the cosmos-sdk gateway routes, like `/cosmos/staking/v1beta1/validators`
+    V1Beta1,
+}
+impl ApiVersion {
+    fn to_u8(self) -> u8 {
+        match self {
+            ApiVersion::Legacy => 1,
+            ApiVersion::V1Beta1 => 2,
+        }
+    }
+    fn from_u8(value: u8) -> Option<ApiVersion> {
+        match value {
+            1 => Some(ApiVersion::Legacy),
+            2 => Some(ApiVersion::V1Beta1),
+            _ => None,
+        }
+    }
+}
+
+/// Settings for the underlying HTTP client.
+/// Bots that poll the LCD benefit from keeping connections alive and compressed responses
+#[derive(Clone, Debug)]
+pub struct HttpOptions {
+    /// ask for gzip/deflate compressed responses, and decompress them
+    pub compression: bool,
+    /// how long an idle connection is kept in the pool. None keeps it forever
+    pub pool_idle_timeout: Option<Duration>,
+    /// maximum number of idle connections kept per host
+    pub pool_max_idle_per_host: usize,
+    /// TCP keep-alive interval. None turns it off
+    pub tcp_keepalive: Option<Duration>,
+    /// ignore any HTTP(S)_PROXY settings
+    pub no_proxy: bool,
+}
+impl Default for HttpOptions {
+    fn default() -> Self {
+        HttpOptions {
+            compression: true,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            pool_max_idle_per_host: usize::MAX,
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            no_proxy: false,
+        }
+    }
+}
+impl HttpOptions {
+    /// for a node on the same host: nothing is compressed, proxied or probed
+    pub fn local() -> HttpOptions {
+        HttpOptions {
+            compression: false,
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: usize::MAX,
+            tcp_keepalive: None,
+            no_proxy: true,
+        }
+    }
+}
+
+/// How big a transaction can be. Ones over the limits are turned away by the node after signing,
+/// with errors that don't say why. See [TxLimits::check]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxLimits {
+    /// the mempool's max_tx_bytes
+    pub max_tx_bytes: u64,
+    /// the consensus params' block max_gas. None for no limit
+    pub max_block_gas: Option<u64>,
+}
+impl Default for TxLimits {
+    /// tendermint's defaults
+    fn default() -> Self {
+        TxLimits {
+            max_tx_bytes: 1024 * 1024,
+            max_block_gas: None,
+        }
+    }
+}
+#[cfg(feature = "rpc")]
+impl TxLimits {
+    /// the limits a chain's consensus params set. the mempool's max_tx_bytes is node config,
+    /// so a transaction is held to the smaller of that default and the block's max_bytes
+    pub fn from_consensus_params(params: &RPCConsensusParams) -> TxLimits {
+        let default = TxLimits::default();
+        TxLimits {
+            max_tx_bytes: default
+                .max_tx_bytes
+                .min(params.consensus_params.block.max_bytes),
+            max_block_gas: params.max_block_gas(),
+        }
+    }
+}
+#[cfg(feature = "tx")]
+impl TxLimits {
+    /// the JSON the LCD is sent is a little bigger than the encoding the node checks, so
+    /// this errs on the side of turning a transaction away
+    pub fn check<S: Serialize>(
+        &self,
+        std_sign_msg: &StdSignMsg,
+        sigs: &[S],
+    ) -> Result<(), TerraRustAPIError> {
+        let std_tx = StdTx::from_StdSignMsg(std_sign_msg, sigs, "sync");
+        let size = serde_json::to_vec(&std_tx.tx)?.len() as u64;
+        if size > self.max_tx_bytes {
+            return Err(TerraRustAPIError::TxTooLarge(size, self.max_tx_bytes));
+        }
+        match self.max_block_gas {
+            Some(max_gas) if std_sign_msg.fee.gas > max_gas => Err(
+                TerraRustAPIError::TxGasOverBlockLimit(std_sign_msg.fee.gas, max_gas),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// does the query string already ask for a height
+fn has_height_param(args: &str) -> bool {
+    args.split(['?', '&'])
+        .any(|param| param.starts_with("height="))
+}
+
+/// How a hosted LCD wants its API key. It is only sent to the LCD, and is masked in the client's logs & errors.
+/// see [Terra::with_api_key]
+#[derive(Clone)]
+pub enum ApiKey {
+    /// `Authorization: Bearer <token>`
+    Bearer(String),
+    /// the key in this header, like `x-api-key`
+    Header(String, String),
+    /// the key in this query parameter, like `apikey`
+    QueryParam(String, String),
+}
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiKey::Bearer(_) => write!(f, "Bearer"),
+            ApiKey::Header(name, _) => write!(f, "Header({})", name),
+            ApiKey::QueryParam(name, _) => write!(f, "QueryParam({})", name),
+        }
+    }
+}
+
+/// how an API key is attached to a request to the LCD
+#[derive(Clone)]
+enum ApiKeyAuth {
+    Header(HeaderName, HeaderValue),
+    QueryParam(String, String),
+}
+
+/// what happens to a request besides it being sent. see [Terra::with_wire_log], [Terra::with_replay]
+/// and [Terra::with_request_signer]
+#[derive(Clone, Copy)]
+struct Wire<'a> {
+    log: Option<&'a WireLog>,
+    replay: Option<&'a Replay>,
+    /// the body sent
+    body: Option<&'a str>,
+    signer: Option<&'a dyn RequestSigner>,
+    /// answers a `304 Not Modified`. see [Terra::send_cmd_cached]
+    cache: Option<&'a HttpCache>,
+    /// masked in what is logged, besides the usual. see [Terra::redact]
+    secrets: &'a [String],
+    #[cfg(all(unix, feature = "tokio"))]
+    unix_socket: Option<&'a local::UnixSocket>,
+}
+impl Wire<'_> {
+    /// send the request, down the unix socket if it is for the node behind it
+    async fn send(
+        wire: Option<Wire<'_>>,
+        request_url: &str,
+        req: RequestBuilder,
+    ) -> Result<reqwest::Response, TerraRustAPIError> {
+        #[cfg(all(unix, feature = "tokio"))]
+        if let Some(socket) = wire.and_then(|wire| wire.unix_socket) {
+            if request_url.starts_with(local::UNIX_SOCKET_HOST) {
+                return socket.send(req).await;
+            }
+        }
+        #[cfg(not(all(unix, feature = "tokio")))]
+        let _ = (wire, request_url);
+        Ok(req.send().await?)
+    }
 }
 
 /// The main structure that all API calls are generated from
@@ -132,6 +637,12 @@ impl GasOptions {
 pub struct Terra {
     /// reqwest Client
     client: Client,
+    /// sent with every request to the LCD. see [Terra::with_user_agent] & [Terra::with_header]
+    headers: HeaderMap,
+    /// added to every request to the LCD, and no other
+    api_key: Option<ApiKeyAuth>,
+    /// masked in this client's logs & errors, like the API key. see [Terra::redact]
+    secrets: Vec<String>,
     /// The URL of the LCD
     url: String,
 
@@ -140,6 +651,52 @@ pub struct Terra {
     /// Gas Options used to help with gas/fee generation of transactions
     pub gas_options: Option<GasOptions>,
     pub debug: bool,
+    /// debug logging, with secrets and sensitive query parameters masked. see [crate::redact]
+    pub debug_redacted: bool,
+    /// what to do with response fields our structures don't have
+    pub unknown_fields: UnknownFields,
+    /// check the chain_id against the LCD's before signing the first transaction
+    pub check_chain_id: bool,
+    /// sign messages without running [Message::validate] on them first
+    pub skip_validation: bool,
+    /// transactions over these are not broadcast
+    pub tx_limits: TxLimits,
+    /// what goes in the memo of the transactions signed by [Terra::generate_transaction_to_broadcast]
+    pub memo_policy: MemoPolicy,
+    /// where to go when the LCD has pruned the height a query asks for
+    pub pruned_heights: PrunedHeights,
+    /// what has been learnt about the chain & the LCDs, shared between clones. see [shared]
+    inner: Arc<shared::Inner>,
+    /// queries that don't ask for a height are made at this one. see [snapshot::ConsistentSnapshot]
+    pinned_height: Option<u64>,
+    /// where the queries answered at another height or by another LCD are kept. see [Terra::tracking_substitutions]
+    height_substitutions: Option<Arc<Mutex<Vec<HeightSubstitution>>>>,
+    /// requests fail with [TerraRustAPIError::DeadlineExceeded] once this has passed. see [Terra::with_timeout]
+    deadline: Option<Instant>,
+    /// requests fail with [TerraRustAPIError::Cancelled] once this is cancelled
+    cancel: Option<cancel::CancelToken>,
+    /// what polls sleep with, instead of [crate::runtime::timer]
+    timer: Option<Arc<dyn Timer>>,
+    /// conditional requests for [Terra::send_cmd_cached]. None sends them unconditionally
+    http_cache: Option<HttpCache>,
+    /// where requests and responses are written, if anywhere
+    wire_log: Option<WireLog>,
+    /// recorded responses, answering requests instead of the LCD
+    replay: Option<Replay>,
+    /// the tendermint RPC whose websocket [tx::TX::confirmation] waits on, instead of polling the LCD
+    #[cfg(feature = "websocket")]
+    websocket: Option<String>,
+    /// adds its headers to every request to the LCD
+    request_signer: Option<Arc<dyn RequestSigner>>,
+    /// requests to [local::UNIX_SOCKET_HOST] go down this socket. see [Terra::with_unix_socket]
+    #[cfg(all(unix, feature = "tokio"))]
+    unix_socket: Option<Arc<local::UnixSocket>>,
+    /// told about transactions sent by the `submit_transaction_*` helpers
+    #[cfg(feature = "tx")]
+    notifier: Option<Arc<dyn TxNotifier>>,
+    /// keeps a record of every transaction signed
+    #[cfg(feature = "tx")]
+    audit_sink: Option<Arc<dyn AuditSink>>,
 }
 impl Terra {
     /// Create a LCD client interface
@@ -149,28 +706,457 @@ impl Terra {
         gas_options: &GasOptions,
         debug: Option<bool>,
     ) -> Terra {
-        let client = reqwest::Client::new();
+        let client = Terra::http_client();
         Terra {
             client,
+            headers: Terra::construct_headers(),
+            api_key: None,
+            secrets: vec![],
             url: url.into(),
             chain_id: chain_id.into(),
             gas_options: Some(gas_options.clone()),
             debug: debug.unwrap_or(false),
+            debug_redacted: false,
+            unknown_fields: UnknownFields::default(),
+            check_chain_id: false,
+            skip_validation: false,
+            tx_limits: TxLimits::default(),
+            memo_policy: MemoPolicy::default(),
+            pruned_heights: PrunedHeights::default(),
+            inner: Arc::default(),
+            pinned_height: None,
+            height_substitutions: None,
+            deadline: None,
+            cancel: None,
+            timer: None,
+            http_cache: None,
+            wire_log: None,
+            replay: None,
+            #[cfg(feature = "websocket")]
+            websocket: None,
+            request_signer: None,
+            #[cfg(all(unix, feature = "tokio"))]
+            unix_socket: None,
+            #[cfg(feature = "tx")]
+            notifier: None,
+            #[cfg(feature = "tx")]
+            audit_sink: None,
         }
     }
 
     /// Create a read-only / query client interface
     pub fn lcd_client_no_tx<S: Into<String>>(url: S, chain_id: S) -> Terra {
-        let client = reqwest::Client::new();
+        let client = Terra::http_client();
         Terra {
             client,
+            headers: Terra::construct_headers(),
+            api_key: None,
+            secrets: vec![],
             url: url.into(),
             chain_id: chain_id.into(),
             gas_options: None,
             debug: false,
+            debug_redacted: false,
+            unknown_fields: UnknownFields::default(),
+            check_chain_id: false,
+            skip_validation: false,
+            tx_limits: TxLimits::default(),
+            memo_policy: MemoPolicy::default(),
+            pruned_heights: PrunedHeights::default(),
+            inner: Arc::default(),
+            pinned_height: None,
+            height_substitutions: None,
+            deadline: None,
+            cancel: None,
+            timer: None,
+            http_cache: None,
+            wire_log: None,
+            replay: None,
+            #[cfg(feature = "websocket")]
+            websocket: None,
+            request_signer: None,
+            #[cfg(all(unix, feature = "tokio"))]
+            unix_socket: None,
+            #[cfg(feature = "tx")]
+            notifier: None,
+            #[cfg(feature = "tx")]
+            audit_sink: None,
         }
     }
 
+    /// replace the HTTP client with one using these settings
+    pub fn with_http_options(mut self, http_options: &HttpOptions) -> Terra {
+        self.client = Terra::http_client_with(http_options);
+        self
+    }
+    /// keep responses that carry an ETag/Last-Modified, and ask for them again conditionally. see [HttpCache]
+    pub fn with_http_cache(mut self) -> Terra {
+        self.http_cache = Some(HttpCache::create());
+        self
+    }
+    /// append every request and its response to `wire_log`. see [WireLog]
+    pub fn with_wire_log(mut self, wire_log: WireLog) -> Terra {
+        self.wire_log = Some(wire_log);
+        self
+    }
+    /// identify the application to the LCD, instead of with the `PFC-terra-rust-api/version` user agent.
+    /// some hosted LCDs attribute quota by it
+    pub fn with_user_agent(self, user_agent: &str) -> Result<Terra, TerraRustAPIError> {
+        self.with_header(USER_AGENT.as_str(), user_agent)
+    }
+    /// send this header with every request to the LCD, replacing any default of the same name
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Terra, TerraRustAPIError> {
+        let invalid = || TerraRustAPIError::Config(format!("header {}", name), value.into());
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+        let value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+    /// authenticate with a hosted LCD on every request to it. requests to other hosts, like the FCD,
+    /// an RPC or an archive LCD, go without. the key is masked in this client's logs & errors
+    pub fn with_api_key(mut self, api_key: ApiKey) -> Result<Terra, TerraRustAPIError> {
+        let (auth, secret) = match api_key {
+            ApiKey::Bearer(token) => (
+                Terra::api_key_header(AUTHORIZATION.as_str(), &format!("Bearer {}", token))?,
+                token,
+            ),
+            ApiKey::Header(name, key) => (Terra::api_key_header(&name, &key)?, key),
+            ApiKey::QueryParam(name, key) => (ApiKeyAuth::QueryParam(name, key.clone()), key),
+        };
+        self.api_key = Some(auth);
+        self.add_secret(secret);
+        Ok(self)
+    }
+    /// mask this in the client's logs & errors
+    fn add_secret(&mut self, secret: String) {
+        if !secret.is_empty() && !self.secrets.contains(&secret) {
+            self.secrets.push(secret);
+        }
+    }
+    fn api_key_header(name: &str, value: &str) -> Result<ApiKeyAuth, TerraRustAPIError> {
+        let invalid = || TerraRustAPIError::Config(format!("header {}", name), "[REDACTED]".into());
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+        let mut value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+        value.set_sensitive(true);
+        Ok(ApiKeyAuth::Header(name, value))
+    }
+    /// does the URL have the LCD's scheme, host & port
+    fn is_lcd(&self, url: &str) -> bool {
+        match (reqwest::Url::parse(url), reqwest::Url::parse(&self.url)) {
+            (Ok(url), Ok(lcd)) => url.origin() == lcd.origin(),
+            _ => false,
+        }
+    }
+    /// add the API key if the request is to the LCD. it is added to the request only,
+    /// so it stays out of logs, wire logs & recordings
+    fn authenticate(&self, url: &str, req: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(ApiKeyAuth::Header(name, value)) if self.is_lcd(url) => {
+                // header() would clear the value's sensitive flag
+                let mut headers = HeaderMap::new();
+                headers.insert(name.clone(), value.clone());
+                req.headers(headers)
+            }
+            Some(ApiKeyAuth::QueryParam(name, key)) if self.is_lcd(url) => {
+                req.query(&[(name, key)])
+            }
+            _ => req,
+        }
+    }
+    /// mask the secrets in text, including the ones only this client knows about, like its API key.
+    /// see [crate::redact]
+    pub fn redact(&self, text: &str) -> String {
+        crate::redact::redact_with_secrets(text, &self.secrets)
+    }
+    /// sign every request to the LCD just before it is sent, as a gateway in front of it requires.
+    /// requests to other hosts, like the FCD, an RPC or an archive LCD, aren't signed.
+    /// see [request_signing::HmacSigner] & [request_signing::KeySigner]
+    pub fn with_request_signer<S: RequestSigner + 'static>(mut self, signer: S) -> Terra {
+        for secret in signer.secrets() {
+            self.add_secret(secret);
+        }
+        self.request_signer = Some(Arc::new(signer));
+        self
+    }
+    /// answer requests from a recording, instead of sending them. see [Replay]
+    pub fn with_replay(mut self, replay: Replay) -> Terra {
+        self.replay = Some(replay);
+        self
+    }
+    /// wait for transactions on this tendermint RPC's websocket, rather than polling the LCD for them.
+    /// not used while replaying, as recordings only hold LCD traffic
+    #[cfg(feature = "websocket")]
+    pub fn with_websocket(mut self, rpc_url: &str) -> Terra {
+        self.websocket = Some(rpc_url.into());
+        self
+    }
+    /// the log, recording & signer requests to `url` go through, if there are any. `body` is what is sent.
+    /// only requests to the LCD are signed
+    fn wire<'a>(
+        &'a self,
+        url: &str,
+        body: Option<&'a str>,
+        cache: Option<&'a HttpCache>,
+    ) -> Option<Wire<'a>> {
+        let signer = self.request_signer.as_deref().filter(|_| self.is_lcd(url));
+        #[cfg(all(unix, feature = "tokio"))]
+        let unix_socket = self.unix_socket.as_deref();
+        #[cfg(not(all(unix, feature = "tokio")))]
+        let unix_socket: Option<()> = None;
+        if self.wire_log.is_none()
+            && self.replay.is_none()
+            && signer.is_none()
+            && cache.is_none()
+            && unix_socket.is_none()
+        {
+            return None;
+        }
+        Some(Wire {
+            log: self.wire_log.as_ref(),
+            replay: self.replay.as_ref(),
+            body,
+            signer,
+            cache,
+            secrets: &self.secrets,
+            #[cfg(all(unix, feature = "tokio"))]
+            unix_socket,
+        })
+    }
+    /// the response cache, if there is one
+    pub fn http_cache(&self) -> Option<&HttpCache> {
+        self.http_cache.as_ref()
+    }
+    /// the same client, talking to a node's LCD over its unix domain socket
+    #[cfg(all(unix, feature = "tokio"))]
+    pub async fn with_unix_socket<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> Result<Terra, TerraRustAPIError> {
+        let socket = local::UnixSocket::create(path)?;
+        let mut terra = self.with_lcd(local::UNIX_SOCKET_HOST);
+        terra.unix_socket = Some(Arc::new(socket));
+        Ok(terra)
+    }
+    /// the same client, talking to another LCD. the HTTP connection pool is shared
+    pub fn with_lcd<S: Into<String>>(&self, url: S) -> Terra {
+        Terra {
+            url: url.into(),
+            ..self.clone()
+        }
+    }
+    /// the same client, with queries that don't give a height made at `height`
+    pub fn at_height(&self, height: u64) -> Terra {
+        Terra {
+            pinned_height: Some(height),
+            ..self.clone()
+        }
+    }
+    /// the same client, with every request failing with [TerraRustAPIError::DeadlineExceeded] once `timeout` from now
+    /// has passed. for a deadline on one call: `terra.with_timeout(Duration::from_secs(5)).bank().balances(..)`
+    pub fn with_timeout(&self, timeout: Duration) -> Terra {
+        self.with_deadline(Instant::now() + timeout)
+    }
+    /// the same client, with every request failing with [TerraRustAPIError::DeadlineExceeded] after `deadline`.
+    /// an earlier deadline already set is kept
+    pub fn with_deadline(&self, deadline: Instant) -> Terra {
+        Terra {
+            deadline: Some(self.deadline.map_or(deadline, |d| d.min(deadline))),
+            ..self.clone()
+        }
+    }
+    /// the same client, with requests stopped, failing with [TerraRustAPIError::Cancelled],
+    /// once `token` is cancelled. see [cancel]
+    pub fn with_cancel(&self, token: &cancel::CancelToken) -> Terra {
+        Terra {
+            cancel: Some(token.clone()),
+            ..self.clone()
+        }
+    }
+    /// fails if the client has been cancelled or its deadline has passed, with `waiting_on` as what was being waited on
+    pub fn check_live(&self, waiting_on: &str) -> Result<(), TerraRustAPIError> {
+        if matches!(&self.cancel, Some(c) if c.is_cancelled()) {
+            return Err(TerraRustAPIError::Cancelled);
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                Err(TerraRustAPIError::DeadlineExceeded(self.redact(waiting_on)))
+            }
+            _ => Ok(()),
+        }
+    }
+    /// the time left before the deadline
+    fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+    /// sleep with this timer between polls, rather than the [crate::runtime] one
+    pub fn with_timer<T: Timer + 'static>(mut self, timer: T) -> Terra {
+        self.timer = Some(Arc::new(timer));
+        self
+    }
+    /// the timer polls sleep with. see [crate::runtime]
+    pub fn timer(&self) -> Arc<dyn Timer> {
+        self.timer.clone().unwrap_or_else(crate::runtime::timer)
+    }
+    /// wait for `duration` between polls, stopping early if the client is cancelled or
+    /// its deadline comes first
+    pub async fn pause(
+        &self,
+        duration: Duration,
+        waiting_on: &str,
+    ) -> Result<(), TerraRustAPIError> {
+        self.check_live(waiting_on)?;
+        let duration = self.remaining().map_or(duration, |r| r.min(duration));
+        let sleep = self.timer().sleep(duration);
+        match &self.cancel {
+            Some(token) => {
+                let cancelled = token.cancelled();
+                futures::pin_mut!(cancelled);
+                futures::future::select(sleep, cancelled).await;
+            }
+            None => sleep.await,
+        }
+        self.check_live(waiting_on)
+    }
+    /// the request, stopped by cancelling or the deadline. dropping it mid-flight aborts the request
+    async fn bounded<T, F: std::future::Future<Output = Result<T, TerraRustAPIError>>>(
+        &self,
+        url: &str,
+        request: F,
+    ) -> Result<T, TerraRustAPIError> {
+        self.check_live(url)?;
+        let result = match &self.cancel {
+            Some(token) => {
+                futures::pin_mut!(request);
+                let cancelled = token.cancelled();
+                futures::pin_mut!(cancelled);
+                match futures::future::select(request, cancelled).await {
+                    futures::future::Either::Left((result, _)) => result,
+                    futures::future::Either::Right(_) => Err(TerraRustAPIError::Cancelled),
+                }
+            }
+            None => request.await,
+        };
+        // a request timed out by the deadline fails as the deadline passing
+        match result {
+            Err(TerraRustAPIError::Cancelled) => Err(TerraRustAPIError::Cancelled),
+            Err(e) => self.check_live(url).and(Err(e)),
+            ok => ok,
+        }
+    }
+    /// report transactions sent by the `submit_transaction_*` helpers to `notifier`
+    #[cfg(feature = "tx")]
+    pub fn with_notifier<N: TxNotifier + 'static>(mut self, notifier: N) -> Terra {
+        self.notifier = Some(Arc::new(notifier));
+        self
+    }
+    /// record every transaction signed in `audit_sink`. see [crate::audit]
+    #[cfg(feature = "tx")]
+    pub fn with_audit_sink<A: AuditSink + 'static>(mut self, audit_sink: A) -> Terra {
+        self.audit_sink = Some(Arc::new(audit_sink));
+        self
+    }
+    /// the height queries are pinned to, if any
+    pub fn pinned_height(&self) -> Option<u64> {
+        self.pinned_height
+    }
+    /// a clone that keeps the queries it, and its clones, had answered at another height or by another LCD
+    /// than asked, as [PrunedHeights] allows. make one per call to know which queries were. see [Terra::height_substitutions]
+    pub fn tracking_substitutions(&self) -> Terra {
+        Terra {
+            height_substitutions: Some(Arc::default()),
+            ..self.clone()
+        }
+    }
+    /// the substitutions since this was last called. always empty unless the client came from
+    /// [Terra::tracking_substitutions]
+    pub fn height_substitutions(&self) -> Vec<HeightSubstitution> {
+        match &self.height_substitutions {
+            Some(substitutions) => std::mem::take(&mut *substitutions.lock().unwrap()),
+            None => vec![],
+        }
+    }
+    /// how each endpoint the client (or any clone of it) has sent requests to has been answering
+    pub fn endpoint_health(&self) -> Vec<shared::EndpointHealth> {
+        self.inner.health()
+    }
+    /// the sequences of transactions sent from each account, shared between clones
+    pub fn sequences(&self) -> &shared::SequenceManager {
+        &self.inner.sequences
+    }
+
+    /// configure a client, starting from the defaults
+    pub fn builder() -> builder::TerraBuilder {
+        builder::TerraBuilder::default()
+    }
+    /// create a client configured by the TERRARUST_ environment variables. see [builder::TerraBuilder]
+    pub async fn from_env() -> Result<Terra, TerraRustAPIError> {
+        builder::TerraBuilder::from_env()?.build().await
+    }
+
+    /// the chain the LCD is on
+    pub async fn node_chain_id(&self) -> Result<String, TerraRustAPIError> {
+        Ok(self
+            .send_cmd::<NodeInfoResult>("/node_info", None, None)
+            .await?
+            .node_info
+            .network)
+    }
+    /// fail with [TerraRustAPIError::ChainIdMismatch] if our chain_id isn't the LCD's.
+    /// each LCD is only asked once
+    pub async fn verify_chain_id(&self) -> Result<(), TerraRustAPIError> {
+        if self.inner.chain_id_checked(&self.url, &self.chain_id) {
+            return Ok(());
+        }
+        let node_chain_id = self.node_chain_id().await?;
+        if node_chain_id != self.chain_id {
+            return Err(TerraRustAPIError::ChainIdMismatch(
+                self.chain_id.clone(),
+                node_chain_id,
+            ));
+        }
+        self.inner.set_chain_id(&self.url, &node_chain_id);
+        Ok(())
+    }
+
+    /// use these routes, instead of asking the LCD
+    pub fn set_api_version(&self, api_version: ApiVersion) {
+        self.inner.set_api_version(&self.url, api_version.to_u8());
+    }
+    /// the routes the LCD serves. the legacy routes are used when they are there,
+    /// as the response structures follow them. each LCD is asked until it gives a definitive answer:
+    /// a legacy route that answers, or one that isn't there at all.
+    /// other failures, like a 503 from a busy node, are returned and not remembered
+    pub async fn api_version(&self) -> Result<ApiVersion, TerraRustAPIError> {
+        if let Some(api_version) = ApiVersion::from_u8(self.inner.api_version(&self.url)) {
+            return Ok(api_version);
+        }
+        let api_version = match self
+            .send_cmd::<serde_json::Value>("/node_info", None, None)
+            .await
+        {
+            Ok(_) => ApiVersion::Legacy,
+            Err(e) => match e.without_context() {
+                TerraRustAPIError::TerraLCDResponse(
+                    StatusCode::NOT_FOUND | StatusCode::NOT_IMPLEMENTED,
+                    _,
+                ) => {
+                    self.send_cmd::<serde_json::Value>(
+                        "/cosmos/base/tendermint/v1beta1/node_info",
+                        None,
+                        None,
+                    )
+                    .await?;
+                    ApiVersion::V1Beta1
+                }
+                _ => return Err(e),
+            },
+        };
+        log::debug!("LCD {} serves {:?} routes", self.url, api_version);
+        self.set_api_version(api_version);
+        Ok(api_version)
+    }
+
     /// Auth API functions
     pub fn auth(&self) -> auth::Auth {
         auth::Auth::create(self)
@@ -183,6 +1169,10 @@ impl Terra {
     pub fn staking(&self) -> staking::Staking {
         staking::Staking::create(self)
     }
+    /// validator signing info & jailing
+    pub fn slashing(&self) -> slashing::Slashing<'_> {
+        slashing::Slashing::create(self)
+    }
     /// Market API functions
     pub fn market(&self) -> market::Market {
         market::Market::create(self)
@@ -191,26 +1181,95 @@ impl Terra {
     pub fn oracle(&self) -> oracle::Oracle {
         oracle::Oracle::create(self)
     }
+    /// collect queries to run with at most `max_concurrency` in flight
+    pub fn batch<'a, T>(&self, max_concurrency: usize) -> batch::Batch<'a, T> {
+        batch::Batch::create(max_concurrency)
+    }
     /// Tendermint (MISC) API Functions
     pub fn tendermint(&self) -> tendermint::Tendermint {
         tendermint::Tendermint::create(self)
     }
+    /// the stability tax
+    pub fn treasury(&self) -> treasury::Treasury<'_> {
+        treasury::Treasury::create(self)
+    }
+    /// software upgrade plans
+    pub fn upgrade(&self) -> upgrade::Upgrade<'_> {
+        upgrade::Upgrade::create(self)
+    }
     /// TXS API Functions
+    #[cfg(feature = "tx")]
     pub fn tx(&self) -> tx::TX {
         tx::TX::create(self)
     }
+    /// IBC transfers sent from this chain
+    #[cfg(feature = "tx")]
+    pub fn ibc(&self) -> ibc::Ibc<'_> {
+        ibc::Ibc::create(self)
+    }
     /// RPC Api Functions
+    #[cfg(feature = "rpc")]
     pub fn rpc<'a>(&'a self, tendermint_url: &'a str) -> rpc::RPC {
         rpc::RPC::create(self, tendermint_url)
     }
+    /// the [TxLimits] the chain's consensus params set, from a tendermint RPC
+    #[cfg(feature = "rpc")]
+    pub async fn fetch_tx_limits(&self, rpc_url: &str) -> Result<TxLimits, TerraRustAPIError> {
+        let params = self
+            .send_cmd_url::<RPCResult<RPCConsensusParams>>(rpc_url, "/consensus_params", None, None)
+            .await?;
+        Ok(TxLimits::from_consensus_params(&params.result))
+    }
     /// FCD Api Functions
+    #[cfg(feature = "fcd")]
     pub fn fcd<'a>(&'a self, fcd_url: &'a str) -> fcd::FCD {
         fcd::FCD::create(self, fcd_url)
     }
     /// WASM module / smart contract API Functions
+    #[cfg(feature = "wasm")]
     pub fn wasm(&self) -> wasm::Wasm {
         wasm::Wasm::create(self)
     }
+    /// cw20 token contract functions
+    #[cfg(feature = "wasm")]
+    pub fn cw20(&self) -> cw20::CW20<'_> {
+        cw20::CW20::create(self)
+    }
+
+    /// the HTTP client used to talk to the LCD. rustls is preferred when the `rustls` feature is on
+    pub fn http_client() -> Client {
+        Terra::http_client_with(&HttpOptions::default())
+    }
+    /// the HTTP client used to talk to the LCD, with connection pool/compression settings
+    pub fn http_client_with(http_options: &HttpOptions) -> Client {
+        let builder = Client::builder()
+            .gzip(http_options.compression)
+            .deflate(http_options.compression)
+            .pool_idle_timeout(http_options.pool_idle_timeout)
+            .pool_max_idle_per_host(http_options.pool_max_idle_per_host)
+            .tcp_keepalive(http_options.tcp_keepalive);
+        let builder = if http_options.no_proxy {
+            builder.no_proxy()
+        } else {
+            builder
+        };
+        #[cfg(feature = "rustls")]
+        let builder = builder.use_rustls_tls();
+        builder.build().expect("TLS backend cannot be initialized")
+    }
+
+    /// are either of the debug modes on
+    pub(crate) fn debug_enabled(&self) -> bool {
+        self.debug || self.debug_redacted
+    }
+    /// the text as it should be logged. masked in debug_redacted mode
+    pub(crate) fn for_log<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.debug_redacted {
+            Cow::Owned(self.redact(text))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
 
     pub fn construct_headers() -> HeaderMap {
         let mut headers = HeaderMap::new();
@@ -239,6 +1298,20 @@ impl Terra {
         self.send_cmd_url(&self.url, path, args, height).await
     }
 
+    /// used to send a GET command to the LCD for something that rarely changes.
+    /// with [Terra::with_http_cache] it is a conditional request, answered from the cache if unchanged
+    pub async fn send_cmd_cached<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        args: Option<&str>,
+        height: Option<u64>,
+    ) -> Result<T, TerraRustAPIError> {
+        let (context, text) = self
+            .get_text(&self.url, path, args, height, self.http_cache.as_ref())
+            .await?;
+        Terra::parse::<T>(context, &text, self.unknown_fields)
+    }
+
     /// used to send a GET command to any URL
     pub async fn send_cmd_url<T: for<'de> Deserialize<'de>>(
         &self,
@@ -247,10 +1320,178 @@ impl Terra {
         args: Option<&str>,
         height: Option<u64>,
     ) -> Result<T, TerraRustAPIError> {
+        let (context, text) = self.get_text(url, path, args, height, None).await?;
+        Terra::parse::<T>(context, &text, self.unknown_fields)
+    }
+
+    /// used to send a GET command to the LCD, returning the untouched JSON along with the structure.
+    /// If T is a [LCDResult] its `raw` field is also filled in
+    pub async fn send_cmd_raw<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        args: Option<&str>,
+        height: Option<u64>,
+    ) -> Result<(T, serde_json::Value), TerraRustAPIError> {
+        let (context, text) = self.get_text(&self.url, path, args, height, None).await?;
+        let parse = || -> Result<(T, serde_json::Value), TerraRustAPIError> {
+            let raw = serde_json::from_str::<serde_json::Value>(&text)?;
+            let typed = Terra::typed::<T>(&context.url, &raw, self.unknown_fields, true)?;
+            Ok((typed, raw))
+        };
+        parse().map_err(|e| e.with_context(context.with_response(&text)))
+    }
+
+    /// the body of a GET. if the height has been pruned, try elsewhere as [Terra::pruned_heights] says
+    async fn get_text(
+        &self,
+        url: &str,
+        path: &str,
+        args: Option<&str>,
+        height: Option<u64>,
+        cache: Option<&HttpCache>,
+    ) -> Result<(RequestContext, String), TerraRustAPIError> {
+        let e = match self.get_at(url, path, args, height, cache).await {
+            Ok(found) => return Ok(found),
+            Err(e) => e,
+        };
+        let (requested, lowest, latest) = match e.without_context() {
+            TerraRustAPIError::HeightPruned(requested, lowest, latest) => {
+                (*requested, *lowest, *latest)
+            }
+            _ => return Err(e),
+        };
+        for archive in &self.pruned_heights.archives {
+            match self
+                .get_at(archive, path, args, Some(requested), cache)
+                .await
+            {
+                Ok(found) => {
+                    self.substituted(path, requested, requested, archive);
+                    return Ok(found);
+                }
+                Err(e) => log::warn!(
+                    "archive {} doesn't have height {}: {}",
+                    crate::redact::redact(archive),
+                    requested,
+                    e
+                ),
+            }
+        }
+        if !self.pruned_heights.nearest {
+            return Err(e);
+        }
+        let (height, found) = match (lowest, latest) {
+            (Some(lowest), _) => (
+                lowest,
+                self.get_at(url, path, args, Some(lowest), cache).await?,
+            ),
+            (None, Some(latest)) => {
+                self.nearest(url, path, args, requested, latest, cache)
+                    .await?
+            }
+            (None, None) => return Err(e),
+        };
+        self.substituted(path, requested, height, url);
+        Ok(found)
+    }
+    /// a GET at the height, or the pinned one. conditional if there is a cache
+    async fn get_at(
+        &self,
+        url: &str,
+        path: &str,
+        args: Option<&str>,
+        height: Option<u64>,
+        cache: Option<&HttpCache>,
+    ) -> Result<(RequestContext, String), TerraRustAPIError> {
+        let (request_url, mut req) = self.get_request(url, path, args, height);
+        if let Some(cache) = cache {
+            req = cache.conditional(&request_url, req);
+        }
+        let context = RequestContext::create("GET", &request_url, None);
+        let started = Instant::now();
+        let text = self
+            .bounded(
+                &request_url,
+                Terra::resp_text(&context, req, self.wire(url, None, cache)),
+            )
+            .await;
+        self.inner.observe(url, started.elapsed(), &text);
+        Ok((context, text?))
+    }
+    /// search for the lowest height above `pruned` the LCD still has, `latest` being one it has
+    async fn nearest(
+        &self,
+        url: &str,
+        path: &str,
+        args: Option<&str>,
+        mut pruned: u64,
+        latest: u64,
+        cache: Option<&HttpCache>,
+    ) -> Result<(u64, (RequestContext, String)), TerraRustAPIError> {
+        let mut available = latest;
+        let mut found = None;
+        while available - pruned > 1 {
+            let height = pruned + (available - pruned) / 2;
+            match self.get_at(url, path, args, Some(height), cache).await {
+                Ok(answer) => {
+                    available = height;
+                    found = Some(answer);
+                }
+                Err(e) if matches!(e.without_context(), TerraRustAPIError::HeightPruned(..)) => {
+                    pruned = height
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        match found {
+            Some(answer) => Ok((available, answer)),
+            None => Ok((
+                latest,
+                self.get_at(url, path, args, Some(latest), cache).await?,
+            )),
+        }
+    }
+    fn substituted(&self, path: &str, requested: u64, height: u64, lcd: &str) {
+        let lcd = crate::redact::redact(lcd);
+        log::warn!(
+            "{} at height {} has been pruned, answered at height {} by {}",
+            path,
+            requested,
+            height,
+            lcd
+        );
+        if let Some(substitutions) = &self.height_substitutions {
+            substitutions.lock().unwrap().push(HeightSubstitution {
+                path: path.into(),
+                requested,
+                height,
+                lcd,
+            });
+        }
+    }
+
+    fn get_request(
+        &self,
+        url: &str,
+        path: &str,
+        args: Option<&str>,
+        height: Option<u64>,
+    ) -> (String, RequestBuilder) {
+        // a height already in the query wins over the pinned one, rather than being sent twice
+        let pinned = self
+            .pinned_height
+            .filter(|_| !matches!(args, Some(args) if has_height_param(args)));
+        let height = height.or(pinned);
         let mut request_url = match args {
             Some(a) => format!("{}{}{}", url.to_owned(), path, a),
             None => format!("{}{}", url.to_owned(), path),
         };
+        let gateway_height = if path.starts_with("/cosmos/") || path.starts_with("/cosmwasm/") {
+            height
+        } else {
+            None
+        };
+        let height = height.filter(|_| gateway_height.is_none());
         if let Some(height) = height {
             // this is a bit hacky, we probably should use .query(&[("a", "b"), ("c", "d")])
             // but then it would have larger changes.
@@ -259,15 +1500,21 @@ impl Terra {
             request_url.push_str(height_query.as_str());
         }
 
-        if self.debug {
-            log::debug!("URL={}", &request_url);
+        if self.debug_enabled() {
+            log::debug!("URL={}", self.for_log(&request_url));
         }
-        let req = self
-            .client
-            .get(&request_url)
-            .headers(Terra::construct_headers());
-
-        Terra::resp::<T>(&request_url, req).await
+        let mut req = self.authenticate(
+            url,
+            self.client.get(&request_url).headers(self.headers.clone()),
+        );
+        if let Some(remaining) = self.remaining() {
+            req = req.timeout(remaining);
+        }
+        if let Some(height) = gateway_height {
+            // the cosmos-sdk gateway ignores a height parameter, and takes it as a header
+            req = req.header("x-cosmos-block-height", height.to_string());
+        }
+        (request_url, req)
     }
 
     pub async fn fetch_url<T: for<'de> Deserialize<'de>>(
@@ -282,8 +1529,8 @@ impl Terra {
         };
 
         let req = client.get(&request_url).headers(Terra::construct_headers());
-
-        Terra::resp::<T>(&request_url, req).await
+        let context = RequestContext::create("GET", &request_url, None);
+        Terra::resp::<T>(context, req, UnknownFields::Ignore, None).await
     }
 
     /// used to send a POST with a JSON body to the LCD
@@ -294,99 +1541,264 @@ impl Terra {
     ) -> Result<T, TerraRustAPIError> {
         let request_url = format!("{}{}", self.url.to_owned(), path);
 
-        if self.debug {
-            log::debug!("URL={}", &request_url);
+        if self.debug_enabled() {
+            log::debug!("URL={}", self.for_log(&request_url));
+        }
+
+        let mut req = self.authenticate(
+            &self.url,
+            self.client
+                .post(&request_url)
+                .headers(self.headers.clone())
+                .json::<R>(args),
+        );
+        if let Some(remaining) = self.remaining() {
+            req = req.timeout(remaining);
+        }
+        let body = serde_json::to_string(args).ok();
+        let context = RequestContext::create("POST", &request_url, body.as_deref());
+        let wire = self.wire(&self.url, body.as_deref(), None);
+        let started = Instant::now();
+        let text = self
+            .bounded(&request_url, Terra::resp_text(&context, req, wire))
+            .await;
+        self.inner.observe(&self.url, started.elapsed(), &text);
+        Terra::parse::<T>(context, &text?, self.unknown_fields)
+    }
+
+    async fn resp<T: for<'de> Deserialize<'de>>(
+        context: RequestContext,
+        req: RequestBuilder,
+        unknown_fields: UnknownFields,
+        wire: Option<Wire<'_>>,
+    ) -> Result<T, TerraRustAPIError> {
+        let text = Terra::resp_text(&context, req, wire).await?;
+        Terra::parse::<T>(context, &text, unknown_fields)
+    }
+    /// turn a response body into the structure
+    fn parse<T: for<'de> Deserialize<'de>>(
+        context: RequestContext,
+        text: &str,
+        unknown_fields: UnknownFields,
+    ) -> Result<T, TerraRustAPIError> {
+        let parse = || -> Result<T, TerraRustAPIError> {
+            if unknown_fields == UnknownFields::Ignore {
+                Ok(serde_json::from_str::<T>(text)?)
+            } else {
+                let raw = serde_json::from_str::<serde_json::Value>(text)?;
+                Terra::typed::<T>(
+                    &context.url,
+                    &raw,
+                    unknown_fields,
+                    unknown_fields == UnknownFields::Capture,
+                )
+            }
+        };
+        parse().map_err(|e| e.with_context(context.with_response(text)))
+    }
+
+    /// send the request, or find it in the recording, and return the body of a successful response.
+    /// the exchange is written to the wire log, if there is one
+    async fn resp_text(
+        context: &RequestContext,
+        req: RequestBuilder,
+        wire: Option<Wire<'_>>,
+    ) -> Result<String, TerraRustAPIError> {
+        let started = Instant::now();
+        let replay = wire.and_then(|wire| wire.replay);
+        let body = wire.and_then(|wire| wire.body);
+        let result = match (replay, wire.and_then(|wire| wire.signer)) {
+            (Some(replay), _) => replay.respond(&context.method, &context.url, body),
+            (None, Some(signer)) => {
+                let req = request_signing::sign_request(signer, req, Utc::now().timestamp())
+                    .map_err(|e| e.with_context(context.clone()))?;
+                Terra::send_for_text(&context.url, req, wire).await
+            }
+            (None, None) => Terra::send_for_text(&context.url, req, wire).await,
+        };
+        // the LCD may echo the API key back
+        let secrets = wire.map(|wire| wire.secrets).unwrap_or_default();
+        let result = result.map_err(|e| match e {
+            TerraRustAPIError::TerraLCDResponse(status, text) if !secrets.is_empty() => {
+                TerraRustAPIError::TerraLCDResponse(
+                    status,
+                    crate::redact::redact_with_secrets(&text, secrets),
+                )
+            }
+            e => e,
+        });
+        if let Some(wire_log) = wire.and_then(|wire| wire.log) {
+            let logged = match &result {
+                Ok(response) => Ok(response.clone()),
+                Err(TerraRustAPIError::TerraLCDResponse(status, text)) => {
+                    Ok((status.as_u16(), text.clone()))
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            let entry = WireLogEntry::create(&context.method, &context.url, body);
+            wire_log.record(entry, started.elapsed(), &logged, secrets);
+        }
+        result.map(|(_, text)| text).map_err(|e| {
+            let pruned = match &e {
+                TerraRustAPIError::TerraLCDResponse(_, text) => pruned::parse(text),
+                _ => None,
+            };
+            match (pruned, &e) {
+                (
+                    Some((requested, lowest, latest)),
+                    TerraRustAPIError::TerraLCDResponse(_, text),
+                ) => TerraRustAPIError::HeightPruned(requested, lowest, latest)
+                    .with_context(context.clone().with_response(text)),
+                _ => e.with_context(context.clone()),
+            }
+        })
+    }
+
+    /// the status & body of a successful response. a `304 Not Modified` comes with the cached body
+    async fn send_for_text(
+        request_url: &str,
+        req: RequestBuilder,
+        wire: Option<Wire<'_>>,
+    ) -> Result<(u16, String), TerraRustAPIError> {
+        if let Some(cache) = wire.and_then(|wire| wire.cache) {
+            let response = Wire::send(wire, request_url, req).await?;
+            return cache.answer(request_url, response).await;
         }
-
-        let req = self
-            .client
-            .post(&request_url)
-            .headers(Terra::construct_headers())
-            .json::<R>(args);
-
-        Terra::resp::<T>(&request_url, req).await
+        let response = Terra::check_status(request_url, req, wire).await?;
+        let status = response.status().as_u16();
+        Ok((status, response.text().await?))
     }
 
-    async fn resp<T: for<'de> Deserialize<'de>>(
+    async fn check_status(
         request_url: &str,
         req: RequestBuilder,
-    ) -> Result<T, TerraRustAPIError> {
-        let response = req.send().await?;
+        wire: Option<Wire<'_>>,
+    ) -> Result<reqwest::Response, TerraRustAPIError> {
+        let response = Wire::send(wire, request_url, req).await?;
         let status = response.status();
         if !&status.is_success() {
             let status_text = response.text().await?;
             //  eprintln!("{}", &request_url);
-            log::debug!("URL={} - {}", &request_url, &status_text);
+            log::debug!(
+                "URL={} - {}",
+                crate::redact::redact(request_url),
+                &status_text
+            );
             Err(TerraRustAPIError::TerraLCDResponse(status, status_text))
         } else {
-            let struct_response: T = response.json::<T>().await?;
-            Ok(struct_response)
+            Ok(response)
         }
     }
 
-    /// Generate Fee structure, either by estimation method or hardcoded
-    ///
+    /// turn the JSON into the structure, dealing with unknown fields as asked
+    fn typed<T: for<'de> Deserialize<'de>>(
+        request_url: &str,
+        raw: &serde_json::Value,
+        unknown_fields: UnknownFields,
+        inject_raw: bool,
+    ) -> Result<T, TerraRustAPIError> {
+        let (struct_response, unknown) = unknown_fields::from_value::<T>(raw, inject_raw)?;
+        if !unknown.is_empty() {
+            match unknown_fields {
+                UnknownFields::Error => {
+                    return Err(TerraRustAPIError::UnknownFields(
+                        request_url.into(),
+                        unknown.join(", "),
+                    ))
+                }
+                UnknownFields::Capture => {
+                    log::warn!(
+                        "URL={} unknown fields {}",
+                        crate::redact::redact(request_url),
+                        unknown.join(", ")
+                    )
+                }
+                UnknownFields::Ignore => {}
+            }
+        }
+        Ok(struct_response)
+    }
 
+    /// Generate Fee structure, either by estimation method or hardcoded.
+    /// fails if it is over the [GasOptions::max_fee]
+    #[cfg(feature = "tx")]
     pub async fn calc_fees(
         &self,
         auth_account: &AuthAccount,
         messages: &[Message],
     ) -> Result<StdFee, TerraRustAPIError> {
-        match &self.gas_options {
+        let fee = match &self.gas_options {
             None => Err(TerraRustAPIError::NoGasOpts),
 
-            Some(gas) => {
-                match &gas.fees {
-                    Some(f) => {
-                        let fee_coin: Coin = Coin::create(&f.denom, f.amount);
-                        Ok(StdFee::create(vec![fee_coin], gas.gas.unwrap_or(0)))
-                    }
-
-                    None => {
-                        let fee: StdFee = match &gas.estimate_gas {
-                            true => {
-                                let default_gas_coin = Coin::create("ukrw", dec!(1.0));
-                                let gas_coin = match &gas.gas_price {
-                                    Some(c) => c,
-                                    None => &default_gas_coin,
-                                };
-                                let res: LCDResult<TxFeeResult> = self
-                                    .tx()
-                                    .estimate_fee(
-                                        &auth_account.address,
-                                        messages,
-                                        gas.gas_adjustment.unwrap_or(1.0),
-                                        &[gas_coin],
-                                    )
-                                    .await?;
-                                //  let gas_amount = gas.gas_adjustment.unwrap_or(1.0) * res.result.gas as f64;
-                                let mut fees: Vec<Coin> = vec![];
-                                for fee in res.result.fee.amount {
-                                    fees.push(Coin::create(&fee.denom, fee.amount))
-                                }
-                                StdFee::create(fees, res.result.fee.gas as u64)
-                            }
-                            false => {
-                                let mut fees: Vec<Coin> = vec![];
-                                match &gas.fees {
-                                    Some(fee) => {
-                                        fees.push(Coin::create(&fee.denom, fee.amount));
-                                    }
-                                    None => {}
-                                }
-
-                                StdFee::create(fees, gas.gas.unwrap_or(0))
-                            }
-                        };
-                        Ok(fee)
-                    }
+            Some(gas) => match &gas.fees {
+                Some(f) => {
+                    let tax = if gas.add_tax {
+                        let sent = messages
+                            .iter()
+                            .flat_map(Message::coins_sent)
+                            .collect::<Vec<_>>();
+                        self.treasury().tax(&sent).await?
+                    } else {
+                        vec![]
+                    };
+                    Ok(StdFee::create(
+                        fees::fixed_fee(f, &tax),
+                        gas.gas.unwrap_or(0),
+                    ))
                 }
-            }
+                None if !gas.estimate_gas => {
+                    let gas_amount = gas.gas.unwrap_or(0);
+                    let fee = gas
+                        .gas_price
+                        .iter()
+                        .map(|price| fees::fee_for_gas(price, gas_amount))
+                        .collect();
+                    Ok(StdFee::create(fee, gas_amount))
+                }
+                None => {
+                    let default_gas_coin = Coin::create("ukrw", dec!(1.0));
+                    let gas_coin = match &gas.gas_price {
+                        Some(c) => c,
+                        None => &default_gas_coin,
+                    };
+                    let res: LCDResult<TxFeeResult> = self
+                        .tx()
+                        .estimate_fee(
+                            &auth_account.address,
+                            messages,
+                            gas.gas_adjustment.unwrap_or(1.0),
+                            &[gas_coin],
+                        )
+                        .await?;
+                    let gas_amount = res.result.fee.gas;
+                    Ok(StdFee::create(
+                        fees::estimated_fee(&res.result.fee.amount, gas_amount, gas_coin),
+                        gas_amount,
+                    ))
+                }
+            },
+        }?;
+        if let Some(gas) = &self.gas_options {
+            gas.check_fee(&fee.amount)?;
         }
+        Ok(fee)
+    }
+
+    /// the fee [Terra::calc_fees] would set on the messages if `from_address` sent them, with the gas in [StdFee::gas].
+    /// nothing is signed, so costs can be shown before asking for the key
+    #[cfg(feature = "tx")]
+    pub async fn preview_fees(
+        &self,
+        from_address: &str,
+        messages: &[Message],
+    ) -> Result<StdFee, TerraRustAPIError> {
+        let auth = self.auth().account(from_address, None).await?;
+        self.calc_fees(&auth.result.value, messages).await
     }
 
     /// helper function to generate a 'StdSignMsg' & 'Signature' blocks to be used to broadcast a transaction
     #[allow(clippy::too_many_arguments)]
+    #[cfg(feature = "tx")]
     fn generate_transaction_to_broadcast_fees<C: Signing + secp256k1::Context>(
         chain_id: &str,
         auth_account: &AuthAccount,
@@ -405,11 +1817,7 @@ impl Terra {
             sequence,
             fee,
             msgs: messages,
-            memo: memo.unwrap_or(format!(
-                "PFC-{}/{}",
-                NAME.unwrap_or("TERRA-RUST"),
-                VERSION.unwrap_or("dev")
-            )),
+            memo: memo.unwrap_or_else(memo::default_memo),
         };
         let js = serde_json::to_string(&std_sign_msg)?;
         if js.len() > 1000 {
@@ -421,18 +1829,20 @@ impl Terra {
                 messages_len
             );
         } else {
-            log::debug!("TO SIGN - {}", js);
+            log::debug!("TO SIGN - {}", crate::redact::redact(&js));
         }
 
         // eprintln!("Client.rs:311\n{}", js);
-        let sig = from.sign(secp, &js)?;
+        let sig = std_sign_msg.sign(secp, from)?;
         let sigs: Vec<StdSignature> = vec![sig];
 
         Ok((std_sign_msg, sigs))
     }
 
     /// helper function to generate a 'StdSignMsg' & 'Signature' blocks to be used to broadcast a transaction
-    /// This version calculates fees, and obtains account# and sequence# as well
+    /// This version calculates fees, and obtains account# and sequence# as well.
+    /// fails if the [AuditSink] can't record it
+    #[cfg(feature = "tx")]
     pub async fn generate_transaction_to_broadcast<C: secp256k1::Signing + secp256k1::Context>(
         &self,
         secp: &Secp256k1<C>,
@@ -440,21 +1850,104 @@ impl Terra {
         messages: Vec<Message>,
         memo: Option<String>,
     ) -> Result<(StdSignMsg, Vec<StdSignature>), TerraRustAPIError> {
-        let from_public = from.public_key(secp);
-        let from_account = from_public.account()?;
-        let auth = self.auth().account(&from_account, None).await?;
-        let fees = self.calc_fees(&auth.result.value, &messages).await?;
-        Terra::generate_transaction_to_broadcast_fees(
+        let (std_sign_msg, sigs) = self.sign_transaction(secp, from, messages, memo).await?;
+        if let Some(sink) = &self.audit_sink {
+            let signer = from.public_key(secp).account()?;
+            let record = SigningRecord::create(&std_sign_msg, &signer, None)?;
+            sink.record(&record)
+                .await
+                .map_err(|e| TerraRustAPIError::Audit(e.to_string()))?;
+        }
+        Ok((std_sign_msg, sigs))
+    }
+    /// [Terra::generate_transaction_to_broadcast], without recording it
+    #[cfg(feature = "tx")]
+    async fn sign_transaction<C: secp256k1::Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        messages: Vec<Message>,
+        memo: Option<String>,
+    ) -> Result<(StdSignMsg, Vec<StdSignature>), TerraRustAPIError> {
+        let auth_account = self.signing_account(secp, from).await?;
+        self.sign_as(secp, from, &auth_account, messages, memo)
+            .await
+    }
+    /// [Terra::sign_transaction], counting its sequence as used by a transaction about to be broadcast
+    #[cfg(feature = "tx")]
+    async fn sign_to_send<C: secp256k1::Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        messages: Vec<Message>,
+        memo: Option<String>,
+    ) -> Result<(StdSignMsg, Vec<StdSignature>), TerraRustAPIError> {
+        let mut auth_account = self.signing_account(secp, from).await?;
+        let reservation = self
+            .inner
+            .sequences
+            .hold(&auth_account.address, auth_account.sequence.unwrap_or(0));
+        auth_account.sequence = Some(reservation.sequence);
+        let signed = self
+            .sign_as(secp, from, &auth_account, messages, memo)
+            .await?;
+        reservation.keep();
+        Ok(signed)
+    }
+    /// the key's account, at the sequence to sign with: the chain's, or the one after the transactions
+    /// sent but not yet committed. see [shared::SequenceManager]
+    #[cfg(feature = "tx")]
+    async fn signing_account<C: secp256k1::Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+    ) -> Result<AuthAccount, TerraRustAPIError> {
+        if self.check_chain_id {
+            self.verify_chain_id().await?;
+        }
+        let from_account = from.public_key(secp).account()?;
+        let mut auth_account = self.auth().account(&from_account, None).await?.result.value;
+        let chain_sequence = auth_account.sequence.unwrap_or(0);
+        auth_account.sequence = Some(self.inner.sequences.peek(&from_account, chain_sequence));
+        Ok(auth_account)
+    }
+    /// sign the messages as `auth_account`, at the sequence it has
+    #[cfg(feature = "tx")]
+    async fn sign_as<C: secp256k1::Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        auth_account: &AuthAccount,
+        messages: Vec<Message>,
+        memo: Option<String>,
+    ) -> Result<(StdSignMsg, Vec<StdSignature>), TerraRustAPIError> {
+        if !self.skip_validation {
+            for message in &messages {
+                message.validate()?;
+            }
+        }
+        let fees = self.calc_fees(auth_account, &messages).await?;
+        let memo = self.memo_policy.memo(&MemoContext {
+            chain_id: self.chain_id.clone(),
+            sender: auth_account.address.clone(),
+            account_number: auth_account.account_number,
+            sequence: auth_account.sequence.unwrap_or(0),
+            memo,
+        })?;
+        let (std_sign_msg, sigs) = Terra::generate_transaction_to_broadcast_fees(
             &self.chain_id,
-            &auth.result.value,
+            auth_account,
             fees,
             secp,
             from,
             messages,
-            memo,
-        )
+            Some(memo),
+        )?;
+        self.tx_limits.check(&std_sign_msg, &sigs)?;
+        Ok((std_sign_msg, sigs))
     }
     /// helper: sign & submit the transaction sync
+    #[cfg(feature = "tx")]
     pub async fn submit_transaction_sync<C: Signing + secp256k1::Context>(
         &self,
         secp: &Secp256k1<C>,
@@ -462,17 +1955,349 @@ impl Terra {
         messages: Vec<Message>,
         memo: Option<String>,
     ) -> Result<TXResultSync, TerraRustAPIError> {
-        let (std_sign_msg, sigs) = self
-            .generate_transaction_to_broadcast(secp, from, messages, memo)
-            .await?;
-        let resp = self.tx().broadcast_sync(&std_sign_msg, &sigs).await?;
+        let (std_sign_msg, sigs) = self.sign_to_send(secp, from, messages, memo).await?;
+        self.broadcast_signed_sync(secp, from, &std_sign_msg, &sigs)
+            .await
+    }
+    /// helper: sign a transaction for each set of messages, with consecutive sequence numbers, and broadcast them
+    /// back-to-back without waiting for any to be committed. Wait for them afterwards with [tx::TX::get_and_wait_v1].
+    ///
+    /// There is one result per transaction, in order, up to the first the node rejects.
+    /// the ones after it can't be valid, as their sequence numbers would leave a gap, so they aren't sent
+    #[cfg(feature = "tx")]
+    pub async fn submit_transactions_sync<C: Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        transactions: Vec<Vec<Message>>,
+        memo: Option<String>,
+    ) -> Result<Vec<Result<TXResultSync, TerraRustAPIError>>, TerraRustAPIError> {
+        let mut auth_account = self.signing_account(secp, from).await?;
+        let mut results = Vec::with_capacity(transactions.len());
+        for messages in transactions {
+            // each one after the last
+            let reservation = self
+                .inner
+                .sequences
+                .hold(&auth_account.address, auth_account.sequence.unwrap_or(0));
+            auth_account.sequence = Some(reservation.sequence);
+            let resp = match self
+                .sign_as(secp, from, &auth_account, messages, memo.clone())
+                .await
+            {
+                Ok((std_sign_msg, sigs)) => {
+                    reservation.keep();
+                    self.broadcast_signed_sync(secp, from, &std_sign_msg, &sigs)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            let sent = resp.is_ok();
+            results.push(resp);
+            if !sent {
+                break;
+            }
+        }
+        Ok(results)
+    }
+    /// broadcast a signed transaction sync, recording & notifying as [Terra::submit_transaction_sync] does.
+    /// one the node turns away gives its sequence back, see [shared::SequenceManager::release]. one turned away
+    /// for its sequence, or whose fate is unknown, resets the account's sequence, see [shared::SequenceManager::reset]
+    #[cfg(feature = "tx")]
+    async fn broadcast_signed_sync<C: Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        std_sign_msg: &StdSignMsg,
+        sigs: &[StdSignature],
+    ) -> Result<TXResultSync, TerraRustAPIError> {
+        let resp = self.tx().broadcast_sync(std_sign_msg, sigs).await;
+        // a CheckTx rejection has a txhash, but it never makes it into a block
+        self.audit_broadcast(
+            secp,
+            from,
+            std_sign_msg,
+            resp.as_ref()
+                .ok()
+                .filter(|r| r.code.is_none())
+                .map(|r| &r.txhash),
+        )
+        .await;
+        match &resp {
+            Ok(resp) if resp.code.is_none() => {}
+            // its sequence wasn't used, so the next transaction follows on from those still waiting
+            Ok(resp) if resp.code != Some(escalation::WRONG_SEQUENCE_CODE) => {
+                self.release_sequence(secp, from, std_sign_msg.sequence)
+            }
+            Err(e) if e.unsent() => self.release_sequence(secp, from, std_sign_msg.sequence),
+            _ => self.reset_sequence(secp, from),
+        }
+        let resp = match resp {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.notify_failed("", None, &e.to_string()).await;
+                return Err(e);
+            }
+        };
 
         match resp.code {
-            Some(code) => Err(TxResultError(code, resp.txhash, resp.raw_log)),
-            None => Ok(resp),
+            Some(code) => {
+                self.notify_failed(&resp.txhash, Some(code), &resp.raw_log)
+                    .await;
+                Err(TxResultError(code, resp.txhash, resp.raw_log))
+            }
+            None => {
+                self.notify_broadcasted(&resp.txhash).await;
+                Ok(resp)
+            }
+        }
+    }
+    /// helper: sign & submit the transaction sync, and wait up to `max_blocks` blocks for it to be committed
+    #[cfg(feature = "tx")]
+    pub async fn submit_transaction_and_confirm<C: Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        messages: Vec<Message>,
+        memo: Option<String>,
+        max_blocks: u64,
+    ) -> Result<V1TXResult, TerraRustAPIError> {
+        let resp = self
+            .submit_transaction_sync(secp, from, messages, memo)
+            .await?;
+        let tx = match self.tx().confirmation(&resp.txhash, max_blocks).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                self.notify_failed(&resp.txhash, None, &e.to_string()).await;
+                return Err(e);
+            }
+        };
+        self.confirmed(tx).await
+    }
+    /// helper: sign & submit the transaction sync, and if it isn't committed within [FeeEscalation::blocks] blocks,
+    /// sign it again at the same sequence with a higher fee and broadcast that, until one of them is committed.
+    /// fails with [TerraRustAPIError::TxConfirmationTimeout] once one at the capped price isn't committed either.
+    ///
+    /// a higher fee only gets in once the node has evicted the original, see [escalation]. while the original is
+    /// still in its mempool, the node turns the next one away with [escalation::WRONG_SEQUENCE_CODE], and this waits
+    /// one more round for what was sent before giving up. turned away fee raises don't reset the account's sequence,
+    /// and aren't notified as failures
+    #[cfg(feature = "tx")]
+    pub async fn submit_transaction_escalating<C: Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        messages: Vec<Message>,
+        memo: Option<String>,
+        escalation: &FeeEscalation,
+    ) -> Result<V1TXResult, TerraRustAPIError> {
+        let base = self
+            .gas_options
+            .clone()
+            .ok_or(TerraRustAPIError::NoGasOpts)?;
+        let attempts = escalation.attempts_for(&base)?;
+        // the same sequence every time, so only one of them can be committed
+        let mut auth_account = self.signing_account(secp, from).await?;
+        let mut reservation = Some(
+            self.inner
+                .sequences
+                .hold(&auth_account.address, auth_account.sequence.unwrap_or(0)),
+        );
+        auth_account.sequence = reservation.as_ref().map(|r| r.sequence);
+        let mut sent: Vec<String> = vec![];
+        let mut waited = 0;
+        for attempt in 0..attempts {
+            let mut terra = self.clone();
+            terra.gas_options = Some(escalation.gas_options(&base, attempt)?);
+            let (std_sign_msg, sigs) = terra
+                .sign_as(secp, from, &auth_account, messages.clone(), memo.clone())
+                .await?;
+            if let Some(reservation) = reservation.take() {
+                reservation.keep();
+                sent.push(
+                    self.broadcast_signed_sync(secp, from, &std_sign_msg, &sigs)
+                        .await?
+                        .txhash,
+                );
+            } else {
+                // the earlier ones are still out there, so a rejection here isn't the account's to reset
+                let resp = self.tx().broadcast_sync(&std_sign_msg, &sigs).await;
+                let accepted = resp.as_ref().ok().filter(|r| r.code.is_none());
+                self.audit_broadcast(secp, from, &std_sign_msg, accepted.map(|r| &r.txhash))
+                    .await;
+                match resp {
+                    Ok(resp) if resp.code.is_none() => sent.push(resp.txhash),
+                    Ok(resp) if resp.code == Some(escalation::WRONG_SEQUENCE_CODE) => {
+                        log::info!(
+                            "the fee can't be raised while {} is in the mempool: {}",
+                            sent.last().map_or("", |h| h.as_str()),
+                            resp.raw_log
+                        );
+                        return self
+                            .confirmation_after_escalating(&sent, escalation.blocks, waited)
+                            .await;
+                    }
+                    // an earlier one may have been committed since, using up the sequence
+                    Ok(resp) => log::warn!("rebroadcast rejected: {}", resp.raw_log),
+                    Err(e) => log::warn!("rebroadcast failed: {}", e),
+                }
+            }
+            waited += escalation.blocks;
+            match self
+                .tx()
+                .confirmation_of_any(&sent, escalation.blocks)
+                .await
+            {
+                Ok(tx) => return self.confirmed(tx).await,
+                Err(TerraRustAPIError::TxConfirmationTimeout(hash, _)) => log::info!(
+                    "{} not committed after {} blocks, raising the fee",
+                    hash,
+                    escalation.blocks
+                ),
+                Err(e) => return Err(e),
+            }
+        }
+        self.escalation_failed(&sent, waited).await
+    }
+    /// the last wait of [Terra::submit_transaction_escalating], once the fee can't be raised any more
+    #[cfg(feature = "tx")]
+    async fn confirmation_after_escalating(
+        &self,
+        sent: &[String],
+        blocks: u64,
+        waited: u64,
+    ) -> Result<V1TXResult, TerraRustAPIError> {
+        match self.tx().confirmation_of_any(sent, blocks).await {
+            Ok(tx) => self.confirmed(tx).await,
+            Err(TerraRustAPIError::TxConfirmationTimeout(_, _)) => {
+                self.escalation_failed(sent, waited + blocks).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+    #[cfg(feature = "tx")]
+    async fn escalation_failed(
+        &self,
+        sent: &[String],
+        waited: u64,
+    ) -> Result<V1TXResult, TerraRustAPIError> {
+        let hash = sent.last().cloned().unwrap_or_default();
+        self.notify_failed(&hash, None, "not committed at the highest fee")
+            .await;
+        Err(TerraRustAPIError::TxConfirmationTimeout(hash, waited))
+    }
+    /// a queue that signs & sends transactions from `from` one after another, without waiting for each to be
+    /// committed. spawn the worker on an executor; it stops once every [tx_queue::TxQueue] is dropped
+    #[cfg(feature = "tx")]
+    pub fn tx_queue(
+        &self,
+        from: &PrivateKey,
+        options: tx_queue::TxQueueOptions,
+    ) -> (tx_queue::TxQueue, tx_queue::TxQueueWorker) {
+        tx_queue::TxQueueWorker::create(self, from, options)
+    }
+    /// a committed transaction, an error if it failed
+    #[cfg(feature = "tx")]
+    async fn confirmed(&self, tx: V1TXResult) -> Result<V1TXResult, TerraRustAPIError> {
+        let response = &tx.tx_response;
+        if response.code != 0 {
+            self.notify_failed(&response.txhash, Some(response.code), &response.raw_log)
+                .await;
+            return Err(TxResultError(
+                response.code,
+                response.txhash.clone(),
+                response.raw_log.clone(),
+            ));
+        }
+        notify(
+            &self.notifier,
+            self.timer().as_ref(),
+            TxEvent::Included {
+                chain_id: self.chain_id.clone(),
+                txhash: response.txhash.clone(),
+                height: response.height,
+                gas_used: response.gas_used,
+            },
+        )
+        .await;
+        Ok(tx)
+    }
+    /// record a transaction the `submit_transaction_*` helpers broadcast. it has been sent, so a failure is only logged
+    #[cfg(feature = "tx")]
+    async fn audit_broadcast<C: Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        std_sign_msg: &StdSignMsg,
+        txhash: Option<&String>,
+    ) {
+        if self.audit_sink.is_none() {
+            return;
+        }
+        let record = from
+            .public_key(secp)
+            .account()
+            .and_then(|signer| SigningRecord::create(std_sign_msg, &signer, txhash.cloned()));
+        match record {
+            Ok(record) => record_logged(&self.audit_sink, record).await,
+            Err(e) => log::error!(
+                "audit record of sequence {} failed: {}",
+                std_sign_msg.sequence,
+                e
+            ),
+        }
+    }
+    /// a transaction from the key was turned away, so those after it would be too
+    #[cfg(feature = "tx")]
+    fn reset_sequence<C: Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+    ) {
+        if let Ok(address) = from.public_key(secp).account() {
+            self.inner.sequences.reset(&address);
+        }
+    }
+    /// a transaction from the key was turned away before it reached the mempool, so its sequence is free again
+    #[cfg(feature = "tx")]
+    fn release_sequence<C: Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        sequence: u64,
+    ) {
+        if let Ok(address) = from.public_key(secp).account() {
+            self.inner.sequences.release(&address, sequence);
         }
     }
+    #[cfg(feature = "tx")]
+    async fn notify_broadcasted(&self, txhash: &str) {
+        notify(
+            &self.notifier,
+            self.timer().as_ref(),
+            TxEvent::Broadcasted {
+                chain_id: self.chain_id.clone(),
+                txhash: txhash.to_string(),
+            },
+        )
+        .await
+    }
+    #[cfg(feature = "tx")]
+    async fn notify_failed(&self, txhash: &str, code: Option<usize>, log: &str) {
+        notify(
+            &self.notifier,
+            self.timer().as_ref(),
+            TxEvent::Failed {
+                chain_id: self.chain_id.clone(),
+                txhash: txhash.to_string(),
+                code,
+                log: log.to_string(),
+            },
+        )
+        .await
+    }
     /// helper: sign & submit the transaction async
+    #[cfg(feature = "tx")]
     pub async fn submit_transaction_async<C: Signing + secp256k1::Context>(
         &self,
         secp: &Secp256k1<C>,
@@ -480,10 +2305,24 @@ impl Terra {
         messages: Vec<Message>,
         memo: Option<String>,
     ) -> Result<TXResultAsync, TerraRustAPIError> {
-        let (std_sign_msg, sigs) = self
-            .generate_transaction_to_broadcast(secp, from, messages, memo)
-            .await?;
-        let resp = self.tx().broadcast_async(&std_sign_msg, &sigs).await?;
+        let (std_sign_msg, sigs) = self.sign_to_send(secp, from, messages, memo).await?;
+        let resp = self.tx().broadcast_async(&std_sign_msg, &sigs).await;
+        self.audit_broadcast(
+            secp,
+            from,
+            &std_sign_msg,
+            resp.as_ref().ok().map(|r| &r.txhash),
+        )
+        .await;
+        let resp = match resp {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.reset_sequence(secp, from);
+                self.notify_failed("", None, &e.to_string()).await;
+                return Err(e);
+            }
+        };
+        self.notify_broadcasted(&resp.txhash).await;
         Ok(resp)
     }
 
@@ -495,21 +2334,34 @@ impl Terra {
     pub async fn testnet_address_book() -> Result<AddressBook, TerraRustAPIError> {
         Self::address_book(NETWORK_TEST_ADDRESS_BOOK).await
     }
-    /// fetch a address book json structure
+    /// fetch a address book json structure, gzipped or not. `file://` reads a local file, and
+    /// `embedded:` the snapshot compiled in with the embedded-addressbook feature. see [crate::addressbook::source_for]
     pub async fn address_book(addr_url: &str) -> Result<AddressBook, TerraRustAPIError> {
-        if let Some(file_name) = addr_url.strip_prefix("file://") {
-            let file = File::open(file_name).unwrap();
-            let add: AddressBook = serde_json::from_reader(file)?;
-            Ok(add)
-        } else {
-            let client = reqwest::Client::new();
-
-            let req = client.get(addr_url).headers(Self::construct_headers());
-            Ok(Self::resp::<AddressBook>(addr_url, req).await?)
+        AddressBook::load(&*source_for(addr_url)).await
+    }
+    /// [Terra::address_book], through this client's [HttpCache] if it has one
+    pub async fn address_book_cached(
+        &self,
+        addr_url: &str,
+    ) -> Result<AddressBook, TerraRustAPIError> {
+        match &self.http_cache {
+            // the cache keeps text, so a gzipped book goes around it
+            Some(cache) if addr_url.starts_with("http") && !addr_url.ends_with(".gz") => {
+                let req = self.client.get(addr_url).headers(Self::construct_headers());
+                let req = cache.conditional(addr_url, req);
+                let context = RequestContext::create("GET", addr_url, None);
+                let answer = match req.send().await {
+                    Ok(response) => cache.answer(addr_url, response).await,
+                    Err(e) => Err(e.into()),
+                };
+                let (_, text) = answer.map_err(|e| e.with_context(context.clone()))?;
+                Terra::parse::<AddressBook>(context, &text, UnknownFields::Ignore)
+            }
+            _ => Self::address_book(addr_url).await,
         }
     }
 }
-#[cfg(test)]
+#[cfg(all(test, feature = "tx"))]
 mod tst {
     use super::*;
     //use crate::client::auth::Auth;
@@ -529,7 +2381,7 @@ mod tst {
 
         let send = MsgSend::create_single(
             from_address,
-            "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh".into(),
+            "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh",
             Coin::parse("100000uluna")?.unwrap(),
         )?;
         let json = serde_json::to_string(&send)?;
@@ -567,6 +2419,140 @@ mod tst {
         Ok(())
     }
 
+    #[test]
+    pub fn test_tx_limits() -> Result<(), TerraRustAPIError> {
+        let str_1 = "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut";
+        let secp = Secp256k1::new();
+        let pk = PrivateKey::from_words(&secp, str_1, 0, 0)?;
+        let send = MsgSend::create_single(
+            pk.public_key(&secp).account()?,
+            "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh",
+            Coin::parse("100000uluna")?.unwrap(),
+        )?;
+        let auth_account = AuthAccount {
+            address: "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7".to_string(),
+            public_key: None,
+            account_number: 43045,
+            sequence: Some(3),
+        };
+        let (sign_message, signatures) = Terra::generate_transaction_to_broadcast_fees(
+            "tequila-0004",
+            &auth_account,
+            StdFee::create_single(Coin::parse("50000uluna")?.unwrap(), 90000),
+            &secp,
+            &pk,
+            vec![send],
+            None,
+        )?;
+        TxLimits::default().check(&sign_message, &signatures)?;
+        let small = TxLimits {
+            max_tx_bytes: 100,
+            max_block_gas: Some(80000),
+        };
+        match small.check(&sign_message, &signatures) {
+            Err(TerraRustAPIError::TxTooLarge(size, 100)) => assert!(size > 100),
+            other => panic!("expected TxTooLarge, got {:?}", other),
+        }
+        let low_gas = TxLimits {
+            max_block_gas: Some(80000),
+            ..TxLimits::default()
+        };
+        match low_gas.check(&sign_message, &signatures) {
+            Err(TerraRustAPIError::TxGasOverBlockLimit(90000, 80000)) => {}
+            other => panic!("expected TxGasOverBlockLimit, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    pub async fn test_fetch_tx_limits() -> anyhow::Result<()> {
+        use crate::client::wire_log::{Replay, WireLogEntry};
+        let params = |max_bytes: &str, max_gas: &str| WireLogEntry {
+            status: Some(200),
+            response: Some(format!(
+                r#"{{"jsonrpc":"2.0","id":-1,"result":{{"block_height":"5","consensus_params":{{"block":{{"max_bytes":"{}","max_gas":"{}"}}}}}}}}"#,
+                max_bytes, max_gas
+            )),
+            ..WireLogEntry::create("GET", "http://rpc/consensus_params", None)
+        };
+        let terra =
+            Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(Replay::create(vec![
+                params("5000000", "100000000"),
+                params("200000", "-1"),
+            ]));
+        let limits = terra.fetch_tx_limits("http://rpc").await?;
+        assert_eq!(
+            limits,
+            TxLimits {
+                max_tx_bytes: 1024 * 1024,
+                max_block_gas: Some(100000000),
+            }
+        );
+        let limits = terra.fetch_tx_limits("http://rpc").await?;
+        assert_eq!(
+            limits,
+            TxLimits {
+                max_tx_bytes: 200000,
+                max_block_gas: None,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_headers() -> Result<(), TerraRustAPIError> {
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5");
+        assert!(terra.headers[USER_AGENT]
+            .to_str()
+            .unwrap()
+            .starts_with("PFC-"));
+        let terra = terra
+            .with_user_agent("my-bot/1.0")?
+            .with_header("x-client-id", "abc")?;
+        assert_eq!(terra.headers[USER_AGENT], "my-bot/1.0");
+        assert_eq!(terra.headers["x-client-id"], "abc");
+        assert!(terra.clone().with_header("bad header", "x").is_err());
+
+        let terra = terra.with_api_key(ApiKey::QueryParam("apikey".into(), "k3y-465".into()))?;
+        let (url, req) = terra.get_request("http://lcd", "/blocks/latest", None, Some(5));
+        assert_eq!(url, "http://lcd/blocks/latest?height=5");
+        assert_eq!(
+            req.build().unwrap().url().as_str(),
+            "http://lcd/blocks/latest?height=5&apikey=k3y-465"
+        );
+        // other hosts go without it
+        let (_, req) = terra.get_request("http://fcd", "/v1/txs/gas_prices", None, None);
+        assert_eq!(
+            req.build().unwrap().url().as_str(),
+            "http://fcd/v1/txs/gas_prices"
+        );
+        // masked by this client only
+        assert_eq!(terra.redact("sent k3y-465"), "sent [REDACTED]");
+        assert_eq!(crate::redact::redact("sent k3y-465"), "sent k3y-465");
+
+        let terra = terra.with_api_key(ApiKey::Bearer("t0ken".into()))?;
+        assert!(!terra.headers.contains_key(AUTHORIZATION));
+        let (_, req) = terra.get_request("http://lcd", "/blocks/latest", None, None);
+        let req = req.build().unwrap();
+        assert_eq!(req.headers()[AUTHORIZATION], "Bearer t0ken");
+        assert!(req.headers()[AUTHORIZATION].is_sensitive());
+        let (_, req) = terra.get_request("http://lcd:1317", "/blocks/latest", None, None);
+        assert!(!req.build().unwrap().headers().contains_key(AUTHORIZATION));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_request_signer_origin() {
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5")
+            .with_request_signer(request_signing::HmacSigner::create("gateway-1", "s3cret"));
+        let signer = |url: &str| terra.wire(url, None, None).and_then(|wire| wire.signer);
+        assert!(signer("http://lcd").is_some());
+        assert!(signer("http://fcd").is_none());
+        assert!(signer("http://lcd:26657").is_none());
+        assert_eq!(terra.redact("signed with s3cret"), "signed with [REDACTED]");
+    }
+
     #[test]
     pub fn test_wasm() -> Result<(), TerraRustAPIError> {
         let key_words = "sell raven long age tooth still predict idea quit march gasp bamboo hurdle problem voyage east tiger divide machine brain hole tiger find smooth";
@@ -639,4 +2625,342 @@ mod tst {
 
         Ok(())
     }
+    #[test]
+    pub fn test_gas_options_builder() -> anyhow::Result<()> {
+        let estimated = GasOptions::builder()
+            .gas_price("0.15uusd")
+            .adjustment(1.4)
+            .max_fee("5000000uusd")
+            .build()?;
+        assert!(estimated.estimate_gas);
+        assert_eq!(estimated.fees, None);
+        assert_eq!(
+            estimated.max_fee.as_ref().unwrap().to_string(),
+            "5000000uusd"
+        );
+        assert!(estimated
+            .check_fee(&[Coin::create("uusd", dec!(5000001))])
+            .is_err());
+        assert!(estimated
+            .check_fee(&[Coin::create("uluna", dec!(9000000))])
+            .is_ok());
+
+        let fixed = GasOptions::builder()
+            .gas_price("0.15uusd")
+            .gas(100001)
+            .build()?;
+        assert!(!fixed.estimate_gas);
+        assert_eq!(fixed.fees.unwrap().to_string(), "15001uusd");
+        let fixed = GasOptions::create_with_fees("30000uluna", 200000)?;
+        assert_eq!(fixed.gas, Some(200000));
+
+        let invalid = |builder: GasOptionsBuilder| match builder.build() {
+            Err(TerraRustAPIError::InvalidGasOptions(reason)) => reason,
+            other => format!("{:?}", other),
+        };
+        assert!(
+            invalid(GasOptions::builder().fees("5uusd").estimate_gas().gas(5))
+                .contains("estimating")
+        );
+        assert!(invalid(GasOptions::builder().fees("5uusd")).contains("fixed amount of gas"));
+        assert!(invalid(GasOptions::builder()).contains("gas price"));
+        assert!(invalid(GasOptions::builder().gas_price("0uusd")).contains("positive"));
+        assert!(invalid(GasOptions::builder().gas_price("0.15uusd").add_tax()).contains("tax"));
+        assert!(
+            invalid(GasOptions::builder().fees("5uusd").gas(5).adjustment(1.4))
+                .contains("adjustment")
+        );
+        assert!(invalid(
+            GasOptions::builder()
+                .gas_price("0.15uusd")
+                .max_fee("5uluna")
+        )
+        .contains("paid in uusd"));
+        assert!(matches!(
+            GasOptions::builder()
+                .fees("6uusd")
+                .gas(5)
+                .max_fee("5uusd")
+                .build(),
+            Err(TerraRustAPIError::FeeOverMax(_, _))
+        ));
+        Ok(())
+    }
+    #[tokio::test]
+    pub async fn test_fixed_fee_tax() -> anyhow::Result<()> {
+        let get = |path: &str, response: &str| WireLogEntry {
+            status: Some(200),
+            response: Some(response.into()),
+            ..WireLogEntry::create("GET", &format!("http://lcd{}", path), None)
+        };
+        let replay = || {
+            Replay::create(vec![
+                get("/node_info", "{}"),
+                get("/treasury/tax_rate", r#"{"height":"5","result":"0.005"}"#),
+                get(
+                    "/treasury/tax_cap/uusd",
+                    r#"{"height":"5","result":"1000000"}"#,
+                ),
+            ])
+        };
+        let account = AuthAccount {
+            address: "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7".into(),
+            public_key: None,
+            account_number: 1,
+            sequence: Some(1),
+        };
+        let send = vec![MsgSend::create_single(
+            account.address.clone(),
+            "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh".to_string(),
+            Coin::create("uusd", dec!(200000)),
+        )?];
+        let fees = |options: GasOptions| {
+            let (account, send) = (&account, &send);
+            async move {
+                Terra::lcd_client("http://lcd", "columbus-5", &options, None)
+                    .with_replay(replay())
+                    .calc_fees(account, send)
+                    .await
+            }
+        };
+        let fixed = GasOptions::create_with_fees("30000uusd", 200000)?;
+        assert_eq!(fees(fixed).await?.amount[0].amount, dec!(30000));
+        let taxed = GasOptions::builder()
+            .fees("30000uusd")
+            .gas(200000)
+            .add_tax()
+            .build()?;
+        assert_eq!(fees(taxed).await?.amount[0].amount, dec!(31000));
+        Ok(())
+    }
+    #[tokio::test]
+    #[cfg(feature = "tx")]
+    pub async fn test_submit_transactions_sync() -> anyhow::Result<()> {
+        use crate::audit::{CallbackAuditSink, SigningRecord};
+        use crate::client::wire_log::{Replay, WireLogEntry};
+        let secp = Secp256k1::new();
+        let key = PrivateKey::from_words(&secp, "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut", 0, 0)?;
+        let from = key.public_key(&secp).account()?;
+        let gas = GasOptions::create_with_fees("30000uluna", 200000)?;
+        let sends = (1..=4)
+            .map(|amount| {
+                MsgSend::create_single(
+                    from.clone(),
+                    "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh".to_string(),
+                    Coin::create("uluna", Decimal::from(amount)),
+                )
+                .map(|send| vec![send])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // what the node gets for the send at each sequence, and what it answers
+        let signer = Terra::lcd_client("http://lcd", "columbus-5", &gas, None);
+        let mut entries = vec![WireLogEntry {
+            status: Some(200),
+            response: Some(format!(
+                r#"{{"height":"5","result":{{"type":"core/Account","value":{{"address":"{}","public_key":null,"account_number":"42","sequence":"7"}}}}}}"#,
+                from
+            )),
+            ..WireLogEntry::create("GET", &format!("http://lcd/auth/accounts/{}", from), None)
+        }];
+        for (sequence, (send, code)) in (7..).zip(sends.iter().zip([None, None, Some(32)])) {
+            let account = AuthAccount {
+                address: from.clone(),
+                public_key: None,
+                account_number: 42,
+                sequence: Some(sequence),
+            };
+            let (std_sign_msg, sigs) = signer
+                .sign_as(&secp, &key, &account, send.clone(), None)
+                .await?;
+            let body =
+                serde_json::to_string(&StdTx::from_StdSignMsg(&std_sign_msg, &sigs, "sync"))?;
+            let response = match code {
+                None => format!(r#"{{"txhash":"TX{}","raw_log":"[]"}}"#, sequence),
+                Some(code) => format!(
+                    r#"{{"txhash":"TX{}","code":{},"raw_log":"account sequence mismatch"}}"#,
+                    sequence, code
+                ),
+            };
+            entries.push(WireLogEntry {
+                status: Some(200),
+                response: Some(response),
+                ..WireLogEntry::create("POST", "http://lcd/txs", Some(&body))
+            });
+        }
+        let records = Arc::new(std::sync::Mutex::new(vec![]));
+        let recorded = records.clone();
+        let terra = Terra::lcd_client("http://lcd", "columbus-5", &gas, None)
+            .with_replay(Replay::create(entries))
+            .with_audit_sink(CallbackAuditSink::create(move |record: SigningRecord| {
+                recorded.lock().unwrap().push(record);
+                Box::pin(async { Ok(()) })
+            }));
+
+        let results = terra
+            .submit_transactions_sync(&secp, &key, sends, None)
+            .await?;
+        // in order, at consecutive sequences, stopping at the first the node turns away
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[0].as_ref().map(|r| r.txhash.as_str()).ok(),
+            Some("TX7")
+        );
+        assert_eq!(
+            results[1].as_ref().map(|r| r.txhash.as_str()).ok(),
+            Some("TX8")
+        );
+        match &results[2] {
+            Err(TerraRustAPIError::TxResultError(32, txhash, _)) => assert_eq!(txhash, "TX9"),
+            other => anyhow::bail!("{:?}", other),
+        }
+        // the rejection has no txhash in the audit log, as it isn't on chain
+        let records = records.lock().unwrap();
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| (r.sequence, r.txhash.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(7, Some("TX7")), (8, Some("TX8")), (9, None)]
+        );
+        // and the next transaction starts from the chain's sequence again
+        assert_eq!(terra.inner.sequences.peek(&from, 7), 7);
+        Ok(())
+    }
+    #[tokio::test]
+    #[cfg(feature = "tx")]
+    pub async fn test_escalation_wrong_sequence() -> anyhow::Result<()> {
+        use crate::audit::{CallbackAuditSink, SigningRecord};
+        use crate::client::wire_log::{Replay, WireLogEntry};
+        use crate::notify::{CallbackNotifier, TxEvent};
+        let secp = Secp256k1::new();
+        let key = PrivateKey::from_words(&secp, "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut", 0, 0)?;
+        let from = key.public_key(&secp).account()?;
+        let gas = GasOptions::create_with_fees("30000uusd", 200000)?;
+        let escalation = FeeEscalation::create(dec!(50), Coin::create("uusd", dec!(0.3))).blocks(0);
+        let send = vec![MsgSend::create_single(
+            from.clone(),
+            "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh".to_string(),
+            Coin::create("uusd", dec!(1)),
+        )?];
+        let get = |url: String, status: u16, response: String| WireLogEntry {
+            status: Some(status),
+            response: Some(response),
+            ..WireLogEntry::create("GET", &url, None)
+        };
+        let block_id = r#"{"hash":"","parts":{"total":0,"hash":""}}"#;
+        let mut entries = vec![
+            get(
+                format!("http://lcd/auth/accounts/{}", from),
+                200,
+                format!(
+                    r#"{{"height":"5","result":{{"type":"core/Account","value":{{"address":"{}","public_key":null,"account_number":"42","sequence":"7"}}}}}}"#,
+                    from
+                ),
+            ),
+            get(
+                "http://lcd/blocks/latest".into(),
+                200,
+                format!(
+                    r#"{{"block_id":{id},"block":{{"header":{{"version":{{"block":"11"}},"chain_id":"columbus-5","height":"5","time":"2022-01-01T00:00:00Z","last_block_id":{id},"last_commit_hash":"","data_hash":"","validators_hash":"","next_validators_hash":"","consensus_hash":"","app_hash":"","last_results_hash":"","evidence_hash":"","proposer_address":""}},"data":{{"txs":null}},"evidence":{{}},"last_commit":{{"height":"4","round":0,"block_id":{id},"signatures":[]}}}}}}"#,
+                    id = block_id
+                ),
+            ),
+            get(
+                "http://lcd/cosmos/tx/v1beta1/txs/TX0".into(),
+                404,
+                r#"{"code":5,"message":"tx not found"}"#.into(),
+            ),
+        ];
+        // the original goes in, and the node turns the higher fee away as the original is still in its mempool
+        let account = AuthAccount {
+            address: from.clone(),
+            public_key: None,
+            account_number: 42,
+            sequence: Some(7),
+        };
+        for (attempt, response) in [
+            r#"{"txhash":"TX0","raw_log":"[]"}"#,
+            r#"{"txhash":"TX1","code":32,"raw_log":"account sequence mismatch, expected 8, got 7"}"#,
+        ]
+        .iter()
+        .enumerate()
+        {
+            let signer = Terra::lcd_client(
+                "http://lcd",
+                "columbus-5",
+                &escalation.gas_options(&gas, attempt as u32)?,
+                None,
+            );
+            let (std_sign_msg, sigs) = signer
+                .sign_as(&secp, &key, &account, send.clone(), None)
+                .await?;
+            let body = serde_json::to_string(&StdTx::from_StdSignMsg(&std_sign_msg, &sigs, "sync"))?;
+            entries.push(WireLogEntry {
+                status: Some(200),
+                response: Some(response.to_string()),
+                ..WireLogEntry::create("POST", "http://lcd/txs", Some(&body))
+            });
+        }
+        let events = Arc::new(std::sync::Mutex::new(vec![]));
+        let notified = events.clone();
+        let records = Arc::new(std::sync::Mutex::new(vec![]));
+        let recorded = records.clone();
+        let terra = Terra::lcd_client("http://lcd", "columbus-5", &gas, None)
+            .with_replay(Replay::create(entries))
+            .with_notifier(CallbackNotifier::create(move |event: TxEvent| {
+                notified.lock().unwrap().push(event);
+                Box::pin(async { Ok(()) })
+            }))
+            .with_audit_sink(CallbackAuditSink::create(move |record: SigningRecord| {
+                recorded.lock().unwrap().push(record);
+                Box::pin(async { Ok(()) })
+            }));
+
+        match terra
+            .submit_transaction_escalating(&secp, &key, send, None, &escalation)
+            .await
+        {
+            // it stops at the rejection, rather than going on to the cap
+            Err(TerraRustAPIError::TxConfirmationTimeout(hash, _)) => assert_eq!(hash, "TX0"),
+            other => anyhow::bail!("{:?}", other),
+        }
+        let signed = records
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.txhash.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(signed, vec![Some("TX0".to_string()), None]);
+        // the original still holds sequence 7
+        assert_eq!(terra.inner.sequences.peek(&from, 7), 8);
+        let failures = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| matches!(e, TxEvent::Failed { .. }))
+            .count();
+        assert_eq!(failures, 1);
+        Ok(())
+    }
+    #[tokio::test]
+    pub async fn test_api_version() -> anyhow::Result<()> {
+        let node_info = |url: &str, status: u16| WireLogEntry {
+            status: Some(status),
+            response: Some("{}".into()),
+            ..WireLogEntry::create("GET", url, None)
+        };
+        let replay = Replay::create(vec![
+            node_info("http://lcd/node_info", 503),
+            node_info("http://lcd/node_info", 404),
+            node_info("http://lcd/cosmos/base/tendermint/v1beta1/node_info", 200),
+        ]);
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay);
+        // a busy node isn't an answer
+        assert!(terra.api_version().await.is_err());
+        assert_eq!(terra.inner.api_version(&terra.url), 0);
+        assert_eq!(terra.api_version().await?, ApiVersion::V1Beta1);
+        // and the answer is kept
+        assert_eq!(terra.api_version().await?, ApiVersion::V1Beta1);
+        Ok(())
+    }
 }
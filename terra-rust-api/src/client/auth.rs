@@ -1,8 +1,23 @@
-use crate::auth_types::AuthAccount;
+#[cfg(feature = "tx")]
+use crate::auth_types::AccountFirstSeen;
+use crate::auth_types::{AuthAccount, DelegationSnapshot};
+use crate::client::batch::DEFAULT_MAX_CONCURRENCY;
+#[cfg(feature = "tx")]
+use crate::client::event_query::EventQuery;
 use crate::errors::TerraRustAPIError;
 use crate::staking_types::{Validator, ValidatorDelegation, ValidatorUnbondingDelegation};
 use crate::{LCDResult, LCDResultVec, LCDTypeValue, Terra};
 
+/// the events [Auth::first_seen] looks for the account in, and whether that means it signed the transaction
+#[cfg(feature = "tx")]
+pub const FIRST_SEEN_EVENTS: &[(&str, bool)] = &[
+    ("message.sender", true),
+    ("transfer.recipient", false),
+    ("transfer.sender", false),
+    ("coin_received.receiver", false),
+    ("coin_spent.spender", false),
+];
+
 pub struct Auth<'a> {
     terra: &'a Terra,
 }
@@ -23,6 +38,44 @@ impl Auth<'_> {
             )
             .await
     }
+    /// the account as it was at a block: its number, and how many transactions it had sent.
+    /// old heights need an archive node
+    pub async fn account_at(
+        &self,
+        account_address: &str,
+        height: u64,
+    ) -> Result<AuthAccount, TerraRustAPIError> {
+        Ok(self
+            .account(account_address, Some(height))
+            .await?
+            .result
+            .value)
+    }
+    /// the account's first transaction, either signing it or turning up in one: sending or being
+    /// sent coins, including by a contract or module. one search per event in [FIRST_SEEN_EVENTS].
+    /// None if the node's index has no transactions for it
+    #[cfg(feature = "tx")]
+    pub async fn first_seen(
+        &self,
+        account_address: &str,
+    ) -> Result<Option<AccountFirstSeen>, TerraRustAPIError> {
+        let mut seen: Option<AccountFirstSeen> = None;
+        for (key, sender) in FIRST_SEEN_EVENTS {
+            let query = EventQuery::new().eq(key, account_address);
+            let txs = self.terra.tx().search(&query, None, Some(1)).await?;
+            if let Some(tx) = txs.tx_responses.into_iter().next() {
+                if !matches!(&seen, Some(s) if s.height <= tx.height) {
+                    seen = Some(AccountFirstSeen {
+                        height: tx.height,
+                        txhash: tx.txhash,
+                        timestamp: tx.timestamp,
+                        sender: *sender,
+                    });
+                }
+            }
+        }
+        Ok(seen)
+    }
     /// all delegations for a given account
     pub async fn validator_delegations(
         &self,
@@ -68,4 +121,106 @@ impl Auth<'_> {
             )
             .await
     }
+    /// the account's delegations at each of the heights, in the order given.
+    /// the node has to still hold the state for them all, so old heights need an archive node
+    pub async fn delegation_history(
+        &self,
+        account_address: &str,
+        heights: &[u64],
+    ) -> Result<Vec<DelegationSnapshot>, TerraRustAPIError> {
+        let mut batch = self.terra.batch(DEFAULT_MAX_CONCURRENCY);
+        batch.extend(heights.iter().map(|&height| async move {
+            self.validator_delegations(account_address, Some(height))
+                .await
+                .map(|delegations| DelegationSnapshot {
+                    height,
+                    delegations: delegations.result,
+                })
+        }));
+        batch.run().await.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::wire_log::{Replay, WireLogEntry};
+
+    fn entry(url: &str, response: String) -> WireLogEntry {
+        WireLogEntry {
+            status: Some(200),
+            response: Some(response),
+            ..WireLogEntry::create("GET", url, None)
+        }
+    }
+
+    #[cfg(feature = "tx")]
+    fn search(query: EventQuery, height: Option<u64>) -> anyhow::Result<WireLogEntry> {
+        let url = format!(
+            "http://lcd/cosmos/tx/v1beta1/txs?{}&order_by=ORDER_BY_ASC&pagination.limit=1&pagination.offset=0",
+            query.to_lcd_events()?
+        );
+        let responses = match height {
+            Some(height) => format!(
+                r#"[{{"height":"{}","txhash":"TX{}","codespace":"","code":0,"data":"","raw_log":"","info":"","gas_wanted":"1","gas_used":"1","tx":{{}},"timestamp":"2021-10-0{}T00:00:00Z","events":null,"logs":null}}]"#,
+                height,
+                height,
+                height % 10
+            ),
+            None => "[]".into(),
+        };
+        Ok(entry(
+            &url,
+            format!(
+                r#"{{"txs":[],"tx_responses":{},"pagination":{{"next_key":null,"total":"0"}}}}"#,
+                responses
+            ),
+        ))
+    }
+
+    #[tokio::test]
+    pub async fn test_account_at() -> anyhow::Result<()> {
+        let replay = Replay::create(vec![entry(
+            "http://lcd/auth/accounts/terra1holder?height=1234",
+            r#"{"height":"1234","result":{"type":"core/Account","value":{"address":"terra1holder","public_key":null,"account_number":"42","sequence":"7"}}}"#.into(),
+        )]);
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay);
+        let account = terra.auth().account_at("terra1holder", 1234).await?;
+        assert_eq!(account.account_number, 42);
+        assert_eq!(account.sequence, Some(7));
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tx")]
+    pub async fn test_first_seen() -> anyhow::Result<()> {
+        let query = |key: &str| EventQuery::new().eq(key, "terra1holder");
+        // the account was first paid by a contract, which only shows in coin_received
+        let replay = Replay::create(vec![
+            search(query("message.sender"), Some(205))?,
+            search(query("transfer.recipient"), Some(103))?,
+            search(query("transfer.sender"), None)?,
+            search(query("coin_received.receiver"), Some(101))?,
+            search(query("coin_spent.spender"), Some(205))?,
+        ]);
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay);
+        let seen = terra.auth().first_seen("terra1holder").await?.unwrap();
+        assert_eq!(seen.height, 101);
+        assert_eq!(seen.txhash, "TX101");
+        assert!(!seen.sender);
+        assert_eq!(
+            seen.age("2021-10-02T00:00:00Z".parse()?),
+            chrono::Duration::days(1)
+        );
+
+        let replay = Replay::create(
+            FIRST_SEEN_EVENTS
+                .iter()
+                .map(|(key, _)| search(query(key), None))
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        );
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay);
+        assert_eq!(terra.auth().first_seen("terra1holder").await?, None);
+        Ok(())
+    }
 }
@@ -1,6 +1,9 @@
-use crate::client::client_types::{terra_opt_u64_format, terra_u64_format};
+use crate::client::client_types::{terra_datetime_format, terra_opt_u64_format, terra_u64_format};
+use chrono::{DateTime, Duration, Utc};
 
 use crate::core_types::PubKeySig;
+use crate::staking_types::ValidatorDelegation;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 /// This structure serves a few purposes
@@ -19,3 +22,69 @@ pub struct AuthAccount {
     #[serde(default, with = "terra_opt_u64_format")]
     pub sequence: Option<u64>,
 }
+
+/// an account's delegations as they were at a block. see [crate::client::auth::Auth::delegation_history]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DelegationSnapshot {
+    pub height: u64,
+    pub delegations: Vec<ValidatorDelegation>,
+}
+impl DelegationSnapshot {
+    /// the total delegated in a denom, across all validators
+    pub fn total(&self, denom: &str) -> Decimal {
+        self.delegations
+            .iter()
+            .filter(|d| d.balance.denom == denom)
+            .map(|d| d.balance.amount)
+            .sum()
+    }
+    /// the amount delegated to one validator, if any
+    pub fn to_validator(&self, validator_address: &str) -> Option<Decimal> {
+        self.delegations
+            .iter()
+            .find(|d| d.delegation.validator_address == validator_address)
+            .map(|d| d.balance.amount)
+    }
+}
+
+/// the earliest transaction an account was in. see [crate::client::auth::Auth::first_seen]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccountFirstSeen {
+    pub height: u64,
+    pub txhash: String,
+    #[serde(with = "terra_datetime_format")]
+    pub timestamp: DateTime<Utc>,
+    /// the account signed it, rather than being sent coins in it
+    pub sender: bool,
+}
+impl AccountFirstSeen {
+    /// how long the account has been around at `now`
+    pub fn age(&self, now: DateTime<Utc>) -> Duration {
+        now - self.timestamp
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_delegation_snapshot() -> anyhow::Result<()> {
+        let delegations: Vec<ValidatorDelegation> = serde_json::from_str(
+            r#"[{"delegation":{"delegator_address":"terra1a","validator_address":"terravaloper1x","shares":"100.5"},"balance":{"denom":"uluna","amount":"100"}},
+                {"delegation":{"delegator_address":"terra1a","validator_address":"terravaloper1y","shares":"50"},"balance":{"denom":"uluna","amount":"50"}}]"#,
+        )?;
+        let snapshot = DelegationSnapshot {
+            height: 10,
+            delegations,
+        };
+        assert_eq!(snapshot.total("uluna"), Decimal::from(150));
+        assert_eq!(snapshot.total("uusd"), Decimal::ZERO);
+        assert_eq!(
+            snapshot.to_validator("terravaloper1y"),
+            Some(Decimal::from(50))
+        );
+        assert_eq!(snapshot.to_validator("terravaloper1z"), None);
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
+use crate::client::bank_types::{DenomMetadata, DenomsMetadataResponse};
+use crate::client::denom_registry::DenomRegistry;
 use crate::core_types::Coin;
+#[cfg(feature = "rpc")]
+use crate::{errors::TerraRustAPIError, proof, PublicKey};
 use crate::{LCDResultVec, Terra};
 
 pub struct Bank<'a> {
@@ -23,4 +27,43 @@ impl Bank<'_> {
             .await?;
         Ok(response)
     }
+    /// the metadata the bank module has for the chain's denoms
+    pub async fn denoms_metadata(&self) -> anyhow::Result<Vec<DenomMetadata>> {
+        let response = self
+            .terra
+            .send_cmd::<DenomsMetadataResponse>("/cosmos/bank/v1beta1/denoms_metadata", None, None)
+            .await?;
+        Ok(response.metadatas)
+    }
+    /// Terra's native coins, with the chain's denom metadata on top.
+    /// if the metadata can't be fetched, just the native coins
+    pub async fn denom_registry(&self) -> DenomRegistry {
+        match self.denoms_metadata().await {
+            Ok(metadata) => DenomRegistry::terra().with_metadata(metadata),
+            Err(e) => {
+                log::warn!("no denom metadata from the LCD: {}", e);
+                DenomRegistry::terra()
+            }
+        }
+    }
+    /// the balance of `denom` held by `account_address` at `height`, read through the RPC
+    /// and proven against `app_hash`. `app_hash` must come from the verified header at `height + 1`
+    #[cfg(feature = "rpc")]
+    pub async fn verified_balance(
+        &self,
+        rpc_url: &str,
+        account_address: &str,
+        denom: &str,
+        height: u64,
+        app_hash: &[u8],
+    ) -> Result<Coin, TerraRustAPIError> {
+        let account = PublicKey::from_account(account_address)?;
+        let raw_address = account
+            .raw_address()
+            .ok_or_else(|| TerraRustAPIError::InvalidAddress(account_address.into()))?;
+        let key = proof::balance_store_key(raw_address, denom);
+        let value =
+            proof::verified_query(self.terra, rpc_url, "bank", &key, height, app_hash).await?;
+        proof::decode_coin(&value)
+    }
 }
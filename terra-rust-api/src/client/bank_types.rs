@@ -0,0 +1,98 @@
+use crate::errors::TerraRustAPIError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// one of the units a denomination is counted in, like `luna` = 10^6 `uluna`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DenomUnit {
+    pub denom: String,
+    /// the power of 10 of the base denom one of these is. left out of the JSON when 0
+    #[serde(default)]
+    pub exponent: u32,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// what the bank module knows about a denomination
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DenomMetadata {
+    #[serde(default)]
+    pub description: String,
+    pub denom_units: Vec<DenomUnit>,
+    /// the denom coins are counted in on chain, like `uluna`
+    pub base: String,
+    /// the unit to show people, like `luna`
+    #[serde(default)]
+    pub display: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub symbol: String,
+}
+impl DenomMetadata {
+    /// a base denom, and a display unit 10^`exponent` of it
+    pub fn create(base: &str, display: &str, exponent: u32, symbol: &str) -> DenomMetadata {
+        DenomMetadata {
+            description: String::new(),
+            denom_units: vec![
+                DenomUnit {
+                    denom: base.into(),
+                    exponent: 0,
+                    aliases: vec![],
+                },
+                DenomUnit {
+                    denom: display.into(),
+                    exponent,
+                    aliases: vec![],
+                },
+            ],
+            base: base.into(),
+            display: display.into(),
+            name: symbol.into(),
+            symbol: symbol.into(),
+        }
+    }
+    /// the unit with this denom or alias, ignoring case
+    pub fn unit(&self, denom: &str) -> Option<&DenomUnit> {
+        self.denom_units.iter().find(|unit| {
+            unit.denom.eq_ignore_ascii_case(denom)
+                || unit.aliases.iter().any(|a| a.eq_ignore_ascii_case(denom))
+        })
+    }
+    /// the exponent of the display unit. 0 if there isn't one
+    pub fn display_exponent(&self) -> u32 {
+        self.unit(&self.display).map(|u| u.exponent).unwrap_or(0)
+    }
+    /// the symbol, or else the display unit in capitals
+    pub fn ticker(&self) -> String {
+        if !self.symbol.is_empty() {
+            self.symbol.clone()
+        } else if !self.display.is_empty() {
+            self.display.to_uppercase()
+        } else {
+            self.base.clone()
+        }
+    }
+    /// `amount` of the base denom, in display units
+    pub fn to_display(&self, amount: Decimal) -> Result<Decimal, TerraRustAPIError> {
+        let scale = self.scale(self.display_exponent())?;
+        amount.checked_div(scale).ok_or_else(|| {
+            TerraRustAPIError::DenomExponent(self.base.clone(), self.display_exponent())
+        })
+    }
+    /// how many of the base denom one of a unit with this exponent is.
+    /// fails if that is more than a Decimal holds
+    pub fn scale(&self, exponent: u32) -> Result<Decimal, TerraRustAPIError> {
+        (0..exponent)
+            .try_fold(Decimal::from(1u64), |scale, _| {
+                scale.checked_mul(Decimal::from(10u64))
+            })
+            .ok_or_else(|| TerraRustAPIError::DenomExponent(self.base.clone(), exponent))
+    }
+}
+
+/// the response to `/cosmos/bank/v1beta1/denoms_metadata`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DenomsMetadataResponse {
+    pub metadatas: Vec<DenomMetadata>,
+}
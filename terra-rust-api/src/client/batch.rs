@@ -0,0 +1,73 @@
+use futures::future::BoxFuture;
+use futures::{Future, StreamExt};
+
+/// how many queries the library's own fan-outs (like sweeps) run at once
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+/// A set of queries run with at most `max_concurrency` in flight at once.
+/// Created by [crate::Terra::batch]. Results come back in the order the queries were added
+pub struct Batch<'a, T> {
+    pub max_concurrency: usize,
+    queries: Vec<BoxFuture<'a, T>>,
+}
+impl<'a, T> Batch<'a, T> {
+    /// a `max_concurrency` of 0 is treated as 1
+    pub fn create(max_concurrency: usize) -> Batch<'a, T> {
+        Batch {
+            max_concurrency: max_concurrency.max(1),
+            queries: vec![],
+        }
+    }
+    /// add a query
+    pub fn push<F: Future<Output = T> + Send + 'a>(&mut self, query: F) -> &mut Self {
+        self.queries.push(Box::pin(query));
+        self
+    }
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+    /// run them all
+    pub async fn run(self) -> Vec<T> {
+        futures::stream::iter(self.queries)
+            .buffered(self.max_concurrency)
+            .collect()
+            .await
+    }
+}
+impl<'a, T, F: Future<Output = T> + Send + 'a> Extend<F> for Batch<'a, T> {
+    fn extend<I: IntoIterator<Item = F>>(&mut self, queries: I) {
+        for query in queries {
+            self.push(query);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    pub async fn test_batch() {
+        let running = AtomicUsize::new(0);
+        let most = AtomicUsize::new(0);
+        let mut batch = Batch::create(3);
+        batch.extend((0..10u64).map(|i| {
+            let running = &running;
+            let most = &most;
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                most.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(tokio::time::Duration::from_millis(10 - i)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        }));
+        assert_eq!(batch.len(), 10);
+        assert_eq!(batch.run().await, (0..10).collect::<Vec<_>>());
+        assert_eq!(most.load(Ordering::SeqCst), 3);
+    }
+}
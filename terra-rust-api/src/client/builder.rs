@@ -0,0 +1,570 @@
+use crate::client::memo::MemoPolicy;
+use crate::client::pruned::PrunedHeights;
+use crate::client::request_signing::HmacSigner;
+use crate::client::wire_log::{Replay, WireLog};
+use crate::core_types::Coin;
+use crate::errors::TerraRustAPIError;
+use crate::{
+    ApiKey, ApiVersion, GasOptions, GasOptionsBuilder, HttpOptions, Terra, TxLimits, UnknownFields,
+};
+
+/// the LCD for main-net
+pub const DEFAULT_LCD: &str = "https://lcd.terra.dev";
+/// the FCD for main-net. only used to fetch gas prices
+pub const DEFAULT_FCD: &str = "https://fcd.terra.dev";
+/// main-net
+pub const DEFAULT_CHAIN_ID: &str = "columbus-5";
+
+/// Configuration for a [Terra] client.
+///
+/// The settings, their environment variables and defaults are the same ones the CLI uses
+/// * lcd `TERRARUST_LCD` (https://lcd.terra.dev)
+/// * fcd `TERRARUST_FCD` (https://fcd.terra.dev)
+/// * chain_id `TERRARUST_CHAIN` (columbus-5). 'auto' uses whatever chain the LCD is on
+/// * gas_prices `TERRARUST_GAS_PRICES` (auto, which fetches them from the FCD)
+/// * gas_denom `TERRARUST_GAS_DENOM` (ukrw)
+/// * gas_adjustment `TERRARUST_GAS_ADJUSTMENT` (1.4)
+/// * fees `TERRARUST_FEES` (none)
+/// * gas `TERRARUST_GAS` (auto)
+/// * max_fee `TERRARUST_MAX_FEE` (none). transactions with a higher fee aren't sent
+/// * add_tax `TERRARUST_ADD_TAX` (false). add the stability tax on the coins sent to fixed fees
+/// * memo_policy `TERRARUST_MEMO_POLICY` (default). `none`, a fixed memo, or a template. see [MemoPolicy]
+/// * unix_socket `TERRARUST_UNIX_SOCKET` (none). talk to the LCD over this socket instead
+/// * websocket `TERRARUST_WEBSOCKET` (none). a tendermint RPC to wait for transactions on
+#[derive(Clone, Debug)]
+pub struct TerraBuilder {
+    lcd: String,
+    fcd: String,
+    chain_id: String,
+    gas_prices: String,
+    gas_denom: String,
+    gas_adjustment: f64,
+    fees: String,
+    gas: String,
+    max_fee: Option<String>,
+    add_tax: bool,
+    read_only: bool,
+    debug: bool,
+    debug_redacted: bool,
+    unknown_fields: UnknownFields,
+    http_options: HttpOptions,
+    check_chain_id: bool,
+    skip_validation: bool,
+    tx_limits: TxLimits,
+    tx_limits_from: Option<String>,
+    memo_policy: MemoPolicy,
+    pruned_heights: PrunedHeights,
+    api_version: Option<ApiVersion>,
+    http_cache: bool,
+    unix_socket: Option<String>,
+    websocket: Option<String>,
+    wire_log: Option<String>,
+    replay: Option<String>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+    api_key: Option<ApiKey>,
+    hmac_signer: Option<HmacSigner>,
+}
+impl Default for TerraBuilder {
+    fn default() -> Self {
+        TerraBuilder {
+            lcd: DEFAULT_LCD.into(),
+            fcd: DEFAULT_FCD.into(),
+            chain_id: DEFAULT_CHAIN_ID.into(),
+            gas_prices: "auto".into(),
+            gas_denom: "ukrw".into(),
+            gas_adjustment: 1.4,
+            fees: "".into(),
+            gas: "auto".into(),
+            max_fee: None,
+            add_tax: false,
+            read_only: false,
+            debug: false,
+            debug_redacted: false,
+            unknown_fields: UnknownFields::default(),
+            http_options: HttpOptions::default(),
+            check_chain_id: false,
+            skip_validation: false,
+            tx_limits: TxLimits::default(),
+            tx_limits_from: None,
+            memo_policy: MemoPolicy::default(),
+            pruned_heights: PrunedHeights::default(),
+            api_version: None,
+            http_cache: false,
+            unix_socket: None,
+            websocket: None,
+            wire_log: None,
+            replay: None,
+            user_agent: None,
+            headers: vec![],
+            api_key: None,
+            hmac_signer: None,
+        }
+    }
+}
+impl TerraBuilder {
+    /// the defaults, overridden by any TERRARUST_ environment variables that are set
+    pub fn from_env() -> Result<TerraBuilder, TerraRustAPIError> {
+        let mut builder = TerraBuilder::default();
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        if let Some(lcd) = env("TERRARUST_LCD") {
+            builder.lcd = lcd;
+        }
+        if let Some(fcd) = env("TERRARUST_FCD") {
+            builder.fcd = fcd;
+        }
+        if let Some(chain_id) = env("TERRARUST_CHAIN") {
+            builder.chain_id = chain_id;
+        }
+        if let Some(gas_prices) = env("TERRARUST_GAS_PRICES") {
+            builder.gas_prices = gas_prices;
+        }
+        if let Some(gas_denom) = env("TERRARUST_GAS_DENOM") {
+            builder.gas_denom = gas_denom;
+        }
+        if let Some(gas_adjustment) = env("TERRARUST_GAS_ADJUSTMENT") {
+            builder.gas_adjustment = gas_adjustment.parse::<f64>().map_err(|_| {
+                TerraRustAPIError::Config("TERRARUST_GAS_ADJUSTMENT".into(), gas_adjustment)
+            })?;
+        }
+        if let Some(fees) = env("TERRARUST_FEES") {
+            builder.fees = fees;
+        }
+        if let Some(gas) = env("TERRARUST_GAS") {
+            builder.gas = gas;
+        }
+        if let Some(max_fee) = env("TERRARUST_MAX_FEE") {
+            builder.max_fee = Some(max_fee);
+        }
+        if let Some(add_tax) = env("TERRARUST_ADD_TAX") {
+            builder.add_tax = add_tax
+                .parse::<bool>()
+                .map_err(|_| TerraRustAPIError::Config("TERRARUST_ADD_TAX".into(), add_tax))?;
+        }
+        if let Some(memo_policy) = env("TERRARUST_MEMO_POLICY") {
+            builder.memo_policy = memo_policy.parse()?;
+        }
+        if let Some(unix_socket) = env("TERRARUST_UNIX_SOCKET") {
+            builder = builder.unix_socket(unix_socket);
+        }
+        if let Some(websocket) = env("TERRARUST_WEBSOCKET") {
+            builder = builder.websocket(websocket);
+        }
+        if let Some(wire_log) = env("TERRARUST_WIRE_LOG") {
+            builder = builder.wire_log(wire_log);
+        }
+        if let Some(replay) = env("TERRARUST_REPLAY") {
+            builder = builder.replay(replay);
+        }
+        if let Some(user_agent) = env("TERRARUST_USER_AGENT") {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(archives) = env("TERRARUST_ARCHIVE_LCD") {
+            builder.pruned_heights.archives = archives.split(',').map(String::from).collect();
+        }
+        if let Some(nearest) = env("TERRARUST_NEAREST_HEIGHT") {
+            builder.pruned_heights.nearest = nearest == "true" || nearest == "1";
+        }
+        if let Some(key) = env("TERRARUST_API_KEY") {
+            builder = builder.api_key(match env("TERRARUST_API_KEY_HEADER") {
+                Some(header) => ApiKey::Header(header, key),
+                None => ApiKey::Bearer(key),
+            });
+        }
+        if let (Some(key_id), Some(secret)) =
+            (env("TERRARUST_HMAC_KEY_ID"), env("TERRARUST_HMAC_SECRET"))
+        {
+            builder = builder.hmac_signer(HmacSigner::create(key_id, secret));
+        }
+        Ok(builder)
+    }
+    /// the LCD to talk to
+    pub fn lcd<S: Into<String>>(mut self, lcd: S) -> Self {
+        self.lcd = lcd.into();
+        self
+    }
+    /// the FCD, used to fetch gas prices when they are 'auto'
+    pub fn fcd<S: Into<String>>(mut self, fcd: S) -> Self {
+        self.fcd = fcd.into();
+        self
+    }
+    /// the chain id, or 'auto' to ask the LCD
+    pub fn chain_id<S: Into<String>>(mut self, chain_id: S) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+    /// NNNdenom, or 'auto' to fetch them from the FCD
+    pub fn gas_prices<S: Into<String>>(mut self, gas_prices: S) -> Self {
+        self.gas_prices = gas_prices.into();
+        self
+    }
+    /// the denom to pay fees in, when the gas prices are 'auto'
+    pub fn gas_denom<S: Into<String>>(mut self, gas_denom: S) -> Self {
+        self.gas_denom = gas_denom.into();
+        self
+    }
+    pub fn gas_adjustment(mut self, gas_adjustment: f64) -> Self {
+        self.gas_adjustment = gas_adjustment;
+        self
+    }
+    /// hard-coded fees eg. 1000uluna. empty for none
+    pub fn fees<S: Into<String>>(mut self, fees: S) -> Self {
+        self.fees = fees.into();
+        self
+    }
+    /// gas amount, or 'auto' to estimate
+    pub fn gas<S: Into<String>>(mut self, gas: S) -> Self {
+        self.gas = gas.into();
+        self
+    }
+    /// the most to pay in fees, eg. 5000000uusd
+    pub fn max_fee<S: Into<String>>(mut self, max_fee: S) -> Self {
+        self.max_fee = Some(max_fee.into());
+        self
+    }
+    /// add the stability tax on the coins sent to fixed fees
+    pub fn add_tax(mut self, add_tax: bool) -> Self {
+        self.add_tax = add_tax;
+        self
+    }
+    /// build a client without gas options, that can't send transactions
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+    /// debug logging, with secrets masked. see [crate::redact]
+    pub fn debug_redacted(mut self, debug_redacted: bool) -> Self {
+        self.debug_redacted = debug_redacted;
+        self
+    }
+    pub fn unknown_fields(mut self, unknown_fields: UnknownFields) -> Self {
+        self.unknown_fields = unknown_fields;
+        self
+    }
+    pub fn http_options(mut self, http_options: HttpOptions) -> Self {
+        self.http_options = http_options;
+        self
+    }
+    /// the LCD is on this host, so use [HttpOptions::local]
+    pub fn local_node(mut self, local_node: bool) -> Self {
+        self.http_options = if local_node {
+            HttpOptions::local()
+        } else {
+            HttpOptions::default()
+        };
+        self
+    }
+    /// talk to the LCD over the node's unix domain socket, rather than `lcd`. implies [TerraBuilder::local_node]
+    pub fn unix_socket<S: Into<String>>(mut self, path: S) -> Self {
+        self.unix_socket = Some(path.into());
+        self.local_node(true)
+    }
+    /// check the chain_id against the LCD's before signing the first transaction
+    pub fn check_chain_id(mut self, check_chain_id: bool) -> Self {
+        self.check_chain_id = check_chain_id;
+        self
+    }
+    /// sign messages without checking them first. see [crate::messages::Message::validate]
+    pub fn skip_validation(mut self, skip_validation: bool) -> Self {
+        self.skip_validation = skip_validation;
+        self
+    }
+    /// don't broadcast transactions over these. see [TxLimits::check]
+    pub fn tx_limits(mut self, tx_limits: TxLimits) -> Self {
+        self.tx_limits = tx_limits;
+        self
+    }
+    /// fetch the [TxLimits] from this tendermint RPC's consensus params when building. needs the rpc feature
+    pub fn tx_limits_from<S: Into<String>>(mut self, rpc_url: S) -> Self {
+        self.tx_limits_from = Some(rpc_url.into());
+        self
+    }
+    /// what goes in the memo of the transactions the client signs
+    pub fn memo_policy(mut self, memo_policy: MemoPolicy) -> Self {
+        self.memo_policy = memo_policy;
+        self
+    }
+    /// what to do when the LCD has pruned the height a query asks for
+    pub fn pruned_heights(mut self, pruned_heights: PrunedHeights) -> Self {
+        self.pruned_heights = pruned_heights;
+        self
+    }
+    /// use these routes, instead of asking the LCD which it serves
+    pub fn api_version(mut self, api_version: ApiVersion) -> Self {
+        self.api_version = Some(api_version);
+        self
+    }
+    /// make conditional requests for things that rarely change. see [crate::client::http_cache::HttpCache]
+    pub fn http_cache(mut self, http_cache: bool) -> Self {
+        self.http_cache = http_cache;
+        self
+    }
+    /// wait for transactions on this tendermint RPC's websocket. needs the websocket feature
+    pub fn websocket<S: Into<String>>(mut self, rpc_url: S) -> Self {
+        self.websocket = Some(rpc_url.into());
+        self
+    }
+    /// append every request and response to this file. see [crate::client::wire_log::WireLog]
+    pub fn wire_log<S: Into<String>>(mut self, path: S) -> Self {
+        self.wire_log = Some(path.into());
+        self
+    }
+    /// answer requests from a wire log written earlier, instead of the LCD. see [crate::client::wire_log::Replay]
+    pub fn replay<S: Into<String>>(mut self, path: S) -> Self {
+        self.replay = Some(path.into());
+        self
+    }
+    /// identify the application to the LCD. see [Terra::with_user_agent]
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+    /// send this header with every request to the LCD
+    pub fn header<S: Into<String>>(mut self, name: S, value: S) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+    /// authenticate with a hosted LCD. only requests to it carry the key. see [Terra::with_api_key]
+    pub fn api_key(mut self, api_key: ApiKey) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+    /// sign every request for a gateway in front of the LCD. see [Terra::with_request_signer]
+    pub fn hmac_signer(mut self, hmac_signer: HmacSigner) -> Self {
+        self.hmac_signer = Some(hmac_signer);
+        self
+    }
+
+    /// turn the gas settings into GasOptions. 'auto' gas prices are fetched from the FCD
+    pub async fn gas_options(&self) -> Result<GasOptions, TerraRustAPIError> {
+        if self.gas_prices == "auto" {
+            let gas_price = self.fcd_gas_price().await?;
+            log::info!("Using Gas price of {}", gas_price);
+            self.auto_gas_options(&gas_price)
+        } else {
+            let mut builder = GasOptions::builder().gas_price(&self.gas_prices);
+            if !self.fees.is_empty() {
+                builder = builder.fees(&self.fees);
+            }
+            builder = if self.gas == "auto" {
+                builder.estimate_gas().adjustment(self.gas_adjustment)
+            } else {
+                builder.gas(
+                    self.gas
+                        .parse::<u64>()
+                        .map_err(|_| TerraRustAPIError::Config("gas".into(), self.gas.clone()))?,
+                )
+            };
+            self.with_limits(builder).build()
+        }
+    }
+    /// estimated gas at a price fetched from the FCD, checked like any other settings
+    fn auto_gas_options(&self, gas_price: &Coin) -> Result<GasOptions, TerraRustAPIError> {
+        let builder = GasOptions::builder()
+            .gas_price(gas_price.to_string())
+            .adjustment(self.gas_adjustment);
+        self.with_limits(builder).build()
+    }
+    fn with_limits(&self, mut builder: GasOptionsBuilder) -> GasOptionsBuilder {
+        if let Some(max_fee) = &self.max_fee {
+            builder = builder.max_fee(max_fee);
+        }
+        if self.add_tax {
+            builder = builder.add_tax();
+        }
+        builder
+    }
+    #[cfg(feature = "fcd")]
+    async fn fcd_gas_price(&self) -> Result<Coin, TerraRustAPIError> {
+        let client = Terra::http_client_with(&self.http_options);
+        let prices = crate::client::fcd::FCD::fetch_gas_prices(&client, &self.fcd).await?;
+        match prices.get(&self.gas_denom) {
+            Some(price) => Ok(Coin::create(&self.gas_denom, *price)),
+            None => Err(TerraRustAPIError::GasPriceError(self.gas_denom.clone())),
+        }
+    }
+    #[cfg(not(feature = "fcd"))]
+    async fn fcd_gas_price(&self) -> Result<Coin, TerraRustAPIError> {
+        Err(TerraRustAPIError::Config(
+            "gas_prices".into(),
+            "'auto' needs the fcd feature".into(),
+        ))
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    async fn with_unix_socket(&self, terra: Terra, path: &str) -> Result<Terra, TerraRustAPIError> {
+        terra.with_unix_socket(path).await
+    }
+    #[cfg(not(all(unix, feature = "tokio")))]
+    async fn with_unix_socket(
+        &self,
+        _terra: Terra,
+        path: &str,
+    ) -> Result<Terra, TerraRustAPIError> {
+        Err(TerraRustAPIError::Config(
+            "unix_socket".into(),
+            format!("{}: needs a unix host and the tokio feature", path),
+        ))
+    }
+
+    #[cfg(feature = "websocket")]
+    fn with_websocket(&self, terra: Terra, rpc_url: &str) -> Result<Terra, TerraRustAPIError> {
+        Ok(terra.with_websocket(rpc_url))
+    }
+    #[cfg(not(feature = "websocket"))]
+    fn with_websocket(&self, _terra: Terra, rpc_url: &str) -> Result<Terra, TerraRustAPIError> {
+        Err(TerraRustAPIError::Config(
+            "websocket".into(),
+            format!("{}: needs the websocket feature", rpc_url),
+        ))
+    }
+
+    #[cfg(feature = "rpc")]
+    async fn fetch_tx_limits(
+        &self,
+        terra: &Terra,
+        rpc_url: &str,
+    ) -> Result<TxLimits, TerraRustAPIError> {
+        terra.fetch_tx_limits(rpc_url).await
+    }
+    #[cfg(not(feature = "rpc"))]
+    async fn fetch_tx_limits(
+        &self,
+        _terra: &Terra,
+        rpc_url: &str,
+    ) -> Result<TxLimits, TerraRustAPIError> {
+        Err(TerraRustAPIError::Config(
+            "tx_limits_from".into(),
+            format!("{}: needs the rpc feature", rpc_url),
+        ))
+    }
+
+    /// create the client
+    pub async fn build(&self) -> Result<Terra, TerraRustAPIError> {
+        let mut terra = if self.read_only {
+            Terra::lcd_client_no_tx(self.lcd.as_str(), self.chain_id.as_str())
+        } else {
+            let gas_options = self.gas_options().await?;
+            Terra::lcd_client(
+                self.lcd.as_str(),
+                self.chain_id.as_str(),
+                &gas_options,
+                Some(self.debug),
+            )
+        }
+        .with_http_options(&self.http_options);
+        if let Some(user_agent) = &self.user_agent {
+            terra = terra.with_user_agent(user_agent)?;
+        }
+        for (name, value) in &self.headers {
+            terra = terra.with_header(name, value)?;
+        }
+        if let Some(api_key) = &self.api_key {
+            terra = terra.with_api_key(api_key.clone())?;
+        }
+        if let Some(hmac_signer) = &self.hmac_signer {
+            terra = terra.with_request_signer(hmac_signer.clone());
+        }
+        terra.debug = self.debug;
+        terra.debug_redacted = self.debug_redacted;
+        terra.unknown_fields = self.unknown_fields;
+        terra.check_chain_id = self.check_chain_id;
+        terra.skip_validation = self.skip_validation;
+        terra.tx_limits = self.tx_limits;
+        terra.memo_policy = self.memo_policy.clone();
+        terra.pruned_heights = self.pruned_heights.clone();
+        if let Some(wire_log) = &self.wire_log {
+            terra = terra.with_wire_log(WireLog::to_file(wire_log)?);
+        }
+        if let Some(replay) = &self.replay {
+            terra = terra.with_replay(Replay::from_file(replay)?);
+        }
+        if let Some(websocket) = &self.websocket {
+            terra = self.with_websocket(terra, websocket)?;
+        }
+        if let Some(api_version) = self.api_version {
+            terra.set_api_version(api_version);
+        }
+        if self.http_cache {
+            terra = terra.with_http_cache();
+        }
+        if let Some(unix_socket) = &self.unix_socket {
+            terra = self.with_unix_socket(terra, unix_socket).await?;
+        }
+        if let Some(rpc_url) = &self.tx_limits_from {
+            terra.tx_limits = self.fetch_tx_limits(&terra, rpc_url).await?;
+        }
+        if self.chain_id == "auto" {
+            terra.chain_id = terra.node_chain_id().await?;
+        }
+        Ok(terra)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[tokio::test]
+    pub async fn test_gas_options() -> anyhow::Result<()> {
+        let gas_options = TerraBuilder::default()
+            .gas_prices("0.15uluna")
+            .gas("200000")
+            .fees("30000uluna")
+            .gas_options()
+            .await?;
+        assert!(!gas_options.estimate_gas);
+        assert_eq!(gas_options.gas, Some(200000));
+        assert_eq!(gas_options.fees.unwrap().to_string(), "30000uluna");
+        assert_eq!(gas_options.gas_price.unwrap().denom, "uluna");
+
+        let terra = TerraBuilder::default()
+            .chain_id("bombay-12")
+            .read_only(true)
+            .build()
+            .await?;
+        assert_eq!(terra.chain_id, "bombay-12");
+        assert!(terra.gas_options.is_none());
+
+        assert!(TerraBuilder::default()
+            .gas_prices("0.15uluna")
+            .gas("lots")
+            .gas_options()
+            .await
+            .is_err());
+        // fixed fees, but the gas left to be estimated
+        assert!(TerraBuilder::default()
+            .gas_prices("0.15uluna")
+            .fees("30000uluna")
+            .gas_options()
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_auto_gas_options() -> anyhow::Result<()> {
+        let price = Coin::parse("0.15ukrw")?.unwrap();
+        let gas_options = TerraBuilder::default()
+            .max_fee("5000000ukrw")
+            .auto_gas_options(&price)?;
+        assert!(gas_options.estimate_gas());
+        assert_eq!(gas_options.gas_adjustment(), Some(1.4));
+        assert_eq!(gas_options.max_fee().unwrap().to_string(), "5000000ukrw");
+        // the fee is paid in ukrw, so a uusd limit would never be checked
+        assert!(TerraBuilder::default()
+            .max_fee("5000000uusd")
+            .auto_gas_options(&price)
+            .is_err());
+        // estimated fees already include the tax
+        assert!(TerraBuilder::default()
+            .add_tax(true)
+            .auto_gas_options(&price)
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+//! Stopping requests part way. Every future the client returns can be dropped at any point, which aborts the
+//! request in flight; a [CancelToken] does the same for all the requests of every client it was given to,
+//! from anywhere, such as a GUI's cancel button. Loops that wait, like [crate::client::tx::TX::confirmation],
+//! stop waiting as soon as it is cancelled, and fail with [crate::errors::TerraRustAPIError::Cancelled]
+
+use futures::channel::oneshot;
+use futures::future::{FutureExt, Shared};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// cancels the requests of the clients given it with [crate::Terra::with_cancel]. clones cancel together
+/// ```
+/// use terra_rust_api::client::cancel::CancelToken;
+/// use terra_rust_api::Terra;
+/// let token = CancelToken::new();
+/// let terra = Terra::lcd_client_no_tx("https://lcd.terra.dev", "columbus-5").with_cancel(&token);
+/// token.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    sender: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+    receiver: Shared<oneshot::Receiver<()>>,
+}
+impl Default for CancelToken {
+    fn default() -> Self {
+        CancelToken::new()
+    }
+}
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        let (sender, receiver) = oneshot::channel();
+        CancelToken {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            sender: Arc::new(Mutex::new(Some(sender))),
+            receiver: receiver.shared(),
+        }
+    }
+    /// stop every request of the clients given this token, or a clone of it, now and from now on
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        // dropping the sender wakes everything waiting on the receiver
+        self.sender.lock().unwrap().take();
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+    /// completes once the token is cancelled
+    pub async fn cancelled(&self) {
+        let _ = self.receiver.clone().await;
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[tokio::test]
+    pub async fn test_cancel() {
+        let token = CancelToken::new();
+        let waiting = token.clone();
+        let waiter = tokio::spawn(async move { waiting.cancelled().await });
+        assert!(!token.is_cancelled());
+        token.cancel();
+        tokio::time::timeout(std::time::Duration::from_secs(5), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        // already cancelled
+        token.cancelled().await;
+    }
+
+    #[tokio::test]
+    pub async fn test_client() -> anyhow::Result<()> {
+        use crate::client::wire_log::{Replay, WireLogEntry};
+        use crate::errors::TerraRustAPIError;
+        use crate::Terra;
+        let replay = Replay::create(vec![WireLogEntry {
+            status: Some(200),
+            response: Some(r#"{"height":"1","result":{"type":"core/Account","value":{"address":"terra1holder","public_key":null,"account_number":"42","sequence":"7"}}}"#.into()),
+            ..WireLogEntry::create("GET", "http://lcd/auth/accounts/terra1holder", None)
+        }]);
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay);
+        let token = CancelToken::new();
+        let cancellable = terra.with_cancel(&token);
+        assert!(cancellable
+            .auth()
+            .account("terra1holder", None)
+            .await
+            .is_ok());
+        token.cancel();
+        let cancelled = cancellable.auth().account("terra1holder", None).await;
+        assert!(
+            matches!(cancelled, Err(ref e) if matches!(e.without_context(), TerraRustAPIError::Cancelled))
+        );
+        let late = terra
+            .with_timeout(std::time::Duration::ZERO)
+            .auth()
+            .account("terra1holder", None)
+            .await;
+        assert!(
+            matches!(late, Err(ref e) if matches!(e.without_context(), TerraRustAPIError::DeadlineExceeded(_)))
+        );
+        assert!(terra.auth().account("terra1holder", None).await.is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use crate::client::builder::TerraBuilder;
+#[cfg(feature = "tx")]
+use crate::client::tx_types::TXResultSync;
+use crate::core_types::Coin;
+use crate::errors::TerraRustAPIError;
+#[cfg(feature = "tx")]
+use crate::messages::ibc::MsgTransfer;
+use crate::{shared_secp256k1, PrivateKey, Terra};
+use futures::future::join_all;
+use std::collections::BTreeMap;
+#[cfg(feature = "tx")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// a client, and the key it signs with
+#[derive(Clone)]
+pub struct ManagedChain {
+    pub terra: Terra,
+    pub signer: Option<PrivateKey>,
+}
+
+/// Clients for several chains, keyed by chain id, for apps that work across them, like relayers
+/// and arbitrage bots. Each may have a key to sign with
+#[derive(Clone, Default)]
+pub struct ChainManager {
+    chains: BTreeMap<String, ManagedChain>,
+}
+impl ChainManager {
+    pub fn new() -> ChainManager {
+        ChainManager::default()
+    }
+    /// a read-only chain, replacing any with the same chain id
+    pub fn add(&mut self, terra: Terra) -> &mut ChainManager {
+        self.chains.insert(
+            terra.chain_id.clone(),
+            ManagedChain {
+                terra,
+                signer: None,
+            },
+        );
+        self
+    }
+    /// a chain `signer` sends transactions on
+    pub fn add_with_signer(&mut self, terra: Terra, signer: PrivateKey) -> &mut ChainManager {
+        self.chains.insert(
+            terra.chain_id.clone(),
+            ManagedChain {
+                terra,
+                signer: Some(signer),
+            },
+        );
+        self
+    }
+    /// build a client and add it. an `auto` chain id is the one the LCD is on
+    pub async fn add_from_builder(
+        &mut self,
+        builder: &TerraBuilder,
+        signer: Option<PrivateKey>,
+    ) -> Result<&mut ChainManager, TerraRustAPIError> {
+        let terra = builder.build().await?;
+        Ok(match signer {
+            Some(signer) => self.add_with_signer(terra, signer),
+            None => self.add(terra),
+        })
+    }
+    pub fn remove(&mut self, chain_id: &str) -> Option<ManagedChain> {
+        self.chains.remove(chain_id)
+    }
+    pub fn chain_ids(&self) -> Vec<&str> {
+        self.chains.keys().map(|k| k.as_str()).collect()
+    }
+    pub fn chain(&self, chain_id: &str) -> Result<&Terra, TerraRustAPIError> {
+        self.managed(chain_id).map(|chain| &chain.terra)
+    }
+    fn managed(&self, chain_id: &str) -> Result<&ManagedChain, TerraRustAPIError> {
+        self.chains
+            .get(chain_id)
+            .ok_or_else(|| TerraRustAPIError::UnknownChain(chain_id.into()))
+    }
+    pub fn signer(&self, chain_id: &str) -> Result<&PrivateKey, TerraRustAPIError> {
+        self.managed(chain_id)?
+            .signer
+            .as_ref()
+            .ok_or_else(|| TerraRustAPIError::NoSigner(chain_id.into()))
+    }
+    /// the account the chain's signer sends from
+    pub fn account(&self, chain_id: &str) -> Result<String, TerraRustAPIError> {
+        self.signer(chain_id)?
+            .public_key(shared_secp256k1())
+            .account()
+    }
+    /// what `address` holds on one chain
+    pub async fn balances(&self, chain_id: &str, address: &str) -> anyhow::Result<Vec<Coin>> {
+        let terra = self.chain(chain_id)?;
+        Ok(terra.bank().balances(address, None).await?.result)
+    }
+    /// what `address` holds on every chain, asking them all at once
+    pub async fn balances_everywhere(
+        &self,
+        address: &str,
+    ) -> BTreeMap<String, anyhow::Result<Vec<Coin>>> {
+        let balances = join_all(
+            self.chains
+                .keys()
+                .map(|chain_id| self.balances(chain_id, address)),
+        )
+        .await;
+        self.chains.keys().cloned().zip(balances).collect()
+    }
+    /// send `token` from the signer of `chain_id` over its IBC `channel` to `receiver` on the other side.
+    /// the packet times out after `timeout`
+    #[cfg(feature = "tx")]
+    pub async fn ibc_transfer(
+        &self,
+        chain_id: &str,
+        channel: &str,
+        receiver: &str,
+        token: Coin,
+        timeout: Duration,
+    ) -> Result<TXResultSync, TerraRustAPIError> {
+        let chain = self.managed(chain_id)?;
+        let signer = self.signer(chain_id)?;
+        let sender = self.account(chain_id)?;
+        let timeout_timestamp = (SystemTime::now() + timeout)
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        let transfer = MsgTransfer::create(&sender, channel, receiver, token, timeout_timestamp)?;
+        chain
+            .terra
+            .submit_transaction_sync(shared_secp256k1(), signer, vec![transfer], None)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::wire_log::{Replay, WireLogEntry};
+    use rust_decimal_macros::dec;
+
+    fn chain(chain_id: &str, lcd: &str, denom: &str) -> Terra {
+        let url = format!("{}/bank/balances/terra1holder", lcd);
+        let response = format!(
+            r#"{{"height":"5","result":[{{"denom":"{}","amount":"42"}}]}}"#,
+            denom
+        );
+        let replay = Replay::create(vec![WireLogEntry {
+            status: Some(200),
+            response: Some(response),
+            ..WireLogEntry::create("GET", &url, None)
+        }]);
+        Terra::lcd_client_no_tx(lcd, chain_id).with_replay(replay)
+    }
+
+    #[tokio::test]
+    pub async fn test_chain_manager() -> anyhow::Result<()> {
+        let mut manager = ChainManager::new();
+        manager
+            .add(chain("columbus-5", "http://classic", "uluna"))
+            .add(chain("phoenix-1", "http://phoenix", "uusd"));
+        assert_eq!(manager.chain_ids(), vec!["columbus-5", "phoenix-1"]);
+        assert_eq!(
+            manager.balances("phoenix-1", "terra1holder").await?,
+            vec![Coin::create("uusd", dec!(42))]
+        );
+        let everywhere = manager.balances_everywhere("terra1holder").await;
+        assert_eq!(everywhere.len(), 2);
+        assert_eq!(everywhere["columbus-5"].as_ref().unwrap()[0].denom, "uluna");
+        assert!(matches!(
+            manager.chain("pisco-1"),
+            Err(TerraRustAPIError::UnknownChain(_))
+        ));
+        assert!(matches!(
+            manager.signer("columbus-5"),
+            Err(TerraRustAPIError::NoSigner(_))
+        ));
+
+        let words = "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut";
+        let key = PrivateKey::from_words(shared_secp256k1(), words, 0, 0)?;
+        let account = key.public_key(shared_secp256k1()).account()?;
+        manager.add_with_signer(chain("columbus-5", "http://classic", "uluna"), key);
+        assert_eq!(manager.account("columbus-5")?, account);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "tx")]
+    pub fn test_transfer_msg() -> anyhow::Result<()> {
+        let msg = MsgTransfer::create(
+            "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7",
+            "channel-1",
+            "osmo1receiver",
+            Coin::create("uluna", dec!(1000)),
+            1700000000000000000,
+        )?;
+        assert!(msg.validate().is_ok());
+        // the sign bytes cosmjs' amino converter gives for the same transfer: the zero height is `{}`
+        let sign_msg = crate::core_types::StdSignMsg {
+            account_number: 43045,
+            chain_id: "columbus-5".into(),
+            fee: crate::core_types::StdFee::create_single(
+                Coin::create("uluna", dec!(5000)),
+                200000,
+            ),
+            memo: "".into(),
+            msgs: vec![msg],
+            sequence: 3,
+        };
+        assert_eq!(
+            serde_json::to_string(&sign_msg)?,
+            r#"{"account_number":"43045","chain_id":"columbus-5","fee":{"amount":[{"amount":"5000","denom":"uluna"}],"gas":"200000"},"memo":"","msgs":[{"type":"cosmos-sdk/MsgTransfer","value":{"receiver":"osmo1receiver","sender":"terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7","source_channel":"channel-1","source_port":"transfer","timeout_height":{},"timeout_timestamp":"1700000000000000000","token":{"amount":"1000","denom":"uluna"}}}],"sequence":"3"}"#
+        );
+        let height = crate::messages::ibc::IbcHeight {
+            revision_number: 1,
+            revision_height: 0,
+        };
+        assert_eq!(
+            serde_json::to_string(&height)?,
+            r#"{"revision_number":"1"}"#
+        );
+        Ok(())
+    }
+}
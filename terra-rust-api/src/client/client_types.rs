@@ -1,14 +1,65 @@
 /*!
 routines used to serialize / deserialize a Cosmos / Tendermint / TerraD structure
 */
+/// helpers shared by the formats below. different node versions send the same field differently
+mod tolerant {
+    use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+    use serde::de::{self, Deserializer, Visitor};
+    use serde::Deserialize;
+    use std::fmt;
+
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+    /// a number, sent either as a string or as a JSON number
+    pub struct NumberString(pub String);
+
+    struct NumberStringVisitor;
+    impl<'de> Visitor<'de> for NumberStringVisitor {
+        type Value = NumberString;
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number or a string containing a number")
+        }
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(NumberString(v.to_string()))
+        }
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+            Ok(NumberString(v))
+        }
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(NumberString(v.to_string()))
+        }
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            Ok(NumberString(v.to_string()))
+        }
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            Ok(NumberString(v.to_string()))
+        }
+    }
+    impl<'de> Deserialize<'de> for NumberString {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(NumberStringVisitor)
+        }
+    }
+
+    /// RFC3339 with or without nanos, with a 'Z' or an offset, or without a timezone at all (assumed UTC)
+    pub fn parse_datetime(s: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+        match DateTime::parse_from_rfc3339(s) {
+            Ok(dt) => Ok(dt.with_timezone(&Utc)),
+            Err(e) => match NaiveDateTime::parse_from_str(s.trim_end_matches('Z'), FORMAT) {
+                Ok(dt) => Ok(Utc.from_utc_datetime(&dt)),
+                Err(_) => Err(e),
+            },
+        }
+    }
+}
+
 /// Convert a JSON date time into a rust one
 pub mod terra_datetime_format {
-    use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+    use super::tolerant::parse_datetime;
+    use chrono::{DateTime, Utc};
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
-    const FORMAT_TZ_SUPPLIED: &str = "%Y-%m-%dT%H:%M:%S.%f%:z";
-    const FORMAT_SHORT_Z: &str = "%Y-%m-%dT%H:%M:%SZ";
 
     // The signature of a serialize_with function must follow the pattern:
     //
@@ -40,38 +91,23 @@ pub mod terra_datetime_format {
         D: Deserializer<'de>,
     {
         let s: String = String::deserialize(deserializer)?;
-        let len = s.len();
-        let slice_len = if s.contains('.') {
-            len.saturating_sub(4)
-        } else {
-            len
-        };
-
-        let sliced = &s[0..slice_len];
-        match NaiveDateTime::parse_from_str(sliced, FORMAT) {
-            Err(_e) => match NaiveDateTime::parse_from_str(&s, FORMAT_TZ_SUPPLIED) {
-                Err(_e2) => match NaiveDateTime::parse_from_str(sliced, FORMAT_SHORT_Z) {
-                    Err(_e3) => {
-                        eprintln!("DateTime Fail {} {:#?}", s, _e);
-                        Err(serde::de::Error::custom(_e))
-                    }
-                    Ok(dt) => Ok(Utc.from_utc_datetime(&dt)),
-                },
-                Ok(dt) => Ok(Utc.from_utc_datetime(&dt)),
-            },
-            Ok(dt) => Ok(Utc.from_utc_datetime(&dt)),
+        match parse_datetime(&s) {
+            Err(_e) => {
+                eprintln!("DateTime Fail {} {:#?}", s, _e);
+                Err(serde::de::Error::custom(_e))
+            }
+            Ok(dt) => Ok(dt),
         }
     }
 }
 
 /// Convert a JSON date time into a rust one
 pub mod terra_opt_datetime_format {
-    use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+    use super::tolerant::parse_datetime;
+    use chrono::{DateTime, Utc};
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
-    const FORMAT_TZ_SUPPLIED: &str = "%Y-%m-%dT%H:%M:%S.%f%:z";
-    const FORMAT_SHORT_Z: &str = "%Y-%m-%dT%H:%M:%SZ";
 
     // The signature of a serialize_with function must follow the pattern:
     //
@@ -110,26 +146,12 @@ pub mod terra_opt_datetime_format {
         match value {
             Ok(s_opt) => {
                 if let Some(s) = s_opt {
-                    let len = s.len();
-                    let slice_len = if s.contains('.') {
-                        len.saturating_sub(4)
-                    } else {
-                        len
-                    };
-                    let sliced = &s[0..slice_len];
-                    match NaiveDateTime::parse_from_str(sliced, FORMAT) {
-                        Err(_e) => match NaiveDateTime::parse_from_str(&s, FORMAT_TZ_SUPPLIED) {
-                            Err(_e2) => match NaiveDateTime::parse_from_str(sliced, FORMAT_SHORT_Z)
-                            {
-                                Err(_e3) => {
-                                    eprintln!("DateTime Fail {} {:#?}", s, _e);
-                                    Err(serde::de::Error::custom(_e))
-                                }
-                                Ok(dt) => Ok(Some(Utc.from_utc_datetime(&dt))),
-                            },
-                            Ok(dt) => Ok(Some(Utc.from_utc_datetime(&dt))),
-                        },
-                        Ok(dt) => Ok(Some(Utc.from_utc_datetime(&dt))),
+                    match parse_datetime(&s) {
+                        Err(_e) => {
+                            eprintln!("DateTime Fail {} {:#?}", s, _e);
+                            Err(serde::de::Error::custom(_e))
+                        }
+                        Ok(dt) => Ok(Some(dt)),
                     }
                 } else {
                     Ok(None)
@@ -145,6 +167,7 @@ pub mod terra_opt_datetime_format {
 
 /// Convert a u64 number (which is sent as a string) into a u64 rust structure
 pub mod terra_u64_format {
+    use super::tolerant::NumberString;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     // convert a number in string format into a regular u64
@@ -176,7 +199,7 @@ pub mod terra_u64_format {
     where
         D: Deserializer<'de>,
     {
-        let s: String = String::deserialize(deserializer)?;
+        let s = NumberString::deserialize(deserializer)?.0;
         match s.parse::<u64>() {
             Err(_e) => {
                 eprintln!("u64 Fail {} {:#?}", s, _e);
@@ -187,8 +210,32 @@ pub mod terra_u64_format {
     }
 }
 
+/// Convert a u128 number (cosmwasm's Uint128, which is sent as a string) into a u128 rust structure
+pub mod terra_u128_format {
+    use super::tolerant::NumberString;
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    #[allow(missing_docs)]
+    pub fn serialize<S>(val: &u128, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_string())
+    }
+
+    #[allow(missing_docs)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = NumberString::deserialize(deserializer)?.0;
+        s.parse::<u128>().map_err(serde::de::Error::custom)
+    }
+}
+
 /// Convert a i64 number (which is sent as a string) into a u64 rust structure
 pub mod terra_i64_format {
+    use super::tolerant::NumberString;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     // convert a number in string format into a regular u64
@@ -220,7 +267,7 @@ pub mod terra_i64_format {
     where
         D: Deserializer<'de>,
     {
-        let s: String = String::deserialize(deserializer)?;
+        let s = NumberString::deserialize(deserializer)?.0;
         match s.parse::<i64>() {
             Err(_e) => {
                 eprintln!("i64 Fail {} {:#?}", s, _e);
@@ -233,6 +280,7 @@ pub mod terra_i64_format {
 
 /// Convert a f64 number (which is sent as a string) into a f64 rust structure
 pub mod terra_f64_format {
+    use super::tolerant::NumberString;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     // convert a number in string format into a regular u64
@@ -263,7 +311,7 @@ pub mod terra_f64_format {
     where
         D: Deserializer<'de>,
     {
-        let s: String = String::deserialize(deserializer)?;
+        let s = NumberString::deserialize(deserializer)?.0;
 
         match s.parse::<f64>() {
             Err(_e) => {
@@ -277,6 +325,7 @@ pub mod terra_f64_format {
 
 /// Convert a Decimal number (which is sent as a string) into a Decimal rust structure
 pub mod terra_decimal_format {
+    use super::tolerant::NumberString;
     use rust_decimal::Decimal;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
@@ -308,7 +357,7 @@ pub mod terra_decimal_format {
     where
         D: Deserializer<'de>,
     {
-        let s: String = String::deserialize(deserializer)?;
+        let s = NumberString::deserialize(deserializer)?.0;
 
         match s.parse::<Decimal>() {
             Err(_e) => {
@@ -322,6 +371,7 @@ pub mod terra_decimal_format {
 
 /// Convert a Optional Decimal number (which is sent as a string) into a decimal rust structure
 pub mod terra_opt_decimal_format {
+    use super::tolerant::NumberString;
     use rust_decimal::Decimal;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
@@ -356,8 +406,8 @@ pub mod terra_opt_decimal_format {
     where
         D: Deserializer<'de>,
     {
-        match String::deserialize(deserializer) {
-            Ok(s) => {
+        match NumberString::deserialize(deserializer) {
+            Ok(NumberString(s)) => {
                 if s.is_empty() {
                     Ok(None)
                 } else {
@@ -377,6 +427,7 @@ pub mod terra_opt_decimal_format {
 
 /// Convert a Optional u64 number (which is sent as a string) into a u64 rust structure
 pub mod terra_opt_u64_format {
+    use super::tolerant::NumberString;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     // convert a number in string format into a regular u64
@@ -410,8 +461,8 @@ pub mod terra_opt_u64_format {
     where
         D: Deserializer<'de>,
     {
-        match String::deserialize(deserializer) {
-            Ok(s) => {
+        match NumberString::deserialize(deserializer) {
+            Ok(NumberString(s)) => {
                 if s.is_empty() {
                     Ok(None)
                 } else {
@@ -535,3 +586,37 @@ pub mod base64_opt_encoded_format {
         }
     }
 }
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use chrono::{Datelike, Timelike};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Mixed {
+        #[serde(with = "terra_u64_format")]
+        height: u64,
+        #[serde(with = "terra_i64_format")]
+        power: i64,
+        #[serde(with = "terra_datetime_format")]
+        time: chrono::DateTime<chrono::Utc>,
+    }
+    #[test]
+    pub fn test_tolerant() -> anyhow::Result<()> {
+        for js in [
+            r#"{"height":"7","power":"-3","time":"2021-12-01T10:11:12.123456789Z"}"#,
+            r#"{"height":7,"power":-3,"time":"2021-12-01T10:11:12Z"}"#,
+            r#"{"height":"7","power":-3,"time":"2021-12-01T12:11:12.5+02:00"}"#,
+            r#"{"height":7,"power":"-3","time":"2021-12-01T10:11:12.123"}"#,
+        ] {
+            let mixed: Mixed = serde_json::from_str(js)?;
+            assert_eq!(mixed.height, 7);
+            assert_eq!(mixed.power, -3);
+            assert_eq!(mixed.time.day(), 1);
+            assert_eq!(mixed.time.hour(), 10);
+            assert_eq!(mixed.time.second(), 12);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::client::bank_types::DenomMetadata;
+use crate::core_types::Coin;
+
+/// Shows coins the way people count them, like `LUNA 1,500.25` for `1500250000uluna`.
+/// denoms without metadata keep their base amounts, and long `ibc/` hashes are shortened
+#[derive(Clone, Debug)]
+pub struct CoinFormatter {
+    metadata: Vec<DenomMetadata>,
+    /// decimal places to round to
+    pub max_decimals: u32,
+    /// separate thousands with commas
+    pub group_thousands: bool,
+}
+impl CoinFormatter {
+    pub fn create(metadata: Vec<DenomMetadata>) -> CoinFormatter {
+        CoinFormatter {
+            metadata,
+            max_decimals: 6,
+            group_thousands: true,
+        }
+    }
+    /// the metadata for a base denom
+    pub fn metadata(&self, denom: &str) -> Option<&DenomMetadata> {
+        self.metadata.iter().find(|m| m.base == denom)
+    }
+    pub fn format(&self, coin: &Coin) -> String {
+        // metadata with an exponent too large to scale by is ignored
+        let display = self
+            .metadata(&coin.denom)
+            .and_then(|metadata| Some((metadata.ticker(), metadata.to_display(coin.amount).ok()?)));
+        let (ticker, amount) = display.unwrap_or_else(|| (short_denom(&coin.denom), coin.amount));
+        let amount = amount.round_dp(self.max_decimals).normalize().to_string();
+        let amount = if self.group_thousands {
+            group_thousands(&amount)
+        } else {
+            amount
+        };
+        format!("{} {}", ticker, amount)
+    }
+    /// the coins, comma separated
+    pub fn format_coins(&self, coins: &[Coin]) -> String {
+        coins
+            .iter()
+            .map(|c| self.format(c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// `ibc/` and the first & last 4 characters of the hash
+fn short_denom(denom: &str) -> String {
+    match denom.strip_prefix("ibc/") {
+        Some(hash) if hash.len() > 12 => {
+            format!("ibc/{}…{}", &hash[..4], &hash[hash.len() - 4..])
+        }
+        _ => denom.to_string(),
+    }
+}
+
+fn group_thousands(amount: &str) -> String {
+    let (sign, unsigned) = match amount.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", amount),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    match fraction {
+        Some(fraction) => format!("{}{}.{}", sign, grouped, fraction),
+        None => format!("{}{}", sign, grouped),
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_coin_format() -> anyhow::Result<()> {
+        let luna = DenomMetadata::create("uluna", "luna", 6, "LUNA");
+        let formatter = CoinFormatter::create(vec![luna.clone()]);
+        assert_eq!(
+            formatter.format(&Coin::create("uluna", dec!(1500250000))),
+            "LUNA 1,500.25"
+        );
+        assert_eq!(
+            formatter.format(&Coin::create("uluna", dec!(1))),
+            "LUNA 0.000001"
+        );
+        assert_eq!(formatter.format(&Coin::create("uluna", dec!(0))), "LUNA 0");
+        assert_eq!(
+            formatter.format(&Coin::create(
+                "ibc/EB2CED20AB0466F18BE49285E56B31306D4C60438A022EA995BA65D5E3CF7E09",
+                dec!(566.75)
+            )),
+            "ibc/EB2C…7E09 566.75"
+        );
+        let mut plain = CoinFormatter::create(vec![luna]);
+        plain.group_thousands = false;
+        plain.max_decimals = 2;
+        assert_eq!(
+            plain.format_coins(&[
+                Coin::create("uluna", dec!(1234567891)),
+                Coin::create("ukrw", dec!(-1000))
+            ]),
+            "LUNA 1234.57, ukrw -1000"
+        );
+        // metadata too large to scale by is left out
+        let big = CoinFormatter::create(vec![DenomMetadata::create("atto", "big", 40, "BIG")]);
+        assert!(big.metadata[0].to_display(dec!(1)).is_err());
+        assert_eq!(big.format(&Coin::create("atto", dec!(7))), "atto 7");
+        assert_eq!(group_thousands("-1234567.5"), "-1,234,567.5");
+        assert_eq!(group_thousands("123"), "123");
+        Ok(())
+    }
+}
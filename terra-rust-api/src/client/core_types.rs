@@ -6,12 +6,71 @@ use rust_decimal_macros::dec;
 // use rust_decimal::prelude::*;
 use rust_decimal::Decimal;
 
+use crate::client::bank_types::DenomMetadata;
 use crate::errors::TerraRustAPIError;
 use crate::messages::Message;
+use crate::PrivateKey;
+use secp256k1::Secp256k1;
 //use base64::{ToBase64, STANDARD};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
 use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
 use std::fmt;
 
+/// paging through a cosmos-sdk list. the next page starts at the `next_key` of the previous one
+#[derive(Debug, Clone, Default)]
+pub struct Pagination {
+    /// base64 key to start at
+    pub key: Option<String>,
+    pub offset: Option<u64>,
+    pub limit: Option<u64>,
+    pub reverse: bool,
+    /// have the response's pagination say how many entries there are in all
+    pub count_total: bool,
+}
+impl Pagination {
+    /// the first `limit` entries
+    pub fn limit(limit: u64) -> Pagination {
+        Pagination {
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+    /// `limit` entries, starting at `key`
+    pub fn after(key: &str, limit: u64) -> Pagination {
+        Pagination {
+            key: Some(key.to_string()),
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+    /// as query parameters, without the leading ? or &
+    pub fn to_query(&self) -> String {
+        let mut params = vec![];
+        if let Some(key) = &self.key {
+            let key = key
+                .replace('+', "%2B")
+                .replace('/', "%2F")
+                .replace('=', "%3D");
+            params.push(format!("pagination.key={}", key));
+        }
+        if let Some(offset) = self.offset {
+            params.push(format!("pagination.offset={}", offset));
+        }
+        if let Some(limit) = self.limit {
+            params.push(format!("pagination.limit={}", limit));
+        }
+        if self.reverse {
+            params.push("pagination.reverse=true".into());
+        }
+        if self.count_total {
+            params.push("pagination.count_total=true".into());
+        }
+        params.join("&")
+    }
+}
+
 /// The primary way to denote currency
 /// NB: Internally everything is represented by their uXXX format.
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -31,26 +90,44 @@ impl Coin {
             amount,
         }
     }
-    /// Parse the string "nnnnnXXXX" format where XXXX is the coin type
+    /// Parse the string "nnnnnXXXX" format where XXXX is the coin type. the amount may have decimals,
+    /// and the denom be any the cosmos-sdk allows, like `ibc/<hash>` or `factory/<creator>/<name>`
     pub fn parse(str: &str) -> Result<Option<Coin>, TerraRustAPIError> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"^(\d+[.]?\d*)([a-zA-Z]+)$").unwrap();
-            static ref RE_IBC: Regex = Regex::new(r"^(\d+[.]?\d*)(ibc/[a-fA-F0-9]+)$").unwrap();
+            static ref RE: Regex =
+                Regex::new(r"^(\d+[.]?\d*)([a-zA-Z][a-zA-Z0-9/:._-]{0,127})$").unwrap();
         }
-        //    let RE: Regex = Regex::new(r"^(\d+)(\s+)$").unwrap();
 
-        match RE.captures(str) {
+        match RE.captures(str.trim()) {
             Some(cap) => Ok(Some(Coin::create(
                 &cap[2],
                 cap.get(1).unwrap().as_str().parse::<Decimal>()?,
             ))),
-            None => match RE_IBC.captures(str) {
-                Some(cap) => Ok(Some(Coin::create(
-                    &cap[2],
-                    cap.get(1).unwrap().as_str().parse::<Decimal>()?,
-                ))),
-                None => Ok(None),
-            },
+            None => Ok(None),
+        }
+    }
+    /// [Coin::parse], with amounts in a unit of one of the denoms in `metadata` turned into its base denom,
+    /// so "1.5luna" is 1500000uluna
+    pub fn parse_with(
+        str: &str,
+        metadata: &[DenomMetadata],
+    ) -> Result<Option<Coin>, TerraRustAPIError> {
+        let coin = match Coin::parse(str)? {
+            Some(coin) => coin,
+            None => return Ok(None),
+        };
+        let found = metadata
+            .iter()
+            .find_map(|m| m.unit(&coin.denom).map(|unit| (m, unit.exponent)));
+        match found {
+            Some((metadata, exponent)) => {
+                let amount = metadata
+                    .scale(exponent)?
+                    .checked_mul(coin.amount)
+                    .ok_or_else(|| TerraRustAPIError::CoinParseErr(str.to_string()))?;
+                Ok(Some(Coin::create(&metadata.base, amount.normalize())))
+            }
+            None => Ok(Some(coin)),
         }
     }
     /// this will take a comma delimited string of coins and return a sorted (by denom) vector of coins
@@ -122,7 +199,7 @@ impl StdFee {
 }
 /// The messages component of the transaction that gets signed.
 /// Note: The ordering is alphabetical and *IS* important
-#[derive(Serialize)]
+#[derive(Deserialize, Serialize)]
 pub struct StdSignMsg {
     #[serde(with = "terra_u64_format")]
     /// from auth::account response
@@ -140,6 +217,27 @@ pub struct StdSignMsg {
     #[serde(with = "terra_u64_format")]
     pub sequence: u64,
 }
+impl StdSignMsg {
+    /// sign the message. This can be done offline, as everything needed is in the message itself
+    pub fn sign<C: secp256k1::Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+    ) -> Result<StdSignature, TerraRustAPIError> {
+        let js = serde_json::to_string(self)?;
+        from.sign(secp, &js)
+    }
+}
+
+/// A sign message and its signatures. Used to move a transaction between the machine that signs it
+/// and the one that broadcasts it
+#[derive(Deserialize, Serialize)]
+pub struct SignedTx<S = StdSignature> {
+    /// what was signed
+    pub sign_msg: StdSignMsg,
+    /// the signature(s). a [crate::MultisigSignature] for multisig accounts
+    pub signatures: Vec<S>,
+}
 
 /// The structure used in sending the public key in a transaction
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -177,33 +275,67 @@ impl StdSignature {
             pub_key: PubKeySig::create(bpub),
         }
     }
+    /// check the signature is the public key's, over the sha256 of `blob`
+    pub fn verify<C: secp256k1::Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+        blob: &[u8],
+    ) -> Result<(), TerraRustAPIError> {
+        let mut sha = Sha256::new();
+        let mut sha_result: [u8; 32] = [0; 32];
+        sha.input(blob);
+        sha.result(&mut sha_result);
+        let message = secp256k1::Message::from_slice(&sha_result)?;
+        let signature = secp256k1::Signature::from_compact(&base64::decode(&self.signature)?)?;
+        let public_key = secp256k1::PublicKey::from_slice(&base64::decode(&self.pub_key.value)?)?;
+        Ok(secp.verify(&message, &signature, &public_key)?)
+    }
+    /// [StdSignature::verify] each of the blobs & signatures, with the shared signing context.
+    /// they are checked in parallel with the `rayon` feature. the results are in the same order
+    pub fn verify_batch<M, S>(msgs_and_sigs: &[(M, S)]) -> Vec<Result<(), TerraRustAPIError>>
+    where
+        M: AsRef<[u8]> + Sync,
+        S: Borrow<StdSignature> + Sync,
+    {
+        let secp = crate::shared_secp256k1();
+        let verify = |(blob, signature): &(M, S)| signature.borrow().verify(secp, blob.as_ref());
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            msgs_and_sigs.par_iter().map(verify).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            msgs_and_sigs.iter().map(verify).collect()
+        }
+    }
 }
 
 #[allow(missing_docs)]
 #[derive(Serialize)]
-pub struct StdTxInner<'a> {
+pub struct StdTxInner<'a, S: Serialize = StdSignature> {
     pub msg: &'a [Message],
     pub fee: &'a StdFee,
-    pub signatures: &'a [StdSignature],
+    pub signatures: &'a [S],
     pub memo: &'a str,
 }
 
 #[allow(missing_docs)]
 #[derive(Serialize)]
-pub struct StdTx<'a> {
+pub struct StdTx<'a, S: Serialize = StdSignature> {
     // #[serde(rename = "type")]
-    pub tx: StdTxInner<'a>,
+    pub tx: StdTxInner<'a, S>,
     pub mode: &'a str,
 }
-impl<'a> StdTx<'a> {
+impl<'a, S: Serialize> StdTx<'a, S> {
     /// create the TX which is used to POST to LCD
     pub fn create(
         msg: &'a [Message],
         fee: &'a StdFee,
-        signatures: &'a [StdSignature],
+        signatures: &'a [S],
         memo: &'a str,
         mode: &'a str,
-    ) -> StdTx<'a> {
+    ) -> StdTx<'a, S> {
         StdTx {
             mode,
             tx: StdTxInner {
@@ -219,9 +351,9 @@ impl<'a> StdTx<'a> {
     #[allow(non_snake_case)]
     pub fn from_StdSignMsg(
         std_sign_msg: &'a StdSignMsg,
-        signatures: &'a [StdSignature],
+        signatures: &'a [S],
         mode: &'a str,
-    ) -> StdTx<'a> {
+    ) -> StdTx<'a, S> {
         StdTx {
             mode,
             tx: StdTxInner {
@@ -308,6 +440,46 @@ mod tst {
         Ok(())
     }
     #[test]
+    fn test_parse_with() -> anyhow::Result<()> {
+        let metadata = vec![DenomMetadata::create("uluna", "luna", 6, "LUNA")];
+        assert_eq!(
+            Coin::parse_with("1.5luna", &metadata)?,
+            Some(Coin::create("uluna", dec!(1500000)))
+        );
+        assert_eq!(
+            Coin::parse_with("1.5LUNA", &metadata)?,
+            Some(Coin::create("uluna", dec!(1500000)))
+        );
+        assert_eq!(
+            Coin::parse_with("700uluna", &metadata)?,
+            Some(Coin::create("uluna", dec!(700)))
+        );
+        assert_eq!(
+            Coin::parse_with("3uusd", &metadata)?,
+            Some(Coin::create("uusd", dec!(3)))
+        );
+        assert_eq!(Coin::parse_with("luna", &metadata)?, None);
+        // 18 decimals, like an ERC20 over a bridge. too large an exponent is an error, not a wrong amount
+        let wide = vec![
+            DenomMetadata::create("wei", "eth", 18, "ETH"),
+            DenomMetadata::create("atto", "big", 40, "BIG"),
+        ];
+        assert_eq!(
+            Coin::parse_with("2eth", &wide)?,
+            Some(Coin::create("wei", dec!(2000000000000000000)))
+        );
+        assert!(matches!(
+            Coin::parse_with("1big", &wide),
+            Err(TerraRustAPIError::DenomExponent(_, 40))
+        ));
+        assert_eq!(
+            Coin::parse("5u")?,
+            Some(Coin::create("u", dec!(5))),
+            "1 character denoms parse"
+        );
+        Ok(())
+    }
+    #[test]
     fn test_ibc_coins() -> anyhow::Result<()> {
         let c = Coin::parse("566.750000000000000000ibc/EB2CED20AB0466F18BE49285E56B31306D4C60438A022EA995BA65D5E3CF7E09")?;
         match c {
@@ -320,10 +492,63 @@ mod tst {
             }
             None => assert!(false),
         }
+        let lower =
+            Coin::parse("1ibc/eb2ced20ab0466f18be49285e56b31306d4c60438a022ea995ba65d5e3cf7e09")?;
+        assert!(lower.is_some());
+        let factory = Coin::parse("25factory/terra1abc/token")?;
+        assert_eq!(
+            factory.map(|c| c.denom),
+            Some("factory/terra1abc/token".to_string())
+        );
         let ibc_coin_string="566.750000000000000000ibc/EB2CED20AB0466F18BE49285E56B31306D4C60438A022EA995BA65D5E3CF7E09,26762036.250000000000000000ukrw,2545.950000000000000000uluna,528551.000000000000000000uusd";
         let vec = Coin::parse_coins(ibc_coin_string)?;
         assert_eq!(vec.len(), 4);
 
         Ok(())
     }
+    #[test]
+    fn test_sign_msg_roundtrip() -> anyhow::Result<()> {
+        let msg = crate::messages::MsgSend::create_single(
+            "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7",
+            "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh",
+            Coin::create("uluna", dec!(1000)),
+        )?;
+        let sign_msg = StdSignMsg {
+            account_number: 42,
+            chain_id: "bombay-12".into(),
+            fee: StdFee::create(vec![Coin::create("uluna", dec!(30000))], 200000),
+            memo: "memo".into(),
+            msgs: vec![msg],
+            sequence: 7,
+        };
+        let js = serde_json::to_string(&sign_msg)?;
+        // what gets signed offline has to be byte for byte what was generated
+        let read_back: StdSignMsg = serde_json::from_str(&js)?;
+        assert_eq!(serde_json::to_string(&read_back)?, js);
+
+        Ok(())
+    }
+    #[test]
+    fn test_verify_batch() -> anyhow::Result<()> {
+        let secp = Secp256k1::new();
+        let pk = PrivateKey::from_words(&secp, "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut", 0, 0)?;
+        let payloads = ["one", "two", "three"];
+        let mut batch = payloads
+            .iter()
+            .map(|p| Ok((p.as_bytes().to_vec(), pk.sign(&secp, p)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert!(StdSignature::verify_batch(&batch).iter().all(|r| r.is_ok()));
+        batch[1].0 = b"tampered".to_vec();
+        let results = StdSignature::verify_batch(&batch);
+        assert!(results[0].is_ok() && results[1].is_err() && results[2].is_ok());
+        Ok(())
+    }
+    #[test]
+    fn test_pagination() {
+        assert_eq!(Pagination::default().to_query(), "");
+        assert_eq!(
+            Pagination::after("AAEC/w==", 10).to_query(),
+            "pagination.key=AAEC%2Fw%3D%3D&pagination.limit=10"
+        );
+    }
 }
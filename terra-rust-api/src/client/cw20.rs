@@ -0,0 +1,103 @@
+use crate::client::cw20_types::{
+    Cw20BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20TokenInfoResponse,
+};
+use crate::client::tx_types::TXResultSync;
+use crate::errors::TerraRustAPIError;
+use crate::messages::wasm::MsgExecuteContract;
+use crate::{LCDResult, Message, PrivateKey, Terra};
+use secp256k1::{Secp256k1, Signing};
+
+pub struct CW20<'a> {
+    terra: &'a Terra,
+}
+
+impl CW20<'_> {
+    pub fn create(terra: &'_ Terra) -> CW20<'_> {
+        CW20 { terra }
+    }
+    /// token balance of an address
+    pub async fn balance(
+        &self,
+        token: &str,
+        address: &str,
+        height: Option<u64>,
+    ) -> Result<LCDResult<Cw20BalanceResponse>, TerraRustAPIError> {
+        let query = serde_json::to_string(&Cw20QueryMsg::Balance {
+            address: address.into(),
+        })?;
+        self.terra.wasm().query(token, &query, height).await
+    }
+    /// name, symbol, decimals & supply of a token
+    pub async fn token_info(
+        &self,
+        token: &str,
+        height: Option<u64>,
+    ) -> Result<LCDResult<Cw20TokenInfoResponse>, TerraRustAPIError> {
+        let query = serde_json::to_string(&Cw20QueryMsg::TokenInfo {})?;
+        self.terra.wasm().query(token, &query, height).await
+    }
+    /// the message to transfer `amount` (in the token's smallest unit) to recipient
+    pub fn transfer_msg(
+        sender: &str,
+        token: &str,
+        recipient: &str,
+        amount: u128,
+    ) -> Result<Message, TerraRustAPIError> {
+        let msg = serde_json::to_value(Cw20ExecuteMsg::Transfer {
+            recipient: recipient.into(),
+            amount,
+        })?;
+        MsgExecuteContract::create_from_value(sender, token, &msg, &[])
+    }
+    /// the message to send `amount` to a contract, along with a message for the contract
+    pub fn send_msg(
+        sender: &str,
+        token: &str,
+        contract: &str,
+        amount: u128,
+        msg: &serde_json::Value,
+    ) -> Result<Message, TerraRustAPIError> {
+        let msg = serde_json::to_value(Cw20ExecuteMsg::Send {
+            contract: contract.into(),
+            amount,
+            msg: base64::encode(serde_json::to_string(msg)?),
+        })?;
+        MsgExecuteContract::create_from_value(sender, token, &msg, &[])
+    }
+    /// transfer tokens
+    pub async fn transfer<C: Signing>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        token: &str,
+        recipient: &str,
+        amount: u128,
+        memo: Option<String>,
+    ) -> Result<TXResultSync, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let messages = vec![CW20::transfer_msg(&sender, token, recipient, amount)?];
+        self.terra
+            .submit_transaction_sync(secp, from, messages, memo)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    #[test]
+    pub fn test_transfer_msg() -> anyhow::Result<()> {
+        let msg = CW20::transfer_msg(
+            "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7",
+            "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh",
+            "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7",
+            1_000_000,
+        )?;
+        let js = serde_json::to_value(&msg)?;
+        assert_eq!(
+            js["value"]["execute_msg"],
+            serde_json::json!({"transfer":{"recipient":"terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7","amount":"1000000"}})
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::client::client_types::terra_u128_format;
+
+use serde::{Deserialize, Serialize};
+
+#[allow(missing_docs)]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Cw20BalanceResponse {
+    #[serde(with = "terra_u128_format")]
+    pub balance: u128,
+}
+#[allow(missing_docs)]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Cw20TokenInfoResponse {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    #[serde(with = "terra_u128_format")]
+    pub total_supply: u128,
+}
+
+/// the cw20 execute messages we know how to build
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20ExecuteMsg {
+    Transfer {
+        recipient: String,
+        #[serde(with = "terra_u128_format")]
+        amount: u128,
+    },
+    /// send tokens to a contract, with a (base64) message for it to act on
+    Send {
+        contract: String,
+        #[serde(with = "terra_u128_format")]
+        amount: u128,
+        msg: String,
+    },
+}
+
+/// the cw20 queries we know how to build
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20QueryMsg {
+    Balance { address: String },
+    TokenInfo {},
+}
@@ -0,0 +1,172 @@
+use crate::client::bank_types::DenomMetadata;
+use crate::client::coin_format::CoinFormatter;
+use crate::core_types::Coin;
+use crate::errors::TerraRustAPIError;
+
+/// Terra's native coins and their tickers. all count in millionths
+const TERRA_DENOMS: &[(&str, &str)] = &[
+    ("uluna", "LUNA"),
+    ("uusd", "UST"),
+    ("ukrw", "KRT"),
+    ("usdr", "SDT"),
+    ("umnt", "MNT"),
+    ("ueur", "EUT"),
+    ("ucny", "CNT"),
+    ("ujpy", "JPT"),
+    ("ugbp", "GBT"),
+    ("uinr", "INT"),
+    ("ucad", "CAT"),
+    ("uchf", "CHT"),
+    ("uaud", "AUT"),
+    ("usgd", "SGT"),
+    ("uthb", "THT"),
+    ("usek", "SET"),
+    ("unok", "NOT"),
+    ("udkk", "DKT"),
+    ("uidr", "IDT"),
+    ("uphp", "PHT"),
+    ("uhkd", "HKT"),
+    ("umyr", "MYT"),
+    ("utwd", "TWT"),
+];
+
+/// Which symbol and exponent each denom has. Starts with Terra's native coins, and takes
+/// the chain's bank metadata on top with [DenomRegistry::with_metadata]. see [crate::client::bank::Bank::denom_registry]
+#[derive(Clone, Debug)]
+pub struct DenomRegistry {
+    metadata: Vec<DenomMetadata>,
+}
+impl Default for DenomRegistry {
+    fn default() -> Self {
+        DenomRegistry::terra()
+    }
+}
+impl DenomRegistry {
+    /// the static table of Terra's native coins. the ticker is also a unit, so `12.34ust` parses
+    pub fn terra() -> DenomRegistry {
+        let metadata = TERRA_DENOMS
+            .iter()
+            .map(|(base, symbol)| {
+                let mut metadata = DenomMetadata::create(base, &base[1..], 6, symbol);
+                if !symbol.eq_ignore_ascii_case(&base[1..]) {
+                    metadata.denom_units[1].aliases.push(symbol.to_lowercase());
+                }
+                metadata
+            })
+            .collect();
+        DenomRegistry { metadata }
+    }
+    /// the chain's metadata replaces what the registry had for the same base denom,
+    /// keeping the old symbol if the chain gives none
+    pub fn with_metadata(mut self, metadata: Vec<DenomMetadata>) -> DenomRegistry {
+        for mut entry in metadata {
+            match self.metadata.iter_mut().find(|m| m.base == entry.base) {
+                Some(existing) => {
+                    if entry.symbol.is_empty() {
+                        entry.symbol = existing.symbol.clone();
+                    }
+                    *existing = entry;
+                }
+                None => self.metadata.push(entry),
+            }
+        }
+        self
+    }
+    /// the metadata for a base denom
+    pub fn get(&self, denom: &str) -> Option<&DenomMetadata> {
+        self.metadata.iter().find(|m| m.base == denom)
+    }
+    /// like `UST` for `uusd`
+    pub fn symbol(&self, denom: &str) -> Option<String> {
+        self.get(denom).map(|m| m.ticker())
+    }
+    /// the exponent of the display unit of a base denom
+    pub fn exponent(&self, denom: &str) -> Option<u32> {
+        self.get(denom).map(|m| m.display_exponent())
+    }
+    /// the metadata with this symbol, base denom, or unit, ignoring case
+    pub fn resolve(&self, name: &str) -> Option<&DenomMetadata> {
+        self.metadata
+            .iter()
+            .find(|m| m.symbol.eq_ignore_ascii_case(name) || m.unit(name).is_some())
+    }
+    /// a coin in the base denom, from any of its units, like `12.34ust`
+    pub fn parse(&self, text: &str) -> Result<Option<Coin>, TerraRustAPIError> {
+        Coin::parse_with(text, &self.metadata)
+    }
+    pub fn formatter(&self) -> CoinFormatter {
+        CoinFormatter::create(self.metadata.clone())
+    }
+    /// like `UST 12.34` for `12340000uusd`
+    pub fn format(&self, coin: &Coin) -> String {
+        self.formatter().format(coin)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::wire_log::{Replay, WireLogEntry};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_denom_registry() -> anyhow::Result<()> {
+        let registry = DenomRegistry::terra();
+        assert_eq!(registry.symbol("uusd"), Some("UST".into()));
+        assert_eq!(registry.exponent("ukrw"), Some(6));
+        assert_eq!(registry.symbol("ibc/ABC"), None);
+        assert_eq!(
+            registry.format(&Coin::create("uusd", dec!(12340000))),
+            "UST 12.34"
+        );
+        assert_eq!(
+            registry.parse("12.34ust")?,
+            Some(Coin::create("uusd", dec!(12340000)))
+        );
+        assert_eq!(
+            registry.parse("2usd")?,
+            Some(Coin::create("uusd", dec!(2000000)))
+        );
+        assert_eq!(
+            registry.resolve("krt").map(|m| m.base.as_str()),
+            Some("ukrw")
+        );
+
+        let mut luna = DenomMetadata::create("uluna", "lunc", 6, "");
+        luna.description = "the chain's".into();
+        let axl = DenomMetadata::create("ibc/AXLUSDC", "axlusdc", 6, "axlUSDC");
+        let merged = registry.with_metadata(vec![luna, axl]);
+        assert_eq!(merged.symbol("uluna"), Some("LUNA".into()));
+        assert_eq!(
+            merged.get("uluna").map(|m| m.display.as_str()),
+            Some("lunc")
+        );
+        assert_eq!(
+            merged.format(&Coin::create("ibc/AXLUSDC", dec!(5000000))),
+            "axlUSDC 5"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_bank_denom_registry() -> anyhow::Result<()> {
+        let url = "http://lcd/cosmos/bank/v1beta1/denoms_metadata";
+        let response = r#"{"metadatas":[{"description":"","denom_units":[{"denom":"uaxl","aliases":[]},{"denom":"axl","exponent":6,"aliases":[]}],"base":"uaxl","display":"axl","name":"Axelar","symbol":"AXL"}],"pagination":{"next_key":null,"total":"1"}}"#;
+        let replay = Replay::create(vec![WireLogEntry {
+            status: Some(200),
+            response: Some(response.into()),
+            ..WireLogEntry::create("GET", url, None)
+        }]);
+        let terra = crate::Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay);
+        let registry = terra.bank().denom_registry().await;
+        assert_eq!(registry.symbol("uaxl"), Some("AXL".into()));
+        assert_eq!(registry.symbol("uusd"), Some("UST".into()));
+
+        let offline = crate::Terra::lcd_client_no_tx("http://lcd", "columbus-5")
+            .with_replay(Replay::create(vec![]));
+        let registry = offline.bank().denom_registry().await;
+        assert_eq!(registry.symbol("uaxl"), None);
+        assert_eq!(registry.symbol("uusd"), Some("UST".into()));
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+//! A transaction that isn't committed is replaced by signing the same messages at the same sequence
+//! with a higher fee. Only one of them can be committed, as the sequence can only be used once.
+//!
+//! Tendermint 0.34 (columbus-5) has no replace-by-fee: while the original is still in a node's mempool,
+//! one at the same sequence fails CheckTx with [WRONG_SEQUENCE_CODE]. So a higher fee only gets in once
+//! the original has been evicted, eg. by the node restarting or its mempool filling up.
+
+use crate::client::core_types::Coin;
+use crate::errors::TerraRustAPIError;
+use crate::GasOptions;
+use rust_decimal::Decimal;
+
+/// the code a node turns a transaction away with when its sequence isn't the account's next one
+pub const WRONG_SEQUENCE_CODE: usize = 32;
+/// the most transactions an escalation signs. a smaller step, or a higher cap, is turned away
+pub const MAX_ESCALATION_ATTEMPTS: u32 = 100;
+
+/// how [crate::Terra::submit_transaction_escalating] raises the fee of a transaction that isn't committed.
+/// After waiting [FeeEscalation::blocks] blocks, the gas price goes up by the step, compounding, up to the cap.
+/// Once a transaction at the cap hasn't been committed either, it gives up. It also stops raising the fee
+/// once a node turns one away with [WRONG_SEQUENCE_CODE], as the original is still waiting to be committed
+/// ```
+/// use terra_rust_api::client::escalation::FeeEscalation;
+/// use terra_rust_api::core_types::Coin;
+/// use rust_decimal_macros::dec;
+/// let escalation = FeeEscalation::create(dec!(50), Coin::create("uusd", dec!(0.3))).blocks(3);
+/// let price = Coin::create("uusd", dec!(0.15));
+/// assert_eq!(escalation.gas_price(&price, 1).unwrap().amount, dec!(0.225));
+/// assert_eq!(escalation.gas_price(&price, 2).unwrap().amount, dec!(0.3));
+/// assert_eq!(escalation.attempts(&price).unwrap(), 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FeeEscalation {
+    /// how much each attempt raises the gas price by, in percent
+    pub step_percent: Decimal,
+    /// the gas price is never raised above this
+    pub max_gas_price: Coin,
+    /// blocks to wait for the transaction before raising its fee
+    pub blocks: u64,
+}
+impl FeeEscalation {
+    /// raise the gas price by `step_percent` each time, up to `max_gas_price`, waiting 5 blocks each time
+    pub fn create(step_percent: Decimal, max_gas_price: Coin) -> FeeEscalation {
+        FeeEscalation {
+            step_percent,
+            max_gas_price,
+            blocks: 5,
+        }
+    }
+    /// blocks to wait before raising the fee
+    pub fn blocks(mut self, blocks: u64) -> FeeEscalation {
+        self.blocks = blocks;
+        self
+    }
+    /// the gas price of `attempt`, the first being 0
+    pub fn gas_price(&self, base: &Coin, attempt: u32) -> Result<Coin, TerraRustAPIError> {
+        if base.denom != self.max_gas_price.denom {
+            return Err(TerraRustAPIError::InvalidGasOptions(format!(
+                "gas price is in {}, and its cap in {}",
+                base.denom, self.max_gas_price.denom
+            )));
+        }
+        if self.step_percent <= Decimal::ZERO {
+            return Err(TerraRustAPIError::InvalidGasOptions(format!(
+                "escalation step of {}%",
+                self.step_percent
+            )));
+        }
+        let step = Decimal::ONE + self.step_percent / Decimal::ONE_HUNDRED;
+        let mut amount = base.amount;
+        for _ in 0..attempt {
+            if amount >= self.max_gas_price.amount {
+                break;
+            }
+            let raised = (amount * step).min(self.max_gas_price.amount);
+            // too small a step is lost to rounding
+            if raised <= amount {
+                return Err(TerraRustAPIError::InvalidGasOptions(format!(
+                    "escalation step of {}% doesn't raise a gas price of {}",
+                    self.step_percent, amount
+                )));
+            }
+            amount = raised;
+        }
+        Ok(Coin::create(&base.denom, amount.max(base.amount)))
+    }
+    /// how many transactions are signed, at most, before it gives up.
+    /// fails if it would take more than [MAX_ESCALATION_ATTEMPTS] to reach the cap
+    pub fn attempts(&self, base: &Coin) -> Result<u32, TerraRustAPIError> {
+        let mut attempt = 0;
+        while self.gas_price(base, attempt)?.amount < self.max_gas_price.amount {
+            attempt += 1;
+            if attempt >= MAX_ESCALATION_ATTEMPTS {
+                return Err(TerraRustAPIError::InvalidGasOptions(format!(
+                    "a {}% step takes over {} attempts to go from {} to {}",
+                    self.step_percent, MAX_ESCALATION_ATTEMPTS, base, self.max_gas_price
+                )));
+            }
+        }
+        Ok(attempt + 1)
+    }
+    /// `base` with its gas price raised for `attempt`. fixed fees are raised the same way,
+    /// as the price they pay for their gas
+    pub fn gas_options(
+        &self,
+        base: &GasOptions,
+        attempt: u32,
+    ) -> Result<GasOptions, TerraRustAPIError> {
+        base.at_gas_price(self.gas_price(&base_price(base)?, attempt)?)
+    }
+    /// [FeeEscalation::attempts] for the gas options
+    pub fn attempts_for(&self, base: &GasOptions) -> Result<u32, TerraRustAPIError> {
+        self.attempts(&base_price(base)?)
+    }
+}
+
+/// the price paid for gas: the fixed fee over the gas, or the gas price
+fn base_price(base: &GasOptions) -> Result<Coin, TerraRustAPIError> {
+    match (&base.fees, base.gas, &base.gas_price) {
+        (Some(fee), Some(gas), _) if gas > 0 => {
+            Ok(Coin::create(&fee.denom, fee.amount / Decimal::from(gas)))
+        }
+        (None, _, Some(price)) => Ok(price.clone()),
+        _ => Err(TerraRustAPIError::InvalidGasOptions(
+            "escalating the fee needs a gas price, or fixed fees with their gas".into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_escalation() -> anyhow::Result<()> {
+        let escalation = FeeEscalation::create(dec!(20), Coin::create("uusd", dec!(0.3)));
+        let options = GasOptions::create_with_gas_estimate("0.15uusd", 1.4)?;
+        let prices = (0..escalation.attempts_for(&options)?)
+            .map(|attempt| {
+                escalation
+                    .gas_options(&options, attempt)
+                    .map(|o| o.gas_price.unwrap().amount)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            prices,
+            vec![dec!(0.15), dec!(0.18), dec!(0.216), dec!(0.2592), dec!(0.3)]
+        );
+
+        let options = GasOptions::create_with_fees("30000uusd", 200_000)?;
+        let raised = escalation.gas_options(&options, 1)?;
+        assert_eq!(raised.fees.unwrap().amount, dec!(36000));
+        assert_eq!(
+            escalation.gas_options(&options, 10)?.fees.unwrap().amount,
+            dec!(60000)
+        );
+        assert!(escalation
+            .gas_price(&Coin::create("uluna", dec!(0.01)), 1)
+            .is_err());
+
+        // a step lost to rounding, or one that takes forever to reach the cap, is turned away
+        let price = Coin::create("uusd", dec!(0.15));
+        let tiny = FeeEscalation::create(Decimal::new(1, 26), Coin::create("uusd", dec!(0.3)));
+        assert!(tiny.gas_price(&price, 1).is_err());
+        assert!(tiny.attempts(&price).is_err());
+        let slow = FeeEscalation::create(dec!(0.1), Coin::create("uusd", dec!(0.3)));
+        assert!(slow.gas_price(&price, 1).is_ok());
+        assert!(slow.attempts(&price).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use crate::errors::TerraRustAPIError;
+use regex::Regex;
+use std::fmt;
+
+/// the value an event attribute is compared with
+#[derive(Clone, Debug, PartialEq)]
+enum Operand {
+    Text(String),
+    Number(u64),
+}
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Text(text) => write!(f, "'{}'", text),
+            Operand::Number(number) => write!(f, "{}", number),
+        }
+    }
+}
+
+/// Conditions on the events of a transaction, all of which must hold, for [crate::client::tx::TX::search]
+/// and [crate::client::rpc::RPC::tx_search]
+/// ```
+/// use terra_rust_api::client::event_query::EventQuery;
+/// let query = EventQuery::new()
+///     .contract("terra1contract")
+///     .action("transfer")
+///     .sender("terra1sender");
+/// assert_eq!(
+///     query.to_query().unwrap(),
+///     "wasm.contract_address='terra1contract' AND wasm.action='transfer' AND message.sender='terra1sender'"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventQuery {
+    conditions: Vec<(String, &'static str, Operand)>,
+}
+impl EventQuery {
+    pub fn new() -> EventQuery {
+        EventQuery::default()
+    }
+    /// `key` is `event_type.attribute`, like `transfer.recipient`
+    pub fn eq(mut self, key: &str, value: &str) -> EventQuery {
+        self.conditions
+            .push((key.into(), "=", Operand::Text(value.into())));
+        self
+    }
+    /// a numeric attribute, like `tx.height`
+    pub fn eq_number(mut self, key: &str, value: u64) -> EventQuery {
+        self.conditions
+            .push((key.into(), "=", Operand::Number(value)));
+        self
+    }
+    /// transactions in this block
+    pub fn height(self, height: u64) -> EventQuery {
+        self.eq_number("tx.height", height)
+    }
+    /// transactions from this block on. only the RPC can search a range
+    pub fn min_height(mut self, height: u64) -> EventQuery {
+        self.conditions
+            .push(("tx.height".into(), ">=", Operand::Number(height)));
+        self
+    }
+    /// transactions up to this block. only the RPC can search a range
+    pub fn max_height(mut self, height: u64) -> EventQuery {
+        self.conditions
+            .push(("tx.height".into(), "<=", Operand::Number(height)));
+        self
+    }
+    /// the transaction with this hash
+    pub fn hash(self, hash: &str) -> EventQuery {
+        self.eq("tx.hash", &hash.to_uppercase())
+    }
+    /// transactions with a message signed by this account
+    pub fn sender(self, sender: &str) -> EventQuery {
+        self.eq("message.sender", sender)
+    }
+    /// transactions with a message of this type, like `/cosmos.bank.v1beta1.MsgSend`
+    pub fn message_action(self, action: &str) -> EventQuery {
+        self.eq("message.action", action)
+    }
+    /// transactions calling this contract
+    pub fn contract(self, contract: &str) -> EventQuery {
+        self.eq("wasm.contract_address", contract)
+    }
+    /// transactions where a contract says it did this, like a cw20 `transfer`
+    pub fn action(self, action: &str) -> EventQuery {
+        self.eq("wasm.action", action)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    fn check(&self) -> Result<(), TerraRustAPIError> {
+        lazy_static! {
+            static ref KEY: Regex = Regex::new(r"^[A-Za-z0-9_/-]+(\.[A-Za-z0-9_/-]+)+$").unwrap();
+        }
+        for (key, _, operand) in &self.conditions {
+            if !KEY.is_match(key) {
+                return Err(TerraRustAPIError::EventQuery(
+                    key.clone(),
+                    "is not event_type.attribute".into(),
+                ));
+            }
+            if let Operand::Text(text) = operand {
+                // the query language has no way to escape a quote
+                if text.contains('\'') {
+                    return Err(TerraRustAPIError::EventQuery(
+                        key.clone(),
+                        format!("`{}` has a quote", text),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+    /// the tendermint query, as the RPC and websocket subscriptions take it
+    pub fn to_query(&self) -> Result<String, TerraRustAPIError> {
+        self.check()?;
+        Ok(self
+            .conditions
+            .iter()
+            .map(|(key, op, operand)| format!("{}{}{}", key, op, operand))
+            .collect::<Vec<_>>()
+            .join(" AND "))
+    }
+    /// as `events=` query parameters for the LCD, without the leading ? or &.
+    /// the LCD only takes `=` conditions, and no `=` in the values
+    pub fn to_lcd_events(&self) -> Result<String, TerraRustAPIError> {
+        self.check()?;
+        let mut params = vec![];
+        for (key, op, operand) in &self.conditions {
+            if *op != "=" {
+                return Err(TerraRustAPIError::EventQuery(
+                    key.clone(),
+                    format!("the LCD can't search {} a height", op),
+                ));
+            }
+            let condition = format!("{}={}", key, operand);
+            if condition.matches('=').count() > 1 {
+                return Err(TerraRustAPIError::EventQuery(
+                    key.clone(),
+                    "the LCD can't search for a value with = in it".into(),
+                ));
+            }
+            params.push(format!("events={}", percent_encode(&condition)));
+        }
+        Ok(params.join("&"))
+    }
+}
+
+/// escape everything but the unreserved characters of a URL
+pub(crate) fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_event_query() -> anyhow::Result<()> {
+        let query = EventQuery::new().sender("terra1abc").height(5);
+        assert_eq!(
+            query.to_query()?,
+            "message.sender='terra1abc' AND tx.height=5"
+        );
+        assert_eq!(
+            query.to_lcd_events()?,
+            "events=message.sender%3D%27terra1abc%27&events=tx.height%3D5"
+        );
+        let range = EventQuery::new()
+            .contract("terra1c")
+            .min_height(5)
+            .max_height(9);
+        assert_eq!(
+            range.to_query()?,
+            "wasm.contract_address='terra1c' AND tx.height>=5 AND tx.height<=9"
+        );
+        assert!(range.to_lcd_events().is_err());
+        assert!(EventQuery::new()
+            .eq("transfer.memo", "it's")
+            .to_query()
+            .is_err());
+        assert!(EventQuery::new().eq("sender", "x").to_query().is_err());
+        assert!(EventQuery::new()
+            .eq("wasm.key", "a=b")
+            .to_lcd_events()
+            .is_err());
+        assert_eq!(EventQuery::new().hash("ab12").to_query()?, "tx.hash='AB12'");
+        Ok(())
+    }
+}
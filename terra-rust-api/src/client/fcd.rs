@@ -1,5 +1,7 @@
+use crate::client::fcd_types::{Candle, CandleInterval, MarketPriceResponse};
 use crate::errors::TerraRustAPIError;
 use crate::Terra;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
@@ -11,8 +13,12 @@ impl FCD<'_> {
     pub fn create<'a>(terra: &'a Terra, fcd_url: &'a str) -> FCD<'a> {
         FCD { terra, fcd_url }
     }
+    /// the gas prices, fetched again once the ones any clone of the client fetched are a minute old
     pub async fn gas_prices(&self) -> Result<HashMap<String, Decimal>, TerraRustAPIError> {
-        Ok(self
+        if let Some(prices) = self.terra.inner.gas_prices(self.fcd_url) {
+            return Ok(prices);
+        }
+        let prices = self
             .terra
             .send_cmd_url::<HashMap<String, Decimal>>(
                 self.fcd_url,
@@ -20,7 +26,34 @@ impl FCD<'_> {
                 None,
                 None,
             )
-            .await?)
+            .await?;
+        self.terra.inner.set_gas_prices(self.fcd_url, &prices);
+        Ok(prices)
+    }
+    /// the luna price in `denom`, as candles of `interval` starting between `from` and `to`.
+    /// FCD only keeps single prices, so the candles are built from those at a shorter interval.
+    /// how far back they go is up to FCD
+    pub async fn price_candles(
+        &self,
+        denom: &str,
+        interval: CandleInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Candle>, TerraRustAPIError> {
+        let response = self
+            .terra
+            .send_cmd_url::<MarketPriceResponse>(
+                self.fcd_url,
+                "/v1/market/price",
+                Some(&format!(
+                    "?denom={}&interval={}",
+                    denom,
+                    interval.samples().as_str()
+                )),
+                None,
+            )
+            .await?;
+        Ok(Candle::from_prices(&response.prices, interval, from, to))
     }
     pub async fn fetch_gas_prices(
         client: &reqwest::Client,
@@ -0,0 +1,179 @@
+use crate::client::client_types::{terra_datetime_format, terra_decimal_format, terra_u64_format};
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// the candle lengths FCD keeps prices for
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandleInterval {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "30m")]
+    ThirtyMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+    #[serde(rename = "1d")]
+    OneDay,
+}
+impl CandleInterval {
+    pub fn seconds(&self) -> i64 {
+        match self {
+            CandleInterval::OneMinute => 60,
+            CandleInterval::FiveMinutes => 5 * 60,
+            CandleInterval::FifteenMinutes => 15 * 60,
+            CandleInterval::ThirtyMinutes => 30 * 60,
+            CandleInterval::OneHour => 60 * 60,
+            CandleInterval::OneDay => 24 * 60 * 60,
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandleInterval::OneMinute => "1m",
+            CandleInterval::FiveMinutes => "5m",
+            CandleInterval::FifteenMinutes => "15m",
+            CandleInterval::ThirtyMinutes => "30m",
+            CandleInterval::OneHour => "1h",
+            CandleInterval::OneDay => "1d",
+        }
+    }
+    /// the interval whose prices are sampled to build candles of this one
+    pub fn samples(&self) -> CandleInterval {
+        match self {
+            CandleInterval::OneMinute
+            | CandleInterval::FiveMinutes
+            | CandleInterval::FifteenMinutes
+            | CandleInterval::ThirtyMinutes => CandleInterval::OneMinute,
+            CandleInterval::OneHour => CandleInterval::FiveMinutes,
+            CandleInterval::OneDay => CandleInterval::OneHour,
+        }
+    }
+}
+impl FromStr for CandleInterval {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1m" => Ok(CandleInterval::OneMinute),
+            "5m" => Ok(CandleInterval::FiveMinutes),
+            "15m" => Ok(CandleInterval::FifteenMinutes),
+            "30m" => Ok(CandleInterval::ThirtyMinutes),
+            "1h" => Ok(CandleInterval::OneHour),
+            "1d" => Ok(CandleInterval::OneDay),
+            _ => Err(format!("{} isn't one of 1m, 5m, 15m, 30m, 1h, 1d", s)),
+        }
+    }
+}
+
+/// the price of a luna at a point in time
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PricePoint {
+    /// milliseconds since the epoch
+    #[serde(with = "terra_u64_format")]
+    pub datetime: u64,
+    #[serde(with = "terra_decimal_format")]
+    pub price: Decimal,
+}
+#[derive(Deserialize, Clone, Debug)]
+pub struct MarketPriceResponse {
+    #[serde(default)]
+    pub prices: Vec<PricePoint>,
+}
+
+/// open, high, low & close of the luna price over an interval
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Candle {
+    /// when the interval starts
+    #[serde(with = "terra_datetime_format")]
+    pub start: DateTime<Utc>,
+    #[serde(with = "terra_decimal_format")]
+    pub open: Decimal,
+    #[serde(with = "terra_decimal_format")]
+    pub high: Decimal,
+    #[serde(with = "terra_decimal_format")]
+    pub low: Decimal,
+    #[serde(with = "terra_decimal_format")]
+    pub close: Decimal,
+    /// how many prices the candle was built from
+    pub samples: usize,
+}
+impl Candle {
+    /// group the prices into candles of `interval`, for the intervals starting in [from, to)
+    pub fn from_prices(
+        prices: &[PricePoint],
+        interval: CandleInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<Candle> {
+        let length = interval.seconds() * 1000;
+        let mut sorted = prices.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|p| p.datetime);
+        let mut candles: BTreeMap<i64, Candle> = BTreeMap::new();
+        for point in sorted {
+            let millis = point.datetime as i64;
+            let start = millis - millis.rem_euclid(length);
+            if start < from.timestamp_millis() || start >= to.timestamp_millis() {
+                continue;
+            }
+            candles
+                .entry(start)
+                .and_modify(|c| {
+                    c.high = c.high.max(point.price);
+                    c.low = c.low.min(point.price);
+                    c.close = point.price;
+                    c.samples += 1;
+                })
+                .or_insert_with(|| Candle {
+                    start: Utc.timestamp_millis_opt(start).unwrap(),
+                    open: point.price,
+                    high: point.price,
+                    low: point.price,
+                    close: point.price,
+                    samples: 1,
+                });
+        }
+        candles.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_candles() -> anyhow::Result<()> {
+        let response: MarketPriceResponse = serde_json::from_str(
+            r#"{"lastPrice":51.5,"oneDayVariation":"1.2","oneDayVariationRate":"0.02","prices":[
+              {"denom":"uusd","datetime":1640995260000,"price":50.5},
+              {"denom":"uusd","datetime":1640995200000,"price":50},
+              {"denom":"uusd","datetime":1640995320000,"price":49.75},
+              {"denom":"uusd","datetime":1640995500000,"price":51},
+              {"denom":"uusd","datetime":1640995560000,"price":51.5},
+              {"denom":"uusd","datetime":1640995800000,"price":52}
+            ]}"#,
+        )?;
+        let from: DateTime<Utc> = "2022-01-01T00:00:00Z".parse()?;
+        let to: DateTime<Utc> = "2022-01-01T00:10:00Z".parse()?;
+        let candles = Candle::from_prices(&response.prices, "5m".parse().unwrap(), from, to);
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].start, from);
+        assert_eq!(
+            (
+                candles[0].open,
+                candles[0].high,
+                candles[0].low,
+                candles[0].close
+            ),
+            (dec!(50), dec!(50.5), dec!(49.75), dec!(49.75))
+        );
+        assert_eq!(candles[0].samples, 3);
+        // the price at 00:10 starts a candle after `to`
+        assert_eq!((candles[1].open, candles[1].close), (dec!(51), dec!(51.5)));
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+//! The chain wants fees in whole base units, and checks `fee >= ceil(gas price * gas)` for each denom.
+//! Everything here rounds towards paying enough, never towards paying less, as a fee that's
+//! a fraction short is rejected with 'insufficient fee'.
+//! The stability tax is the exception: the chain truncates it, so it is truncated here too
+
+use crate::client::core_types::Coin;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+/// the fee for `gas` at `price`: the product rounded up to a whole base unit
+pub fn fee_for_gas(price: &Coin, gas: u64) -> Coin {
+    Coin::create(&price.denom, (price.amount * Decimal::from(gas)).ceil())
+}
+
+/// the gas estimate multiplied by the adjustment, rounded up.
+/// the adjustment goes through its shortest decimal form, so 1.4 is 1.4 and not 1.39999..
+pub fn adjusted_gas(estimate: u64, adjustment: f64) -> u64 {
+    Decimal::from_f64(adjustment)
+        .and_then(|adjustment| (Decimal::from(estimate) * adjustment).ceil().to_u64())
+        .unwrap_or(estimate)
+}
+
+/// `rate` of the coin's amount, truncated as the chain does, and no more than `cap`
+pub fn tax_due(coin: &Coin, rate: Decimal, cap: Decimal) -> Option<Coin> {
+    let due = (coin.amount * rate).trunc().min(cap);
+    if due.is_zero() {
+        None
+    } else {
+        Some(Coin::create(&coin.denom, due))
+    }
+}
+
+/// the coin rounded up to a whole base unit
+pub fn round_up(coin: &Coin) -> Coin {
+    Coin::create(&coin.denom, coin.amount.ceil())
+}
+
+/// the coins summed by denom, in whole base units, and sorted by denom as the chain requires
+pub fn add_coins(coins: &[Coin], more: &[Coin]) -> Vec<Coin> {
+    let mut sum: Vec<Coin> = vec![];
+    for coin in coins.iter().chain(more) {
+        match sum.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(c) => c.amount += coin.amount,
+            None => sum.push(coin.clone()),
+        }
+    }
+    let mut sum = sum.iter().map(round_up).collect::<Vec<_>>();
+    sum.sort_by(|a, b| a.denom.cmp(&b.denom));
+    sum
+}
+
+/// a fixed fee, plus the tax on what is sent
+pub fn fixed_fee(fee: &Coin, tax: &[Coin]) -> Vec<Coin> {
+    add_coins(std::slice::from_ref(fee), tax)
+}
+
+/// the fee the LCD `quoted` for `gas`, in whole base units, and at least what the gas costs at `price`.
+/// The LCD works the fee out in floating point, which can land a base unit short
+pub fn estimated_fee(quoted: &[Coin], gas: u64, price: &Coin) -> Vec<Coin> {
+    let needed = fee_for_gas(price, gas);
+    let mut fee = add_coins(quoted, &[]);
+    match fee.iter_mut().find(|c| c.denom == needed.denom) {
+        Some(c) => c.amount = c.amount.max(needed.amount),
+        None if !needed.amount.is_zero() => {
+            fee.push(needed);
+            fee.sort_by(|a, b| a.denom.cmp(&b.denom));
+        }
+        None => {}
+    }
+    fee
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use proptest::prelude::*;
+    use rust_decimal_macros::dec;
+
+    /// amounts with up to 6 decimals, as gas prices are given
+    fn amount() -> impl Strategy<Value = Decimal> {
+        (1u64..100_000_000).prop_map(|n| Decimal::new(n as i64, 6))
+    }
+    fn denom() -> impl Strategy<Value = String> {
+        prop::sample::select(vec!["uluna", "uusd", "ukrw", "usdr"]).prop_map(String::from)
+    }
+    fn coin() -> impl Strategy<Value = Coin> {
+        (denom(), amount()).prop_map(|(denom, amount)| Coin::create(&denom, amount))
+    }
+    fn is_whole(coin: &Coin) -> bool {
+        coin.amount.fract().is_zero()
+    }
+
+    #[test]
+    pub fn test_fees() {
+        let price = Coin::create("uusd", dec!(0.15));
+        assert_eq!(fee_for_gas(&price, 200_000).amount, dec!(30000));
+        assert_eq!(fee_for_gas(&price, 200_001).amount, dec!(30001));
+        assert_eq!(adjusted_gas(100_000, 1.4), 140_000);
+        assert_eq!(adjusted_gas(3, 1.5), 5);
+        let fee = fixed_fee(
+            &Coin::create("uusd", dec!(30000)),
+            &[
+                Coin::create("ukrw", dec!(10)),
+                Coin::create("uusd", dec!(617)),
+            ],
+        );
+        assert_eq!(
+            fee,
+            vec![
+                Coin::create("ukrw", dec!(10)),
+                Coin::create("uusd", dec!(30617))
+            ]
+        );
+        // the LCD came up a fraction short
+        let fee = estimated_fee(&[Coin::create("uusd", dec!(29999.7))], 200_001, &price);
+        assert_eq!(fee, vec![Coin::create("uusd", dec!(30001))]);
+    }
+
+    proptest! {
+        #[test]
+        fn fee_covers_gas(price in coin(), gas in 0u64..100_000_000) {
+            let fee = fee_for_gas(&price, gas);
+            prop_assert!(is_whole(&fee));
+            prop_assert!(fee.amount >= price.amount * Decimal::from(gas));
+            prop_assert!(fee.amount - price.amount * Decimal::from(gas) < Decimal::ONE);
+        }
+
+        #[test]
+        fn adjustment_rounds_up(estimate in 0u64..100_000_000, tenths in 10u32..50, more in 0u32..10) {
+            let adjustment = f64::from(tenths) / 10.0;
+            let gas = adjusted_gas(estimate, adjustment);
+            prop_assert!(gas as f64 >= estimate as f64 * adjustment - 1e-6);
+            prop_assert!(gas >= estimate);
+            prop_assert!(adjusted_gas(estimate, adjustment + f64::from(more) / 10.0) >= gas);
+        }
+
+        #[test]
+        fn tax_is_truncated_and_capped(sent in coin(), rate in 0u32..1000, cap in amount()) {
+            let rate = Decimal::new(rate.into(), 4);
+            let cap = cap.ceil();
+            if let Some(due) = tax_due(&sent, rate, cap) {
+                prop_assert!(is_whole(&due));
+                prop_assert!(due.amount <= sent.amount * rate);
+                prop_assert!(due.amount <= cap);
+            }
+        }
+
+        #[test]
+        fn denoms_kept(coins in prop::collection::vec(coin(), 0..8), more in prop::collection::vec(coin(), 0..8)) {
+            let sum = add_coins(&coins, &more);
+            prop_assert!(sum.windows(2).all(|w| w[0].denom < w[1].denom));
+            for coin in coins.iter().chain(&more) {
+                let total: Decimal = coins.iter().chain(&more).filter(|c| c.denom == coin.denom).map(|c| c.amount).sum();
+                let summed = sum.iter().find(|c| c.denom == coin.denom);
+                prop_assert!(matches!(summed, Some(c) if is_whole(c) && c.amount >= total && c.amount - total < Decimal::ONE));
+            }
+        }
+
+        #[test]
+        fn estimate_never_short(quoted in prop::collection::vec(coin(), 0..4), price in coin(), gas in 0u64..10_000_000) {
+            let fee = estimated_fee(&quoted, gas, &price);
+            prop_assert!(fee.iter().all(is_whole));
+            let paid = fee.iter().find(|c| c.denom == price.denom).map(|c| c.amount).unwrap_or_default();
+            prop_assert!(paid >= price.amount * Decimal::from(gas));
+            for coin in &quoted {
+                prop_assert!(fee.iter().any(|c| c.denom == coin.denom && c.amount >= coin.amount));
+            }
+        }
+    }
+}
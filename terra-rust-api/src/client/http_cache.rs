@@ -0,0 +1,192 @@
+use crate::errors::TerraRustAPIError;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{RequestBuilder, StatusCode};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug)]
+struct CachedResponse {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// Conditional GETs for things that rarely change, like the oracle parameters or validator descriptions.
+/// Responses carrying an `ETag` or `Last-Modified` header are kept, and the next request for the same URL
+/// asks the server for them only if they changed. A `304 Not Modified` is answered from the cache.
+/// Shared between clones of the client
+#[derive(Clone, Debug, Default)]
+pub struct HttpCache {
+    entries: Arc<Mutex<HashMap<String, CachedResponse>>>,
+}
+impl HttpCache {
+    pub fn create() -> HttpCache {
+        HttpCache::default()
+    }
+    /// how many responses are held
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear()
+    }
+    /// ask for the response only if it changed since the one held
+    pub(crate) fn conditional(&self, url: &str, mut req: RequestBuilder) -> RequestBuilder {
+        if let Some(cached) = self.entries.lock().unwrap().get(url) {
+            if let Some(etag) = &cached.etag {
+                req = req.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                req = req.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        req
+    }
+    /// the status and body of a successful response to a [HttpCache::conditional] request,
+    /// or `304` and the cached body if it hasn't changed
+    pub(crate) async fn answer(
+        &self,
+        url: &str,
+        response: reqwest::Response,
+    ) -> Result<(u16, String), TerraRustAPIError> {
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = self.entries.lock().unwrap().get(url) {
+                log::debug!("URL={} - not modified", crate::redact::redact(url));
+                return Ok((status.as_u16(), cached.body.clone()));
+            }
+        }
+        if !status.is_success() {
+            return Err(TerraRustAPIError::TerraLCDResponse(
+                status,
+                response.text().await?,
+            ));
+        }
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = response.text().await?;
+        if etag.is_some() || last_modified.is_some() {
+            self.entries.lock().unwrap().insert(
+                url.to_string(),
+                CachedResponse {
+                    etag,
+                    last_modified,
+                    body: body.clone(),
+                },
+            );
+        }
+        Ok((status.as_u16(), body))
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::wire_log::{Replay, WireLog};
+    use crate::Terra;
+    use std::io::Write;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// answers with an ETag, and 304 when it is sent back. returns how many full bodies it sent
+    async fn server(listener: TcpListener, requests: usize) -> usize {
+        let mut bodies = 0;
+        for _ in 0..requests {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+            let response = if request.contains("if-none-match: \"v1\"") {
+                "HTTP/1.1 304 Not Modified\r\netag: \"v1\"\r\ncontent-length: 0\r\n\r\n".to_string()
+            } else {
+                bodies += 1;
+                "HTTP/1.1 200 OK\r\netag: \"v1\"\r\ncontent-length: 11\r\n\r\n{\"a\":\"one\"}"
+                    .to_string()
+            };
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+        bodies
+    }
+
+    #[tokio::test]
+    pub async fn test_not_modified() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/oracle/parameters", listener.local_addr()?);
+        let server = tokio::spawn(server(listener, 2));
+        let client = reqwest::Client::builder()
+            .pool_max_idle_per_host(0)
+            .build()?;
+        let cache = HttpCache::create();
+        let fetch = || async {
+            let response = cache.conditional(&url, client.get(&url)).send().await?;
+            cache.answer(&url, response).await
+        };
+        assert_eq!(fetch().await?, (200, r#"{"a":"one"}"#.to_string()));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(fetch().await?, (304, r#"{"a":"one"}"#.to_string()));
+        assert_eq!(server.await?, 1);
+        Ok(())
+    }
+
+    /// a writer the test can look at afterwards
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            Write::write(&mut *self.0.lock().unwrap(), buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_cached_requests() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let lcd = format!("http://{}", listener.local_addr()?);
+        let server = tokio::spawn(server(listener, 2));
+        let log = Shared::default();
+        let wire_log = WireLog::create(log.clone());
+        let terra = Terra::lcd_client_no_tx(lcd.as_str(), "columbus-5")
+            .with_http_options(&crate::HttpOptions {
+                pool_max_idle_per_host: 0,
+                no_proxy: true,
+                ..Default::default()
+            })
+            .with_http_cache()
+            .with_wire_log(wire_log.clone());
+        for _ in 0..2 {
+            let value = terra
+                .send_cmd_cached::<serde_json::Value>("/oracle/parameters", None, None)
+                .await?;
+            assert_eq!(value["a"], "one");
+        }
+        assert_eq!(server.await?, 1);
+        // the 304 is logged as such, with the body it stood for, and replays
+        wire_log.flush();
+        let entries = WireLog::read(std::io::Cursor::new(log.0.lock().unwrap().clone()))?;
+        let statuses = entries.iter().map(|e| e.status).collect::<Vec<_>>();
+        assert_eq!(statuses, vec![Some(200), Some(304)]);
+        assert_eq!(entries[1].response.as_deref(), Some(r#"{"a":"one"}"#));
+        let replayed = Terra::lcd_client_no_tx(lcd.as_str(), "columbus-5")
+            .with_http_cache()
+            .with_replay(Replay::create(entries));
+        for _ in 0..2 {
+            let value = replayed
+                .send_cmd_cached::<serde_json::Value>("/oracle/parameters", None, None)
+                .await?;
+            assert_eq!(value["a"], "one");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use crate::client::event_query::EventQuery;
+use crate::client::ibc_types::{IbcPacket, IbcTransfer, IbcTransferStatus};
+use crate::client::tx_types::V1TXResponse;
+use crate::errors::TerraRustAPIError;
+use crate::Terra;
+
+/// following IBC transfers sent from this chain. Relayers deliver the packet to the other chain,
+/// and its acknowledgement or timeout comes back here, so all of this asks only the source chain
+pub struct Ibc<'a> {
+    terra: &'a Terra,
+}
+impl Ibc<'_> {
+    pub fn create(terra: &'_ Terra) -> Ibc<'_> {
+        Ibc { terra }
+    }
+    /// the packets a transaction sent
+    pub async fn sent_packets(&self, txhash: &str) -> Result<Vec<IbcPacket>, TerraRustAPIError> {
+        let tx = self.terra.tx().get_v1(txhash).await?;
+        Ok(IbcPacket::sent_by(&tx.tx_response))
+    }
+    /// what has happened to the packet so far
+    pub async fn packet_status(
+        &self,
+        packet: &IbcPacket,
+    ) -> Result<IbcTransferStatus, TerraRustAPIError> {
+        let acks = self.search_packet(packet, "acknowledge_packet").await?;
+        if let Some(status) = acks
+            .iter()
+            .find_map(|tx| IbcTransferStatus::acknowledged_in(packet, tx))
+        {
+            return Ok(status);
+        }
+        let timeouts = self.search_packet(packet, "timeout_packet").await?;
+        Ok(timeouts
+            .iter()
+            .find_map(|tx| IbcTransferStatus::timed_out_in(packet, tx))
+            .unwrap_or(IbcTransferStatus::Pending))
+    }
+    async fn search_packet(
+        &self,
+        packet: &IbcPacket,
+        event_type: &str,
+    ) -> Result<Vec<V1TXResponse>, TerraRustAPIError> {
+        let query = EventQuery::new()
+            .eq(
+                &format!("{}.packet_sequence", event_type),
+                &packet.sequence.to_string(),
+            )
+            .eq(
+                &format!("{}.packet_src_channel", event_type),
+                &packet.src_channel,
+            );
+        Ok(self
+            .terra
+            .tx()
+            .search(&query, None, None)
+            .await?
+            .tx_responses)
+    }
+    /// find the packet the transfer `txhash` sent, and check on it up to `max_times`, `sleep_amount` apart,
+    /// until it is acknowledged or times out. It is still [IbcTransferStatus::Pending] if neither happened by then
+    pub async fn follow_ibc_transfer(
+        &self,
+        txhash: &str,
+        max_times: usize,
+        sleep_amount: std::time::Duration,
+    ) -> Result<IbcTransfer, TerraRustAPIError> {
+        let tx = self
+            .terra
+            .tx()
+            .get_and_wait_v1(txhash, max_times, sleep_amount)
+            .await?;
+        let packet = IbcPacket::sent_by(&tx.tx_response)
+            .into_iter()
+            .next()
+            .ok_or_else(|| TerraRustAPIError::NoIbcPacket(txhash.into()))?;
+        let mut status = IbcTransferStatus::Pending;
+        for times in 0..max_times {
+            if times > 0 {
+                self.terra.pause(sleep_amount, txhash).await?;
+            }
+            status = self.packet_status(&packet).await?;
+            if status.is_final() {
+                break;
+            }
+            log::debug!(
+                "packet {} on {} pending .. check #{}",
+                packet.sequence,
+                packet.src_channel,
+                times + 1
+            );
+        }
+        Ok(IbcTransfer {
+            txhash: tx.tx_response.txhash,
+            packet,
+            status,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::wire_log::{Replay, WireLogEntry};
+    use tokio::time::Duration;
+
+    const SENT: &str = r#"{"tx":{"body":{"messages":[],"memo":"","timeout_height":"0","extension_options":[],"non_critical_extension_options":[]},"auth_info":{},"signatures":[]},"tx_response":{"height":"100","txhash":"SENT","codespace":"","code":0,"data":"","raw_log":"","info":"","gas_wanted":"200000","gas_used":"100000","tx":{},"timestamp":"2022-01-02T03:04:05Z","events":null,
+        "logs":[{"msg_index":0,"events":[{"type":"send_packet","attributes":[
+            {"key":"packet_sequence","value":"7"},{"key":"packet_src_port","value":"transfer"},{"key":"packet_src_channel","value":"channel-1"},
+            {"key":"packet_dst_port","value":"transfer"},{"key":"packet_dst_channel","value":"channel-72"},
+            {"key":"packet_timeout_height","value":"0-0"},{"key":"packet_timeout_timestamp","value":"1700000000000000000"}]}]}]}}"#;
+
+    fn acks(sequence: &str, error: Option<&str>) -> String {
+        let result = match error {
+            Some(error) => format!(r#"{{"key":"error","value":"{}"}}"#, error),
+            None => r#"{"key":"success","value":"\u0001"}"#.into(),
+        };
+        format!(
+            r#"{{"txs":[],"pagination":{{"next_key":null,"total":"1"}},"tx_responses":[{{"height":"105","txhash":"ACK","codespace":"","code":0,"data":"","raw_log":"","info":"","gas_wanted":"1","gas_used":"1","tx":{{}},"timestamp":"2022-01-02T03:05:05Z","events":null,
+            "logs":[{{"msg_index":0,"events":[{{"type":"acknowledge_packet","attributes":[
+                {{"key":"packet_sequence","value":"{}"}},{{"key":"packet_src_port","value":"transfer"}},{{"key":"packet_src_channel","value":"channel-1"}}]}},
+                {{"type":"fungible_token_packet","attributes":[{}]}}]}}]}}]}}"#,
+            sequence, result
+        )
+    }
+
+    fn entry(url: String, response: String) -> WireLogEntry {
+        WireLogEntry {
+            status: Some(200),
+            response: Some(response),
+            ..WireLogEntry::create("GET", &url, None)
+        }
+    }
+
+    fn search_url(event_type: &str) -> anyhow::Result<String> {
+        let query = EventQuery::new()
+            .eq(&format!("{}.packet_sequence", event_type), "7")
+            .eq(&format!("{}.packet_src_channel", event_type), "channel-1");
+        Ok(format!(
+            "http://lcd/cosmos/tx/v1beta1/txs?{}&order_by=ORDER_BY_ASC&pagination.limit=100&pagination.offset=0",
+            query.to_lcd_events()?
+        ))
+    }
+
+    fn terra(ack: String) -> anyhow::Result<Terra> {
+        let empty = r#"{"txs":[],"tx_responses":[],"pagination":{"next_key":null,"total":"0"}}"#;
+        let replay = Replay::create(vec![
+            entry("http://lcd/cosmos/tx/v1beta1/txs/SENT".into(), SENT.into()),
+            entry(search_url("acknowledge_packet")?, ack),
+            entry(search_url("timeout_packet")?, empty.into()),
+        ]);
+        Ok(Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay))
+    }
+
+    #[tokio::test]
+    pub async fn test_follow_ibc_transfer() -> anyhow::Result<()> {
+        let terra = terra(acks("7", None))?;
+        let packets = terra.ibc().sent_packets("SENT").await?;
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].dst_channel, "channel-72");
+        assert_eq!(packets[0].timeout_timestamp, 1700000000000000000);
+        let transfer = terra
+            .ibc()
+            .follow_ibc_transfer("SENT", 1, Duration::from_millis(1))
+            .await?;
+        assert_eq!(
+            transfer.status,
+            IbcTransferStatus::Acknowledged {
+                height: 105,
+                txhash: "ACK".into(),
+                success: true,
+                error: None
+            }
+        );
+
+        let terra = self::terra(acks("7", Some("insufficient funds")))?;
+        let status = terra.ibc().packet_status(&packets[0]).await?;
+        assert!(matches!(
+            status,
+            IbcTransferStatus::Acknowledged { success: false, error: Some(ref e), .. } if e == "insufficient funds"
+        ));
+
+        // an acknowledgement of another packet in the same transaction doesn't count
+        let terra = self::terra(acks("8", None))?;
+        let transfer = terra
+            .ibc()
+            .follow_ibc_transfer("SENT", 2, Duration::from_millis(1))
+            .await?;
+        assert_eq!(transfer.status, IbcTransferStatus::Pending);
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::client::tx_types::{TxResultBlockEvent, TxResultBlockMsg, V1TXResponse};
+use serde::Serialize;
+
+/// a packet sent over an IBC channel, from its `send_packet` event
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcPacket {
+    pub sequence: u64,
+    pub src_port: String,
+    pub src_channel: String,
+    pub dst_port: String,
+    pub dst_channel: String,
+    /// `revision-height` on the counterparty. `0-0` for none
+    pub timeout_height: String,
+    /// nanoseconds since the unix epoch. 0 for none
+    pub timeout_timestamp: u64,
+}
+impl IbcPacket {
+    /// the packet described by a `send_packet` event
+    pub fn from_event(event: &TxResultBlockEvent) -> Option<IbcPacket> {
+        if event.s_type != "send_packet" {
+            return None;
+        }
+        let value = |key: &str| event.get_first_value(key).unwrap_or_default();
+        Some(IbcPacket {
+            sequence: value("packet_sequence").parse().ok()?,
+            src_port: value("packet_src_port"),
+            src_channel: value("packet_src_channel"),
+            dst_port: value("packet_dst_port"),
+            dst_channel: value("packet_dst_channel"),
+            timeout_height: value("packet_timeout_height"),
+            timeout_timestamp: value("packet_timeout_timestamp")
+                .parse()
+                .unwrap_or_default(),
+        })
+    }
+    /// the packets a transaction sent
+    pub fn sent_by(tx: &V1TXResponse) -> Vec<IbcPacket> {
+        tx.get_events("send_packet")
+            .iter()
+            .filter_map(IbcPacket::from_event)
+            .collect()
+    }
+    /// is this event about this packet
+    fn is_about(&self, event: &TxResultBlockEvent) -> bool {
+        event.get_first_value("packet_sequence") == Some(self.sequence.to_string())
+            && event.get_first_value("packet_src_channel").as_deref()
+                == Some(self.src_channel.as_str())
+            && event.get_first_value("packet_src_port").as_deref() == Some(self.src_port.as_str())
+    }
+    /// the message of `tx` with an `event_type` event about this packet
+    pub(crate) fn message_in<'a>(
+        &self,
+        tx: &'a V1TXResponse,
+        event_type: &str,
+    ) -> Option<&'a TxResultBlockMsg> {
+        tx.logs.iter().flatten().find(|log| {
+            log.events
+                .iter()
+                .any(|event| event.s_type == event_type && self.is_about(event))
+        })
+    }
+}
+
+/// where a packet is, as seen from the chain that sent it
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum IbcTransferStatus {
+    /// neither acknowledged nor timed out yet
+    Pending,
+    /// the counterparty received it. `error` is why it refused the transfer, and the coins went back
+    Acknowledged {
+        height: u64,
+        txhash: String,
+        success: bool,
+        error: Option<String>,
+    },
+    /// it wasn't received in time, and the coins went back
+    TimedOut { height: u64, txhash: String },
+}
+impl IbcTransferStatus {
+    /// the acknowledgement of `packet` in `tx`, if it has one
+    pub fn acknowledged_in(packet: &IbcPacket, tx: &V1TXResponse) -> Option<IbcTransferStatus> {
+        let message = packet.message_in(tx, "acknowledge_packet")?;
+        let error = message
+            .events
+            .iter()
+            .filter(|event| event.s_type == "fungible_token_packet")
+            .find_map(|event| event.get_first_value("error"));
+        Some(IbcTransferStatus::Acknowledged {
+            height: tx.height,
+            txhash: tx.txhash.clone(),
+            success: error.is_none(),
+            error,
+        })
+    }
+    /// the timeout of `packet` in `tx`, if it has one
+    pub fn timed_out_in(packet: &IbcPacket, tx: &V1TXResponse) -> Option<IbcTransferStatus> {
+        packet
+            .message_in(tx, "timeout_packet")
+            .map(|_| IbcTransferStatus::TimedOut {
+                height: tx.height,
+                txhash: tx.txhash.clone(),
+            })
+    }
+    /// acknowledged or timed out
+    pub fn is_final(&self) -> bool {
+        !matches!(self, IbcTransferStatus::Pending)
+    }
+}
+
+/// a packet a transfer sent, and what became of it
+#[derive(Serialize, Debug, Clone)]
+pub struct IbcTransfer {
+    /// the transaction that sent it
+    pub txhash: String,
+    pub packet: IbcPacket,
+    pub status: IbcTransferStatus,
+}
@@ -8,6 +8,16 @@ pub struct LCDResult<T> {
     pub height: u64,
     /// the LCD response
     pub result: T,
+    /// the untouched response. only filled in when the client captures unknown fields,
+    /// or it was fetched with send_cmd_raw
+    #[serde(default, skip_serializing)]
+    pub raw: Option<serde_json::Value>,
+}
+impl<T> LCDResult<T> {
+    /// the untouched JSON response, if it was kept
+    pub fn raw(&self) -> Option<&serde_json::Value> {
+        self.raw.as_ref()
+    }
 }
 #[derive(Deserialize, Serialize, Debug)]
 /// ALL interactions with LCD will return a LCD result
@@ -17,6 +27,10 @@ pub struct LCDResultVec<T> {
     pub height: u64,
     /// the LCD response
     pub result: Vec<T>,
+    /// the untouched response. only filled in when the client captures unknown fields,
+    /// or it was fetched with send_cmd_raw
+    #[serde(default, skip_serializing)]
+    pub raw: Option<serde_json::Value>,
 }
 #[allow(missing_docs)]
 #[derive(Deserialize, Serialize, Debug)]
@@ -0,0 +1,170 @@
+use crate::errors::TerraRustAPIError;
+use futures::future::BoxFuture;
+use hyper::client::connect::{Connected, Connection};
+use hyper::{Body, Client, Uri};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::UnixStream;
+
+/// the host requests to a unix socket are made to. the socket is all that matters, so it is never looked up
+pub const UNIX_SOCKET_HOST: &str = "http://localhost";
+
+/// Sends requests to a node's LCD over its unix domain socket, with hyper connecting to the socket itself.
+/// Nothing listens on, or leaves, the host, so there is no TLS or DNS involved
+pub struct UnixSocket {
+    pub path: PathBuf,
+    client: Client<UnixConnector, Body>,
+}
+impl UnixSocket {
+    /// talk to the socket at `path`. fails if there is nothing there
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<UnixSocket> {
+        let path = path.as_ref().to_path_buf();
+        std::fs::metadata(&path)?;
+        let client = Client::builder().build(UnixConnector(Arc::new(path.clone())));
+        Ok(UnixSocket { path, client })
+    }
+    /// send the request down the socket. the host in its URL is ignored
+    pub(crate) async fn send(
+        &self,
+        req: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, TerraRustAPIError> {
+        let request = req.build()?;
+        let url = request.url();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let mut builder = hyper::Request::builder()
+            .method(request.method().clone())
+            .uri(format!("{}{}", UNIX_SOCKET_HOST, path));
+        for (name, value) in request.headers() {
+            builder = builder.header(name, value);
+        }
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| bytes.to_vec())
+            .unwrap_or_default();
+        let hyper_request = builder
+            .body(Body::from(body))
+            .map_err(|e| TerraRustAPIError::StdErr(e.to_string()))?;
+        let exchange = async {
+            let response = self
+                .client
+                .request(hyper_request)
+                .await
+                .map_err(|e| self.error(e))?;
+            let (parts, body) = response.into_parts();
+            let bytes = hyper::body::to_bytes(body)
+                .await
+                .map_err(|e| self.error(e))?;
+            Ok(reqwest::Response::from(hyper::Response::from_parts(
+                parts,
+                bytes.to_vec(),
+            )))
+        };
+        match request.timeout() {
+            Some(timeout) => tokio::time::timeout(*timeout, exchange)
+                .await
+                .map_err(|_| {
+                    TerraRustAPIError::IOErr(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("{} timed out", self.path.display()),
+                    ))
+                })?,
+            None => exchange.await,
+        }
+    }
+    fn error(&self, e: hyper::Error) -> TerraRustAPIError {
+        TerraRustAPIError::StdErr(format!("unix socket {}: {}", self.path.display(), e))
+    }
+}
+
+/// connects hyper to the socket, whatever the URI
+#[derive(Clone)]
+struct UnixConnector(Arc<PathBuf>);
+impl hyper::service::Service<Uri> for UnixConnector {
+    type Response = UnixConnection;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<UnixConnection>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let path = self.0.clone();
+        Box::pin(async move { Ok(UnixConnection(UnixStream::connect(&*path).await?)) })
+    }
+}
+
+struct UnixConnection(UnixStream);
+impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+impl AsyncRead for UnixConnection {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_read(cx, buf)
+    }
+}
+impl AsyncWrite for UnixConnection {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_write(cx, buf)
+    }
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_flush(cx)
+    }
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::Terra;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    #[tokio::test]
+    pub async fn test_unix_socket() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("terra-rust-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let node = UnixListener::bind(&path)?;
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = node.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 14\r\n\r\n{\"height\":\"5\"}")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+        let terra = Terra::lcd_client_no_tx("https://lcd.terra.dev", "columbus-5")
+            .with_unix_socket(&path)
+            .await?;
+        let body = terra
+            .send_cmd::<serde_json::Value>("/blocks/latest", Some("?a=1"), None)
+            .await?;
+        assert_eq!(body["height"], "5");
+        let request = server.await?;
+        assert!(request.starts_with("GET /blocks/latest?a=1 HTTP/1.1\r\n"));
+        std::fs::remove_file(&path)?;
+        assert!(UnixSocket::create(&path).is_err());
+        Ok(())
+    }
+}
@@ -1,10 +1,15 @@
 use crate::client::core_types::Coin;
+use crate::client::market_types::{
+    MarketParameters, SwapSimulation, SweepOptions, SweepReportLine, MICRO_LUNA, MICRO_SDR,
+};
 
-use crate::{LCDResult, Message, Terra};
+use crate::{ConsistentSnapshot, LCDResult, Message, Terra};
+use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 
+use crate::client::batch::DEFAULT_MAX_CONCURRENCY;
 use crate::messages::market::MsgSwap;
-use futures::future::join_all;
 
 /// Market functions. mainly around swapping tokens
 pub struct Market<'a> {
@@ -31,6 +36,102 @@ impl Market<'_> {
             .await?;
         Ok(response)
     }
+    /// the market parameters
+    pub async fn parameters(
+        &self,
+        height: Option<u64>,
+    ) -> anyhow::Result<LCDResult<MarketParameters>> {
+        Ok(self
+            .terra
+            .send_cmd::<LCDResult<MarketParameters>>("/market/parameters", None, height)
+            .await?)
+    }
+    /// how far the virtual pool is from its base, in micro SDR. positive when terra has been swapped in
+    pub async fn terra_pool_delta(&self, height: Option<u64>) -> anyhow::Result<Decimal> {
+        let response = self
+            .terra
+            .send_cmd::<LCDResult<String>>("/market/terra_pool_delta", None, height)
+            .await?;
+        Ok(response.result.parse::<Decimal>()?)
+    }
+    /// [Market::swap], along with the spread it pays, and what it does to the virtual pool.
+    /// everything comes from the same block
+    pub async fn simulate_swap(
+        &self,
+        offer: &Coin,
+        ask_denom: &str,
+        height: Option<u64>,
+    ) -> anyhow::Result<SwapSimulation> {
+        let height = match height {
+            Some(height) => Some(height),
+            None => Some(ConsistentSnapshot::create(self.terra).await?.height),
+        };
+        let oracle = self.terra.oracle();
+        let (ask, params, terra_pool_delta, rates, oracle_params) = futures::try_join!(
+            self.swap(offer, ask_denom, height),
+            self.parameters(height),
+            self.terra_pool_delta(height),
+            oracle.exchange_rates(height),
+            oracle.parameters(height),
+        )?;
+        let params = params.result;
+        // exchange rates are the price of a luna
+        let mut rates = rates
+            .result
+            .into_iter()
+            .map(|c| (c.denom, c.amount))
+            .collect::<HashMap<_, _>>();
+        rates.insert(MICRO_LUNA.into(), Decimal::ONE);
+        let convert = |amount: Decimal, from: &str, to: &str| -> anyhow::Result<Decimal> {
+            let rate = |denom: &str| {
+                rates
+                    .get(denom)
+                    .copied()
+                    .filter(|r| !r.is_zero())
+                    .ok_or_else(|| anyhow::anyhow!("no exchange rate for {}", denom))
+            };
+            Ok(amount * rate(to)? / rate(from)?)
+        };
+        let ask_at_oracle_rate =
+            Coin::create(ask_denom, convert(offer.amount, &offer.denom, ask_denom)?);
+        let offer_is_luna = offer.denom == MICRO_LUNA;
+        let (spread, min_spread, terra_pool_delta_after) = if offer_is_luna
+            || ask_denom == MICRO_LUNA
+        {
+            let base_offer = convert(offer.amount, &offer.denom, MICRO_SDR)?;
+            let spread =
+                SwapSimulation::pool_spread(&params, terra_pool_delta, offer_is_luna, base_offer)
+                    .ok_or_else(|| anyhow::anyhow!("swap of {} is out of range", offer))?;
+            let after = if offer_is_luna {
+                terra_pool_delta - base_offer
+            } else {
+                terra_pool_delta + base_offer
+            };
+            (spread, params.min_stability_spread, after)
+        } else {
+            // terra <-> terra swaps pay the higher tobin tax, and don't touch the pool
+            let tobin_tax = oracle_params
+                .result
+                .whitelist
+                .iter()
+                .filter(|d| d.name == offer.denom || d.name == ask_denom)
+                .filter_map(|d| Decimal::from_f64(d.tobin_tax))
+                .max()
+                .unwrap_or_default();
+            (tobin_tax, tobin_tax, terra_pool_delta)
+        };
+        Ok(SwapSimulation {
+            offer: offer.clone(),
+            ask: ask.result,
+            ask_at_oracle_rate,
+            spread,
+            min_spread,
+            terra_pool_delta,
+            terra_pool_delta_after,
+            base_pool: params.base_pool,
+            pool_recovery_period: params.pool_recovery_period,
+        })
+    }
     /// generate a set of transactions to swap a account's tokens into another, as long as they are above a certain threshold
     pub async fn generate_sweep_messages(
         &self,
@@ -39,56 +140,181 @@ impl Market<'_> {
         threshold: Decimal,
         height: Option<u64>,
     ) -> anyhow::Result<Vec<Message>> {
-        let account_balances = self.terra.bank().balances(&from, height).await?;
-        let potential_coins = account_balances
+        self.generate_sweep_messages_with(&from, &to_coin, &SweepOptions::create(threshold), height)
+            .await
+    }
+    /// [Market::generate_sweep_messages], choosing the coins by `options`
+    pub async fn generate_sweep_messages_with(
+        &self,
+        from: &str,
+        to_coin: &str,
+        options: &SweepOptions,
+        height: Option<u64>,
+    ) -> anyhow::Result<Vec<Message>> {
+        let height = self.sweep_height(height).await?;
+        let quotes = self
+            .sweep_quotes(from, to_coin, height, |denom| options.considers(denom))
+            .await?;
+        let mut messages = Vec::new();
+        for (offer, _) in options.select(quotes) {
+            messages.push(MsgSwap::create(offer, to_coin, from)?);
+        }
+        Ok(messages)
+    }
+    /// what a sweep would do with each of the account's coins, without making any messages.
+    /// the values are the same [Market::swap] quotes the sweep itself uses, so net of the spread.
+    /// the spread is how far they are below the oracle rate, or zero if the oracle has no rate
+    pub async fn sweep_report(
+        &self,
+        from: &str,
+        to_coin: &str,
+        options: &SweepOptions,
+        height: Option<u64>,
+    ) -> anyhow::Result<Vec<SweepReportLine>> {
+        let height = self.sweep_height(height).await?;
+        let oracle = self.terra.oracle();
+        let (quotes, rates) = futures::try_join!(
+            self.sweep_quotes(from, to_coin, height, |_| true),
+            oracle.exchange_rates(height),
+        )?;
+        // exchange rates are the price of a luna
+        let mut rates = rates
             .result
             .into_iter()
-            .filter(|c| c.denom != to_coin);
-        //.collect::<Vec<Coin>>();
-        let into_currency_futures = potential_coins
+            .map(|c| (c.denom, c.amount))
+            .collect::<HashMap<_, _>>();
+        rates.insert(MICRO_LUNA.into(), Decimal::ONE);
+        let rate = |denom: &str| rates.get(denom).copied().filter(|r| !r.is_zero());
+        let quotes = quotes
             .into_iter()
-            .map(|c| async {
-                let resp = self
-                    .terra
-                    .market()
-                    .swap(&c.clone(), &to_coin, height)
-                    .await
-                    .map(|f| (c, f.result));
-                resp
+            .map(|(offer, value)| {
+                let spread = match (rate(&offer.denom), rate(to_coin)) {
+                    (Some(from_rate), Some(to_rate)) => {
+                        let at_oracle_rate = offer.amount * to_rate / from_rate;
+                        if at_oracle_rate.is_zero() {
+                            Decimal::ZERO
+                        } else {
+                            Decimal::ONE - value.amount / at_oracle_rate
+                        }
+                    }
+                    _ => Decimal::ZERO,
+                };
+                (offer, value, spread)
             })
-            .collect::<Vec<_>>();
+            .collect();
+        Ok(options.report(quotes))
+    }
+    /// the balances and the swap rates have to come from the same block
+    async fn sweep_height(&self, height: Option<u64>) -> anyhow::Result<Option<u64>> {
+        Ok(match height {
+            Some(height) => Some(height),
+            None => Some(ConsistentSnapshot::create(self.terra).await?.height),
+        })
+    }
+    /// what each of the account's coins in the denoms `considers` allows would swap into
+    async fn sweep_quotes(
+        &self,
+        from: &str,
+        to_coin: &str,
+        height: Option<u64>,
+        considers: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<Vec<(Coin, Coin)>> {
+        let account_balances = self.terra.bank().balances(from, height).await?;
+        let potential_coins = account_balances
+            .result
+            .into_iter()
+            .filter(|c| c.denom != to_coin && considers(&c.denom));
+        let mut batch = self.terra.batch(DEFAULT_MAX_CONCURRENCY);
+        batch.extend(
+            potential_coins.map(|c| async move {
+                self.swap(&c, to_coin, height).await.map(|f| (c, f.result))
+            }),
+        );
+        batch.run().await.into_iter().collect()
+    }
+}
 
-        let into_currency = join_all(into_currency_futures).await;
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::wire_log::{Replay, WireLogEntry};
+    use rust_decimal_macros::dec;
 
-        let mut err = None;
-        let to_convert = &into_currency
-            .into_iter()
-            .flat_map(|f| match f {
-                Ok(coins) => {
-                    if coins.1.amount > threshold {
-                        Some(coins)
-                    } else {
-                        None
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error  {}", e);
-                    err = Some(e);
-                    None
-                }
+    fn entry(url: &str, response: &str) -> WireLogEntry {
+        WireLogEntry {
+            status: Some(200),
+            response: Some(response.into()),
+            ..WireLogEntry::create("GET", url, None)
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_sweep_report() -> anyhow::Result<()> {
+        let swap = |offer: &str, value: &str| {
+            entry(
+                &format!(
+                    "http://lcd/market/swap?offer_coin={}&ask_denom=uusd&height=7",
+                    offer
+                ),
+                &format!(
+                    r#"{{"height":"7","result":{{"denom":"uusd","amount":"{}"}}}}"#,
+                    value
+                ),
+            )
+        };
+        let replay = || {
+            Replay::create(vec![
+                entry(
+                    "http://lcd/bank/balances/terra1holder?height=7",
+                    r#"{"height":"7","result":[{"denom":"ukrw","amount":"1000"},{"denom":"uluna","amount":"10"},{"denom":"usdr","amount":"100"},{"denom":"uusd","amount":"5"}]}"#,
+                ),
+                swap("1000ukrw", "5"),
+                swap("10uluna", "980"),
+                swap("100usdr", "140"),
+                entry(
+                    "http://lcd/oracle/denoms/exchange_rates?height=7",
+                    r#"{"height":"7","result":[{"denom":"ukrw","amount":"200000"},{"denom":"uusd","amount":"100"}]}"#,
+                ),
+            ])
+        };
+        let options = SweepOptions::create(dec!(10)).deny("ukrw").max_messages(1);
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay());
+        let report = terra
+            .market()
+            .sweep_report("terra1holder", "uusd", &options, Some(7))
+            .await?;
+        let summary = report
+            .iter()
+            .map(|l| {
+                (
+                    l.balance.denom.as_str(),
+                    l.value.amount,
+                    l.passes,
+                    l.swapped,
+                )
             })
             .collect::<Vec<_>>();
-        match err {
-            Some(e) => Err(e),
-            None => {
-                let mut messages = Vec::new();
-                for swap_coins in to_convert {
-                    let message =
-                        MsgSwap::create(swap_coins.0.clone(), to_coin.clone(), from.clone())?;
-                    messages.push(message);
-                }
-                Ok(messages)
-            }
-        }
+        assert_eq!(
+            summary,
+            vec![
+                ("uluna", dec!(980), true, true),
+                ("usdr", dec!(140), true, false),
+                ("ukrw", dec!(5), false, false),
+            ]
+        );
+        // a luna is worth 100uusd at the oracle rate, and the quote was 2% under it
+        assert_eq!(report[0].spread, dec!(0.02));
+        // usdr has no oracle rate
+        assert_eq!(report[1].spread, Decimal::ZERO);
+
+        // the sweep swaps what the report says it would
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay());
+        let messages = terra
+            .market()
+            .generate_sweep_messages_with("terra1holder", "uusd", &options, Some(7))
+            .await?;
+        assert_eq!(messages.len(), 1);
+        assert!(serde_json::to_string(&messages[0])?.contains(r#""denom":"uluna""#));
+        Ok(())
     }
 }
@@ -0,0 +1,297 @@
+use crate::client::client_types::{terra_decimal_format, terra_u64_format};
+use crate::client::core_types::Coin;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// the micro SDR, the market module's unit of account
+pub const MICRO_SDR: &str = "usdr";
+/// the micro luna
+pub const MICRO_LUNA: &str = "uluna";
+
+/// the market module's parameters. luna <-> terra swaps trade against a virtual constant product pool
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MarketParameters {
+    /// the size of each side of the pool, in micro SDR
+    #[serde(with = "terra_decimal_format")]
+    pub base_pool: Decimal,
+    /// the number of blocks the pool takes to recover to its base
+    #[serde(with = "terra_u64_format")]
+    pub pool_recovery_period: u64,
+    /// the least spread a luna <-> terra swap pays
+    #[serde(with = "terra_decimal_format")]
+    pub min_stability_spread: Decimal,
+}
+
+/// what a swap would do, as of the block it was simulated at
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SwapSimulation {
+    pub offer: Coin,
+    /// what the chain would return
+    pub ask: Coin,
+    /// what the offer is worth at the oracle rates, before any spread
+    pub ask_at_oracle_rate: Coin,
+    /// the fraction of the swap taken as spread
+    #[serde(with = "terra_decimal_format")]
+    pub spread: Decimal,
+    /// the spread the swap pays when the pool is at its base. the stability spread, or the tobin tax between terra denominations
+    #[serde(with = "terra_decimal_format")]
+    pub min_spread: Decimal,
+    /// how far the pool is from its base, in micro SDR, before the swap
+    #[serde(with = "terra_decimal_format")]
+    pub terra_pool_delta: Decimal,
+    /// and after it
+    #[serde(with = "terra_decimal_format")]
+    pub terra_pool_delta_after: Decimal,
+    #[serde(with = "terra_decimal_format")]
+    pub base_pool: Decimal,
+    #[serde(with = "terra_u64_format")]
+    pub pool_recovery_period: u64,
+}
+impl SwapSimulation {
+    /// the pool is depleted enough that the swap pays more than the minimum spread
+    pub fn exceeds_min_spread(&self) -> bool {
+        self.spread > self.min_spread
+    }
+    /// how much of the base pool the delta uses after the swap. 1 means as much as the base pool again
+    pub fn pool_usage_after(&self) -> Decimal {
+        if self.base_pool.is_zero() {
+            Decimal::ZERO
+        } else {
+            self.terra_pool_delta_after.abs() / self.base_pool
+        }
+    }
+    /// the spread of a luna <-> terra swap worth `base_offer` micro SDR, as the market module computes it.
+    /// None if the numbers are out of range
+    pub fn pool_spread(
+        params: &MarketParameters,
+        terra_pool_delta: Decimal,
+        offer_is_luna: bool,
+        base_offer: Decimal,
+    ) -> Option<Decimal> {
+        if base_offer.is_zero() {
+            return Some(params.min_stability_spread);
+        }
+        let cp = params.base_pool.checked_mul(params.base_pool)?;
+        let terra_pool = params.base_pool.checked_add(terra_pool_delta)?;
+        let luna_pool = cp.checked_div(terra_pool)?;
+        let (offer_pool, ask_pool) = if offer_is_luna {
+            (luna_pool, terra_pool)
+        } else {
+            (terra_pool, luna_pool)
+        };
+        let ask_base =
+            ask_pool.checked_sub(cp.checked_div(offer_pool.checked_add(base_offer)?)?)?;
+        let spread = base_offer.checked_sub(ask_base)?.checked_div(base_offer)?;
+        Some(spread.max(params.min_stability_spread))
+    }
+}
+
+/// which of an account's coins a sweep swaps. values are in the denom swept into
+#[derive(Clone, Debug, Default)]
+pub struct SweepOptions {
+    /// coins worth no more than this aren't swapped
+    pub threshold: Decimal,
+    /// thresholds for particular denoms, in place of `threshold`
+    pub thresholds: HashMap<String, Decimal>,
+    /// if not empty, only these denoms are swapped
+    pub allow: Vec<String>,
+    /// these denoms are never swapped
+    pub deny: Vec<String>,
+    /// swap at most this many coins, the most valuable first
+    pub max_messages: Option<usize>,
+}
+impl SweepOptions {
+    /// swap every coin worth more than `threshold`
+    pub fn create(threshold: Decimal) -> SweepOptions {
+        SweepOptions {
+            threshold,
+            ..Default::default()
+        }
+    }
+    /// `denom` is only swapped when worth more than `threshold`
+    pub fn threshold_for(mut self, denom: &str, threshold: Decimal) -> Self {
+        self.thresholds.insert(denom.into(), threshold);
+        self
+    }
+    /// swap `denom`. once one is allowed, the others aren't
+    pub fn allow(mut self, denom: &str) -> Self {
+        self.allow.push(denom.into());
+        self
+    }
+    /// never swap `denom`
+    pub fn deny(mut self, denom: &str) -> Self {
+        self.deny.push(denom.into());
+        self
+    }
+    pub fn max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = Some(max_messages);
+        self
+    }
+    /// the threshold `denom` has to pass
+    pub fn threshold_of(&self, denom: &str) -> Decimal {
+        self.thresholds
+            .get(denom)
+            .copied()
+            .unwrap_or(self.threshold)
+    }
+    /// is `denom` swapped at all, whatever it's worth
+    pub fn considers(&self, denom: &str) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(|d| d == denom))
+            && !self.deny.iter().any(|d| d == denom)
+    }
+    /// is an `offer` worth `value` swapped
+    pub fn passes(&self, offer: &Coin, value: &Coin) -> bool {
+        self.considers(&offer.denom) && value.amount > self.threshold_of(&offer.denom)
+    }
+    /// the (offer, value) pairs that pass, the most valuable first, cut to `max_messages`
+    pub fn select(&self, quotes: Vec<(Coin, Coin)>) -> Vec<(Coin, Coin)> {
+        let mut selected = quotes
+            .into_iter()
+            .filter(|(offer, value)| self.passes(offer, value))
+            .collect::<Vec<_>>();
+        selected.sort_by_key(|(_, value)| std::cmp::Reverse(value.amount));
+        if let Some(max_messages) = self.max_messages {
+            selected.truncate(max_messages);
+        }
+        selected
+    }
+}
+
+/// what a sweep would do with one of the account's coins
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SweepReportLine {
+    /// the account's balance of the denom
+    pub balance: Coin,
+    /// what it would swap into
+    pub value: Coin,
+    /// the fraction of the swap taken as spread
+    #[serde(with = "terra_decimal_format")]
+    pub spread: Decimal,
+    /// what `value` has to be more than
+    #[serde(with = "terra_decimal_format")]
+    pub threshold: Decimal,
+    /// above its threshold, and not left out by the allow or deny lists
+    pub passes: bool,
+    /// one of the swaps the sweep makes. it may pass, and still be cut by `max_messages`
+    pub swapped: bool,
+}
+impl SweepOptions {
+    /// a line for each (offer, value, spread) quote, the most valuable first
+    pub fn report(&self, quotes: Vec<(Coin, Coin, Decimal)>) -> Vec<SweepReportLine> {
+        let swapped = self.select(
+            quotes
+                .iter()
+                .map(|(offer, value, _)| (offer.clone(), value.clone()))
+                .collect(),
+        );
+        let mut lines = quotes
+            .into_iter()
+            .map(|(offer, value, spread)| SweepReportLine {
+                passes: self.passes(&offer, &value),
+                swapped: swapped.iter().any(|(o, _)| o.denom == offer.denom),
+                threshold: self.threshold_of(&offer.denom),
+                balance: offer,
+                value,
+                spread,
+            })
+            .collect::<Vec<_>>();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.value.amount));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_pool_spread() {
+        let params = MarketParameters {
+            base_pool: dec!(1000000),
+            pool_recovery_period: 36,
+            min_stability_spread: dec!(0.005),
+        };
+        // tiny swaps against a balanced pool only pay the minimum
+        assert_eq!(
+            SwapSimulation::pool_spread(&params, Decimal::ZERO, true, dec!(1)),
+            Some(dec!(0.005))
+        );
+        // a swap of a tenth of the pool: ask = 1M - 1M^2/1.1M, spread = 1/11
+        let spread =
+            SwapSimulation::pool_spread(&params, Decimal::ZERO, true, dec!(100000)).unwrap();
+        assert_eq!(spread.round_dp(6), dec!(0.090909));
+        // after terra was swapped in, swapping more terra costs more than swapping luna
+        let terra = SwapSimulation::pool_spread(&params, dec!(200000), false, dec!(50000)).unwrap();
+        let luna = SwapSimulation::pool_spread(&params, dec!(200000), true, dec!(50000)).unwrap();
+        assert!(terra > luna);
+    }
+
+    #[test]
+    pub fn test_sweep_options() {
+        let quote = |denom: &str, value: Decimal| {
+            (Coin::create(denom, dec!(1000)), Coin::create("uusd", value))
+        };
+        let quotes = vec![
+            quote("ukrw", dec!(5)),
+            quote("uluna", dec!(90)),
+            quote("usdr", dec!(20)),
+            quote("ueur", dec!(60)),
+        ];
+        let denoms = |selected: Vec<(Coin, Coin)>| {
+            selected
+                .into_iter()
+                .map(|(offer, _)| offer.denom)
+                .collect::<Vec<_>>()
+        };
+        let options = SweepOptions::create(dec!(10));
+        assert_eq!(
+            denoms(options.select(quotes.clone())),
+            vec!["uluna", "ueur", "usdr"]
+        );
+        let options = SweepOptions::create(dec!(10))
+            .threshold_for("uluna", dec!(100))
+            .threshold_for("ukrw", dec!(1))
+            .deny("usdr");
+        assert_eq!(denoms(options.select(quotes.clone())), vec!["ueur", "ukrw"]);
+        let options = SweepOptions::create(dec!(0))
+            .allow("ukrw")
+            .allow("uluna")
+            .max_messages(1);
+        assert!(!options.considers("ueur"));
+        assert_eq!(denoms(options.select(quotes)), vec!["uluna"]);
+    }
+
+    #[test]
+    pub fn test_sweep_report() {
+        let simulation = |denom: &str, value: Decimal| {
+            (
+                Coin::create(denom, dec!(1000)),
+                Coin::create("uusd", value),
+                dec!(0.02),
+            )
+        };
+        let options = SweepOptions::create(dec!(10))
+            .threshold_for("ukrw", dec!(1))
+            .deny("usdr")
+            .max_messages(1);
+        let report = options.report(vec![
+            simulation("ukrw", dec!(5)),
+            simulation("usdr", dec!(90)),
+            simulation("uluna", dec!(60)),
+        ]);
+        let summary = report
+            .iter()
+            .map(|l| (l.balance.denom.as_str(), l.threshold, l.passes, l.swapped))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("usdr", dec!(10), false, false),
+                ("uluna", dec!(10), true, true),
+                ("ukrw", dec!(1), true, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use super::{NAME, VERSION};
+use crate::errors::TerraRustAPIError;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// what goes in the memo of the transactions a client signs. see [crate::Terra::memo_policy]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum MemoPolicy {
+    /// the memo the transaction is given, or `PFC-terra-rust-api/<version>` without one (the default)
+    #[default]
+    Default,
+    /// always empty, whatever memo the transaction is given
+    Empty,
+    /// always this, whatever memo the transaction is given
+    Static(String),
+    /// filled in for each transaction
+    Template(MemoTemplate),
+}
+impl MemoPolicy {
+    /// the memo for a transaction
+    pub fn memo(&self, context: &MemoContext) -> Result<String, TerraRustAPIError> {
+        match self {
+            MemoPolicy::Default => Ok(context.memo.clone().unwrap_or_else(default_memo)),
+            MemoPolicy::Empty => Ok(String::new()),
+            MemoPolicy::Static(memo) => Ok(memo.clone()),
+            MemoPolicy::Template(template) => template.render(context),
+        }
+    }
+}
+/// `default` or empty, `none`, a template if it has a `{`, otherwise a static memo
+impl FromStr for MemoPolicy {
+    type Err = TerraRustAPIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" | "default" => Ok(MemoPolicy::Default),
+            "none" => Ok(MemoPolicy::Empty),
+            s if s.contains('{') => Ok(MemoPolicy::Template(MemoTemplate::create(s)?)),
+            s => Ok(MemoPolicy::Static(s.into())),
+        }
+    }
+}
+
+/// the memo this crate signs with, when it isn't given one
+pub fn default_memo() -> String {
+    format!(
+        "PFC-{}/{}",
+        NAME.unwrap_or("TERRA-RUST"),
+        VERSION.unwrap_or("dev")
+    )
+}
+
+/// what a memo template can refer to
+#[derive(Clone, Debug)]
+pub struct MemoContext {
+    pub chain_id: String,
+    /// the account signing
+    pub sender: String,
+    pub account_number: u64,
+    pub sequence: u64,
+    /// the memo the transaction was given
+    pub memo: Option<String>,
+}
+
+/// A memo with `{name}` placeholders, replaced by the transaction's `{chain_id}`, `{sender}`, `{account_number}`,
+/// `{sequence}` and `{memo}` (empty if it has none), `{default}` for this crate's memo, or variables the application sets.
+/// `{{` and `}}` are literal braces. An unknown placeholder fails the transaction
+/// ```
+/// use terra_rust_api::client::memo::{MemoContext, MemoTemplate};
+/// let template = MemoTemplate::create("{app}/{chain_id}#{sequence}").unwrap().var("app", "treasury");
+/// let context = MemoContext {
+///     chain_id: "columbus-5".into(),
+///     sender: "terra1..".into(),
+///     account_number: 1,
+///     sequence: 42,
+///     memo: None,
+/// };
+/// assert_eq!(template.render(&context).unwrap(), "treasury/columbus-5#42");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoTemplate {
+    template: String,
+    vars: BTreeMap<String, String>,
+}
+impl MemoTemplate {
+    /// fails if the braces don't pair up
+    pub fn create<S: Into<String>>(template: S) -> Result<MemoTemplate, TerraRustAPIError> {
+        let template = MemoTemplate {
+            template: template.into(),
+            vars: BTreeMap::new(),
+        };
+        template.fill(|_| Some(String::new()))?;
+        Ok(template)
+    }
+    /// what `{name}` is replaced by
+    pub fn var<S: Into<String>>(mut self, name: &str, value: S) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+    /// the memo for a transaction
+    pub fn render(&self, context: &MemoContext) -> Result<String, TerraRustAPIError> {
+        self.fill(|name| match name {
+            "chain_id" => Some(context.chain_id.clone()),
+            "sender" => Some(context.sender.clone()),
+            "account_number" => Some(context.account_number.to_string()),
+            "sequence" => Some(context.sequence.to_string()),
+            "memo" => Some(context.memo.clone().unwrap_or_default()),
+            "default" => Some(default_memo()),
+            name => self.vars.get(name).cloned(),
+        })
+    }
+    fn fill<F: Fn(&str) -> Option<String>>(&self, value: F) -> Result<String, TerraRustAPIError> {
+        let invalid = |reason: String| {
+            TerraRustAPIError::InvalidMemo(format!("{} in `{}`", reason, self.template))
+        };
+        let mut out = String::new();
+        let mut chars = self.template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    out.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    out.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(invalid("unclosed {".into())),
+                            Some(c) => name.push(c),
+                        }
+                    }
+                    let name = name.trim();
+                    out.push_str(
+                        &value(name).ok_or_else(|| invalid(format!("unknown {{{}}}", name)))?,
+                    );
+                }
+                '}' => return Err(invalid("unopened }".into())),
+                c => out.push(c),
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_memo_policy() -> anyhow::Result<()> {
+        let context = MemoContext {
+            chain_id: "bombay-12".into(),
+            sender: "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7".into(),
+            account_number: 43045,
+            sequence: 3,
+            memo: Some("invoice 7".into()),
+        };
+        assert_eq!(MemoPolicy::Default.memo(&context)?, "invoice 7");
+        assert!(MemoPolicy::Default
+            .memo(&MemoContext {
+                memo: None,
+                ..context.clone()
+            })?
+            .starts_with("PFC-terra-rust-api/"));
+        assert_eq!("none".parse::<MemoPolicy>()?.memo(&context)?, "");
+        assert_eq!("ACME".parse::<MemoPolicy>()?.memo(&context)?, "ACME");
+
+        let policy = "{{{app}}} {chain_id}/{sequence}: {memo}".parse::<MemoPolicy>()?;
+        let policy = match policy {
+            MemoPolicy::Template(template) => MemoPolicy::Template(template.var("app", "desk")),
+            _ => anyhow::bail!("not a template"),
+        };
+        assert_eq!(policy.memo(&context)?, "{desk} bombay-12/3: invoice 7");
+
+        let unknown = MemoTemplate::create("{app}")?;
+        assert!(unknown.render(&context).is_err());
+        assert!(MemoTemplate::create("{chain_id").is_err());
+        assert!(MemoTemplate::create("chain}").is_err());
+        Ok(())
+    }
+}
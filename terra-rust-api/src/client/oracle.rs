@@ -1,4 +1,6 @@
+use crate::client::oracle_types::{MissEvent, MissTracker};
 use crate::client::oracle_types::{OracleParameters, OraclePreVotes, OracleVotes};
+use crate::core_types::Coin;
 use crate::{LCDResult, Terra};
 
 pub struct Oracle<'a> {
@@ -14,7 +16,18 @@ impl<'a> Oracle<'a> {
     ) -> anyhow::Result<LCDResult<OracleParameters>> {
         let response = self
             .terra
-            .send_cmd::<LCDResult<OracleParameters>>("/oracle/parameters", None, height)
+            .send_cmd_cached::<LCDResult<OracleParameters>>("/oracle/parameters", None, height)
+            .await?;
+        Ok(response)
+    }
+    /// the price of a luna in each of the whitelisted denominations
+    pub async fn exchange_rates(
+        &self,
+        height: Option<u64>,
+    ) -> anyhow::Result<LCDResult<Vec<Coin>>> {
+        let response = self
+            .terra
+            .send_cmd::<LCDResult<Vec<Coin>>>("/oracle/denoms/exchange_rates", None, height)
             .await?;
         Ok(response)
     }
@@ -78,4 +91,40 @@ impl<'a> Voters<'a> {
             .await?;
         Ok(response)
     }
+    /// the miss counter as a number
+    pub async fn miss_count(&self, height: Option<u64>) -> anyhow::Result<LCDResult<u64>> {
+        let response = self.miss(height).await?;
+        Ok(LCDResult {
+            height: response.height,
+            result: response.result.parse::<u64>()?,
+            raw: response.raw,
+        })
+    }
+    /// poll the miss counter every `interval`, calling `callback` whenever it goes up.
+    /// stops when the callback returns false. errors talking to the LCD are logged and retried
+    pub async fn watch_miss<F: FnMut(&MissEvent) -> bool>(
+        &self,
+        interval: std::time::Duration,
+        mut callback: F,
+    ) -> anyhow::Result<()> {
+        let allowed = Oracle::create(self.terra)
+            .parameters(None)
+            .await?
+            .result
+            .allowed_misses();
+        let mut tracker = MissTracker::create(self.validator, Some(allowed));
+        loop {
+            match self.miss_count(None).await {
+                Ok(misses) => {
+                    if let Some(event) = tracker.observe(misses.height, misses.result) {
+                        if !callback(&event) {
+                            return Ok(());
+                        }
+                    }
+                }
+                Err(e) => log::warn!("unable to fetch miss counter: {}", e),
+            }
+            self.terra.pause(interval, "miss counter").await?;
+        }
+    }
 }
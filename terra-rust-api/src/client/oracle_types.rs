@@ -27,6 +27,16 @@ pub struct OracleParameters {
     #[serde(with = "terra_f64_format")]
     pub min_valid_per_window: f64,
 }
+impl OracleParameters {
+    /// how many votes can be missed in a slash window before the validator is slashed
+    pub fn allowed_misses(&self) -> u64 {
+        if self.vote_period == 0 {
+            return 0;
+        }
+        let votes = (self.slash_window / self.vote_period) as f64;
+        (votes * (1.0 - self.min_valid_per_window)).floor() as u64
+    }
+}
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct OracleVotes {
@@ -44,3 +54,70 @@ pub struct OraclePreVotes {
     #[serde(with = "terra_u64_format")]
     pub submit_block: u64,
 }
+
+/// the miss counter went up
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissEvent {
+    pub validator: String,
+    pub height: u64,
+    pub previous: u64,
+    pub misses: u64,
+    /// misses allowed in the slash window, if known
+    pub allowed: Option<u64>,
+}
+impl MissEvent {
+    /// the misses since the last check
+    pub fn new_misses(&self) -> u64 {
+        self.misses - self.previous
+    }
+}
+
+/// follows a validator's miss counter, reporting when it goes up.
+/// the counter resets at the end of each slash window, which is not reported
+#[derive(Debug, Clone)]
+pub struct MissTracker {
+    pub validator: String,
+    pub allowed: Option<u64>,
+    last: Option<u64>,
+}
+impl MissTracker {
+    pub fn create(validator: &str, allowed: Option<u64>) -> MissTracker {
+        MissTracker {
+            validator: validator.to_string(),
+            allowed,
+            last: None,
+        }
+    }
+    /// record the counter at `height`. the first observation only sets the baseline
+    pub fn observe(&mut self, height: u64, misses: u64) -> Option<MissEvent> {
+        let previous = self.last.replace(misses)?;
+        if misses <= previous {
+            return None;
+        }
+        Some(MissEvent {
+            validator: self.validator.clone(),
+            height,
+            previous,
+            misses,
+            allowed: self.allowed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_miss_tracker() {
+        let mut tracker = MissTracker::create("terravaloper1", Some(100));
+        assert_eq!(tracker.observe(10, 3), None);
+        assert_eq!(tracker.observe(15, 3), None);
+        let event = tracker.observe(20, 5).unwrap();
+        assert_eq!(event.new_misses(), 2);
+        assert_eq!(event.allowed, Some(100));
+        // a new slash window
+        assert_eq!(tracker.observe(25, 0), None);
+        assert_eq!(tracker.observe(30, 1).unwrap().previous, 0);
+    }
+}
@@ -0,0 +1,149 @@
+use regex::Regex;
+
+/// What to do when a query asks for a height the LCD has pruned.
+/// By default nothing, and the query fails with [crate::errors::TerraRustAPIError::HeightPruned]
+#[derive(Clone, Debug, Default)]
+pub struct PrunedHeights {
+    /// archival LCDs, asked at the same height, in order
+    pub archives: Vec<String>,
+    /// if no archive has it, ask the LCD again at the nearest height it still has.
+    /// when the LCD doesn't say which that is, it is searched for, a request per halving
+    pub nearest: bool,
+}
+
+/// a query answered at another height, or by another LCD, than asked. see [crate::Terra::tracking_substitutions]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeightSubstitution {
+    pub path: String,
+    pub requested: u64,
+    /// the height the answer is from
+    pub height: u64,
+    /// the LCD that answered
+    pub lcd: String,
+}
+
+/// the height asked for, and the lowest & latest the LCD says it has, if the response is a pruned height error
+pub(crate) fn parse(text: &str) -> Option<(u64, Option<u64>, Option<u64>)> {
+    lazy_static! {
+        // cosmos-sdk 0.44/0.45
+        static ref LOAD_STATE: Regex =
+            Regex::new(r"failed to load state at height (\d+);[^(]*does not exist[^(]*\(latest height: (\d+)\)")
+                .unwrap();
+        static ref LOWEST: Regex =
+            Regex::new(r"height (\d+) is not available, lowest height is (\d+)").unwrap();
+    }
+    let number = |m: Option<regex::Match>| m.and_then(|m| m.as_str().parse::<u64>().ok());
+    if let Some(captures) = LOWEST.captures(text) {
+        let requested = number(captures.get(1))?;
+        return Some((requested, number(captures.get(2)), None));
+    }
+    let captures = LOAD_STATE.captures(text)?;
+    let requested = number(captures.get(1))?;
+    let latest = number(captures.get(2))?;
+    // a height in the future isn't pruned
+    if requested < latest {
+        Some((requested, None, Some(latest)))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_parse() {
+        let legacy = r#"{"error":"failed to load state at height 100; version does not exist (latest height: 7000000)"}"#;
+        assert_eq!(parse(legacy), Some((100, None, Some(7000000))));
+        let gateway = r#"{"code":3,"message":"failed to load state at height 100; version does not exist (latest height: 5000): invalid request","details":[]}"#;
+        assert_eq!(parse(gateway), Some((100, None, Some(5000))));
+        let future = r#"{"error":"failed to load state at height 9000; version does not exist (latest height: 5000)"}"#;
+        assert_eq!(parse(future), None);
+        let lowest = r#"{"code":26,"message":"height 12 is not available, lowest height is 4500: invalid height"}"#;
+        assert_eq!(parse(lowest), Some((12, Some(4500), None)));
+        assert_eq!(parse(r#"{"error":"account not found"}"#), None);
+    }
+
+    #[tokio::test]
+    pub async fn test_fallback() -> anyhow::Result<()> {
+        use crate::client::wire_log::{Replay, WireLogEntry};
+        use crate::errors::TerraRustAPIError;
+        // the LCD has heights from 121 on, the archive all of them
+        let recorded = |lcd: &str, height: u64| {
+            let url = format!("{}/bank/balances/terra1?height={}", lcd, height);
+            let (status, response) = if lcd == "http://lcd" && height < 121 {
+                (
+                    500,
+                    format!(
+                        r#"{{"error":"failed to load state at height {}; version does not exist (latest height: 164)"}}"#,
+                        height
+                    ),
+                )
+            } else {
+                (200, format!(r#"{{"height":"{}","result":[]}}"#, height))
+            };
+            WireLogEntry {
+                status: Some(status),
+                response: Some(response),
+                ..WireLogEntry::create("GET", &url, None)
+            }
+        };
+        let entries = (100..=164)
+            .flat_map(|height| {
+                vec![
+                    recorded("http://lcd", height),
+                    recorded("http://archive", height),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut terra = crate::Terra::lcd_client_no_tx("http://lcd", "columbus-5")
+            .with_replay(Replay::create(entries));
+        let balances = |terra: crate::Terra| async move {
+            terra
+                .send_cmd::<serde_json::Value>("/bank/balances/terra1", None, Some(100))
+                .await
+                .map(|v| v["height"].as_str().unwrap_or_default().to_string())
+        };
+        match balances(terra.clone()).await {
+            Err(e) => assert!(matches!(
+                e.without_context(),
+                TerraRustAPIError::HeightPruned(100, None, Some(164))
+            )),
+            Ok(v) => panic!("{}", v),
+        }
+        assert!(terra.height_substitutions().is_empty());
+
+        terra.pruned_heights.nearest = true;
+        let tracked = terra.tracking_substitutions();
+        assert_eq!(balances(tracked.clone()).await?, "121");
+        let substitution = HeightSubstitution {
+            path: "/bank/balances/terra1".into(),
+            requested: 100,
+            height: 121,
+            lcd: "http://lcd".into(),
+        };
+        assert_eq!(tracked.height_substitutions(), vec![substitution.clone()]);
+        assert!(tracked.height_substitutions().is_empty());
+
+        // each call's clone only sees its own
+        terra.pruned_heights.archives = vec!["http://archive".into()];
+        let (pruned, current) = (
+            terra.tracking_substitutions(),
+            terra.tracking_substitutions(),
+        );
+        let current_balances =
+            current.send_cmd::<serde_json::Value>("/bank/balances/terra1", None, Some(150));
+        let (archived, _) = futures::join!(balances(pruned.clone()), current_balances);
+        assert_eq!(archived?, "100");
+        let substitution = HeightSubstitution {
+            height: 100,
+            lcd: "http://archive".into(),
+            ..substitution
+        };
+        assert_eq!(pruned.height_substitutions(), vec![substitution]);
+        assert!(current.height_substitutions().is_empty());
+        assert!(terra.height_substitutions().is_empty());
+        Ok(())
+    }
+}
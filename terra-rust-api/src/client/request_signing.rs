@@ -0,0 +1,202 @@
+use crate::errors::TerraRustAPIError;
+use crate::PrivateKey;
+use crypto::digest::Digest;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::RequestBuilder;
+use secp256k1::{All, Secp256k1};
+use std::fmt;
+
+/// the header carrying the unix time a request was signed at
+pub const TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+/// the header carrying the key id of a [HmacSigner], or the base64 public key of a [KeySigner]
+pub const KEY_HEADER: &str = "x-signature-key";
+/// the header carrying the base64 signature
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// a request, as it is about to be sent
+#[derive(Clone, Debug)]
+pub struct SignableRequest<'a> {
+    /// upper case, like `GET`
+    pub method: &'a str,
+    /// the path & query of the URL, like `/blocks/latest?height=5`
+    pub path: &'a str,
+    /// empty for a GET
+    pub body: &'a [u8],
+    /// seconds since the unix epoch
+    pub timestamp: i64,
+}
+impl<'a> SignableRequest<'a> {
+    /// what the built in signers sign: the timestamp, method, path and the hex SHA-256 of the body, a line each
+    pub fn string_to_sign(&self) -> String {
+        let mut sha = Sha256::new();
+        sha.input(self.body);
+        format!(
+            "{}\n{}\n{}\n{}",
+            self.timestamp,
+            self.method,
+            self.path,
+            sha.result_str()
+        )
+    }
+}
+
+/// Signs each request to the LCD, for gateways that authenticate requests in front of their nodes.
+/// see [crate::Terra::with_request_signer]
+pub trait RequestSigner: Send + Sync {
+    /// the headers to send with the request
+    fn sign(&self, request: &SignableRequest) -> Result<Vec<(String, String)>, TerraRustAPIError>;
+    /// masked in the logs & errors of the client signing with it. see [crate::Terra::redact]
+    fn secrets(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// HMAC-SHA256 of [SignableRequest::string_to_sign] with a shared secret. The secret is masked in the client's logs
+#[derive(Clone)]
+pub struct HmacSigner {
+    pub key_id: String,
+    secret: String,
+}
+impl HmacSigner {
+    pub fn create<S: Into<String>>(key_id: S, secret: S) -> HmacSigner {
+        HmacSigner {
+            key_id: key_id.into(),
+            secret: secret.into(),
+        }
+    }
+}
+impl fmt::Debug for HmacSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HmacSigner({})", self.key_id)
+    }
+}
+impl RequestSigner for HmacSigner {
+    fn sign(&self, request: &SignableRequest) -> Result<Vec<(String, String)>, TerraRustAPIError> {
+        let mut hmac = Hmac::new(Sha256::new(), self.secret.as_bytes());
+        hmac.input(request.string_to_sign().as_bytes());
+        Ok(vec![
+            (TIMESTAMP_HEADER.into(), request.timestamp.to_string()),
+            (KEY_HEADER.into(), self.key_id.clone()),
+            (
+                SIGNATURE_HEADER.into(),
+                base64::encode(hmac.result().code()),
+            ),
+        ])
+    }
+    fn secrets(&self) -> Vec<String> {
+        vec![self.secret.clone()]
+    }
+}
+
+/// a secp256k1 signature of [SignableRequest::string_to_sign], with the key's public key,
+/// for gateways that know their clients by account
+pub struct KeySigner {
+    key: PrivateKey,
+    secp: Secp256k1<All>,
+}
+impl KeySigner {
+    pub fn create(key: PrivateKey) -> KeySigner {
+        KeySigner {
+            key,
+            secp: Secp256k1::new(),
+        }
+    }
+}
+impl RequestSigner for KeySigner {
+    fn sign(&self, request: &SignableRequest) -> Result<Vec<(String, String)>, TerraRustAPIError> {
+        let signature = self
+            .key
+            .sign_canonical(&self.secp, &request.string_to_sign())?;
+        Ok(vec![
+            (TIMESTAMP_HEADER.into(), request.timestamp.to_string()),
+            (KEY_HEADER.into(), signature.pub_key.value),
+            (SIGNATURE_HEADER.into(), signature.signature),
+        ])
+    }
+}
+
+/// the request with the signer's headers added, marked sensitive
+pub(crate) fn sign_request(
+    signer: &dyn RequestSigner,
+    req: RequestBuilder,
+    timestamp: i64,
+) -> Result<RequestBuilder, TerraRustAPIError> {
+    let request = req
+        .try_clone()
+        .ok_or_else(|| TerraRustAPIError::StdErr("a streamed body can't be signed".into()))?
+        .build()?;
+    let url = request.url();
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let headers = signer.sign(&SignableRequest {
+        method: request.method().as_str(),
+        path: &path,
+        body: request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .unwrap_or_default(),
+        timestamp,
+    })?;
+    // RequestBuilder::header would clear the sensitive flag
+    let mut signed = HeaderMap::new();
+    for (name, value) in headers {
+        let invalid =
+            || TerraRustAPIError::Config(format!("signed header {}", name), value.clone());
+        let header = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+        let mut value = HeaderValue::from_str(&value).map_err(|_| invalid())?;
+        value.set_sensitive(true);
+        signed.insert(header, value);
+    }
+    Ok(req.headers(signed))
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_request_signing() -> anyhow::Result<()> {
+        let request = SignableRequest {
+            method: "POST",
+            path: "/txs?apikey=k",
+            body: b"{}",
+            timestamp: 1700000000,
+        };
+        assert_eq!(
+            request.string_to_sign(),
+            "1700000000\nPOST\n/txs?apikey=k\n44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        let headers = HmacSigner::create("gateway-1", "s3cret").sign(&request)?;
+        assert_eq!(headers[0], (TIMESTAMP_HEADER.into(), "1700000000".into()));
+        assert_eq!(headers[1], (KEY_HEADER.into(), "gateway-1".into()));
+        let mut hmac = Hmac::new(Sha256::new(), b"s3cret");
+        hmac.input(request.string_to_sign().as_bytes());
+        assert_eq!(headers[2].1, base64::encode(hmac.result().code()));
+        assert_ne!(
+            headers,
+            HmacSigner::create("gateway-1", "other").sign(&request)?
+        );
+
+        let client = reqwest::Client::new();
+        let req = client
+            .get("http://lcd/blocks/latest?height=5")
+            .query(&[("apikey", "k")]);
+        let signed =
+            sign_request(&HmacSigner::create("gateway-1", "s3cret"), req, 1700000000)?.build()?;
+        let expected = HmacSigner::create("gateway-1", "s3cret").sign(&SignableRequest {
+            method: "GET",
+            path: "/blocks/latest?height=5&apikey=k",
+            body: b"",
+            timestamp: 1700000000,
+        })?;
+        let signature = signed.headers().get(SIGNATURE_HEADER).unwrap();
+        assert!(signature.is_sensitive());
+        assert_eq!(signature.to_str()?, expected[2].1);
+        Ok(())
+    }
+}
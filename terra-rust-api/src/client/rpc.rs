@@ -1,6 +1,13 @@
-use crate::client::rpc_types::{RPCNetInfo, RPCResult, RPCStatus, RPCUnconfirmedTXS};
+use crate::client::batch::DEFAULT_MAX_CONCURRENCY;
+use crate::client::event_query::{percent_encode, EventQuery};
+use crate::client::rpc_types::{
+    RPCABCIQuery, RPCABCIQueryResponse, RPCConsensusParams, RPCNetInfo, RPCResult, RPCStatus,
+    RPCTxSearch, RPCUnconfirmedTXS, ValidatorEvents,
+};
+use crate::client::slashing::Slashing;
 use crate::tendermint_types::{BlockResult, BlockResultsResult};
 use crate::Terra;
+use futures::{stream, StreamExt};
 pub struct RPC<'a> {
     terra: &'a Terra,
     rpc_url: &'a str,
@@ -82,4 +89,104 @@ impl RPC<'_> {
             .await?
             .result)
     }
+    /// transactions matching the query, oldest first. `page` starts at 1, `per_page` is at most 100
+    pub async fn tx_search(
+        &self,
+        query: &EventQuery,
+        page: u64,
+        per_page: u64,
+    ) -> anyhow::Result<RPCTxSearch> {
+        let query = percent_encode(&format!("\"{}\"", query.to_query()?));
+        Ok(self
+            .terra
+            .send_cmd_url::<RPCResult<RPCTxSearch>>(
+                self.rpc_url,
+                &format!(
+                    "/tx_search?query={}&page={}&per_page={}&order_by=%22asc%22",
+                    query, page, per_page
+                ),
+                None,
+                None,
+            )
+            .await?
+            .result)
+    }
+    /// a validator's commission withdrawals & slashes from block `from` to `to`, inclusive,
+    /// read from the block results. the node has to still have them, so old ranges need an archive node.
+    /// blocks are folded in as they arrive, so a long range doesn't pile up in memory. ones that
+    /// can't be fetched are listed in [ValidatorEvents::failed_heights] rather than failing the lot
+    pub async fn validator_events(
+        &self,
+        operator_address: &str,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<ValidatorEvents> {
+        let validator = self
+            .terra
+            .staking()
+            .validator(operator_address)
+            .await?
+            .result;
+        let consensus_address = Slashing::consensus_address(&validator.consensus_pubkey)?;
+        let mut blocks = stream::iter(from..=to)
+            .map(|height| async move { (height, self.block_results_at_height(height).await) })
+            .buffer_unordered(DEFAULT_MAX_CONCURRENCY);
+        let mut events = ValidatorEvents::default();
+        while let Some((height, block)) = blocks.next().await {
+            match block {
+                Ok(block) => events.add_block(&block, operator_address, &consensus_address),
+                Err(e) => {
+                    // give up once cancelled or past the deadline, as the rest would fail too
+                    self.terra.check_live("validator events")?;
+                    log::warn!("block results at height {}: {}", height, e);
+                    events.failed_heights.push(height);
+                }
+            }
+        }
+        events.slashes.sort_by_key(|slash| slash.height);
+        events
+            .commission_withdrawals
+            .sort_by_key(|withdrawal| withdrawal.height);
+        events.failed_heights.sort_unstable();
+        Ok(events)
+    }
+    /// the block size & gas limits. see [crate::TxLimits]
+    pub async fn consensus_params(&self) -> anyhow::Result<RPCConsensusParams> {
+        Ok(self
+            .terra
+            .send_cmd_url::<RPCResult<RPCConsensusParams>>(
+                self.rpc_url,
+                "/consensus_params",
+                None,
+                None,
+            )
+            .await?
+            .result)
+    }
+    /// query the app's state directly. `path` is something like `/store/bank/key`.
+    /// with `prove` set, the response carries the merkle proof, see [crate::proof]
+    pub async fn abci_query(
+        &self,
+        path: &str,
+        data: &[u8],
+        height: Option<u64>,
+        prove: bool,
+    ) -> anyhow::Result<RPCABCIQueryResponse> {
+        Ok(self
+            .terra
+            .send_cmd_url::<RPCResult<RPCABCIQuery>>(
+                self.rpc_url,
+                "/abci_query",
+                Some(&format!(
+                    "?path=%22{}%22&data=0x{}&prove={}",
+                    path,
+                    hex::encode(data),
+                    prove
+                )),
+                height,
+            )
+            .await?
+            .result
+            .response)
+    }
 }
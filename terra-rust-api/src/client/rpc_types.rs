@@ -1,4 +1,6 @@
 use crate::client::client_types::terra_u64_format;
+use crate::core_types::Coin;
+use crate::tendermint_types::{BlockResultsResult, EventType, RPCTXResult};
 
 use serde::Deserialize;
 use serde::Serialize;
@@ -86,3 +88,245 @@ pub struct RPCResult<T> {
     pub id: i64,
     pub result: T,
 }
+/// one step of a merkle proof. key and data are base64
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RPCProofOp {
+    #[serde(rename = "type")]
+    pub op_type: String,
+    pub key: String,
+    pub data: String,
+}
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RPCProofOps {
+    pub ops: Vec<RPCProofOp>,
+}
+/// the answer to an abci_query. key and value are base64
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RPCABCIQueryResponse {
+    #[serde(default)]
+    pub code: u32,
+    #[serde(default)]
+    pub log: String,
+    pub key: Option<String>,
+    pub value: Option<String>,
+    #[serde(rename = "proofOps")]
+    pub proof_ops: Option<RPCProofOps>,
+    #[serde(with = "terra_u64_format")]
+    pub height: u64,
+}
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RPCABCIQuery {
+    pub response: RPCABCIQueryResponse,
+}
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RPCBlockParams {
+    #[serde(with = "terra_u64_format")]
+    pub max_bytes: u64,
+    /// -1 for no limit
+    pub max_gas: String,
+}
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RPCConsensusParamsInner {
+    pub block: RPCBlockParams,
+}
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RPCConsensusParams {
+    #[serde(with = "terra_u64_format")]
+    pub block_height: u64,
+    pub consensus_params: RPCConsensusParamsInner,
+}
+impl RPCConsensusParams {
+    /// the most gas a block can use, if there is a limit
+    pub fn max_block_gas(&self) -> Option<u64> {
+        self.consensus_params.block.max_gas.parse::<u64>().ok()
+    }
+}
+
+/// a transaction found by [crate::client::rpc::RPC::tx_search]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RPCTx {
+    pub hash: String,
+    #[serde(with = "terra_u64_format")]
+    pub height: u64,
+    /// its place in the block
+    pub index: u64,
+    pub tx_result: RPCTXResult,
+    /// base64 encoded protobuf
+    pub tx: String,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RPCTxSearch {
+    pub txs: Vec<RPCTx>,
+    /// of all the pages
+    #[serde(with = "terra_u64_format")]
+    pub total_count: u64,
+}
+
+/// a validator withdrawing its commission
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CommissionWithdrawal {
+    #[serde(with = "terra_u64_format")]
+    pub height: u64,
+    pub amount: Vec<Coin>,
+}
+/// a validator being slashed
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SlashEvent {
+    #[serde(with = "terra_u64_format")]
+    pub height: u64,
+    /// `missing_signature` or `double_sign`
+    pub reason: String,
+    /// the voting power it was slashed at
+    #[serde(with = "terra_u64_format")]
+    pub power: u64,
+    pub jailed: bool,
+}
+/// what happened to a validator over a range of blocks, that the LCD doesn't keep. see [crate::client::rpc::RPC::validator_events]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ValidatorEvents {
+    pub commission_withdrawals: Vec<CommissionWithdrawal>,
+    pub slashes: Vec<SlashEvent>,
+    /// blocks whose results couldn't be fetched, so any events in them are missing
+    #[serde(default)]
+    pub failed_heights: Vec<u64>,
+}
+impl ValidatorEvents {
+    /// add the events of a block. slashes name the validator by its terravalcons address,
+    /// commission withdrawals by its terravaloper one
+    pub fn add_block(
+        &mut self,
+        block: &BlockResultsResult,
+        operator_address: &str,
+        consensus_address: &str,
+    ) {
+        let height = block.height;
+        let block_events = block
+            .begin_block_events
+            .iter()
+            .chain(block.end_block_events.iter())
+            .flatten();
+        for event in block_events.filter(|e| e.s_type == "slash") {
+            let attributes = event.attribute_map();
+            let attribute = |key: &str| attributes.get(key).cloned().flatten();
+            if attribute("address").as_deref() != Some(consensus_address) {
+                continue;
+            }
+            self.slashes.push(SlashEvent {
+                height,
+                reason: attribute("reason").unwrap_or_default(),
+                power: attribute("power")
+                    .and_then(|p| p.parse::<u64>().ok())
+                    .unwrap_or_default(),
+                jailed: attribute("jailed").as_deref() == Some(consensus_address),
+            });
+        }
+        for tx in block.txs_results.iter().flatten().filter(|tx| tx.code == 0) {
+            self.add_tx_events(height, &tx.events, operator_address);
+        }
+    }
+    /// a `withdraw_commission` event only has the amount. the distribution module's `message` event
+    /// following it says which validator withdrew it
+    fn add_tx_events(&mut self, height: u64, events: &[EventType], operator_address: &str) {
+        let mut amount: Option<String> = None;
+        for event in events {
+            let attributes = event.attribute_map();
+            let attribute = |key: &str| attributes.get(key).cloned().flatten();
+            match event.s_type.as_str() {
+                "withdraw_commission" => amount = attribute("amount"),
+                "message" if attribute("sender").is_some() => {
+                    if let Some(amount) = amount.take() {
+                        if attribute("sender").as_deref() == Some(operator_address) {
+                            self.commission_withdrawals.push(CommissionWithdrawal {
+                                height,
+                                amount: Coin::parse_coins(&amount).unwrap_or_default(),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_validator_events() -> anyhow::Result<()> {
+        let event = |s_type: &str, attributes: &[(&str, &str)]| {
+            let attributes = attributes
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        r#"{{"key":"{}","value":"{}","index":true}}"#,
+                        base64::encode(k),
+                        base64::encode(v)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!(
+                r#"{{"type":"{}","attributes":[{}]}}"#,
+                s_type,
+                attributes.join(",")
+            )
+        };
+        let tx = |events: Vec<String>| {
+            format!(
+                r#"{{"code":0,"data":null,"log":"","info":"","gas_wanted":"1","gas_used":"1","events":[{}],"codespace":""}}"#,
+                events.join(",")
+            )
+        };
+        let withdraw = |validator: &str, amount: &str| {
+            vec![
+                event("message", &[("action", "withdraw_validator_commission")]),
+                event("withdraw_commission", &[("amount", amount)]),
+                event(
+                    "message",
+                    &[("module", "distribution"), ("sender", validator)],
+                ),
+            ]
+        };
+        let mut both = withdraw("terravaloper1other", "5uluna");
+        both.extend(withdraw("terravaloper1me", "7uluna,3uusd"));
+        let json = format!(
+            r#"{{"height":"10","txs_results":[{}],"begin_block_events":[{},{}],"end_block_events":null,"validator_updates":null,"consensus_param_updates":null}}"#,
+            tx(both),
+            event(
+                "slash",
+                &[
+                    ("address", "terravalcons1me"),
+                    ("power", "1000"),
+                    ("reason", "missing_signature"),
+                    ("jailed", "terravalcons1me")
+                ]
+            ),
+            event(
+                "slash",
+                &[
+                    ("address", "terravalcons1other"),
+                    ("power", "5"),
+                    ("reason", "double_sign")
+                ]
+            ),
+        );
+        let block = serde_json::from_str::<BlockResultsResult>(&json)?;
+        let mut events = ValidatorEvents::default();
+        events.add_block(&block, "terravaloper1me", "terravalcons1me");
+        assert_eq!(events.commission_withdrawals.len(), 1);
+        let amount = &events.commission_withdrawals[0].amount;
+        assert_eq!(amount.len(), 2);
+        assert_eq!(amount[0].to_string(), "7uluna");
+        assert_eq!(
+            events.slashes,
+            vec![SlashEvent {
+                height: 10,
+                reason: "missing_signature".into(),
+                power: 1000,
+                jailed: true,
+            }]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+//! What every clone of a [Terra](crate::Terra) shares, so they cooperate instead of each finding things out again.
+//! The settings, like the gas options or the LCD's URL, belong to each clone, and can be changed on one
+//! without touching the others. What has been learnt about the chain and the LCDs is kept here:
+//! which LCDs have been checked and which routes they serve, how they have been answering,
+//! the sequences of transactions sent and not yet committed, and recent gas prices
+
+use crate::errors::TerraRustAPIError;
+#[cfg(feature = "fcd")]
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(feature = "fcd")]
+use std::time::Instant;
+
+/// gas prices fetched less than this long ago are used again
+#[cfg(feature = "fcd")]
+const GAS_PRICES_TTL: Duration = Duration::from_secs(60);
+/// by denom
+#[cfg(feature = "fcd")]
+type GasPrices = HashMap<String, Decimal>;
+
+#[derive(Debug, Default)]
+pub(crate) struct Inner {
+    pub(crate) sequences: SequenceManager,
+    endpoints: Mutex<HashMap<String, Endpoint>>,
+    #[cfg(feature = "fcd")]
+    gas_prices: Mutex<HashMap<String, (Instant, GasPrices)>>,
+}
+
+#[derive(Debug, Default)]
+struct Endpoint {
+    /// the chain_id the LCD was found to be on
+    chain_id: Option<String>,
+    /// [crate::ApiVersion::to_u8], 0 until it is known
+    api_version: u8,
+    health: EndpointHealth,
+}
+
+impl Inner {
+    fn endpoint<R>(&self, lcd: &str, f: impl FnOnce(&mut Endpoint) -> R) -> R {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let endpoint = endpoints
+            .entry(lcd.to_string())
+            .or_insert_with(|| Endpoint {
+                health: EndpointHealth {
+                    endpoint: crate::redact::redact(lcd),
+                    ..EndpointHealth::default()
+                },
+                ..Endpoint::default()
+            });
+        f(endpoint)
+    }
+    pub(crate) fn chain_id_checked(&self, lcd: &str, chain_id: &str) -> bool {
+        self.endpoint(lcd, |e| e.chain_id.as_deref() == Some(chain_id))
+    }
+    pub(crate) fn set_chain_id(&self, lcd: &str, chain_id: &str) {
+        self.endpoint(lcd, |e| e.chain_id = Some(chain_id.to_string()))
+    }
+    pub(crate) fn api_version(&self, lcd: &str) -> u8 {
+        self.endpoint(lcd, |e| e.api_version)
+    }
+    pub(crate) fn set_api_version(&self, lcd: &str, api_version: u8) {
+        self.endpoint(lcd, |e| e.api_version = api_version)
+    }
+    /// count a request to the endpoint. answers the LCD gave, even errors like not found, show it is up
+    pub(crate) fn observe<T>(
+        &self,
+        lcd: &str,
+        elapsed: Duration,
+        result: &Result<T, TerraRustAPIError>,
+    ) {
+        let failure = match result {
+            Ok(_) => None,
+            Err(e) => match e.without_context() {
+                TerraRustAPIError::TerraLCDResponse(status, _) if !status.is_server_error() => None,
+                TerraRustAPIError::HeightPruned(..) => None,
+                e => Some(e.to_string()),
+            },
+        };
+        self.endpoint(lcd, |e| {
+            let health = &mut e.health;
+            health.requests += 1;
+            health.last_latency_ms = elapsed.as_millis() as u64;
+            match failure {
+                None => health.consecutive_failures = 0,
+                Some(error) => {
+                    health.failures += 1;
+                    health.consecutive_failures += 1;
+                    health.last_error = Some(error);
+                }
+            }
+        })
+    }
+    pub(crate) fn health(&self) -> Vec<EndpointHealth> {
+        let mut health = self
+            .endpoints
+            .lock()
+            .unwrap()
+            .values()
+            .map(|e| e.health.clone())
+            .filter(|h| h.requests > 0)
+            .collect::<Vec<_>>();
+        health.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+        health
+    }
+    /// the gas prices from `fcd_url`, if they were fetched recently
+    #[cfg(feature = "fcd")]
+    pub(crate) fn gas_prices(&self, fcd_url: &str) -> Option<GasPrices> {
+        self.gas_prices
+            .lock()
+            .unwrap()
+            .get(fcd_url)
+            .filter(|(fetched, _)| fetched.elapsed() < GAS_PRICES_TTL)
+            .map(|(_, prices)| prices.clone())
+    }
+    #[cfg(feature = "fcd")]
+    pub(crate) fn set_gas_prices(&self, fcd_url: &str, prices: &GasPrices) {
+        self.gas_prices
+            .lock()
+            .unwrap()
+            .insert(fcd_url.to_string(), (Instant::now(), prices.clone()));
+    }
+}
+
+/// how an endpoint has been answering, since the client was created. see [crate::Terra::endpoint_health]
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct EndpointHealth {
+    /// the LCD (or FCD) URL, with secrets masked
+    pub endpoint: String,
+    pub requests: u64,
+    /// requests that got no answer, or a server error
+    pub failures: u64,
+    /// failures since the last answer
+    pub consecutive_failures: u64,
+    pub last_latency_ms: u64,
+    pub last_error: Option<String>,
+}
+impl EndpointHealth {
+    /// the last request was answered
+    pub fn is_up(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+}
+
+/// The sequence numbers of transactions broadcast by the `submit_transaction_*` helpers and not yet committed.
+/// The LCD only knows the sequence of the committed ones, so without this, a second transaction sent before
+/// the first is in a block would be signed with the same sequence, and rejected.
+/// Shared between clones, so tasks sending from the same account don't collide
+/// ```
+/// use terra_rust_api::client::shared::SequenceManager;
+/// let sequences = SequenceManager::default();
+/// assert_eq!(sequences.reserve("terra1sender", 7), 7);
+/// assert_eq!(sequences.reserve("terra1sender", 7), 8);
+/// assert_eq!(sequences.peek("terra1sender", 7), 9);
+/// // committed since
+/// assert_eq!(sequences.reserve("terra1sender", 12), 12);
+/// sequences.reset("terra1sender");
+/// assert_eq!(sequences.peek("terra1sender", 7), 7);
+/// ```
+#[derive(Debug, Default)]
+pub struct SequenceManager {
+    next: Mutex<HashMap<String, u64>>,
+}
+impl SequenceManager {
+    /// the sequence to sign the address's next transaction with, given the one the chain has
+    pub fn peek(&self, address: &str, chain_sequence: u64) -> u64 {
+        self.next
+            .lock()
+            .unwrap()
+            .get(address)
+            .map_or(chain_sequence, |next| chain_sequence.max(*next))
+    }
+    /// [SequenceManager::peek], counting it as used by a transaction about to be broadcast
+    pub fn reserve(&self, address: &str, chain_sequence: u64) -> u64 {
+        self.hold(address, chain_sequence).keep()
+    }
+    /// [SequenceManager::reserve], given back unless [Reservation::keep] is called.
+    /// so a transaction that fails to sign, or whose future is dropped before it is broadcast, leaves no gap
+    pub(crate) fn hold(&self, address: &str, chain_sequence: u64) -> Reservation<'_> {
+        let mut next = self.next.lock().unwrap();
+        let sequence = next
+            .get(address)
+            .map_or(chain_sequence, |next| chain_sequence.max(*next));
+        next.insert(address.to_string(), sequence + 1);
+        Reservation {
+            sequences: self,
+            address: address.to_string(),
+            sequence,
+            kept: false,
+        }
+    }
+    /// go back to the chain's sequence for the address. done when a broadcast is turned away,
+    /// which leaves a gap that the transactions after it would fall into
+    pub fn reset(&self, address: &str) {
+        self.next.lock().unwrap().remove(address);
+    }
+    /// a transaction signed with `sequence` never made it into the mempool, so the sequence is free again.
+    /// the last one handed out is simply given back, so the transactions still waiting to be committed are
+    /// followed on from. one before others leaves a gap they fall into, so that resets instead
+    pub fn release(&self, address: &str, sequence: u64) {
+        let mut next = self.next.lock().unwrap();
+        if next.get(address) == Some(&(sequence + 1)) {
+            next.insert(address.to_string(), sequence);
+        } else {
+            next.remove(address);
+        }
+    }
+}
+
+/// a sequence held for a transaction about to be broadcast. see [SequenceManager::hold]
+pub(crate) struct Reservation<'a> {
+    sequences: &'a SequenceManager,
+    address: String,
+    pub(crate) sequence: u64,
+    kept: bool,
+}
+impl Reservation<'_> {
+    /// the transaction is going out with it
+    pub(crate) fn keep(mut self) -> u64 {
+        self.kept = true;
+        self.sequence
+    }
+}
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if !self.kept {
+            self.sequences.release(&self.address, self.sequence);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    pub fn test_endpoint_health() {
+        let inner = Inner::default();
+        let ok: Result<(), TerraRustAPIError> = Ok(());
+        let not_found: Result<(), TerraRustAPIError> = Err(TerraRustAPIError::TerraLCDResponse(
+            StatusCode::NOT_FOUND,
+            "".into(),
+        ));
+        let down: Result<(), TerraRustAPIError> = Err(TerraRustAPIError::TerraLCDResponse(
+            StatusCode::BAD_GATEWAY,
+            "".into(),
+        ));
+        inner.observe("http://a", Duration::from_millis(5), &ok);
+        inner.observe("http://a", Duration::from_millis(7), &not_found);
+        inner.observe("http://b", Duration::from_millis(5), &down);
+        inner.observe("http://b", Duration::from_millis(5), &down);
+        let health = inner.health();
+        assert_eq!(health.len(), 2);
+        assert!(health[0].is_up());
+        assert_eq!(health[0].requests, 2);
+        assert_eq!(health[0].last_latency_ms, 7);
+        assert!(!health[1].is_up());
+        assert_eq!(health[1].consecutive_failures, 2);
+
+        inner.set_chain_id("http://a", "columbus-5");
+        assert!(inner.chain_id_checked("http://a", "columbus-5"));
+        assert!(!inner.chain_id_checked("http://a", "bombay-12"));
+        assert!(!inner.chain_id_checked("http://b", "columbus-5"));
+    }
+
+    #[test]
+    pub fn test_reservation() {
+        let sequences = SequenceManager::default();
+        assert_eq!(sequences.reserve("terra1a", 3), 3);
+        drop(sequences.hold("terra1a", 3));
+        assert_eq!(sequences.peek("terra1a", 3), 4);
+        let first = sequences.hold("terra1a", 3);
+        let second = sequences.hold("terra1a", 3);
+        assert_eq!((first.sequence, second.sequence), (4, 5));
+        second.keep();
+        drop(first);
+        assert_eq!(sequences.peek("terra1a", 3), 3);
+        // turned away after the ones before it were sent
+        assert_eq!(sequences.reserve("terra1a", 3), 3);
+        assert_eq!(sequences.reserve("terra1a", 3), 4);
+        sequences.release("terra1a", 4);
+        assert_eq!(sequences.peek("terra1a", 3), 4);
+    }
+}
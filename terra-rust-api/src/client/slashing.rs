@@ -0,0 +1,106 @@
+use crate::client::slashing_types::{
+    SigningInfo, SigningInfoV1Response, SlashingParameters, SlashingParametersV1Response,
+};
+use crate::errors::TerraRustAPIError;
+use crate::tendermint_types::TendermintPublicKey;
+use crate::{ApiVersion, LCDResult, PublicKey, Terra};
+use bitcoin::bech32::{encode, ToBase32, Variant};
+
+/// validator signing info & jailing. The routes follow [Terra::api_version]
+pub struct Slashing<'a> {
+    terra: &'a Terra,
+}
+impl Slashing<'_> {
+    pub fn create(terra: &'_ Terra) -> Slashing<'_> {
+        Slashing { terra }
+    }
+    /// the slashing parameters
+    pub async fn parameters(&self) -> Result<SlashingParameters, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self
+                .terra
+                .send_cmd_cached::<LCDResult<SlashingParameters>>(
+                    "/slashing/parameters",
+                    None,
+                    None,
+                )
+                .await?
+                .result),
+            ApiVersion::V1Beta1 => Ok(self
+                .terra
+                .send_cmd_cached::<SlashingParametersV1Response>(
+                    "/cosmos/slashing/v1beta1/params",
+                    None,
+                    None,
+                )
+                .await?
+                .params),
+        }
+    }
+    /// the signing info of the validator with this consensus key, as the staking validator query gives it
+    pub async fn signing_info(
+        &self,
+        consensus_pubkey: &TendermintPublicKey,
+    ) -> Result<SigningInfo, TerraRustAPIError> {
+        let (pubkey, address) = Slashing::consensus_keys(consensus_pubkey)?;
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self
+                .terra
+                .send_cmd::<LCDResult<SigningInfo>>(
+                    &format!("/slashing/validators/{}/signing_info", pubkey),
+                    None,
+                    None,
+                )
+                .await?
+                .result),
+            ApiVersion::V1Beta1 => Ok(self
+                .terra
+                .send_cmd::<SigningInfoV1Response>(
+                    "/cosmos/slashing/v1beta1/signing_infos/",
+                    Some(&address),
+                    None,
+                )
+                .await?
+                .val_signing_info),
+        }
+    }
+    /// the terravalcons of a validator's consensus key, which block events name it by
+    pub fn consensus_address(
+        consensus_pubkey: &TendermintPublicKey,
+    ) -> Result<String, TerraRustAPIError> {
+        Ok(Slashing::consensus_keys(consensus_pubkey)?.1)
+    }
+    /// the terravalconspub & terravalcons of a base64 ed25519 consensus key
+    fn consensus_keys(
+        consensus_pubkey: &TendermintPublicKey,
+    ) -> Result<(String, String), TerraRustAPIError> {
+        let raw = base64::decode(&consensus_pubkey.value)?;
+        let pubkey = PublicKey::pubkey_from_ed25519_public_key(&raw);
+        let address = PublicKey::address_from_public_ed25519_key(&pubkey)?;
+        let pubkey = encode("terravalconspub", pubkey.to_base32(), Variant::Bech32)
+            .map_err(|_| TerraRustAPIError::Bech32DecodeErr)?;
+        let address = PublicKey::from_tendermint_address(&hex::encode(address))?.tendermint()?;
+        Ok((pubkey, address))
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_consensus_keys() -> anyhow::Result<()> {
+        let key = TendermintPublicKey {
+            s_type: "tendermint/PubKeyEd25519".into(),
+            value: "e6zfBnTwHA9Ud4nLQ3m+LNjRzyeB/Nx5Wsf0YRTd0uQ=".into(),
+        };
+        let (pubkey, address) = Slashing::consensus_keys(&key)?;
+        assert!(pubkey.starts_with("terravalconspub1"));
+        assert!(address.starts_with("terravalcons1"));
+        assert_eq!(
+            PublicKey::from_tendermint_key(&pubkey)?.tendermint()?,
+            address
+        );
+        Ok(())
+    }
+}
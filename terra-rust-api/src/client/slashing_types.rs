@@ -0,0 +1,69 @@
+use crate::client::client_types::{terra_datetime_format, terra_decimal_format, terra_u64_format};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// how a validator has been signing blocks
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SigningInfo {
+    /// the terravalcons address
+    pub address: String,
+    /// the height the validator started signing at
+    #[serde(with = "terra_u64_format")]
+    pub start_height: u64,
+    /// where in the signing window the validator is
+    #[serde(with = "terra_u64_format")]
+    pub index_offset: u64,
+    /// when a jailed validator can unjail
+    #[serde(with = "terra_datetime_format")]
+    pub jailed_until: DateTime<Utc>,
+    /// tombstoned validators double signed, and can never unjail
+    pub tombstoned: bool,
+    /// blocks missed in the current window
+    #[serde(with = "terra_u64_format")]
+    pub missed_blocks_counter: u64,
+}
+#[derive(Deserialize, Clone, Debug)]
+pub struct SigningInfoV1Response {
+    pub val_signing_info: SigningInfo,
+}
+
+/// the slashing module's parameters
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SlashingParameters {
+    /// the number of blocks missed blocks are counted over
+    #[serde(with = "terra_u64_format")]
+    pub signed_blocks_window: u64,
+    /// signing fewer than this fraction of the window jails the validator
+    #[serde(with = "terra_decimal_format")]
+    pub min_signed_per_window: Decimal,
+}
+#[derive(Deserialize, Clone, Debug)]
+pub struct SlashingParametersV1Response {
+    pub params: SlashingParameters,
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::LCDResult;
+
+    #[test]
+    pub fn test_signing_info() -> anyhow::Result<()> {
+        let v1: SigningInfoV1Response = serde_json::from_str(
+            r#"{"val_signing_info":{"address":"terravalcons1qqq","start_height":"0","index_offset":"5806217","jailed_until":"1970-01-01T00:00:00Z","tombstoned":false,"missed_blocks_counter":"12"}}"#,
+        )?;
+        assert_eq!(v1.val_signing_info.index_offset, 5806217);
+        assert_eq!(v1.val_signing_info.missed_blocks_counter, 12);
+        assert!(!v1.val_signing_info.tombstoned);
+        let params: LCDResult<SlashingParameters> = serde_json::from_str(
+            r#"{"height":"1","result":{"signed_blocks_window":"10000","min_signed_per_window":"0.050000000000000000","downtime_jail_duration":"600000000000","slash_fraction_double_sign":"0.050000000000000000","slash_fraction_downtime":"0.000100000000000000"}}"#,
+        )?;
+        assert_eq!(params.result.signed_blocks_window, 10000);
+        assert_eq!(
+            params.result.min_signed_per_window.to_string(),
+            "0.050000000000000000"
+        );
+        Ok(())
+    }
+}
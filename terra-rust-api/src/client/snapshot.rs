@@ -0,0 +1,72 @@
+use crate::Terra;
+use futures::Future;
+
+/// Queries made one after another can straddle a block, and mix balances from one block with
+/// swap rates from the next. A snapshot reads the latest height once, and hands out a client
+/// whose queries are all made at that height, unless they ask for another one.
+pub struct ConsistentSnapshot {
+    /// the height every query is made at
+    pub height: u64,
+    terra: Terra,
+}
+impl ConsistentSnapshot {
+    /// pin to the latest block
+    pub async fn create(terra: &Terra) -> anyhow::Result<ConsistentSnapshot> {
+        let height = terra.tendermint().blocks().await?.block.header.height;
+        Ok(ConsistentSnapshot::at_height(terra, height))
+    }
+    /// pin to a given block
+    pub fn at_height(terra: &Terra, height: u64) -> ConsistentSnapshot {
+        ConsistentSnapshot {
+            height,
+            terra: terra.at_height(height),
+        }
+    }
+    /// the pinned client
+    pub fn terra(&self) -> &Terra {
+        &self.terra
+    }
+    /// run `f` with a client pinned to the latest block
+    /// ```ignore
+    /// let (balances, rate) = ConsistentSnapshot::run(&terra, |terra| async move {
+    ///     let balances = terra.bank().balances(&account, None).await?;
+    ///     let rate = terra.market().swap(&coin, "uusd", None).await?;
+    ///     Ok((balances, rate))
+    /// })
+    /// .await?;
+    /// ```
+    pub async fn run<T, F, Fut>(terra: &Terra, f: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(Terra) -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let snapshot = ConsistentSnapshot::create(terra).await?;
+        f(snapshot.terra).await
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_pinned_height() {
+        let terra = Terra::lcd_client_no_tx("https://lcd.terra.dev", "columbus-5");
+        assert_eq!(terra.pinned_height(), None);
+        let snapshot = ConsistentSnapshot::at_height(&terra, 1234);
+        assert_eq!(snapshot.height, 1234);
+        assert_eq!(snapshot.terra().pinned_height(), Some(1234));
+        let pinned = snapshot.terra();
+        let (url, _) = pinned.get_request(&pinned.url, "/bank/balances/terra1", None, None);
+        assert_eq!(
+            url,
+            "https://lcd.terra.dev/bank/balances/terra1?height=1234"
+        );
+        let (url, _) = pinned.get_request(&pinned.url, "/bank/balances/terra1", None, Some(1));
+        assert_eq!(url, "https://lcd.terra.dev/bank/balances/terra1?height=1");
+        let (url, _) = pinned.get_request(&pinned.url, "/txs", Some("?height=7&limit=5"), None);
+        assert_eq!(url, "https://lcd.terra.dev/txs?height=7&limit=5");
+        let (url, _) = pinned.get_request(&pinned.url, "/txs", Some("?tx.height=7"), None);
+        assert_eq!(url, "https://lcd.terra.dev/txs?tx.height=7&height=1234");
+    }
+}
@@ -1,8 +1,22 @@
-use crate::client::staking_types::{Validator, ValidatorDelegation, ValidatorUnbondingDelegation};
+use crate::client::core_types::Pagination;
+use crate::client::staking_types::{
+    UnbondingSchedule, Validator, ValidatorDelegation, ValidatorUnbondingDelegation,
+};
 use crate::errors::TerraRustAPIError;
-use crate::staking_types::ValidatorDelegationsV1Response;
-use crate::{LCDResult, Terra};
+use crate::staking_types::{
+    ValidatorDelegationsV1Response, ValidatorUnbondingDelegationsV1Response, ValidatorV1Response,
+    ValidatorsV1Response,
+};
+use crate::{ApiVersion, LCDResult, Terra};
+use futures::{Stream, StreamExt};
 
+/// how many delegations are asked for at a time. top validators have over 100k delegators,
+/// too many for the LCD to return in one response before timing out
+pub const DELEGATIONS_PAGE_LIMIT: u64 = 1000;
+
+/// The routes follow [Terra::api_version]. The gateway routes don't say which height they answered at,
+/// so results from them carry the height asked for, or 0.
+/// Validator lookups go through [Terra::send_cmd_cached]
 pub struct Staking<'a> {
     terra: &'a Terra,
 }
@@ -10,55 +24,101 @@ impl Staking<'_> {
     pub fn create(terra: &'_ Terra) -> Staking<'_> {
         Staking { terra }
     }
+    fn lcd_result<T>(result: T, height: Option<u64>) -> LCDResult<T> {
+        LCDResult {
+            height: height.unwrap_or_default(),
+            result,
+            raw: None,
+        }
+    }
     pub async fn validator(&self, key: &str) -> Result<LCDResult<Validator>, TerraRustAPIError> {
         //   let url = self.terra.url.to_owned() + "/staking/validators/" + key;
-        Ok(self
-            .terra
-            .send_cmd::<LCDResult<Validator>>("/staking/validators/", Some(key), None)
-            .await?)
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self
+                .terra
+                .send_cmd_cached::<LCDResult<Validator>>("/staking/validators/", Some(key), None)
+                .await?),
+            ApiVersion::V1Beta1 => {
+                let response = self
+                    .terra
+                    .send_cmd_cached::<ValidatorV1Response>(
+                        "/cosmos/staking/v1beta1/validators/",
+                        Some(key),
+                        None,
+                    )
+                    .await?;
+                Ok(Staking::lcd_result(response.validator.into(), None))
+            }
+        }
     }
     /// Get list of validators
     pub async fn validators(&self) -> Result<LCDResult<Vec<Validator>>, TerraRustAPIError> {
-        Ok(self
-            .terra
-            .send_cmd::<LCDResult<Vec<Validator>>>("/staking/validators", None, None)
-            .await?)
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self
+                .terra
+                .send_cmd_cached::<LCDResult<Vec<Validator>>>("/staking/validators", None, None)
+                .await?),
+            ApiVersion::V1Beta1 => self.validators_v1(None, None).await,
+        }
     }
-    /// Get list of validators at a given height
-    pub async fn validators_at_height(
+    async fn validators_v1(
         &self,
-        height: u64,
+        height: Option<u64>,
         limit: Option<u64>,
     ) -> Result<LCDResult<Vec<Validator>>, TerraRustAPIError> {
-        Ok(self
+        let response = self
             .terra
-            .send_cmd::<LCDResult<Vec<Validator>>>(
+            .send_cmd_cached::<ValidatorsV1Response>(
                 &format!(
-                    "/staking/validators?height={}&limit={}",
-                    height,
+                    "/cosmos/staking/v1beta1/validators?pagination.limit={}",
                     limit.unwrap_or(200u64)
                 ),
                 None,
-                None,
+                height,
             )
-            .await?)
+            .await?;
+        Ok(Staking::lcd_result(
+            response
+                .validators
+                .into_iter()
+                .map(Validator::from)
+                .collect(),
+            height,
+        ))
+    }
+    /// Get list of validators at a given height
+    pub async fn validators_at_height(
+        &self,
+        height: u64,
+        limit: Option<u64>,
+    ) -> Result<LCDResult<Vec<Validator>>, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self
+                .terra
+                .send_cmd::<LCDResult<Vec<Validator>>>(
+                    &format!(
+                        "/staking/validators?height={}&limit={}",
+                        height,
+                        limit.unwrap_or(200u64)
+                    ),
+                    None,
+                    None,
+                )
+                .await?),
+            ApiVersion::V1Beta1 => self.validators_v1(Some(height), limit).await,
+        }
     }
     pub async fn validator_by_moniker(
         &self,
         moniker: &str,
     ) -> Result<Option<Validator>, TerraRustAPIError> {
-        let lst = self
-            .terra
-            .send_cmd::<LCDResult<Vec<Validator>>>("/staking/validators", None, None)
-            .await?
-            .result;
+        let lst = self.validators().await?.result;
         match lst.iter().find(|&p| p.description.moniker == moniker) {
             None => Ok(None),
             Some(v) => Ok(Some(v.to_owned())),
         }
     }
-    /// all delegations for a given validator
-    pub async fn validator_delegations(
+    async fn delegations_legacy(
         &self,
         key: &str,
     ) -> Result<LCDResult<Vec<ValidatorDelegation>>, TerraRustAPIError> {
@@ -70,35 +130,255 @@ impl Staking<'_> {
             )
             .await
     }
-    /// all delegations for a given validator (limit) (new format)
-    pub async fn validator_delegations_limit(
+    /// a page of delegations for a given validator. needs an LCD serving the v1beta1 routes
+    pub async fn delegations_page(
         &self,
         key: &str,
-        limit: u64,
+        pagination: &Pagination,
     ) -> Result<ValidatorDelegationsV1Response, TerraRustAPIError> {
         self.terra
             .send_cmd::<ValidatorDelegationsV1Response>(
-                &format!(
-                    "/cosmos/staking/v1beta1/validators/{}/delegations?pagination.limit={}",
-                    key, limit
-                ),
-                None,
+                &format!("/cosmos/staking/v1beta1/validators/{}/delegations?", key),
+                Some(&pagination.to_query()),
                 None,
             )
             .await
     }
+    /// the delegations for a given validator, `limit` to a page, following the pagination.
+    /// the stream ends after the last page, or the first error. needs an LCD serving the v1beta1 routes
+    pub fn delegation_pages<'b>(
+        &'b self,
+        key: &'b str,
+        limit: u64,
+    ) -> impl Stream<Item = Result<ValidatorDelegationsV1Response, TerraRustAPIError>> + 'b {
+        // None once there are no more pages
+        let start: Option<Option<String>> = Some(None);
+        futures::stream::unfold(start, move |next_key| async move {
+            let pagination = match next_key? {
+                Some(next_key) => Pagination::after(&next_key, limit),
+                None => Pagination::limit(limit),
+            };
+            match self.delegations_page(key, &pagination).await {
+                Ok(page) => {
+                    let next_key = page
+                        .pagination
+                        .as_ref()
+                        .and_then(|p| p.next_key.clone())
+                        .filter(|k| !k.is_empty());
+                    Some((Ok(page), next_key.map(Some)))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+    /// how many delegators a given validator has, without fetching the delegations
+    pub async fn delegation_count(&self, key: &str) -> Result<u64, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self.delegations_legacy(key).await?.result.len() as u64),
+            ApiVersion::V1Beta1 => {
+                let pagination = Pagination {
+                    count_total: true,
+                    ..Pagination::limit(1)
+                };
+                let page = self.delegations_page(key, &pagination).await?;
+                Ok(page.pagination.map(|p| p.total).unwrap_or_default())
+            }
+        }
+    }
+    /// the first `limit` delegations, a page at a time
+    async fn delegations_v1(
+        &self,
+        key: &str,
+        limit: u64,
+    ) -> Result<Vec<ValidatorDelegation>, TerraRustAPIError> {
+        let mut delegations = vec![];
+        let mut pages = Box::pin(self.delegation_pages(key, limit.min(DELEGATIONS_PAGE_LIMIT)));
+        while let Some(page) = pages.next().await {
+            delegations.extend(page?.delegation_responses);
+            if delegations.len() as u64 >= limit {
+                delegations.truncate(limit as usize);
+                break;
+            }
+        }
+        Ok(delegations)
+    }
+    /// all delegations for a given validator
+    pub async fn validator_delegations(
+        &self,
+        key: &str,
+    ) -> Result<LCDResult<Vec<ValidatorDelegation>>, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => self.delegations_legacy(key).await,
+            ApiVersion::V1Beta1 => {
+                let delegations = self.delegations_v1(key, u64::MAX).await?;
+                Ok(Staking::lcd_result(delegations, None))
+            }
+        }
+    }
+    /// all delegations for a given validator (limit) (new format)
+    pub async fn validator_delegations_limit(
+        &self,
+        key: &str,
+        limit: u64,
+    ) -> Result<ValidatorDelegationsV1Response, TerraRustAPIError> {
+        let delegations = match self.terra.api_version().await? {
+            ApiVersion::Legacy => {
+                let mut delegations = self.delegations_legacy(key).await?.result;
+                delegations.truncate(limit as usize);
+                delegations
+            }
+            ApiVersion::V1Beta1 => self.delegations_v1(key, limit).await?,
+        };
+        Ok(ValidatorDelegationsV1Response {
+            delegation_responses: delegations,
+            pagination: None,
+        })
+    }
 
     /// all unbondings for a given validator
     pub async fn validator_unbonding_delegations(
         &self,
         key: &str,
     ) -> Result<LCDResult<Vec<ValidatorUnbondingDelegation>>, TerraRustAPIError> {
-        self.terra
-            .send_cmd::<LCDResult<Vec<ValidatorUnbondingDelegation>>>(
-                &format!("/staking/validators/{}/unbonding_delegations", key),
-                None,
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => {
+                self.terra
+                    .send_cmd::<LCDResult<Vec<ValidatorUnbondingDelegation>>>(
+                        &format!("/staking/validators/{}/unbonding_delegations", key),
+                        None,
+                        None,
+                    )
+                    .await
+            }
+            ApiVersion::V1Beta1 => {
+                let response = self
+                    .terra
+                    .send_cmd::<ValidatorUnbondingDelegationsV1Response>(
+                        &format!(
+                            "/cosmos/staking/v1beta1/validators/{}/unbonding_delegations",
+                            key
+                        ),
+                        None,
+                        None,
+                    )
+                    .await?;
+                Ok(Staking::lcd_result(response.unbonding_responses, None))
+            }
+        }
+    }
+    /// all unbondings of a given delegator
+    pub async fn delegator_unbonding_delegations(
+        &self,
+        delegator: &str,
+    ) -> Result<LCDResult<Vec<ValidatorUnbondingDelegation>>, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => {
+                self.terra
+                    .send_cmd::<LCDResult<Vec<ValidatorUnbondingDelegation>>>(
+                        &format!("/staking/delegators/{}/unbonding_delegations", delegator),
+                        None,
+                        None,
+                    )
+                    .await
+            }
+            ApiVersion::V1Beta1 => {
+                let response = self
+                    .terra
+                    .send_cmd::<ValidatorUnbondingDelegationsV1Response>(
+                        &format!(
+                            "/cosmos/staking/v1beta1/delegators/{}/unbonding_delegations",
+                            delegator
+                        ),
+                        None,
+                        None,
+                    )
+                    .await?;
+                Ok(Staking::lcd_result(response.unbonding_responses, None))
+            }
+        }
+    }
+    /// when a delegator's unbondings unlock, soonest first, and how much has already unlocked as of now
+    pub async fn unbonding_schedule(
+        &self,
+        delegator: &str,
+    ) -> Result<UnbondingSchedule, TerraRustAPIError> {
+        let unbondings = self.delegator_unbonding_delegations(delegator).await?;
+        Ok(UnbondingSchedule::create(
+            delegator,
+            &unbondings.result,
+            chrono::Utc::now(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::wire_log::{Replay, WireLogEntry};
+
+    #[tokio::test]
+    pub async fn test_delegation_pages() -> anyhow::Result<()> {
+        let delegation = |delegator: &str| {
+            format!(
+                r#"{{"delegation":{{"delegator_address":"{}","validator_address":"terravaloper1x","shares":"1.0"}},"balance":{{"denom":"uluna","amount":"1"}}}}"#,
+                delegator
+            )
+        };
+        let recorded = |query: &str, response: String| WireLogEntry {
+            status: Some(200),
+            response: Some(response),
+            ..WireLogEntry::create(
+                "GET",
+                &format!(
+                    "http://lcd/cosmos/staking/v1beta1/validators/terravaloper1x/delegations?{}",
+                    query
+                ),
                 None,
             )
-            .await
+        };
+        let replay = Replay::create(vec![
+            recorded(
+                "pagination.limit=1000",
+                format!(
+                    r#"{{"delegation_responses":[{},{}],"pagination":{{"next_key":"AAE=","total":"0"}}}}"#,
+                    delegation("terra1a"),
+                    delegation("terra1b")
+                ),
+            ),
+            recorded(
+                "pagination.key=AAE%3D&pagination.limit=1000",
+                format!(
+                    r#"{{"delegation_responses":[{}],"pagination":{{"next_key":null,"total":"0"}}}}"#,
+                    delegation("terra1c")
+                ),
+            ),
+            recorded(
+                "pagination.limit=1&pagination.count_total=true",
+                format!(
+                    r#"{{"delegation_responses":[{}],"pagination":{{"next_key":"AAE=","total":"3"}}}}"#,
+                    delegation("terra1a")
+                ),
+            ),
+        ]);
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay);
+        terra.set_api_version(ApiVersion::V1Beta1);
+        let staking = terra.staking();
+
+        let pages = staking
+            .delegation_pages("terravaloper1x", DELEGATIONS_PAGE_LIMIT)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(pages.len(), 2);
+        let delegations = staking
+            .validator_delegations("terravaloper1x")
+            .await?
+            .result;
+        let delegators = delegations
+            .iter()
+            .map(|d| d.delegation.delegator_address.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(delegators, vec!["terra1a", "terra1b", "terra1c"]);
+        assert_eq!(staking.delegation_count("terravaloper1x").await?, 3);
+        Ok(())
     }
 }
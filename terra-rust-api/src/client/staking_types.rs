@@ -4,6 +4,7 @@ use crate::client::client_types::{
     terra_f64_format, // terra_opt_u64_format,
     terra_u64_format,
 };
+use crate::client::tx_types::V1Pagination;
 use crate::tendermint_types::TendermintPublicKey;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
@@ -137,4 +138,235 @@ pub struct ValidatorUnbondingDelegation {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ValidatorDelegationsV1Response {
     pub delegation_responses: Vec<ValidatorDelegation>,
+    /// where the next page starts, and with `count_total`, how many there are
+    #[serde(default)]
+    pub pagination: Option<V1Pagination>,
+}
+
+/// the consensus key, as the cosmos-sdk gateway routes give it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConsensusPubKeyV1 {
+    #[serde(rename = "@type")]
+    pub s_type: String,
+    pub key: String,
+}
+/// a validator, as the cosmos-sdk gateway routes give it. converts into [Validator]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValidatorV1 {
+    pub operator_address: String,
+    pub consensus_pubkey: ConsensusPubKeyV1,
+    pub jailed: Option<bool>,
+    /// BOND_STATUS_BONDED and friends
+    pub status: String,
+    #[serde(with = "terra_u64_format")]
+    pub tokens: u64,
+    #[serde(with = "terra_f64_format")]
+    pub delegator_shares: f64,
+    pub description: ValidatorDescription,
+    #[serde(with = "terra_datetime_format")]
+    pub unbonding_time: DateTime<Utc>,
+    pub commission: ValidatorCommission,
+    #[serde(with = "terra_u64_format")]
+    pub min_self_delegation: u64,
+}
+impl From<ValidatorV1> for Validator {
+    fn from(v: ValidatorV1) -> Self {
+        let status = match v.status.as_str() {
+            "BOND_STATUS_UNBONDED" => 1,
+            "BOND_STATUS_UNBONDING" => 2,
+            "BOND_STATUS_BONDED" => 3,
+            _ => 0,
+        };
+        Validator {
+            operator_address: v.operator_address,
+            consensus_pubkey: TendermintPublicKey {
+                s_type: "tendermint/PubKeyEd25519".into(),
+                value: v.consensus_pubkey.key,
+            },
+            jailed: v.jailed,
+            status,
+            tokens: v.tokens,
+            delegator_shares: v.delegator_shares,
+            description: v.description,
+            unbonding_time: v.unbonding_time,
+            commission: v.commission,
+            min_self_delegation: v.min_self_delegation,
+        }
+    }
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValidatorV1Response {
+    pub validator: ValidatorV1,
+}
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValidatorsV1Response {
+    pub validators: Vec<ValidatorV1>,
+}
+#[derive(Deserialize, Clone, Debug)]
+pub struct ValidatorUnbondingDelegationsV1Response {
+    pub unbonding_responses: Vec<ValidatorUnbondingDelegation>,
+}
+
+/// an unbonding entry, and the validator it is leaving
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UnbondingEntry {
+    /// validator 'oper' address
+    pub validator_address: String,
+    /// block # when unbonding happened
+    #[serde(with = "terra_u64_format")]
+    pub creation_height: u64,
+    /// when the funds unlock
+    #[serde(with = "terra_datetime_format")]
+    pub completion_time: DateTime<Utc>,
+    /// what unlocks, after any slashing
+    #[serde(with = "terra_u64_format")]
+    pub balance: u64,
+}
+
+/// all of a delegator's unbondings, in the order they complete
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UnbondingSchedule {
+    pub delegator_address: String,
+    /// entries completing by then are claimable
+    #[serde(with = "terra_datetime_format")]
+    pub as_of: DateTime<Utc>,
+    pub entries: Vec<UnbondingEntry>,
+    /// unlocked by `as_of`. the chain pays these out at the end of the block they complete in
+    #[serde(with = "terra_u64_format")]
+    pub claimable: u64,
+    /// still locked at `as_of`
+    #[serde(with = "terra_u64_format")]
+    pub pending: u64,
+}
+impl UnbondingSchedule {
+    pub fn create(
+        delegator_address: &str,
+        unbondings: &[ValidatorUnbondingDelegation],
+        as_of: DateTime<Utc>,
+    ) -> UnbondingSchedule {
+        let mut entries = unbondings
+            .iter()
+            .flat_map(|unbonding| {
+                unbonding.entries.iter().map(move |entry| UnbondingEntry {
+                    validator_address: unbonding.validator_address.clone(),
+                    creation_height: entry.creation_height,
+                    completion_time: entry.completion_time,
+                    balance: entry.balance,
+                })
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| {
+            (a.completion_time, &a.validator_address)
+                .cmp(&(b.completion_time, &b.validator_address))
+        });
+        let (claimable, pending) = entries.iter().fold((0, 0), |(claimable, pending), entry| {
+            if entry.completion_time <= as_of {
+                (claimable + entry.balance, pending)
+            } else {
+                (claimable, pending + entry.balance)
+            }
+        });
+        UnbondingSchedule {
+            delegator_address: delegator_address.into(),
+            as_of,
+            entries,
+            claimable,
+            pending,
+        }
+    }
+    /// the next entry to unlock after `as_of`
+    pub fn next_unlock(&self) -> Option<&UnbondingEntry> {
+        self.entries.iter().find(|e| e.completion_time > self.as_of)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_unbonding_schedule() -> anyhow::Result<()> {
+        let unbonding = |validator: &str, entries: &[(&str, u64)]| ValidatorUnbondingDelegation {
+            delegator_address: "terra1d".into(),
+            validator_address: validator.into(),
+            entries: entries
+                .iter()
+                .map(
+                    |(completion_time, balance)| ValidatorUnbondingDelegationEntry {
+                        creation_height: 1,
+                        completion_time: completion_time.parse().unwrap(),
+                        initial_balance: *balance,
+                        balance: *balance,
+                    },
+                )
+                .collect(),
+        };
+        let unbondings = vec![
+            unbonding(
+                "terravaloper1b",
+                &[("2022-03-20T00:00:00Z", 5), ("2022-03-01T00:00:00Z", 1)],
+            ),
+            unbonding("terravaloper1a", &[("2022-03-10T00:00:00Z", 20)]),
+        ];
+        let as_of = "2022-03-10T00:00:00Z".parse()?;
+        let schedule = UnbondingSchedule::create("terra1d", &unbondings, as_of);
+        let order = schedule
+            .entries
+            .iter()
+            .map(|e| (e.validator_address.as_str(), e.balance))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            vec![
+                ("terravaloper1b", 1),
+                ("terravaloper1a", 20),
+                ("terravaloper1b", 5)
+            ]
+        );
+        assert_eq!(schedule.claimable, 21);
+        assert_eq!(schedule.pending, 5);
+        assert_eq!(schedule.next_unlock().map(|e| e.balance), Some(5));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_validator_v1() -> anyhow::Result<()> {
+        let json = r#"{
+          "operator_address": "terravaloper1259cmu5zyklsdkmgstxhwqpe0utfe5hhyty0at",
+          "consensus_pubkey": {
+            "@type": "/cosmos.crypto.ed25519.PubKey",
+            "key": "e6zfBnTwHA9Ud4nLQ3m+LNjRzyeB/Nx5Wsf0YRTd0uQ="
+          },
+          "jailed": false,
+          "status": "BOND_STATUS_BONDED",
+          "tokens": "1028311455432",
+          "delegator_shares": "1028311455432.000000000000000000",
+          "description": {
+            "moniker": "PFC",
+            "identity": "",
+            "website": "",
+            "security_contact": "",
+            "details": ""
+          },
+          "unbonding_height": "0",
+          "unbonding_time": "1970-01-01T00:00:00Z",
+          "commission": {
+            "commission_rates": {
+              "rate": "0.050000000000000000",
+              "max_rate": "0.200000000000000000",
+              "max_change_rate": "0.010000000000000000"
+            },
+            "update_time": "2021-09-30T07:10:06.134286064Z"
+          },
+          "min_self_delegation": "1"
+        }"#;
+        let validator: Validator = serde_json::from_str::<ValidatorV1>(json)?.into();
+        assert_eq!(validator.status, 3);
+        assert_eq!(validator.tokens, 1028311455432);
+        assert_eq!(
+            validator.consensus_pubkey.value,
+            "e6zfBnTwHA9Ud4nLQ3m+LNjRzyeB/Nx5Wsf0YRTd0uQ="
+        );
+        Ok(())
+    }
 }
@@ -1,6 +1,14 @@
-use crate::client::tendermint_types::{BlockResult, ValidatorSetResult};
+use crate::client::tendermint_types::{
+    BlockResult, BlockTimeEstimate, NodeInfoResult, SyncingResult, ValidatorSetResult,
+};
+use crate::errors::TerraRustAPIError;
 use crate::errors::TerraRustAPIError::TendermintValidatorSet;
+use crate::light::LightBlock;
 use crate::{LCDResult, Terra};
+use chrono::{DateTime, Utc};
+
+/// how many blocks are averaged over when estimating block times
+pub const BLOCK_TIME_SAMPLE: u64 = 100;
 
 pub struct Tendermint<'a> {
     terra: &'a Terra,
@@ -17,6 +25,90 @@ impl Tendermint<'_> {
             .await?;
         Ok(response)
     }
+    /// check every `interval` until the chain reaches `height`, returning the latest block then.
+    /// errors, like those while the chain is halted, are logged and retried until the client's deadline,
+    /// see [Terra::with_timeout]
+    pub async fn wait_for_height(
+        &self,
+        height: u64,
+        interval: std::time::Duration,
+    ) -> Result<BlockResult, TerraRustAPIError> {
+        loop {
+            match self.blocks().await {
+                Ok(block) if block.block.header.height >= height => return Ok(block),
+                Ok(block) => log::debug!(
+                    "at height {}, waiting for {}",
+                    block.block.header.height,
+                    height
+                ),
+                Err(e) => log::info!("waiting for height {}: {}", height, e),
+            }
+            self.terra
+                .pause(interval, &format!("height {}", height))
+                .await?;
+        }
+    }
+    /// information about the node, including which chain it is on
+    pub async fn node_info(&self) -> anyhow::Result<NodeInfoResult> {
+        let response = self
+            .terra
+            .send_cmd::<NodeInfoResult>("/node_info", None, None)
+            .await?;
+        Ok(response)
+    }
+    /// is the node still catching up with the chain
+    pub async fn syncing(&self) -> anyhow::Result<SyncingResult> {
+        let response = self
+            .terra
+            .send_cmd::<SyncingResult>("/syncing", None, None)
+            .await?;
+        Ok(response)
+    }
+    /// the average block time over the last `sample` blocks
+    pub async fn block_time_estimate(&self, sample: u64) -> anyhow::Result<BlockTimeEstimate> {
+        let latest = self.blocks().await?.block.header;
+        let earlier = self
+            .blocks_at_height(latest.height.saturating_sub(sample).max(1))
+            .await?
+            .block
+            .header;
+        BlockTimeEstimate::from_headers(&earlier, &latest).ok_or_else(|| {
+            TerraRustAPIError::StdErr(format!(
+                "not enough blocks to estimate block time at height {}",
+                latest.height
+            ))
+            .into()
+        })
+    }
+    /// the height the chain should be at, at `time`, going by the recent average block time
+    pub async fn estimate_height_at(&self, time: DateTime<Utc>) -> anyhow::Result<u64> {
+        Ok(self
+            .block_time_estimate(BLOCK_TIME_SAMPLE)
+            .await?
+            .height_at(time))
+    }
+    /// when the chain should reach `height`. heights already reached give the block's actual time
+    pub async fn estimate_time_at(&self, height: u64) -> anyhow::Result<DateTime<Utc>> {
+        let estimate = self.block_time_estimate(BLOCK_TIME_SAMPLE).await?;
+        if height <= estimate.height {
+            return Ok(self.blocks_at_height(height).await?.block.header.time);
+        }
+        Ok(estimate.time_at(height))
+    }
+    /// the header at a height, the commit signing it, the validators that did so and the ones after them.
+    /// Check them with the [crate::light] functions
+    pub async fn light_block(&self, height: u64) -> anyhow::Result<LightBlock> {
+        let block = self.blocks_at_height(height).await?;
+        let next = self.blocks_at_height(height + 1).await?;
+        let validators = self.validatorsets_full_at_height(height).await?;
+        let next_validators = self.validatorsets_full_at_height(height + 1).await?;
+        Ok(LightBlock {
+            header: block.block.header,
+            commit: next.block.last_commit,
+            validators: validators.result.validators,
+            next_validators: next_validators.result.validators,
+        })
+    }
     /// get a block at a specific height
     pub async fn blocks_at_height(&self, height: u64) -> anyhow::Result<BlockResult> {
         let response = self
@@ -67,6 +159,7 @@ impl Tendermint<'_> {
         Ok(LCDResult {
             height: part_1.height,
             result: vs_combined,
+            raw: None,
         })
     }
     /// get the full validatorset at a certain height
@@ -93,6 +186,7 @@ impl Tendermint<'_> {
         Ok(LCDResult {
             height: part_1.height,
             result: vs_combined,
+            raw: None,
         })
     }
 
@@ -1,7 +1,10 @@
 use crate::client::client_types::{
     base64_encoded_format, base64_opt_encoded_format, terra_datetime_format, terra_i64_format,
-    terra_u64_format,
+    terra_opt_u64_format, terra_u64_format,
 };
+use crate::client::wasm_events::{self, WasmEvent};
+use crate::errors::TerraRustAPIError;
+use crate::tx_decode::{decode_tx, DecodedTx};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -22,8 +25,8 @@ pub struct BlockId {
 pub struct BlockHeaderVersion {
     #[serde(with = "terra_u64_format")]
     pub block: u64,
-    //  #[serde(with = "terra_u64_format")]
-    //  pub app: u64,
+    #[serde(default, with = "terra_opt_u64_format")]
+    pub app: Option<u64>,
 }
 #[derive(Deserialize, Serialize, Debug)]
 pub struct BlockHeader {
@@ -78,6 +81,18 @@ pub struct Block {
     pub evidence: BlockEvidence,
     pub last_commit: BlockCommit,
 }
+impl Block {
+    /// the block's transactions, decoded without asking the LCD. see [crate::tx_decode]
+    pub fn decoded_txs(&self) -> Vec<Result<DecodedTx, TerraRustAPIError>> {
+        self.data
+            .txs
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|tx| decode_tx(tx))
+            .collect()
+    }
+}
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct BlockResult {
@@ -105,6 +120,15 @@ impl EventType {
             .map(|attr| (attr.key.clone(), attr.value.clone()))
             .collect::<HashMap<String, Option<String>>>()
     }
+    /// the cw20/cw721/swap events in it. see [wasm_events::decode]
+    pub fn wasm_events(&self) -> Vec<WasmEvent> {
+        wasm_events::decode(
+            &self.s_type,
+            self.attributes
+                .iter()
+                .map(|attr| (attr.key.as_str(), attr.value.as_deref().unwrap_or_default())),
+        )
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -118,6 +142,15 @@ pub struct RPCTXResult {
     pub events: Vec<EventType>,
     pub codespace: String,
 }
+impl RPCTXResult {
+    /// the cw20/cw721/swap events of the transaction, in order
+    pub fn wasm_events(&self) -> Vec<WasmEvent> {
+        self.events
+            .iter()
+            .flat_map(EventType::wasm_events)
+            .collect()
+    }
+}
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct RPCPubKeyOuter {
@@ -153,6 +186,25 @@ pub struct BlockResultsResult {
     pub consensus_param_updates: Option<serde_json::Value>,
 }
 
+/// the parts of /node_info we use
+#[derive(Deserialize, Serialize, Debug)]
+pub struct NodeInfo {
+    pub id: String,
+    /// the chain id
+    pub network: String,
+    pub version: String,
+    pub moniker: String,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct NodeInfoResult {
+    pub node_info: NodeInfo,
+}
+/// is the node still catching up with the chain
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SyncingResult {
+    pub syncing: bool,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct ValidatorSetResult {
     #[serde(with = "terra_u64_format")]
@@ -174,3 +226,68 @@ pub struct Validator {
     #[serde(with = "terra_u64_format")]
     pub voting_power: u64,
 }
+/// the chain's recent pace, for converting between heights and times
+#[derive(Debug, Clone, Copy)]
+pub struct BlockTimeEstimate {
+    /// the latest block when the estimate was made
+    pub height: u64,
+    pub time: DateTime<Utc>,
+    /// average time between blocks
+    pub block_time: chrono::Duration,
+}
+impl BlockTimeEstimate {
+    /// average over the blocks between two headers
+    pub fn from_headers(earlier: &BlockHeader, latest: &BlockHeader) -> Option<BlockTimeEstimate> {
+        let blocks = latest.height.checked_sub(earlier.height)?;
+        if blocks == 0 {
+            return None;
+        }
+        Some(BlockTimeEstimate {
+            height: latest.height,
+            time: latest.time,
+            block_time: (latest.time - earlier.time) / blocks as i32,
+        })
+    }
+    /// the height the chain should be at, at `time`
+    pub fn height_at(&self, time: DateTime<Utc>) -> u64 {
+        let block_ms = self.block_time.num_milliseconds().max(1);
+        let blocks = (time - self.time).num_milliseconds() / block_ms;
+        (self.height as i64 + blocks).max(1) as u64
+    }
+    /// when the chain should reach `height`
+    pub fn time_at(&self, height: u64) -> DateTime<Utc> {
+        let blocks = height as i64 - self.height as i64;
+        self.time + chrono::Duration::milliseconds(self.block_time.num_milliseconds() * blocks)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    pub fn test_block_time_estimate() {
+        let estimate = BlockTimeEstimate {
+            height: 1000,
+            time: Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap(),
+            block_time: chrono::Duration::milliseconds(6500),
+        };
+        assert_eq!(
+            estimate.height_at(Utc.with_ymd_and_hms(2022, 1, 1, 0, 1, 5).unwrap()),
+            1010
+        );
+        assert_eq!(
+            estimate.height_at(Utc.with_ymd_and_hms(2021, 12, 31, 23, 58, 55).unwrap()),
+            990
+        );
+        assert_eq!(
+            estimate.height_at(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
+            1
+        );
+        assert_eq!(
+            estimate.time_at(1100),
+            Utc.with_ymd_and_hms(2022, 1, 1, 0, 10, 50).unwrap()
+        );
+    }
+}
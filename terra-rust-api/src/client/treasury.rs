@@ -0,0 +1,106 @@
+use crate::client::core_types::Coin;
+use crate::client::fees;
+use crate::client::treasury_types::{TaxCapV1Response, TaxRateV1Response};
+use crate::errors::TerraRustAPIError;
+use crate::{ApiVersion, LCDResult, Terra};
+use rust_decimal::Decimal;
+
+/// the stability tax charged on coins sent in a transaction. The routes follow [Terra::api_version]
+pub struct Treasury<'a> {
+    terra: &'a Terra,
+}
+impl Treasury<'_> {
+    pub fn create(terra: &'_ Terra) -> Treasury<'_> {
+        Treasury { terra }
+    }
+    /// the fraction of the coins sent that is taxed
+    pub async fn tax_rate(&self, height: Option<u64>) -> Result<Decimal, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self
+                .terra
+                .send_cmd::<LCDResult<Decimal>>("/treasury/tax_rate", None, height)
+                .await?
+                .result),
+            ApiVersion::V1Beta1 => Ok(self
+                .terra
+                .send_cmd::<TaxRateV1Response>("/terra/treasury/v1beta1/tax_rate", None, height)
+                .await?
+                .tax_rate),
+        }
+    }
+    /// the most tax that is charged on one send of the denom
+    pub async fn tax_cap(
+        &self,
+        denom: &str,
+        height: Option<u64>,
+    ) -> Result<Decimal, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self
+                .terra
+                .send_cmd::<LCDResult<Decimal>>(
+                    &format!("/treasury/tax_cap/{}", denom),
+                    None,
+                    height,
+                )
+                .await?
+                .result),
+            ApiVersion::V1Beta1 => Ok(self
+                .terra
+                .send_cmd::<TaxCapV1Response>(
+                    &format!("/terra/treasury/v1beta1/tax_caps/{}", denom),
+                    None,
+                    height,
+                )
+                .await?
+                .tax_cap),
+        }
+    }
+    /// the tax due on sending the coins. luna is not taxed
+    pub async fn tax(&self, coins: &[Coin]) -> Result<Vec<Coin>, TerraRustAPIError> {
+        self.tax_at(coins, None).await
+    }
+    /// the tax that was due on sending the coins at a height
+    pub async fn tax_at(
+        &self,
+        coins: &[Coin],
+        height: Option<u64>,
+    ) -> Result<Vec<Coin>, TerraRustAPIError> {
+        let taxed = coins
+            .iter()
+            .filter(|c| c.denom != "uluna")
+            .collect::<Vec<_>>();
+        if taxed.is_empty() {
+            return Ok(vec![]);
+        }
+        let rate = self.tax_rate(height).await?;
+        let mut tax = vec![];
+        for coin in taxed {
+            let cap = self.tax_cap(&coin.denom, height).await?;
+            if let Some(due) = Treasury::tax_due(coin, rate, cap) {
+                tax.push(due)
+            }
+        }
+        Ok(tax)
+    }
+    /// `rate` of the coin's amount, truncated as the chain does, and no more than `cap`
+    pub fn tax_due(coin: &Coin, rate: Decimal, cap: Decimal) -> Option<Coin> {
+        fees::tax_due(coin, rate, cap)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_tax_due() {
+        let rate = dec!(0.005);
+        let cap = dec!(1000000);
+        let due = Treasury::tax_due(&Coin::create("uusd", dec!(123456)), rate, cap).unwrap();
+        assert_eq!(due.amount, dec!(617));
+        let capped = Treasury::tax_due(&Coin::create("uusd", dec!(1000000000)), rate, cap).unwrap();
+        assert_eq!(capped.amount, cap);
+        assert!(Treasury::tax_due(&Coin::create("uusd", dec!(100)), rate, cap).is_none());
+    }
+}
@@ -0,0 +1,33 @@
+use crate::client::client_types::terra_decimal_format;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TaxRateV1Response {
+    #[serde(with = "terra_decimal_format")]
+    pub tax_rate: Decimal,
+}
+#[derive(Deserialize, Clone, Debug)]
+pub struct TaxCapV1Response {
+    #[serde(with = "terra_decimal_format")]
+    pub tax_cap: Decimal,
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::LCDResult;
+
+    #[test]
+    pub fn test_tax() -> anyhow::Result<()> {
+        let legacy: LCDResult<Decimal> =
+            serde_json::from_str(r#"{"height":"5","result":"0.005"}"#)?;
+        assert_eq!(legacy.result.to_string(), "0.005");
+        let rate: TaxRateV1Response =
+            serde_json::from_str(r#"{"tax_rate":"0.001350000000000000"}"#)?;
+        assert_eq!(rate.tax_rate.normalize().to_string(), "0.00135");
+        let cap: TaxCapV1Response = serde_json::from_str(r#"{"tax_cap":"1410000"}"#)?;
+        assert_eq!(cap.tax_cap.to_string(), "1410000");
+        Ok(())
+    }
+}
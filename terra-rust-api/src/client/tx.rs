@@ -3,14 +3,23 @@ use reqwest::StatusCode;
 #[allow(deprecated)]
 use crate::client::tx_types::TXResultBlock;
 use crate::client::tx_types::{
-    TXResultAsync, TXResultSync, TxEstimate, TxFeeResult, V1TXResult, V1TXSResult,
+    GasReport, TXResultAsync, TXResultSync, TxEstimate, TxFeeResult, V1TXResponse, V1TXResult,
+    V1TXSResult,
 };
 
-use crate::core_types::{Coin, StdSignMsg, StdSignature, StdTx};
+use crate::client::batch::DEFAULT_MAX_CONCURRENCY;
+use crate::client::event_query::EventQuery;
+use crate::core_types::{Coin, StdSignMsg, StdTx};
 use crate::errors::TerraRustAPIError;
-use crate::errors::TerraRustAPIError::TXNotFound;
+use crate::errors::TerraRustAPIError::{TXNotFound, TxConfirmationTimeout};
 use crate::messages::Message;
+use crate::tendermint_types::BlockResult;
 use crate::{LCDResult, Terra};
+use serde::Serialize;
+use std::time::Duration;
+
+/// how often [TX::confirmation] looks for the transaction
+const CONFIRMATION_POLL: Duration = Duration::from_secs(2);
 
 #[allow(clippy::upper_case_acronyms)]
 pub struct TX<'a> {
@@ -22,54 +31,54 @@ impl<'a> TX<'a> {
     }
     /// perform an Async submission to the blockchain. This returns the TXHash
     /// This is not guaranteed to successfully create a transaction record, due to numerous factors
-    pub async fn broadcast_async(
+    pub async fn broadcast_async<S: Serialize>(
         &self,
         std_sign_msg: &StdSignMsg,
-        sigs: &[StdSignature],
+        sigs: &[S],
     ) -> Result<TXResultAsync, TerraRustAPIError> {
-        let std_tx: StdTx = StdTx::from_StdSignMsg(std_sign_msg, sigs, "async");
+        let std_tx: StdTx<S> = StdTx::from_StdSignMsg(std_sign_msg, sigs, "async");
 
         //  let js_sig = serde_json::to_string(&std_tx)?;
         let response = self
             .terra
-            .post_cmd::<StdTx, TXResultAsync>("/txs", &std_tx)
+            .post_cmd::<StdTx<S>, TXResultAsync>("/txs", &std_tx)
             .await?;
         Ok(response)
     }
     /// perform a sync submission to the blockchain. This will return more validation logic than async
     /// but you wait. It is still not guaranteed to create a blockchain transaction
     #[allow(deprecated)]
-    pub async fn broadcast_sync(
+    pub async fn broadcast_sync<S: Serialize>(
         &self,
         std_sign_msg: &StdSignMsg,
-        sigs: &[StdSignature],
+        sigs: &[S],
     ) -> Result<TXResultSync, TerraRustAPIError> {
-        let std_tx: StdTx = StdTx::from_StdSignMsg(std_sign_msg, sigs, "sync");
+        let std_tx: StdTx<S> = StdTx::from_StdSignMsg(std_sign_msg, sigs, "sync");
         //    let js_sig = serde_json::to_string(&std_tx)?;
         log::info!("TX broadcast #messages ={}", &std_tx.tx.msg.len());
-        if self.terra.debug {
-            log::debug!("{}", serde_json::to_string(&std_tx)?);
+        if self.terra.debug_enabled() {
+            log::debug!("{}", self.terra.for_log(&serde_json::to_string(&std_tx)?));
         }
         let response = self
             .terra
-            .post_cmd::<StdTx, TXResultSync>("/txs", &std_tx)
+            .post_cmd::<StdTx<S>, TXResultSync>("/txs", &std_tx)
             .await?;
         Ok(response)
     }
     /// perform a 'blocking' submission to the blockchain. This will only return once the transaction
     /// is executed on the blockchain. This is great for debugging, but not recommended to be used otherwise
     #[allow(deprecated)]
-    pub async fn broadcast_block(
+    pub async fn broadcast_block<S: Serialize>(
         &self,
         std_sign_msg: &StdSignMsg,
-        sigs: &[StdSignature],
+        sigs: &[S],
     ) -> Result<TXResultBlock, TerraRustAPIError> {
         log::warn!("Broadcast_block is not recommended to be used in production situations");
-        let std_tx: StdTx = StdTx::from_StdSignMsg(std_sign_msg, sigs, "block");
+        let std_tx: StdTx<S> = StdTx::from_StdSignMsg(std_sign_msg, sigs, "block");
         //    let js_sig = serde_json::to_string(&std_tx)?;
         let response = self
             .terra
-            .post_cmd::<StdTx, TXResultBlock>("/txs", &std_tx)
+            .post_cmd::<StdTx<S>, TXResultBlock>("/txs", &std_tx)
             .await?;
         Ok(response)
     }
@@ -104,7 +113,7 @@ impl<'a> TX<'a> {
         &self,
         hash: &str,
         max_times: usize,
-        sleep_amount: tokio::time::Duration,
+        sleep_amount: Duration,
     ) -> Result<TXResultBlock, TerraRustAPIError> {
         let mut times = 0;
         while times < max_times {
@@ -113,9 +122,12 @@ impl<'a> TX<'a> {
 
             match tx {
                 Ok(tx_response) => return Ok(tx_response),
+                Err(
+                    e @ (TerraRustAPIError::Cancelled | TerraRustAPIError::DeadlineExceeded(_)),
+                ) => return Err(e),
                 Err(e) => {
                     times += 1;
-                    match &e {
+                    match e.without_context() {
                         TerraRustAPIError::TerraLCDResponse(statuscode, out) => {
                             if statuscode == &StatusCode::NOT_FOUND {
                                 log::debug!(
@@ -123,7 +135,7 @@ impl<'a> TX<'a> {
                                     times,
                                     sleep_amount.as_secs()
                                 );
-                                tokio::time::sleep(sleep_amount).await;
+                                self.terra.pause(sleep_amount, hash).await?;
                             } else {
                                 log::error!("Invalid Response TX: {} {}", statuscode, out);
                                 break;
@@ -144,7 +156,7 @@ impl<'a> TX<'a> {
         &self,
         hash: &str,
         max_times: usize,
-        sleep_amount: tokio::time::Duration,
+        sleep_amount: Duration,
     ) -> Result<V1TXResult, TerraRustAPIError> {
         let mut times = 0;
         while times < max_times {
@@ -152,9 +164,12 @@ impl<'a> TX<'a> {
 
             match tx {
                 Ok(tx_response) => return Ok(tx_response),
+                Err(
+                    e @ (TerraRustAPIError::Cancelled | TerraRustAPIError::DeadlineExceeded(_)),
+                ) => return Err(e),
                 Err(e) => {
                     times += 1;
-                    match &e {
+                    match e.without_context() {
                         TerraRustAPIError::TerraLCDResponse(statuscode, out) => {
                             if statuscode == &StatusCode::BAD_REQUEST {
                                 log::debug!(
@@ -162,7 +177,7 @@ impl<'a> TX<'a> {
                                     times,
                                     sleep_amount.as_secs()
                                 );
-                                tokio::time::sleep(sleep_amount).await;
+                                self.terra.pause(sleep_amount, hash).await?;
                             } else {
                                 log::error!("Invalid Response TX: {} {}", statuscode, out);
                                 break;
@@ -195,8 +210,11 @@ impl<'a> TX<'a> {
             gas_prices,
         );
 
-        if self.terra.debug {
-            log::info!("Estimate Transaction = {}", serde_json::to_string(&tx_est)?);
+        if self.terra.debug_enabled() {
+            log::info!(
+                "Estimate Transaction = {}",
+                self.terra.for_log(&serde_json::to_string(&tx_est)?)
+            );
         } else {
             log::debug!(
                 "Estimate Transaction = {:#?} #messages={}",
@@ -238,26 +256,284 @@ impl<'a> TX<'a> {
             .await?;
         Ok(resp)
     }
-    /// Get a list of transactions in a given block
-    pub async fn get_txs_in_block(
+    /// transactions matching the query, oldest first
+    pub async fn search(
         &self,
-        height: u64,
+        query: &EventQuery,
         offset: Option<u64>,
         limit: Option<u64>,
     ) -> Result<V1TXSResult, TerraRustAPIError> {
-        let resp = self
-            .terra
+        self.terra
             .send_cmd::<V1TXSResult>(
                 &format!(
-                    "/cosmos/tx/v1beta1/txs?events=tx.height={}&order_by=ORDER_BY_ASC&pagination.limit={}&pagination.offset={}",
-                    height,
+                    "/cosmos/tx/v1beta1/txs?{}&order_by=ORDER_BY_ASC&pagination.limit={}&pagination.offset={}",
+                    query.to_lcd_events()?,
                     limit.unwrap_or(100),
                     offset.unwrap_or_default()
                 ),
                 None,
-                None
+                None,
             )
+            .await
+    }
+    /// Get a list of transactions in a given block
+    pub async fn get_txs_in_block(
+        &self,
+        height: u64,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<V1TXSResult, TerraRustAPIError> {
+        self.search(&EventQuery::new().height(height), offset, limit)
+            .await
+    }
+    /// transactions sent by an account, oldest first
+    pub async fn get_txs_for_account(
+        &self,
+        account_address: &str,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<V1TXSResult, TerraRustAPIError> {
+        self.search(&EventQuery::new().sender(account_address), offset, limit)
+            .await
+    }
+    /// gas & fees of a committed transaction, with the stability tax it paid
+    pub async fn gas_report(&self, tx: &V1TXResponse) -> Result<GasReport, TerraRustAPIError> {
+        let mut report = tx.gas_report();
+        report.tax = self
+            .terra
+            .treasury()
+            .tax_at(&tx.coins_sent(), Some(tx.height))
             .await?;
-        Ok(resp)
+        Ok(report)
+    }
+    /// [TX::gas_report] of each transaction. sum them up with [crate::client::tx_types::GasSummary]
+    pub async fn gas_reports(
+        &self,
+        hashes: &[String],
+    ) -> Result<Vec<GasReport>, TerraRustAPIError> {
+        let mut batch = self.terra.batch(DEFAULT_MAX_CONCURRENCY);
+        batch.extend(hashes.iter().map(|hash| async move {
+            let tx = self.get_v1(hash).await?;
+            self.gas_report(&tx.tx_response).await
+        }));
+        batch.run().await.into_iter().collect()
+    }
+
+    async fn latest_height(&self) -> Result<u64, TerraRustAPIError> {
+        Ok(self
+            .terra
+            .send_cmd::<BlockResult>("/blocks/latest", None, None)
+            .await?
+            .block
+            .header
+            .height)
+    }
+    /// the transaction, or None if it isn't in a block yet
+    async fn find_v1(&self, hash: &str) -> Result<Option<V1TXResult>, TerraRustAPIError> {
+        match self.get_v1(hash).await {
+            Ok(tx) => Ok(Some(tx)),
+            Err(e) => match e.without_context() {
+                TerraRustAPIError::TerraLCDResponse(statuscode, _)
+                    if statuscode == &StatusCode::BAD_REQUEST
+                        || statuscode == &StatusCode::NOT_FOUND =>
+                {
+                    Ok(None)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+    /// wait for a transaction to be committed, on the websocket given to [Terra::with_websocket] if there is one.
+    /// fails with [TerraRustAPIError::TxConfirmationTimeout] if it isn't within `max_blocks` blocks
+    pub async fn confirmation(
+        &self,
+        hash: &str,
+        max_blocks: u64,
+    ) -> Result<V1TXResult, TerraRustAPIError> {
+        #[cfg(feature = "websocket")]
+        if let (Some(rpc_url), None) = (&self.terra.websocket, &self.terra.replay) {
+            return self.confirmation_ws(rpc_url, hash, max_blocks).await;
+        }
+        self.confirmation_of_any(&[hash.to_string()], max_blocks)
+            .await
+    }
+    /// wait for any of the transactions to be committed, such as ones signed at the same sequence.
+    /// fails with [TerraRustAPIError::TxConfirmationTimeout], naming the last, if none are within `max_blocks` blocks
+    pub async fn confirmation_of_any(
+        &self,
+        hashes: &[String],
+        max_blocks: u64,
+    ) -> Result<V1TXResult, TerraRustAPIError> {
+        let start = self.latest_height().await?;
+        loop {
+            for hash in hashes {
+                if let Some(tx) = self.find_v1(hash).await? {
+                    return Ok(tx);
+                }
+            }
+            if self.latest_height().await? >= start + max_blocks {
+                return Err(TxConfirmationTimeout(
+                    hashes.last().cloned().unwrap_or_default(),
+                    max_blocks,
+                ));
+            }
+            self.terra
+                .pause(CONFIRMATION_POLL, hashes.last().map_or("", |h| h.as_str()))
+                .await?;
+        }
+    }
+    /// [TX::confirmation], woken by the new block and transaction events of the tendermint RPC's websocket.
+    /// falls back to polling if the websocket can't be used
+    #[cfg(feature = "websocket")]
+    pub async fn confirmation_ws(
+        &self,
+        rpc_url: &str,
+        hash: &str,
+        max_blocks: u64,
+    ) -> Result<V1TXResult, TerraRustAPIError> {
+        match self.confirmation_events(rpc_url, hash, max_blocks).await {
+            Ok(result) => result,
+            Err(e) => {
+                log::warn!("websocket {} unusable, polling instead: {}", rpc_url, e);
+                self.confirmation_of_any(&[hash.to_string()], max_blocks)
+                    .await
+            }
+        }
+    }
+    /// the outer error is a websocket failure, the inner one the answer
+    #[cfg(feature = "websocket")]
+    async fn confirmation_events(
+        &self,
+        rpc_url: &str,
+        hash: &str,
+        max_blocks: u64,
+    ) -> Result<Result<V1TXResult, TerraRustAPIError>, tokio_tungstenite::tungstenite::Error> {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let ws_url = format!(
+            "{}/websocket",
+            rpc_url
+                .trim_end_matches('/')
+                .replacen("https://", "wss://", 1)
+                .replacen("http://", "ws://", 1)
+        );
+        let (mut socket, _) = tokio_tungstenite::connect_async(ws_url.as_str()).await?;
+        let new_block = EventQuery::new().eq("tm.event", "NewBlock");
+        let tx = EventQuery::new().eq("tm.event", "Tx").hash(hash);
+        for (id, query) in [(1, new_block), (2, tx)] {
+            let query = match query.to_query() {
+                Ok(query) => query,
+                Err(e) => return Ok(Err(e)),
+            };
+            let subscribe = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "subscribe",
+                "id": id,
+                "params": { "query": query }
+            });
+            socket.send(WsMessage::Text(subscribe.to_string())).await?;
+        }
+        // it may have been committed before we subscribed
+        match self.find_v1(hash).await {
+            Ok(Some(tx)) => return Ok(Ok(tx)),
+            Ok(None) => {}
+            Err(e) => return Ok(Err(e)),
+        }
+        let mut blocks = 0;
+        while let Some(message) = socket.next().await {
+            let text = match message? {
+                WsMessage::Text(text) => text,
+                WsMessage::Close(_) => break,
+                _ => continue,
+            };
+            let event_type = serde_json::from_str::<serde_json::Value>(&text)
+                .ok()
+                .and_then(|v| v["result"]["data"]["type"].as_str().map(String::from));
+            match event_type.as_deref() {
+                Some("tendermint/event/NewBlock") => blocks += 1,
+                Some("tendermint/event/Tx") => {}
+                _ => continue,
+            }
+            match self.find_v1(hash).await {
+                Ok(Some(tx)) => return Ok(Ok(tx)),
+                Ok(None) if blocks >= max_blocks => {
+                    return Ok(Err(TxConfirmationTimeout(hash.to_string(), max_blocks)))
+                }
+                Ok(None) => {}
+                Err(e) => return Ok(Err(e)),
+            }
+        }
+        Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use crate::client::wire_log::{Replay, WireLogEntry};
+    use crate::errors::TerraRustAPIError;
+    use crate::runtime::FnTimer;
+    use crate::Terra;
+
+    fn get(path: &str, status: u16, response: String) -> WireLogEntry {
+        WireLogEntry {
+            status: Some(status),
+            response: Some(response),
+            ..WireLogEntry::create("GET", &format!("http://lcd{}", path), None)
+        }
+    }
+    fn latest(height: u64) -> WireLogEntry {
+        let id = r#"{"hash":"H","parts":{"total":1,"hash":""}}"#;
+        get(
+            "/blocks/latest",
+            200,
+            format!(
+                r#"{{"block_id":{id},"block":{{"header":{{"version":{{"block":"11"}},"chain_id":"columbus-5","height":"{}","time":"2022-01-01T00:00:00Z","last_block_id":{id},"last_commit_hash":"","data_hash":"","validators_hash":"","next_validators_hash":"","consensus_hash":"","app_hash":"","last_results_hash":"","evidence_hash":"","proposer_address":""}},"data":{{"txs":null}},"evidence":{{}},"last_commit":{{"height":"0","round":0,"block_id":{id},"signatures":[]}}}}}}"#,
+                height,
+                id = id
+            ),
+        )
+    }
+    fn tx(status: u16) -> WireLogEntry {
+        get(
+            "/cosmos/tx/v1beta1/txs/AB",
+            status,
+            if status == 200 {
+                r#"{"tx":{"body":{"messages":[],"memo":"","timeout_height":"0","extension_options":[],"non_critical_extension_options":[]},"auth_info":{},"signatures":[]},"tx_response":{"height":"101","txhash":"AB","codespace":"","code":0,"data":"","raw_log":"","info":"","gas_wanted":"1","gas_used":"1","tx":{},"timestamp":"2022-01-02T03:04:05Z","events":null,"logs":[]}}"#.into()
+            } else {
+                r#"{"code":5,"message":"tx not found: AB","details":[]}"#.into()
+            },
+        )
+    }
+    fn replayed(entries: Vec<WireLogEntry>) -> Terra {
+        Terra::lcd_client_no_tx("http://lcd", "columbus-5")
+            .with_replay(Replay::create(entries))
+            .with_timer(FnTimer::new(|_| Box::pin(async {})))
+    }
+
+    #[tokio::test]
+    pub async fn test_confirmation() -> anyhow::Result<()> {
+        let terra = replayed(vec![latest(100), tx(404), tx(404), tx(200)]);
+        let confirmed = terra.tx().confirmation("AB", 5).await?;
+        assert_eq!(confirmed.tx_response.height, 101);
+
+        let terra = replayed(vec![latest(100), tx(404), latest(101), latest(102)]);
+        assert!(matches!(
+            terra.tx().confirmation("AB", 2).await,
+            Err(TerraRustAPIError::TxConfirmationTimeout(hash, 2)) if hash == "AB"
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    pub async fn test_confirmation_replayed() -> anyhow::Result<()> {
+        // a recording has no websocket traffic, so a replayed client polls even when given one
+        let terra = replayed(vec![latest(100), tx(200)]).with_websocket("http://127.0.0.1:9");
+        assert_eq!(
+            terra.tx().confirmation("AB", 5).await?.tx_response.height,
+            101
+        );
+        Ok(())
     }
 }
@@ -0,0 +1,875 @@
+//! Sending many transactions from one key. Callers [TxQueue::enqueue] sets of messages, and get a
+//! [TxTicket] back that completes once the transaction is committed. The [TxQueueWorker] signs them in
+//! order, each with the sequence after the last (see [crate::client::shared::SequenceManager]), so it doesn't wait
+//! for one to be committed before sending the next. At most [TxQueueOptions::max_in_flight] are waiting to be
+//! committed at a time. One the node turns away for a full mempool, or that couldn't be sent, is sent again
+//! ahead of the rest of its lane (see [TxQueueOptions::max_attempts]); any other rejection fails its ticket, and
+//! the next transaction takes its sequence.
+//!
+//! Transactions go in [TxLane]s. Whenever a lane has transactions waiting, they are sent before those of lanes
+//! with a lower priority, so oracle votes aren't stuck behind a sweep of a thousand accounts. Lanes of the
+//! same priority take turns, by weight.
+//!
+//! The worker is a future, so it runs on whichever executor it is spawned on
+//! ```
+//! # #[cfg(feature = "tokio")]
+//! # async fn demo(terra: terra_rust_api::Terra, key: terra_rust_api::PrivateKey, vote: terra_rust_api::Message, send: terra_rust_api::Message) -> anyhow::Result<()> {
+//! use terra_rust_api::client::tx_queue::{TxLane, TxQueueOptions};
+//! use terra_rust_api::core_types::Coin;
+//! use rust_decimal_macros::dec;
+//! let options = TxQueueOptions::default()
+//!     .lane(TxLane::new("oracle").priority(10).gas_price(Coin::create("uluna", dec!(0.02))))
+//!     .lane(TxLane::new("sweep").max_in_flight(2));
+//! let (queue, worker) = terra.tx_queue(&key, options);
+//! tokio::spawn(worker.run());
+//! let sweep = queue.lane("sweep")?.enqueue(vec![send], None)?;
+//! let tx = queue.lane("oracle")?.enqueue(vec![vote], None)?.await?;
+//! println!("{} in block {}", tx.tx_response.txhash, tx.tx_response.height);
+//! sweep.await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::core_types::Coin;
+use crate::client::escalation::WRONG_SEQUENCE_CODE;
+use crate::client::tx_types::V1TXResult;
+use crate::errors::TerraRustAPIError;
+use crate::{shared_secp256k1, Message, PrivateKey, Terra};
+use futures::channel::{mpsc, oneshot};
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// the lane [TxQueue::enqueue] puts transactions in, unless the queue came from [TxQueue::lane]
+pub const DEFAULT_LANE: &str = "default";
+/// the code a node turns a transaction away with when its mempool is full
+pub const MEMPOOL_FULL_CODE: usize = 20;
+
+/// how the queue sends, and when it tries again
+#[derive(Clone, Debug)]
+pub struct TxQueueOptions {
+    /// transactions broadcast and not yet committed, at most
+    pub max_in_flight: usize,
+    /// blocks to wait for a transaction to be committed. one that isn't fails with
+    /// [TerraRustAPIError::TxConfirmationTimeout], and isn't sent again, as it may still be committed
+    pub max_blocks: u64,
+    /// times a transaction is signed & sent, at most. it is only sent again when it surely didn't get in:
+    /// the node turned it away for its sequence or a full mempool, or couldn't be reached
+    /// (see [TerraRustAPIError::unsent]). one the node may have taken isn't, as it could then run twice
+    pub max_attempts: u32,
+    /// wait before sending a transaction again
+    pub backoff: Duration,
+    /// send a transaction again when it was committed and failed, rather than only when the node turned it away
+    pub retry_failed_execution: bool,
+    /// lanes besides [DEFAULT_LANE], which has priority 0 and weight 1 unless it is given here too
+    pub lanes: Vec<TxLane>,
+}
+impl Default for TxQueueOptions {
+    fn default() -> Self {
+        TxQueueOptions {
+            max_in_flight: 8,
+            max_blocks: 10,
+            max_attempts: 3,
+            backoff: Duration::from_secs(2),
+            retry_failed_execution: false,
+            lanes: vec![],
+        }
+    }
+}
+impl TxQueueOptions {
+    /// add a lane, replacing the one of the same name
+    pub fn lane(mut self, lane: TxLane) -> Self {
+        self.lanes.retain(|l| l.name != lane.name);
+        self.lanes.push(lane);
+        self
+    }
+    /// is a transaction that failed with `error` worth sending again. `committed` is whether the node took it,
+    /// and the error came from waiting on it
+    fn retry(&self, error: &TerraRustAPIError, committed: bool, attempts: u32) -> bool {
+        if attempts >= self.max_attempts {
+            return false;
+        }
+        match error.without_context() {
+            TerraRustAPIError::TxResultError(..) if committed => self.retry_failed_execution,
+            // others, like insufficient funds, would only be turned away again
+            TerraRustAPIError::TxResultError(code, ..) => {
+                *code == WRONG_SEQUENCE_CODE || *code == MEMPOOL_FULL_CODE
+            }
+            e => !committed && e.unsent(),
+        }
+    }
+    /// every lane, the default one first
+    fn all_lanes(&self) -> Vec<TxLane> {
+        let mut lanes = self.lanes.clone();
+        if !lanes.iter().any(|l| l.name == DEFAULT_LANE) {
+            lanes.insert(0, TxLane::new(DEFAULT_LANE));
+        }
+        lanes
+    }
+}
+
+/// a class of transactions in the queue
+#[derive(Clone, Debug)]
+pub struct TxLane {
+    pub name: String,
+    /// transactions waiting in a lane are sent before those in lanes with a lower priority
+    pub priority: u8,
+    /// turns taken against the other lanes of the same priority. twice the weight, twice as many sent
+    pub weight: u32,
+    /// transactions of this lane in flight, at most. capping bulk lanes leaves room for urgent ones,
+    /// which would otherwise wait for a bulk transaction to be committed before they are sent
+    pub max_in_flight: Option<usize>,
+    /// the gas price this lane's transactions pay, rather than the client's
+    pub gas_price: Option<Coin>,
+}
+impl TxLane {
+    /// a lane of priority 0 and weight 1
+    pub fn new<S: Into<String>>(name: S) -> TxLane {
+        TxLane {
+            name: name.into(),
+            priority: 0,
+            weight: 1,
+            max_in_flight: None,
+            gas_price: None,
+        }
+    }
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+    pub fn weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight);
+        self
+    }
+    pub fn gas_price(mut self, gas_price: Coin) -> Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+}
+
+struct Job {
+    lane: usize,
+    messages: Vec<Message>,
+    memo: Option<String>,
+    attempts: u32,
+    done: oneshot::Sender<Result<V1TXResult, TerraRustAPIError>>,
+}
+
+/// what became of a transaction in flight, or one waiting to be sent again
+enum Outcome {
+    Committed(Job, Box<Result<V1TXResult, TerraRustAPIError>>),
+    Retry(Job),
+}
+
+struct LaneState {
+    lane: TxLane,
+    pending: VecDeque<Job>,
+    in_flight: usize,
+    /// jobs waiting to be sent again. the lane holds off until they are, so its transactions keep their order
+    retrying: usize,
+    /// turns owed, for the weighted round robin between lanes of the same priority
+    credit: i64,
+}
+impl LaneState {
+    /// has a job waiting, and room to send it
+    fn ready(&self) -> bool {
+        !self.pending.is_empty()
+            && self.retrying == 0
+            && self.in_flight < self.lane.max_in_flight.unwrap_or(usize::MAX).max(1)
+    }
+}
+
+/// which job goes next
+struct Scheduler {
+    lanes: Vec<LaneState>,
+}
+impl Scheduler {
+    fn create(lanes: Vec<TxLane>) -> Scheduler {
+        Scheduler {
+            lanes: lanes
+                .into_iter()
+                .map(|lane| LaneState {
+                    lane,
+                    pending: VecDeque::new(),
+                    in_flight: 0,
+                    retrying: 0,
+                    credit: 0,
+                })
+                .collect(),
+        }
+    }
+    fn push(&mut self, job: Job) {
+        self.lanes[job.lane].pending.push_back(job)
+    }
+    /// back at the head of its lane, to be sent again
+    fn push_front(&mut self, job: Job) {
+        self.lanes[job.lane].pending.push_front(job)
+    }
+    /// the next job of the highest priority lane that can send one. each ready lane of that priority gains its
+    /// weight in credit, and the one with the most goes, paying back what they all gained
+    fn next(&mut self) -> Option<Job> {
+        let priority = self
+            .lanes
+            .iter()
+            .filter(|l| l.ready())
+            .map(|l| l.lane.priority)
+            .max()?;
+        let mut total = 0;
+        let mut chosen: Option<usize> = None;
+        for i in 0..self.lanes.len() {
+            let state = &mut self.lanes[i];
+            if !state.ready() || state.lane.priority != priority {
+                continue;
+            }
+            let weight = i64::from(state.lane.weight.max(1));
+            state.credit += weight;
+            total += weight;
+            if !matches!(chosen, Some(c) if self.lanes[c].credit >= self.lanes[i].credit) {
+                chosen = Some(i);
+            }
+        }
+        let state = &mut self.lanes[chosen?];
+        state.credit -= total;
+        state.pending.pop_front()
+    }
+    fn started(&mut self, lane: usize) {
+        self.lanes[lane].in_flight += 1;
+    }
+    fn finished(&mut self, lane: usize) {
+        self.lanes[lane].in_flight = self.lanes[lane].in_flight.saturating_sub(1);
+    }
+}
+
+/// where transactions are queued. clones queue into the same worker
+#[derive(Clone)]
+pub struct TxQueue {
+    sender: mpsc::UnboundedSender<Job>,
+    lanes: Arc<Vec<String>>,
+    lane: usize,
+}
+impl TxQueue {
+    /// the same queue, putting transactions in the lane called `name`
+    pub fn lane(&self, name: &str) -> Result<TxQueue, TerraRustAPIError> {
+        let lane = self
+            .lanes
+            .iter()
+            .position(|l| l == name)
+            .ok_or_else(|| TerraRustAPIError::UnknownLane(name.into()))?;
+        Ok(TxQueue {
+            lane,
+            ..self.clone()
+        })
+    }
+    /// sign & send the messages after those already queued in this lane.
+    /// fails with [TerraRustAPIError::Cancelled] if the worker has stopped
+    pub fn enqueue(
+        &self,
+        messages: Vec<Message>,
+        memo: Option<String>,
+    ) -> Result<TxTicket, TerraRustAPIError> {
+        let (done, receiver) = oneshot::channel();
+        self.sender
+            .unbounded_send(Job {
+                lane: self.lane,
+                messages,
+                memo,
+                attempts: 0,
+                done,
+            })
+            .map_err(|_| TerraRustAPIError::Cancelled)?;
+        Ok(TxTicket { receiver })
+    }
+}
+
+/// completes with the committed transaction, or why it couldn't be.
+/// [TerraRustAPIError::Cancelled] if the worker stopped first
+pub struct TxTicket {
+    receiver: oneshot::Receiver<Result<V1TXResult, TerraRustAPIError>>,
+}
+impl Future for TxTicket {
+    type Output = Result<V1TXResult, TerraRustAPIError>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver
+            .poll_unpin(cx)
+            .map(|result| result.unwrap_or(Err(TerraRustAPIError::Cancelled)))
+    }
+}
+
+/// signs, sends and tracks what is queued. [TxQueueWorker::run] it on an executor
+pub struct TxQueueWorker {
+    terra: Terra,
+    key: PrivateKey,
+    options: TxQueueOptions,
+    receiver: mpsc::UnboundedReceiver<Job>,
+    scheduler: Scheduler,
+    /// transactions the node took, not yet committed
+    waiting: usize,
+    /// a transaction was turned away for its sequence, so the account's is fetched from the chain again
+    resync: bool,
+}
+impl TxQueueWorker {
+    pub(crate) fn create(
+        terra: &Terra,
+        key: &PrivateKey,
+        options: TxQueueOptions,
+    ) -> (TxQueue, TxQueueWorker) {
+        let (sender, receiver) = mpsc::unbounded();
+        let lanes = options.all_lanes();
+        let queue = TxQueue {
+            sender,
+            lanes: Arc::new(lanes.iter().map(|l| l.name.clone()).collect()),
+            lane: lanes
+                .iter()
+                .position(|l| l.name == DEFAULT_LANE)
+                .unwrap_or(0),
+        };
+        let worker = TxQueueWorker {
+            terra: terra.clone(),
+            key: key.clone(),
+            options,
+            receiver,
+            scheduler: Scheduler::create(lanes),
+            waiting: 0,
+            resync: false,
+        };
+        (queue, worker)
+    }
+    /// send what is queued until every [TxQueue] is dropped and all that was queued is done
+    pub async fn run(mut self) {
+        let mut in_flight: FuturesUnordered<BoxFuture<'static, Outcome>> = FuturesUnordered::new();
+        let mut open = true;
+        loop {
+            while in_flight.len() < self.options.max_in_flight.max(1) && !self.resyncing() {
+                let job = match self.scheduler.next() {
+                    Some(job) => job,
+                    None => break,
+                };
+                let lane = job.lane;
+                if let Some(waiting) = self.send(job).await {
+                    self.scheduler.started(lane);
+                    in_flight.push(waiting);
+                }
+            }
+            if in_flight.is_empty() && !open {
+                return;
+            }
+            if in_flight.is_empty() {
+                match self.receiver.next().await {
+                    Some(job) => self.scheduler.push(job),
+                    None => open = false,
+                }
+                continue;
+            }
+            let next = if open {
+                futures::select! {
+                    job = self.receiver.next() => {
+                        match job {
+                            Some(job) => self.scheduler.push(job),
+                            None => open = false,
+                        }
+                        None
+                    }
+                    outcome = in_flight.select_next_some() => Some(outcome),
+                }
+            } else {
+                in_flight.next().await
+            };
+            match next {
+                Some(Outcome::Retry(job)) => {
+                    self.scheduler.finished(job.lane);
+                    self.scheduler.lanes[job.lane].retrying -= 1;
+                    self.scheduler.push_front(job)
+                }
+                Some(Outcome::Committed(job, result)) => {
+                    let lane = job.lane;
+                    self.waiting -= 1;
+                    self.scheduler.finished(lane);
+                    if let Some(waiting) = self.finish(job, *result, true) {
+                        self.scheduler.started(lane);
+                        in_flight.push(waiting)
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+    /// holding off sending. after a transaction is turned away for its sequence, the next is signed with the
+    /// chain's, which is only right once those the node took before it are committed
+    fn resyncing(&mut self) -> bool {
+        if self.waiting == 0 {
+            self.resync = false;
+        }
+        self.resync
+    }
+    /// the client to sign the lane's transactions with, paying its gas price
+    fn client_for(&self, lane: usize) -> Result<Terra, TerraRustAPIError> {
+        let mut terra = self.terra.clone();
+        if let Some(price) = &self.scheduler.lanes[lane].lane.gas_price {
+            let options = terra
+                .gas_options
+                .as_ref()
+                .ok_or(TerraRustAPIError::NoGasOpts)?;
+            terra.gas_options = Some(options.at_gas_price(price.clone())?);
+        }
+        Ok(terra)
+    }
+    /// sign & broadcast the job, returning what to wait on
+    async fn send(&mut self, mut job: Job) -> Option<BoxFuture<'static, Outcome>> {
+        job.attempts += 1;
+        let sent = match self.client_for(job.lane) {
+            Ok(terra) => {
+                terra
+                    .submit_transaction_sync(
+                        shared_secp256k1(),
+                        &self.key,
+                        job.messages.clone(),
+                        job.memo.clone(),
+                    )
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match sent {
+            Ok(resp) => {
+                self.waiting += 1;
+                let terra = self.terra.clone();
+                let max_blocks = self.options.max_blocks;
+                Some(
+                    async move {
+                        let committed =
+                            match terra.tx().confirmation(&resp.txhash, max_blocks).await {
+                                Ok(tx) => terra.confirmed(tx).await,
+                                Err(e) => Err(e),
+                            };
+                        Outcome::Committed(job, Box::new(committed))
+                    }
+                    .boxed(),
+                )
+            }
+            Err(e) => {
+                if matches!(e.without_context(), TerraRustAPIError::TxResultError(code, ..) if *code == WRONG_SEQUENCE_CODE)
+                {
+                    self.resync = true;
+                }
+                self.finish(job, Err(e), false)
+            }
+        }
+    }
+    /// hand the result to the ticket, or come back to the job after the backoff
+    fn finish(
+        &mut self,
+        job: Job,
+        result: Result<V1TXResult, TerraRustAPIError>,
+        committed: bool,
+    ) -> Option<BoxFuture<'static, Outcome>> {
+        match result {
+            Err(e) if self.options.retry(&e, committed, job.attempts) => {
+                log::warn!(
+                    "sending again after attempt {} of {}: {}",
+                    job.attempts,
+                    self.options.max_attempts,
+                    e
+                );
+                self.scheduler.lanes[job.lane].retrying += 1;
+                let timer = self.terra.timer();
+                let backoff = self.options.backoff;
+                Some(
+                    async move {
+                        timer.sleep(backoff).await;
+                        Outcome::Retry(job)
+                    }
+                    .boxed(),
+                )
+            }
+            result => {
+                // the caller may have stopped waiting
+                let _ = job.done.send(result);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::auth_types::AuthAccount;
+    use crate::client::core_types::StdTx;
+    use crate::client::wire_log::{Replay, WireLogEntry};
+    use crate::messages::MsgSend;
+    use crate::notify::{CallbackNotifier, TxEvent};
+    use crate::runtime::FnTimer;
+    use crate::GasOptions;
+    use reqwest::StatusCode;
+    use rust_decimal::Decimal;
+
+    #[test]
+    pub fn test_retry() {
+        let options = TxQueueOptions::default();
+        let rejected =
+            TerraRustAPIError::TxResultError(32, "TX".into(), "sequence mismatch".into());
+        assert!(options.retry(&rejected, false, 1));
+        assert!(!options.retry(&rejected, false, 3));
+        assert!(!options.retry(&rejected, true, 1));
+        assert!(TxQueueOptions {
+            retry_failed_execution: true,
+            ..TxQueueOptions::default()
+        }
+        .retry(&rejected, true, 1));
+        let full = TerraRustAPIError::TxResultError(20, "TX".into(), "mempool is full".into());
+        assert!(options.retry(&full, false, 1));
+        let broke = TerraRustAPIError::TxResultError(5, "TX".into(), "insufficient funds".into());
+        assert!(!options.retry(&broke, false, 1));
+        let down = TerraRustAPIError::TerraLCDResponse(StatusCode::SERVICE_UNAVAILABLE, "".into());
+        assert!(options.retry(&down, false, 1));
+        assert!(!options.retry(&down, true, 1));
+        // the LCD may have passed it on before failing
+        let failed = TerraRustAPIError::TerraLCDResponse(StatusCode::BAD_GATEWAY, "".into());
+        assert!(!options.retry(&failed, false, 1));
+        let timeout = TerraRustAPIError::TxConfirmationTimeout("TX".into(), 10);
+        assert!(!options.retry(&timeout, true, 1));
+    }
+
+    #[tokio::test]
+    pub async fn test_retry_unsent() -> anyhow::Result<()> {
+        let options = TxQueueOptions::default();
+        // nothing listening
+        let closed = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+        let refused = reqwest::get(format!("http://{}/txs", closed))
+            .await
+            .unwrap_err();
+        assert!(options.retry(&refused.into(), false, 1));
+        // taken, and never answered
+        let silent = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let timed_out = reqwest::Client::new()
+            .post(format!("http://{}/txs", silent.local_addr()?))
+            .timeout(Duration::from_millis(50))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(!options.retry(&timed_out.into(), false, 1));
+        Ok(())
+    }
+
+    fn job(lane: usize, memo: &str) -> Job {
+        Job {
+            lane,
+            messages: vec![],
+            memo: Some(memo.into()),
+            attempts: 0,
+            done: oneshot::channel().0,
+        }
+    }
+
+    #[test]
+    pub fn test_scheduler() {
+        let options = TxQueueOptions::default()
+            .lane(TxLane::new("oracle").priority(10))
+            .lane(TxLane::new("sweep").weight(2).max_in_flight(3));
+        let lanes = options.all_lanes();
+        assert_eq!(lanes[0].name, DEFAULT_LANE);
+        let mut scheduler = Scheduler::create(lanes);
+        for i in 0..4 {
+            scheduler.push(job(0, &format!("d{}", i)));
+            scheduler.push(job(2, &format!("s{}", i)));
+        }
+        scheduler.push(job(1, "o0"));
+        let mut sent = vec![];
+        while let Some(job) = scheduler.next() {
+            scheduler.started(job.lane);
+            sent.push(job.memo.unwrap());
+        }
+        // the oracle first, then two sweeps for each default, until the sweeps are capped
+        assert_eq!(sent, vec!["o0", "s0", "d0", "s1", "s2", "d1", "d2", "d3"]);
+        scheduler.finished(2);
+        scheduler.push(job(1, "o1"));
+        assert_eq!(scheduler.next().unwrap().memo.unwrap(), "o1");
+        assert_eq!(scheduler.next().unwrap().memo.unwrap(), "s3");
+        assert!(scheduler.next().is_none());
+    }
+
+    #[test]
+    pub fn test_lane_gas_price() -> anyhow::Result<()> {
+        let gas = GasOptions::builder()
+            .gas_price("0.15uusd")
+            .max_fee("5000000uusd")
+            .build()?;
+        let terra = Terra::lcd_client("http://lcd", "columbus-5", &gas, None);
+        let key = PrivateKey::from_words(shared_secp256k1(), MNEMONIC, 0, 0)?;
+        let options = TxQueueOptions::default()
+            .lane(TxLane::new("oracle").gas_price(Coin::create("uusd", Decimal::new(2, 1))))
+            .lane(TxLane::new("sweep").gas_price(Coin::create("uluna", Decimal::new(2, 2))));
+        let (_queue, worker) = terra.tx_queue(&key, options);
+        let lane = |name: &str| {
+            worker
+                .scheduler
+                .lanes
+                .iter()
+                .position(|l| l.lane.name == name)
+                .unwrap()
+        };
+        let oracle = worker.client_for(lane("oracle"))?;
+        assert_eq!(
+            oracle.gas_options.unwrap().gas_price().unwrap().to_string(),
+            "0.2uusd"
+        );
+        // a uusd limit wouldn't be checked against a fee in uluna
+        assert!(matches!(
+            worker.client_for(lane("sweep")),
+            Err(TerraRustAPIError::InvalidGasOptions(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_stopped() {
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5");
+        let key = PrivateKey::from_words(
+            shared_secp256k1(),
+            "notice oak worry limit wrap speak medal online prefer cluster roof addict wrist behave treat actual wasp year salad speed social layer crew genius",
+            0,
+            0,
+        )
+        .unwrap();
+        let options = TxQueueOptions::default().lane(TxLane::new("oracle").priority(10));
+        let (queue, worker) = terra.tx_queue(&key, options);
+        assert!(queue.lane("oracle").is_ok());
+        assert!(matches!(
+            queue.lane("sweep"),
+            Err(TerraRustAPIError::UnknownLane(_))
+        ));
+        let ticket = queue.enqueue(vec![], None).unwrap();
+        drop(worker);
+        assert!(matches!(
+            futures::executor::block_on(ticket),
+            Err(TerraRustAPIError::Cancelled)
+        ));
+        assert!(queue.enqueue(vec![], None).is_err());
+    }
+
+    const MNEMONIC: &str = "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut";
+
+    fn sends(from: &str, amounts: u32) -> anyhow::Result<Vec<Vec<Message>>> {
+        Ok((1..=amounts)
+            .map(|amount| {
+                MsgSend::create_single(
+                    from.to_string(),
+                    "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh".to_string(),
+                    Coin::create("uluna", Decimal::from(amount)),
+                )
+                .map(|send| vec![send])
+            })
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+    fn get(url: String, status: u16, response: String) -> WireLogEntry {
+        WireLogEntry {
+            status: Some(status),
+            response: Some(response),
+            ..WireLogEntry::create("GET", &url, None)
+        }
+    }
+    /// the account at sequence 7, and the chain at height 100
+    fn chain(from: &str) -> Vec<WireLogEntry> {
+        let id = r#"{"hash":"H","parts":{"total":1,"hash":""}}"#;
+        vec![
+            get(
+                format!("http://lcd/auth/accounts/{}", from),
+                200,
+                format!(
+                    r#"{{"height":"5","result":{{"type":"core/Account","value":{{"address":"{}","public_key":null,"account_number":"42","sequence":"7"}}}}}}"#,
+                    from
+                ),
+            ),
+            get(
+                "http://lcd/blocks/latest".into(),
+                200,
+                format!(
+                    r#"{{"block_id":{id},"block":{{"header":{{"version":{{"block":"11"}},"chain_id":"columbus-5","height":"100","time":"2022-01-01T00:00:00Z","last_block_id":{id},"last_commit_hash":"","data_hash":"","validators_hash":"","next_validators_hash":"","consensus_hash":"","app_hash":"","last_results_hash":"","evidence_hash":"","proposer_address":""}},"data":{{"txs":null}},"evidence":{{}},"last_commit":{{"height":"0","round":0,"block_id":{id},"signatures":[]}}}}}}"#,
+                    id = id
+                ),
+            ),
+        ]
+    }
+    /// the node's answer to the messages signed at `sequence`
+    async fn broadcast(
+        key: &PrivateKey,
+        from: &str,
+        sequence: u64,
+        messages: &[Message],
+        response: String,
+    ) -> anyhow::Result<WireLogEntry> {
+        let gas = GasOptions::create_with_fees("30000uluna", 200000)?;
+        let account = AuthAccount {
+            address: from.to_string(),
+            public_key: None,
+            account_number: 42,
+            sequence: Some(sequence),
+        };
+        let (std_sign_msg, sigs) = Terra::lcd_client("http://lcd", "columbus-5", &gas, None)
+            .sign_as(shared_secp256k1(), key, &account, messages.to_vec(), None)
+            .await?;
+        let body = serde_json::to_string(&StdTx::from_StdSignMsg(&std_sign_msg, &sigs, "sync"))?;
+        Ok(WireLogEntry {
+            status: Some(200),
+            response: Some(response),
+            ..WireLogEntry::create("POST", "http://lcd/txs", Some(&body))
+        })
+    }
+    fn accepted(txhash: &str) -> String {
+        format!(r#"{{"txhash":"{}","raw_log":"[]"}}"#, txhash)
+    }
+    fn committed(txhash: &str) -> WireLogEntry {
+        get(
+            format!("http://lcd/cosmos/tx/v1beta1/txs/{}", txhash),
+            200,
+            format!(
+                r#"{{"tx":{{"body":{{"messages":[],"memo":"","timeout_height":"0","extension_options":[],"non_critical_extension_options":[]}},"auth_info":{{}},"signatures":[]}},"tx_response":{{"height":"101","txhash":"{}","codespace":"","code":0,"data":"","raw_log":"","info":"","gas_wanted":"1","gas_used":"1","tx":{{}},"timestamp":"2022-01-02T03:04:05Z","events":null,"logs":[]}}}}"#,
+                txhash
+            ),
+        )
+    }
+    fn not_yet(txhash: &str) -> WireLogEntry {
+        get(
+            format!("http://lcd/cosmos/tx/v1beta1/txs/{}", txhash),
+            404,
+            r#"{"code":5,"message":"tx not found","details":[]}"#.into(),
+        )
+    }
+    /// a client answering from `entries`, logging what became of each transaction
+    fn replayed(
+        entries: Vec<WireLogEntry>,
+        pause: Duration,
+    ) -> (Terra, Arc<std::sync::Mutex<Vec<String>>>) {
+        let gas = GasOptions::create_with_fees("30000uluna", 200000).unwrap();
+        let events = Arc::new(std::sync::Mutex::new(vec![]));
+        let logged = events.clone();
+        let terra = Terra::lcd_client("http://lcd", "columbus-5", &gas, None)
+            .with_replay(Replay::create(entries))
+            .with_timer(FnTimer::new(move |_| Box::pin(tokio::time::sleep(pause))))
+            .with_notifier(CallbackNotifier::create(move |event: TxEvent| {
+                let line = match event {
+                    TxEvent::Broadcasted { txhash, .. } => format!("sent {}", txhash),
+                    TxEvent::Included { txhash, .. } => format!("included {}", txhash),
+                    TxEvent::Failed { txhash, .. } => format!("failed {}", txhash),
+                };
+                logged.lock().unwrap().push(line);
+                Box::pin(async { Ok(()) })
+            }));
+        (terra, events)
+    }
+
+    #[tokio::test]
+    pub async fn test_in_flight() -> anyhow::Result<()> {
+        let key = PrivateKey::from_words(shared_secp256k1(), MNEMONIC, 0, 0)?;
+        let from = key.public_key(shared_secp256k1()).account()?;
+        let sends = sends(&from, 3)?;
+        let mut entries = chain(&from);
+        for (sequence, send) in (7..).zip(&sends) {
+            let txhash = format!("TX{}", sequence);
+            entries.push(broadcast(&key, &from, sequence, send, accepted(&txhash)).await?);
+            // a poll before each is in a block
+            entries.push(not_yet(&txhash));
+            entries.push(committed(&txhash));
+        }
+        let (terra, events) = replayed(entries, Duration::from_millis(50));
+        let options = TxQueueOptions {
+            max_in_flight: 2,
+            ..TxQueueOptions::default()
+        };
+        let (queue, worker) = terra.tx_queue(&key, options);
+        let tickets = sends
+            .into_iter()
+            .map(|send| queue.enqueue(send, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(queue);
+        worker.run().await;
+
+        // each at the sequence after the last, in the order they were queued
+        let mut committed = vec![];
+        for ticket in tickets {
+            committed.push(ticket.await?.tx_response.txhash);
+        }
+        assert_eq!(committed, vec!["TX7", "TX8", "TX9"]);
+        let events = events.lock().unwrap();
+        assert_eq!(events[..2], ["sent TX7", "sent TX8"]);
+        // the third waits for one of the first two to be committed
+        let mut in_flight = 0;
+        for event in events.iter() {
+            if event.starts_with("sent") {
+                in_flight += 1;
+            } else if event.starts_with("included") {
+                in_flight -= 1;
+            }
+            assert!(in_flight <= 2, "{:?}", events);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_queue_retry() -> anyhow::Result<()> {
+        let key = PrivateKey::from_words(shared_secp256k1(), MNEMONIC, 0, 0)?;
+        let from = key.public_key(shared_secp256k1()).account()?;
+        let sends = sends(&from, 4)?;
+        let mut entries = chain(&from);
+        entries.push(broadcast(&key, &from, 7, &sends[0], accepted("TX7")).await?);
+        // turned away for good, leaving sequence 8 unused while TX7 is still in flight
+        let broke = r#"{"txhash":"TXB","code":5,"raw_log":"insufficient funds"}"#;
+        entries.push(broadcast(&key, &from, 8, &sends[1], broke.into()).await?);
+        // turned away once, then taken at the same sequence
+        let full = r#"{"txhash":"TX8","code":20,"raw_log":"mempool is full"}"#;
+        entries.push(broadcast(&key, &from, 8, &sends[2], full.into()).await?);
+        entries.push(broadcast(&key, &from, 8, &sends[2], accepted("TX8")).await?);
+        entries.push(broadcast(&key, &from, 9, &sends[3], accepted("TX9")).await?);
+        entries.extend(["TX7", "TX8", "TX9"].iter().map(|txhash| committed(txhash)));
+        let (terra, events) = replayed(entries, Duration::ZERO);
+        let (queue, worker) = terra.tx_queue(&key, TxQueueOptions::default());
+        let tickets = sends
+            .into_iter()
+            .map(|send| queue.enqueue(send, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(queue);
+        worker.run().await;
+
+        let mut results = vec![];
+        for ticket in tickets {
+            results.push(ticket.await.map(|tx| tx.tx_response.txhash));
+        }
+        assert_eq!(results[0].as_deref().ok(), Some("TX7"));
+        assert!(matches!(
+            &results[1],
+            Err(TerraRustAPIError::TxResultError(5, txhash, _)) if txhash == "TXB"
+        ));
+        // the retry goes ahead of the one queued after it
+        assert_eq!(results[2].as_deref().ok(), Some("TX8"));
+        assert_eq!(results[3].as_deref().ok(), Some("TX9"));
+        let sent = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| !e.starts_with("included"))
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sent,
+            vec![
+                "sent TX7",
+                "failed TXB",
+                "failed TX8",
+                "sent TX8",
+                "sent TX9"
+            ]
+        );
+        assert_eq!(terra.inner.sequences.peek(&from, 7), 10);
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use crate::client::client_types::{terra_datetime_format, terra_f64_format, terra_u64_format};
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 
+use crate::client::wasm_events::{self, WasmEvent};
 use crate::core_types::Coin;
 use crate::messages::Message;
 use serde::{Deserialize, Serialize};
@@ -66,6 +68,15 @@ impl TxResultBlockEvent {
             .first()
             .map(|attr| attr.value.clone().unwrap_or_default())
     }
+    /// the cw20/cw721/swap events in it. see [wasm_events::decode]
+    pub fn wasm_events(&self) -> Vec<WasmEvent> {
+        wasm_events::decode(
+            &self.s_type,
+            self.attributes
+                .iter()
+                .map(|attr| (attr.key.as_str(), attr.value.as_deref().unwrap_or_default())),
+        )
+    }
 }
 #[derive(Deserialize, Clone, Serialize, Debug)]
 pub struct TxResultBlockMsg {
@@ -242,7 +253,7 @@ pub struct V1TX {
     pub auth_info: serde_json::Value,
     pub signatures: Vec<serde_json::Value>,
 }
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct V1TXResponse {
     #[serde(with = "terra_u64_format")]
     pub height: u64,
@@ -302,6 +313,15 @@ impl V1TXResponse {
 
         response
     }
+    /// the cw20/cw721/swap events of all its messages, in order
+    pub fn wasm_events(&self) -> Vec<WasmEvent> {
+        self.logs
+            .iter()
+            .flatten()
+            .flat_map(|log| log.events.iter())
+            .flat_map(TxResultBlockEvent::wasm_events)
+            .collect()
+    }
     /// get the list of event types from a TX record
     pub fn get_events(&self, event_type: &str) -> Vec<TxResultBlockEvent> {
         let mut response: Vec<TxResultBlockEvent> = Default::default();
@@ -322,7 +342,128 @@ impl V1TXResponse {
         response
     }
 }
-#[derive(Deserialize, Serialize, Debug)]
+/// the message fields holding coins sent along with it, by message type
+const COINS_SENT: [(&str, &str); 3] = [
+    ("/cosmos.bank.v1beta1.MsgSend", "amount"),
+    ("/terra.wasm.v1beta1.MsgExecuteContract", "coins"),
+    ("/terra.wasm.v1beta1.MsgInstantiateContract", "init_coins"),
+];
+
+impl V1TXResponse {
+    /// the fee the transaction paid
+    pub fn fee(&self) -> Vec<Coin> {
+        serde_json::from_value(self.tx["auth_info"]["fee"]["amount"].clone()).unwrap_or_default()
+    }
+    /// the coins the transaction's messages sent, which the stability tax is charged on
+    pub fn coins_sent(&self) -> Vec<Coin> {
+        let messages = self.tx["body"]["messages"].as_array().cloned();
+        messages
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|msg| {
+                COINS_SENT
+                    .iter()
+                    .filter(|(s_type, _)| msg["@type"] == *s_type)
+                    .flat_map(|(_, field)| {
+                        serde_json::from_value::<Vec<Coin>>(msg[*field].clone()).unwrap_or_default()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+    /// gas & fees of the transaction. the tax is left empty, as it isn't recorded in the transaction.
+    /// [crate::client::tx::TX::gas_report] works it out
+    pub fn gas_report(&self) -> GasReport {
+        GasReport {
+            txhash: self.txhash.clone(),
+            height: self.height,
+            success: self.code == 0,
+            gas_wanted: self.gas_wanted,
+            gas_used: self.gas_used,
+            fee: self.fee(),
+            tax: vec![],
+        }
+    }
+}
+
+/// add `coins` to `total`, denom by denom
+fn add_coins(total: &mut Vec<Coin>, coins: &[Coin]) {
+    for coin in coins {
+        match total.iter_mut().find(|t| t.denom == coin.denom) {
+            Some(t) => t.amount += coin.amount,
+            None => total.push(coin.clone()),
+        }
+    }
+}
+
+/// what a committed transaction cost
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GasReport {
+    pub txhash: String,
+    pub height: u64,
+    pub success: bool,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+    /// everything paid, tax included
+    pub fee: Vec<Coin>,
+    /// the stability tax part of the fee
+    pub tax: Vec<Coin>,
+}
+impl GasReport {
+    /// the fee less the tax, per unit of gas wanted. the chain charges for the gas asked for, not the gas used
+    pub fn gas_price(&self) -> Vec<Coin> {
+        if self.gas_wanted == 0 {
+            return vec![];
+        }
+        self.fee
+            .iter()
+            .map(|fee| {
+                let tax = self
+                    .tax
+                    .iter()
+                    .filter(|t| t.denom == fee.denom)
+                    .map(|t| t.amount)
+                    .sum::<Decimal>();
+                Coin::create(
+                    &fee.denom,
+                    ((fee.amount - tax) / Decimal::from(self.gas_wanted)).normalize(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// gas & fees over a set of transactions
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GasSummary {
+    pub txs: usize,
+    pub failed: usize,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+    pub fee: Vec<Coin>,
+    pub tax: Vec<Coin>,
+}
+impl GasSummary {
+    pub fn add(&mut self, report: &GasReport) {
+        self.txs += 1;
+        if !report.success {
+            self.failed += 1;
+        }
+        self.gas_wanted += report.gas_wanted;
+        self.gas_used += report.gas_used;
+        add_coins(&mut self.fee, &report.fee);
+        add_coins(&mut self.tax, &report.tax);
+    }
+    pub fn from_reports<'a, I: IntoIterator<Item = &'a GasReport>>(reports: I) -> GasSummary {
+        let mut summary = GasSummary::default();
+        for report in reports {
+            summary.add(report);
+        }
+        summary
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct V1Pagination {
     pub next_key: Option<String>,
     #[serde(with = "terra_u64_format")]
@@ -340,3 +481,37 @@ pub struct V1TXResult {
     pub tx: V1TX,
     pub tx_response: V1TXResponse,
 }
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_gas_report() -> anyhow::Result<()> {
+        let tx: V1TXResponse = serde_json::from_str(
+            r#"{"height":"5","txhash":"AB","codespace":"","code":0,"data":"","raw_log":"","info":"",
+            "gas_wanted":"200000","gas_used":"150000","timestamp":"2022-01-02T03:04:05Z","events":null,"logs":null,
+            "tx":{"body":{"messages":[
+                {"@type":"/cosmos.bank.v1beta1.MsgSend","amount":[{"denom":"uusd","amount":"1000000"}]},
+                {"@type":"/terra.wasm.v1beta1.MsgExecuteContract","coins":[{"denom":"uluna","amount":"5"}]},
+                {"@type":"/cosmos.staking.v1beta1.MsgDelegate","amount":{"denom":"uluna","amount":"7"}}]},
+            "auth_info":{"fee":{"amount":[{"denom":"uusd","amount":"35000"}]}}}}"#,
+        )?;
+        let sent = tx.coins_sent();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].amount, dec!(1000000));
+        let mut report = tx.gas_report();
+        assert_eq!(report.fee[0].amount, dec!(35000));
+        assert_eq!(report.gas_price()[0].amount, dec!(0.175));
+        report.tax = vec![Coin::create("uusd", dec!(5000))];
+        assert_eq!(report.gas_price()[0].amount, dec!(0.15));
+
+        let summary = GasSummary::from_reports(&[report.clone(), report]);
+        assert_eq!(summary.txs, 2);
+        assert_eq!(summary.gas_used, 300000);
+        assert_eq!(summary.fee[0].amount, dec!(70000));
+        assert_eq!(summary.tax[0].amount, dec!(10000));
+        Ok(())
+    }
+}
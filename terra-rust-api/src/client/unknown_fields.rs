@@ -0,0 +1,289 @@
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+
+/// What to do with fields the LCD returns that our structures don't know about
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownFields {
+    /// drop them (the default)
+    #[default]
+    Ignore,
+    /// fail the call. Useful in testing to catch LCD schema drift early
+    Error,
+    /// log them, and keep the untouched response in the `raw` field of [crate::LCDResult]
+    Capture,
+}
+
+/// the structures that get the untouched response injected into their `raw` field
+const RAW_STRUCTS: &[&str] = &["LCDResult", "LCDResultVec"];
+
+/// deserialize `value`, returning the paths of the fields that were ignored along the way.
+/// if `inject_raw` is set, the LCDResult's raw field is filled in
+pub(crate) fn from_value<T: for<'de> de::Deserialize<'de>>(
+    value: &Value,
+    inject_raw: bool,
+) -> Result<(T, Vec<String>), serde_json::Error> {
+    let unknown = RefCell::new(vec![]);
+    let t = T::deserialize(Tracked {
+        value,
+        path: String::new(),
+        unknown: &unknown,
+        inject_raw,
+    })?;
+    Ok((t, unknown.into_inner()))
+}
+
+/// a serde_json::Value deserializer that remembers which fields were skipped
+struct Tracked<'a> {
+    value: &'a Value,
+    path: String,
+    unknown: &'a RefCell<Vec<String>>,
+    inject_raw: bool,
+}
+impl<'a> Tracked<'a> {
+    fn child(&self, value: &'a Value, segment: &str) -> Tracked<'a> {
+        Tracked {
+            value,
+            path: if self.path.is_empty() {
+                segment.to_string()
+            } else {
+                format!("{}.{}", self.path, segment)
+            },
+            unknown: self.unknown,
+            inject_raw: self.inject_raw,
+        }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Tracked<'a> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Number(n) => {
+                if let Some(u) = n.as_u64() {
+                    visitor.visit_u64(u)
+                } else if let Some(i) = n.as_i64() {
+                    visitor.visit_i64(i)
+                } else {
+                    visitor.visit_f64(n.as_f64().unwrap_or_default())
+                }
+            }
+            Value::String(s) => visitor.visit_str(s),
+            Value::Array(a) => visitor.visit_seq(TrackedSeq {
+                parent: &self,
+                iter: a.iter().enumerate(),
+            }),
+            Value::Object(m) => visitor.visit_map(TrackedMap {
+                parent: &self,
+                iter: m.iter(),
+                value: None,
+                raw: None,
+            }),
+        }
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Object(m)
+                if self.inject_raw
+                    && RAW_STRUCTS.contains(&name)
+                    && fields.contains(&"raw")
+                    && !m.contains_key("raw") =>
+            {
+                visitor.visit_map(TrackedMap {
+                    parent: &self,
+                    iter: m.iter(),
+                    value: None,
+                    raw: Some(self.value.clone()),
+                })
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::String(s) => visitor.visit_enum(s.as_str().into_deserializer()),
+            Value::Object(m) if m.len() == 1 => {
+                let (variant, value) = m.iter().next().unwrap();
+                visitor.visit_enum(TrackedEnum {
+                    variant,
+                    value: self.child(value, variant),
+                })
+            }
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::Other("non enum"),
+                &"a string or single key object",
+            )),
+        }
+    }
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.unknown.borrow_mut().push(self.path);
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
+    }
+}
+
+struct TrackedSeq<'a, 'b, I> {
+    parent: &'b Tracked<'a>,
+    iter: I,
+}
+impl<'de, 'a, 'b, I: Iterator<Item = (usize, &'a Value)>> SeqAccess<'de> for TrackedSeq<'a, 'b, I> {
+    type Error = serde_json::Error;
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.iter.next() {
+            Some((i, value)) => seed
+                .deserialize(self.parent.child(value, &i.to_string()))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct TrackedMap<'a, 'b> {
+    parent: &'b Tracked<'a>,
+    iter: serde_json::map::Iter<'a>,
+    value: Option<(&'a String, &'a Value)>,
+    /// the untouched response, handed out as the last field of a LCDResult
+    raw: Option<Value>,
+}
+impl<'de, 'a, 'b> MapAccess<'de> for TrackedMap<'a, 'b> {
+    type Error = serde_json::Error;
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some((key, value));
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None if self.raw.is_some() => {
+                self.value = None;
+                seed.deserialize("raw".into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value.take() {
+            Some((key, value)) => seed.deserialize(self.parent.child(value, key)),
+            None => {
+                let raw = self.raw.take().unwrap_or(Value::Object(Map::new()));
+                // the raw value is passed through as is. nothing in it is 'unknown'
+                seed.deserialize(raw)
+            }
+        }
+    }
+}
+
+struct TrackedEnum<'a, 'b> {
+    variant: &'b str,
+    value: Tracked<'a>,
+}
+impl<'de, 'a, 'b> EnumAccess<'de> for TrackedEnum<'a, 'b> {
+    type Error = serde_json::Error;
+    type Variant = Tracked<'a>;
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.value))
+    }
+}
+impl<'de, 'a> VariantAccess<'de> for Tracked<'a> {
+    type Error = serde_json::Error;
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::LCDResult;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug)]
+    struct Inner {
+        known: u64,
+        list: Vec<Option<String>>,
+    }
+    #[test]
+    pub fn test_unknown_fields() -> anyhow::Result<()> {
+        let js = serde_json::json!({"height": "12", "result": {"known": 1, "new_field": {"a": 1}, "list": ["x", null]}, "extra": true});
+        let (result, unknown) = from_value::<LCDResult<Inner>>(&js, false)?;
+        assert_eq!(result.height, 12);
+        assert_eq!(result.result.known, 1);
+        assert_eq!(result.result.list.len(), 2);
+        assert!(result.raw.is_none());
+        assert_eq!(unknown, vec!["extra", "result.new_field"]);
+
+        let (result, _) = from_value::<LCDResult<Inner>>(&js, true)?;
+        assert_eq!(result.raw, Some(js));
+
+        let (_, unknown) =
+            from_value::<Inner>(&serde_json::json!({"known": 1, "list": []}), false)?;
+        assert!(unknown.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use crate::client::upgrade_types::{AppliedPlanV1Response, UpgradePlan, UpgradePlanV1Response};
+use crate::errors::TerraRustAPIError;
+use crate::{ApiVersion, LCDResult, Terra};
+
+/// software upgrade plans. The routes follow [Terra::api_version]
+pub struct Upgrade<'a> {
+    terra: &'a Terra,
+}
+impl Upgrade<'_> {
+    pub fn create(terra: &'_ Terra) -> Upgrade<'_> {
+        Upgrade { terra }
+    }
+    /// the upgrade the chain is waiting for, if any
+    pub async fn current_plan(&self) -> Result<Option<UpgradePlan>, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self
+                .terra
+                .send_cmd::<LCDResult<Option<UpgradePlan>>>("/upgrade/current", None, None)
+                .await?
+                .result),
+            ApiVersion::V1Beta1 => Ok(self
+                .terra
+                .send_cmd::<UpgradePlanV1Response>(
+                    "/cosmos/upgrade/v1beta1/current_plan",
+                    None,
+                    None,
+                )
+                .await?
+                .plan),
+        }
+    }
+    /// the height the upgrade `name` was applied at, or None if it hasn't been
+    pub async fn applied_plan(&self, name: &str) -> Result<Option<u64>, TerraRustAPIError> {
+        let height = match self.terra.api_version().await? {
+            ApiVersion::Legacy => {
+                // a number, or a string of one
+                let result = self
+                    .terra
+                    .send_cmd::<LCDResult<serde_json::Value>>(
+                        "/upgrade/applied_plan/",
+                        Some(name),
+                        None,
+                    )
+                    .await?
+                    .result;
+                result
+                    .as_u64()
+                    .or_else(|| result.as_str().and_then(|h| h.parse::<u64>().ok()))
+                    .unwrap_or_default()
+            }
+            ApiVersion::V1Beta1 => {
+                self.terra
+                    .send_cmd::<AppliedPlanV1Response>(
+                        "/cosmos/upgrade/v1beta1/applied_plan/",
+                        Some(name),
+                        None,
+                    )
+                    .await?
+                    .height
+            }
+        };
+        Ok(Some(height).filter(|h| *h > 0))
+    }
+    /// check every `interval` until the upgrade `name` has been applied, returning the height it was applied at.
+    /// fails with [TerraRustAPIError::UnknownUpgrade] if `name` is neither applied nor the chain's current plan.
+    /// errors, like those while the chain is halted for the upgrade, are logged and retried until the
+    /// client's deadline, see [Terra::with_timeout]
+    pub async fn wait_applied(
+        &self,
+        name: &str,
+        interval: std::time::Duration,
+    ) -> Result<u64, TerraRustAPIError> {
+        if let Some(height) = self.applied_plan(name).await? {
+            return Ok(height);
+        }
+        match self.current_plan().await? {
+            Some(plan) if plan.name == name => {}
+            Some(plan) => {
+                return Err(TerraRustAPIError::UnknownUpgrade(
+                    name.to_string(),
+                    Some(plan.name),
+                ))
+            }
+            None => return Err(TerraRustAPIError::UnknownUpgrade(name.to_string(), None)),
+        }
+        loop {
+            match self.applied_plan(name).await {
+                Ok(Some(height)) => return Ok(height),
+                Ok(None) => log::debug!("upgrade {} not applied yet", name),
+                Err(e) => log::info!("waiting for upgrade {}: {}", name, e),
+            }
+            self.terra.pause(interval, name).await?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use crate::client::wire_log::{Replay, WireLogEntry};
+    use crate::errors::TerraRustAPIError;
+    use crate::runtime::FnTimer;
+    use crate::Terra;
+    use std::time::Duration;
+
+    fn get(path: &str, status: u16, response: &str) -> WireLogEntry {
+        WireLogEntry {
+            status: Some(status),
+            response: Some(response.into()),
+            ..WireLogEntry::create("GET", &format!("http://lcd{}", path), None)
+        }
+    }
+    fn replayed(mut entries: Vec<WireLogEntry>) -> Terra {
+        entries.push(get("/node_info", 404, "{}"));
+        entries.push(get("/cosmos/base/tendermint/v1beta1/node_info", 200, "{}"));
+        Terra::lcd_client_no_tx("http://lcd", "columbus-5")
+            .with_replay(Replay::create(entries))
+            .with_timer(FnTimer::new(|_| Box::pin(async {})))
+    }
+    fn applied(status: u16, height: &str) -> WireLogEntry {
+        get(
+            "/cosmos/upgrade/v1beta1/applied_plan/v2",
+            status,
+            &format!(r#"{{"height":"{}"}}"#, height),
+        )
+    }
+    fn planned(name: &str) -> WireLogEntry {
+        get(
+            "/cosmos/upgrade/v1beta1/current_plan",
+            200,
+            &format!(
+                r#"{{"plan":{{"name":"{}","height":"120","info":""}}}}"#,
+                name
+            ),
+        )
+    }
+
+    #[tokio::test]
+    pub async fn test_wait_applied() -> anyhow::Result<()> {
+        let interval = Duration::from_secs(1);
+        let terra = replayed(vec![applied(200, "0"), planned("v3")]);
+        assert!(matches!(
+            terra.upgrade().wait_applied("v2", interval).await,
+            Err(TerraRustAPIError::UnknownUpgrade(name, Some(plan))) if name == "v2" && plan == "v3"
+        ));
+
+        let terra = replayed(vec![
+            applied(200, "0"),
+            planned("v2"),
+            applied(200, "0"),
+            applied(503, "0"),
+            applied(200, "120"),
+        ]);
+        assert_eq!(terra.upgrade().wait_applied("v2", interval).await?, 120);
+
+        // already applied, so there is no plan to check
+        let terra = replayed(vec![applied(200, "120")]);
+        assert_eq!(terra.upgrade().wait_applied("v2", interval).await?, 120);
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::client::client_types::terra_u64_format;
+use serde::{Deserialize, Serialize};
+
+/// a software upgrade the chain will halt for
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UpgradePlan {
+    /// the name the new binary's upgrade handler is registered under
+    pub name: String,
+    /// the chain halts before this height, and resumes on the new binary
+    #[serde(with = "terra_u64_format")]
+    pub height: u64,
+    /// usually where to get the new binary
+    #[serde(default)]
+    pub info: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct UpgradePlanV1Response {
+    pub plan: Option<UpgradePlan>,
+}
+#[derive(Deserialize, Clone, Debug)]
+pub struct AppliedPlanV1Response {
+    #[serde(with = "terra_u64_format")]
+    pub height: u64,
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::LCDResult;
+
+    #[test]
+    pub fn test_plan() -> anyhow::Result<()> {
+        let legacy: LCDResult<Option<UpgradePlan>> = serde_json::from_str(
+            r#"{"height":"7000000","result":{"name":"v0.5.16","time":"0001-01-01T00:00:00Z","height":"7100000","info":"https://github.com/terra-money/core/releases","upgraded_client_state":null}}"#,
+        )?;
+        let plan = legacy.result.unwrap();
+        assert_eq!(plan.name, "v0.5.16");
+        assert_eq!(plan.height, 7100000);
+        let none: UpgradePlanV1Response = serde_json::from_str(r#"{"plan":null}"#)?;
+        assert!(none.plan.is_none());
+        let applied: AppliedPlanV1Response = serde_json::from_str(r#"{"height":"7100000"}"#)?;
+        assert_eq!(applied.height, 7100000);
+        Ok(())
+    }
+}
@@ -1,15 +1,21 @@
 use crate::client::wasm_types::{
-    WasmCodeResult, WasmContractInfoResult, WasmParameterResult, WasmQueryRawResult,
+    InstantiateResult, MigrateResult, StoreResult, WasmCodeInfosResult, WasmCodeResult,
+    WasmContractInfoResult, WasmContractStateResult, WasmContractsByCodeResult, WasmParameter,
+    WasmParameterResult, WasmParameterV1Response, WasmPinnedCodesResult, WasmQueryRawResult,
 };
-use crate::{Message, PrivateKey, Terra};
+#[cfg(feature = "rpc")]
+use crate::{proof, PublicKey};
+use crate::{ApiVersion, Message, PrivateKey, Terra};
 use secp256k1::{Secp256k1, Signing};
 use std::path::Path;
 
 use crate::client::tx_types::TXResultSync;
-use crate::core_types::Coin;
+use crate::core_types::{Coin, Pagination};
 use crate::errors::TerraRustAPIError;
-use crate::messages::wasm::{MsgInstantiateContract, MsgMigrateContract, MsgStoreCode};
-use serde::Deserialize;
+use crate::messages::wasm::{
+    AccessConfig, MsgInstantiateContract, MsgMigrateContract, MsgStoreCode,
+};
+use serde::{Deserialize, Serialize};
 
 pub struct Wasm<'a> {
     terra: &'a Terra,
@@ -19,7 +25,8 @@ impl Wasm<'_> {
     pub fn create(terra: &'_ Terra) -> Wasm<'_> {
         Wasm { terra }
     }
-    pub async fn codes(
+    /// a stored code
+    pub async fn code(
         &self,
         code_id: u64,
         height: Option<u64>,
@@ -30,6 +37,42 @@ impl Wasm<'_> {
             .await?;
         Ok(code)
     }
+    /// a stored code
+    #[deprecated(note = "renamed to code(). the list of codes is code_list()")]
+    pub async fn codes(
+        &self,
+        code_id: u64,
+        height: Option<u64>,
+    ) -> Result<WasmCodeResult, TerraRustAPIError> {
+        self.code(code_id, height).await
+    }
+    /// the stored codes, a page at a time. needs an LCD serving the wasmd `/cosmwasm/wasm/v1` routes
+    pub async fn code_list(
+        &self,
+        pagination: &Pagination,
+    ) -> Result<WasmCodeInfosResult, TerraRustAPIError> {
+        self.terra
+            .send_cmd::<WasmCodeInfosResult>(
+                "/cosmwasm/wasm/v1/code?",
+                Some(&pagination.to_query()),
+                None,
+            )
+            .await
+    }
+    /// the contracts instantiated from a code, a page at a time
+    pub async fn contracts_by_code(
+        &self,
+        code_id: u64,
+        pagination: &Pagination,
+    ) -> Result<WasmContractsByCodeResult, TerraRustAPIError> {
+        self.terra
+            .send_cmd::<WasmContractsByCodeResult>(
+                &format!("/cosmwasm/wasm/v1/code/{}/contracts?", code_id),
+                Some(&pagination.to_query()),
+                None,
+            )
+            .await
+    }
     pub async fn info(
         &self,
         contract_address: &str,
@@ -55,6 +98,39 @@ impl Wasm<'_> {
             .await?;
         Ok(code)
     }
+    /// the wasm module's limits, on either LCD route
+    pub async fn params(&self, height: Option<u64>) -> Result<WasmParameter, TerraRustAPIError> {
+        match self.terra.api_version().await? {
+            ApiVersion::Legacy => Ok(self.parameters(height).await?.result),
+            ApiVersion::V1Beta1 => Ok(self
+                .terra
+                .send_cmd::<WasmParameterV1Response>("/terra/wasm/v1beta1/params", None, height)
+                .await?
+                .params),
+        }
+    }
+    /// the codes pinned in the VM cache, a page at a time. on terra's own wasm module's route
+    pub async fn pinned_codes(
+        &self,
+        pagination: &Pagination,
+    ) -> Result<WasmPinnedCodesResult, TerraRustAPIError> {
+        self.terra
+            .send_cmd::<WasmPinnedCodesResult>(
+                "/terra/wasm/v1beta1/codes/pinned?",
+                Some(&pagination.to_query()),
+                None,
+            )
+            .await
+    }
+    /// fail before signing anything if a wasm file is over the chain's max contract size
+    pub async fn check_code_size(&self, wasm: &str) -> Result<u64, TerraRustAPIError> {
+        let size = std::fs::metadata(wasm)?.len();
+        let max = self.params(None).await?.max_contract_size;
+        if size > max {
+            return Err(TerraRustAPIError::WasmCodeTooLarge(wasm.into(), size, max));
+        }
+        Ok(size)
+    }
     pub async fn query<T: for<'de> Deserialize<'de>>(
         &self,
         contract_address: &str,
@@ -71,6 +147,31 @@ impl Wasm<'_> {
             .await?;
         Ok(code)
     }
+    /// query, returning the untouched JSON as well as the structure
+    pub async fn query_with_raw<T: for<'de> Deserialize<'de>>(
+        &self,
+        contract_address: &str,
+        json_query: &str,
+        height: Option<u64>,
+    ) -> Result<(T, serde_json::Value), TerraRustAPIError> {
+        self.terra
+            .send_cmd_raw::<T>(
+                &format!("/wasm/contracts/{}/store?", contract_address),
+                Some(&format!("query_msg={}", json_query)),
+                height,
+            )
+            .await
+    }
+    /// query using a contract's QueryMsg
+    pub async fn query_msg<Q: Serialize, T: for<'de> Deserialize<'de>>(
+        &self,
+        contract_address: &str,
+        query_msg: &Q,
+        height: Option<u64>,
+    ) -> Result<T, TerraRustAPIError> {
+        self.query(contract_address, &serde_json::to_string(query_msg)?, height)
+            .await
+    }
     pub async fn query_raw(
         &self,
         contract_address: &str,
@@ -99,6 +200,69 @@ impl Wasm<'_> {
 
         Ok((key, value))
     }
+    /// one page of a contract's raw store. `next_key` is the base64 key from the previous page's pagination
+    pub async fn contract_state_page(
+        &self,
+        contract_address: &str,
+        next_key: Option<&str>,
+        limit: u64,
+        height: Option<u64>,
+    ) -> Result<WasmContractStateResult, TerraRustAPIError> {
+        let pagination = match next_key {
+            Some(next_key) => Pagination::after(next_key, limit),
+            None => Pagination::limit(limit),
+        };
+        self.terra
+            .send_cmd::<WasmContractStateResult>(
+                &format!("/cosmwasm/wasm/v1/contract/{}/state?", contract_address),
+                Some(&pagination.to_query()),
+                height,
+            )
+            .await
+    }
+    /// every key/value in a contract's raw store, following the pagination.
+    /// this needs an LCD serving the wasmd `/cosmwasm/wasm/v1` routes
+    pub async fn all_contract_state(
+        &self,
+        contract_address: &str,
+        height: Option<u64>,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, TerraRustAPIError> {
+        let mut state = vec![];
+        let mut next_key: Option<String> = None;
+        loop {
+            let page = self
+                .contract_state_page(contract_address, next_key.as_deref(), 100, height)
+                .await?;
+            for model in page.models {
+                state.push((hex::decode(&model.key)?, base64::decode(&model.value)?));
+            }
+            next_key = page
+                .pagination
+                .and_then(|p| p.next_key)
+                .filter(|k| !k.is_empty());
+            if next_key.is_none() {
+                return Ok(state);
+            }
+        }
+    }
+    /// a contract's raw storage at `height`, read through the RPC and proven against `app_hash`.
+    /// `app_hash` must come from the verified header at `height + 1`
+    #[cfg(feature = "rpc")]
+    pub async fn verified_raw_query(
+        &self,
+        rpc_url: &str,
+        contract_address: &str,
+        key: &[u8],
+        height: u64,
+        app_hash: &[u8],
+    ) -> Result<Vec<u8>, TerraRustAPIError> {
+        let contract = PublicKey::from_account(contract_address)?;
+        let raw_address = contract
+            .raw_address()
+            .ok_or_else(|| TerraRustAPIError::InvalidAddress(contract_address.into()))?;
+        let key = proof::contract_store_key(raw_address, key);
+        proof::verified_query(self.terra, rpc_url, "wasm", &key, height, app_hash).await
+    }
     /// store a wasm file onto the chain.
     pub async fn store<C: Signing + Signing>(
         &self,
@@ -107,18 +271,65 @@ impl Wasm<'_> {
         wasm: &str,
         memo: Option<String>,
     ) -> Result<TXResultSync, TerraRustAPIError> {
-        let from_public_key = from.public_key(secp);
-
-        let wasm_path = Path::new(wasm);
-
-        let store_message = MsgStoreCode::create_from_file(&from_public_key.account()?, wasm_path)?;
-        let messages: Vec<Message> = vec![store_message];
-
-        let resp = self
-            .terra
+        self.store_with_permission(secp, from, wasm, None, memo)
+            .await
+    }
+    /// store a wasm file onto the chain, restricting who can instantiate it
+    pub async fn store_with_permission<C: Signing + Signing>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        wasm: &str,
+        instantiate_permission: Option<AccessConfig>,
+        memo: Option<String>,
+    ) -> Result<TXResultSync, TerraRustAPIError> {
+        let messages = vec![store_message(secp, from, wasm, instantiate_permission)?];
+        self.terra
             .submit_transaction_sync(secp, from, messages, memo)
-            .await;
-        resp
+            .await
+    }
+    /// store a wasm file, wait up to `max_blocks` blocks for it to be committed, and return its code id
+    pub async fn store_and_wait<C: Signing + Signing>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        wasm: &str,
+        instantiate_permission: Option<AccessConfig>,
+        memo: Option<String>,
+        max_blocks: u64,
+    ) -> Result<StoreResult, TerraRustAPIError> {
+        let messages = vec![store_message(secp, from, wasm, instantiate_permission)?];
+        let tx = self
+            .terra
+            .submit_transaction_and_confirm(secp, from, messages, memo, max_blocks)
+            .await?;
+        StoreResult::from_tx(&tx)
+    }
+    /// store several wasm files, one transaction each, signed with consecutive sequence numbers and broadcast
+    /// without waiting for each to be committed. see [Terra::submit_transactions_sync] for what comes back
+    pub async fn store_many<C: Signing + Signing>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        wasms: &[String],
+        instantiate_permission: Option<AccessConfig>,
+        memo: Option<String>,
+    ) -> Result<Vec<Result<TXResultSync, TerraRustAPIError>>, TerraRustAPIError> {
+        let sender = from.public_key(secp).account()?;
+        let transactions = wasms
+            .iter()
+            .map(|wasm| {
+                MsgStoreCode::create_from_file_with_permission(
+                    &sender,
+                    Path::new(wasm),
+                    instantiate_permission.clone(),
+                )
+                .map(|message| vec![message])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.terra
+            .submit_transactions_sync(secp, from, transactions, memo)
+            .await
     }
     /// create a contract using code_id, json init args, and optionally admin on the chain
     #[allow(clippy::too_many_arguments)]
@@ -132,22 +343,34 @@ impl Wasm<'_> {
         admin: Option<String>,
         memo: Option<String>,
     ) -> Result<TXResultSync, TerraRustAPIError> {
-        let from_public_key = from.public_key(secp);
-        let init_message = MsgInstantiateContract::create_from_json(
-            &from_public_key.account()?,
-            admin,
-            code_id,
-            &json,
-            coins,
-        )?;
-        let messages: Vec<Message> = vec![init_message];
-
-        let resp = self
-            .terra
+        let messages = vec![instantiate_message(
+            secp, from, code_id, &json, &coins, admin,
+        )?];
+        self.terra
             .submit_transaction_sync(secp, from, messages, memo)
-            .await;
-
-        resp
+            .await
+    }
+    /// [Wasm::instantiate], waiting up to `max_blocks` blocks for it to be committed, returning the new contract
+    #[allow(clippy::too_many_arguments)]
+    pub async fn instantiate_and_wait<C: Signing + Signing>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        code_id: u64,
+        json: String,
+        coins: Vec<Coin>,
+        admin: Option<String>,
+        memo: Option<String>,
+        max_blocks: u64,
+    ) -> Result<InstantiateResult, TerraRustAPIError> {
+        let messages = vec![instantiate_message(
+            secp, from, code_id, &json, &coins, admin,
+        )?];
+        let tx = self
+            .terra
+            .submit_transaction_and_confirm(secp, from, messages, memo, max_blocks)
+            .await?;
+        InstantiateResult::from_tx(&tx)
     }
 
     /// migrate an existing contract to new_code_id, optionally with a migrate args
@@ -160,30 +383,176 @@ impl Wasm<'_> {
         migrate: Option<String>,
         memo: Option<String>,
     ) -> Result<TXResultSync, TerraRustAPIError> {
-        let from_public_key = from.public_key(secp);
+        let messages = vec![migrate_message(secp, from, contract, new_code_id, migrate)?];
+        self.terra
+            .submit_transaction_sync(secp, from, messages, memo)
+            .await
+    }
+    /// [Wasm::migrate], waiting up to `max_blocks` blocks for it to be committed
+    #[allow(clippy::too_many_arguments)]
+    pub async fn migrate_and_wait<C: Signing + Signing>(
+        &self,
+        secp: &Secp256k1<C>,
+        from: &PrivateKey,
+        contract: &str,
+        new_code_id: u64,
+        migrate: Option<String>,
+        memo: Option<String>,
+        max_blocks: u64,
+    ) -> Result<MigrateResult, TerraRustAPIError> {
+        let messages = vec![migrate_message(secp, from, contract, new_code_id, migrate)?];
+        let tx = self
+            .terra
+            .submit_transaction_and_confirm(secp, from, messages, memo, max_blocks)
+            .await?;
+        MigrateResult::from_tx(&tx)
+    }
+}
 
-        let migrate_message = if let Some(migrate_string) = migrate {
-            MsgMigrateContract::create_from_json(
-                &from_public_key.account()?,
-                contract,
-                new_code_id,
-                &migrate_string,
-            )?
-        } else {
-            MsgMigrateContract::create_from_json(
-                &from_public_key.account()?,
-                contract,
-                new_code_id,
-                "{}",
-            )?
-        };
+fn store_message<C: Signing>(
+    secp: &Secp256k1<C>,
+    from: &PrivateKey,
+    wasm: &str,
+    instantiate_permission: Option<AccessConfig>,
+) -> Result<Message, TerraRustAPIError> {
+    MsgStoreCode::create_from_file_with_permission(
+        &from.public_key(secp).account()?,
+        Path::new(wasm),
+        instantiate_permission,
+    )
+}
 
-        let messages: Vec<Message> = vec![migrate_message];
+fn instantiate_message<C: Signing>(
+    secp: &Secp256k1<C>,
+    from: &PrivateKey,
+    code_id: u64,
+    json: &str,
+    coins: &[Coin],
+    admin: Option<String>,
+) -> Result<Message, TerraRustAPIError> {
+    MsgInstantiateContract::create_from_json(
+        &from.public_key(secp).account()?,
+        admin,
+        code_id,
+        json,
+        coins,
+    )
+}
 
-        let resp = self
-            .terra
-            .submit_transaction_sync(secp, from, messages, memo)
-            .await;
-        resp
+fn migrate_message<C: Signing>(
+    secp: &Secp256k1<C>,
+    from: &PrivateKey,
+    contract: &str,
+    new_code_id: u64,
+    migrate: Option<String>,
+) -> Result<Message, TerraRustAPIError> {
+    MsgMigrateContract::create_from_json(
+        &from.public_key(secp).account()?,
+        contract,
+        new_code_id,
+        migrate.as_deref().unwrap_or("{}"),
+    )
+}
+
+#[cfg(test)]
+mod tst {
+    use crate::client::wire_log::{Replay, WireLogEntry};
+    use crate::core_types::Pagination;
+    use crate::Terra;
+
+    #[tokio::test]
+    pub async fn test_store_many() -> anyhow::Result<()> {
+        use crate::auth_types::AuthAccount;
+        use crate::core_types::StdTx;
+        use crate::messages::wasm::MsgStoreCode;
+        use crate::{GasOptions, PrivateKey};
+        use bitcoin::secp256k1::Secp256k1;
+        let secp = Secp256k1::new();
+        let key = PrivateKey::from_words(&secp, "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut", 0, 0)?;
+        let from = key.public_key(&secp).account()?;
+        let dir = std::env::temp_dir().join(format!("terra-store-many-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let wasms = ["a.wasm", "b.wasm", "c.wasm"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let file = dir.join(name);
+                std::fs::write(&file, [0u8, b'a', b's', b'm', i as u8])?;
+                Ok(file.display().to_string())
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let gas = GasOptions::create_with_fees("30000uluna", 200000)?;
+        let signer = Terra::lcd_client("http://lcd", "columbus-5", &gas, None);
+        let mut entries = vec![WireLogEntry {
+            status: Some(200),
+            response: Some(format!(
+                r#"{{"height":"5","result":{{"type":"core/Account","value":{{"address":"{}","public_key":null,"account_number":"42","sequence":"3"}}}}}}"#,
+                from
+            )),
+            ..WireLogEntry::create("GET", &format!("http://lcd/auth/accounts/{}", from), None)
+        }];
+        // the second is turned away, so the third isn't sent
+        for (sequence, (wasm, code)) in (3..).zip(wasms.iter().zip([None, Some(5)])) {
+            let account = AuthAccount {
+                address: from.clone(),
+                public_key: None,
+                account_number: 42,
+                sequence: Some(sequence),
+            };
+            let store = MsgStoreCode::create_from_file_with_permission(
+                &from,
+                std::path::Path::new(wasm),
+                None,
+            )?;
+            let (std_sign_msg, sigs) = signer
+                .sign_as(&secp, &key, &account, vec![store], None)
+                .await?;
+            let body =
+                serde_json::to_string(&StdTx::from_StdSignMsg(&std_sign_msg, &sigs, "sync"))?;
+            let response = match code {
+                None => format!(r#"{{"txhash":"TX{}","raw_log":"[]"}}"#, sequence),
+                Some(code) => format!(
+                    r#"{{"txhash":"TX{}","code":{},"raw_log":"insufficient funds"}}"#,
+                    sequence, code
+                ),
+            };
+            entries.push(WireLogEntry {
+                status: Some(200),
+                response: Some(response),
+                ..WireLogEntry::create("POST", "http://lcd/txs", Some(&body))
+            });
+        }
+        let terra = Terra::lcd_client("http://lcd", "columbus-5", &gas, None)
+            .with_replay(Replay::create(entries));
+        let results = terra
+            .wasm()
+            .store_many(&secp, &key, &wasms, None, None)
+            .await?;
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].as_ref().map(|r| r.txhash.as_str()).ok(),
+            Some("TX3")
+        );
+        assert!(matches!(
+            &results[1],
+            Err(crate::errors::TerraRustAPIError::TxResultError(5, txhash, _)) if txhash == "TX4"
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_pinned_codes() -> anyhow::Result<()> {
+        let pinned = WireLogEntry {
+            status: Some(200),
+            response: Some(r#"{"code_ids":["3","17"],"pagination":null}"#.into()),
+            ..WireLogEntry::create("GET", "http://lcd/terra/wasm/v1beta1/codes/pinned?", None)
+        };
+        let terra = Terra::lcd_client_no_tx("http://lcd", "columbus-5")
+            .with_replay(Replay::create(vec![pinned]));
+        let codes = terra.wasm().pinned_codes(&Pagination::default()).await?;
+        assert_eq!(codes.code_ids, vec![3, 17]);
+        Ok(())
     }
 }
@@ -0,0 +1,185 @@
+use crate::client::client_types::terra_decimal_format;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// A wasm event of one of the common contract standards. Decoded from the `wasm` events of
+/// transaction logs with [decode], or from [crate::client::tx_types::V1TXResponse::wasm_events]
+/// and [crate::client::tendermint_types::EventType::wasm_events]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WasmEvent {
+    /// a cw20 `transfer`, `send`, `transfer_from` or `send_from`
+    Cw20Transfer {
+        contract: String,
+        action: String,
+        from: String,
+        to: String,
+        #[serde(with = "terra_decimal_format")]
+        amount: Decimal,
+    },
+    /// a cw721 `transfer_nft` or `send_nft`
+    Cw721Transfer {
+        contract: String,
+        action: String,
+        sender: String,
+        recipient: String,
+        token_id: String,
+    },
+    /// a terraswap/astroport style pair `swap`
+    Swap {
+        contract: String,
+        sender: String,
+        receiver: String,
+        offer_asset: String,
+        ask_asset: String,
+        #[serde(with = "terra_decimal_format")]
+        offer_amount: Decimal,
+        #[serde(with = "terra_decimal_format")]
+        return_amount: Decimal,
+        #[serde(with = "terra_decimal_format")]
+        spread_amount: Decimal,
+        #[serde(with = "terra_decimal_format")]
+        commission_amount: Decimal,
+    },
+}
+
+/// the typed events in an event's attributes. Terra's logs put every contract call of a message
+/// into one `wasm` event, each starting at its `contract_address`, so an event can hold several.
+/// wasmd's `wasm-*` custom events are decoded too. anything else is skipped
+pub fn decode<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(
+    event_type: &str,
+    attributes: I,
+) -> Vec<WasmEvent> {
+    if event_type != "wasm" && !event_type.starts_with("wasm-") {
+        return vec![];
+    }
+    let mut calls: Vec<HashMap<&str, &str>> = vec![];
+    for (key, value) in attributes {
+        if key == "contract_address" || key == "_contract_address" || calls.is_empty() {
+            calls.push(HashMap::new());
+        }
+        let key = key.trim_start_matches('_');
+        if let Some(call) = calls.last_mut() {
+            call.entry(key).or_insert(value);
+        }
+    }
+    let action_of = |call: &HashMap<&str, &str>| -> Option<String> {
+        call.get("action")
+            .map(|a| a.to_string())
+            .or_else(|| event_type.strip_prefix("wasm-").map(String::from))
+    };
+    calls
+        .iter()
+        .filter_map(|call| decode_call(call, &action_of(call)?))
+        .collect()
+}
+
+fn decode_call(call: &HashMap<&str, &str>, action: &str) -> Option<WasmEvent> {
+    let text = |key: &str| call.get(key).map(|v| v.to_string());
+    let amount = |key: &str| call.get(key).and_then(|v| Decimal::from_str(v).ok());
+    let contract = text("contract_address")?;
+    match action {
+        "transfer" | "send" | "transfer_from" | "send_from" => Some(WasmEvent::Cw20Transfer {
+            contract,
+            action: action.into(),
+            from: text("from")?,
+            to: text("to")?,
+            amount: amount("amount")?,
+        }),
+        "transfer_nft" | "send_nft" => Some(WasmEvent::Cw721Transfer {
+            contract,
+            action: action.into(),
+            sender: text("sender")?,
+            recipient: text("recipient")?,
+            token_id: text("token_id")?,
+        }),
+        "swap" => Some(WasmEvent::Swap {
+            contract,
+            sender: text("sender")?,
+            receiver: text("receiver").or_else(|| text("sender"))?,
+            offer_asset: text("offer_asset")?,
+            ask_asset: text("ask_asset")?,
+            offer_amount: amount("offer_amount")?,
+            return_amount: amount("return_amount")?,
+            spread_amount: amount("spread_amount").unwrap_or_default(),
+            commission_amount: amount("commission_amount").unwrap_or_default(),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_decode() {
+        // a swap on a pair, sending the cw20 it returns
+        let attributes = [
+            ("contract_address", "terra1pair"),
+            ("action", "swap"),
+            ("sender", "terra1me"),
+            ("receiver", "terra1me"),
+            ("offer_asset", "uluna"),
+            ("ask_asset", "terra1token"),
+            ("offer_amount", "1000"),
+            ("return_amount", "990"),
+            ("tax_amount", "0"),
+            ("spread_amount", "7"),
+            ("commission_amount", "3"),
+            ("contract_address", "terra1token"),
+            ("action", "transfer"),
+            ("from", "terra1pair"),
+            ("to", "terra1me"),
+            ("amount", "990"),
+            ("contract_address", "terra1other"),
+            ("action", "something_else"),
+        ];
+        let events = decode("wasm", attributes);
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            WasmEvent::Swap {
+                contract,
+                return_amount,
+                commission_amount,
+                ..
+            } => {
+                assert_eq!(contract, "terra1pair");
+                assert_eq!(*return_amount, dec!(990));
+                assert_eq!(*commission_amount, dec!(3));
+            }
+            e => panic!("{:?}", e),
+        }
+        assert_eq!(
+            events[1],
+            WasmEvent::Cw20Transfer {
+                contract: "terra1token".into(),
+                action: "transfer".into(),
+                from: "terra1pair".into(),
+                to: "terra1me".into(),
+                amount: dec!(990),
+            }
+        );
+
+        let nft = [
+            ("_contract_address", "terra1nft"),
+            ("sender", "terra1a"),
+            ("recipient", "terra1b"),
+            ("token_id", "42"),
+        ];
+        assert_eq!(
+            decode("wasm-transfer_nft", nft),
+            vec![WasmEvent::Cw721Transfer {
+                contract: "terra1nft".into(),
+                action: "transfer_nft".into(),
+                sender: "terra1a".into(),
+                recipient: "terra1b".into(),
+                token_id: "42".into(),
+            }]
+        );
+        assert!(decode("transfer", [("recipient", "terra1b")]).is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 use crate::client::client_types::terra_u64_format;
+use crate::client::tx_types::{V1Pagination, V1TXResult};
+use crate::errors::TerraRustAPIError;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[allow(missing_docs)]
 #[derive(Deserialize, Serialize, Debug)]
@@ -51,6 +53,26 @@ pub struct WasmParameterResult {
     pub height: u64,
     pub result: WasmParameter,
 }
+#[derive(Deserialize, Debug)]
+pub struct WasmParameterV1Response {
+    pub params: WasmParameter,
+}
+/// codes pinned in the wasm VM's cache. executing them costs no compile gas
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WasmPinnedCodesResult {
+    #[serde(deserialize_with = "u64_strings")]
+    pub code_ids: Vec<u64>,
+    pub pagination: Option<V1Pagination>,
+}
+fn u64_strings<'de, D>(deserializer: D) -> Result<Vec<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|id| id.parse::<u64>().map_err(serde::de::Error::custom))
+        .collect()
+}
 #[allow(missing_docs)]
 #[derive(Deserialize, Serialize, Debug)]
 pub struct WasmQueryRaw {
@@ -64,3 +86,177 @@ pub struct WasmQueryRawResult {
     pub height: u64,
     pub result: WasmQueryRaw,
 }
+/// a raw key/value from a contract's store. key is hex, value is base64
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WasmContractStateModel {
+    pub key: String,
+    pub value: String,
+}
+#[allow(missing_docs)]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WasmContractStateResult {
+    pub models: Vec<WasmContractStateModel>,
+    pub pagination: Option<V1Pagination>,
+}
+/// a stored code, as listed by the wasmd routes
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WasmCodeInfo {
+    #[serde(with = "terra_u64_format")]
+    pub code_id: u64,
+    pub creator: String,
+    pub data_hash: String,
+}
+#[allow(missing_docs)]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WasmCodeInfosResult {
+    pub code_infos: Vec<WasmCodeInfo>,
+    pub pagination: Option<V1Pagination>,
+}
+#[allow(missing_docs)]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WasmContractsByCodeResult {
+    pub contracts: Vec<String>,
+    pub pagination: Option<V1Pagination>,
+}
+
+/// a code stored by [crate::client::wasm::Wasm::store_and_wait], read from the transaction's logs
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct StoreResult {
+    pub code_id: u64,
+    pub txhash: String,
+    pub height: u64,
+    pub gas_used: u64,
+}
+impl StoreResult {
+    /// the code id from the `store_code` event of a committed transaction
+    pub fn from_tx(tx: &V1TXResult) -> Result<StoreResult, TerraRustAPIError> {
+        Ok(StoreResult {
+            code_id: log_code_id(tx, "store_code")?,
+            txhash: tx.tx_response.txhash.clone(),
+            height: tx.tx_response.height,
+            gas_used: tx.tx_response.gas_used,
+        })
+    }
+}
+
+/// a contract created by [crate::client::wasm::Wasm::instantiate_and_wait], read from the transaction's logs
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct InstantiateResult {
+    pub contract_address: String,
+    pub code_id: u64,
+    pub txhash: String,
+    pub height: u64,
+    pub gas_used: u64,
+}
+impl InstantiateResult {
+    /// the contract from the `instantiate_contract` event of a committed transaction
+    pub fn from_tx(tx: &V1TXResult) -> Result<InstantiateResult, TerraRustAPIError> {
+        Ok(InstantiateResult {
+            contract_address: log_attribute(tx, "instantiate_contract", "contract_address")?,
+            code_id: log_code_id(tx, "instantiate_contract")?,
+            txhash: tx.tx_response.txhash.clone(),
+            height: tx.tx_response.height,
+            gas_used: tx.tx_response.gas_used,
+        })
+    }
+}
+
+/// a contract moved to new code by [crate::client::wasm::Wasm::migrate_and_wait], read from the transaction's logs
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct MigrateResult {
+    pub contract_address: String,
+    /// the code the contract now runs
+    pub code_id: u64,
+    pub txhash: String,
+    pub height: u64,
+    pub gas_used: u64,
+}
+impl MigrateResult {
+    /// the contract from the `migrate_contract` event of a committed transaction
+    pub fn from_tx(tx: &V1TXResult) -> Result<MigrateResult, TerraRustAPIError> {
+        Ok(MigrateResult {
+            contract_address: log_attribute(tx, "migrate_contract", "contract_address")?,
+            code_id: log_code_id(tx, "migrate_contract")?,
+            txhash: tx.tx_response.txhash.clone(),
+            height: tx.tx_response.height,
+            gas_used: tx.tx_response.gas_used,
+        })
+    }
+}
+
+/// the first value of the attribute, in the first event of its type
+fn log_attribute(
+    tx: &V1TXResult,
+    event_type: &str,
+    attribute_key: &str,
+) -> Result<String, TerraRustAPIError> {
+    tx.tx_response
+        .get_attribute_from_logs(event_type, attribute_key)
+        .into_iter()
+        .next()
+        .map(|(_, value)| value)
+        .ok_or_else(|| {
+            TerraRustAPIError::MissingLogAttribute(
+                event_type.into(),
+                attribute_key.into(),
+                tx.tx_response.txhash.clone(),
+            )
+        })
+}
+
+fn log_code_id(tx: &V1TXResult, event_type: &str) -> Result<u64, TerraRustAPIError> {
+    let code_id = log_attribute(tx, event_type, "code_id")?;
+    code_id.parse::<u64>().map_err(|_| {
+        TerraRustAPIError::StdErr(format!(
+            "code_id {} in the logs of {} isn't a number",
+            code_id, tx.tx_response.txhash
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_params_and_pinned() -> anyhow::Result<()> {
+        let params: WasmParameterV1Response = serde_json::from_str(
+            r#"{"params":{"max_contract_size":"614400","max_contract_gas":"20000000","max_contract_msg_size":"4096"}}"#,
+        )?;
+        assert_eq!(params.params.max_contract_size, 614400);
+        let pinned: WasmPinnedCodesResult = serde_json::from_str(
+            r#"{"code_ids":["3","17"],"pagination":{"next_key":null,"total":"0"}}"#,
+        )?;
+        assert_eq!(pinned.code_ids, vec![3, 17]);
+        Ok(())
+    }
+
+    fn tx(logs: &str) -> V1TXResult {
+        serde_json::from_str(&format!(
+            r#"{{"tx":{{"body":{{"messages":[],"memo":"","timeout_height":"0","extension_options":[],"non_critical_extension_options":[]}},"auth_info":{{}},"signatures":[]}},"tx_response":{{"height":"100","txhash":"TX","codespace":"","code":0,"data":"","raw_log":"","info":"","gas_wanted":"200000","gas_used":"150000","tx":{{}},"timestamp":"2022-01-02T03:04:05Z","events":null,"logs":{}}}}}"#,
+            logs
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    pub fn test_results() -> anyhow::Result<()> {
+        let instantiated = InstantiateResult::from_tx(&tx(
+            r#"[{"msg_index":0,"events":[{"type":"instantiate_contract","attributes":[{"key":"creator","value":"terra1creator"},{"key":"admin","value":""},{"key":"code_id","value":"42"},{"key":"contract_address","value":"terra1contract"}]}]}]"#,
+        ))?;
+        assert_eq!(instantiated.contract_address, "terra1contract");
+        assert_eq!(instantiated.code_id, 42);
+        assert_eq!(instantiated.height, 100);
+        assert_eq!(instantiated.gas_used, 150000);
+
+        let stored = tx(
+            r#"[{"msg_index":0,"events":[{"type":"store_code","attributes":[{"key":"sender","value":"terra1creator"},{"key":"code_id","value":"7"}]}]}]"#,
+        );
+        assert_eq!(StoreResult::from_tx(&stored)?.code_id, 7);
+        assert!(matches!(
+            MigrateResult::from_tx(&stored),
+            Err(TerraRustAPIError::MissingLogAttribute(..))
+        ));
+        Ok(())
+    }
+}
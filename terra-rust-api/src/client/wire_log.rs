@@ -0,0 +1,359 @@
+use crate::client::client_types::terra_datetime_format;
+use crate::errors::TerraRustAPIError;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// one request to the LCD (or RPC/FCD) and what came back
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WireLogEntry {
+    #[serde(with = "terra_datetime_format")]
+    pub timestamp: DateTime<Utc>,
+    pub method: String,
+    pub url: String,
+    /// the body POSTed
+    pub request: Option<String>,
+    /// None if no response came back. a `304 Not Modified` answered from the http cache is logged with the cached body
+    pub status: Option<u16>,
+    pub response: Option<String>,
+    /// why there was no response
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+impl WireLogEntry {
+    pub fn create(method: &str, url: &str, request: Option<&str>) -> WireLogEntry {
+        WireLogEntry {
+            timestamp: Utc::now(),
+            method: method.into(),
+            url: url.into(),
+            request: request.map(String::from),
+            status: None,
+            response: None,
+            error: None,
+            elapsed_ms: 0,
+        }
+    }
+    /// the entry with the secrets in it masked. see [crate::redact]
+    pub fn redacted(&self) -> WireLogEntry {
+        self.redacted_with_secrets(&[])
+    }
+    /// the entry with the secrets in it masked, and these exact texts as well
+    pub fn redacted_with_secrets(&self, secrets: &[String]) -> WireLogEntry {
+        let redact_text = |text: &str| crate::redact::redact_with_secrets(text, secrets);
+        let redact = |text: &Option<String>| text.as_deref().map(redact_text);
+        WireLogEntry {
+            url: redact_text(&self.url),
+            request: redact(&self.request),
+            response: redact(&self.response),
+            error: redact(&self.error),
+            ..self.clone()
+        }
+    }
+}
+
+/// Appends every request the client makes, and its response, to a file or writer as JSON lines.
+/// Secrets are masked before anything is written. Meant for bug reports about LCD incompatibilities,
+/// where the exact payloads matter. Shared between clones of the client.
+///
+/// The writing happens on a thread of its own, so a slow disk doesn't hold up the requests;
+/// the last clone to be dropped waits for it to write what is left. see [WireLog::flush]
+#[derive(Clone)]
+pub struct WireLog {
+    writer: Arc<WireLogWriter>,
+}
+struct WireLogWriter {
+    sink: Option<Sender<WireLogCommand>>,
+    thread: Option<JoinHandle<()>>,
+}
+impl WireLogWriter {
+    fn send(&self, command: WireLogCommand) -> Result<(), TerraRustAPIError> {
+        self.sink
+            .as_ref()
+            .and_then(|sink| sink.send(command).ok())
+            .ok_or_else(|| TerraRustAPIError::StdErr("the writer has stopped".into()))
+    }
+}
+impl Drop for WireLogWriter {
+    fn drop(&mut self) {
+        // the writer stops once the channel is closed
+        self.sink.take();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+enum WireLogCommand {
+    Line(String),
+    /// answered once the lines before it are written
+    Flush(Sender<()>),
+}
+impl WireLog {
+    pub fn create<W: Write + Send + 'static>(mut writer: W) -> WireLog {
+        let (sink, lines) = channel::<WireLogCommand>();
+        let thread = std::thread::spawn(move || {
+            for command in lines {
+                match command {
+                    WireLogCommand::Line(line) => {
+                        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+                            log::warn!("unable to write to the wire log: {}", e)
+                        }
+                    }
+                    WireLogCommand::Flush(done) => {
+                        done.send(()).ok();
+                    }
+                }
+            }
+        });
+        WireLog {
+            writer: Arc::new(WireLogWriter {
+                sink: Some(sink),
+                thread: Some(thread),
+            }),
+        }
+    }
+    /// append to the file, creating it if needed
+    pub fn to_file<P: AsRef<Path>>(path: P) -> Result<WireLog, TerraRustAPIError> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(WireLog::create(file))
+    }
+    /// queue an entry to be written. a log that can't be written to is reported, but doesn't fail the request
+    pub fn append(&self, entry: &WireLogEntry) {
+        self.append_with_secrets(entry, &[])
+    }
+    /// queue an entry, masking these exact texts as well
+    fn append_with_secrets(&self, entry: &WireLogEntry, secrets: &[String]) {
+        let write = || -> Result<(), TerraRustAPIError> {
+            let line = serde_json::to_string(&entry.redacted_with_secrets(secrets))?;
+            self.writer.send(WireLogCommand::Line(line))
+        };
+        if let Err(e) = write() {
+            log::warn!("unable to write to the wire log: {}", e)
+        }
+    }
+    /// wait until the entries appended so far are written. this blocks, so call it outside async code
+    pub fn flush(&self) {
+        let (done, written) = channel();
+        if self.writer.send(WireLogCommand::Flush(done)).is_ok() {
+            written.recv().ok();
+        }
+    }
+    /// write an entry for a finished request
+    pub(crate) fn record(
+        &self,
+        mut entry: WireLogEntry,
+        elapsed: Duration,
+        result: &Result<(u16, String), String>,
+        secrets: &[String],
+    ) {
+        entry.elapsed_ms = elapsed.as_millis() as u64;
+        match result {
+            Ok((status, text)) => {
+                entry.status = Some(*status);
+                entry.response = Some(text.clone());
+            }
+            Err(e) => entry.error = Some(e.clone()),
+        }
+        self.append_with_secrets(&entry, secrets)
+    }
+    /// the entries of a wire log, oldest first
+    pub fn read<R: BufRead>(reader: R) -> Result<Vec<WireLogEntry>, TerraRustAPIError> {
+        let mut entries = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Answers requests with the responses in a wire log, instead of sending them. Record some traffic with
+/// [WireLog] once, and tests can replay it without a network.
+/// Requests are matched on method, URL and body, with the secrets masked as they were when recorded.
+/// Matching responses are handed out in the order they were recorded, the last repeating once they run out,
+/// so polling works. Responses that had secrets masked when they were recorded aren't replayed, as the
+/// masked text isn't what the LCD sent; they fail with [TerraRustAPIError::ReplayRedacted].
+/// Shared between clones of the client
+#[derive(Clone, Debug)]
+pub struct Replay {
+    /// the entries, and whether each has been replayed
+    entries: Arc<Mutex<Vec<(WireLogEntry, bool)>>>,
+}
+impl Replay {
+    pub fn create(entries: Vec<WireLogEntry>) -> Replay {
+        Replay {
+            entries: Arc::new(Mutex::new(
+                entries.into_iter().map(|e| (e, false)).collect(),
+            )),
+        }
+    }
+    /// a wire log written by [WireLog::to_file]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Replay, TerraRustAPIError> {
+        let file = std::fs::File::open(path)?;
+        Ok(Replay::create(WireLog::read(std::io::BufReader::new(
+            file,
+        ))?))
+    }
+    /// the recorded status & response body.
+    /// fails with [TerraRustAPIError::ReplayMissing] if nothing like the request was recorded,
+    /// and [TerraRustAPIError::ReplayRedacted] if the response was masked
+    pub(crate) fn respond(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<&str>,
+    ) -> Result<(u16, String), TerraRustAPIError> {
+        let request = WireLogEntry::create(method, url, body).redacted();
+        let mut entries = self.entries.lock().unwrap();
+        let matching = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (entry, _))| {
+                entry.method == request.method
+                    && entry.url == request.url
+                    && entry.request == request.request
+            })
+            .map(|(i, (_, replayed))| (i, *replayed))
+            .collect::<Vec<_>>();
+        let index = match matching
+            .iter()
+            .find(|(_, replayed)| !replayed)
+            .or(matching.last())
+        {
+            Some((i, _)) => *i,
+            None => return Err(TerraRustAPIError::ReplayMissing(method.into(), request.url)),
+        };
+        let (entry, replayed) = &mut entries[index];
+        *replayed = true;
+        if matches!(&entry.response, Some(text) if crate::redact::is_redacted(text)) {
+            return Err(TerraRustAPIError::ReplayRedacted(
+                method.into(),
+                request.url,
+            ));
+        }
+        match (entry.status, &entry.response, &entry.error) {
+            (Some(status), Some(text), _) if (200..300).contains(&status) || status == 304 => {
+                Ok((status, text.clone()))
+            }
+            (Some(status), text, _) => Err(TerraRustAPIError::TerraLCDResponse(
+                StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                text.clone().unwrap_or_default(),
+            )),
+            (None, _, error) => Err(TerraRustAPIError::StdErr(error.clone().unwrap_or_default())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    /// a writer the test can look at afterwards
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    pub fn test_wire_log() -> anyhow::Result<()> {
+        let out = Shared::default();
+        let wire_log = WireLog::create(out.clone());
+        let entry = WireLogEntry::create("GET", "https://lcd/blocks/latest?apikey=abc", None);
+        wire_log.record(
+            entry,
+            Duration::from_millis(12),
+            &Ok((200, r#"{"block":{}}"#.into())),
+            &[],
+        );
+        let entry = WireLogEntry::create("POST", "https://lcd/txs", Some(r#"{"seed":"x"}"#));
+        wire_log.record(
+            entry,
+            Duration::ZERO,
+            &Err("timed out with s3cret".into()),
+            &["s3cret".to_string()],
+        );
+        wire_log.flush();
+
+        let entries = WireLog::read(out.0.lock().unwrap().as_slice())?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].url,
+            "https://lcd/blocks/latest?apikey=[REDACTED]"
+        );
+        assert_eq!(entries[0].status, Some(200));
+        assert_eq!(entries[0].elapsed_ms, 12);
+        assert_eq!(
+            entries[1].request.as_deref(),
+            Some(r#"{"seed":"[REDACTED]"}"#)
+        );
+        assert_eq!(
+            entries[1].error.as_deref(),
+            Some("timed out with [REDACTED]")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_replay() -> anyhow::Result<()> {
+        let recorded = |path: &str, status, response: &str| WireLogEntry {
+            status: Some(status),
+            response: Some(response.into()),
+            ..WireLogEntry::create("GET", &format!("http://lcd{}", path), None)
+        };
+        let replay = Replay::create(vec![
+            recorded("/blocks/latest", 200, r#"{"height":1}"#),
+            recorded("/blocks/latest", 200, r#"{"height":2}"#),
+            recorded("/txs/AB", 404, "not found"),
+            recorded("/keys/x", 200, r#"{"private_key":"[REDACTED]"}"#),
+        ]);
+        let terra = crate::Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(replay);
+        let height = |value: serde_json::Value| value["height"].as_u64();
+        let latest = || terra.send_cmd::<serde_json::Value>("/blocks/latest", None, None);
+        assert_eq!(height(latest().await?), Some(1));
+        assert_eq!(height(latest().await?), Some(2));
+        // the last one repeats
+        assert_eq!(height(latest().await?), Some(2));
+        let missing = terra
+            .send_cmd::<serde_json::Value>("/txs/AB", None, None)
+            .await;
+        match missing.map_err(|e| e.without_context().to_string()) {
+            Err(e) => assert!(e.contains("not found"), "{}", e),
+            Ok(v) => panic!("{}", v),
+        }
+        match terra
+            .send_cmd::<serde_json::Value>("/txs/CD", None, None)
+            .await
+        {
+            Err(e) => assert!(matches!(
+                e.without_context(),
+                TerraRustAPIError::ReplayMissing(_, _)
+            )),
+            Ok(v) => panic!("{}", v),
+        }
+        match terra
+            .send_cmd::<serde_json::Value>("/keys/x", None, None)
+            .await
+        {
+            Err(e) => assert!(matches!(
+                e.without_context(),
+                TerraRustAPIError::ReplayRedacted(_, _)
+            )),
+            Ok(v) => panic!("{}", v),
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,57 @@
 #![allow(missing_docs)]
 
 use reqwest::StatusCode;
+use std::fmt;
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
+/// how much of a request/response body is kept in an error
+const SNIPPET_LEN: usize = 512;
+
+fn snippet(text: &str) -> String {
+    match text.char_indices().nth(SNIPPET_LEN) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// the request an error happened on
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub method: String,
+    pub url: String,
+    /// the start of the request body
+    pub body: Option<String>,
+    /// the start of the response body
+    pub response: Option<String>,
+}
+impl RequestContext {
+    pub fn create(method: &str, url: &str, body: Option<&str>) -> RequestContext {
+        RequestContext {
+            method: method.into(),
+            url: url.into(),
+            body: body.map(snippet),
+            response: None,
+        }
+    }
+    pub fn with_response(mut self, response: &str) -> RequestContext {
+        self.response = Some(snippet(response));
+        self
+    }
+}
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.method, crate::redact::redact(&self.url))?;
+        if let Some(body) = &self.body {
+            write!(f, " body={}", crate::redact::redact(body))?;
+        }
+        if let Some(response) = &self.response {
+            write!(f, " response={}", response)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum TerraRustAPIError {
     #[error("Reqwest HTTP(s) Error")]
@@ -14,6 +62,12 @@ pub enum TerraRustAPIError {
     SerdeJson(#[from] ::serde_json::Error),
     #[error(transparent)]
     IOErr(#[from] ::std::io::Error),
+    #[cfg(feature = "export")]
+    #[error(transparent)]
+    Csv(#[from] ::csv::Error),
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    Parquet(#[from] ::parquet::errors::ParquetError),
     #[error(transparent)]
     ED25519(#[from] ::ed25519_dalek::ed25519::Error),
     #[error(transparent)]
@@ -26,6 +80,8 @@ pub enum TerraRustAPIError {
     HexError(#[from] ::hex::FromHexError),
     #[error(transparent)]
     Secp256k1(#[from] ::secp256k1::Error),
+    #[error(transparent)]
+    Base64(#[from] ::base64::DecodeError),
 
     #[error("Terra `{0}` CLI Error")]
     Terra(String),
@@ -37,6 +93,12 @@ pub enum TerraRustAPIError {
     Bech32DecodeExpanded(String, usize, String, usize),
     #[error("Mnemonic - Bad Phrase")]
     Phrasing,
+    #[error("Mnemonic - {0} words. seed phrases have 24")]
+    MnemonicWordCount(usize),
+    #[error("Mnemonic - word {0} is not in the word list")]
+    MnemonicUnknownWord(usize),
+    #[error("Mnemonic - bad checksum. a word is wrong, or they are out of order")]
+    MnemonicChecksum,
     #[error("Mnemonic - Missing Phrase")]
     MissingPhrase,
     #[error("Bad Implementation. Missing Component")]
@@ -62,6 +124,8 @@ pub enum TerraRustAPIError {
     CoinParseErrV { parse: String },
     #[error("Can't parse `{0}` into a coin")]
     CoinParseErr(String),
+    #[error("Denom {0} has a unit with an exponent of {1}, too large to scale by")]
+    DenomExponent(String, u32),
     #[error("TX submit returned `{0}` - {1} '{2}'")]
     TxResultError(usize, String, String),
     #[error("No price found for Gas using denom {0}")]
@@ -70,8 +134,168 @@ pub enum TerraRustAPIError {
     TendermintValidatorSet(u64, u64),
     #[error("Transaction {0} not found after {1} attempts")]
     TXNotFound(String, usize),
+    #[error("Transaction {0} not committed within {1} blocks")]
+    TxConfirmationTimeout(String, u64),
+    #[error("{0}/{1} not present in the logs of {2}")]
+    MissingLogAttribute(String, String, String),
+    #[error("Test vector {0}: {1} doesn't match")]
+    TestVectorMismatch(String, String),
+    #[error("Can't export as {0}. try csv or parquet")]
+    ExportFormat(String),
+    #[error("{0} is {1} bytes, over the max contract size of {2}")]
+    WasmCodeTooLarge(String, u64, u64),
+    #[error("Multisig threshold {0} invalid for {1} keys")]
+    MultisigThreshold(usize, usize),
+    #[error("Signature from {0} is not one of the multisig keys")]
+    MultisigUnknownSigner(String),
+    #[error("Multisig needs {1} signatures, only have {0}")]
+    MultisigNotEnoughSignatures(usize, usize),
+    #[error("{0} returned fields the response structure doesn't have: {1}")]
+    UnknownFields(String, String),
+    #[error("Configured for chain {0}, but the LCD is on {1}")]
+    ChainIdMismatch(String, String),
+    #[error("Light client verification failed: {0}")]
+    LightClient(String),
+    #[error("Protobuf decoding error: {0}")]
+    Protobuf(String),
+    #[error("Invalid setting {0}: {1}")]
+    Config(String, String),
+    #[error("{0} message: {1}")]
+    InvalidMessage(String, String),
+    #[error("Transaction is about {0} bytes, over the mempool limit of {1}")]
+    TxTooLarge(u64, u64),
+    #[error("Transaction wants {0} gas, over the block limit of {1}")]
+    TxGasOverBlockLimit(u64, u64),
+    #[error("The address book is over the {0} byte limit once decompressed")]
+    AddressBookTooLarge(u64),
+    #[error("Nothing recorded for {0} {1}")]
+    ReplayMissing(String, String),
+    #[error("{0} {1} was recorded with secrets masked in the response, so it can't be replayed")]
+    ReplayRedacted(String, String),
+    /// the height asked for, and the lowest & latest the LCD says it has
+    #[error("Height {0} has been pruned by the LCD")]
+    HeightPruned(u64, Option<u64>, Option<u64>),
+    #[error("Invalid event query {0}: {1}")]
+    EventQuery(String, String),
+    #[error("Chain registry entry for {0}: {1}")]
+    ChainRegistry(String, String),
+    #[error("No client for chain {0}")]
+    UnknownChain(String),
+    #[error("Invalid gas options: {0}")]
+    InvalidGasOptions(String),
+    #[error("Fee {0} is over the maximum of {1}")]
+    FeeOverMax(String, String),
+    #[error("No signing key for chain {0}")]
+    NoSigner(String),
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("Transaction {0} sent no IBC packet")]
+    NoIbcPacket(String),
+    #[error("Invalid memo template: {0}")]
+    InvalidMemo(String),
+    #[error("No value for {0}")]
+    UnknownPlaceholders(String),
+    #[error("{0} is outside a string, and its value isn't a JSON value: {1}")]
+    PlaceholderNotJson(String, String),
+    #[error("Message doesn't match its schema: {0}")]
+    SchemaMismatch(String),
+    #[error("Cancelled")]
+    Cancelled,
+    #[error("Deadline passed waiting on {0}")]
+    DeadlineExceeded(String),
+    #[error("No transaction queue lane {0}")]
+    UnknownLane(String),
+    #[error("Upgrade {0} is not planned (current plan: {1:?})")]
+    UnknownUpgrade(String, Option<String>),
+    #[error("Audit record failed: {0}")]
+    Audit(String),
+    /// every error from an LCD/RPC request arrives wrapped in this. Match on
+    /// [TerraRustAPIError::without_context] to get at the variant it used to be
+    #[error("{context}: {source}")]
+    Request {
+        context: RequestContext,
+        source: Box<TerraRustAPIError>,
+    },
     #[error("unknown Terra-Rust API error")]
     Unknown,
     #[error("Generic Error {0}")]
     StdErr(String),
 }
+impl TerraRustAPIError {
+    /// attach the request this error happened on
+    pub fn with_context(self, mut context: RequestContext) -> TerraRustAPIError {
+        match self {
+            TerraRustAPIError::Request { .. } => self,
+            e => {
+                if let (TerraRustAPIError::TerraLCDResponse(_, text), None) =
+                    (&e, &context.response)
+                {
+                    context = context.with_response(text);
+                }
+                TerraRustAPIError::Request {
+                    context,
+                    source: Box::new(e),
+                }
+            }
+        }
+    }
+    /// the request this error happened on, if it came from one
+    pub fn context(&self) -> Option<&RequestContext> {
+        match self {
+            TerraRustAPIError::Request { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+    /// the underlying error, without the request context
+    pub fn without_context(&self) -> &TerraRustAPIError {
+        match self {
+            TerraRustAPIError::Request { source, .. } => source.without_context(),
+            e => e,
+        }
+    }
+    /// a broadcast that failed with this surely never reached the node: the connection couldn't be made, or
+    /// the LCD was unavailable. after anything else, the node may have taken the transaction before the answer was lost
+    pub fn unsent(&self) -> bool {
+        match self.without_context() {
+            TerraRustAPIError::ReqwestError(e) => e.is_connect(),
+            TerraRustAPIError::TerraLCDResponse(status, _) => {
+                *status == StatusCode::SERVICE_UNAVAILABLE
+            }
+            _ => false,
+        }
+    }
+    /// the underlying error by value, dropping the request context
+    pub fn into_without_context(self) -> TerraRustAPIError {
+        match self {
+            TerraRustAPIError::Request { source, .. } => source.into_without_context(),
+            e => e,
+        }
+    }
+}
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_context() {
+        let e = TerraRustAPIError::TerraLCDResponse(StatusCode::NOT_FOUND, "x".repeat(600))
+            .with_context(RequestContext::create(
+                "POST",
+                "https://lcd.example/txs?token=abc",
+                Some("{}"),
+            ));
+        let context = e.context().unwrap();
+        assert_eq!(context.response.as_ref().unwrap().len(), SNIPPET_LEN + 3);
+        assert!(matches!(
+            e.without_context(),
+            TerraRustAPIError::TerraLCDResponse(StatusCode::NOT_FOUND, _)
+        ));
+        assert!(e
+            .to_string()
+            .starts_with("POST https://lcd.example/txs?token=[REDACTED] body={} response=xxx"));
+        assert!(matches!(
+            e.into_without_context(),
+            TerraRustAPIError::TerraLCDResponse(StatusCode::NOT_FOUND, _)
+        ));
+    }
+}
@@ -0,0 +1,379 @@
+use crate::client::core_types::Coin;
+use crate::client::staking_types::ValidatorDelegation;
+use crate::client::tx_types::V1TXResponse;
+use crate::errors::TerraRustAPIError;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A query result that can be written as a row of a table
+pub trait ExportRow {
+    /// the column names. they are also the parquet field names, so stick to `[a-z_]`
+    fn columns() -> &'static [&'static str];
+    /// one value per column
+    fn row(&self) -> Vec<String>;
+}
+
+impl ExportRow for Coin {
+    fn columns() -> &'static [&'static str] {
+        &["denom", "amount"]
+    }
+    fn row(&self) -> Vec<String> {
+        vec![self.denom.clone(), self.amount.to_string()]
+    }
+}
+
+impl ExportRow for ValidatorDelegation {
+    fn columns() -> &'static [&'static str] {
+        &[
+            "delegator_address",
+            "validator_address",
+            "shares",
+            "denom",
+            "amount",
+        ]
+    }
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.delegation.delegator_address.clone(),
+            self.delegation.validator_address.clone(),
+            self.delegation.shares.to_string(),
+            self.balance.denom.clone(),
+            self.balance.amount.to_string(),
+        ]
+    }
+}
+
+/// coins as `1000uluna,20uusd`
+fn coin_list(coins: &serde_json::Value) -> String {
+    coins
+        .as_array()
+        .map(|coins| {
+            coins
+                .iter()
+                .map(|c| {
+                    format!(
+                        "{}{}",
+                        c["amount"].as_str().unwrap_or_default(),
+                        c["denom"].as_str().unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .unwrap_or_default()
+}
+
+impl ExportRow for V1TXResponse {
+    fn columns() -> &'static [&'static str] {
+        &[
+            "height",
+            "txhash",
+            "timestamp",
+            "code",
+            "gas_wanted",
+            "gas_used",
+            "fee",
+            "memo",
+        ]
+    }
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.height.to_string(),
+            self.txhash.clone(),
+            self.timestamp.to_rfc3339(),
+            self.code.to_string(),
+            self.gas_wanted.to_string(),
+            self.gas_used.to_string(),
+            coin_list(&self.tx["auth_info"]["fee"]["amount"]),
+            self.tx["body"]["memo"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        ]
+    }
+}
+
+/// one attribute of an event a transaction emitted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRow {
+    pub height: u64,
+    pub txhash: String,
+    pub msg_index: usize,
+    pub event_type: String,
+    pub key: String,
+    pub value: String,
+}
+impl EventRow {
+    /// every attribute of every event in the transaction's logs
+    pub fn from_tx(tx: &V1TXResponse) -> Vec<EventRow> {
+        tx.logs
+            .iter()
+            .flatten()
+            .enumerate()
+            .flat_map(|(i, log)| {
+                let msg_index = log.msg_index.unwrap_or(i);
+                log.events.iter().flat_map(move |event| {
+                    event.attributes.iter().map(move |attr| EventRow {
+                        height: tx.height,
+                        txhash: tx.txhash.clone(),
+                        msg_index,
+                        event_type: event.s_type.clone(),
+                        key: attr.key.clone(),
+                        value: attr.value.clone().unwrap_or_default(),
+                    })
+                })
+            })
+            .collect()
+    }
+}
+impl ExportRow for EventRow {
+    fn columns() -> &'static [&'static str] {
+        &[
+            "height",
+            "txhash",
+            "msg_index",
+            "event_type",
+            "key",
+            "value",
+        ]
+    }
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.height.to_string(),
+            self.txhash.clone(),
+            self.msg_index.to_string(),
+            self.event_type.clone(),
+            self.key.clone(),
+            self.value.clone(),
+        ]
+    }
+}
+
+/// file formats rows can be exported as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// needs the `parquet` feature
+    Parquet,
+}
+impl ExportFormat {
+    /// from a file's extension. anything but `.parquet` is CSV
+    pub fn from_path<P: AsRef<Path>>(path: P) -> ExportFormat {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("parquet") => ExportFormat::Parquet,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+impl FromStr for ExportFormat {
+    type Err = TerraRustAPIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            _ => Err(TerraRustAPIError::ExportFormat(s.into())),
+        }
+    }
+}
+
+enum Sink<W: Write + Send> {
+    Csv(csv::Writer<W>),
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::file::writer::SerializedFileWriter<W>),
+}
+
+/// Writes rows to a CSV or Parquet file, a page of query results at a time, so long histories
+/// don't have to be held in memory. Each page is a row group in parquet files.
+/// Every parquet column is UTF8 text, as amounts can be wider than 64 bits. Call [Exporter::finish] when done
+pub struct Exporter<W: Write + Send, R: ExportRow> {
+    sink: Sink<W>,
+    rows: usize,
+    row_type: PhantomData<R>,
+}
+impl<W: Write + Send, R: ExportRow> Exporter<W, R> {
+    pub fn create(writer: W, format: ExportFormat) -> Result<Exporter<W, R>, TerraRustAPIError> {
+        let sink = match format {
+            ExportFormat::Csv => {
+                let mut csv = csv::Writer::from_writer(writer);
+                csv.write_record(R::columns())?;
+                Sink::Csv(csv)
+            }
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet => Sink::Parquet(parquet_writer::create::<W, R>(writer)?),
+            #[cfg(not(feature = "parquet"))]
+            ExportFormat::Parquet => {
+                return Err(TerraRustAPIError::ExportFormat(
+                    "parquet (built without the parquet feature)".into(),
+                ))
+            }
+        };
+        Ok(Exporter {
+            sink,
+            rows: 0,
+            row_type: PhantomData,
+        })
+    }
+    /// add rows. returns how many were written
+    pub fn write<'r, I>(&mut self, rows: I) -> Result<usize, TerraRustAPIError>
+    where
+        I: IntoIterator<Item = &'r R>,
+        R: 'r,
+    {
+        let rows = rows.into_iter().map(ExportRow::row).collect::<Vec<_>>();
+        match &mut self.sink {
+            Sink::Csv(csv) => {
+                for row in &rows {
+                    csv.write_record(row)?;
+                }
+            }
+            #[cfg(feature = "parquet")]
+            Sink::Parquet(writer) => parquet_writer::row_group(writer, R::columns().len(), &rows)?,
+        }
+        self.rows += rows.len();
+        Ok(rows.len())
+    }
+    /// flush & close the file. returns the total number of rows
+    pub fn finish(self) -> Result<usize, TerraRustAPIError> {
+        match self.sink {
+            Sink::Csv(mut csv) => csv.flush()?,
+            #[cfg(feature = "parquet")]
+            Sink::Parquet(writer) => {
+                writer.close()?;
+            }
+        }
+        Ok(self.rows)
+    }
+}
+
+/// write all the rows to a file in one go
+pub fn export<'r, W, R, I>(
+    writer: W,
+    format: ExportFormat,
+    rows: I,
+) -> Result<usize, TerraRustAPIError>
+where
+    W: Write + Send,
+    R: ExportRow + 'r,
+    I: IntoIterator<Item = &'r R>,
+{
+    let mut exporter = Exporter::create(writer, format)?;
+    exporter.write(rows)?;
+    exporter.finish()
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_writer {
+    use super::ExportRow;
+    use crate::errors::TerraRustAPIError;
+    use parquet::data_type::{ByteArray, ByteArrayType};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    pub fn create<W: Write + Send, R: ExportRow>(
+        writer: W,
+    ) -> Result<SerializedFileWriter<W>, TerraRustAPIError> {
+        let fields = R::columns()
+            .iter()
+            .map(|c| format!("REQUIRED BYTE_ARRAY {} (UTF8);", c))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let schema = parse_message_type(&format!("message export {{ {} }}", fields))?;
+        Ok(SerializedFileWriter::new(
+            writer,
+            Arc::new(schema),
+            Arc::new(WriterProperties::builder().build()),
+        )?)
+    }
+
+    pub fn row_group<W: Write + Send>(
+        writer: &mut SerializedFileWriter<W>,
+        columns: usize,
+        rows: &[Vec<String>],
+    ) -> Result<(), TerraRustAPIError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let mut row_group = writer.next_row_group()?;
+        for column in 0..columns {
+            if let Some(mut writer) = row_group.next_column()? {
+                let values = rows
+                    .iter()
+                    .map(|row| ByteArray::from(row[column].as_str()))
+                    .collect::<Vec<_>>();
+                writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, None, None)?;
+                writer.close()?;
+            }
+        }
+        row_group.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_csv() -> anyhow::Result<()> {
+        let coins = vec![
+            Coin::create("uluna", dec!(1000)),
+            Coin::create("uusd", dec!(2.5)),
+        ];
+        let mut out = vec![];
+        assert_eq!(export(&mut out, ExportFormat::Csv, &coins)?, 2);
+        assert_eq!(
+            String::from_utf8(out)?,
+            "denom,amount\nuluna,1000\nuusd,2.5\n"
+        );
+        assert_eq!(
+            ExportFormat::from_path("txs.parquet"),
+            ExportFormat::Parquet
+        );
+        assert_eq!(ExportFormat::from_path("txs.csv"), ExportFormat::Csv);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_tx_rows() -> anyhow::Result<()> {
+        let tx: V1TXResponse = serde_json::from_str(
+            r#"{"height":"5","txhash":"AB","codespace":"","code":0,"data":"","raw_log":"","info":"",
+            "gas_wanted":"200000","gas_used":"150000","timestamp":"2022-01-02T03:04:05Z","events":null,
+            "tx":{"body":{"memo":"hi, there"},"auth_info":{"fee":{"amount":[{"denom":"uluna","amount":"100"}]}}},
+            "logs":[{"msg_index":0,"events":[{"type":"transfer","attributes":[{"key":"recipient","value":"terra1b"},{"key":"amount","value":"1uluna"}]}]}]}"#,
+        )?;
+        assert_eq!(tx.row()[6], "100uluna");
+        let events = EventRow::from_tx(&tx);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].key, "amount");
+        let mut exporter = Exporter::create(vec![], ExportFormat::Csv)?;
+        exporter.write(std::slice::from_ref(&tx))?;
+        assert_eq!(exporter.finish()?, 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    pub fn test_parquet() -> anyhow::Result<()> {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        let coins = vec![Coin::create("uluna", dec!(1000))];
+        let path = std::env::temp_dir().join(format!("terra-rust-{}.parquet", std::process::id()));
+        let mut exporter = Exporter::create(std::fs::File::create(&path)?, ExportFormat::Parquet)?;
+        exporter.write(&coins)?;
+        exporter.write(&coins)?;
+        assert_eq!(exporter.finish()?, 2);
+        let reader = SerializedFileReader::new(std::fs::File::open(&path)?)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        assert_eq!(reader.num_row_groups(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+/*!
+ * Follow the chain block by block, coping with reorgs.
+ *
+ * [BlockFollower] remembers the hashes of the last few blocks it handed out. When the node's chain
+ * goes backwards, or a new block doesn't build on the one before it, the follower looks for the last block
+ * both chains agree on, emits [BlockEvent::Reorg] from the block after it, and replays the blocks from there.
+ * Anything derived from blocks at or above `from_height` should be thrown away when a reorg arrives.
+ */
+use crate::tendermint_types::BlockResult;
+use crate::Terra;
+use futures::future::BoxFuture;
+use std::collections::VecDeque;
+
+/// where blocks come from
+pub trait BlockSource: Send + Sync {
+    fn latest(&self) -> BoxFuture<'_, anyhow::Result<BlockResult>>;
+    fn block_at(&self, height: u64) -> BoxFuture<'_, anyhow::Result<BlockResult>>;
+}
+impl BlockSource for Terra {
+    fn latest(&self) -> BoxFuture<'_, anyhow::Result<BlockResult>> {
+        Box::pin(async move { self.tendermint().blocks().await })
+    }
+    fn block_at(&self, height: u64) -> BoxFuture<'_, anyhow::Result<BlockResult>> {
+        Box::pin(async move { self.tendermint().blocks_at_height(height).await })
+    }
+}
+
+#[derive(Debug)]
+pub enum BlockEvent {
+    /// the next block
+    Block(Box<BlockResult>),
+    /// blocks from this height on were replaced. they will be sent again
+    Reorg { from_height: u64 },
+}
+
+pub struct BlockFollower<S: BlockSource> {
+    source: S,
+    /// (height, hash) of the most recent blocks
+    recent: VecDeque<(u64, String)>,
+    /// how many blocks to remember. a deeper reorg is an error
+    pub depth: usize,
+    start_height: Option<u64>,
+}
+impl<S: BlockSource> BlockFollower<S> {
+    /// follow from `start_height`, or from the latest block
+    pub fn create(source: S, start_height: Option<u64>) -> BlockFollower<S> {
+        BlockFollower {
+            source,
+            recent: VecDeque::new(),
+            depth: 100,
+            start_height,
+        }
+    }
+    /// the height of the last block handed out
+    pub fn tip(&self) -> Option<u64> {
+        self.recent.back().map(|(height, _)| *height)
+    }
+    fn remember(&mut self, block: &BlockResult) {
+        self.recent
+            .push_back((block.block.header.height, block.block_id.hash.clone()));
+        while self.recent.len() > self.depth {
+            self.recent.pop_front();
+        }
+    }
+    /// the highest block we have that the node still agrees with. forgets the ones after it.
+    /// blocks above the node's latest height are gone from its chain. a block that can't be fetched is an error,
+    /// not a fork, and leaves what we remember alone
+    async fn rewind(&mut self, latest_height: u64) -> anyhow::Result<u64> {
+        let recent = self.recent.iter().rev().cloned().collect::<Vec<_>>();
+        for (height, hash) in recent {
+            if height > latest_height {
+                continue;
+            }
+            if self.source.block_at(height).await?.block_id.hash == hash {
+                log::warn!("reorg: chain forked after height {}", height);
+                while matches!(self.tip(), Some(tip) if tip > height) {
+                    self.recent.pop_back();
+                }
+                return Ok(height);
+            }
+        }
+        Err(anyhow::anyhow!(
+            "reorg deeper than the {} blocks remembered",
+            self.depth
+        ))
+    }
+    /// everything that happened since the last poll. when a request fails part way,
+    /// the events so far are returned and the rest come with the next poll
+    pub async fn poll(&mut self) -> anyhow::Result<Vec<BlockEvent>> {
+        let mut events = vec![];
+        match self.poll_into(&mut events).await {
+            Ok(()) => Ok(events),
+            Err(e) if events.is_empty() => Err(e),
+            Err(e) => {
+                log::warn!("following blocks stopped short: {}", e);
+                Ok(events)
+            }
+        }
+    }
+    async fn poll_into(&mut self, events: &mut Vec<BlockEvent>) -> anyhow::Result<()> {
+        let latest = self.source.latest().await?;
+        let latest_height = latest.block.header.height;
+        if let Some((tip, tip_hash)) = self.recent.back() {
+            if latest_height < *tip || (latest_height == *tip && latest.block_id.hash != *tip_hash)
+            {
+                let fork = self.rewind(latest_height).await?;
+                events.push(BlockEvent::Reorg {
+                    from_height: fork + 1,
+                });
+            }
+        }
+        let mut height = match self.tip() {
+            Some(tip) => tip + 1,
+            None => self.start_height.unwrap_or(latest_height),
+        };
+        while height <= latest_height {
+            let block = self.source.block_at(height).await?;
+            if let Some((tip, tip_hash)) = self.recent.back() {
+                if *tip + 1 == height && block.block.header.last_block_id.hash != *tip_hash {
+                    let tip = *tip;
+                    let fork = self.rewind(latest_height).await?;
+                    if fork == tip {
+                        anyhow::bail!(
+                            "block {} does not build on block {}, which hasn't changed",
+                            height,
+                            tip
+                        );
+                    }
+                    events.push(BlockEvent::Reorg {
+                        from_height: fork + 1,
+                    });
+                    height = fork + 1;
+                    continue;
+                }
+            }
+            self.remember(&block);
+            events.push(BlockEvent::Block(Box::new(block)));
+            height += 1;
+        }
+        Ok(())
+    }
+    /// poll every `interval`, handing each event to `callback`. stops when it returns false
+    pub async fn follow<F: FnMut(BlockEvent) -> bool>(
+        &mut self,
+        interval: std::time::Duration,
+        mut callback: F,
+    ) -> anyhow::Result<()> {
+        loop {
+            for event in self.poll().await? {
+                if !callback(event) {
+                    return Ok(());
+                }
+            }
+            crate::runtime::sleep(interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// block hashes by height. the parent of each is the one before it.
+    /// asking for the height in the second field fails once, like a timeout
+    struct Chain(Mutex<Vec<String>>, Mutex<Option<u64>>);
+    impl Chain {
+        fn create(hashes: &[&str]) -> Chain {
+            Chain(
+                Mutex::new(hashes.iter().map(|s| s.to_string()).collect()),
+                Mutex::new(None),
+            )
+        }
+        fn block(&self, height: u64) -> anyhow::Result<BlockResult> {
+            if *self.1.lock().unwrap() == Some(height) {
+                *self.1.lock().unwrap() = None;
+                anyhow::bail!("timed out fetching block {}", height);
+            }
+            let chain = self.0.lock().unwrap();
+            let hash = chain
+                .get(height as usize)
+                .ok_or_else(|| anyhow::anyhow!("no block {}", height))?;
+            let parent = &chain[height.saturating_sub(1) as usize];
+            let id =
+                |hash: &str| serde_json::json!({"hash": hash, "parts": {"total": 1, "hash": ""}});
+            Ok(serde_json::from_value(serde_json::json!({
+                "block_id": id(hash),
+                "block": {
+                    "header": {
+                        "version": {"block": "11"},
+                        "chain_id": "test",
+                        "height": height.to_string(),
+                        "time": "2022-01-01T00:00:00Z",
+                        "last_block_id": id(parent),
+                        "last_commit_hash": "", "data_hash": "", "validators_hash": "",
+                        "next_validators_hash": "", "consensus_hash": "", "app_hash": "",
+                        "last_results_hash": "", "evidence_hash": "", "proposer_address": ""
+                    },
+                    "data": {"txs": null},
+                    "evidence": {},
+                    "last_commit": {"height": "0", "round": 0, "block_id": id(""), "signatures": []}
+                }
+            }))?)
+        }
+    }
+    impl BlockSource for Chain {
+        fn latest(&self) -> BoxFuture<'_, anyhow::Result<BlockResult>> {
+            let height = self.0.lock().unwrap().len() as u64 - 1;
+            Box::pin(async move { self.block(height) })
+        }
+        fn block_at(&self, height: u64) -> BoxFuture<'_, anyhow::Result<BlockResult>> {
+            Box::pin(async move { self.block(height) })
+        }
+    }
+    fn summary(events: &[BlockEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|e| match e {
+                BlockEvent::Block(b) => b.block_id.hash.clone(),
+                BlockEvent::Reorg { from_height } => format!("reorg {}", from_height),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    pub async fn test_reorg() -> anyhow::Result<()> {
+        let chain = Chain::create(&["a0", "a1", "a2", "a3"]);
+        let mut follower = BlockFollower::create(chain, Some(1));
+        assert_eq!(summary(&follower.poll().await?), vec!["a1", "a2", "a3"]);
+        // a new block on a fork from height 2
+        *follower.source.0.lock().unwrap() = ["a0", "a1", "b2", "b3", "b4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            summary(&follower.poll().await?),
+            vec!["reorg 2", "b2", "b3", "b4"]
+        );
+        // the chain went backwards onto another fork
+        *follower.source.0.lock().unwrap() = ["a0", "a1", "b2", "c3"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(summary(&follower.poll().await?), vec!["reorg 3", "c3"]);
+        assert_eq!(summary(&follower.poll().await?), Vec::<String>::new());
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_transient_errors() -> anyhow::Result<()> {
+        let chain = Chain::create(&["a0", "a1", "a2", "a3"]);
+        let mut follower = BlockFollower::create(chain, Some(1));
+        assert_eq!(summary(&follower.poll().await?), vec!["a1", "a2", "a3"]);
+        // block 3 was replaced. looking at block 2 times out: that isn't a fork at 2
+        *follower.source.0.lock().unwrap() = ["a0", "a1", "a2", "b3", "b4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        *follower.source.1.lock().unwrap() = Some(2);
+        assert!(follower.poll().await.is_err());
+        assert_eq!(follower.tip(), Some(3));
+        assert_eq!(
+            summary(&follower.poll().await?),
+            vec!["reorg 3", "b3", "b4"]
+        );
+        // a failure after some blocks hands those out, and picks up from there next time
+        follower
+            .source
+            .0
+            .lock()
+            .unwrap()
+            .extend(["b5".into(), "b6".into(), "b7".into()]);
+        *follower.source.1.lock().unwrap() = Some(6);
+        assert_eq!(summary(&follower.poll().await?), vec!["b5"]);
+        assert_eq!(summary(&follower.poll().await?), vec!["b6", "b7"]);
+        Ok(())
+    }
+}
@@ -1,8 +1,23 @@
 // mod address;
 // pub mod mnemonic_key;
+mod mnemonic;
+mod multisig;
 mod private;
 mod public;
 
-pub use private::PrivateKey;
+pub(crate) use mnemonic::is_word as is_mnemonic_word;
+pub use multisig::{LegacyAminoMultisig, MultisigSignature};
+pub use private::{DerivedAddress, PrivateKey};
 
 pub use public::PublicKey;
+
+use secp256k1::{All, Secp256k1};
+
+lazy_static! {
+    static ref SECP256K1: Secp256k1<All> = Secp256k1::new();
+}
+/// a signing & verification context shared by the whole process.
+/// building one precomputes tables, which costs more than most signatures do
+pub fn shared_secp256k1() -> &'static Secp256k1<All> {
+    &SECP256K1
+}
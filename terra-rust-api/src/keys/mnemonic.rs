@@ -0,0 +1,116 @@
+use crate::errors::TerraRustAPIError;
+use hkd32::mnemonic::{Language, Phrase};
+
+/// the words hkd32 generates and accepts. it only handles 24 word phrases
+const WORD_COUNT: usize = 24;
+
+/// hkd32's english word list, which it keeps private. each word is the first of the phrase
+/// made from entropy starting with its 11 bit index
+fn word_list() -> Vec<String> {
+    (0..2048u16)
+        .map(|index| {
+            let mut entropy = [0u8; 32];
+            entropy[0] = (index >> 3) as u8;
+            entropy[1] = ((index & 7) << 5) as u8;
+            let phrase = Phrase::from_entropy(entropy, Language::English);
+            phrase
+                .phrase()
+                .split(' ')
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect()
+}
+
+lazy_static! {
+    static ref WORD_LIST: Vec<String> = word_list();
+}
+
+/// is this one of the BIP39 english words
+pub(crate) fn is_word(word: &str) -> bool {
+    WORD_LIST.binary_search_by(|w| w.as_str().cmp(word)).is_ok()
+}
+
+/// words the unknown one may have been a typo of. BIP39 words are unique in their first 4 letters,
+/// so those are tried first, then the words one edit away
+pub(crate) fn suggestions(unknown: &str) -> Vec<&'static str> {
+    let prefix: String = unknown.chars().take(4).collect();
+    let by_prefix = WORD_LIST
+        .iter()
+        .map(String::as_str)
+        .filter(|w| prefix.len() == 4 && w.starts_with(&prefix))
+        .collect::<Vec<_>>();
+    if !by_prefix.is_empty() {
+        return by_prefix;
+    }
+    WORD_LIST
+        .iter()
+        .map(String::as_str)
+        .filter(|w| edit_distance(w, unknown) == 1)
+        .take(5)
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// check a seed phrase the way hkd32 does, but say what is wrong with it.
+/// the errors only give word positions, so the phrase can't end up in a log
+pub(crate) fn validate(phrase: &str) -> Result<(), TerraRustAPIError> {
+    let given = phrase.split_whitespace().collect::<Vec<_>>();
+    if given.len() != WORD_COUNT {
+        return Err(TerraRustAPIError::MnemonicWordCount(given.len()));
+    }
+    if let Some(position) = given.iter().position(|word| !is_word(word)) {
+        return Err(TerraRustAPIError::MnemonicUnknownWord(position + 1));
+    }
+    // every word is known, so all that is left to fail is the checksum
+    Phrase::new(given.join(" "), Language::English)
+        .map(|_| ())
+        .map_err(|_| TerraRustAPIError::MnemonicChecksum)
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    const PHRASE: &str = "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut";
+
+    #[test]
+    pub fn test_validate() {
+        assert_eq!(WORD_LIST.len(), 2048);
+        assert_eq!(WORD_LIST[0], "abandon");
+        assert_eq!(WORD_LIST[2047], "zoo");
+        assert!(WORD_LIST.windows(2).all(|w| w[0] < w[1]));
+        assert!(validate(PHRASE).is_ok());
+        assert!(validate(&PHRASE.replace(' ', "  ")).is_ok());
+        let typo = validate(&PHRASE.replace("dolphin", "dolphn"));
+        assert!(matches!(
+            typo,
+            Err(TerraRustAPIError::MnemonicUnknownWord(12))
+        ));
+        assert!(!typo.unwrap_err().to_string().contains("dolph"));
+        assert_eq!(suggestions("dolphn"), vec!["dolphin"]);
+        match validate(&PHRASE.replace("island relax ", "")) {
+            Err(TerraRustAPIError::MnemonicWordCount(22)) => {}
+            other => panic!("{:?}", other),
+        }
+        // a real word, in the wrong place
+        match validate(&PHRASE.replace("walnut", "island")) {
+            Err(TerraRustAPIError::MnemonicChecksum) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::core_types::{PubKeySig, StdSignature};
+use crate::errors::TerraRustAPIError;
+use crate::PublicKey;
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use serde::{Deserialize, Serialize};
+
+/// amino prefix of "tendermint/PubKeyMultisigThreshold"
+static AMINO_PREFIX_MULTISIG_THRESHOLD: [u8; 4] = [0x22, 0xc1, 0xf7, 0xe2];
+/// amino prefix of "tendermint/PubKeySecp256k1" (followed by the length of the key)
+static AMINO_PREFIX_SECP256K1: [u8; 5] = [0xeb, 0x5a, 0xe9, 0x87, 0x21];
+
+/// A legacy (amino) k-of-n multisig key.
+/// The public keys need to be in the same order as when the multisig was created
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct LegacyAminoMultisig {
+    /// number of signatures needed
+    #[serde(with = "crate::client::client_types::terra_u64_format")]
+    pub threshold: u64,
+    /// the keys that make up the multisig
+    pub pubkeys: Vec<PubKeySig>,
+}
+
+/// how a multisig key is presented in a transaction
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MultisigPubKeySig {
+    #[serde(rename = "type")]
+    pub stype: String,
+    pub value: LegacyAminoMultisig,
+}
+
+/// the combined signature of a multisig. broadcast in place of a [StdSignature]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MultisigSignature {
+    /// base64 of the amino encoded signatures
+    pub signature: String,
+    pub pub_key: MultisigPubKeySig,
+}
+
+impl LegacyAminoMultisig {
+    /// create a threshold multisig from its component keys
+    pub fn create(
+        threshold: u64,
+        pubkeys: Vec<PubKeySig>,
+    ) -> Result<LegacyAminoMultisig, TerraRustAPIError> {
+        if threshold == 0 || threshold as usize > pubkeys.len() {
+            return Err(TerraRustAPIError::MultisigThreshold(
+                threshold as usize,
+                pubkeys.len(),
+            ));
+        }
+        Ok(LegacyAminoMultisig { threshold, pubkeys })
+    }
+    /// amino binary encoding of the key. This is what the address is derived from
+    pub fn amino_bytes(&self) -> Result<Vec<u8>, TerraRustAPIError> {
+        let mut bytes = AMINO_PREFIX_MULTISIG_THRESHOLD.to_vec();
+        bytes.push(0x08);
+        put_uvarint(&mut bytes, self.threshold);
+        for pubkey in &self.pubkeys {
+            let key = base64::decode(&pubkey.value)?;
+            let encoded = [AMINO_PREFIX_SECP256K1.to_vec(), key].concat();
+            bytes.push(0x12);
+            put_uvarint(&mut bytes, encoded.len() as u64);
+            bytes.extend(encoded);
+        }
+        Ok(bytes)
+    }
+    /// the terra1 address of the multisig
+    pub fn account(&self) -> Result<String, TerraRustAPIError> {
+        let mut sha = Sha256::new();
+        let mut sha_result: [u8; 32] = [0; 32];
+        sha.input(&self.amino_bytes()?);
+        sha.result(&mut sha_result);
+        PublicKey::create(None, Some(sha_result[0..20].to_vec())).account()
+    }
+    /// combine the individual signer's signatures into the multisig's signature.
+    /// the signatures can be in any order, but all need to be from keys in the multisig
+    // `div_ceil` needs a newer Rust than the crate builds with
+    #[allow(unknown_lints, clippy::manual_div_ceil)]
+    pub fn combine(
+        &self,
+        signatures: &[StdSignature],
+    ) -> Result<MultisigSignature, TerraRustAPIError> {
+        let mut sigs: Vec<Option<Vec<u8>>> = vec![None; self.pubkeys.len()];
+        for signature in signatures {
+            let index = self
+                .pubkeys
+                .iter()
+                .position(|k| k.value == signature.pub_key.value)
+                .ok_or_else(|| {
+                    TerraRustAPIError::MultisigUnknownSigner(signature.pub_key.value.clone())
+                })?;
+            sigs[index] = Some(base64::decode(&signature.signature)?);
+        }
+        let signed = sigs.iter().filter(|s| s.is_some()).count();
+        if (signed as u64) < self.threshold {
+            return Err(TerraRustAPIError::MultisigNotEnoughSignatures(
+                signed,
+                self.threshold as usize,
+            ));
+        }
+
+        // CompactBitArray of who signed, followed by the signatures in key order
+        let mut elems = vec![0u8; (self.pubkeys.len() + 7) / 8];
+        for (i, sig) in sigs.iter().enumerate() {
+            if sig.is_some() {
+                elems[i / 8] |= 1 << (7 - (i % 8));
+            }
+        }
+        let mut bit_array = vec![];
+        let extra_bits = (self.pubkeys.len() % 8) as u64;
+        if extra_bits != 0 {
+            bit_array.push(0x08);
+            put_uvarint(&mut bit_array, extra_bits);
+        }
+        bit_array.push(0x12);
+        put_uvarint(&mut bit_array, elems.len() as u64);
+        bit_array.extend(elems);
+
+        let mut bytes = vec![0x0a];
+        put_uvarint(&mut bytes, bit_array.len() as u64);
+        bytes.extend(bit_array);
+        for sig in sigs.into_iter().flatten() {
+            bytes.push(0x12);
+            put_uvarint(&mut bytes, sig.len() as u64);
+            bytes.extend(sig);
+        }
+
+        Ok(MultisigSignature {
+            signature: base64::encode(bytes),
+            pub_key: MultisigPubKeySig {
+                stype: "tendermint/PubKeyMultisigThreshold".into(),
+                value: self.clone(),
+            },
+        })
+    }
+}
+
+fn put_uvarint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    fn key(n: u8) -> PubKeySig {
+        let mut raw = [n; 33];
+        raw[0] = 0x02;
+        PubKeySig {
+            stype: "tendermint/PubKeySecp256k1".into(),
+            value: base64::encode(raw),
+        }
+    }
+    fn sig(n: u8) -> StdSignature {
+        StdSignature {
+            signature: base64::encode([n; 64]),
+            pub_key: key(n),
+        }
+    }
+    #[test]
+    pub fn test_combine() -> anyhow::Result<()> {
+        let multisig = LegacyAminoMultisig::create(2, vec![key(1), key(2), key(3)])?;
+        assert!(multisig.account()?.starts_with("terra1"));
+        // out of order on purpose
+        let combined = multisig.combine(&[sig(3), sig(1)])?;
+        let bytes = base64::decode(&combined.signature)?;
+        // 3 bits, keys 0 & 2 set
+        assert_eq!(bytes[0..7], [0x0a, 0x05, 0x08, 0x03, 0x12, 0x01, 0xa0]);
+        assert_eq!(bytes[7..9], [0x12, 0x40]);
+        assert_eq!(bytes[9..73], [1; 64]);
+        assert_eq!(bytes[73..75], [0x12, 0x40]);
+        assert_eq!(bytes[75..], [3; 64]);
+        let js = serde_json::to_value(&combined)?;
+        assert_eq!(js["pub_key"]["value"]["threshold"], "2");
+
+        assert!(multisig.combine(&[sig(1)]).is_err());
+        assert!(multisig.combine(&[sig(1), sig(4)]).is_err());
+        assert!(LegacyAminoMultisig::create(4, vec![key(1), key(2), key(3)]).is_err());
+        Ok(())
+    }
+}
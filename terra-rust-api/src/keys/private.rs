@@ -1,20 +1,41 @@
 use crate::core_types::StdSignature;
-use crate::keys::PublicKey;
+use crate::keys::{mnemonic, PublicKey};
 use bitcoin::util::bip32::{ExtendedPrivKey, IntoDerivationPath};
 use bitcoin::Network;
 use crypto::sha2::Sha256;
 use secp256k1::Message;
 use secp256k1::Secp256k1;
+use secp256k1::Signature;
 
 use crypto::digest::Digest;
 use hkd32::mnemonic::{Phrase, Seed};
 
 use crate::errors::TerraRustAPIError;
 use rand_core::OsRng;
+use serde::Serialize;
+use std::ops::Range;
 
 /// This is the coin type used in most derivations
 pub static LUNA_COIN_TYPE: u32 = 330;
 
+/// An address derived from a seed phrase, and its place in the derivation path `m/44'/coin_type'/account'/0/index`
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DerivedAddress {
+    pub coin_type: u32,
+    pub account: u32,
+    pub index: u32,
+    pub address: String,
+}
+impl DerivedAddress {
+    /// the derivation path, like `m/44'/330'/0'/0/1`
+    pub fn path(&self) -> String {
+        format!(
+            "m/44'/{}'/{}'/0/{}",
+            self.coin_type, self.account, self.index
+        )
+    }
+}
+
 /// The Private key structure that is used to generate signatures and public keys
 /// WARNING: No Security Audit has been performed
 #[derive(Clone)]
@@ -64,7 +85,7 @@ impl PrivateKey {
             Ok(phrase) => {
                 PrivateKey::gen_private_key_phrase(secp, phrase, account, index, LUNA_COIN_TYPE, "")
             }
-            Err(_) => Err(TerraRustAPIError::Phrasing),
+            Err(_) => Err(PrivateKey::phrase_error(words)),
         }
     }
 
@@ -78,7 +99,7 @@ impl PrivateKey {
             Ok(phrase) => {
                 PrivateKey::gen_private_key_phrase(secp, phrase, 0, 0, LUNA_COIN_TYPE, seed_pass)
             }
-            Err(_) => Err(TerraRustAPIError::Phrasing),
+            Err(_) => Err(PrivateKey::phrase_error(words)),
         }
     }
 
@@ -98,6 +119,91 @@ impl PrivateKey {
         self.private_key.private_key.public_key(secp).to_bytes()
     }
 
+    /// check a seed phrase, saying which word isn't in the word list, or if there are the wrong
+    /// number of words, or the checksum is wrong. errors give word positions, never the words
+    pub fn validate_words(words: &str) -> Result<(), TerraRustAPIError> {
+        mnemonic::validate(words)
+    }
+
+    /// the word list words `word` may be a typo of
+    pub fn word_suggestions(word: &str) -> Vec<&'static str> {
+        mnemonic::suggestions(word)
+    }
+
+    /// why hkd32 rejected the phrase
+    fn phrase_error(words: &str) -> TerraRustAPIError {
+        match mnemonic::validate(words) {
+            Err(e) => e,
+            // it splits on single spaces only
+            Ok(()) => TerraRustAPIError::Phrasing,
+        }
+    }
+
+    /// every address for `account_range` x `index_range` of the seed phrase, a row per account.
+    /// for finding which derivation path holds funds that went missing. `coin_type` is usually
+    /// [LUNA_COIN_TYPE], or 118 for keys made by cosmos wallets
+    pub fn derive_addresses<C: secp256k1::Signing + secp256k1::Context>(
+        secp: &Secp256k1<C>,
+        words: &str,
+        coin_type: u32,
+        account_range: Range<u32>,
+        index_range: Range<u32>,
+    ) -> Result<Vec<Vec<DerivedAddress>>, TerraRustAPIError> {
+        PrivateKey::derive_addresses_seed(secp, words, "", coin_type, account_range, index_range)
+    }
+
+    /// [PrivateKey::derive_addresses] for a seed phrase with a passphrase
+    pub fn derive_addresses_seed<C: secp256k1::Signing + secp256k1::Context>(
+        secp: &Secp256k1<C>,
+        words: &str,
+        seed_pass: &str,
+        coin_type: u32,
+        account_range: Range<u32>,
+        index_range: Range<u32>,
+    ) -> Result<Vec<Vec<DerivedAddress>>, TerraRustAPIError> {
+        let phrase = hkd32::mnemonic::Phrase::new(words, hkd32::mnemonic::Language::English)
+            .map_err(|_| PrivateKey::phrase_error(words))?;
+        // the seed is the slow part, so it is only done once
+        let root_private_key = PrivateKey::root_private_key(&phrase, seed_pass);
+        account_range
+            .map(|account| {
+                index_range
+                    .clone()
+                    .map(|index| {
+                        let key =
+                            PrivateKey::derive(secp, &root_private_key, account, index, coin_type)?;
+                        Ok(DerivedAddress {
+                            coin_type,
+                            account,
+                            index,
+                            address: PublicKey::from_bitcoin_public_key(
+                                &key.private_key.public_key(secp),
+                            )
+                            .account()?,
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn root_private_key(phrase: &Phrase, seed_phrase: &str) -> ExtendedPrivKey {
+        let seed = phrase.to_seed(seed_phrase);
+        ExtendedPrivKey::new_master(Network::Bitcoin, seed.as_bytes()).unwrap()
+    }
+
+    fn derive<C: secp256k1::Signing + secp256k1::Context>(
+        secp: &Secp256k1<C>,
+        root_private_key: &ExtendedPrivKey,
+        account: u32,
+        index: u32,
+        coin_type: u32,
+    ) -> Result<ExtendedPrivKey, TerraRustAPIError> {
+        let path = format!("m/44'/{}'/{}'/0/{}", coin_type, account, index);
+        let derivation_path = path.into_derivation_path()?;
+        Ok(root_private_key.derive_priv(secp, &derivation_path)?)
+    }
+
     fn gen_private_key_phrase<C: secp256k1::Signing + secp256k1::Context>(
         secp: &Secp256k1<C>,
         phrase: Phrase,
@@ -106,13 +212,8 @@ impl PrivateKey {
         coin_type: u32,
         seed_phrase: &str,
     ) -> Result<PrivateKey, TerraRustAPIError> {
-        let seed = phrase.to_seed(seed_phrase);
-        let root_private_key =
-            ExtendedPrivKey::new_master(Network::Bitcoin, seed.as_bytes()).unwrap();
-        let path = format!("m/44'/{}'/{}'/0/{}", coin_type, account, index);
-        let derivation_path = path.into_derivation_path()?;
-
-        let private_key = root_private_key.derive_priv(secp, &derivation_path)?;
+        let root_private_key = PrivateKey::root_private_key(&phrase, seed_phrase);
+        let private_key = PrivateKey::derive(secp, &root_private_key, account, index, coin_type)?;
         Ok(PrivateKey {
             account,
             index,
@@ -149,6 +250,36 @@ impl PrivateKey {
         Ok(sig)
     }
 
+    /// like [PrivateKey::sign], with the form of the signature guaranteed: the nonce is RFC6979's,
+    /// so the same key and blob always give the same signature, and S is in the lower half of the
+    /// curve order, as nodes reject signatures with a high S
+    pub fn sign_canonical<C: secp256k1::Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        blob: &str,
+    ) -> Result<StdSignature, TerraRustAPIError> {
+        let pub_k = &self.private_key.private_key.public_key(secp);
+        let priv_k = self.private_key.private_key.key;
+        let mut sha = Sha256::new();
+        let mut sha_result: [u8; 32] = [0; 32];
+        sha.input_str(blob);
+        sha.result(&mut sha_result);
+
+        let message: Message = Message::from_slice(&sha_result)?;
+        // libsecp256k1 signs with the RFC6979 nonce, and gives a low S already.
+        // normalizing costs little, and doesn't rely on that
+        let mut signature = secp.sign(&message, &priv_k);
+        signature.normalize_s();
+        Ok(StdSignature::create(&signature.serialize_compact(), pub_k))
+    }
+    /// is a 64 byte compact signature's S in the lower half of the curve order
+    pub fn is_low_s(compact: &[u8]) -> Result<bool, TerraRustAPIError> {
+        let signature = Signature::from_compact(compact)?;
+        let mut normalized = signature;
+        normalized.normalize_s();
+        Ok(normalized == signature)
+    }
+
     /// signs a blob of data and returns a [StdSignature]
     pub fn sign_bytes<C: secp256k1::Signing + secp256k1::Context>(
         &self,
@@ -255,4 +386,70 @@ mod tst {
 
         Ok(())
     }
+    #[test]
+    pub fn test_sign_canonical() -> anyhow::Result<()> {
+        let str_1 =  "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut";
+        let secp = Secp256k1::new();
+        let pk = PrivateKey::from_words(&secp, str_1, 0, 0)?;
+        let to_sign = r#"{"account_number":"45","chain_id":"columbus-3-testnet"}"#;
+
+        let sig = pk.sign_canonical(&secp, to_sign)?;
+        assert_eq!(sig.signature, pk.sign_canonical(&secp, to_sign)?.signature);
+        assert_eq!(sig.signature, pk.sign(&secp, to_sign)?.signature);
+        let compact = base64::decode(&sig.signature)?;
+        assert!(PrivateKey::is_low_s(&compact)?);
+
+        let mut sha = Sha256::new();
+        let mut sha_result: [u8; 32] = [0; 32];
+        sha.input_str(to_sign);
+        sha.result(&mut sha_result);
+        let message = Message::from_slice(&sha_result)?;
+        let pub_k = secp256k1::PublicKey::from_slice(&base64::decode(&sig.pub_key.value)?)?;
+        let signature = Signature::from_compact(&compact)?;
+        assert!(secp.verify(&message, &signature, &pub_k).is_ok());
+
+        // the same signature with S flipped to N - S is valid maths, but high
+        const N: [u8; 32] = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c,
+            0xd0, 0x36, 0x41, 0x41,
+        ];
+        let mut high = compact.clone();
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let d = N[i] as i16 - compact[32 + i] as i16 - borrow;
+            borrow = i16::from(d < 0);
+            high[32 + i] = (d + 256 * borrow) as u8;
+        }
+        assert!(!PrivateKey::is_low_s(&high)?);
+        let mut high_signature = Signature::from_compact(&high)?;
+        assert!(secp.verify(&message, &high_signature, &pub_k).is_err());
+        high_signature.normalize_s();
+        assert_eq!(high_signature, signature);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_derive_addresses() -> anyhow::Result<()> {
+        let str_1 = "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut";
+        let secp = Secp256k1::new();
+        let matrix = PrivateKey::derive_addresses(&secp, str_1, LUNA_COIN_TYPE, 0..2, 1..4)?;
+        assert_eq!(matrix.len(), 2);
+        assert!(matrix.iter().all(|row| row.len() == 3));
+        for derived in matrix.iter().flatten() {
+            let pk = PrivateKey::from_words(&secp, str_1, derived.account, derived.index)?;
+            assert_eq!(derived.address, pk.public_key(&secp).account()?);
+        }
+        assert_eq!((matrix[1][0].account, matrix[1][0].index), (1, 1));
+        assert_eq!(matrix[1][0].path(), "m/44'/330'/1'/0/1");
+        let cosmos = PrivateKey::derive_addresses(&secp, str_1, 118, 0..1, 0..1)?;
+        let pk = PrivateKey::from_words_seed(&secp, str_1, "")?;
+        assert_ne!(cosmos[0][0].address, pk.public_key(&secp).account()?);
+        assert_eq!(cosmos[0][0].path(), "m/44'/118'/0'/0/0");
+        assert!(
+            PrivateKey::derive_addresses(&secp, "not a phrase", LUNA_COIN_TYPE, 0..1, 0..1)
+                .is_err()
+        );
+        Ok(())
+    }
 }
@@ -6,6 +6,7 @@ use crypto::ripemd160::Ripemd160;
 use crypto::sha2::Sha256;
 pub use ed25519_dalek::PublicKey as Ed25519;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 static BECH32_PUBKEY_DATA_PREFIX_SECP256K1: [u8; 5] = [0xeb, 0x5a, 0xe9, 0x87, 0x21]; // "eb5ae98721";
 static BECH32_PUBKEY_DATA_PREFIX_ED25519: [u8; 5] = [0x16, 0x24, 0xde, 0x64, 0x20]; // "eb5ae98721";
 
@@ -13,15 +14,64 @@ static BECH32_PUBKEY_DATA_PREFIX_ED25519: [u8; 5] = [0x16, 0x24, 0xde, 0x64, 0x2
 /// The public key we used to generate the cosmos/tendermind/terrad addresses
 pub struct PublicKey {
     /// This is optional as we can generate non-pub keys without
-    pub raw_pub_key: Option<Vec<u8>>,
+    raw_pub_key: Option<Vec<u8>>,
     /// The raw bytes used to generate non-pub keys
-    pub raw_address: Option<Vec<u8>>,
+    raw_address: Option<Vec<u8>>,
+    /// the bech32 strings, encoded the first time they are asked for.
+    /// the raw fields are private so they can't change underneath it
+    #[serde(skip)]
+    cache: Bech32Cache,
+}
+#[derive(Debug, Default)]
+struct Bech32Cache {
+    account: Mutex<Option<String>>,
+    operator_address: Mutex<Option<String>>,
+    application_public_key: Mutex<Option<String>>,
+}
+impl Clone for Bech32Cache {
+    fn clone(&self) -> Self {
+        let copy = |cell: &Mutex<Option<String>>| Mutex::new(cell.lock().unwrap().clone());
+        Bech32Cache {
+            account: copy(&self.account),
+            operator_address: copy(&self.operator_address),
+            application_public_key: copy(&self.application_public_key),
+        }
+    }
+}
+fn cached<F>(cell: &Mutex<Option<String>>, encode: F) -> Result<String, TerraRustAPIError>
+where
+    F: FnOnce() -> Result<String, TerraRustAPIError>,
+{
+    let mut value = cell.lock().unwrap();
+    if let Some(value) = value.as_ref() {
+        return Ok(value.clone());
+    }
+    let encoded = encode()?;
+    *value = Some(encoded.clone());
+    Ok(encoded)
 }
 /*
 upgrade eventually to support
 Variant::Bech32M ?
  */
 impl PublicKey {
+    /// a key from its raw bytes: the amino encoded public key, if known, and the 20 byte address.
+    /// the bytes aren't checked, use the `from_*` constructors for that
+    pub fn create(raw_pub_key: Option<Vec<u8>>, raw_address: Option<Vec<u8>>) -> PublicKey {
+        PublicKey {
+            raw_pub_key,
+            raw_address,
+            cache: Bech32Cache::default(),
+        }
+    }
+    /// the amino encoded public key, if known
+    pub fn raw_pub_key(&self) -> Option<&[u8]> {
+        self.raw_pub_key.as_deref()
+    }
+    /// the raw bytes the addresses are encoded from
+    pub fn raw_address(&self) -> Option<&[u8]> {
+        self.raw_address.as_deref()
+    }
     /// Generate a Cosmos/Tendermint/Terrad Public Key
     pub fn from_bitcoin_public_key(bpub: &bitcoin::util::key::PublicKey) -> PublicKey {
         let bpub_bytes = bpub.key.serialize();
@@ -29,20 +79,14 @@ impl PublicKey {
         let raw_pub_key = PublicKey::pubkey_from_public_key(&bpub_bytes);
         let raw_address = PublicKey::address_from_public_key(&bpub_bytes);
 
-        PublicKey {
-            raw_pub_key: Some(raw_pub_key),
-            raw_address: Some(raw_address),
-        }
+        PublicKey::create(Some(raw_pub_key), Some(raw_address))
     }
     /// Generate from secp256k1 Cosmos/Terrad Public Key
     pub fn from_public_key(bpub: &[u8]) -> PublicKey {
         let raw_pub_key = PublicKey::pubkey_from_public_key(bpub);
         let raw_address = PublicKey::address_from_public_key(bpub);
 
-        PublicKey {
-            raw_pub_key: Some(raw_pub_key),
-            raw_address: Some(raw_address),
-        }
+        PublicKey::create(Some(raw_pub_key), Some(raw_address))
     }
     /// Generate a Cosmos/Tendermint/Terrad Account
     pub fn from_account(acc_address: &str) -> Result<PublicKey, TerraRustAPIError> {
@@ -53,10 +97,19 @@ impl PublicKey {
                     source,
                 }
             })?;
-            Ok(PublicKey {
-                raw_pub_key: None,
-                raw_address: Some(vu8),
-            })
+            Ok(PublicKey::create(None, Some(vu8)))
+        })
+    }
+    /// an address from a contract's. they hold 32 bytes, rather than an account's 20
+    pub fn from_contract(contract_address: &str) -> Result<PublicKey, TerraRustAPIError> {
+        PublicKey::check_prefix_and_length("terra", contract_address, 64).and_then(|vu5| {
+            let vu8 = Vec::from_base32(vu5.as_slice()).map_err(|source| {
+                TerraRustAPIError::Conversion {
+                    key: contract_address.into(),
+                    source,
+                }
+            })?;
+            Ok(PublicKey::create(None, Some(vu8)))
         })
     }
     /// build a public key from a tendermint public key
@@ -81,10 +134,7 @@ impl PublicKey {
                         let public_key = PublicKey::public_key_from_pubkey(&vu8)?;
                         let raw = PublicKey::address_from_public_key(&public_key);
 
-                        Ok(PublicKey {
-                            raw_pub_key: Some(vu8),
-                            raw_address: Some(raw),
-                        })
+                        Ok(PublicKey::create(Some(vu8), Some(raw)))
                     } else {
                         Err(TerraRustAPIError::ConversionSECP256k1)
                     }
@@ -106,10 +156,7 @@ impl PublicKey {
                     if vu8.starts_with(&BECH32_PUBKEY_DATA_PREFIX_ED25519) {
                         //   let public_key = PublicKey::pubkey_from_ed25519_public_key(&vu8);
                         let raw = PublicKey::address_from_public_ed25519_key(&vu8)?;
-                        Ok(PublicKey {
-                            raw_pub_key: Some(vu8),
-                            raw_address: Some(raw),
-                        })
+                        Ok(PublicKey::create(Some(vu8), Some(raw)))
                     } else {
                         //     eprintln!("{}", hex::encode(&vu8));
                         Err(TerraRustAPIError::ConversionED25519)
@@ -129,10 +176,7 @@ impl PublicKey {
         let len = tendermint_hex_address.len();
         if len == 40 {
             let raw = hex::decode(tendermint_hex_address)?;
-            Ok(PublicKey {
-                raw_pub_key: None,
-                raw_address: Some(raw),
-            })
+            Ok(PublicKey::create(None, Some(raw)))
         } else {
             Err(TerraRustAPIError::ConversionLengthED25519Hex(len))
         }
@@ -146,10 +190,7 @@ impl PublicKey {
                     source,
                 }
             })?;
-            Ok(PublicKey {
-                raw_pub_key: None,
-                raw_address: Some(vu8),
-            })
+            Ok(PublicKey::create(None, Some(vu8)))
         })
     }
 
@@ -157,10 +198,7 @@ impl PublicKey {
     pub fn from_raw_address(raw_address: &str) -> Result<PublicKey, TerraRustAPIError> {
         let vec1 = hex::decode(raw_address)?;
 
-        Ok(PublicKey {
-            raw_pub_key: None,
-            raw_address: Some(vec1),
-        })
+        Ok(PublicKey::create(None, Some(vec1)))
     }
     fn check_prefix_and_length(
         prefix: &str,
@@ -294,7 +332,7 @@ impl PublicKey {
     }
     /// The main account used in most things
     pub fn account(&self) -> Result<String, TerraRustAPIError> {
-        match &self.raw_address {
+        cached(&self.cache.account, || match &self.raw_address {
             Some(raw) => {
                 let data = encode("terra", raw.to_base32(), Variant::Bech32);
                 match data {
@@ -303,11 +341,11 @@ impl PublicKey {
                 }
             }
             None => Err(TerraRustAPIError::Implementation),
-        }
+        })
     }
     /// The operator address used for validators
     pub fn operator_address(&self) -> Result<String, TerraRustAPIError> {
-        match &self.raw_address {
+        cached(&self.cache.operator_address, || match &self.raw_address {
             Some(raw) => {
                 let data = encode("terravaloper", raw.to_base32(), Variant::Bech32);
                 match data {
@@ -316,23 +354,25 @@ impl PublicKey {
                 }
             }
             None => Err(TerraRustAPIError::Implementation),
-        }
+        })
     }
     /// application public key - Application keys are associated with a public key terrapub- and an address terra-
     pub fn application_public_key(&self) -> Result<String, TerraRustAPIError> {
-        match &self.raw_pub_key {
-            Some(raw) => {
-                let data = encode("terrapub", raw.to_base32(), Variant::Bech32);
-                match data {
-                    Ok(acc) => Ok(acc),
-                    Err(_) => Err(TerraRustAPIError::Bech32DecodeErr),
+        cached(&self.cache.application_public_key, || {
+            match &self.raw_pub_key {
+                Some(raw) => {
+                    let data = encode("terrapub", raw.to_base32(), Variant::Bech32);
+                    match data {
+                        Ok(acc) => Ok(acc),
+                        Err(_) => Err(TerraRustAPIError::Bech32DecodeErr),
+                    }
+                }
+                None => {
+                    log::warn!("Missing Public Key. Can't continue");
+                    Err(TerraRustAPIError::Implementation)
                 }
             }
-            None => {
-                log::warn!("Missing Public Key. Can't continue");
-                Err(TerraRustAPIError::Implementation)
-            }
-        }
+        })
     }
     /// The operator address used for validators public key.
     pub fn operator_address_public_key(&self) -> Result<String, TerraRustAPIError> {
@@ -384,6 +424,24 @@ mod tst {
     #[allow(unused_imports)]
     use env_logger;
     #[test]
+    pub fn test_cached_bech32() -> anyhow::Result<()> {
+        let key = PublicKey::from_public_key(&hex::decode(
+            "02A1633CAFCC01EBFB6D78E39F687A1F0995C62FC95F51EAD10A02EE0BE551B5DC",
+        )?);
+        assert!(key.cache.account.lock().unwrap().is_none());
+        let account = key.account()?;
+        assert_eq!(*key.cache.account.lock().unwrap(), Some(account.clone()));
+        assert_eq!(key.account()?, account);
+        let copy: PublicKey = serde_json::from_str(&serde_json::to_string(&key)?)?;
+        assert!(copy.cache.account.lock().unwrap().is_none());
+        assert_eq!(copy.account()?, account);
+        assert_eq!(
+            PublicKey::from_account(&account)?.operator_address()?,
+            key.operator_address()?
+        );
+        Ok(())
+    }
+    #[test]
     pub fn tst_conv() -> anyhow::Result<()> {
         let pub_key = PublicKey::from_account("terra1jnzv225hwl3uxc5wtnlgr8mwy6nlt0vztv3qqm")?;
 
@@ -24,29 +24,29 @@
 * tokio = { version = "1.4", features = ["full"] }
 * ```
 * ```
-* use terra_rust_api::{Terra, GasOptions, PrivateKey};
+* use terra_rust_api::{shared_secp256k1, Terra, GasOptions, PrivateKey};
 * use terra_rust_api::core_types::{Coin, StdSignMsg, StdSignature};
 * use terra_rust_api::messages::{MsgSend, Message};
-* use bitcoin::secp256k1::Secp256k1;
 *
 *
+* #[cfg(feature = "tx")]
 * async fn demo() -> anyhow::Result<()> {
 * // set up the LCD client
 * let gas_opts = GasOptions::create_with_gas_estimate("50ukrw",1.4)?;
 * let terra = Terra::lcd_client("https://bombay-lcd.terra.dev/", "bombay-12", &gas_opts,None);
-* // generate a private key
-* let secp = Secp256k1::new();
-* let from_key = PrivateKey::from_words(&secp,"your secret words",0,0)?;
-* let from_public_key = from_key.public_key(&secp);
+* // generate a private key, with the shared signing context
+* let secp = shared_secp256k1();
+* let from_key = PrivateKey::from_words(secp,"your secret words",0,0)?;
+* let from_public_key = from_key.public_key(secp);
 * // generate the message SEND 1000 uluna from your private key to someone else
 * let coin: Coin = Coin::parse("1000uluna")?.unwrap();
 * let from_account = from_public_key.account()?;
-* let send: Message = MsgSend::create(from_account, String::from("terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh"), vec![coin])?;
+* let send: Message = MsgSend::create_single(from_account, "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh", coin)?;
 * // generate the transaction & calc fees
 * let messages: Vec<Message> = vec![send];
 * let (std_sign_msg, sigs) = terra
 *                .generate_transaction_to_broadcast(
-*                    &secp,
+*                    secp,
 *                    &from_key,
 *                    messages,
 *                    None
@@ -66,16 +66,62 @@
 * Ok(())
 * }
 * ```
+* # Features
+* `full` (the default) turns everything on. If you only need keys and the basic LCD queries,
+* turn off default features and pick from
+* * `tx` fee calculation, signing, broadcasting and fetching transactions. polls sleep with any executor's timer, see [runtime],
+*   but requests still need a Tokio reactor
+* * `wasm` smart contract queries & execution, and the cw20 helpers (implies `tx`)
+* * `rpc` the tendermint RPC endpoints
+* * `fcd` the FCD endpoints
+* * `export` query results as CSV files
+* * `tokio` the address book watcher and LCDs on unix sockets, which need a tokio runtime
+*
+* `rayon` (off by default) checks [core_types::StdSignature::verify_batch] in parallel
+*
+* `export` writes query results to CSV files, see [export]. `parquet` (off by default) adds Parquet files
 */
 /// address book definition
 pub mod addressbook;
+/// a record of every transaction signed
+#[cfg(feature = "tx")]
+pub mod audit;
 /// APIs
 pub mod client;
 /// Error Messages
 pub mod errors;
+/// query results as CSV or Parquet files
+#[cfg(feature = "export")]
+pub mod export;
+/// follow the chain block by block, coping with reorgs
+pub mod follow;
 mod keys;
+/// verify block headers without trusting the LCD
+pub mod light;
 /// definitions of the different type of Messages we have implemented
 pub mod messages;
+/// compare the health of several LCD and RPC endpoints
+#[cfg(feature = "rpc")]
+pub mod monitor;
+/// report transactions as they are broadcast, included or fail
+#[cfg(feature = "tx")]
+pub mod notify;
+/// price averaging for oracle feeders
+pub mod pricing;
+/// verify merkle proofs of the app's state
+pub mod proof;
+mod protobuf;
+/// masking secrets in log output
+pub mod redact;
+/// endpoints & gas prices from the cosmos chain registry
+#[cfg(feature = "rpc")]
+pub mod registry;
+/// sleeping between polls, under tokio or any other executor
+pub mod runtime;
+/// sign doc & signature fixtures, for checking other SDKs against this one
+pub mod test_vectors;
+/// decode the transactions in a block
+pub mod tx_decode;
 
 #[macro_use]
 extern crate lazy_static;
@@ -93,10 +139,29 @@ pub use crate::client_types::{
     terra_opt_u64_format, terra_u64_format,
 };
 pub use addressbook::AddressBook;
+pub use client::bank_types::{DenomMetadata, DenomUnit};
+pub use client::builder::TerraBuilder;
+pub use client::chain_manager::ChainManager;
+pub use client::coin_format::CoinFormatter;
+pub use client::denom_registry::DenomRegistry;
+#[cfg(feature = "tx")]
+pub use client::escalation::FeeEscalation;
 pub use client::lcd_types::{LCDResult, LCDResultVec, LCDTypeValue};
-pub use client::{auth_types, client_types, core_types, staking_types, tendermint_types};
-pub use client::{GasOptions, Terra};
-pub use keys::{PrivateKey, PublicKey};
+pub use client::memo::{MemoPolicy, MemoTemplate};
+pub use client::pruned::{HeightSubstitution, PrunedHeights};
+pub use client::request_signing::{HmacSigner, KeySigner, RequestSigner};
+pub use client::snapshot::ConsistentSnapshot;
+#[cfg(feature = "tx")]
+pub use client::tx_queue::{TxLane, TxQueue, TxQueueOptions, TxTicket};
+pub use client::unknown_fields::UnknownFields;
+pub use client::{
+    auth_types, client_types, core_types, market_types, slashing_types, staking_types,
+    tendermint_types, treasury_types, upgrade_types,
+};
+pub use client::{ApiKey, ApiVersion, GasOptions, GasOptionsBuilder, HttpOptions, Terra, TxLimits};
+pub use keys::{
+    shared_secp256k1, DerivedAddress, LegacyAminoMultisig, MultisigSignature, PrivateKey, PublicKey,
+};
 pub use messages::bank;
 pub use messages::wasm::MsgExecuteContract;
-pub use messages::Message;
+pub use messages::{Address, Message};
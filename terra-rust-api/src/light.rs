@@ -0,0 +1,579 @@
+/*!
+ * Light client verification of Tendermint block headers.
+ *
+ * Instead of trusting whatever a single LCD says, start from a header (and validator set) you trust,
+ * and only accept newer headers that enough of the trusted validators have signed.
+ * * [verify_sequential] for the very next block. +2/3 of its validators must have signed it,
+ *   and they must be the validators the trusted header said would come next.
+ * * [verify_skipping] to jump ahead. More than `trust_level` (usually 1/3) of the validators the trusted
+ *   header said would come next must have signed it, as well as +2/3 of its own validators.
+ *
+ * Once a header is verified, its app_hash can be used to check proofs of the state behind queries.
+ */
+use crate::client::tendermint_types::{BlockCommit, BlockHeader, Validator};
+use crate::errors::TerraRustAPIError;
+use chrono::{DateTime, Duration, Utc};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use ed25519_dalek::Verifier;
+use std::convert::TryFrom;
+
+/// block_id_flag of a validator that signed the block
+const BLOCK_ID_FLAG_COMMIT: usize = 2;
+/// SignedMsgType of a precommit vote
+const PRECOMMIT_TYPE: u64 = 2;
+const ED25519_TYPE: &str = "tendermint/PubKeyEd25519";
+
+/// a header, the commit that signed it, and the validator set that produced it
+#[derive(Debug)]
+pub struct LightBlock {
+    pub header: BlockHeader,
+    /// the commit for `header`. This is the `last_commit` of the *next* block
+    pub commit: BlockCommit,
+    pub validators: Vec<Validator>,
+    /// the validator set of the next block, which `header.next_validators_hash` commits to
+    pub next_validators: Vec<Validator>,
+}
+
+/// the fraction of the trusted validators' voting power that must have signed a skipped-to header
+#[derive(Clone, Copy, Debug)]
+pub struct TrustLevel {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+impl Default for TrustLevel {
+    /// 1/3, the tendermint default
+    fn default() -> Self {
+        TrustLevel {
+            numerator: 1,
+            denominator: 3,
+        }
+    }
+}
+
+fn fail<T>(reason: String) -> Result<T, TerraRustAPIError> {
+    Err(TerraRustAPIError::LightClient(reason))
+}
+
+/// the LCD gives hashes in hex, newer endpoints in base64
+// `is_multiple_of` needs a newer Rust than the crate builds with
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+fn decode_bytes(s: &str) -> Result<Vec<u8>, TerraRustAPIError> {
+    if s.len() % 2 == 0 && s.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hex::decode(s)?)
+    } else {
+        Ok(base64::decode(s)?)
+    }
+}
+
+fn sha256(bytes: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.input(bytes);
+    let mut out = vec![0u8; 32];
+    hasher.result(&mut out);
+    out
+}
+
+/// the RFC-6962 merkle root tendermint uses
+pub fn merkle_root(items: &[Vec<u8>]) -> Vec<u8> {
+    match items.len() {
+        0 => sha256(&[]),
+        1 => sha256(&[&[0u8][..], &items[0]].concat()),
+        n => {
+            let split = n.next_power_of_two() / 2;
+            let left = merkle_root(&items[..split]);
+            let right = merkle_root(&items[split..]);
+            sha256(&[&[1u8][..], &left, &right].concat())
+        }
+    }
+}
+
+/// just enough protobuf to reproduce what tendermint hashes and signs
+pub(crate) mod proto {
+    use chrono::{DateTime, Utc};
+
+    pub fn uvarint(out: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+    fn key(out: &mut Vec<u8>, field: u64, wire_type: u64) {
+        uvarint(out, field << 3 | wire_type)
+    }
+    /// any of the varint types. zero is the default, so it is left out
+    pub fn varint(out: &mut Vec<u8>, field: u64, value: u64) {
+        if value != 0 {
+            key(out, field, 0);
+            uvarint(out, value);
+        }
+    }
+    pub fn sfixed64(out: &mut Vec<u8>, field: u64, value: i64) {
+        if value != 0 {
+            key(out, field, 1);
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    pub fn bytes(out: &mut Vec<u8>, field: u64, value: &[u8]) {
+        if !value.is_empty() {
+            key(out, field, 2);
+            uvarint(out, value.len() as u64);
+            out.extend_from_slice(value);
+        }
+    }
+    /// an embedded message. these are always written, even if empty
+    pub fn message(out: &mut Vec<u8>, field: u64, value: &[u8]) {
+        key(out, field, 2);
+        uvarint(out, value.len() as u64);
+        out.extend_from_slice(value);
+    }
+    pub fn timestamp(time: &DateTime<Utc>) -> Vec<u8> {
+        let mut out = vec![];
+        varint(&mut out, 1, time.timestamp() as u64);
+        varint(&mut out, 2, time.timestamp_subsec_nanos() as u64);
+        out
+    }
+    /// BlockID / CanonicalBlockID. they encode the same way
+    pub fn block_id(hash: &[u8], total: u64, parts_hash: &[u8]) -> Vec<u8> {
+        let mut parts = vec![];
+        varint(&mut parts, 1, total);
+        bytes(&mut parts, 2, parts_hash);
+        let mut out = vec![];
+        bytes(&mut out, 1, hash);
+        message(&mut out, 2, &parts);
+        out
+    }
+}
+
+/// the hash of a header, as used in block ids
+pub fn header_hash(header: &BlockHeader) -> Result<Vec<u8>, TerraRustAPIError> {
+    let mut version = vec![];
+    proto::varint(&mut version, 1, header.version.block);
+    proto::varint(&mut version, 2, header.version.app.unwrap_or(0));
+    let mut chain_id = vec![];
+    proto::bytes(&mut chain_id, 1, header.chain_id.as_bytes());
+    let mut height = vec![];
+    proto::varint(&mut height, 1, header.height);
+    let last_block_id = proto::block_id(
+        &decode_bytes(&header.last_block_id.hash)?,
+        header.last_block_id.parts.total as u64,
+        &decode_bytes(&header.last_block_id.parts.hash)?,
+    );
+    let mut fields = vec![
+        version,
+        chain_id,
+        height,
+        proto::timestamp(&header.time),
+        last_block_id,
+    ];
+    for hash in &[
+        &header.last_commit_hash,
+        &header.data_hash,
+        &header.validators_hash,
+        &header.next_validators_hash,
+        &header.consensus_hash,
+        &header.app_hash,
+        &header.last_results_hash,
+        &header.evidence_hash,
+        &header.proposer_address,
+    ] {
+        let mut field = vec![];
+        proto::bytes(&mut field, 1, &decode_bytes(hash)?);
+        fields.push(field);
+    }
+    Ok(merkle_root(&fields))
+}
+
+/// a validator's ed25519 key, tendermint address and voting power
+struct KnownValidator {
+    key: ed25519_dalek::PublicKey,
+    address: Vec<u8>,
+    voting_power: u64,
+}
+fn known_validators(validators: &[Validator]) -> Result<Vec<KnownValidator>, TerraRustAPIError> {
+    let mut known = validators
+        .iter()
+        .map(|v| {
+            if v.pub_key.s_type != ED25519_TYPE {
+                return fail(format!(
+                    "unsupported validator key type {}",
+                    v.pub_key.s_type
+                ));
+            }
+            let key_bytes = base64::decode(&v.pub_key.value)?;
+            Ok(KnownValidator {
+                key: ed25519_dalek::PublicKey::from_bytes(&key_bytes)?,
+                address: sha256(&key_bytes)[..20].to_vec(),
+                voting_power: v.voting_power,
+            })
+        })
+        .collect::<Result<Vec<_>, TerraRustAPIError>>()?;
+    // the order tendermint keeps validator sets in
+    known.sort_by(|a, b| {
+        b.voting_power
+            .cmp(&a.voting_power)
+            .then_with(|| a.address.cmp(&b.address))
+    });
+    Ok(known)
+}
+
+/// the hash of a validator set, as found in a header's validators_hash
+pub fn validator_set_hash(validators: &[Validator]) -> Result<Vec<u8>, TerraRustAPIError> {
+    let leaves = known_validators(validators)?
+        .iter()
+        .map(|v| {
+            let mut pub_key = vec![];
+            proto::bytes(&mut pub_key, 1, v.key.as_bytes());
+            let mut leaf = vec![];
+            proto::message(&mut leaf, 1, &pub_key);
+            proto::varint(&mut leaf, 2, v.voting_power);
+            leaf
+        })
+        .collect::<Vec<_>>();
+    Ok(merkle_root(&leaves))
+}
+
+/// the bytes a validator signs when it votes for the commit's block
+pub fn vote_sign_bytes(
+    chain_id: &str,
+    commit: &BlockCommit,
+    timestamp: &DateTime<Utc>,
+) -> Result<Vec<u8>, TerraRustAPIError> {
+    let mut vote = vec![];
+    proto::varint(&mut vote, 1, PRECOMMIT_TYPE);
+    proto::sfixed64(&mut vote, 2, commit.height as i64);
+    proto::sfixed64(&mut vote, 3, commit.round as i64);
+    let block_id = proto::block_id(
+        &decode_bytes(&commit.block_id.hash)?,
+        commit.block_id.parts.total as u64,
+        &decode_bytes(&commit.block_id.parts.hash)?,
+    );
+    proto::message(&mut vote, 4, &block_id);
+    proto::message(&mut vote, 5, &proto::timestamp(timestamp));
+    proto::bytes(&mut vote, 6, chain_id.as_bytes());
+    let mut out = vec![];
+    proto::uvarint(&mut out, vote.len() as u64);
+    out.extend(vote);
+    Ok(out)
+}
+
+/// the voting power of `validators` that correctly signed the commit, and their total voting power
+fn signed_power(
+    chain_id: &str,
+    commit: &BlockCommit,
+    validators: &[Validator],
+) -> Result<(u64, u64), TerraRustAPIError> {
+    let known = known_validators(validators)?;
+    let total = known.iter().map(|v| v.voting_power).sum::<u64>();
+    let mut signed = 0u64;
+    let mut seen: Vec<&[u8]> = vec![];
+    for signature in &commit.signatures {
+        if signature.block_id_flag != BLOCK_ID_FLAG_COMMIT {
+            continue;
+        }
+        let address = decode_bytes(&signature.validator_address)?;
+        let validator = match known.iter().find(|v| v.address == address) {
+            Some(validator) => validator,
+            None => continue,
+        };
+        if seen.contains(&validator.address.as_slice()) {
+            return fail(format!(
+                "validator {} signed twice",
+                signature.validator_address
+            ));
+        }
+        seen.push(&validator.address);
+        let sig_bytes = match &signature.signature {
+            Some(sig) => base64::decode(sig)?,
+            None => return fail(format!("{} has no signature", signature.validator_address)),
+        };
+        let sig = ed25519_dalek::Signature::try_from(sig_bytes.as_slice())?;
+        let sign_bytes = vote_sign_bytes(chain_id, commit, &signature.timestamp)?;
+        if validator.key.verify(&sign_bytes, &sig).is_err() {
+            return fail(format!(
+                "bad signature from {} at height {}",
+                signature.validator_address, commit.height
+            ));
+        }
+        signed += validator.voting_power;
+    }
+    Ok((signed, total))
+}
+
+/// check that the commit is for this header, and the header is consistent with its validators
+fn verify_block(block: &LightBlock) -> Result<(), TerraRustAPIError> {
+    let header = &block.header;
+    if block.commit.height != header.height {
+        return fail(format!(
+            "commit is for height {}, header is {}",
+            block.commit.height, header.height
+        ));
+    }
+    if decode_bytes(&block.commit.block_id.hash)? != header_hash(header)? {
+        return fail(format!(
+            "commit at height {} is for a different header",
+            header.height
+        ));
+    }
+    if decode_bytes(&header.validators_hash)? != validator_set_hash(&block.validators)? {
+        return fail(format!(
+            "validator set does not match the header at height {}",
+            header.height
+        ));
+    }
+    Ok(())
+}
+
+/// +2/3 of the block's own validators signed it
+pub fn verify_commit(block: &LightBlock) -> Result<(), TerraRustAPIError> {
+    verify_block(block)?;
+    let (signed, total) = signed_power(&block.header.chain_id, &block.commit, &block.validators)?;
+    if signed as u128 * 3 <= total as u128 * 2 {
+        return fail(format!(
+            "only {} of {} voting power signed height {}",
+            signed, total, block.header.height
+        ));
+    }
+    Ok(())
+}
+
+fn verify_newer(
+    trusted: &LightBlock,
+    untrusted: &LightBlock,
+    trusting_period: Duration,
+    now: DateTime<Utc>,
+) -> Result<(), TerraRustAPIError> {
+    if trusted.header.time + trusting_period <= now {
+        return fail(format!(
+            "trusted header at height {} has expired",
+            trusted.header.height
+        ));
+    }
+    if untrusted.header.chain_id != trusted.header.chain_id {
+        return fail(format!(
+            "header is for chain {}, expected {}",
+            untrusted.header.chain_id, trusted.header.chain_id
+        ));
+    }
+    if untrusted.header.height <= trusted.header.height {
+        return fail(format!(
+            "header at height {} is not newer than {}",
+            untrusted.header.height, trusted.header.height
+        ));
+    }
+    if untrusted.header.time <= trusted.header.time {
+        return fail(format!(
+            "header at height {} is not later than the trusted one",
+            untrusted.header.height
+        ));
+    }
+    Ok(())
+}
+
+/// verify the block directly after a trusted one
+pub fn verify_sequential(
+    trusted: &LightBlock,
+    untrusted: &LightBlock,
+    trusting_period: Duration,
+    now: DateTime<Utc>,
+) -> Result<(), TerraRustAPIError> {
+    verify_newer(trusted, untrusted, trusting_period, now)?;
+    if untrusted.header.height != trusted.header.height + 1 {
+        return fail(format!(
+            "height {} does not follow {}",
+            untrusted.header.height, trusted.header.height
+        ));
+    }
+    if decode_bytes(&untrusted.header.validators_hash)?
+        != decode_bytes(&trusted.header.next_validators_hash)?
+    {
+        return fail(format!(
+            "validators at height {} are not the ones the trusted header expected",
+            untrusted.header.height
+        ));
+    }
+    verify_commit(untrusted)
+}
+
+/// verify a block further ahead, using the validators the trusted header said would come next
+pub fn verify_skipping(
+    trusted: &LightBlock,
+    untrusted: &LightBlock,
+    trust_level: TrustLevel,
+    trusting_period: Duration,
+    now: DateTime<Utc>,
+) -> Result<(), TerraRustAPIError> {
+    verify_newer(trusted, untrusted, trusting_period, now)?;
+    if decode_bytes(&trusted.header.next_validators_hash)?
+        != validator_set_hash(&trusted.next_validators)?
+    {
+        return fail(format!(
+            "next validator set does not match the header at height {}",
+            trusted.header.height
+        ));
+    }
+    let (signed, total) = signed_power(
+        &untrusted.header.chain_id,
+        &untrusted.commit,
+        &trusted.next_validators,
+    )?;
+    if signed as u128 * trust_level.denominator as u128
+        <= total as u128 * trust_level.numerator as u128
+    {
+        return fail(format!(
+            "only {} of {} trusted voting power signed height {}",
+            signed, total, untrusted.header.height
+        ));
+    }
+    verify_commit(untrusted)
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::client::tendermint_types::{
+        BlockHeaderVersion, BlockId, BlockIdParts, BlockSignature, TendermintPublicKey,
+    };
+    use chrono::TimeZone;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+    use std::ops::Range;
+
+    fn sum(s: &str) -> String {
+        hex::encode(sha256(s.as_bytes()))
+    }
+
+    fn header(height: u64, validators_hash: &str, next_validators_hash: &str) -> BlockHeader {
+        BlockHeader {
+            version: BlockHeaderVersion {
+                block: 1,
+                app: Some(2),
+            },
+            chain_id: "chainId".into(),
+            height,
+            time: Utc.with_ymd_and_hms(2019, 10, 13, 16, 14, 44).unwrap(),
+            last_block_id: BlockId {
+                hash: hex::encode([0u8; 32]),
+                parts: BlockIdParts {
+                    total: 6,
+                    hash: hex::encode([0u8; 32]),
+                },
+            },
+            last_commit_hash: sum("last_commit_hash"),
+            data_hash: sum("data_hash"),
+            validators_hash: validators_hash.into(),
+            next_validators_hash: next_validators_hash.into(),
+            consensus_hash: sum("consensus_hash"),
+            app_hash: sum("app_hash"),
+            last_results_hash: sum("last_results_hash"),
+            evidence_hash: sum("evidence_hash"),
+            proposer_address: hex::encode(&sha256(b"proposer_address")[..20]),
+        }
+    }
+
+    #[test]
+    pub fn test_header_hash() -> anyhow::Result<()> {
+        // the test vector from tendermint's types/block_test.go
+        let header = header(3, &sum("validators_hash"), &sum("next_validators_hash"));
+        assert_eq!(
+            hex::encode_upper(header_hash(&header)?),
+            "F740121F553B5418C3EFBD343C2DBFE9E007BB67B0D020A0741374BAB65242A4"
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_verify() -> anyhow::Result<()> {
+        let keys = (1..=8u8)
+            .map(|i| {
+                let secret = SecretKey::from_bytes(&[i; 32]).unwrap();
+                let public = PublicKey::from(&secret);
+                Keypair { secret, public }
+            })
+            .collect::<Vec<_>>();
+        let validator = |k: &Keypair| Validator {
+            address: "".into(),
+            pub_key: TendermintPublicKey {
+                s_type: ED25519_TYPE.into(),
+                value: base64::encode(k.public.as_bytes()),
+            },
+            proposer_priority: 0,
+            voting_power: 10,
+        };
+        // a block from the validators in `set`, signed by the first `signers` of them
+        let block_with = |height: u64,
+                          set: Range<usize>,
+                          next_set: Range<usize>,
+                          signers: usize|
+         -> LightBlock {
+            let validators = keys[set.clone()].iter().map(validator).collect::<Vec<_>>();
+            let next_validators = keys[next_set].iter().map(validator).collect::<Vec<_>>();
+            let mut header = header(
+                height,
+                &hex::encode(validator_set_hash(&validators).unwrap()),
+                &hex::encode(validator_set_hash(&next_validators).unwrap()),
+            );
+            header.time += Duration::seconds(height as i64);
+            let mut commit = BlockCommit {
+                height,
+                round: 0,
+                block_id: BlockId {
+                    hash: hex::encode(header_hash(&header).unwrap()),
+                    parts: BlockIdParts {
+                        total: 1,
+                        hash: sum("parts"),
+                    },
+                },
+                signatures: vec![],
+            };
+            for key in keys[set].iter().take(signers) {
+                let sign_bytes = vote_sign_bytes("chainId", &commit, &header.time).unwrap();
+                commit.signatures.push(BlockSignature {
+                    block_id_flag: BLOCK_ID_FLAG_COMMIT,
+                    validator_address: hex::encode_upper(&sha256(key.public.as_bytes())[..20]),
+                    timestamp: header.time,
+                    signature: Some(base64::encode(key.sign(&sign_bytes).to_bytes())),
+                });
+            }
+            LightBlock {
+                header,
+                commit,
+                validators,
+                next_validators,
+            }
+        };
+        let block = |height: u64, signers: usize| block_with(height, 0..4, 0..4, signers);
+        let period = Duration::days(14);
+        let now = Utc.with_ymd_and_hms(2019, 10, 14, 0, 0, 0).unwrap();
+        let trusted = block(3, 4);
+        verify_commit(&trusted)?;
+        verify_sequential(&trusted, &block(4, 3), period, now)?;
+        assert!(verify_sequential(&trusted, &block(4, 2), period, now).is_err());
+        assert!(verify_sequential(&trusted, &block(5, 4), period, now).is_err());
+        verify_skipping(&trusted, &block(10, 3), TrustLevel::default(), period, now)?;
+        assert!(verify_skipping(
+            &trusted,
+            &block(10, 4),
+            TrustLevel::default(),
+            period,
+            now + Duration::days(30)
+        )
+        .is_err());
+
+        // the validators change completely after the trusted block
+        let handed_over = block_with(3, 0..4, 4..8, 4);
+        verify_commit(&handed_over)?;
+        let later = block_with(10, 4..8, 4..8, 3);
+        verify_skipping(&handed_over, &later, TrustLevel::default(), period, now)?;
+        // none of the trusted block's own validators signed it
+        assert!(verify_skipping(&trusted, &later, TrustLevel::default(), period, now).is_err());
+        let mut swapped = block_with(3, 0..4, 4..8, 4);
+        swapped.next_validators = swapped.validators.clone();
+        assert!(verify_skipping(&swapped, &later, TrustLevel::default(), period, now).is_err());
+
+        let mut tampered = block(4, 4);
+        tampered.commit.signatures[0].timestamp = tampered.header.time + Duration::seconds(1);
+        assert!(verify_sequential(&trusted, &tampered, period, now).is_err());
+        Ok(())
+    }
+}
@@ -1,22 +1,37 @@
+/// addresses checked when made
+pub mod address;
 /// Bank API Transactions
 pub mod bank;
 pub mod distribution;
+/// governance proposals, votes and deposits
+pub mod gov;
+/// IBC token transfers
+pub mod ibc;
 /// market messages
 pub mod market;
 /// Oracle API Transactions
 pub mod oracle;
+/// checking contract messages against their JSON schema
+pub mod schema;
 /// slashing messages
 pub mod slashing;
 /// messages around staking
 pub mod staking;
+/// placeholders filled in instantiate & migrate JSON
+pub mod template;
+/// checks run on messages before they are signed
+mod validate;
 /// regular contract interactions
 pub mod wasm;
 
+use crate::core_types::Coin;
+use crate::errors::TerraRustAPIError;
+pub use address::Address;
 pub use bank::MsgSend;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 pub use wasm::MsgExecuteContract;
 
-#[derive(Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 /// Message: Send N coins from an address to another
 pub struct Message {
     #[allow(missing_docs)]
@@ -25,3 +40,21 @@ pub struct Message {
     #[allow(missing_docs)]
     value: serde_json::Value,
 }
+impl Message {
+    /// catch malformed messages before they cost gas: addresses must be terra ones that decode,
+    /// coins positive, sorted and with valid denoms, and contract messages JSON (or base64 encoded JSON)
+    pub fn validate(&self) -> Result<(), TerraRustAPIError> {
+        validate::check(&self.value)
+            .map_err(|reason| TerraRustAPIError::InvalidMessage(self.s_type.clone(), reason))
+    }
+    /// the coins the message moves out of the sender's account, which the stability tax is charged on
+    pub fn coins_sent(&self) -> Vec<Coin> {
+        let coins = match self.s_type.as_str() {
+            "bank/MsgSend" => &self.value["amount"],
+            "wasm/MsgExecuteContract" => &self.value["coins"],
+            "wasm/MsgInstantiateContract" => &self.value["init_coins"],
+            _ => return vec![],
+        };
+        serde_json::from_value(coins.clone()).unwrap_or_default()
+    }
+}
@@ -0,0 +1,88 @@
+use crate::errors::TerraRustAPIError;
+use crate::messages::validate::check_address;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// a terra account, contract or validator operator address, checked to decode when made.
+/// message constructors take it anywhere they take an address
+/// ```
+/// use std::convert::TryFrom;
+/// use terra_rust_api::messages::Address;
+/// let to = Address::try_from("terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7").unwrap();
+/// assert_eq!(to.as_str(), "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7");
+/// assert!(Address::try_from("cosmos1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7").is_err());
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(try_from = "String", into = "String")]
+pub struct Address(String);
+impl Address {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+impl TryFrom<&str> for Address {
+    type Error = TerraRustAPIError;
+    fn try_from(address: &str) -> Result<Self, Self::Error> {
+        check_address(address).map_err(TerraRustAPIError::InvalidAddress)?;
+        Ok(Address(address.into()))
+    }
+}
+impl TryFrom<String> for Address {
+    type Error = TerraRustAPIError;
+    fn try_from(address: String) -> Result<Self, Self::Error> {
+        check_address(&address).map_err(TerraRustAPIError::InvalidAddress)?;
+        Ok(Address(address))
+    }
+}
+impl std::str::FromStr for Address {
+    type Err = TerraRustAPIError;
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        Address::try_from(address)
+    }
+}
+impl From<Address> for String {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+impl From<&Address> for String {
+    fn from(address: &Address) -> Self {
+        address.0.clone()
+    }
+}
+impl AsRef<str> for Address {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::core_types::Coin;
+    use crate::messages::MsgSend;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_address() -> anyhow::Result<()> {
+        let from = Address::try_from("terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7")?;
+        let to: Address = "terra1vr0e7kylhu9am44v0s3gwkccmz7k3naxysrwew".parse()?;
+        let msg = MsgSend::create_single(&from, to, Coin::create("uluna", dec!(5)))?;
+        assert!(msg.validate().is_ok());
+        assert!(Address::try_from("terravaloper1usws7c2c6cs7nuc8vma9qzaky5pkgvm2ujy8ny").is_ok());
+        assert!(matches!(
+            Address::try_from("terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg8"),
+            Err(TerraRustAPIError::InvalidAddress(_))
+        ));
+        let js = serde_json::to_string(&from)?;
+        assert_eq!(js, r#""terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7""#);
+        assert!(serde_json::from_str::<Address>(r#""terra1nope""#).is_err());
+        Ok(())
+    }
+}
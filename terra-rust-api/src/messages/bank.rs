@@ -18,22 +18,22 @@ impl MsgInternal for MsgSend {}
 impl MsgSend {
     /// Send amount coins from from_address to to_address
     pub fn create_single(
-        from_address: String,
-        to_address: String,
+        from_address: impl Into<String>,
+        to_address: impl Into<String>,
         amount: Coin,
     ) -> Result<Message, TerraRustAPIError> {
-        MsgSend::create(from_address, to_address, vec![amount])
+        MsgSend::create(from_address, to_address, &[amount])
     }
     /// send multiple coins from from_address to to_address
     pub fn create(
-        from_address: String,
-        to_address: String,
-        amount: Vec<Coin>,
+        from_address: impl Into<String>,
+        to_address: impl Into<String>,
+        amount: &[Coin],
     ) -> Result<Message, TerraRustAPIError> {
         let internal = MsgSend {
-            amount,
-            from_address,
-            to_address,
+            amount: amount.to_vec(),
+            from_address: from_address.into(),
+            to_address: to_address.into(),
         };
         Ok(Message {
             s_type: "bank/MsgSend".into(),
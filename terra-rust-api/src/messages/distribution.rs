@@ -9,8 +9,10 @@ pub struct MsgWithdrawValidatorCommission {
 }
 impl MsgInternal for MsgWithdrawValidatorCommission {}
 impl MsgWithdrawValidatorCommission {
-    pub fn create(validator_address: String) -> anyhow::Result<Message> {
-        let internal = MsgWithdrawValidatorCommission { validator_address };
+    pub fn create(validator_address: impl Into<String>) -> anyhow::Result<Message> {
+        let internal = MsgWithdrawValidatorCommission {
+            validator_address: validator_address.into(),
+        };
         Ok(Message {
             s_type: "distribution/MsgWithdrawValidatorCommission".into(),
             value: serde_json::to_value(internal)?,
@@ -25,10 +27,13 @@ pub struct MsgWithdrawDelegationReward {
 }
 impl MsgInternal for MsgWithdrawDelegationReward {}
 impl MsgWithdrawDelegationReward {
-    pub fn create(delegator_address: String, validator_address: String) -> anyhow::Result<Message> {
+    pub fn create(
+        delegator_address: impl Into<String>,
+        validator_address: impl Into<String>,
+    ) -> anyhow::Result<Message> {
         let internal = MsgWithdrawDelegationReward {
-            delegator_address,
-            validator_address,
+            delegator_address: delegator_address.into(),
+            validator_address: validator_address.into(),
         };
         Ok(Message {
             s_type: "distribution/MsgWithdrawDelegationReward".into(),
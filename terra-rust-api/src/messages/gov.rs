@@ -0,0 +1,552 @@
+use crate::client::client_types::terra_u64_format;
+use crate::core_types::{Coin, MsgInternal};
+use crate::messages::Message;
+use crate::upgrade_types::UpgradePlan;
+use bitcoin::bech32::{self, FromBase32};
+use serde::Serialize;
+
+/// the limits the gov module puts on a proposal's text
+const MAX_TITLE_LENGTH: usize = 140;
+const MAX_DESCRIPTION_LENGTH: usize = 5000;
+
+fn check_text(title: &str, description: &str) -> anyhow::Result<()> {
+    if title.trim().is_empty() || description.trim().is_empty() {
+        anyhow::bail!("proposals need a title and a description");
+    }
+    if title.len() > MAX_TITLE_LENGTH {
+        anyhow::bail!("titles can be at most {} long", MAX_TITLE_LENGTH);
+    }
+    if description.len() > MAX_DESCRIPTION_LENGTH {
+        anyhow::bail!(
+            "descriptions can be at most {} long",
+            MAX_DESCRIPTION_LENGTH
+        );
+    }
+    Ok(())
+}
+
+/// a parameter to change, in a parameter change proposal
+#[derive(Serialize, Debug, Clone)]
+pub struct ParamChange {
+    pub subspace: String,
+    pub key: String,
+    /// the new value, as JSON text
+    pub value: String,
+}
+#[derive(Serialize, Debug)]
+struct TextProposal {
+    title: String,
+    description: String,
+}
+/// the parameters each module's subspace has, on columbus-5
+const KNOWN_PARAMS: &[(&str, &[&str])] = &[
+    (
+        "auth",
+        &[
+            "MaxMemoCharacters",
+            "TxSigLimit",
+            "TxSizeCostPerByte",
+            "SigVerifyCostED25519",
+            "SigVerifyCostSecp256k1",
+        ],
+    ),
+    ("bank", &["SendEnabled", "DefaultSendEnabled"]),
+    (
+        "baseapp",
+        &["BlockParams", "EvidenceParams", "ValidatorParams"],
+    ),
+    ("crisis", &["ConstantFee"]),
+    (
+        "distribution",
+        &[
+            "communitytax",
+            "baseproposerreward",
+            "bonusproposerreward",
+            "withdrawaddrenabled",
+        ],
+    ),
+    ("gov", &["depositparams", "votingparams", "tallyparams"]),
+    (
+        "market",
+        &["BasePool", "PoolRecoveryPeriod", "MinStabilitySpread"],
+    ),
+    (
+        "mint",
+        &[
+            "MintDenom",
+            "InflationRateChange",
+            "InflationMax",
+            "InflationMin",
+            "GoalBonded",
+            "BlocksPerYear",
+        ],
+    ),
+    (
+        "oracle",
+        &[
+            "VotePeriod",
+            "VoteThreshold",
+            "RewardBand",
+            "RewardDistributionWindow",
+            "Whitelist",
+            "SlashFraction",
+            "SlashWindow",
+            "MinValidPerWindow",
+        ],
+    ),
+    (
+        "slashing",
+        &[
+            "SignedBlocksWindow",
+            "MinSignedPerWindow",
+            "DowntimeJailDuration",
+            "SlashFractionDoubleSign",
+            "SlashFractionDowntime",
+        ],
+    ),
+    (
+        "staking",
+        &[
+            "UnbondingTime",
+            "MaxValidators",
+            "MaxEntries",
+            "HistoricalEntries",
+            "BondDenom",
+        ],
+    ),
+    ("transfer", &["SendEnabled", "ReceiveEnabled"]),
+    (
+        "treasury",
+        &[
+            "TaxPolicy",
+            "RewardPolicy",
+            "SeigniorageBurdenTarget",
+            "MiningIncrement",
+            "WindowShort",
+            "WindowLong",
+            "WindowProbation",
+        ],
+    ),
+    (
+        "wasm",
+        &["MaxContractSize", "MaxContractGas", "MaxContractMsgSize"],
+    ),
+];
+/// the keys of a module's parameters, or None if the subspace isn't known
+pub fn known_param_keys(subspace: &str) -> Option<&'static [&'static str]> {
+    KNOWN_PARAMS
+        .iter()
+        .find(|(s, _)| *s == subspace)
+        .map(|(_, keys)| *keys)
+}
+
+/// change module parameters. built a change at a time, checking each subspace & key exist
+#[derive(Serialize, Debug, Clone)]
+pub struct ParameterChangeProposal {
+    pub title: String,
+    pub description: String,
+    pub changes: Vec<ParamChange>,
+}
+impl ParameterChangeProposal {
+    pub fn create(
+        title: impl Into<String>,
+        description: impl Into<String>,
+    ) -> ParameterChangeProposal {
+        ParameterChangeProposal {
+            title: title.into(),
+            description: description.into(),
+            changes: vec![],
+        }
+    }
+    /// set `subspace`'s `key` to `value`, the JSON text of the new value. like `"\"5\""` for a number.
+    /// the subspace and key have to be ones this knows about, see [ParameterChangeProposal::change_unchecked]
+    pub fn change(
+        self,
+        subspace: &str,
+        key: &str,
+        value: &str,
+    ) -> anyhow::Result<ParameterChangeProposal> {
+        let keys = known_param_keys(subspace).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown subspace {}. try one of {}",
+                subspace,
+                KNOWN_PARAMS
+                    .iter()
+                    .map(|(s, _)| *s)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+        if !keys.contains(&key) {
+            anyhow::bail!(
+                "{} has no parameter {}. try one of {}",
+                subspace,
+                key,
+                keys.join(", ")
+            );
+        }
+        self.change_unchecked(subspace, key, value)
+    }
+    /// like [ParameterChangeProposal::change], for a subspace or key added since this was written.
+    /// only the value is checked, so a typo makes a proposal the chain will fail to execute
+    pub fn change_unchecked(
+        mut self,
+        subspace: &str,
+        key: &str,
+        value: &str,
+    ) -> anyhow::Result<ParameterChangeProposal> {
+        serde_json::from_str::<serde_json::Value>(value)
+            .map_err(|e| anyhow::anyhow!("the value of {}/{} isn't JSON: {}", subspace, key, e))?;
+        self.changes.push(ParamChange {
+            subspace: subspace.into(),
+            key: key.into(),
+            value: value.into(),
+        });
+        Ok(self)
+    }
+    pub fn content(self) -> anyhow::Result<ProposalContent> {
+        check_text(&self.title, &self.description)?;
+        if self.changes.is_empty() {
+            anyhow::bail!("a parameter change proposal needs a change");
+        }
+        ProposalContent::create("params/ParameterChangeProposal", self)
+    }
+}
+/// pay coins from the community pool to an account or contract
+#[derive(Serialize, Debug, Clone)]
+pub struct CommunityPoolSpendProposal {
+    pub title: String,
+    pub description: String,
+    pub recipient: String,
+    pub amount: Vec<Coin>,
+}
+impl CommunityPoolSpendProposal {
+    /// checks the recipient is a terra address, and the amount is whole, positive coins of distinct denominations.
+    /// the coins are sorted by denomination, as the chain requires
+    pub fn create(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        recipient: impl Into<String>,
+        amount: &[Coin],
+    ) -> anyhow::Result<CommunityPoolSpendProposal> {
+        lazy_static! {
+            static ref DENOM: regex::Regex =
+                regex::Regex::new(r"^[a-zA-Z][a-zA-Z0-9/:._-]{2,127}$").unwrap();
+        }
+        let (title, description, recipient) = (title.into(), description.into(), recipient.into());
+        check_text(&title, &description)?;
+        let (hrp, data, _) = bech32::decode(&recipient)
+            .map_err(|e| anyhow::anyhow!("recipient {} isn't an address: {}", recipient, e))?;
+        let length = Vec::<u8>::from_base32(&data)?.len();
+        if hrp != "terra" || !(length == 20 || length == 32) {
+            anyhow::bail!("recipient {} isn't a terra account or contract", recipient);
+        }
+        if amount.is_empty() {
+            anyhow::bail!("the amount can't be empty");
+        }
+        let mut amount = amount.to_vec();
+        amount.sort_by(|a, b| a.denom.cmp(&b.denom));
+        for (i, coin) in amount.iter().enumerate() {
+            if !DENOM.is_match(&coin.denom) {
+                anyhow::bail!("{} isn't a valid denomination", coin.denom);
+            }
+            if coin.amount <= rust_decimal::Decimal::ZERO || !coin.amount.fract().is_zero() {
+                anyhow::bail!("{} isn't a whole, positive amount", coin);
+            }
+            if i > 0 && amount[i - 1].denom == coin.denom {
+                anyhow::bail!("{} appears more than once", coin.denom);
+            }
+        }
+        Ok(CommunityPoolSpendProposal {
+            title,
+            description,
+            recipient,
+            amount,
+        })
+    }
+    pub fn content(self) -> anyhow::Result<ProposalContent> {
+        ProposalContent::create("distribution/CommunityPoolSpendProposal", self)
+    }
+}
+#[derive(Serialize, Debug)]
+struct SoftwareUpgradeProposal {
+    title: String,
+    description: String,
+    plan: UpgradePlan,
+}
+
+/// what a proposal proposes
+#[derive(Serialize, Debug)]
+pub struct ProposalContent {
+    #[serde(rename = "type")]
+    s_type: String,
+    value: serde_json::Value,
+}
+impl ProposalContent {
+    fn create<T: Serialize>(s_type: &str, value: T) -> anyhow::Result<ProposalContent> {
+        Ok(ProposalContent {
+            s_type: s_type.into(),
+            value: serde_json::to_value(value)?,
+        })
+    }
+    /// a proposal that is just words
+    pub fn text(
+        title: impl Into<String>,
+        description: impl Into<String>,
+    ) -> anyhow::Result<ProposalContent> {
+        ProposalContent::create(
+            "gov/TextProposal",
+            TextProposal {
+                title: title.into(),
+                description: description.into(),
+            },
+        )
+    }
+    /// change module parameters. see [ParameterChangeProposal::change]
+    pub fn parameter_change(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        changes: Vec<ParamChange>,
+    ) -> anyhow::Result<ProposalContent> {
+        changes
+            .iter()
+            .try_fold(
+                ParameterChangeProposal::create(title, description),
+                |proposal, c| proposal.change(&c.subspace, &c.key, &c.value),
+            )?
+            .content()
+    }
+    /// pay `amount` from the community pool to `recipient`. see [CommunityPoolSpendProposal::create]
+    pub fn community_pool_spend(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        recipient: impl Into<String>,
+        amount: &[Coin],
+    ) -> anyhow::Result<ProposalContent> {
+        CommunityPoolSpendProposal::create(title, description, recipient, amount)?.content()
+    }
+    /// halt the chain for an upgrade at `plan.height`
+    pub fn software_upgrade(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        plan: UpgradePlan,
+    ) -> anyhow::Result<ProposalContent> {
+        ProposalContent::create(
+            "upgrade/SoftwareUpgradeProposal",
+            SoftwareUpgradeProposal {
+                title: title.into(),
+                description: description.into(),
+                plan,
+            },
+        )
+    }
+}
+
+/// submit a governance proposal
+#[derive(Serialize, Debug)]
+pub struct MsgSubmitProposal {
+    pub content: ProposalContent,
+    pub initial_deposit: Vec<Coin>,
+    pub proposer: String,
+}
+impl MsgInternal for MsgSubmitProposal {}
+impl MsgSubmitProposal {
+    pub fn create(
+        proposer: impl Into<String>,
+        content: ProposalContent,
+        initial_deposit: &[Coin],
+    ) -> anyhow::Result<Message> {
+        let internal = MsgSubmitProposal {
+            content,
+            initial_deposit: initial_deposit.to_vec(),
+            proposer: proposer.into(),
+        };
+        Ok(Message {
+            s_type: "gov/MsgSubmitProposal".into(),
+            value: serde_json::to_value(internal)?,
+        })
+    }
+}
+
+/// how to vote. amino signs the option as its protobuf number, not its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteOption {
+    Yes = 1,
+    Abstain = 2,
+    No = 3,
+    NoWithVeto = 4,
+}
+impl Serialize for VoteOption {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(*self as i32)
+    }
+}
+impl std::str::FromStr for VoteOption {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "yes" => Ok(VoteOption::Yes),
+            "abstain" => Ok(VoteOption::Abstain),
+            "no" => Ok(VoteOption::No),
+            "nowithveto" | "veto" => Ok(VoteOption::NoWithVeto),
+            _ => Err(anyhow::anyhow!(
+                "vote option {} isn't one of yes, no, abstain, no_with_veto",
+                s
+            )),
+        }
+    }
+}
+
+/// vote on a proposal
+#[derive(Serialize, Debug)]
+pub struct MsgVote {
+    #[serde(with = "terra_u64_format")]
+    pub proposal_id: u64,
+    pub voter: String,
+    pub option: VoteOption,
+}
+impl MsgInternal for MsgVote {}
+impl MsgVote {
+    pub fn create(
+        voter: impl Into<String>,
+        proposal_id: u64,
+        option: VoteOption,
+    ) -> anyhow::Result<Message> {
+        let internal = MsgVote {
+            proposal_id,
+            voter: voter.into(),
+            option,
+        };
+        Ok(Message {
+            s_type: "gov/MsgVote".into(),
+            value: serde_json::to_value(internal)?,
+        })
+    }
+}
+
+/// add to a proposal's deposit
+#[derive(Serialize, Debug)]
+pub struct MsgDeposit {
+    #[serde(with = "terra_u64_format")]
+    pub proposal_id: u64,
+    pub depositor: String,
+    pub amount: Vec<Coin>,
+}
+impl MsgInternal for MsgDeposit {}
+impl MsgDeposit {
+    pub fn create(
+        depositor: impl Into<String>,
+        proposal_id: u64,
+        amount: &[Coin],
+    ) -> anyhow::Result<Message> {
+        let internal = MsgDeposit {
+            proposal_id,
+            depositor: depositor.into(),
+            amount: amount.to_vec(),
+        };
+        Ok(Message {
+            s_type: "gov/MsgDeposit".into(),
+            value: serde_json::to_value(internal)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::core_types::{StdFee, StdSignMsg};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    pub fn test_gov_messages() -> anyhow::Result<()> {
+        let content = ProposalContent::parameter_change(
+            "t",
+            "d",
+            vec![ParamChange {
+                subspace: "oracle".into(),
+                key: "VotePeriod".into(),
+                value: "\"5\"".into(),
+            }],
+        )?;
+        let msg = MsgSubmitProposal::create(
+            "terra1proposer",
+            content,
+            &Coin::parse_coins("512000000uluna")?,
+        )?;
+        assert_eq!(
+            serde_json::to_string(&msg)?,
+            r#"{"type":"gov/MsgSubmitProposal","value":{"content":{"type":"params/ParameterChangeProposal","value":{"changes":[{"key":"VotePeriod","subspace":"oracle","value":"\"5\""}],"description":"d","title":"t"}},"initial_deposit":[{"amount":"512000000","denom":"uluna"}],"proposer":"terra1proposer"}}"#
+        );
+        let vote = MsgVote::create("terra1voter", 42, "no-with-veto".parse()?)?;
+        assert_eq!(
+            serde_json::to_string(&vote)?,
+            r#"{"type":"gov/MsgVote","value":{"option":4,"proposal_id":"42","voter":"terra1voter"}}"#
+        );
+        let sign_msg = StdSignMsg {
+            account_number: 7,
+            chain_id: "columbus-5".into(),
+            fee: StdFee::create(vec![Coin::create("uluna", dec!(3000))], 200000),
+            memo: "".into(),
+            msgs: vec![MsgVote::create("terra1voter", 42, VoteOption::Yes)?],
+            sequence: 3,
+        };
+        // what the node rebuilds and checks the signature against
+        assert_eq!(
+            serde_json::to_string(&sign_msg)?,
+            r#"{"account_number":"7","chain_id":"columbus-5","fee":{"amount":[{"amount":"3000","denom":"uluna"}],"gas":"200000"},"memo":"","msgs":[{"type":"gov/MsgVote","value":{"option":1,"proposal_id":"42","voter":"terra1voter"}}],"sequence":"3"}"#
+        );
+        assert!("maybe".parse::<VoteOption>().is_err());
+        Ok(())
+    }
+    #[test]
+    pub fn test_community_pool_spend() -> anyhow::Result<()> {
+        let spend = |recipient: &str, amount: &str| {
+            CommunityPoolSpendProposal::create("t", "d", recipient, &Coin::parse_coins(amount)?)
+        };
+        let recipient = "terra1jnzv225hwl3uxc5wtnlgr8mwy6nlt0vztv3qqm";
+        let proposal = spend(recipient, "5uusd,10uluna")?;
+        assert_eq!(proposal.amount[0].denom, "uluna");
+        assert_eq!(
+            serde_json::to_string(&proposal.content()?)?,
+            r#"{"type":"distribution/CommunityPoolSpendProposal","value":{"amount":[{"amount":"10","denom":"uluna"},{"amount":"5","denom":"uusd"}],"description":"d","recipient":"terra1jnzv225hwl3uxc5wtnlgr8mwy6nlt0vztv3qqm","title":"t"}}"#
+        );
+        assert!(spend("cosmos1jnzv225hwl3uxc5wtnlgr8mwy6nlt0vzc6qxds", "5uusd").is_err());
+        assert!(spend("terra1notanaddress", "5uusd").is_err());
+        assert!(spend(recipient, "0uusd").is_err());
+        assert!(spend(recipient, "1.5uusd").is_err());
+        assert!(spend(recipient, "1uusd,2uusd").is_err());
+        Ok(())
+    }
+    #[test]
+    pub fn test_parameter_change() -> anyhow::Result<()> {
+        let proposal = ParameterChangeProposal::create("t", "d")
+            .change("market", "MinStabilitySpread", "\"0.01\"")?
+            .change("wasm", "MaxContractGas", "\"20000000\"")?;
+        assert_eq!(proposal.changes.len(), 2);
+        assert!(proposal.content().is_ok());
+        let empty = ParameterChangeProposal::create("t", "d");
+        assert!(empty
+            .clone()
+            .change("market", "BasePools", "\"1\"")
+            .is_err());
+        assert!(empty
+            .clone()
+            .change("markets", "BasePool", "\"1\"")
+            .is_err());
+        assert!(empty.clone().change("market", "BasePool", "1uusd").is_err());
+        assert!(empty
+            .clone()
+            .change("transfer", "SendEnabled", "false")
+            .is_ok());
+        let unknown = empty
+            .clone()
+            .change_unchecked("icahost", "HostEnabled", "true")?;
+        assert_eq!(unknown.changes[0].subspace, "icahost");
+        assert!(empty
+            .clone()
+            .change_unchecked("icahost", "HostEnabled", "yes")
+            .is_err());
+        assert!(empty.content().is_err());
+        Ok(())
+    }
+}
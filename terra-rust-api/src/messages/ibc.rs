@@ -0,0 +1,60 @@
+use crate::client::client_types::terra_u64_format;
+use crate::core_types::{Coin, MsgInternal};
+use crate::errors::TerraRustAPIError;
+use crate::messages::Message;
+use serde::Serialize;
+
+/// a height on the counterparty chain. the revision is the number at the end of its chain id.
+/// zeros are left out of the JSON, as ibc-go's amino JSON does, so no height is `{}`
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IbcHeight {
+    #[serde(with = "terra_u64_format", skip_serializing_if = "is_zero")]
+    pub revision_number: u64,
+    #[serde(with = "terra_u64_format", skip_serializing_if = "is_zero")]
+    pub revision_height: u64,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Message: send a coin over an IBC channel to an account on another chain
+#[derive(Serialize, Debug)]
+pub struct MsgTransfer {
+    pub source_port: String,
+    pub source_channel: String,
+    pub token: Coin,
+    pub sender: String,
+    pub receiver: String,
+    /// the packet times out once the counterparty reaches this height. zero for none
+    pub timeout_height: IbcHeight,
+    /// or once its clock passes this, in nanoseconds since the unix epoch. zero for none, left out of the JSON
+    #[serde(with = "terra_u64_format", skip_serializing_if = "is_zero")]
+    pub timeout_timestamp: u64,
+}
+impl MsgInternal for MsgTransfer {}
+impl MsgTransfer {
+    /// send `token` from `sender` over `source_channel` of the `transfer` port,
+    /// timing out at `timeout_timestamp` nanoseconds
+    pub fn create(
+        sender: impl Into<String>,
+        source_channel: impl Into<String>,
+        receiver: impl Into<String>,
+        token: Coin,
+        timeout_timestamp: u64,
+    ) -> Result<Message, TerraRustAPIError> {
+        let internal = MsgTransfer {
+            source_port: "transfer".into(),
+            source_channel: source_channel.into(),
+            token,
+            sender: sender.into(),
+            receiver: receiver.into(),
+            timeout_height: IbcHeight::default(),
+            timeout_timestamp,
+        };
+        Ok(Message {
+            s_type: "cosmos-sdk/MsgTransfer".into(),
+            value: serde_json::to_value(internal)?,
+        })
+    }
+}
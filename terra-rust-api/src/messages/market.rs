@@ -17,11 +17,15 @@ pub struct MsgSwap {
 impl MsgInternal for MsgSwap {}
 impl MsgSwap {
     /// swap a coin, and send it to someone
-    pub fn create(offer_coin: Coin, ask_denom: String, trader: String) -> anyhow::Result<Message> {
+    pub fn create(
+        offer_coin: Coin,
+        ask_denom: impl Into<String>,
+        trader: impl Into<String>,
+    ) -> anyhow::Result<Message> {
         let internal = MsgSwap {
-            ask_denom,
+            ask_denom: ask_denom.into(),
             offer_coin,
-            trader,
+            trader: trader.into(),
         };
         Ok(Message {
             s_type: "market/MsgSwap".into(),
@@ -18,11 +18,15 @@ pub struct MsgAggregateExchangeRatePreVote {
 impl MsgInternal for MsgAggregateExchangeRatePreVote {}
 impl MsgAggregateExchangeRatePreVote {
     /// Create a pre vote message
-    pub fn create(hash: String, feeder: String, validator: String) -> anyhow::Result<Message> {
+    pub fn create(
+        hash: impl Into<String>,
+        feeder: impl Into<String>,
+        validator: impl Into<String>,
+    ) -> anyhow::Result<Message> {
         let internal = MsgAggregateExchangeRatePreVote {
-            feeder,
-            hash,
-            validator,
+            feeder: feeder.into(),
+            hash: hash.into(),
+            validator: validator.into(),
         };
         Ok(Message {
             s_type: "oracle/MsgAggregateExchangeRatePrevote".into(),
@@ -62,15 +66,12 @@ impl MsgAggregateExchangeRateVote {
     }
 
     pub fn create_internal(
-        salt: String,
-        exchange_rates: Vec<Coin>,
-        feeder: String,
-        validator: String,
+        salt: impl Into<String>,
+        exchange_rates: &[Coin],
+        feeder: impl Into<String>,
+        validator: impl Into<String>,
     ) -> MsgAggregateExchangeRateVote {
-        let mut new_rates: Vec<Coin> = Vec::with_capacity(exchange_rates.len());
-        for rate in exchange_rates {
-            new_rates.push(rate);
-        }
+        let mut new_rates = exchange_rates.to_vec();
         new_rates.sort_by(|a, b| a.denom.cmp(&b.denom));
         let coins = new_rates
             .iter()
@@ -78,18 +79,18 @@ impl MsgAggregateExchangeRateVote {
             .collect::<Vec<String>>()
             .join(",");
         MsgAggregateExchangeRateVote {
-            salt,
+            salt: salt.into(),
             exchange_rates: coins,
-            feeder,
-            validator,
+            feeder: feeder.into(),
+            validator: validator.into(),
         }
     }
     /// Create a vote message
     pub fn create(
-        salt: String,
-        exchange_rates: Vec<Coin>,
-        feeder: String,
-        validator: String,
+        salt: impl Into<String>,
+        exchange_rates: &[Coin],
+        feeder: impl Into<String>,
+        validator: impl Into<String>,
     ) -> anyhow::Result<Message> {
         let internal =
             MsgAggregateExchangeRateVote::create_internal(salt, exchange_rates, feeder, validator);
@@ -113,8 +114,8 @@ impl MsgAggregateExchangeRateVote {
     pub fn gen_pre_vote(&self, previous_salt: &str) -> anyhow::Result<Message> {
         MsgAggregateExchangeRatePreVote::create(
             self.generate_hash(previous_salt),
-            self.feeder.clone(),
-            self.validator.clone(),
+            &self.feeder,
+            &self.validator,
         )
     }
 }
@@ -129,8 +130,14 @@ pub struct MsgDelegateFeedConsent {
 impl MsgInternal for MsgDelegateFeedConsent {}
 impl MsgDelegateFeedConsent {
     /// Create a pre vote message
-    pub fn create(operator: String, delegate: String) -> anyhow::Result<Message> {
-        let internal = MsgDelegateFeedConsent { delegate, operator };
+    pub fn create(
+        operator: impl Into<String>,
+        delegate: impl Into<String>,
+    ) -> anyhow::Result<Message> {
+        let internal = MsgDelegateFeedConsent {
+            delegate: delegate.into(),
+            operator: operator.into(),
+        };
         Ok(Message {
             s_type: "oracle/MsgDelegateFeedConsent".into(),
             value: serde_json::to_value(internal)?,
@@ -159,7 +166,7 @@ mod tst {
         assert_eq!(generate_hash(&salt, exchange_rate_str, &validator), hash);
         let vote_1 = MsgAggregateExchangeRateVote::create_internal(
             salt.clone(),
-            exchange_rates,
+            &exchange_rates,
             feeder,
             validator,
         );
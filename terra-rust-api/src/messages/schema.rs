@@ -0,0 +1,296 @@
+use crate::errors::TerraRustAPIError;
+use serde_json::{Map, Value};
+
+/// Check a contract message against the JSON schema `cargo schema` wrote for it, like `execute_msg.json`.
+/// Covers what cosmwasm schemas use: `$ref` into `definitions`, `type`, `enum`, `const`, `properties`,
+/// `required`, `additionalProperties`, `items`, `minimum`/`maximum` and `allOf`/`anyOf`/`oneOf`.
+/// A `oneOf` passes when any of its variants does. `format` isn't checked.
+/// ```
+/// use serde_json::json;
+/// use terra_rust_api::messages::schema::validate;
+/// let schema = json!({"oneOf": [
+///     {"type": "object", "required": ["reset"], "additionalProperties": false,
+///      "properties": {"reset": {"type": "object", "required": ["count"], "properties": {"count": {"type": "integer"}}}}}
+/// ]});
+/// assert!(validate(&json!({"reset": {"count": 5}}), &schema).is_ok());
+/// assert!(validate(&json!({"reset": {"count": "5"}}), &schema).is_err());
+/// ```
+pub fn validate(msg: &Value, schema: &Value) -> Result<(), TerraRustAPIError> {
+    let mut problems = vec![];
+    check(msg, schema, schema, "", &mut problems);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(TerraRustAPIError::SchemaMismatch(problems.join("; ")))
+    }
+}
+
+/// add what's wrong with `value` at `path` to `problems`
+fn check(value: &Value, schema: &Value, root: &Value, path: &str, problems: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            problems.push(format!("{}: not allowed", at(path)));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match resolve(root, reference) {
+            Some(target) => check(value, target, root, path, problems),
+            None => problems.push(format!("{}: unknown $ref {}", at(path), reference)),
+        }
+        return;
+    }
+    if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+        for s in all_of {
+            check(value, s, root, path, problems);
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+            let matches = variants.iter().any(|s| {
+                let mut inner = vec![];
+                check(value, s, root, path, &mut inner);
+                inner.is_empty()
+            });
+            if !matches {
+                problems.push(match variant_names(variants, root) {
+                    Some(names) => format!("{}: expected one of {}", at(path), names.join(", ")),
+                    None => format!(
+                        "{}: matches none of the {} choices",
+                        at(path),
+                        variants.len()
+                    ),
+                });
+            }
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            problems.push(format!(
+                "{}: {} isn't one of {}",
+                at(path),
+                value,
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            problems.push(format!("{}: expected {}", at(path), constant));
+        }
+    }
+    if let Some(types) = schema.get("type") {
+        let types = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect::<Vec<_>>(),
+            Value::String(t) => vec![t.as_str()],
+            _ => vec![],
+        };
+        if !types.is_empty() && !types.iter().any(|t| is_type(value, t)) {
+            problems.push(format!(
+                "{}: expected {}, not {}",
+                at(path),
+                types.join(" or "),
+                value
+            ));
+            return;
+        }
+    }
+    if let Some(n) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            if n < minimum {
+                problems.push(format!("{}: {} is below {}", at(path), value, minimum));
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            if n > maximum {
+                problems.push(format!("{}: {} is above {}", at(path), value, maximum));
+            }
+        }
+    }
+    match value {
+        Value::Object(object) => check_object(object, schema, root, path, problems),
+        Value::Array(items) => match schema.get("items") {
+            Some(Value::Array(tuple)) => {
+                for (i, (item, s)) in items.iter().zip(tuple).enumerate() {
+                    check(item, s, root, &format!("{}/{}", path, i), problems);
+                }
+            }
+            Some(s) => {
+                for (i, item) in items.iter().enumerate() {
+                    check(item, s, root, &format!("{}/{}", path, i), problems);
+                }
+            }
+            None => {}
+        },
+        _ => {}
+    }
+}
+
+fn check_object(
+    object: &Map<String, Value>,
+    schema: &Map<String, Value>,
+    root: &Value,
+    path: &str,
+    problems: &mut Vec<String>,
+) {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                problems.push(format!("{}: missing {}", at(path), name));
+            }
+        }
+    }
+    for (name, value) in object {
+        let field_path = format!("{}/{}", path, name);
+        match properties.and_then(|p| p.get(name)) {
+            Some(s) => check(value, s, root, &field_path, problems),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    problems.push(format!("{}: unknown field {}", at(path), name))
+                }
+                Some(s) => check(value, s, root, &field_path, problems),
+                None => {}
+            },
+        }
+    }
+}
+
+fn is_type(value: &Value, t: &str) -> bool {
+    match t {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        // JSON doesn't tell 3 and 3.0 apart, and neither does JSON schema
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || matches!(value.as_f64(), Some(f) if f.is_finite() && f.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+/// `#/definitions/Name`, or the root itself for `#`
+fn resolve<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+/// the names of enum variants written as objects with one required key, or as string constants
+fn variant_names(variants: &[Value], root: &Value) -> Option<Vec<String>> {
+    let mut names = vec![];
+    for variant in variants {
+        let variant = match variant.get("$ref").and_then(Value::as_str) {
+            Some(reference) => resolve(root, reference)?,
+            None => variant,
+        };
+        if let Some(required) = variant.get("required").and_then(Value::as_array) {
+            names.push(required.first()?.as_str()?.to_string());
+        } else if let Some(allowed) = variant.get("enum").and_then(Value::as_array) {
+            names.extend(
+                allowed
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|s| format!("\"{}\"", s)),
+            );
+        } else {
+            return None;
+        }
+    }
+    Some(names)
+}
+
+/// where in the message, for errors
+fn at(path: &str) -> &str {
+    if path.is_empty() {
+        "/"
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use serde_json::json;
+
+    /// what `cargo schema` writes for a cw20-ish ExecuteMsg
+    fn execute_msg() -> Value {
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "ExecuteMsg",
+            "oneOf": [
+                {"type": "object", "required": ["transfer"], "additionalProperties": false,
+                 "properties": {"transfer": {"type": "object", "required": ["amount", "recipient"],
+                    "properties": {"amount": {"$ref": "#/definitions/Uint128"}, "recipient": {"type": "string"}}}}},
+                {"type": "object", "required": ["set_limit"], "additionalProperties": false,
+                 "properties": {"set_limit": {"type": "object", "required": ["limit"],
+                    "properties": {"limit": {"type": "integer", "format": "uint32", "minimum": 0.0},
+                                   "memo": {"type": ["string", "null"]}}}}},
+                {"type": "string", "enum": ["pause"]}
+            ],
+            "definitions": {"Uint128": {"type": "string"}}
+        })
+    }
+
+    #[test]
+    pub fn test_validate() {
+        let schema = execute_msg();
+        assert!(validate(
+            &json!({"transfer": {"amount": "10", "recipient": "terra1.."}}),
+            &schema
+        )
+        .is_ok());
+        assert!(validate(&json!({"set_limit": {"limit": 3, "memo": null}}), &schema).is_ok());
+        assert!(validate(&json!("pause"), &schema).is_ok());
+        // 3.0 is an integer, 3.5 isn't
+        assert!(validate(&json!({"set_limit": {"limit": 3.0}}), &schema).is_ok());
+        assert!(validate(&json!({"set_limit": {"limit": 3.5}}), &schema).is_err());
+
+        let problem = |msg: Value| match validate(&msg, &schema) {
+            Err(TerraRustAPIError::SchemaMismatch(problem)) => problem,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(
+            problem(json!({"burn": {}})),
+            "/: expected one of transfer, set_limit, \"pause\""
+        );
+
+        // on its own, each variant says what is wrong
+        let transfer = &schema["oneOf"][0];
+        let mut problems = vec![];
+        check(
+            &json!({"transfer": {"amount": 10}}),
+            transfer,
+            &schema,
+            "",
+            &mut problems,
+        );
+        assert_eq!(
+            problems,
+            vec![
+                "/transfer: missing recipient",
+                "/transfer/amount: expected string, not 10"
+            ]
+        );
+        let mut problems = vec![];
+        check(
+            &json!({"set_limit": {"limit": -1}, "x": 1}),
+            &schema["oneOf"][1],
+            &schema,
+            "",
+            &mut problems,
+        );
+        assert_eq!(
+            problems,
+            vec!["/set_limit/limit: -1 is below 0", "/: unknown field x"]
+        );
+    }
+}
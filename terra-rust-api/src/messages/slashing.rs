@@ -10,8 +10,10 @@ pub struct MsgUnjail {
 }
 impl MsgInternal for MsgUnjail {}
 impl MsgUnjail {
-    pub fn create(address: String) -> anyhow::Result<Message> {
-        let internal = MsgUnjail { address };
+    pub fn create(address: impl Into<String>) -> anyhow::Result<Message> {
+        let internal = MsgUnjail {
+            address: address.into(),
+        };
         Ok(Message {
             s_type: "slashing/MsgUnjail".into(),
             value: serde_json::to_value(internal)?,
@@ -76,19 +76,19 @@ impl MsgCreateValidator {
         description: ValidatorDescription,
         commission: ValidatorCommission,
         min_self_delegation: Decimal,
-        delegator_address: String,
-        validator_address: String,
-        pubkey: String,
+        delegator_address: impl Into<String>,
+        validator_address: impl Into<String>,
+        pubkey: impl Into<String>,
         value: Coin,
     ) -> Message {
         let internal = MsgCreateValidator {
             commission,
-            delegator_address,
+            delegator_address: delegator_address.into(),
             description,
             min_self_delegation,
-            pubkey,
+            pubkey: pubkey.into(),
             value,
-            validator_address,
+            validator_address: validator_address.into(),
         };
         Message {
             s_type: "staking/MsgCreateValidator".into(),
@@ -110,12 +110,12 @@ impl MsgInternal for MsgEditValidator {}
 impl MsgEditValidator {
     pub fn create(
         description: ValidatorDescription,
-        address: String,
+        address: impl Into<String>,
         commission_rate: Option<Decimal>,
         min_self_delegation: Option<Decimal>,
     ) -> anyhow::Result<Message> {
         let internal = MsgEditValidator {
-            address,
+            address: address.into(),
             commission_rate,
             description,
             min_self_delegation,
@@ -137,14 +137,14 @@ pub struct MsgUndelegate {
 impl MsgInternal for MsgUndelegate {}
 impl MsgUndelegate {
     pub fn create(
-        delegator_address: String,
-        validator_address: String,
+        delegator_address: impl Into<String>,
+        validator_address: impl Into<String>,
         amount: Coin,
     ) -> anyhow::Result<Message> {
         let internal = MsgUndelegate {
             amount,
-            delegator_address,
-            validator_address,
+            delegator_address: delegator_address.into(),
+            validator_address: validator_address.into(),
         };
         Ok(Message {
             s_type: "staking/MsgUndelegate".into(),
@@ -162,14 +162,14 @@ pub struct MsgDelegate {
 impl MsgInternal for MsgDelegate {}
 impl MsgDelegate {
     pub fn create(
-        delegator_address: String,
-        validator_address: String,
+        delegator_address: impl Into<String>,
+        validator_address: impl Into<String>,
         amount: Coin,
     ) -> anyhow::Result<Message> {
         let internal = MsgDelegate {
             amount,
-            delegator_address,
-            validator_address,
+            delegator_address: delegator_address.into(),
+            validator_address: validator_address.into(),
         };
         Ok(Message {
             s_type: "staking/MsgDelegate".into(),
@@ -188,16 +188,16 @@ pub struct MsgBeginRedelegate {
 impl MsgInternal for MsgBeginRedelegate {}
 impl MsgBeginRedelegate {
     pub fn create(
-        delegator_address: String,
-        validator_dst_address: String,
-        validator_src_address: String,
+        delegator_address: impl Into<String>,
+        validator_dst_address: impl Into<String>,
+        validator_src_address: impl Into<String>,
         amount: Coin,
     ) -> anyhow::Result<Message> {
         let internal = MsgBeginRedelegate {
             amount,
-            delegator_address,
-            validator_src_address,
-            validator_dst_address,
+            delegator_address: delegator_address.into(),
+            validator_src_address: validator_src_address.into(),
+            validator_dst_address: validator_dst_address.into(),
         };
         Ok(Message {
             s_type: "staking/MsgBeginRedelegate".into(),
@@ -0,0 +1,194 @@
+use crate::errors::TerraRustAPIError;
+use std::collections::BTreeMap;
+
+/// Fills the `##NAME##` placeholders of instantiate & migrate JSON, so one file can deploy to any chain.
+/// * `##NAME##` is a variable, like the `##SENDER##`, `##ADMIN##` and `##CODE_ID##` the wasm messages set
+/// * `##env:NAME##` is the environment variable `NAME`
+/// * `##contract:name##` is the address of a contract deployed earlier, from the registry given
+///
+/// A placeholder inside quotes is filled with its value escaped as a JSON string. One outside them,
+/// like a number, has to be a JSON value of its own, so a value can't add fields to the message.
+/// A placeholder that can't be filled is an error, rather than being sent as is
+/// ```
+/// use terra_rust_api::messages::template::MsgTemplate;
+/// let template = MsgTemplate::default()
+///     .var("CODE_ID", "42")
+///     .contract("token", "terra1token");
+/// assert_eq!(
+///     template.render(r###"{"token":"##contract:token##","pair_code_id":##CODE_ID##}"###).unwrap(),
+///     r###"{"token":"terra1token","pair_code_id":42}"###
+/// );
+/// assert!(template.render(r###"{"owner":"##OWNER##"}"###).is_err());
+/// let quoted = MsgTemplate::default().var("NAME", r#"a","admin":"b"#);
+/// assert_eq!(
+///     quoted.render(r###"{"name":"##NAME##"}"###).unwrap(),
+///     r###"{"name":"a\",\"admin\":\"b"}"###
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MsgTemplate {
+    vars: BTreeMap<String, String>,
+    contracts: BTreeMap<String, String>,
+}
+impl MsgTemplate {
+    /// what `##name##` is replaced by
+    pub fn var(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+    /// what `##contract:name##` is replaced by
+    pub fn contract(mut self, name: &str, address: impl Into<String>) -> Self {
+        self.contracts.insert(name.into(), address.into());
+        self
+    }
+    /// every contract in a registry of name -> address
+    pub fn contracts<'a, I: IntoIterator<Item = (&'a String, &'a String)>>(
+        mut self,
+        contracts: I,
+    ) -> Self {
+        for (name, address) in contracts {
+            self.contracts.insert(name.clone(), address.clone());
+        }
+        self
+    }
+    /// `text` with every placeholder filled. fails naming all the ones that can't be
+    pub fn render(&self, text: &str) -> Result<String, TerraRustAPIError> {
+        let mut out = String::with_capacity(text.len());
+        let mut unknown = vec![];
+        let mut in_string = JsonString::default();
+        let mut rest = text;
+        while let Some(start) = rest.find("##") {
+            let after = &rest[start + 2..];
+            let name = after
+                .find("##")
+                .map(|end| &after[..end])
+                .filter(|name| is_placeholder(name));
+            match name {
+                Some(name) => {
+                    out.push_str(&rest[..start]);
+                    in_string.read(&rest[..start]);
+                    match self.value(name) {
+                        Some(value) if in_string.inside => out.push_str(&escape(&value)),
+                        Some(value) => {
+                            serde_json::from_str::<serde_json::Value>(&value).map_err(|e| {
+                                TerraRustAPIError::PlaceholderNotJson(
+                                    format!("##{}##", name),
+                                    e.to_string(),
+                                )
+                            })?;
+                            out.push_str(&value)
+                        }
+                        None => unknown.push(format!("##{}##", name)),
+                    }
+                    rest = &after[name.len() + 2..];
+                }
+                None => {
+                    out.push_str(&rest[..start + 2]);
+                    in_string.read(&rest[..start + 2]);
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        if unknown.is_empty() {
+            Ok(out)
+        } else {
+            Err(TerraRustAPIError::UnknownPlaceholders(unknown.join(", ")))
+        }
+    }
+    fn value(&self, name: &str) -> Option<String> {
+        if let Some(var) = name.strip_prefix("env:") {
+            std::env::var(var).ok()
+        } else if let Some(contract) = name.strip_prefix("contract:") {
+            self.contracts.get(contract).cloned()
+        } else {
+            self.vars.get(name).cloned()
+        }
+    }
+}
+
+/// whether the text read so far has left us inside a JSON string
+#[derive(Default)]
+struct JsonString {
+    inside: bool,
+    escaped: bool,
+}
+impl JsonString {
+    fn read(&mut self, text: &str) {
+        for c in text.chars() {
+            if self.escaped {
+                self.escaped = false;
+            } else if self.inside && c == '\\' {
+                self.escaped = true;
+            } else if c == '"' {
+                self.inside = !self.inside;
+            }
+        }
+    }
+}
+
+/// `value` as it goes between the quotes of a JSON string
+fn escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.into()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// letters, digits and `_-.:/`, with no spaces
+fn is_placeholder(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.:/".contains(c))
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_template() -> anyhow::Result<()> {
+        std::env::set_var("TERRA_RUST_TEMPLATE_TEST", "1000");
+        let template = MsgTemplate::default()
+            .var("SENDER", "terra1sender")
+            .var("ADMIN", "")
+            .contract("token", "terra1token");
+        assert_eq!(
+            template.render(
+                r###"{"owner":"##SENDER##","admin":"##ADMIN##","cap":"##env:TERRA_RUST_TEMPLATE_TEST##","token":"##contract:token##","note":"## not a ## placeholder"}"###
+            )?,
+            r###"{"owner":"terra1sender","admin":"","cap":"1000","token":"terra1token","note":"## not a ## placeholder"}"###
+        );
+        match template.render(
+            r###"{"a":"##contract:pair##","b":"##env:TERRA_RUST_NOT_SET##","c":"##CODE_ID##"}"###,
+        ) {
+            Err(TerraRustAPIError::UnknownPlaceholders(unknown)) => assert_eq!(
+                unknown,
+                "##contract:pair##, ##env:TERRA_RUST_NOT_SET##, ##CODE_ID##"
+            ),
+            other => anyhow::bail!("{:?}", other),
+        }
+
+        // values can't break out of their string, or add fields next to a number
+        let template = MsgTemplate::default()
+            .var("NAME", "a\"b\\c\n")
+            .var("COUNT", "1,\"admin\":\"terra1evil\"");
+        let rendered = template.render(r###"{"name":"x ##NAME##","escaped":"\"##NAME##"}"###)?;
+        assert_eq!(
+            rendered,
+            r###"{"name":"x a\"b\\c\n","escaped":"\"a\"b\\c\n"}"###
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&rendered)?;
+        assert_eq!(parsed["name"], "x a\"b\\c\n");
+        match template.render(r###"{"count":##COUNT##}"###) {
+            Err(TerraRustAPIError::PlaceholderNotJson(name, _)) => assert_eq!(name, "##COUNT##"),
+            other => anyhow::bail!("{:?}", other),
+        }
+        assert_eq!(
+            template
+                .var("COUNT", "3")
+                .render(r###"{"count":##COUNT##}"###)?,
+            r###"{"count":3}"###
+        );
+        Ok(())
+    }
+}
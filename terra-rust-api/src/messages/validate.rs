@@ -0,0 +1,185 @@
+use crate::core_types::Coin;
+use crate::PublicKey;
+use regex::Regex;
+use serde_json::Value;
+
+/// fields holding account, contract or validator addresses, at any depth of a message
+const ADDRESS_FIELDS: [&str; 21] = [
+    "address",
+    "admin",
+    "contract",
+    "delegate",
+    "delegator_address",
+    "depositor",
+    "feeder",
+    "from_address",
+    "new_admin",
+    "operator",
+    "proposer",
+    "recipient",
+    "sender",
+    "to_address",
+    "trader",
+    "validator",
+    "validator_address",
+    "validator_dst_address",
+    "validator_src_address",
+    "voter",
+    "withdraw_address",
+];
+/// fields holding a coin, or a list of coins
+const COIN_FIELDS: [&str; 6] = [
+    "amount",
+    "coins",
+    "init_coins",
+    "initial_deposit",
+    "offer_coin",
+    "value",
+];
+/// fields holding a contract message, as JSON or base64 encoded JSON
+const CONTRACT_MSG_FIELDS: [&str; 3] = ["execute_msg", "init_msg", "migrate_msg"];
+
+/// what is wrong with the first bad field found in the message, if anything
+pub(crate) fn check(value: &Value) -> Result<(), String> {
+    if let Value::Array(values) = value {
+        return values.iter().try_for_each(check);
+    }
+    let fields = match value {
+        Value::Object(fields) => fields,
+        _ => return Ok(()),
+    };
+    for (key, value) in fields {
+        let key = key.as_str();
+        if ADDRESS_FIELDS.contains(&key) {
+            if let Value::String(address) = value {
+                // an empty admin means nobody can migrate the contract
+                if !(key.ends_with("admin") && address.is_empty()) {
+                    check_address(address).map_err(|e| format!("{} {}", key, e))?;
+                }
+                continue;
+            }
+        }
+        if COIN_FIELDS.contains(&key) && check_coins(value).map_err(|e| format!("{} {}", key, e))? {
+            continue;
+        }
+        if CONTRACT_MSG_FIELDS.contains(&key) {
+            check_contract_msg(value).map_err(|e| format!("{} {}", key, e))?;
+            continue;
+        }
+        check(value)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn check_address(address: &str) -> Result<(), String> {
+    let valid = if address.starts_with("terravaloper") {
+        PublicKey::from_operator_address(address).is_ok()
+    } else if address.starts_with("terra") {
+        PublicKey::from_account(address).is_ok() || PublicKey::from_contract(address).is_ok()
+    } else {
+        return Err(format!("`{}` is not a terra address", address));
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("`{}` is not a valid address", address))
+    }
+}
+
+/// true if the value was coins, and they are fine
+fn check_coins(value: &Value) -> Result<bool, String> {
+    let coins: Vec<Coin> = match value {
+        Value::Array(_) => match serde_json::from_value(value.clone()) {
+            Ok(coins) => coins,
+            Err(_) => return Ok(false),
+        },
+        Value::Object(fields) if fields.contains_key("denom") => {
+            match serde_json::from_value(value.clone()) {
+                Ok(coin) => vec![coin],
+                Err(_) => return Ok(false),
+            }
+        }
+        _ => return Ok(false),
+    };
+    lazy_static! {
+        static ref DENOM: Regex = Regex::new(r"^[a-zA-Z][a-zA-Z0-9/:._-]{2,127}$").unwrap();
+    }
+    for coin in &coins {
+        if !DENOM.is_match(&coin.denom) {
+            return Err(format!("`{}` is not a valid denom", coin.denom));
+        }
+        if coin.amount <= rust_decimal::Decimal::ZERO {
+            return Err(format!(
+                "{}{} is not a positive amount",
+                coin.amount, coin.denom
+            ));
+        }
+    }
+    if coins.windows(2).any(|pair| pair[0].denom >= pair[1].denom) {
+        return Err("coins must be sorted by denom, with no denom twice".into());
+    }
+    Ok(true)
+}
+
+fn check_contract_msg(value: &Value) -> Result<(), String> {
+    match value {
+        Value::Object(_) => Ok(()),
+        Value::String(b64) => {
+            let json = base64::decode(b64).map_err(|_| "is not base64".to_string())?;
+            serde_json::from_slice::<Value>(&json)
+                .map(|_| ())
+                .map_err(|_| "is not base64 encoded JSON".to_string())
+        }
+        _ => Err("is not a JSON object".into()),
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use crate::core_types::Coin;
+    use crate::errors::TerraRustAPIError;
+    use crate::messages::{MsgExecuteContract, MsgSend};
+    use rust_decimal_macros::dec;
+
+    const FROM: &str = "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7";
+    const TO: &str = "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh";
+    const CONTRACT: &str = "terra16ckeuu7c6ggu52a8se005mg5c0kd2kmuun63cu";
+
+    fn reason(result: Result<(), TerraRustAPIError>) -> String {
+        match result {
+            Err(TerraRustAPIError::InvalidMessage(_, reason)) => reason,
+            other => panic!("expected an invalid message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn test_validate() -> anyhow::Result<()> {
+        let send = |to: &str, coins: Vec<Coin>| MsgSend::create(FROM, to, &coins);
+        send(
+            TO,
+            vec![
+                Coin::create("uluna", dec!(1)),
+                Coin::create("uusd", dec!(2)),
+            ],
+        )?
+        .validate()?;
+        assert!(reason(send("cosmos1abc", vec![])?.validate()).starts_with("to_address"));
+        assert!(reason(send(&TO.replace('6', "7"), vec![])?.validate()).contains("not a valid"));
+        let unsorted = vec![
+            Coin::create("uusd", dec!(2)),
+            Coin::create("uluna", dec!(1)),
+        ];
+        assert!(reason(send(TO, unsorted)?.validate()).contains("sorted"));
+        let zero = vec![Coin::create("uluna", dec!(0))];
+        assert!(reason(send(TO, zero)?.validate()).contains("positive"));
+
+        let coins = [Coin::create("uluna", dec!(5))];
+        MsgExecuteContract::create_from_json(FROM, CONTRACT, r#"{"a":{}}"#, &coins)?.validate()?;
+        let b64 = serde_json::Value::String(base64::encode(r#"{"a":{}}"#));
+        MsgExecuteContract::create_from_value(FROM, CONTRACT, &b64, &coins)?.validate()?;
+        let garbage = serde_json::Value::String("not json".into());
+        let execute = MsgExecuteContract::create_from_value(FROM, CONTRACT, &garbage, &coins)?;
+        assert!(reason(execute.validate()).starts_with("execute_msg"));
+        Ok(())
+    }
+}
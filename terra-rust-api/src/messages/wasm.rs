@@ -3,6 +3,7 @@ use crate::terra_u64_format;
 use std::path::Path;
 
 use crate::errors::TerraRustAPIError;
+use crate::messages::template::MsgTemplate;
 use crate::messages::Message;
 use serde::Serialize;
 
@@ -19,8 +20,8 @@ impl MsgInternal for MsgExecuteContract {}
 impl MsgExecuteContract {
     /// use provided base64 exec message
     pub fn create_from_value(
-        sender: &str,
-        contract: &str,
+        sender: impl Into<String>,
+        contract: impl Into<String>,
         execute_msg: &serde_json::Value,
         coins: &[Coin],
     ) -> Result<Message, TerraRustAPIError> {
@@ -35,10 +36,24 @@ impl MsgExecuteContract {
             value: serde_json::to_value(internal)?,
         })
     }
+    /// serialize a contract's ExecuteMsg
+    pub fn create_from_msg<T: Serialize>(
+        sender: impl Into<String>,
+        contract: impl Into<String>,
+        execute_msg: &T,
+        coins: &[Coin],
+    ) -> Result<Message, TerraRustAPIError> {
+        MsgExecuteContract::create_from_value(
+            sender,
+            contract,
+            &serde_json::to_value(execute_msg)?,
+            coins,
+        )
+    }
     /// use provided base64 exec message
     pub fn create_from_json(
-        sender: &str,
-        contract: &str,
+        sender: impl Into<String>,
+        contract: impl Into<String>,
         execute_msg_json: &str,
         coins: &[Coin],
     ) -> Result<Message, TerraRustAPIError> {
@@ -47,9 +62,47 @@ impl MsgExecuteContract {
     }
 }
 
+/// who may instantiate a stored code
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum AccessType {
+    Nobody,
+    OnlyAddress,
+    Everybody,
+}
+/// the instantiate permission of a stored code
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccessConfig {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub address: String,
+    pub permission: AccessType,
+}
+impl AccessConfig {
+    pub fn everybody() -> AccessConfig {
+        AccessConfig {
+            address: String::new(),
+            permission: AccessType::Everybody,
+        }
+    }
+    pub fn nobody() -> AccessConfig {
+        AccessConfig {
+            address: String::new(),
+            permission: AccessType::Nobody,
+        }
+    }
+    pub fn only_address(address: &str) -> AccessConfig {
+        AccessConfig {
+            address: address.into(),
+            permission: AccessType::OnlyAddress,
+        }
+    }
+}
+
 #[derive(Serialize, Debug)]
 /// Message: Exec Contract
 pub struct MsgStoreCode {
+    /// who may instantiate the code. the chain's default if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instantiate_permission: Option<AccessConfig>,
     pub sender: String,
     pub wasm_byte_code: String,
 }
@@ -58,10 +111,19 @@ impl MsgInternal for MsgStoreCode {}
 impl MsgStoreCode {
     /// use provided base64 exec message
     pub fn create_from_b64(
-        sender: &str,
+        sender: impl Into<String>,
         wasm_byte_code: &str,
+    ) -> Result<Message, TerraRustAPIError> {
+        MsgStoreCode::create_from_b64_with_permission(sender, wasm_byte_code, None)
+    }
+    /// base64 wasm, restricting who can instantiate it
+    pub fn create_from_b64_with_permission(
+        sender: impl Into<String>,
+        wasm_byte_code: &str,
+        instantiate_permission: Option<AccessConfig>,
     ) -> Result<Message, TerraRustAPIError> {
         let internal = MsgStoreCode {
+            instantiate_permission,
             sender: sender.into(),
             wasm_byte_code: wasm_byte_code.into(),
         };
@@ -71,10 +133,21 @@ impl MsgStoreCode {
         })
     }
     /// use provided base64 exec message
-    pub fn create_from_file(sender: &str, file_name: &Path) -> Result<Message, TerraRustAPIError> {
+    pub fn create_from_file(
+        sender: impl Into<String>,
+        file_name: &Path,
+    ) -> Result<Message, TerraRustAPIError> {
+        MsgStoreCode::create_from_file_with_permission(sender, file_name, None)
+    }
+    /// wasm file, restricting who can instantiate it
+    pub fn create_from_file_with_permission(
+        sender: impl Into<String>,
+        file_name: &Path,
+        instantiate_permission: Option<AccessConfig>,
+    ) -> Result<Message, TerraRustAPIError> {
         let file_contents = std::fs::read(file_name)?;
         let exec_b64 = base64::encode(file_contents);
-        MsgStoreCode::create_from_b64(sender, &exec_b64)
+        MsgStoreCode::create_from_b64_with_permission(sender, &exec_b64, instantiate_permission)
     }
 }
 
@@ -116,11 +189,11 @@ impl MsgInstantiateContract {
      */
     /// create from JSON
     pub fn create_from_json(
-        sender: &str,
+        sender: impl Into<String>,
         admin: Option<String>,
         code_id: u64,
         init_msg: &str,
-        init_coins: Vec<Coin>,
+        init_coins: &[Coin],
     ) -> Result<Message, TerraRustAPIError> {
         // panic!("This message does not function");
 
@@ -131,7 +204,7 @@ impl MsgInstantiateContract {
             admin, //: admin.unwrap_or_else(|| "".into()),
             code_id,
             sender: sender.into(),
-            init_coins,
+            init_coins: init_coins.to_vec(),
             init_msg: contents,
         };
         Ok(Message {
@@ -142,16 +215,25 @@ impl MsgInstantiateContract {
     /// use provided base64 exec message
     /// switches ##SENDER##, ##ADMIN##, ##CODE_ID## with respective values
     pub fn create_from_file(
-        sender: &str,
+        sender: impl Into<String>,
         admin: Option<String>,
         code_id: u64,
         init_file: &Path,
-        init_coins: Vec<Coin>,
+        init_coins: &[Coin],
     ) -> Result<Message, TerraRustAPIError> {
+        let sender = sender.into();
         let contents = std::fs::read_to_string(init_file)?;
-        let new_contents = Self::replace_parameters(sender, admin.clone(), code_id, &contents);
+        let new_contents = Self::fill_parameters(&sender, admin.clone(), code_id, &contents)?;
         Self::create_from_json(sender, admin, code_id, &new_contents, init_coins)
     }
+    /// the placeholders instantiating fills: `##SENDER##`, `##ADMIN##` (empty without one) and `##CODE_ID##`.
+    /// add a registry with [MsgTemplate::contracts] for `##contract:name##`
+    pub fn template(sender: &str, admin: Option<String>, code_id: u64) -> MsgTemplate {
+        MsgTemplate::default()
+            .var("SENDER", sender)
+            .var("ADMIN", admin.unwrap_or_default())
+            .var("CODE_ID", code_id.to_string())
+    }
     /// replace parts of the string with current values
     pub fn replace_parameters(
         sender: &str,
@@ -167,6 +249,16 @@ impl MsgInstantiateContract {
             None => part_1.replace("##ADMIN##", ""),
         }
     }
+    /// [MsgInstantiateContract::replace_parameters], failing on any placeholder left unfilled.
+    /// see [MsgInstantiateContract::template]
+    pub fn fill_parameters(
+        sender: &str,
+        admin: Option<String>,
+        code_id: u64,
+        instantiate_str: &str,
+    ) -> Result<String, TerraRustAPIError> {
+        Self::template(sender, admin, code_id).render(instantiate_str)
+    }
 }
 
 #[derive(Serialize, Debug)]
@@ -183,16 +275,16 @@ impl MsgInternal for MsgMigrateContract {}
 impl MsgMigrateContract {
     /// create from JSON
     pub fn create_from_json(
-        admin: &str,
-        contract: &str,
+        admin: impl Into<String>,
+        contract: impl Into<String>,
         new_code_id: u64,
         migrate_msg: &str,
     ) -> Result<Message, TerraRustAPIError> {
         let contents: serde_json::Value = serde_json::from_str(migrate_msg)?;
 
         let internal = MsgMigrateContract {
-            admin: String::from(admin),
-            contract: String::from(contract),
+            admin: admin.into(),
+            contract: contract.into(),
             new_code_id,
             migrate_msg: contents,
         };
@@ -204,17 +296,28 @@ impl MsgMigrateContract {
     /// use provided base64 exec message
     /// switches ##SENDER##, ##ADMIN##, ##CODE_ID## with respective values
     pub fn create_from_file(
-        admin: &str,
-        contract: &str,
+        admin: impl Into<String>,
+        contract: impl Into<String>,
         new_code_id: u64,
         migrate_file: &Path,
     ) -> Result<Message, TerraRustAPIError> {
+        let (admin, contract) = (admin.into(), contract.into());
         let contents = std::fs::read_to_string(migrate_file)?;
-        let new_contents = Self::replace_parameters(admin, contract, new_code_id, &contents);
+        let new_contents = Self::fill_parameters(&admin, &contract, new_code_id, &contents)?;
 
         Self::create_from_json(admin, contract, new_code_id, &new_contents)
     }
-    /// switches ##SENDER##, ##ADMIN##, ##CODE_ID## with respective values
+    /// the placeholders migrating fills: `##ADMIN##` and `##SENDER##` for the admin migrating it, `##CONTRACT##`,
+    /// and `##NEW_CODE_ID##` or `##CODE_ID##`. add a registry with [MsgTemplate::contracts] for `##contract:name##`
+    pub fn template(admin: &str, contract: &str, new_code_id: u64) -> MsgTemplate {
+        MsgTemplate::default()
+            .var("ADMIN", admin)
+            .var("SENDER", admin)
+            .var("CONTRACT", contract)
+            .var("NEW_CODE_ID", new_code_id.to_string())
+            .var("CODE_ID", new_code_id.to_string())
+    }
+    /// replace parts of the string with current values
     pub fn replace_parameters(
         admin: &str,
         contract: &str,
@@ -226,6 +329,16 @@ impl MsgMigrateContract {
             .replace("##CONTRACT##", contract)
             .replace("##NEW_CODE_ID##", &format!("{}", new_code_id))
     }
+    /// [MsgMigrateContract::replace_parameters], failing on any placeholder left unfilled.
+    /// see [MsgMigrateContract::template]
+    pub fn fill_parameters(
+        admin: &str,
+        contract: &str,
+        new_code_id: u64,
+        migrate_str: &str,
+    ) -> Result<String, TerraRustAPIError> {
+        Self::template(admin, contract, new_code_id).render(migrate_str)
+    }
 }
 
 #[cfg(test)]
@@ -272,4 +385,26 @@ mod tst {
         assert_eq!(out_json.trim(), js);
         Ok(())
     }
+    #[test]
+    pub fn test_instantiate_permission() -> anyhow::Result<()> {
+        let msg = MsgStoreCode::create_from_b64_with_permission(
+            "terra1vr0e7kylhu9am44v0s3gwkccmz7k3naxysrwew",
+            "AGFzbQ==",
+            Some(AccessConfig::only_address(
+                "terra1vr0e7kylhu9am44v0s3gwkccmz7k3naxysrwew",
+            )),
+        )?;
+        assert_eq!(
+            serde_json::to_string(&msg)?,
+            r#"{"type":"wasm/MsgStoreCode","value":{"instantiate_permission":{"address":"terra1vr0e7kylhu9am44v0s3gwkccmz7k3naxysrwew","permission":"OnlyAddress"},"sender":"terra1vr0e7kylhu9am44v0s3gwkccmz7k3naxysrwew","wasm_byte_code":"AGFzbQ=="}}"#
+        );
+        let msg = MsgStoreCode::create_from_b64_with_permission(
+            "terra1vr0e7kylhu9am44v0s3gwkccmz7k3naxysrwew",
+            "AGFzbQ==",
+            Some(AccessConfig::nobody()),
+        )?;
+        assert!(serde_json::to_string(&msg)?
+            .contains(r#""instantiate_permission":{"permission":"Nobody"}"#));
+        Ok(())
+    }
 }
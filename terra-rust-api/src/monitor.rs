@@ -0,0 +1,266 @@
+/*!
+ * Compare the health of several LCD and RPC endpoints.
+ *
+ * [NodeMonitor] polls each endpoint for its latest height and whether it is still catching up,
+ * then fetches the block hash at the highest height they all have, to spot nodes on a fork.
+ * Use [NodeMonitor::check] for a single report, or [NodeMonitor::watch] to be called back on every poll.
+ */
+use crate::Terra;
+use futures::future::join_all;
+use std::time::{Duration, Instant};
+
+/// a node to watch
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Endpoint {
+    LCD(String),
+    RPC(String),
+}
+impl Endpoint {
+    pub fn url(&self) -> &str {
+        match self {
+            Endpoint::LCD(url) => url,
+            Endpoint::RPC(url) => url,
+        }
+    }
+}
+
+/// how one endpoint looked on the last poll
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+    pub endpoint: Endpoint,
+    /// latest height. None if the node couldn't be reached
+    pub height: Option<u64>,
+    pub catching_up: bool,
+    /// how far behind the best node it is
+    pub lag: u64,
+    /// how long the node took to give its latest block
+    pub latency: Option<Duration>,
+    /// block hash at the report's common height
+    pub common_hash: Option<String>,
+    pub error: Option<String>,
+}
+impl NodeStatus {
+    fn failed(endpoint: &Endpoint, error: String) -> NodeStatus {
+        NodeStatus {
+            endpoint: endpoint.clone(),
+            height: None,
+            catching_up: false,
+            lag: 0,
+            latency: None,
+            common_hash: None,
+            error: Some(error),
+        }
+    }
+    /// reachable, synced, and no more than `max_lag` blocks behind
+    pub fn is_healthy(&self, max_lag: u64) -> bool {
+        self.error.is_none() && self.height.is_some() && !self.catching_up && self.lag <= max_lag
+    }
+}
+
+/// the result of polling all the endpoints
+#[derive(Debug, Clone)]
+pub struct MonitorReport {
+    pub nodes: Vec<NodeStatus>,
+    /// the highest height any node has
+    pub best_height: u64,
+    /// the highest height all reachable nodes have. block hashes are compared here
+    pub common_height: Option<u64>,
+    /// nodes disagree on the block hash at the common height
+    pub forked: bool,
+}
+impl MonitorReport {
+    /// the nodes that are fine to send requests to
+    pub fn healthy(&self, max_lag: u64) -> Vec<&NodeStatus> {
+        self.nodes
+            .iter()
+            .filter(|n| n.is_healthy(max_lag))
+            .collect()
+    }
+    /// the healthy node with the highest block, fastest first
+    pub fn best(&self, max_lag: u64) -> Option<&NodeStatus> {
+        self.healthy(max_lag)
+            .into_iter()
+            .min_by_key(|n| (n.lag, n.latency))
+    }
+    /// the endpoints grouped by the block hash they have at the common height
+    pub fn forks(&self) -> Vec<(String, Vec<&Endpoint>)> {
+        let mut forks: Vec<(String, Vec<&Endpoint>)> = vec![];
+        for node in &self.nodes {
+            if let Some(hash) = &node.common_hash {
+                match forks.iter_mut().find(|(h, _)| h == hash) {
+                    Some((_, endpoints)) => endpoints.push(&node.endpoint),
+                    None => forks.push((hash.clone(), vec![&node.endpoint])),
+                }
+            }
+        }
+        forks
+    }
+}
+
+/// polls a set of endpoints and compares them
+pub struct NodeMonitor {
+    terra: Terra,
+    pub endpoints: Vec<Endpoint>,
+    /// how many blocks behind the best a node can be and still be healthy
+    pub max_lag: u64,
+}
+impl NodeMonitor {
+    /// the HTTP client and its settings are taken from `terra`
+    pub fn create(terra: &Terra, endpoints: Vec<Endpoint>) -> NodeMonitor {
+        NodeMonitor {
+            terra: terra.clone(),
+            endpoints,
+            max_lag: 2,
+        }
+    }
+    async fn latest(&self, endpoint: &Endpoint) -> anyhow::Result<(u64, bool)> {
+        match endpoint {
+            Endpoint::LCD(url) => {
+                let lcd = self.terra.with_lcd(url.as_str());
+                let block = lcd.tendermint().blocks().await?;
+                let syncing = lcd.tendermint().syncing().await?;
+                Ok((block.block.header.height, syncing.syncing))
+            }
+            Endpoint::RPC(url) => {
+                let status = self.terra.rpc(url).status().await?;
+                Ok((
+                    status.sync_info.latest_block_height,
+                    status.sync_info.catching_up,
+                ))
+            }
+        }
+    }
+    async fn hash_at(&self, endpoint: &Endpoint, height: u64) -> anyhow::Result<String> {
+        let block = match endpoint {
+            Endpoint::LCD(url) => {
+                self.terra
+                    .with_lcd(url.as_str())
+                    .tendermint()
+                    .blocks_at_height(height)
+                    .await?
+            }
+            Endpoint::RPC(url) => self.terra.rpc(url).block_at_height(height).await?,
+        };
+        Ok(block.block_id.hash)
+    }
+    async fn poll(&self, endpoint: &Endpoint) -> NodeStatus {
+        let start = Instant::now();
+        match self.latest(endpoint).await {
+            Ok((height, catching_up)) => NodeStatus {
+                endpoint: endpoint.clone(),
+                height: Some(height),
+                catching_up,
+                lag: 0,
+                latency: Some(start.elapsed()),
+                common_hash: None,
+                error: None,
+            },
+            Err(e) => {
+                log::warn!("{} is unreachable: {}", endpoint.url(), e);
+                NodeStatus::failed(endpoint, e.to_string())
+            }
+        }
+    }
+    /// poll every endpoint once
+    pub async fn check(&self) -> MonitorReport {
+        let mut nodes = join_all(self.endpoints.iter().map(|e| self.poll(e))).await;
+        let best_height = nodes
+            .iter()
+            .filter_map(|n| n.height)
+            .max()
+            .unwrap_or_default();
+        let common_height = nodes.iter().filter_map(|n| n.height).min();
+        for node in nodes.iter_mut() {
+            if let Some(height) = node.height {
+                node.lag = best_height - height;
+            }
+        }
+        if let Some(common_height) = common_height {
+            let hashes = join_all(
+                nodes
+                    .iter()
+                    .map(|n| self.hash_at(&n.endpoint, common_height)),
+            )
+            .await;
+            for (node, hash) in nodes.iter_mut().zip(hashes) {
+                if node.error.is_some() {
+                    continue;
+                }
+                match hash {
+                    Ok(hash) => node.common_hash = Some(hash),
+                    Err(e) => node.error = Some(e.to_string()),
+                }
+            }
+        }
+        let mut report = MonitorReport {
+            nodes,
+            best_height,
+            common_height,
+            forked: false,
+        };
+        report.forked = report.forks().len() > 1;
+        if report.forked {
+            log::error!(
+                "nodes disagree on the block at height {}",
+                common_height.unwrap_or_default()
+            );
+        }
+        report
+    }
+    /// poll every `interval`, handing each report to `callback`. stops when it returns false
+    pub async fn watch<F: FnMut(&MonitorReport) -> bool>(
+        &self,
+        interval: Duration,
+        mut callback: F,
+    ) {
+        loop {
+            let report = self.check().await;
+            if !callback(&report) {
+                break;
+            }
+            crate::runtime::sleep(interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    fn node(url: &str, height: Option<u64>, lag: u64, hash: Option<&str>) -> NodeStatus {
+        NodeStatus {
+            endpoint: Endpoint::LCD(url.into()),
+            height,
+            catching_up: false,
+            lag,
+            latency: Some(Duration::from_millis(lag * 10)),
+            common_hash: hash.map(String::from),
+            error: if height.is_none() {
+                Some("down".into())
+            } else {
+                None
+            },
+        }
+    }
+    #[test]
+    pub fn test_report() {
+        let report = MonitorReport {
+            nodes: vec![
+                node("a", Some(100), 0, Some("AA")),
+                node("b", Some(95), 5, Some("AA")),
+                node("c", None, 0, None),
+                node("d", Some(99), 1, Some("BB")),
+            ],
+            best_height: 100,
+            common_height: Some(95),
+            forked: true,
+        };
+        assert_eq!(report.healthy(2).len(), 2);
+        assert_eq!(report.best(2).unwrap().endpoint.url(), "a");
+        let forks = report.forks();
+        assert_eq!(forks.len(), 2);
+        assert_eq!(forks[0].1.len(), 2);
+        assert_eq!(forks[1].1[0].url(), "d");
+    }
+}
@@ -0,0 +1,171 @@
+/*!
+ * Tell someone what happened to a transaction.
+ *
+ * Give a [Terra] client a [TxNotifier] with [Terra::with_notifier], and the `submit_transaction_*`
+ * helpers report each transaction as it is broadcast, lands in a block, or fails.
+ * [CallbackNotifier] hands the events to your own async function, [WebhookNotifier] POSTs them as JSON.
+ * A notifier that fails, or takes longer than [NOTIFY_TIMEOUT], only logs; it never fails or holds up the transaction.
+ */
+use crate::runtime::Timer;
+use crate::Terra;
+use futures::future::{self, BoxFuture, Either};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// how long a notifier gets before the transaction carries on without it
+pub const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// what happened to a transaction
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TxEvent {
+    /// accepted into the mempool
+    Broadcasted { chain_id: String, txhash: String },
+    /// committed in a block
+    Included {
+        chain_id: String,
+        txhash: String,
+        height: u64,
+        gas_used: u64,
+    },
+    /// rejected when broadcast, or committed with a failing code. `txhash` is empty if it never got one
+    Failed {
+        chain_id: String,
+        txhash: String,
+        code: Option<usize>,
+        log: String,
+    },
+}
+
+/// somewhere to send [TxEvent]s
+pub trait TxNotifier: Send + Sync {
+    fn notify<'a>(&'a self, event: &'a TxEvent) -> BoxFuture<'a, anyhow::Result<()>>;
+}
+
+/// calls an async function with each event
+pub struct CallbackNotifier<F>
+where
+    F: Fn(TxEvent) -> BoxFuture<'static, anyhow::Result<()>> + Send + Sync,
+{
+    callback: F,
+}
+impl<F> CallbackNotifier<F>
+where
+    F: Fn(TxEvent) -> BoxFuture<'static, anyhow::Result<()>> + Send + Sync,
+{
+    pub fn create(callback: F) -> CallbackNotifier<F> {
+        CallbackNotifier { callback }
+    }
+}
+impl<F> TxNotifier for CallbackNotifier<F>
+where
+    F: Fn(TxEvent) -> BoxFuture<'static, anyhow::Result<()>> + Send + Sync,
+{
+    fn notify<'a>(&'a self, event: &'a TxEvent) -> BoxFuture<'a, anyhow::Result<()>> {
+        (self.callback)(event.clone())
+    }
+}
+
+/// POSTs each event, as JSON, to a URL
+pub struct WebhookNotifier {
+    pub url: String,
+    client: reqwest::Client,
+}
+impl WebhookNotifier {
+    pub fn create<S: Into<String>>(url: S) -> WebhookNotifier {
+        WebhookNotifier {
+            url: url.into(),
+            client: Terra::http_client(),
+        }
+    }
+}
+impl TxNotifier for WebhookNotifier {
+    fn notify<'a>(&'a self, event: &'a TxEvent) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            self.client
+                .post(&self.url)
+                .json(event)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// send the event, logging any failure. gives up after [NOTIFY_TIMEOUT]
+pub(crate) async fn notify(
+    notifier: &Option<Arc<dyn TxNotifier>>,
+    timer: &dyn Timer,
+    event: TxEvent,
+) {
+    if let Some(notifier) = notifier {
+        match future::select(notifier.notify(&event), timer.sleep(NOTIFY_TIMEOUT)).await {
+            Either::Left((Ok(()), _)) => {}
+            Either::Left((Err(e), _)) => log::warn!("tx notification {:?} failed: {}", event, e),
+            Either::Right(_) => log::warn!(
+                "tx notification {:?} gave up after {:?}",
+                event,
+                NOTIFY_TIMEOUT
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    pub fn test_event_json() -> anyhow::Result<()> {
+        let event = TxEvent::Included {
+            chain_id: "bombay-12".into(),
+            txhash: "ABC".into(),
+            height: 10,
+            gas_used: 1000,
+        };
+        assert_eq!(
+            serde_json::to_string(&event)?,
+            r#"{"event":"included","chain_id":"bombay-12","txhash":"ABC","height":10,"gas_used":1000}"#
+        );
+        Ok(())
+    }
+    #[tokio::test]
+    pub async fn test_callback() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let sink = seen.clone();
+        let notifier: Option<Arc<dyn TxNotifier>> =
+            Some(Arc::new(CallbackNotifier::create(move |event| {
+                let sink = sink.clone();
+                Box::pin(async move {
+                    sink.lock().unwrap().push(event);
+                    Ok(())
+                }) as BoxFuture<'static, anyhow::Result<()>>
+            })));
+        let event = TxEvent::Broadcasted {
+            chain_id: "bombay-12".into(),
+            txhash: "ABC".into(),
+        };
+        // the timeout never passes
+        let timer = crate::runtime::FnTimer::new(|_| Box::pin(future::pending()));
+        notify(&notifier, &timer, event.clone()).await;
+        notify(&None, &timer, event.clone()).await;
+        assert_eq!(*seen.lock().unwrap(), vec![event]);
+    }
+    #[tokio::test]
+    pub async fn test_stuck_notifier() {
+        let notifier: Option<Arc<dyn TxNotifier>> =
+            Some(Arc::new(CallbackNotifier::create(|_| {
+                Box::pin(future::pending()) as BoxFuture<'static, anyhow::Result<()>>
+            })));
+        let event = TxEvent::Broadcasted {
+            chain_id: "bombay-12".into(),
+            txhash: "ABC".into(),
+        };
+        // the timeout passes at once
+        let timer = crate::runtime::FnTimer::new(|_| Box::pin(async {}));
+        notify(&notifier, &timer, event).await;
+    }
+}
@@ -0,0 +1,231 @@
+/*!
+ * Price aggregation for oracle feeders.
+ *
+ * Implement [ExchangeSource] for each exchange you take prices from. [PriceFeed] polls them,
+ * keeps a rolling window of samples per denom, and turns the time weighted averages into an
+ * [MsgAggregateExchangeRateVote] ready to be pre-voted and voted.
+ */
+use crate::core_types::Coin;
+use crate::messages::oracle::MsgAggregateExchangeRateVote;
+use chrono::{DateTime, Duration, Utc};
+use futures::future::{join_all, BoxFuture};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, VecDeque};
+
+/// a price seen at an exchange
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceSample {
+    pub price: Decimal,
+    /// the volume traded at this price. use 1 if the exchange doesn't say
+    pub volume: Decimal,
+    pub time: DateTime<Utc>,
+}
+impl PriceSample {
+    pub fn create(price: Decimal, volume: Decimal, time: DateTime<Utc>) -> PriceSample {
+        PriceSample {
+            price,
+            volume,
+            time,
+        }
+    }
+}
+
+/// somewhere to get prices from. returns (denom, sample) pairs, with prices in the oracle's quote (uluna)
+pub trait ExchangeSource: Send + Sync {
+    /// a name for the logs
+    fn name(&self) -> String;
+    fn prices(&self) -> BoxFuture<'_, anyhow::Result<Vec<(String, PriceSample)>>>;
+}
+
+/// time weighted average price between `from` and `to`.
+/// each sample's price holds until the next one. samples must be in time order
+pub fn twap(samples: &[PriceSample], from: DateTime<Utc>, to: DateTime<Utc>) -> Option<Decimal> {
+    let mut weighted = Decimal::ZERO;
+    let mut total = Decimal::ZERO;
+    for (i, sample) in samples.iter().enumerate() {
+        let start = sample.time.max(from);
+        let end = samples.get(i + 1).map(|n| n.time).unwrap_or(to).min(to);
+        if end <= start {
+            continue;
+        }
+        let weight = Decimal::from((end - start).num_milliseconds());
+        weighted += sample.price * weight;
+        total += weight;
+    }
+    if total.is_zero() {
+        // a single sample, or all at the same instant
+        return samples.last().map(|s| s.price);
+    }
+    Some(weighted / total)
+}
+
+/// volume weighted average price
+pub fn vwap(samples: &[PriceSample]) -> Option<Decimal> {
+    let volume: Decimal = samples.iter().map(|s| s.volume).sum();
+    if volume.is_zero() {
+        return None;
+    }
+    Some(samples.iter().map(|s| s.price * s.volume).sum::<Decimal>() / volume)
+}
+
+/// the samples seen over the last `window`
+#[derive(Debug, Clone)]
+pub struct PriceWindow {
+    pub window: Duration,
+    samples: VecDeque<PriceSample>,
+}
+impl PriceWindow {
+    pub fn create(window: Duration) -> PriceWindow {
+        PriceWindow {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+    /// add a sample, dropping any that have fallen out of the window.
+    /// the most recent sample before the window is kept, as its price holds at the window's start
+    pub fn add(&mut self, sample: PriceSample) {
+        let pos = self
+            .samples
+            .iter()
+            .rposition(|s| s.time <= sample.time)
+            .map(|p| p + 1)
+            .unwrap_or(0);
+        self.samples.insert(pos, sample);
+        let start = self.samples[self.samples.len() - 1].time - self.window;
+        while self.samples.len() > 1 && self.samples[1].time <= start {
+            self.samples.pop_front();
+        }
+    }
+    pub fn samples(&self) -> Vec<PriceSample> {
+        self.samples.iter().cloned().collect()
+    }
+    pub fn twap(&self, now: DateTime<Utc>) -> Option<Decimal> {
+        twap(&self.samples(), now - self.window, now)
+    }
+    pub fn vwap(&self, now: DateTime<Utc>) -> Option<Decimal> {
+        let start = now - self.window;
+        vwap(
+            &self
+                .samples()
+                .into_iter()
+                .filter(|s| s.time >= start)
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// polls exchange sources and builds oracle votes from the averages
+pub struct PriceFeed {
+    pub sources: Vec<Box<dyn ExchangeSource>>,
+    pub window: Duration,
+    windows: BTreeMap<String, PriceWindow>,
+}
+impl PriceFeed {
+    pub fn create(sources: Vec<Box<dyn ExchangeSource>>, window: Duration) -> PriceFeed {
+        PriceFeed {
+            sources,
+            window,
+            windows: Default::default(),
+        }
+    }
+    /// add a sample by hand
+    pub fn add(&mut self, denom: &str, sample: PriceSample) {
+        let window = self.window;
+        self.windows
+            .entry(denom.to_string())
+            .or_insert_with(|| PriceWindow::create(window))
+            .add(sample);
+    }
+    /// fetch from every source. a failing source is logged and skipped.
+    /// returns how many samples were added
+    pub async fn poll(&mut self) -> usize {
+        let results = join_all(self.sources.iter().map(|s| s.prices())).await;
+        let mut samples = vec![];
+        for (source, result) in self.sources.iter().zip(results) {
+            match result {
+                Ok(prices) => samples.extend(prices),
+                Err(e) => log::warn!("price source {} failed: {}", source.name(), e),
+            }
+        }
+        let count = samples.len();
+        for (denom, sample) in samples {
+            self.add(&denom, sample);
+        }
+        count
+    }
+    /// the time weighted average of each denom
+    pub fn rates(&self, now: DateTime<Utc>) -> Vec<Coin> {
+        self.windows
+            .iter()
+            .filter_map(|(denom, window)| window.twap(now).map(|p| Coin::create(denom, p)))
+            .collect()
+    }
+    /// a vote on the current rates. use [MsgAggregateExchangeRateVote::gen_pre_vote] for its pre-vote
+    pub fn vote(
+        &self,
+        salt: &str,
+        feeder: &str,
+        validator: &str,
+        now: DateTime<Utc>,
+    ) -> MsgAggregateExchangeRateVote {
+        MsgAggregateExchangeRateVote::create_internal(salt, &self.rates(now), feeder, validator)
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_640_000_000 + secs, 0).unwrap()
+    }
+    struct Fixed(Vec<(String, PriceSample)>);
+    impl ExchangeSource for Fixed {
+        fn name(&self) -> String {
+            "fixed".into()
+        }
+        fn prices(&self) -> BoxFuture<'_, anyhow::Result<Vec<(String, PriceSample)>>> {
+            Box::pin(async move { Ok(self.0.clone()) })
+        }
+    }
+
+    #[test]
+    pub fn test_averages() {
+        let samples = vec![
+            PriceSample::create(dec!(10), dec!(1), at(0)),
+            PriceSample::create(dec!(20), dec!(3), at(30)),
+        ];
+        // 10 for 30s, 20 for 10s
+        assert_eq!(twap(&samples, at(0), at(40)), Some(dec!(12.5)));
+        assert_eq!(twap(&samples, at(20), at(40)), Some(dec!(15)));
+        assert_eq!(twap(&samples[..1], at(0), at(0)), Some(dec!(10)));
+        assert_eq!(vwap(&samples), Some(dec!(17.5)));
+        assert_eq!(vwap(&[]), None);
+
+        let mut window = PriceWindow::create(Duration::seconds(60));
+        window.add(PriceSample::create(dec!(1), dec!(1), at(0)));
+        window.add(PriceSample::create(dec!(2), dec!(1), at(30)));
+        window.add(PriceSample::create(dec!(3), dec!(1), at(100)));
+        // the price from 30s holds at the start of the window
+        assert_eq!(window.samples().len(), 2);
+        assert_eq!(window.twap(at(100)), Some(dec!(2)));
+        assert_eq!(window.twap(at(130)), Some(dec!(2.5)));
+    }
+    #[tokio::test]
+    pub async fn test_feed() {
+        let source = Fixed(vec![
+            (
+                "ukrw".into(),
+                PriceSample::create(dec!(100), dec!(1), at(0)),
+            ),
+            ("uusd".into(), PriceSample::create(dec!(80), dec!(1), at(0))),
+        ]);
+        let mut feed = PriceFeed::create(vec![Box::new(source)], Duration::seconds(60));
+        assert_eq!(feed.poll().await, 2);
+        feed.add("ukrw", PriceSample::create(dec!(200), dec!(1), at(30)));
+        let vote = feed.vote("salt", "feeder", "validator", at(60));
+        assert_eq!(vote.exchange_rates, "150ukrw,80uusd");
+    }
+}
@@ -0,0 +1,419 @@
+/*!
+ * Verification of the ICS-23 merkle proofs returned by `abci_query` with `prove=true`.
+ *
+ * A value is proven in two steps. An IAVL proof takes it up to the root of its module's store,
+ * and a 'simple' (tendermint) proof takes that store root up to the app hash.
+ * The app hash should come from a header verified with [crate::light].
+ * The state at height H is committed to by the app_hash of the header at H+1.
+ *
+ * Only proofs of existence are checked. A proof that a key is absent is reported as an error.
+ */
+use crate::core_types::Coin;
+use crate::errors::TerraRustAPIError;
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// the proof op types the SDK returns
+const IAVL_OP: &str = "ics23:iavl";
+const SIMPLE_OP: &str = "ics23:simple";
+
+const HASH_OP_NO_HASH: u64 = 0;
+const HASH_OP_SHA256: u64 = 1;
+const LENGTH_OP_VAR_PROTO: u64 = 1;
+
+fn fail<T>(reason: String) -> Result<T, TerraRustAPIError> {
+    Err(TerraRustAPIError::LightClient(reason))
+}
+
+fn sha256(bytes: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.input(bytes);
+    let mut out = vec![0u8; 32];
+    hasher.result(&mut out);
+    out
+}
+
+use crate::protobuf as decode;
+use decode::Value;
+
+#[derive(Debug, Default)]
+struct LeafOp {
+    hash: u64,
+    prehash_key: u64,
+    prehash_value: u64,
+    length: u64,
+    prefix: Vec<u8>,
+}
+#[derive(Debug, Default)]
+struct InnerOp {
+    hash: u64,
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+}
+#[derive(Debug, Default)]
+struct ExistenceProof {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    leaf: LeafOp,
+    path: Vec<InnerOp>,
+}
+
+impl LeafOp {
+    fn decode(bytes: &[u8]) -> Result<LeafOp, TerraRustAPIError> {
+        let mut leaf = LeafOp::default();
+        for (field, value) in decode::fields(bytes)? {
+            match (field, value) {
+                (1, Value::Varint(v)) => leaf.hash = v,
+                (2, Value::Varint(v)) => leaf.prehash_key = v,
+                (3, Value::Varint(v)) => leaf.prehash_value = v,
+                (4, Value::Varint(v)) => leaf.length = v,
+                (5, Value::Bytes(v)) => leaf.prefix = v.to_vec(),
+                _ => {}
+            }
+        }
+        Ok(leaf)
+    }
+    fn apply(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, TerraRustAPIError> {
+        if self.hash != HASH_OP_SHA256
+            || self.prehash_key != HASH_OP_NO_HASH
+            || self.prehash_value != HASH_OP_SHA256
+            || self.length != LENGTH_OP_VAR_PROTO
+        {
+            return fail(format!("unsupported leaf op {:?}", self));
+        }
+        let mut data = self.prefix.clone();
+        crate::light::proto::uvarint(&mut data, key.len() as u64);
+        data.extend_from_slice(key);
+        let value = sha256(value);
+        crate::light::proto::uvarint(&mut data, value.len() as u64);
+        data.extend(value);
+        Ok(sha256(&data))
+    }
+}
+impl InnerOp {
+    fn decode(bytes: &[u8]) -> Result<InnerOp, TerraRustAPIError> {
+        let mut inner = InnerOp::default();
+        for (field, value) in decode::fields(bytes)? {
+            match (field, value) {
+                (1, Value::Varint(v)) => inner.hash = v,
+                (2, Value::Bytes(v)) => inner.prefix = v.to_vec(),
+                (3, Value::Bytes(v)) => inner.suffix = v.to_vec(),
+                _ => {}
+            }
+        }
+        Ok(inner)
+    }
+    fn apply(&self, child: &[u8]) -> Result<Vec<u8>, TerraRustAPIError> {
+        if self.hash != HASH_OP_SHA256 {
+            return fail(format!("unsupported inner op hash {}", self.hash));
+        }
+        Ok(sha256(&[&self.prefix, child, &self.suffix].concat()))
+    }
+}
+
+/// the shape of the trees, so an inner node can't be passed off as a leaf or vice versa
+struct ProofSpec {
+    leaf_prefix: &'static [u8],
+    min_prefix_length: usize,
+    max_prefix_length: usize,
+    child_size: usize,
+}
+const IAVL_SPEC: ProofSpec = ProofSpec {
+    leaf_prefix: &[0],
+    min_prefix_length: 4,
+    max_prefix_length: 12,
+    child_size: 33,
+};
+const TENDERMINT_SPEC: ProofSpec = ProofSpec {
+    leaf_prefix: &[0],
+    min_prefix_length: 1,
+    max_prefix_length: 1,
+    child_size: 32,
+};
+
+impl ExistenceProof {
+    fn decode(bytes: &[u8]) -> Result<ExistenceProof, TerraRustAPIError> {
+        let mut proof = ExistenceProof::default();
+        for (field, value) in decode::fields(bytes)? {
+            match (field, value) {
+                (1, Value::Bytes(v)) => proof.key = v.to_vec(),
+                (2, Value::Bytes(v)) => proof.value = v.to_vec(),
+                (3, Value::Bytes(v)) => proof.leaf = LeafOp::decode(v)?,
+                (4, Value::Bytes(v)) => proof.path.push(InnerOp::decode(v)?),
+                _ => {}
+            }
+        }
+        Ok(proof)
+    }
+    /// the existence proof inside a CommitmentProof
+    fn from_commitment(bytes: &[u8]) -> Result<ExistenceProof, TerraRustAPIError> {
+        for (field, value) in decode::fields(bytes)? {
+            match (field, value) {
+                (1, Value::Bytes(v)) => return ExistenceProof::decode(v),
+                (2, _) => return fail("proofs of absence are not supported".into()),
+                _ => {}
+            }
+        }
+        fail("no existence proof found".into())
+    }
+    fn root(&self, spec: &ProofSpec) -> Result<Vec<u8>, TerraRustAPIError> {
+        if !self.leaf.prefix.starts_with(spec.leaf_prefix) {
+            return fail("leaf has the wrong prefix".into());
+        }
+        let mut hash = self.leaf.apply(&self.key, &self.value)?;
+        for inner in &self.path {
+            if inner.prefix.starts_with(spec.leaf_prefix)
+                || inner.prefix.len() < spec.min_prefix_length
+                || inner.prefix.len() > spec.max_prefix_length + spec.child_size
+                || inner.suffix.len() % spec.child_size != 0
+            {
+                return fail("inner node does not match the tree's spec".into());
+            }
+            hash = inner.apply(&hash)?;
+        }
+        Ok(hash)
+    }
+}
+
+/// a proof op, as found in `proofOps` in the abci_query response
+#[derive(Debug, Clone)]
+pub struct ProofOp {
+    pub op_type: String,
+    pub key: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+/// check that `store` holds `value` under `key`, in the state committed to by `app_hash`
+pub fn verify_membership(
+    ops: &[ProofOp],
+    store: &str,
+    key: &[u8],
+    value: &[u8],
+    app_hash: &[u8],
+) -> Result<(), TerraRustAPIError> {
+    let (iavl, simple) = match ops {
+        [iavl, simple] if iavl.op_type == IAVL_OP && simple.op_type == SIMPLE_OP => (iavl, simple),
+        _ => return fail("expected an iavl proof followed by a simple proof".into()),
+    };
+    let in_store = ExistenceProof::from_commitment(&iavl.data)?;
+    if in_store.key != key || iavl.key != key {
+        return fail("proof is for a different key".into());
+    }
+    if in_store.value != value {
+        return fail("proof is for a different value".into());
+    }
+    let store_root = in_store.root(&IAVL_SPEC)?;
+
+    let in_app = ExistenceProof::from_commitment(&simple.data)?;
+    if in_app.key != store.as_bytes() || simple.key != store.as_bytes() {
+        return fail(format!("proof is not for the {} store", store));
+    }
+    if in_app.value != store_root {
+        return fail(format!("{} store root does not match", store));
+    }
+    if in_app.root(&TENDERMINT_SPEC)? != app_hash {
+        return fail("proof does not lead to the app hash".into());
+    }
+    Ok(())
+}
+
+/// the key a contract's raw storage `key` is kept under in the wasm store
+pub fn contract_store_key(contract: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut store_key = vec![0x05, contract.len() as u8];
+    store_key.extend_from_slice(contract);
+    store_key.extend_from_slice(key);
+    store_key
+}
+
+/// the key an account's balance of `denom` is kept under in the bank store
+pub fn balance_store_key(account: &[u8], denom: &str) -> Vec<u8> {
+    let mut store_key = vec![0x02, account.len() as u8];
+    store_key.extend_from_slice(account);
+    store_key.extend_from_slice(denom.as_bytes());
+    store_key
+}
+
+/// a bank balance, as kept in the bank store
+pub fn decode_coin(bytes: &[u8]) -> Result<Coin, TerraRustAPIError> {
+    let mut denom = String::new();
+    let mut amount = String::new();
+    for (field, value) in decode::fields(bytes)? {
+        match (field, value) {
+            (1, Value::Bytes(v)) => denom = String::from_utf8(v.to_vec())?,
+            (2, Value::Bytes(v)) => amount = String::from_utf8(v.to_vec())?,
+            _ => {}
+        }
+    }
+    Ok(Coin::create(&denom, Decimal::from_str(&amount)?))
+}
+
+/// query `key` in `store` through the RPC's abci_query, and verify the proof against `app_hash`.
+/// `app_hash` must come from the verified header at `height + 1`
+#[cfg(feature = "rpc")]
+pub async fn verified_query(
+    terra: &crate::Terra,
+    rpc_url: &str,
+    store: &str,
+    key: &[u8],
+    height: u64,
+    app_hash: &[u8],
+) -> Result<Vec<u8>, TerraRustAPIError> {
+    let response = terra
+        .rpc(rpc_url)
+        .abci_query(&format!("/store/{}/key", store), key, Some(height), true)
+        .await
+        // the RPC calls give anyhow errors, wrapping the request's
+        .map_err(|e| {
+            e.downcast::<TerraRustAPIError>()
+                .unwrap_or_else(|e| TerraRustAPIError::StdErr(e.to_string()))
+        })?;
+    if response.code != 0 {
+        return Err(TerraRustAPIError::StdErr(response.log));
+    }
+    if response.height != height {
+        return Err(TerraRustAPIError::LightClient(format!(
+            "asked for height {}, got {}",
+            height, response.height
+        )));
+    }
+    let value = match &response.value {
+        Some(value) => base64::decode(value)?,
+        None => {
+            return Err(TerraRustAPIError::LightClient(
+                "key not found. proofs of absence are not supported".into(),
+            ))
+        }
+    };
+    let ops = response
+        .proof_ops
+        .map(|p| p.ops)
+        .unwrap_or_default()
+        .iter()
+        .map(|op| {
+            Ok(ProofOp {
+                op_type: op.op_type.clone(),
+                key: base64::decode(&op.key)?,
+                data: base64::decode(&op.data)?,
+            })
+        })
+        .collect::<Result<Vec<_>, TerraRustAPIError>>()?;
+    verify_membership(&ops, store, key, &value, app_hash)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::light::proto;
+
+    fn leaf_op(prefix: &[u8]) -> Vec<u8> {
+        let mut leaf = vec![];
+        proto::varint(&mut leaf, 1, HASH_OP_SHA256);
+        proto::varint(&mut leaf, 3, HASH_OP_SHA256);
+        proto::varint(&mut leaf, 4, LENGTH_OP_VAR_PROTO);
+        proto::bytes(&mut leaf, 5, prefix);
+        leaf
+    }
+    /// a CommitmentProof of `key` sitting one level below the root
+    fn commitment(key: &[u8], value: &[u8], leaf_prefix: &[u8], inner_prefix: &[u8]) -> Vec<u8> {
+        let mut inner = vec![];
+        proto::varint(&mut inner, 1, HASH_OP_SHA256);
+        proto::bytes(&mut inner, 2, inner_prefix);
+        let mut exist = vec![];
+        proto::bytes(&mut exist, 1, key);
+        proto::bytes(&mut exist, 2, value);
+        proto::message(&mut exist, 3, &leaf_op(leaf_prefix));
+        proto::message(&mut exist, 4, &inner);
+        let mut out = vec![];
+        proto::message(&mut out, 1, &exist);
+        out
+    }
+
+    #[test]
+    pub fn test_verify_membership() -> anyhow::Result<()> {
+        let key = balance_store_key(&[7u8; 20], "uluna");
+        let value = b"balance".to_vec();
+        // an iavl inner node: height, size, version varints, then the sibling's hash
+        let mut iavl_inner_prefix = vec![2, 4, 2, 0x20];
+        iavl_inner_prefix.extend(sha256(b"sibling"));
+        let iavl_leaf_prefix = [0, 2, 2];
+        let iavl = commitment(&key, &value, &iavl_leaf_prefix, &iavl_inner_prefix);
+
+        let mut leaf = iavl_leaf_prefix.to_vec();
+        proto::uvarint(&mut leaf, key.len() as u64);
+        leaf.extend(&key);
+        proto::uvarint(&mut leaf, 32);
+        leaf.extend(sha256(&value));
+        let store_root = sha256(&[iavl_inner_prefix.clone(), sha256(&leaf)].concat());
+
+        let mut simple_inner_prefix = vec![1];
+        simple_inner_prefix.extend(sha256(b"another store"));
+        let simple = commitment(b"bank", &store_root, &[0], &simple_inner_prefix);
+        let mut leaf = vec![0, 4];
+        leaf.extend(b"bank");
+        proto::uvarint(&mut leaf, 32);
+        leaf.extend(sha256(&store_root));
+        let app_hash = sha256(&[simple_inner_prefix, sha256(&leaf)].concat());
+
+        let ops = vec![
+            ProofOp {
+                op_type: IAVL_OP.into(),
+                key: key.clone(),
+                data: iavl,
+            },
+            ProofOp {
+                op_type: SIMPLE_OP.into(),
+                key: b"bank".to_vec(),
+                data: simple,
+            },
+        ];
+        verify_membership(&ops, "bank", &key, &value, &app_hash)?;
+        assert!(verify_membership(&ops, "bank", &key, b"more balance", &app_hash).is_err());
+        assert!(verify_membership(&ops, "wasm", &key, &value, &app_hash).is_err());
+        assert!(verify_membership(&ops, "bank", &key, &value, &sha256(b"fork")).is_err());
+        assert!(verify_membership(&ops[..1], "bank", &key, &value, &app_hash).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    pub async fn test_verified_query_errors() -> anyhow::Result<()> {
+        use crate::client::wire_log::{Replay, WireLogEntry};
+        let key = balance_store_key(&[7u8; 20], "uluna");
+        let url = format!(
+            "http://rpc/abci_query?path=%22/store/bank/key%22&data=0x{}&prove=true&height=5",
+            hex::encode(&key)
+        );
+        // the request's own error, not a string
+        let terra = crate::Terra::lcd_client_no_tx("http://lcd", "columbus-5")
+            .with_replay(Replay::create(vec![]));
+        let missing = verified_query(&terra, "http://rpc", "bank", &key, 5, &[]).await;
+        assert!(
+            matches!(
+                missing.as_ref().map_err(|e| e.without_context()),
+                Err(TerraRustAPIError::ReplayMissing(..))
+            ),
+            "{:?}",
+            missing
+        );
+        let terra = crate::Terra::lcd_client_no_tx("http://lcd", "columbus-5").with_replay(
+            Replay::create(vec![WireLogEntry {
+                status: Some(200),
+                response: Some(
+                    r#"{"jsonrpc":"2.0","id":-1,"result":{"response":{"code":0,"log":"","info":"","index":"0","key":null,"value":"YQ==","proofOps":null,"height":"6","codespace":""}}}"#
+                        .into(),
+                ),
+                ..WireLogEntry::create("GET", &url, None)
+            }]),
+        );
+        let wrong_height = verified_query(&terra, "http://rpc", "bank", &key, 5, &[]).await;
+        assert!(
+            matches!(&wrong_height, Err(TerraRustAPIError::LightClient(_))),
+            "{:?}",
+            wrong_height
+        );
+        Ok(())
+    }
+}
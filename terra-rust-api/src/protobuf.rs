@@ -0,0 +1,90 @@
+//! a minimal protobuf reader, enough to walk proofs and transactions without generated code
+use crate::errors::TerraRustAPIError;
+
+fn fail<T>(reason: &str) -> Result<T, TerraRustAPIError> {
+    Err(TerraRustAPIError::Protobuf(reason.into()))
+}
+
+pub enum Value<'a> {
+    Varint(u64),
+    Fixed(u64),
+    Bytes(&'a [u8]),
+}
+impl<'a> Value<'a> {
+    pub fn bytes(&self) -> Result<&'a [u8], TerraRustAPIError> {
+        match self {
+            Value::Bytes(bytes) => Ok(bytes),
+            _ => fail("expected a length delimited field"),
+        }
+    }
+    pub fn string(&self) -> Result<String, TerraRustAPIError> {
+        Ok(String::from_utf8(self.bytes()?.to_vec())?)
+    }
+    /// a varint, or fixed width, integer
+    pub fn varint(&self) -> Result<u64, TerraRustAPIError> {
+        match self {
+            Value::Varint(value) | Value::Fixed(value) => Ok(*value),
+            _ => fail("expected an integer field"),
+        }
+    }
+}
+
+pub fn uvarint(bytes: &[u8], pos: &mut usize) -> Result<u64, TerraRustAPIError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = match bytes.get(*pos) {
+            Some(byte) => *byte,
+            None => return fail("truncated message"),
+        };
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    fail("varint too long")
+}
+
+fn take<'a>(bytes: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], TerraRustAPIError> {
+    match bytes.get(*pos..*pos + len) {
+        Some(value) => {
+            *pos += len;
+            Ok(value)
+        }
+        None => fail("truncated message"),
+    }
+}
+
+/// a message, as (field, wire value) pairs
+pub fn fields(bytes: &[u8]) -> Result<Vec<(u64, Value<'_>)>, TerraRustAPIError> {
+    let mut pos = 0;
+    let mut out = vec![];
+    while pos < bytes.len() {
+        let key = uvarint(bytes, &mut pos)?;
+        let value = match key & 7 {
+            0 => Value::Varint(uvarint(bytes, &mut pos)?),
+            1 => {
+                let mut fixed = [0u8; 8];
+                fixed.copy_from_slice(take(bytes, &mut pos, 8)?);
+                Value::Fixed(u64::from_le_bytes(fixed))
+            }
+            2 => {
+                let len = uvarint(bytes, &mut pos)? as usize;
+                Value::Bytes(take(bytes, &mut pos, len)?)
+            }
+            5 => {
+                let mut fixed = [0u8; 4];
+                fixed.copy_from_slice(take(bytes, &mut pos, 4)?);
+                Value::Fixed(u32::from_le_bytes(fixed) as u64)
+            }
+            wire_type => {
+                return Err(TerraRustAPIError::Protobuf(format!(
+                    "unexpected wire type {}",
+                    wire_type
+                )))
+            }
+        };
+        out.push((key >> 3, value));
+    }
+    Ok(out)
+}
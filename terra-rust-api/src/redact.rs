@@ -0,0 +1,220 @@
+use crate::keys::is_mnemonic_word;
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use regex::{Captures, Regex};
+use std::sync::RwLock;
+
+/// query parameters masked by default
+pub const DEFAULT_QUERY_PARAMS: &[&str] = &["key", "apikey", "api_key", "token", "access_token"];
+const MASK: &str = "[REDACTED]";
+
+lazy_static! {
+    /// 12 or more short lowercase words in a row, which may hold a mnemonic
+    static ref MNEMONIC: Regex = Regex::new(r"\b(?:[a-z]{3,8}\s+){11,}[a-z]{3,8}\b").unwrap();
+    static ref WORD: Regex = Regex::new(r"[a-z]+").unwrap();
+    /// json/debug output of fields holding key material
+    static ref SECRET_FIELD: Regex = Regex::new(
+        r#"(?i)("?(?:private_key|priv_key|privkey|mnemonic|seed|phrase|secret|password)"?\s*[:=]\s*)("[^"]*"|[^\s,}&]+)"#
+    )
+    .unwrap();
+    /// bip32 extended private keys
+    static ref XPRV: Regex = Regex::new(r"\b[xt]prv[1-9A-HJ-NP-Za-km-z]{100,}").unwrap();
+    static ref REDACTOR: RwLock<Redactor> = RwLock::new(Redactor::default());
+}
+
+/// masks mnemonics, private key material and sensitive query parameters in text that is about to be logged
+#[derive(Clone, Debug)]
+pub struct Redactor {
+    query_params: Option<Regex>,
+    /// masked wherever they appear, like API keys
+    secrets: Vec<String>,
+}
+impl Default for Redactor {
+    fn default() -> Self {
+        Redactor::create(DEFAULT_QUERY_PARAMS)
+    }
+}
+impl Redactor {
+    /// a redactor that also masks the values of these query parameters
+    pub fn create<S: AsRef<str>>(query_params: &[S]) -> Redactor {
+        let names = query_params
+            .iter()
+            .map(|p| regex::escape(p.as_ref()))
+            .collect::<Vec<_>>();
+        let query_params = if names.is_empty() {
+            None
+        } else {
+            Some(Regex::new(&format!(r"([?&](?:{})=)[^&#\s]*", names.join("|"))).unwrap())
+        };
+        Redactor {
+            query_params,
+            secrets: vec![],
+        }
+    }
+    /// also mask this exact text
+    pub fn with_secret(mut self, secret: &str) -> Redactor {
+        if !secret.is_empty() && !self.secrets.iter().any(|s| s == secret) {
+            self.secrets.push(secret.into());
+        }
+        self
+    }
+    pub fn redact(&self, text: &str) -> String {
+        let text = SECRET_FIELD.replace_all(text, format!("${{1}}\"{}\"", MASK).as_str());
+        let text = XPRV.replace_all(&text, MASK);
+        let mut text = MNEMONIC.replace_all(&text, mask_mnemonics);
+        for secret in &self.secrets {
+            if text.contains(secret.as_str()) {
+                text = text.replace(secret.as_str(), MASK).into();
+            }
+        }
+        match &self.query_params {
+            Some(query_params) => query_params
+                .replace_all(&text, format!("${{1}}{}", MASK).as_str())
+                .into_owned(),
+            None => text.into_owned(),
+        }
+    }
+}
+
+/// mask the runs of 12 or more BIP39 words in a run of lowercase words, leaving ordinary prose alone
+fn mask_mnemonics(captures: &Captures) -> String {
+    let candidate = &captures[0];
+    let mut masked = String::with_capacity(candidate.len());
+    let mut copied = 0;
+    let mut run: Vec<regex::Match> = vec![];
+    let mut words = WORD.find_iter(candidate).peekable();
+    while let Some(word) = words.next() {
+        let listed = is_mnemonic_word(word.as_str());
+        if listed {
+            run.push(word);
+        }
+        if !listed || words.peek().is_none() {
+            if run.len() >= 12 {
+                masked.push_str(&candidate[copied..run[0].start()]);
+                masked.push_str(MASK);
+                copied = run[run.len() - 1].end();
+            }
+            run.clear();
+        }
+    }
+    masked.push_str(&candidate[copied..]);
+    masked
+}
+
+/// replace the redactor used by [redact] and [RedactingLogger]
+pub fn set_redactor(redactor: Redactor) {
+    *REDACTOR.write().unwrap() = redactor;
+}
+
+/// mask this exact text from now on, in [redact] and [RedactingLogger]
+pub fn mask_secret(secret: &str) {
+    let mut redactor = REDACTOR.write().unwrap();
+    *redactor = redactor.clone().with_secret(secret);
+}
+
+/// mask secrets using the global redactor
+pub fn redact(text: &str) -> String {
+    REDACTOR.read().unwrap().redact(text)
+}
+
+/// mask secrets using the global redactor, and these exact texts as well
+pub fn redact_with_secrets(text: &str, secrets: &[String]) -> String {
+    let mut redactor = REDACTOR.read().unwrap().clone();
+    for secret in secrets {
+        redactor = redactor.with_secret(secret);
+    }
+    redactor.redact(text)
+}
+
+/// has something in the text been masked
+pub fn is_redacted(text: &str) -> bool {
+    text.contains(MASK)
+}
+
+/// a [log::Log] that redacts every message before handing it to another logger.
+/// Suitable for shipping production logs
+/// ```
+/// let inner = env_logger::Builder::from_default_env().build();
+/// let level = inner.filter();
+/// terra_rust_api::redact::RedactingLogger::init(Box::new(inner), level).unwrap();
+/// ```
+pub struct RedactingLogger {
+    inner: Box<dyn Log>,
+}
+impl RedactingLogger {
+    pub fn create(inner: Box<dyn Log>) -> RedactingLogger {
+        RedactingLogger { inner }
+    }
+    /// install as the global logger
+    pub fn init(inner: Box<dyn Log>, level: LevelFilter) -> Result<(), SetLoggerError> {
+        log::set_boxed_logger(Box::new(RedactingLogger::create(inner)))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+impl Log for RedactingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+    fn log(&self, record: &Record) {
+        if !self.inner.enabled(record.metadata()) {
+            return;
+        }
+        let message = redact(&record.args().to_string());
+        self.inner.log(
+            &Record::builder()
+                .metadata(record.metadata().clone())
+                .args(format_args!("{}", message))
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        )
+    }
+    fn flush(&self) {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_redact() {
+        let redactor = Redactor::default();
+        let words = "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut";
+        assert_eq!(
+            redactor.redact(&format!("seed phrase is: {} ok", words)),
+            "seed phrase is: [REDACTED] ok"
+        );
+        assert_eq!(
+            redactor.redact(r#"{"name":"x","private_key":"abcdef0123","height":"1"}"#),
+            r#"{"name":"x","private_key":"[REDACTED]","height":"1"}"#
+        );
+        assert_eq!(
+            redactor.redact("URL=https://lcd.example/blocks/latest?token=s3cret&height=5"),
+            "URL=https://lcd.example/blocks/latest?token=[REDACTED]&height=5"
+        );
+        assert_eq!(
+            Redactor::create(&["height"]).redact("https://lcd.example/x?height=5&token=y"),
+            "https://lcd.example/x?height=[REDACTED]&token=y"
+        );
+        // words that aren't all BIP39 words are prose, not a mnemonic
+        let prose =
+            "the quick brown fox jumps over the lazy dog while seven wizards quietly hex them";
+        assert_eq!(redactor.redact(prose), prose);
+        assert_eq!(
+            redactor.redact(&format!("we will restore from {} thanks", words)),
+            "we will restore from [REDACTED] thanks"
+        );
+        // normal sign docs pass through untouched
+        let sign_doc = r#"{"account_number":"49411","chain_id":"tequila-0004","memo":"PFC-terra-rust-anchor/0.1.1"}"#;
+        assert_eq!(redactor.redact(sign_doc), sign_doc);
+        assert_eq!(
+            redactor
+                .with_secret("k3y")
+                .redact("GET https://lcd.example/x?apikey=k3y failed: k3y"),
+            "GET https://lcd.example/x?apikey=[REDACTED] failed: [REDACTED]"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+/*!
+ * Find endpoints and gas prices in the cosmos chain registry, instead of hard coding them.
+ *
+ * [ChainRegistryEntry::fetch] reads a chain's `chain.json` from the registry (or a `file://` copy of it).
+ * [ChainRegistryEntry::healthy_endpoints] polls the public LCDs & RPCs it lists with a [NodeMonitor],
+ * and [ChainRegistryEntry::client] builds a [Terra] on the best LCD, with the registry's gas price.
+ */
+use crate::client::builder::TerraBuilder;
+use crate::core_types::Coin;
+use crate::errors::TerraRustAPIError;
+use crate::monitor::{Endpoint, NodeMonitor};
+use crate::Terra;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+
+/// terra's entry in the cosmos chain registry
+pub const CHAIN_REGISTRY_URL: &str =
+    "https://raw.githubusercontent.com/cosmos/chain-registry/master/terra/chain.json";
+
+/// a fee token, and the gas prices the registry suggests for it
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RegistryFeeToken {
+    pub denom: String,
+    pub fixed_min_gas_price: Option<Decimal>,
+    pub low_gas_price: Option<Decimal>,
+    pub average_gas_price: Option<Decimal>,
+    pub high_gas_price: Option<Decimal>,
+}
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct RegistryFees {
+    #[serde(default)]
+    pub fee_tokens: Vec<RegistryFeeToken>,
+}
+/// a public endpoint, and who runs it
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RegistryApi {
+    pub address: String,
+    pub provider: Option<String>,
+}
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct RegistryApis {
+    #[serde(default)]
+    pub rpc: Vec<RegistryApi>,
+    /// LCDs
+    #[serde(default)]
+    pub rest: Vec<RegistryApi>,
+    #[serde(default)]
+    pub grpc: Vec<RegistryApi>,
+}
+
+/// the parts of a chain registry `chain.json` the client uses
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ChainRegistryEntry {
+    pub chain_name: String,
+    pub chain_id: String,
+    pub bech32_prefix: Option<String>,
+    #[serde(default)]
+    pub fees: RegistryFees,
+    #[serde(default)]
+    pub apis: RegistryApis,
+}
+
+/// the endpoints that answered, synced, best first
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct RegistryEndpoints {
+    pub lcd: Vec<String>,
+    pub rpc: Vec<String>,
+}
+
+impl ChainRegistryEntry {
+    /// the entry at `url`, like [CHAIN_REGISTRY_URL], or a `file://` path
+    pub async fn fetch(url: &str) -> Result<ChainRegistryEntry, TerraRustAPIError> {
+        if let Some(file_name) = url.strip_prefix("file://") {
+            return ChainRegistryEntry::from_file(file_name);
+        }
+        let text = Terra::http_client()
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+    pub fn from_file(file_name: &str) -> Result<ChainRegistryEntry, TerraRustAPIError> {
+        Ok(serde_json::from_reader(File::open(file_name)?)?)
+    }
+    /// the LCDs listed, without trailing slashes
+    pub fn lcds(&self) -> Vec<String> {
+        ChainRegistryEntry::addresses(&self.apis.rest)
+    }
+    /// the RPCs listed, without trailing slashes
+    pub fn rpcs(&self) -> Vec<String> {
+        ChainRegistryEntry::addresses(&self.apis.rpc)
+    }
+    fn addresses(apis: &[RegistryApi]) -> Vec<String> {
+        apis.iter()
+            .map(|api| api.address.trim_end_matches('/').to_string())
+            .filter(|address| !address.is_empty())
+            .collect()
+    }
+    /// the denominations fees can be paid in, the registry's preferred first
+    pub fn fee_denoms(&self) -> Vec<String> {
+        self.fees
+            .fee_tokens
+            .iter()
+            .map(|token| token.denom.clone())
+            .collect()
+    }
+    /// the average gas price for `denom`, or the lowest one given if there is no average
+    pub fn gas_price(&self, denom: &str) -> Option<Coin> {
+        let token = self.fees.fee_tokens.iter().find(|t| t.denom == denom)?;
+        let price = token
+            .average_gas_price
+            .or(token.low_gas_price)
+            .or(token.fixed_min_gas_price)?;
+        Some(Coin::create(denom, price))
+    }
+    /// poll every LCD & RPC listed. those more than `max_lag` blocks behind the best are left out
+    pub async fn healthy_endpoints(&self, max_lag: u64) -> RegistryEndpoints {
+        let endpoints = self
+            .lcds()
+            .into_iter()
+            .map(Endpoint::LCD)
+            .chain(self.rpcs().into_iter().map(Endpoint::RPC))
+            .collect::<Vec<_>>();
+        let terra = Terra::lcd_client_no_tx("", &self.chain_id);
+        let mut monitor = NodeMonitor::create(&terra, endpoints);
+        monitor.max_lag = max_lag;
+        let report = monitor.check().await;
+        let mut healthy = report.healthy(max_lag);
+        healthy.sort_by_key(|n| (n.lag, n.latency));
+        let mut found = RegistryEndpoints::default();
+        for node in healthy {
+            match &node.endpoint {
+                Endpoint::LCD(url) => found.lcd.push(url.clone()),
+                Endpoint::RPC(url) => found.rpc.push(url.clone()),
+            }
+        }
+        found
+    }
+    /// settings for the chain on `lcd`, paying fees in the registry's preferred denom at its gas price
+    pub fn builder_for(&self, lcd: &str) -> TerraBuilder {
+        let builder = Terra::builder().lcd(lcd).chain_id(self.chain_id.as_str());
+        let gas_price = self
+            .fee_denoms()
+            .first()
+            .and_then(|denom| self.gas_price(denom));
+        match gas_price {
+            Some(gas_price) => builder
+                .gas_prices(gas_price.to_string())
+                .gas_denom(gas_price.denom),
+            None => builder,
+        }
+    }
+    /// [ChainRegistryEntry::builder_for] the healthiest LCD
+    pub async fn builder(&self, max_lag: u64) -> Result<TerraBuilder, TerraRustAPIError> {
+        match self.healthy_endpoints(max_lag).await.lcd.first() {
+            Some(lcd) => Ok(self.builder_for(lcd)),
+            None => Err(TerraRustAPIError::ChainRegistry(
+                self.chain_id.clone(),
+                "none of the LCDs listed is healthy".into(),
+            )),
+        }
+    }
+    /// a client on the healthiest LCD
+    pub async fn client(&self, max_lag: u64) -> Result<Terra, TerraRustAPIError> {
+        self.builder(max_lag).await?.build().await
+    }
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    pub async fn test_registry_entry() -> anyhow::Result<()> {
+        let json = r#"{
+            "$schema": "../chain.schema.json",
+            "chain_name": "terra",
+            "status": "live",
+            "network_type": "mainnet",
+            "chain_id": "columbus-5",
+            "bech32_prefix": "terra",
+            "fees": {
+                "fee_tokens": [
+                    {"denom": "uluna", "fixed_min_gas_price": 28.325, "low_gas_price": 28.325, "average_gas_price": 28.325, "high_gas_price": 50},
+                    {"denom": "uusd", "fixed_min_gas_price": 0.75}
+                ]
+            },
+            "apis": {
+                "rpc": [{"address": "https://rpc.example.com/", "provider": "example"}],
+                "rest": [
+                    {"address": "https://lcd.example.com", "provider": "example"},
+                    {"address": "https://lcd.other.net/"}
+                ]
+            }
+        }"#;
+        let entry: ChainRegistryEntry = serde_json::from_str(json)?;
+        assert_eq!(entry.chain_id, "columbus-5");
+        assert_eq!(
+            entry.lcds(),
+            vec!["https://lcd.example.com", "https://lcd.other.net"]
+        );
+        assert_eq!(entry.rpcs(), vec!["https://rpc.example.com"]);
+        assert_eq!(entry.fee_denoms(), vec!["uluna", "uusd"]);
+        assert_eq!(
+            entry.gas_price("uluna"),
+            Some(Coin::create("uluna", dec!(28.325)))
+        );
+        assert_eq!(
+            entry.gas_price("uusd"),
+            Some(Coin::create("uusd", dec!(0.75)))
+        );
+        assert_eq!(entry.gas_price("ukrw"), None);
+        assert_eq!(entry.fees.fee_tokens[0].high_gas_price, Some(dec!(50)));
+
+        let gas_options = entry
+            .builder_for("https://lcd.example.com")
+            .gas_options()
+            .await?;
+        assert_eq!(
+            gas_options.gas_price,
+            Some(Coin::create("uluna", dec!(28.325)))
+        );
+
+        let bare: ChainRegistryEntry =
+            serde_json::from_str(r#"{"chain_name":"terra","chain_id":"columbus-5"}"#)?;
+        assert!(bare.lcds().is_empty());
+        assert!(bare.fee_denoms().is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+/*!
+* Waiting between polls, without tying the crate to tokio's timer.
+*
+* The helpers that poll, like [crate::client::tx::TX::get_and_wait_v1], [crate::client::tx::TX::confirmation],
+* [crate::follow::BlockFollower::follow] and the watchers, sleep through a [Timer].
+* Under a tokio runtime, with the `tokio` feature, that is tokio's timer. Anywhere else there is no default:
+* give one for the whole process with [set_timer] or for one client with [crate::Terra::with_timer],
+* otherwise the first sleep panics. [ThreadTimer] works anywhere, at the cost of an OS thread per sleep.
+*
+* Only the sleeping is pluggable. The requests themselves go through reqwest, and so hyper, which still needs
+* a Tokio reactor. Under async-std or smol, run the futures inside `async_compat::Compat` (or a tokio runtime
+* on the side) as well as setting a timer
+* ```
+* use terra_rust_api::runtime::{self, FnTimer};
+* // with async-std, say
+* // runtime::set_timer(FnTimer::new(|duration| Box::pin(async_std::task::sleep(duration))));
+* # runtime::set_timer(FnTimer::new(|duration| Box::pin(async move { std::thread::sleep(duration) })));
+* ```
+*/
+use futures::channel::oneshot;
+use futures::future::BoxFuture;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// sleeps, for whichever executor is running the futures
+pub trait Timer: Send + Sync {
+    /// completes after `duration`
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// tokio's timer. needs a tokio runtime
+#[cfg(feature = "tokio")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioTimer;
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// sleeps on a thread of its own, so it works under any executor, at the cost of a thread per sleep.
+/// never picked unless asked for
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadTimer;
+impl Timer for ThreadTimer {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let (sender, receiver) = oneshot::channel::<()>();
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let _ = sender.send(());
+        });
+        Box::pin(async move {
+            let _ = receiver.await;
+        })
+    }
+}
+
+/// a closure returning the sleep future of the executor in use
+pub struct FnTimer<F> {
+    sleep: F,
+}
+impl<F: Fn(Duration) -> BoxFuture<'static, ()> + Send + Sync> FnTimer<F> {
+    pub fn new(sleep: F) -> FnTimer<F> {
+        FnTimer { sleep }
+    }
+}
+impl<F: Fn(Duration) -> BoxFuture<'static, ()> + Send + Sync> Timer for FnTimer<F> {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        (self.sleep)(duration)
+    }
+}
+
+lazy_static! {
+    static ref TIMER: RwLock<Option<Arc<dyn Timer>>> = RwLock::new(None);
+}
+
+/// use `timer` wherever a client hasn't been given one, from now on
+pub fn set_timer<T: Timer + 'static>(timer: T) {
+    *TIMER.write().unwrap() = Some(Arc::new(timer));
+}
+
+/// the timer set with [set_timer]. otherwise tokio's, when called within a tokio runtime
+///
+/// # Panics
+/// when no timer was set and there is no tokio runtime to fall back on
+pub fn timer() -> Arc<dyn Timer> {
+    if let Some(timer) = TIMER.read().unwrap().as_ref() {
+        return timer.clone();
+    }
+    #[cfg(feature = "tokio")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Arc::new(TokioTimer);
+    }
+    panic!("no timer to sleep with outside a tokio runtime: call terra_rust_api::runtime::set_timer or Terra::with_timer first")
+}
+
+/// sleep with [timer]
+pub async fn sleep(duration: Duration) {
+    timer().sleep(duration).await
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    pub fn test_thread_timer() {
+        // no tokio runtime here
+        let started = Instant::now();
+        futures::executor::block_on(ThreadTimer.sleep(Duration::from_millis(20)));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        futures::executor::block_on(
+            FnTimer::new(|duration| ThreadTimer.sleep(duration)).sleep(Duration::from_millis(1)),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "set_timer")]
+    pub fn test_no_default_timer() {
+        // nothing set, and no tokio runtime to fall back on
+        timer();
+    }
+}
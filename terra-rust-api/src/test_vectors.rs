@@ -0,0 +1,221 @@
+use crate::core_types::{Coin, StdFee, StdSignMsg};
+use crate::errors::TerraRustAPIError;
+use crate::messages::bank::MsgSend;
+use crate::{MsgExecuteContract, PrivateKey};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use rust_decimal_macros::dec;
+use secp256k1::{Secp256k1, Signing, Verification};
+use serde::{Deserialize, Serialize};
+
+/// A key derived from a seed phrase, a sign doc, and the key's signature over it.
+/// Written out as JSON, they let SDKs in other languages check they derive, serialize and sign
+/// exactly as this crate does. Never put a seed phrase holding funds in one
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    pub name: String,
+    pub mnemonic: String,
+    /// BIP44 path of the key
+    pub hd_path: String,
+    pub account: u32,
+    pub index: u32,
+    pub address: String,
+    /// compressed secp256k1 public key, base64
+    pub public_key: String,
+    /// the exact JSON text that is signed
+    pub sign_doc: String,
+    /// hex
+    pub sign_doc_sha256: String,
+    /// compact r|s, base64
+    pub signature: String,
+}
+
+fn sha256_hex(blob: &str) -> String {
+    let mut sha = Sha256::new();
+    sha.input_str(blob);
+    sha.result_str()
+}
+
+fn hd_path(private: &PrivateKey) -> String {
+    format!(
+        "m/44'/{}'/{}'/0/{}",
+        private.coin_type, private.account, private.index
+    )
+}
+
+impl TestVector {
+    /// sign `sign_msg` with the key at `account`/`index` of the seed phrase
+    pub fn generate<C: Signing>(
+        secp: &Secp256k1<C>,
+        name: &str,
+        mnemonic: &str,
+        account: u32,
+        index: u32,
+        sign_msg: &StdSignMsg,
+    ) -> Result<TestVector, TerraRustAPIError> {
+        let private = PrivateKey::from_words(secp, mnemonic, account, index)?;
+        let public = private.public_key(secp);
+        let sign_doc = serde_json::to_string(sign_msg)?;
+        let signature = private.sign(secp, &sign_doc)?;
+        Ok(TestVector {
+            name: name.into(),
+            mnemonic: mnemonic.into(),
+            hd_path: hd_path(&private),
+            account,
+            index,
+            address: public.account()?,
+            public_key: signature.pub_key.value,
+            sign_doc_sha256: sha256_hex(&sign_doc),
+            sign_doc,
+            signature: signature.signature,
+        })
+    }
+    /// derive, hash and sign again, failing with [TerraRustAPIError::TestVectorMismatch] on the
+    /// first field that differs. signatures are deterministic (RFC6979), so they must match exactly
+    pub fn verify<C: Signing + Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+    ) -> Result<(), TerraRustAPIError> {
+        let private = PrivateKey::from_words(secp, &self.mnemonic, self.account, self.index)?;
+        let public = private.public_key(secp);
+        let signature = private.sign(secp, &self.sign_doc)?;
+        let expected = [
+            ("hd_path", hd_path(&private), &self.hd_path),
+            ("address", public.account()?, &self.address),
+            (
+                "public_key",
+                signature.pub_key.value.clone(),
+                &self.public_key,
+            ),
+            (
+                "sign_doc_sha256",
+                sha256_hex(&self.sign_doc),
+                &self.sign_doc_sha256,
+            ),
+            ("signature", signature.signature.clone(), &self.signature),
+        ];
+        for (field, actual, fixture) in expected {
+            if &actual != fixture {
+                return Err(TerraRustAPIError::TestVectorMismatch(
+                    self.name.clone(),
+                    field.into(),
+                ));
+            }
+        }
+        signature.verify(secp, self.sign_doc.as_bytes())
+    }
+}
+
+/// the fixtures this crate's own tests sign, plus a few covering other derivation paths,
+/// multiple messages and escaping in memos
+pub fn standard_vectors<C: Signing>(
+    secp: &Secp256k1<C>,
+) -> Result<Vec<TestVector>, TerraRustAPIError> {
+    let island = "island relax shop such yellow opinion find know caught erode blue dolphin behind coach tattoo light focus snake common size analyst imitate employ walnut";
+    let sell = "sell raven long age tooth still predict idea quit march gasp bamboo hurdle problem voyage east tiger divide machine brain hole tiger find smooth";
+    let recipient = "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh";
+    let address = |words: &str, account: u32, index: u32| -> Result<String, TerraRustAPIError> {
+        PrivateKey::from_words(secp, words, account, index)?
+            .public_key(secp)
+            .account()
+    };
+    let sign_msg = |account_number, sequence, fee, msgs, memo: &str| StdSignMsg {
+        chain_id: "tequila-0004".into(),
+        account_number,
+        sequence,
+        fee,
+        memo: memo.into(),
+        msgs,
+    };
+
+    let from = address(island, 0, 0)?;
+    let send = sign_msg(
+        43045,
+        3,
+        StdFee::create_single(Coin::create("uluna", dec!(50000)), 90000),
+        vec![MsgSend::create_single(
+            from,
+            recipient,
+            Coin::create("uluna", dec!(100000)),
+        )?],
+        "PFC-terra-rust/0.1.5",
+    );
+
+    let from = address(island, 1, 2)?;
+    let multi_send = sign_msg(
+        7,
+        0,
+        StdFee::create(
+            vec![
+                Coin::create("uluna", dec!(1000)),
+                Coin::create("uusd", dec!(20000)),
+            ],
+            200000,
+        ),
+        vec![
+            MsgSend::create(
+                &from,
+                recipient,
+                &[
+                    Coin::create("uluna", dec!(1)),
+                    Coin::create("uusd", dec!(2)),
+                ],
+            )?,
+            MsgSend::create_single(from, recipient, Coin::create("ukrw", dec!(3)))?,
+        ],
+        "quotes \" slashes \\ & <tags> é",
+    );
+
+    let from = address(sell, 0, 0)?;
+    let execute = sign_msg(
+        102,
+        54,
+        StdFee::create_single(Coin::create("uusd", dec!(30000)), 250000),
+        vec![MsgExecuteContract::create_from_json(
+            &from,
+            "terra16ckeuu7c6ggu52a8se005mg5c0kd2kmuun63cu",
+            r#"{"cast_vote":{"poll_id":44,"vote":"yes","amount":"1000000"}}"#,
+            &[Coin::create("uluna", dec!(5))],
+        )?],
+        "",
+    );
+
+    Ok(vec![
+        TestVector::generate(secp, "send", island, 0, 0, &send)?,
+        TestVector::generate(secp, "multi_send", island, 1, 2, &multi_send)?,
+        TestVector::generate(secp, "execute_contract", sell, 0, 0, &execute)?,
+    ])
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+
+    #[test]
+    pub fn test_standard_vectors() -> anyhow::Result<()> {
+        let secp = Secp256k1::new();
+        let vectors = standard_vectors(&secp)?;
+        // the same signature as client.rs's test_send
+        assert_eq!(
+            vectors[0].signature,
+            "f1wYTzbSyAYqN2tGR0A4PGmfyNYBUExpuoU7UOiBDpNoRlChF/BMtE7h6pdgbpu/V7jNzitu1Eb0fO35dxVkWA=="
+        );
+        assert_eq!(vectors[1].hd_path, "m/44'/330'/1'/0/2");
+        for vector in &vectors {
+            vector.verify(&secp)?;
+        }
+        let json = serde_json::to_string(&vectors)?;
+        let mut tampered: Vec<TestVector> = serde_json::from_str(&json)?;
+        tampered[1].sign_doc = tampered[1].sign_doc.replace("ukrw", "uluna");
+        match tampered[1].verify(&secp) {
+            Err(TerraRustAPIError::TestVectorMismatch(name, field)) => {
+                assert_eq!(
+                    (name.as_str(), field.as_str()),
+                    ("multi_send", "sign_doc_sha256")
+                )
+            }
+            other => panic!("expected a mismatch, got {:?}", other),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+/*!
+ * Decode the transactions in a block, as found in the base64 `txs` of a tendermint block response.
+ *
+ * Columbus-5 and later encode transactions as protobuf. The messages are described by their type url,
+ * and the common ones are decoded into the same JSON the LCD gives.
+ * Earlier chains used amino. For those, the fee and memo are decoded, and the messages are named, but left raw.
+ */
+use crate::core_types::Coin;
+use crate::errors::TerraRustAPIError;
+use crate::protobuf;
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::{json, Map};
+use std::str::FromStr;
+
+/// how the transaction was encoded
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxEncoding {
+    Protobuf,
+    Amino,
+}
+
+/// a message inside a transaction
+#[derive(Serialize, Debug, Clone)]
+pub struct DecodedMessage {
+    /// the protobuf type url (/cosmos.bank.v1beta1.MsgSend) or amino name (bank/MsgSend)
+    pub type_url: String,
+    /// the message's fields. messages we don't know are given as {"raw": base64}
+    pub value: serde_json::Value,
+}
+
+/// a transaction from a block
+#[derive(Serialize, Debug, Clone)]
+pub struct DecodedTx {
+    /// the transaction hash, as used by the LCD's tx lookups
+    pub hash: String,
+    pub encoding: TxEncoding,
+    pub messages: Vec<DecodedMessage>,
+    pub fee: Vec<Coin>,
+    pub gas: u64,
+    pub memo: String,
+    pub timeout_height: u64,
+}
+
+/// how to turn a field into JSON
+enum Kind {
+    Str,
+    /// bytes that hold JSON, like a contract's execute message
+    Json,
+    Coin,
+    Coins,
+}
+
+/// the protobuf messages we know the shape of
+fn schema(type_url: &str) -> Option<&'static [(u64, &'static str, Kind)]> {
+    use Kind::*;
+    let fields: &'static [(u64, &'static str, Kind)] = match type_url {
+        "/cosmos.bank.v1beta1.MsgSend" => &[
+            (1, "from_address", Str),
+            (2, "to_address", Str),
+            (3, "amount", Coins),
+        ],
+        "/terra.wasm.v1beta1.MsgExecuteContract" => &[
+            (1, "sender", Str),
+            (2, "contract", Str),
+            (3, "execute_msg", Json),
+            (5, "coins", Coins),
+        ],
+        "/cosmos.staking.v1beta1.MsgDelegate" | "/cosmos.staking.v1beta1.MsgUndelegate" => &[
+            (1, "delegator_address", Str),
+            (2, "validator_address", Str),
+            (3, "amount", Coin),
+        ],
+        "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward" => {
+            &[(1, "delegator_address", Str), (2, "validator_address", Str)]
+        }
+        "/terra.market.v1beta1.MsgSwap" => &[
+            (1, "trader", Str),
+            (2, "offer_coin", Coin),
+            (3, "ask_denom", Str),
+        ],
+        "/terra.oracle.v1beta1.MsgAggregateExchangeRatePrevote" => {
+            &[(1, "hash", Str), (2, "feeder", Str), (3, "validator", Str)]
+        }
+        "/terra.oracle.v1beta1.MsgAggregateExchangeRateVote" => &[
+            (1, "salt", Str),
+            (2, "exchange_rates", Str),
+            (3, "feeder", Str),
+            (4, "validator", Str),
+        ],
+        _ => return None,
+    };
+    Some(fields)
+}
+
+/// the amino names we can recognise
+const AMINO_MESSAGES: &[&str] = &[
+    "bank/MsgSend",
+    "bank/MsgMultiSend",
+    "wasm/MsgExecuteContract",
+    "wasm/MsgInstantiateContract",
+    "wasm/MsgStoreCode",
+    "wasm/MsgMigrateContract",
+    "staking/MsgDelegate",
+    "staking/MsgUndelegate",
+    "staking/MsgBeginRedelegate",
+    "distribution/MsgWithdrawDelegationReward",
+    "distribution/MsgWithdrawValidatorCommission",
+    "market/MsgSwap",
+    "market/MsgSwapSend",
+    "oracle/MsgAggregateExchangeRatePrevote",
+    "oracle/MsgAggregateExchangeRateVote",
+    "oracle/MsgDelegateFeedConsent",
+    "gov/MsgVote",
+    "gov/MsgDeposit",
+    "gov/MsgSubmitProposal",
+];
+
+fn sha256(bytes: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.input(bytes);
+    let mut out = vec![0u8; 32];
+    hasher.result(&mut out);
+    out
+}
+
+/// the 4 bytes amino puts in front of a registered type
+fn amino_prefix(name: &str) -> Vec<u8> {
+    let hash = sha256(name.as_bytes());
+    // skip the disambiguation bytes
+    hash.iter()
+        .copied()
+        .skip_while(|b| *b == 0)
+        .skip(3)
+        .skip_while(|b| *b == 0)
+        .take(4)
+        .collect()
+}
+
+fn coin(bytes: &[u8]) -> Result<Coin, TerraRustAPIError> {
+    let mut denom = String::new();
+    let mut amount = String::new();
+    for (field, value) in protobuf::fields(bytes)? {
+        match field {
+            1 => denom = value.string()?,
+            2 => amount = value.string()?,
+            _ => {}
+        }
+    }
+    Ok(Coin::create(&denom, Decimal::from_str(&amount)?))
+}
+
+fn coin_json(coin: &Coin) -> serde_json::Value {
+    json!({"denom": coin.denom, "amount": coin.amount.to_string()})
+}
+
+fn decode_message(type_url: String, bytes: &[u8]) -> Result<DecodedMessage, TerraRustAPIError> {
+    let schema = match schema(&type_url) {
+        Some(schema) => schema,
+        None => {
+            return Ok(DecodedMessage {
+                type_url,
+                value: json!({ "raw": base64::encode(bytes) }),
+            })
+        }
+    };
+    let mut value = Map::new();
+    for (_, name, kind) in schema {
+        if let Kind::Coins = kind {
+            value.insert(name.to_string(), json!([]));
+        }
+    }
+    for (field, field_value) in protobuf::fields(bytes)? {
+        if let Some((_, name, kind)) = schema.iter().find(|(f, _, _)| *f == field) {
+            let json = match kind {
+                Kind::Str => json!(field_value.string()?),
+                Kind::Json => serde_json::from_slice(field_value.bytes()?)?,
+                Kind::Coin => coin_json(&coin(field_value.bytes()?)?),
+                Kind::Coins => {
+                    if let Some(serde_json::Value::Array(coins)) = value.get_mut(*name) {
+                        coins.push(coin_json(&coin(field_value.bytes()?)?));
+                    }
+                    continue;
+                }
+            };
+            value.insert(name.to_string(), json);
+        }
+    }
+    Ok(DecodedMessage {
+        type_url,
+        value: serde_json::Value::Object(value),
+    })
+}
+
+fn decode_proto(tx: &[u8], hash: String) -> Result<DecodedTx, TerraRustAPIError> {
+    let mut decoded = DecodedTx {
+        hash,
+        encoding: TxEncoding::Protobuf,
+        messages: vec![],
+        fee: vec![],
+        gas: 0,
+        memo: String::new(),
+        timeout_height: 0,
+    };
+    for (field, value) in protobuf::fields(tx)? {
+        match field {
+            // TxBody
+            1 => {
+                for (field, value) in protobuf::fields(value.bytes()?)? {
+                    match field {
+                        1 => {
+                            let mut type_url = String::new();
+                            let mut bytes: &[u8] = &[];
+                            for (field, value) in protobuf::fields(value.bytes()?)? {
+                                match field {
+                                    1 => type_url = value.string()?,
+                                    2 => bytes = value.bytes()?,
+                                    _ => {}
+                                }
+                            }
+                            decoded.messages.push(decode_message(type_url, bytes)?);
+                        }
+                        2 => decoded.memo = value.string()?,
+                        3 => decoded.timeout_height = value.varint()?,
+                        _ => {}
+                    }
+                }
+            }
+            // AuthInfo
+            2 => {
+                for (field, value) in protobuf::fields(value.bytes()?)? {
+                    if field == 2 {
+                        for (field, value) in protobuf::fields(value.bytes()?)? {
+                            match field {
+                                1 => decoded.fee.push(coin(value.bytes()?)?),
+                                2 => decoded.gas = value.varint()?,
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(decoded)
+}
+
+fn decode_amino(std_tx: &[u8], hash: String) -> Result<DecodedTx, TerraRustAPIError> {
+    let mut decoded = DecodedTx {
+        hash,
+        encoding: TxEncoding::Amino,
+        messages: vec![],
+        fee: vec![],
+        gas: 0,
+        memo: String::new(),
+        timeout_height: 0,
+    };
+    for (field, value) in protobuf::fields(std_tx)? {
+        match field {
+            1 => {
+                let msg = value.bytes()?;
+                let (prefix, bytes) = msg.split_at(msg.len().min(4));
+                let type_url = AMINO_MESSAGES
+                    .iter()
+                    .find(|name| amino_prefix(name) == prefix)
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("amino:{}", hex::encode(prefix)));
+                decoded.messages.push(DecodedMessage {
+                    type_url,
+                    value: json!({ "raw": base64::encode(bytes) }),
+                });
+            }
+            2 => {
+                for (field, value) in protobuf::fields(value.bytes()?)? {
+                    match field {
+                        1 => decoded.fee.push(coin(value.bytes()?)?),
+                        2 => decoded.gas = value.varint()?,
+                        _ => {}
+                    }
+                }
+            }
+            4 => decoded.memo = value.string()?,
+            _ => {}
+        }
+    }
+    Ok(decoded)
+}
+
+/// decode one transaction from a block
+pub fn decode_tx(tx_base64: &str) -> Result<DecodedTx, TerraRustAPIError> {
+    let tx = base64::decode(tx_base64)?;
+    let hash = hex::encode_upper(sha256(&tx));
+    let std_tx = amino_prefix("core/StdTx");
+    if tx.starts_with(&std_tx) {
+        return decode_amino(&tx[4..], hash);
+    }
+    // amino transactions were usually length prefixed
+    let mut pos = 0;
+    if let Ok(len) = protobuf::uvarint(&tx, &mut pos) {
+        let rest = &tx[pos..];
+        if len as usize == rest.len() && rest.starts_with(&std_tx) {
+            return decode_amino(&rest[4..], hash);
+        }
+    }
+    decode_proto(&tx, hash)
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use crate::light::proto;
+
+    fn coin_bytes(denom: &str, amount: &str) -> Vec<u8> {
+        let mut coin = vec![];
+        proto::bytes(&mut coin, 1, denom.as_bytes());
+        proto::bytes(&mut coin, 2, amount.as_bytes());
+        coin
+    }
+    #[test]
+    pub fn test_decode_proto() -> anyhow::Result<()> {
+        let mut send = vec![];
+        proto::bytes(&mut send, 1, b"terra1from");
+        proto::bytes(&mut send, 2, b"terra1to");
+        proto::message(&mut send, 3, &coin_bytes("uluna", "1000"));
+        let mut any = vec![];
+        proto::bytes(&mut any, 1, b"/cosmos.bank.v1beta1.MsgSend");
+        proto::message(&mut any, 2, &send);
+        let mut unknown = vec![];
+        proto::bytes(&mut unknown, 1, b"/some.Msg");
+        proto::message(&mut unknown, 2, &[8, 1]);
+        let mut body = vec![];
+        proto::message(&mut body, 1, &any);
+        proto::message(&mut body, 1, &unknown);
+        proto::bytes(&mut body, 2, b"hello");
+        let mut fee = vec![];
+        proto::message(&mut fee, 1, &coin_bytes("uusd", "3000"));
+        proto::varint(&mut fee, 2, 200000);
+        let mut auth_info = vec![];
+        proto::message(&mut auth_info, 2, &fee);
+        let mut tx = vec![];
+        proto::message(&mut tx, 1, &body);
+        proto::message(&mut tx, 2, &auth_info);
+        proto::bytes(&mut tx, 3, &[0u8; 64]);
+
+        let decoded = decode_tx(&base64::encode(&tx))?;
+        assert_eq!(decoded.encoding, TxEncoding::Protobuf);
+        assert_eq!(decoded.memo, "hello");
+        assert_eq!(decoded.gas, 200000);
+        assert_eq!(decoded.fee[0].to_string(), "3000uusd");
+        assert_eq!(decoded.messages.len(), 2);
+        assert_eq!(
+            decoded.messages[0].value,
+            json!({"from_address":"terra1from","to_address":"terra1to","amount":[{"denom":"uluna","amount":"1000"}]})
+        );
+        assert_eq!(decoded.messages[1].value, json!({"raw": "CAE="}));
+        Ok(())
+    }
+    #[test]
+    pub fn test_decode_amino() -> anyhow::Result<()> {
+        // the well known prefix of the cosmos-sdk's StdTx
+        assert_eq!(hex::encode(amino_prefix("cosmos-sdk/StdTx")), "282816a9");
+        let mut msg = amino_prefix("bank/MsgSend");
+        msg.extend(&[0x0a, 0x01, 0x01]);
+        let mut fee = vec![];
+        proto::message(&mut fee, 1, &coin_bytes("ukrw", "500"));
+        proto::varint(&mut fee, 2, 100000);
+        let mut std_tx = amino_prefix("core/StdTx");
+        proto::message(&mut std_tx, 1, &msg);
+        proto::message(&mut std_tx, 2, &fee);
+        proto::bytes(&mut std_tx, 4, b"memo");
+        let mut tx = vec![];
+        proto::uvarint(&mut tx, std_tx.len() as u64);
+        tx.extend(std_tx);
+
+        let decoded = decode_tx(&base64::encode(&tx))?;
+        assert_eq!(decoded.encoding, TxEncoding::Amino);
+        assert_eq!(decoded.messages[0].type_url, "bank/MsgSend");
+        assert_eq!(decoded.memo, "memo");
+        assert_eq!(decoded.gas, 100000);
+        Ok(())
+    }
+}
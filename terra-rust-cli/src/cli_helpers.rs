@@ -5,8 +5,12 @@ use secp256k1::{Context, Secp256k1, Signing};
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::Path;
-use terra_rust_api::core_types::Coin;
-use terra_rust_api::{GasOptions, PrivateKey, Terra};
+use std::time::Duration;
+use terra_rust_api::client::wire_log::WireLog;
+use terra_rust_api::market_types::SweepOptions;
+use terra_rust_api::{
+    ApiKey, GasOptions, HmacSigner, PrivateKey, PrunedHeights, Terra, TerraBuilder,
+};
 use terra_rust_wallet::Wallet;
 
 /// your terra swiss army knife
@@ -62,14 +66,36 @@ pub struct Cli<T: clap::FromArgMatches + clap::Subcommand> {
     pub seed: String,
     #[clap(
         name = "fees",
+        env = "TERRARUST_FEES",
         default_value = "",
         short,
         long = "fees",
-        help = "the fees to use. This will override gas parameters if specified."
+        help = "the fees to use, with a fixed --gas. This will override gas parameters if specified."
     )]
     pub fees: String,
+    #[clap(
+        name = "max-fee",
+        env = "TERRARUST_MAX_FEE",
+        long = "max-fee",
+        help = "don't send transactions with a fee over this. eg. 5000000uusd"
+    )]
+    pub max_fee: Option<String>,
+    #[clap(
+        name = "add-tax",
+        long = "add-tax",
+        help = "add the stability tax on the coins sent to fixed --fees"
+    )]
+    pub add_tax: bool,
+    #[clap(
+        name = "memo-policy",
+        env = "TERRARUST_MEMO_POLICY",
+        long = "memo-policy",
+        help = "the memo to sign with: default, none, a fixed memo, or a template like 'desk-7 {chain_id}/{sequence} {memo}'"
+    )]
+    pub memo_policy: Option<String>,
     #[clap(
         name = "gas",
+        env = "TERRARUST_GAS",
         default_value = "auto",
         long = "gas",
         help = "the gas amount to use 'auto' to estimate"
@@ -99,6 +125,13 @@ pub struct Cli<T: clap::FromArgMatches + clap::Subcommand> {
         help = "the adjustment to multiply the estimate to calculate the fee"
     )]
     pub gas_adjustment: f64,
+    #[clap(
+        name = "unix-socket",
+        env = "TERRARUST_UNIX_SOCKET",
+        long = "unix-socket",
+        help = "talk to a node on this host over its LCD's unix domain socket, instead of the lcd url"
+    )]
+    pub unix_socket: Option<String>,
     #[clap(short, long, parse(from_flag))]
     pub debug: std::sync::atomic::AtomicBool,
     #[clap(subcommand)]
@@ -106,42 +139,37 @@ pub struct Cli<T: clap::FromArgMatches + clap::Subcommand> {
 }
 impl<T: clap::FromArgMatches + clap::Subcommand> Cli<T> {
     pub async fn gas_opts(&self) -> Result<GasOptions, TerraRustCLIError> {
-        if self.gas_price == "auto" {
-            let client = reqwest::Client::new();
-            let gas_opts = GasOptions::create_with_fcd(
-                &client,
-                &self.fcd,
-                &self.gas_price_denom,
-                self.gas_adjustment,
-            )
-            .await?;
-            if let Some(gas_price) = &gas_opts.gas_price {
-                log::info!("Using Gas price of {}", gas_price);
-            }
-
-            Ok(gas_opts)
-        } else {
-            let fees = Coin::parse(&self.fees)?;
-            let gas_str = &self.gas;
-            let (estimate_gas, gas) = if gas_str == "auto" {
-                (true, None)
-            } else {
-                let g = &self.gas.parse::<u64>()?;
-                (false, Some(*g))
-            };
-
-            let gas_price = Coin::parse(&self.gas_price)?;
-            let gas_adjustment = Some(self.gas_adjustment);
-
-            Ok(GasOptions {
-                fees,
-                estimate_gas,
-                gas,
-                gas_price,
-                gas_adjustment,
-            })
+        Ok(self.try_builder()?.gas_options().await?)
+    }
+    /// the client settings from the command line, as a [TerraBuilder]. everything but
+    /// `--memo-policy`, which has to be parsed; [Cli::try_builder] includes it
+    pub fn builder(&self) -> TerraBuilder {
+        let builder = Terra::builder()
+            .lcd(&self.lcd)
+            .fcd(&self.fcd)
+            .chain_id(&self.chain_id)
+            .gas_prices(&self.gas_price)
+            .gas_denom(&self.gas_price_denom)
+            .gas_adjustment(self.gas_adjustment)
+            .fees(&self.fees)
+            .gas(&self.gas);
+        let builder = match &self.max_fee {
+            Some(max_fee) => builder.max_fee(max_fee),
+            None => builder,
+        }
+        .add_tax(self.add_tax);
+        match &self.unix_socket {
+            Some(unix_socket) => builder.unix_socket(unix_socket),
+            None => builder,
         }
     }
+    /// [Cli::builder], with the `--memo-policy`. fails if it doesn't parse
+    pub fn try_builder(&self) -> Result<TerraBuilder, TerraRustCLIError> {
+        Ok(match &self.memo_policy {
+            Some(memo_policy) => self.builder().memo_policy(memo_policy.parse()?),
+            None => self.builder(),
+        })
+    }
 }
 #[allow(dead_code)]
 pub fn gen_cli_read_only<'a>(app_name: &'a str, bin_name: &'a str) -> clap::Command<'a> {
@@ -174,13 +202,100 @@ pub fn gen_cli_read_only<'a>(app_name: &'a str, bin_name: &'a str) -> clap::Comm
                 .default_value("columbus-5")
                 .help("bombay-12 is testnet, columbus-5 is main-net"),
         )
+        .arg(
+            Arg::new("unix-socket")
+                .long("unix-socket")
+                .takes_value(true)
+                .value_name("unix-socket")
+                .env("TERRARUST_UNIX_SOCKET")
+                .help("talk to a node on this host over its LCD's unix domain socket, instead of the lcd url"),
+        )
+        .arg(
+            Arg::new("wire-log")
+                .long("wire-log")
+                .takes_value(true)
+                .value_name("wire-log")
+                .env("TERRARUST_WIRE_LOG")
+                .help("append every request and response, with secrets masked, to this file as JSON lines"),
+        )
+        .arg(
+            Arg::new("archive-lcd")
+                .long("archive-lcd")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .use_value_delimiter(true)
+                .value_name("archive-lcd")
+                .env("TERRARUST_ARCHIVE_LCD")
+                .help("archival LCDs to ask when the lcd has pruned the height a query asks for"),
+        )
+        .arg(
+            Arg::new("nearest-height")
+                .long("nearest-height")
+                .takes_value(false)
+                .help("when the lcd has pruned the height a query asks for, answer from the nearest height it has"),
+        )
+        .arg(
+            Arg::new("user-agent")
+                .long("user-agent")
+                .takes_value(true)
+                .value_name("user-agent")
+                .env("TERRARUST_USER_AGENT")
+                .help("the User-Agent to identify yourself to the LCD with"),
+        )
+        .arg(
+            Arg::new("api-key")
+                .long("api-key")
+                .takes_value(true)
+                .value_name("api-key")
+                .env("TERRARUST_API_KEY")
+                .hide_env_values(true)
+                .help("API key for a hosted LCD, only sent to it. sent as a bearer token, unless --api-key-header or --api-key-param is given"),
+        )
+        .arg(
+            Arg::new("api-key-header")
+                .long("api-key-header")
+                .takes_value(true)
+                .value_name("api-key-header")
+                .env("TERRARUST_API_KEY_HEADER")
+                .help("send the API key in this header, like x-api-key"),
+        )
+        .arg(
+            Arg::new("api-key-param")
+                .long("api-key-param")
+                .takes_value(true)
+                .value_name("api-key-param")
+                .conflicts_with("api-key-header")
+                .help("send the API key in this query parameter, like apikey"),
+        )
+        .arg(
+            Arg::new("hmac-key-id")
+                .long("hmac-key-id")
+                .takes_value(true)
+                .value_name("hmac-key-id")
+                .env("TERRARUST_HMAC_KEY_ID")
+                .requires("hmac-secret")
+                .help("sign every request to the LCD with HMAC-SHA256, for a gateway that knows this key id"),
+        )
+        .arg(
+            Arg::new("hmac-secret")
+                .long("hmac-secret")
+                .takes_value(true)
+                .value_name("hmac-secret")
+                .env("TERRARUST_HMAC_SECRET")
+                .hide_env_values(true)
+                .requires("hmac-key-id")
+                .help("the secret shared with the gateway, for --hmac-key-id"),
+        )
 }
 #[allow(dead_code)]
 pub fn gen_cli<'a>(app_name: &'a str, bin_name: &'a str) -> clap::Command<'a> {
     gen_cli_read_only(app_name,bin_name).args(&[
         Arg::new("wallet").long("wallet").takes_value(true).value_name("wallet").env("TERRARUST_WALLET").default_value("default").help( "the default wallet to look for keys in"),
         Arg::new("seed").long("seed").takes_value(true).value_name("seed").env("TERRARUST_SEED_PHRASE").default_value("").help(  "the seed phrase to use with this private key"),
-        Arg::new("fees").long("fees").takes_value(true).value_name("fees").default_value("").help(   "the fees to use. This will override gas parameters if specified."),
+        Arg::new("fees").long("fees").takes_value(true).value_name("fees").default_value("").help(   "the fees to use, with a fixed --gas. This will override gas parameters if specified."),
+        Arg::new("max-fee").long("max-fee").takes_value(true).value_name("max-fee").env("TERRARUST_MAX_FEE").help(   "don't send transactions with a fee over this. eg. 5000000uusd"),
+        Arg::new("add-tax").long("add-tax").help(   "add the stability tax on the coins sent to fixed --fees"),
+        Arg::new("memo-policy").long("memo-policy").takes_value(true).value_name("memo-policy").env("TERRARUST_MEMO_POLICY").help(   "the memo to sign with: default, none, a fixed memo, or a template like 'desk-7 {chain_id}/{sequence} {memo}'"),
         Arg::new("gas").long("gas").takes_value(true).value_name("gas").default_value("auto").help(   "the gas amount to use 'auto' to estimate"),
         Arg::new("gas-prices").long("gas-prices").takes_value(true).value_name("gas-prices").default_value("auto").help(    "the gas price to use to calculate fee. Format is NNNtoken eg. 1000uluna. note we only support a single price for now. if auto. it will use FCD"),
         Arg::new("gas-denom").long("gas-denom").takes_value(true).value_name("gas-denom").env("TERRARUST_GAS_DENOM").default_value("ukrw").help(    "the denomination/currency to use to pay fee. Format is uXXXX."),
@@ -193,51 +308,91 @@ pub fn gen_cli<'a>(app_name: &'a str, bin_name: &'a str) -> clap::Command<'a> {
             .value_name("phrase")
             .required(false)
             .help("the phrase words for the key"),
+        Arg::new("skip-validation")
+            .long("skip-validation")
+            .takes_value(false)
+            .help("sign messages without checking their addresses, coins and contract messages first"),
     ])
 }
 #[allow(dead_code)]
 pub async fn gas_opts(arg_matches: &ArgMatches) -> Result<GasOptions, TerraRustCLIError> {
-    let gas_price = arg_matches
-        .value_of("gas-prices")
-        .expect("gas-prices should be in the CLI");
-    let gas_adjustment = arg_matches
-        .value_of("gas-adjustment")
-        .unwrap()
-        .parse::<f64>()?;
-    if gas_price == "auto" {
-        let fcd = arg_matches.value_of("fcd").unwrap();
-        let gas_price_denom = arg_matches.value_of("gas-denom").unwrap();
-
-        let client = reqwest::Client::new();
-        let gas_opts =
-            GasOptions::create_with_fcd(&client, fcd, gas_price_denom, gas_adjustment).await?;
-        if let Some(gas_price) = &gas_opts.gas_price {
-            log::info!("Using Gas price of {}", gas_price);
-        }
-
-        Ok(gas_opts)
-    } else {
-        let gas_str = arg_matches.value_of("gas").unwrap();
-        let fees = Coin::parse(arg_matches.value_of("fees").unwrap())?;
-
-        let (estimate_gas, gas) = if gas_str == "auto" {
-            (true, None)
-        } else {
-            let g = &gas_str.parse::<u64>()?;
-            (false, Some(*g))
-        };
-
-        let gas_price = Coin::parse(gas_price)?;
-        let gas_adjustment = Some(gas_adjustment);
-
-        Ok(GasOptions {
-            fees,
-            estimate_gas,
-            gas,
-            gas_price,
-            gas_adjustment,
-        })
+    Ok(builder_from_args(arg_matches)?.gas_options().await?)
+}
+/// the API key from --api-key, sent the way --api-key-header / --api-key-param say
+fn api_key_from_args(arg_matches: &ArgMatches) -> Option<ApiKey> {
+    let key = arg_matches.value_of("api-key")?.to_string();
+    Some(
+        match (
+            arg_matches.value_of("api-key-header"),
+            arg_matches.value_of("api-key-param"),
+        ) {
+            (Some(header), _) => ApiKey::Header(header.into(), key),
+            (None, Some(param)) => ApiKey::QueryParam(param.into(), key),
+            (None, None) => ApiKey::Bearer(key),
+        },
+    )
+}
+/// the request signer from --hmac-key-id & --hmac-secret
+fn hmac_signer_from_args(arg_matches: &ArgMatches) -> Option<HmacSigner> {
+    Some(HmacSigner::create(
+        arg_matches.value_of("hmac-key-id")?,
+        arg_matches.value_of("hmac-secret")?,
+    ))
+}
+/// where to go when the LCD has pruned a height, from --archive-lcd & --nearest-height
+fn pruned_heights_from_args(arg_matches: &ArgMatches) -> PrunedHeights {
+    PrunedHeights {
+        archives: arg_matches
+            .values_of("archive-lcd")
+            .map(|archives| archives.map(String::from).collect())
+            .unwrap_or_default(),
+        nearest: arg_matches.is_present("nearest-height"),
+    }
+}
+/// the client settings from the command line, as a [TerraBuilder]
+#[allow(dead_code)]
+pub fn builder_from_args(arg_matches: &ArgMatches) -> Result<TerraBuilder, TerraRustCLIError> {
+    let gas_adjustment = get_arg_value(arg_matches, "gas-adjustment")?.parse::<f64>()?;
+    let builder = match arg_matches.value_of("unix-socket") {
+        Some(unix_socket) => Terra::builder().unix_socket(unix_socket),
+        None => Terra::builder(),
+    };
+    let builder = match arg_matches.value_of("wire-log") {
+        Some(wire_log) => builder.wire_log(wire_log),
+        None => builder,
+    };
+    let builder = match arg_matches.value_of("user-agent") {
+        Some(user_agent) => builder.user_agent(user_agent),
+        None => builder,
+    };
+    let builder = match api_key_from_args(arg_matches) {
+        Some(api_key) => builder.api_key(api_key),
+        None => builder,
+    };
+    let builder = match hmac_signer_from_args(arg_matches) {
+        Some(hmac_signer) => builder.hmac_signer(hmac_signer),
+        None => builder,
+    };
+    let builder = match arg_matches.value_of("max-fee") {
+        Some(max_fee) => builder.max_fee(max_fee),
+        None => builder,
     }
+    .add_tax(arg_matches.is_present("add-tax"));
+    let builder = match arg_matches.value_of("memo-policy") {
+        Some(memo_policy) => builder.memo_policy(memo_policy.parse()?),
+        None => builder,
+    };
+    let builder = builder.pruned_heights(pruned_heights_from_args(arg_matches));
+    Ok(builder
+        .lcd(get_arg_value(arg_matches, "lcd")?)
+        .fcd(get_arg_value(arg_matches, "fcd")?)
+        .chain_id(get_arg_value(arg_matches, "chain")?)
+        .gas_prices(get_arg_value(arg_matches, "gas-prices")?)
+        .gas_denom(get_arg_value(arg_matches, "gas-denom")?)
+        .gas_adjustment(gas_adjustment)
+        .fees(get_arg_value(arg_matches, "fees")?)
+        .gas(get_arg_value(arg_matches, "gas")?)
+        .skip_validation(arg_matches.is_present("skip-validation")))
 }
 #[allow(dead_code)]
 pub fn wallet_from_args(cli: &ArgMatches) -> Result<Wallet, TerraRustCLIError> {
@@ -247,20 +402,33 @@ pub fn wallet_from_args(cli: &ArgMatches) -> Result<Wallet, TerraRustCLIError> {
 
 #[allow(dead_code)]
 pub async fn lcd_from_args(cli: &ArgMatches) -> Result<Terra, TerraRustCLIError> {
-    let gas_opts = gas_opts(cli).await?;
-    let lcd = get_arg_value(cli, "lcd")?;
-    let chain_id = get_arg_value(cli, "chain")?;
-
-    Ok(Terra::lcd_client(lcd, chain_id, &gas_opts, None))
+    Ok(builder_from_args(cli)?.build().await?)
 }
 #[allow(dead_code)]
 pub fn lcd_no_tx_from_args(cli: &ArgMatches) -> Result<Terra, TerraRustCLIError> {
     let lcd = get_arg_value(cli, "lcd")?;
     let chain_id = get_arg_value(cli, "chain")?;
 
-    Ok(Terra::lcd_client_no_tx(lcd, chain_id))
+    let terra = match cli.value_of("user-agent") {
+        Some(user_agent) => Terra::lcd_client_no_tx(lcd, chain_id).with_user_agent(user_agent)?,
+        None => Terra::lcd_client_no_tx(lcd, chain_id),
+    };
+    let terra = match api_key_from_args(cli) {
+        Some(api_key) => terra.with_api_key(api_key)?,
+        None => terra,
+    };
+    let mut terra = match hmac_signer_from_args(cli) {
+        Some(hmac_signer) => terra.with_request_signer(hmac_signer),
+        None => terra,
+    };
+    terra.pruned_heights = pruned_heights_from_args(cli);
+    match cli.value_of("wire-log") {
+        Some(wire_log) => Ok(terra.with_wire_log(WireLog::to_file(wire_log)?)),
+        None => Ok(terra),
+    }
 }
 
+/// the signing key from --phrase or the wallet
 pub fn get_private_key<C: Context + Signing>(
     secp: &Secp256k1<C>,
     matches: &ArgMatches,
@@ -284,23 +452,221 @@ pub fn get_arg_value<'a>(cli: &'a ArgMatches, id: &str) -> Result<&'a str, Terra
         Err(TerraRustCLIError::MissingArgument(id.to_string()))
     }
 }
-/// convert a input parameter into json.
-/// input can either be a json string, a file, or '-' to read stdin.
+/// add the sweep's `--min denom=amount` thresholds to `options`
+pub fn sweep_thresholds(options: SweepOptions, mins: &[String]) -> anyhow::Result<SweepOptions> {
+    let mut options = options;
+    for min in mins {
+        let (denom, amount) = min
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("{} isn't denom=amount", min))?;
+        options = options.threshold_for(denom, amount.parse()?);
+    }
+    Ok(options)
+}
+/// the most JSON read from a file, stdin or a URL
+pub const MAX_JSON_BYTES: u64 = 1024 * 1024;
+/// how long [fetch_json_block] waits for a URL
+pub const JSON_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// convert a input parameter into json. input can be
+/// * inline JSON, starting with `{` or `[`
+/// * `-` to read stdin
+/// * `@file.json`, or a plain file name
 ///
+/// anything bigger than [MAX_JSON_BYTES] is turned away. [fetch_json_block] also takes URLs
 pub fn get_json_block(in_str: &str) -> anyhow::Result<serde_json::Value> {
-    if in_str.starts_with('{') {
-        Ok(serde_json::from_str::<serde_json::Value>(in_str)?)
+    let trimmed = in_str.trim_start();
+    let (text, source) = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        (in_str.to_string(), "inline JSON".to_string())
     } else if in_str == "-" {
-        let input = std::io::stdin();
-        let mut input = input.lock();
-        let mut str_buf = String::new();
-        input.read_to_string(&mut str_buf)?;
-
-        Ok(serde_json::from_str(&str_buf)?)
+        (
+            read_limited(std::io::stdin().lock(), "stdin")?,
+            "stdin".into(),
+        )
+    } else if is_url(in_str) {
+        anyhow::bail!(
+            "{} is a URL. it can only be fetched with fetch_json_block",
+            in_str
+        )
     } else {
-        let p = Path::new(in_str);
-        let file = File::open(p)?;
-        let rdr = BufReader::new(file);
-        Ok(serde_json::from_reader(rdr)?)
+        let file = in_str.strip_prefix('@').unwrap_or(in_str);
+        let f = File::open(Path::new(file))
+            .map_err(|e| anyhow::anyhow!("can't open JSON file {}: {}", file, e))?;
+        (read_limited(BufReader::new(f), file)?, file.to_string())
+    };
+    parse_json(&text, &source)
+}
+/// [get_json_block], or an `https://` (or `http://`) URL, given up on after [JSON_FETCH_TIMEOUT]
+pub async fn fetch_json_block(in_str: &str) -> anyhow::Result<serde_json::Value> {
+    if is_url(in_str) {
+        parse_json(&fetch_json_text(in_str, JSON_FETCH_TIMEOUT).await?, in_str)
+    } else {
+        get_json_block(in_str)
+    }
+}
+/// [fetch_json_block], checked against the contract's schema: a schema file, or the directory `cargo schema`
+/// wrote, where `<kind>_msg.json` is used (eg. kind `execute` uses execute_msg.json)
+pub async fn get_json_block_checked(
+    in_str: &str,
+    schema: Option<&str>,
+    kind: &str,
+) -> anyhow::Result<serde_json::Value> {
+    let json = fetch_json_block(in_str).await?;
+    if let Some(schema) = schema {
+        check_json_schema(&json, schema, kind)?;
+    }
+    Ok(json)
+}
+/// check a contract message against its schema. see [get_json_block_checked]
+pub fn check_json_schema(json: &serde_json::Value, schema: &str, kind: &str) -> anyhow::Result<()> {
+    let path = Path::new(schema);
+    let file = if path.is_dir() {
+        [format!("{}_msg.json", kind), format!("{}.json", kind)]
+            .iter()
+            .map(|name| path.join(name))
+            .find(|file| file.is_file())
+            .ok_or_else(|| anyhow::anyhow!("no {}_msg.json in {}", kind, schema))?
+    } else {
+        path.to_path_buf()
+    };
+    let schema_json = get_json_block(&file.to_string_lossy())?;
+    terra_rust_api::messages::schema::validate(json, &schema_json)
+        .map_err(|e| anyhow::anyhow!("{} ({})", e, file.display()))
+}
+fn read_limited<R: Read>(reader: R, source: &str) -> anyhow::Result<String> {
+    let mut text = String::new();
+    reader.take(MAX_JSON_BYTES + 1).read_to_string(&mut text)?;
+    if text.len() as u64 > MAX_JSON_BYTES {
+        anyhow::bail!(
+            "{} is over the {} byte limit for JSON",
+            source,
+            MAX_JSON_BYTES
+        );
+    }
+    Ok(text)
+}
+fn is_url(in_str: &str) -> bool {
+    in_str.starts_with("https://") || in_str.starts_with("http://")
+}
+fn parse_json(text: &str, source: &str) -> anyhow::Result<serde_json::Value> {
+    serde_json::from_str(text).map_err(|e| anyhow::anyhow!("{} isn't valid JSON: {}", source, e))
+}
+async fn fetch_json_text(url: &str, timeout: Duration) -> anyhow::Result<String> {
+    let fetch_error = |e: reqwest::Error| {
+        if e.is_timeout() {
+            anyhow::anyhow!("fetching {} took over {:?}", url, timeout)
+        } else {
+            anyhow::anyhow!("can't fetch {}: {}", url, e)
+        }
+    };
+    // the timeout covers reading the body too
+    let mut resp = Terra::http_client()
+        .get(url)
+        .timeout(timeout)
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(fetch_error)?;
+    if resp.content_length().unwrap_or(0) > MAX_JSON_BYTES {
+        anyhow::bail!("{} is over the {} byte limit for JSON", url, MAX_JSON_BYTES);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await.map_err(fetch_error)? {
+        body.extend_from_slice(&chunk);
+        if body.len() as u64 > MAX_JSON_BYTES {
+            anyhow::bail!("{} is over the {} byte limit for JSON", url, MAX_JSON_BYTES);
+        }
+    }
+    Ok(String::from_utf8(body)?)
+}
+
+#[cfg(test)]
+mod tst {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    pub fn test_json_block_files() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("terra-json-block-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let file = dir.join("msg.json");
+        std::fs::write(&file, r#"{"reset":{"count":5}}"#)?;
+        let file = file.to_string_lossy();
+        assert_eq!(get_json_block(&format!("@{}", file))?["reset"]["count"], 5);
+        assert_eq!(get_json_block(&file)?["reset"]["count"], 5);
+        assert_eq!(get_json_block(r#" [1, 2]"#)?[1], 2);
+
+        let missing = get_json_block("@/no/such/msg.json").unwrap_err();
+        assert!(missing
+            .to_string()
+            .starts_with("can't open JSON file /no/such/msg.json"));
+        let big = dir.join("big.json");
+        std::fs::write(&big, vec![b' '; MAX_JSON_BYTES as usize + 1])?;
+        let err = get_json_block(&big.to_string_lossy()).unwrap_err();
+        assert!(err.to_string().contains("byte limit"));
+        assert!(get_json_block("https://example.com/msg.json").is_err());
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    /// answers each path with a canned response, and never answers `/slow`
+    async fn serve() -> anyhow::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = vec![0u8; 1024];
+                    let read = socket.read(&mut request).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&request[..read]).to_string();
+                    let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    let response = match path.as_str() {
+                        "/msg.json" => {
+                            let body = r#"{"reset":{"count":5}}"#;
+                            format!(
+                                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}",
+                                body.len(),
+                                body
+                            )
+                        }
+                        "/big.json" => format!(
+                            "HTTP/1.1 200 OK\r\nconnection: close\r\n\r\n{}",
+                            " ".repeat(MAX_JSON_BYTES as usize + 1)
+                        ),
+                        "/slow.json" => {
+                            tokio::time::sleep(Duration::from_secs(60)).await;
+                            return;
+                        }
+                        _ => "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n".into(),
+                    };
+                    socket.write_all(response.as_bytes()).await.ok();
+                });
+            }
+        });
+        Ok(url)
+    }
+
+    #[tokio::test]
+    pub async fn test_fetch_json_block() -> anyhow::Result<()> {
+        let url = serve().await?;
+        let json = fetch_json_block(&format!("{}/msg.json", url)).await?;
+        assert_eq!(json["reset"]["count"], 5);
+        // anything else is read as get_json_block does
+        assert_eq!(fetch_json_block(r#"{"a":1}"#).await?["a"], 1);
+
+        let err = fetch_json_block(&format!("{}/big.json", url))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("byte limit"));
+        let err = fetch_json_block(&format!("{}/missing.json", url))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"));
+        let err = fetch_json_text(&format!("{}/slow.json", url), Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("took over"));
+        Ok(())
     }
 }
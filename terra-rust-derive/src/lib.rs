@@ -0,0 +1,248 @@
+/*!
+Derive macros that turn a contract's cosmwasm `ExecuteMsg`/`QueryMsg` enums into
+[terra-rust-api](https://docs.rs/terra-rust-api) calls.
+
+```
+use serde::Serialize;
+use terra_rust_derive::{TerraExecuteMsg, TerraQueryMsg};
+
+#[derive(Serialize, serde::Deserialize)]
+pub struct BalanceResponse {
+    pub balance: String,
+}
+
+#[derive(Serialize, TerraExecuteMsg)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Transfer { recipient: String, amount: String },
+    Burn { amount: String },
+}
+
+#[derive(Serialize, TerraQueryMsg)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    #[returns(BalanceResponse)]
+    Balance { address: String },
+    TokenInfo {},
+}
+
+let msg = ExecuteMsg::transfer_msg(
+    "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7",
+    "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh",
+    "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7".into(),
+    "1000".into(),
+    &[],
+)
+.unwrap();
+let js = serde_json::to_value(&msg).unwrap();
+assert_eq!(js["value"]["execute_msg"]["transfer"]["amount"], "1000");
+// and to query
+// let balance = QueryMsg::query_balance(&terra, token, address.into(), None).await?.result;
+```
+*/
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
+
+/// `Transfer` -> `transfer`, `UpdateConfig` -> `update_config`
+fn snake_case(ident: &Ident) -> String {
+    let mut name = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// a variant, as the arguments of a function and the expression constructing it from them
+struct VariantArgs {
+    params: Vec<proc_macro2::TokenStream>,
+    construct: proc_macro2::TokenStream,
+}
+
+fn variant_args(variant: &syn::Variant) -> VariantArgs {
+    let name = &variant.ident;
+    match &variant.fields {
+        Fields::Named(fields) => {
+            let idents = fields
+                .named
+                .iter()
+                .map(|f| f.ident.clone().unwrap())
+                .collect::<Vec<_>>();
+            // prefixed, so fields such as cw20's `Send { contract, .. }` don't clash with the function's own parameters
+            let bindings = idents
+                .iter()
+                .map(|i| format_ident!("field_{}", i))
+                .collect::<Vec<_>>();
+            let types = fields.named.iter().map(|f| &f.ty);
+            VariantArgs {
+                params: bindings
+                    .iter()
+                    .zip(types)
+                    .map(|(i, t)| quote! { #i: #t })
+                    .collect(),
+                construct: quote! { Self::#name { #(#idents: #bindings),* } },
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let idents = (0..fields.unnamed.len())
+                .map(|i| format_ident!("arg{}", i))
+                .collect::<Vec<_>>();
+            let types = fields.unnamed.iter().map(|f| &f.ty);
+            VariantArgs {
+                params: idents
+                    .iter()
+                    .zip(types)
+                    .map(|(i, t)| quote! { #i: #t })
+                    .collect(),
+                construct: quote! { Self::#name ( #(#idents),* ) },
+            }
+        }
+        Fields::Unit => VariantArgs {
+            params: vec![],
+            construct: quote! { Self::#name },
+        },
+    }
+}
+
+fn enum_variants(input: &DeriveInput) -> Result<Vec<syn::Variant>, syn::Error> {
+    match &input.data {
+        Data::Enum(data) => Ok(data.variants.iter().cloned().collect()),
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            "only enums can be turned into contract messages",
+        )),
+    }
+}
+
+/// adds `<variant>_msg(sender, contract, fields.., coins)` to build a MsgExecuteContract for each variant,
+/// and `to_message(sender, contract, coins)` for an existing value.
+/// fields may share a name with the parameters, like cw20's `Send`
+/// ```
+/// use serde::Serialize;
+/// use terra_rust_derive::TerraExecuteMsg;
+///
+/// #[derive(Serialize, TerraExecuteMsg)]
+/// #[serde(rename_all = "snake_case")]
+/// pub enum Cw20ExecuteMsg {
+///     Send { contract: String, amount: String, msg: String },
+///     SendFrom { owner: String, contract: String, amount: String, msg: String },
+///     IncreaseAllowance { spender: String, amount: String, expires: Option<u64> },
+/// }
+///
+/// let msg = Cw20ExecuteMsg::send_msg(
+///     "terra1n3g37dsdlv7ryqftlkef8mhgqj4ny7p8v78lg7",
+///     "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh",
+///     "terra1x46rqay4d3cssq8gxxvqz8xt6nwlz4td20k38v".into(),
+///     "1000".into(),
+///     "e30=".into(),
+///     &[],
+/// )
+/// .unwrap();
+/// let js = serde_json::to_value(&msg).unwrap();
+/// assert_eq!(js["value"]["contract"], "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh");
+/// assert_eq!(
+///     js["value"]["execute_msg"]["send"]["contract"],
+///     "terra1x46rqay4d3cssq8gxxvqz8xt6nwlz4td20k38v"
+/// );
+/// ```
+#[proc_macro_derive(TerraExecuteMsg)]
+pub fn derive_execute_msg(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let variants = match enum_variants(&input) {
+        Ok(variants) => variants,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let builders = variants.iter().map(|variant| {
+        let fn_name = Ident::new(
+            &format!("{}_msg", snake_case(&variant.ident)),
+            Span::call_site(),
+        );
+        let VariantArgs { params, construct } = variant_args(variant);
+        quote! {
+            pub fn #fn_name(
+                sender: &str,
+                contract: &str,
+                #(#params,)*
+                coins: &[::terra_rust_api::core_types::Coin],
+            ) -> Result<::terra_rust_api::Message, ::terra_rust_api::errors::TerraRustAPIError> {
+                #construct.to_message(sender, contract, coins)
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// the MsgExecuteContract for this message
+            pub fn to_message(
+                &self,
+                sender: &str,
+                contract: &str,
+                coins: &[::terra_rust_api::core_types::Coin],
+            ) -> Result<::terra_rust_api::Message, ::terra_rust_api::errors::TerraRustAPIError> {
+                ::terra_rust_api::MsgExecuteContract::create_from_msg(sender, contract, self, coins)
+            }
+            #(#builders)*
+        }
+    }
+    .into()
+}
+
+/// adds `query_<variant>(terra, contract, fields.., height)` for each variant.
+/// the response is typed by `#[returns(Type)]` on the variant, or a serde_json::Value if missing
+#[proc_macro_derive(TerraQueryMsg, attributes(returns))]
+pub fn derive_query_msg(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let variants = match enum_variants(&input) {
+        Ok(variants) => variants,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut callers = vec![];
+    for variant in &variants {
+        let returns = match variant
+            .attrs
+            .iter()
+            .find(|a| a.path.is_ident("returns"))
+            .map(|a| a.parse_args::<Type>())
+        {
+            Some(Ok(returns)) => quote! { #returns },
+            Some(Err(e)) => return e.to_compile_error().into(),
+            None => quote! { ::serde_json::Value },
+        };
+        let fn_name = Ident::new(
+            &format!("query_{}", snake_case(&variant.ident)),
+            Span::call_site(),
+        );
+        let VariantArgs { params, construct } = variant_args(variant);
+        callers.push(quote! {
+            pub async fn #fn_name(
+                terra: &::terra_rust_api::Terra,
+                contract: &str,
+                #(#params,)*
+                height: Option<u64>,
+            ) -> Result<::terra_rust_api::LCDResult<#returns>, ::terra_rust_api::errors::TerraRustAPIError> {
+                terra.wasm().query_msg(contract, &#construct, height).await
+            }
+        });
+    }
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#callers)*
+        }
+    }
+    .into()
+}
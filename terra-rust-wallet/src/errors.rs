@@ -19,6 +19,12 @@ pub enum TerraRustWalletError {
         source: KeyringErrorAdapter,
     },
 
+    #[error("`{0}` is watch-only. there is no key to sign with")]
+    WatchOnly(String),
+    #[error("`{0}` is already a key in the wallet")]
+    NameInUse(String),
+    #[error("`{0}` isn't a key or watch-only entry in the wallet")]
+    UnknownName(String),
     #[error("unknown Terra-Rust Wallet error")]
     Unknown,
 }
@@ -30,12 +30,16 @@ use crate::errors::KeyringErrorAdapter;
 use crate::errors::TerraRustWalletError;
 use secp256k1::Secp256k1;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use terra_rust_api::{PrivateKey, PublicKey};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Default)]
 /// Internal structure used to hold list of keys in keyring
 pub struct WalletInternal {
     pub keys: Vec<String>,
+    /// watch-only entries. name -> address, with no key stored
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub watch_only: BTreeMap<String, String>,
 }
 #[derive(Deserialize, Serialize, Debug)]
 /// Internal structure used to hold list of keys in keyring
@@ -58,7 +62,7 @@ impl<'a> Wallet<'a> {
         let wallet = Wallet::create(wallet_name);
         let wallet_list_name = &wallet.full_list_name();
         let keyring = keyring::Entry::new(wallet_name, wallet_list_name);
-        let wallet_internal = WalletInternal { keys: vec![] };
+        let wallet_internal = WalletInternal::default();
         keyring
             .set_password(&serde_json::to_string(&wallet_internal)?)
             .map_err(KeyringErrorAdapter::from)?;
@@ -98,6 +102,9 @@ impl<'a> Wallet<'a> {
         key_name: &'a str,
         seed: Option<&'a str>,
     ) -> Result<PrivateKey, TerraRustWalletError> {
+        if self.watch_only_address(key_name).is_some() {
+            return Err(TerraRustWalletError::WatchOnly(key_name.into()));
+        }
         let full_key_name = self.full_key_name(key_name);
         let keyring = keyring::Entry::new(self.name, &full_key_name);
         let phrase = &keyring.get_password()?; //.map_err(KeyringErrorAdapter::from)?;
@@ -120,58 +127,105 @@ impl<'a> Wallet<'a> {
         Ok(pub_key)
     }
 
-    /// get account from key name
+    /// get account from key name, or a watch-only entry's address
     pub fn get_account<C: secp256k1::Signing + secp256k1::Context>(
         &self,
         secp: &Secp256k1<C>,
         key_name: &str,
         seed: Option<&str>,
     ) -> Result<String, TerraRustWalletError> {
+        if let Some(address) = self.watch_only_address(key_name) {
+            return Ok(address);
+        }
         let pub_key = self.get_public_key(secp, key_name, seed)?;
         let account = pub_key.account()?;
         Ok(account)
     }
-    /// stores the private key into the keyring
+    /// stores the private key into the keyring. it replaces a watch-only entry of the same name
     pub fn store_key(&self, key_name: &str, pk: &PrivateKey) -> Result<bool, TerraRustWalletError> {
         let full_key_name = self.full_key_name(key_name);
 
         let keyring = keyring::Entry::new(self.name, &full_key_name);
         keyring.set_password(pk.words().unwrap())?; // .map_err(KeyringErrorAdapter::from)?;
-        let old_list = self.get_keys()?;
-        let string_key_name: String = String::from(key_name);
-        let mut new_list: Vec<String> = vec![];
-        for s in old_list {
-            if s.ne(key_name) {
-                new_list.push(s);
-            }
-        }
-
-        new_list.push(string_key_name);
-        let wallet_internal = WalletInternal { keys: new_list };
-        self.set_keys(&wallet_internal)?;
+        let mut wallet_internal = self.get_internal()?;
+        wallet_internal.keys.retain(|s| s.ne(key_name));
+        wallet_internal.keys.push(String::from(key_name));
+        wallet_internal.watch_only.remove(key_name);
+        self.set_internal(&wallet_internal)?;
 
         Ok(true)
     }
-    /// deletes the private key from the keyring
+    /// stores an address with no key, for queries and as a recipient, like a cold wallet or a contract
+    pub fn store_address(&self, name: &str, address: &str) -> Result<bool, TerraRustWalletError> {
+        Wallet::check_address(address)?;
+        let mut wallet_internal = self.get_internal()?;
+        if wallet_internal.keys.iter().any(|k| k == name) {
+            return Err(TerraRustWalletError::NameInUse(name.into()));
+        }
+        wallet_internal
+            .watch_only
+            .insert(name.into(), address.into());
+        self.set_internal(&wallet_internal)?;
+        Ok(true)
+    }
+    /// deletes a watch-only entry. unlike [Wallet::delete_key], it never touches a key
+    pub fn delete_address(&self, name: &str) -> Result<bool, TerraRustWalletError> {
+        let mut wallet_internal = self.get_internal()?;
+        if wallet_internal.watch_only.remove(name).is_none() {
+            return Err(TerraRustWalletError::UnknownName(name.into()));
+        }
+        self.set_internal(&wallet_internal)?;
+        Ok(true)
+    }
+    /// where to send to. `@name` (or just `name`) is a watch-only entry or a key in the wallet,
+    /// anything starting with `terra1` has to be an account or contract address
+    pub fn resolve_recipient<C: secp256k1::Signing + secp256k1::Context>(
+        &self,
+        secp: &Secp256k1<C>,
+        recipient: &str,
+        seed: Option<&str>,
+    ) -> Result<String, TerraRustWalletError> {
+        if recipient.starts_with("terra1") {
+            Wallet::check_address(recipient)?;
+            return Ok(recipient.into());
+        }
+        let name = recipient.strip_prefix('@').unwrap_or(recipient);
+        if let Some(address) = self.watch_only_address(name) {
+            return Ok(address);
+        }
+        if self
+            .get_keys()
+            .map(|keys| keys.iter().any(|k| k == name))
+            .unwrap_or(false)
+        {
+            return self.get_account(secp, name, seed);
+        }
+        Err(TerraRustWalletError::UnknownName(name.into()))
+    }
+    fn check_address(address: &str) -> Result<(), TerraRustWalletError> {
+        PublicKey::from_account(address).or_else(|_| PublicKey::from_contract(address))?;
+        Ok(())
+    }
+    /// deletes the private key from the keyring, or the watch-only entry
     pub fn delete_key(&self, key_name: &str) -> Result<bool, TerraRustWalletError> {
-        let full_key_name = self.full_key_name(key_name);
-        let keyring = keyring::Entry::new(self.name, &full_key_name);
-        keyring.delete_password()?; //  .map_err(KeyringErrorAdapter::from)?;
-        let old_list = self.get_keys()?;
-        let mut new_list = vec![];
-        for s in old_list {
-            if s.ne(key_name) {
-                new_list.push(s);
-            }
+        let mut wallet_internal = self.get_internal()?;
+        if wallet_internal.watch_only.remove(key_name).is_none() {
+            let full_key_name = self.full_key_name(key_name);
+            let keyring = keyring::Entry::new(self.name, &full_key_name);
+            keyring.delete_password()?; //  .map_err(KeyringErrorAdapter::from)?;
+            wallet_internal.keys.retain(|s| s.ne(key_name));
         }
-        let wallet_internal = WalletInternal { keys: new_list };
-        self.set_keys(&wallet_internal)?;
+        self.set_internal(&wallet_internal)?;
         Ok(true)
     }
     /// lists the keys in the wallet
     pub fn list(&self) -> Result<Vec<String>, TerraRustWalletError> {
         self.get_keys()
     }
+    /// the watch-only entries, name -> address
+    pub fn list_watch_only(&self) -> Result<BTreeMap<String, String>, TerraRustWalletError> {
+        Ok(self.get_internal()?.watch_only)
+    }
 
     /// deletes the wallet and ALL the keys in the wallet
     pub fn delete(&self) -> Result<(), TerraRustWalletError> {
@@ -210,6 +264,18 @@ impl<'a> Wallet<'a> {
 
     /// get list of keys in a wallet
     fn get_keys(&self) -> Result<Vec<String>, TerraRustWalletError> {
+        Ok(self.get_internal()?.keys)
+    }
+
+    /// the address, if the name is a watch-only entry. a wallet without a key list has none
+    fn watch_only_address(&self, name: &str) -> Option<String> {
+        self.get_internal()
+            .ok()
+            .and_then(|mut w| w.watch_only.remove(name))
+    }
+
+    /// the list of keys, and the watch-only entries
+    fn get_internal(&self) -> Result<WalletInternal, TerraRustWalletError> {
         let wallet_list_name = self.full_list_name();
         let keyring = keyring::Entry::new(self.name, &wallet_list_name);
         let pass = keyring.get_password()?;
@@ -218,8 +284,7 @@ impl<'a> Wallet<'a> {
             source: KeyringErrorAdapter::from(source),
         })?;*/
 
-        let wallet_internal: WalletInternal = serde_json::from_str(&pass)?;
-        Ok(wallet_internal.keys)
+        Ok(serde_json::from_str(&pass)?)
     }
 
     /// get list of wallets
@@ -232,7 +297,7 @@ impl<'a> Wallet<'a> {
     }
 
     /// update keys in a wallet
-    fn set_keys(&self, int: &WalletInternal) -> Result<(), TerraRustWalletError> {
+    fn set_internal(&self, int: &WalletInternal) -> Result<(), TerraRustWalletError> {
         let wallet_list_name = self.full_list_name();
         let keyring = keyring::Entry::new(self.name, &wallet_list_name);
 
@@ -253,6 +318,36 @@ impl<'a> Wallet<'a> {
 mod tst {
     use super::*;
 
+    #[test]
+    pub fn test_wallet_internal_watch_only() -> anyhow::Result<()> {
+        // written before there were watch-only entries
+        let old: WalletInternal = serde_json::from_str(r#"{"keys":["a","b"]}"#)?;
+        assert_eq!(old.keys, vec!["a", "b"]);
+        assert!(old.watch_only.is_empty());
+        assert_eq!(serde_json::to_string(&old)?, r#"{"keys":["a","b"]}"#);
+
+        let mut internal = WalletInternal::default();
+        internal.watch_only.insert(
+            "cold".into(),
+            "terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh".into(),
+        );
+        let json = serde_json::to_string(&internal)?;
+        let back: WalletInternal = serde_json::from_str(&json)?;
+        assert_eq!(back.watch_only, internal.watch_only);
+        Ok(())
+    }
+    #[test]
+    pub fn test_check_address() {
+        assert!(Wallet::check_address("terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh").is_ok());
+        assert!(Wallet::check_address(
+            "terra1466nf3zuxpya8q9emxukd7vftaf6h4psr0a07srl5zw74zh84yjqxl5qul"
+        )
+        .is_ok());
+        assert!(Wallet::check_address("terra1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rx").is_err());
+        assert!(
+            Wallet::check_address("terravaloper1usws7c2c6cs7nuc8vma9qzaky5pkgvm2uag6rh").is_err()
+        );
+    }
     #[test]
     pub fn test_wallet_create_delete() -> anyhow::Result<()> {
         let wallet = Wallet::new("PFC-Test Wallet")?;